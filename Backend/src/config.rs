@@ -4,7 +4,8 @@ pub struct Config {
     pub database_url: String,
     pub app_url: String,
     pub jwt_secret: String,
-    pub jwt_maxage: i64,
+    pub jwt_maxage: i64, // Access token lifetime in minutes
+    pub refresh_token_maxage_days: i64,
     pub port: u16,
     // Redis configuration
     pub redis_url: Option<String>,
@@ -22,6 +23,45 @@ pub struct Config {
     pub from_email: String,
     pub email_rate_limit: usize,
     pub email_rate_window_minutes: i64,
+    pub resend_webhook_secret: String,
+    // Property certificate signing
+    pub certificate_signing_key: Option<String>,
+    pub certificate_retired_public_keys: Vec<String>,
+    // Media storage
+    pub storage_backend: String,
+    pub storage_local_path: String,
+    pub storage_signing_secret: String,
+    pub s3: Option<S3Config>,
+    // Encrypts TOTP secrets at rest
+    pub two_factor_encryption_key: String,
+    // Holds face_detector.onnx and face_embedding.onnx for KYC checks
+    pub face_model_dir: String,
+    // NIN, BVN and CAC lookups
+    pub identity_provider: String,
+    pub identity_api: Option<IdentityApiConfig>,
+    pub identity_fixtures_path: String,
+    // Proxies in front of the API that append to X-Forwarded-For; 0 reads the socket peer
+    pub trusted_proxy_hops: usize,
+}
+
+/// A Dojah-compatible KYC API
+#[derive(Debug, Clone)]
+pub struct IdentityApiConfig {
+    pub base_url: String,
+    pub app_id: String,
+    pub secret_key: String,
+}
+
+/// An S3-compatible bucket (AWS, MinIO, Cloudflare R2, DigitalOcean Spaces)
+#[derive(Debug, Clone)]
+pub struct S3Config {
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    // CDN or bucket URL that serves the public/ prefix without signing
+    pub public_url: Option<String>,
 }
 
 impl Config {
@@ -30,6 +70,11 @@ impl Config {
         let jwt_secret = std::env::var("JWT_SECRET_KEY").expect("JWT_SECRET_KEY must be set");
         let jwt_maxage = std::env::var("JWT_MAXAGE").expect("JWT_MAXAGE must be set");
         let app_url = std::env::var("APP_URL").expect("APP_URL must be set");
+        // Sessions end after this long without a refresh
+        let refresh_token_maxage_days: i64 = std::env::var("REFRESH_TOKEN_MAXAGE_DAYS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()
+            .unwrap_or(30);
         
         // Redis configuration (optional)
         let redis_url = std::env::var("REDIS_URL").ok();
@@ -66,6 +111,68 @@ impl Config {
             .unwrap_or_else(|_| "1".to_string())
             .parse()
             .unwrap_or(1);
+        let resend_webhook_secret = std::env::var("RESEND_WEBHOOK_SECRET")
+            .unwrap_or_else(|_| "".to_string());
+
+        // Base64 Ed25519 seed. Required: startup fails without it.
+        let certificate_signing_key = std::env::var("CERTIFICATE_SIGNING_KEY")
+            .ok()
+            .filter(|key| !key.is_empty());
+        // Comma-separated base64 public keys of earlier signing keys, so their certificates still verify
+        let certificate_retired_public_keys = std::env::var("CERTIFICATE_RETIRED_PUBLIC_KEYS")
+            .map(|keys| keys.split(',').map(|key| key.trim().to_string()).filter(|key| !key.is_empty()).collect())
+            .unwrap_or_default();
+
+        // "local" (default) or "s3"
+        let storage_backend = std::env::var("STORAGE_BACKEND")
+            .unwrap_or_else(|_| "local".to_string());
+        let storage_local_path = std::env::var("STORAGE_LOCAL_PATH")
+            .unwrap_or_else(|_| "./uploads".to_string());
+        // Signs expiring media URLs; falls back to the JWT secret
+        let storage_signing_secret = std::env::var("STORAGE_SIGNING_SECRET")
+            .ok()
+            .filter(|secret| !secret.is_empty())
+            .unwrap_or_else(|| jwt_secret.clone());
+        let s3 = match (std::env::var("S3_BUCKET"), std::env::var("S3_ACCESS_KEY_ID"), std::env::var("S3_SECRET_ACCESS_KEY")) {
+            (Ok(bucket), Ok(access_key_id), Ok(secret_access_key)) => Some(S3Config {
+                endpoint: std::env::var("S3_ENDPOINT")
+                    .unwrap_or_else(|_| "https://s3.amazonaws.com".to_string()),
+                bucket,
+                region: std::env::var("S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+                access_key_id,
+                secret_access_key,
+                public_url: std::env::var("S3_PUBLIC_URL").ok().filter(|url| !url.is_empty()),
+            }),
+            _ => None,
+        };
+        // Falls back to the JWT secret; changing it invalidates every enrolled authenticator
+        let two_factor_encryption_key = std::env::var("TWO_FACTOR_ENCRYPTION_KEY")
+            .ok()
+            .filter(|key| !key.is_empty())
+            .unwrap_or_else(|| jwt_secret.clone());
+        // Shipped in models/; without them every KYC submission goes to a reviewer
+        let face_model_dir = std::env::var("FACE_MODEL_DIR")
+            .unwrap_or_else(|_| "./models".to_string());
+        // "api", "fake" (records from a JSON file) or "none" (default, numbers go unchecked)
+        let identity_provider = std::env::var("IDENTITY_PROVIDER")
+            .unwrap_or_else(|_| "none".to_string());
+        let identity_api = match (std::env::var("IDENTITY_APP_ID"), std::env::var("IDENTITY_SECRET_KEY")) {
+            (Ok(app_id), Ok(secret_key)) => Some(IdentityApiConfig {
+                base_url: std::env::var("IDENTITY_API_URL")
+                    .unwrap_or_else(|_| "https://api.dojah.io".to_string()),
+                app_id,
+                secret_key,
+            }),
+            _ => None,
+        };
+        let identity_fixtures_path = std::env::var("IDENTITY_FIXTURES_PATH")
+            .unwrap_or_else(|_| "./data/identity_fixtures.json".to_string());
+
+        // Set to the number of load balancers and CDNs in front of the API
+        let trusted_proxy_hops = std::env::var("TRUSTED_PROXY_HOPS")
+            .ok()
+            .and_then(|hops| hops.parse().ok())
+            .unwrap_or(0);
 
         if redis_enabled {
             println!("🚀 Redis caching is ENABLED");
@@ -78,6 +185,7 @@ impl Config {
             app_url,
             jwt_secret,
             jwt_maxage: jwt_maxage.parse::<i64>().unwrap(),
+            refresh_token_maxage_days,
             port: 8000,
             redis_url,
             redis_enabled,
@@ -92,6 +200,19 @@ impl Config {
             from_email,
             email_rate_limit,
             email_rate_window_minutes,
+            resend_webhook_secret,
+            certificate_signing_key,
+            certificate_retired_public_keys,
+            storage_backend,
+            storage_local_path,
+            storage_signing_secret,
+            s3,
+            two_factor_encryption_key,
+            face_model_dir,
+            identity_provider,
+            identity_api,
+            identity_fixtures_path,
+            trusted_proxy_hops,
         }
     }
 }
\ No newline at end of file
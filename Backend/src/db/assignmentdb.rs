@@ -0,0 +1,373 @@
+// db/assignmentdb.rs
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    db::{db::DBClient, propertydb::PROPERTY_COLUMNS},
+    models::{
+        propertymodel::{
+            AssignmentStatus, Property, PropertyStatus, VerificationAssignment, VerifierCandidate,
+            VerifierMetrics, VerifierRole,
+        },
+        usermodel::UserRole,
+    },
+};
+
+const ASSIGNMENT_COLUMNS: &str = r#"
+    id, property_id, verifier_id, verifier_type, assigned_by, status,
+    assigned_at, due_at, reminded_at, closed_at
+"#;
+
+/// The properties column holding each kind of verifier, and the status a
+/// listing waits in for them
+fn holder(role: VerifierRole) -> (&'static str, PropertyStatus) {
+    match role {
+        VerifierRole::Agent => ("agent_id", PropertyStatus::AwaitingAgent),
+        VerifierRole::Lawyer => ("lawyer_id", PropertyStatus::AwaitingLawyer),
+    }
+}
+
+#[async_trait]
+pub trait VerificationAssignmentExt {
+    /// Verified users with the role, and how many listings each holds now
+    async fn get_verifier_candidates(
+        &self,
+        role: VerifierRole,
+    ) -> Result<Vec<VerifierCandidate>, sqlx::Error>;
+
+    /// Hands the listing to the verifier and starts their SLA, releasing
+    /// whoever held it before. None if the listing is no longer waiting for
+    /// this kind of verifier.
+    async fn create_assignment(
+        &self,
+        property_id: Uuid,
+        role: VerifierRole,
+        verifier_id: Uuid,
+        assigned_by: Option<Uuid>,
+        due_at: DateTime<Utc>,
+    ) -> Result<Option<(Property, VerificationAssignment)>, sqlx::Error>;
+
+    /// Ends an assignment whose SLA ran out. The verifier keeps the listing
+    /// until someone replaces them. False if it was no longer active.
+    async fn lapse_assignment(
+        &self,
+        assignment_id: Uuid,
+    ) -> Result<bool, sqlx::Error>;
+
+    /// Active assignments due before `due_before` that have not been reminded
+    async fn get_assignments_to_remind(
+        &self,
+        due_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<VerificationAssignment>, sqlx::Error>;
+
+    async fn mark_assignment_reminded(
+        &self,
+        assignment_id: Uuid,
+    ) -> Result<(), sqlx::Error>;
+
+    /// Active assignments past their SLA, oldest first
+    async fn get_lapsed_assignments(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<VerificationAssignment>, sqlx::Error>;
+
+    /// Listings waiting on an agent or lawyer that nobody holds on an SLA
+    async fn get_unassigned_properties(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<Property>, sqlx::Error>;
+
+    /// Verifiers who already let this listing lapse
+    async fn get_lapsed_verifier_ids(
+        &self,
+        property_id: Uuid,
+        role: VerifierRole,
+    ) -> Result<Vec<Uuid>, sqlx::Error>;
+
+    async fn get_active_assignments_for_verifier(
+        &self,
+        verifier_id: Uuid,
+    ) -> Result<Vec<VerificationAssignment>, sqlx::Error>;
+
+    /// Turnaround per verifier for assignments made since `since`
+    async fn get_verifier_metrics(
+        &self,
+        since: DateTime<Utc>,
+        role: Option<VerifierRole>,
+        verifier_id: Option<Uuid>,
+    ) -> Result<Vec<VerifierMetrics>, sqlx::Error>;
+}
+
+#[async_trait]
+impl VerificationAssignmentExt for DBClient {
+    async fn get_verifier_candidates(
+        &self,
+        role: VerifierRole,
+    ) -> Result<Vec<VerifierCandidate>, sqlx::Error> {
+        let user_role = match role {
+            VerifierRole::Agent => UserRole::Agent,
+            VerifierRole::Lawyer => UserRole::Lawyer,
+        };
+
+        sqlx::query_as::<_, VerifierCandidate>(
+            r#"
+            SELECT u.id, u.name, u.lga, u.nearest_landmark, COUNT(a.id) AS active_assignments
+            FROM users u
+            LEFT JOIN property_verification_assignments a
+                ON a.verifier_id = u.id AND a.status = 'active'
+            WHERE (u.role = $1 OR EXISTS (SELECT 1 FROM user_roles ur WHERE ur.user_id = u.id AND ur.role = $1))
+                AND u.verified
+            GROUP BY u.id
+            "#
+        )
+        .bind(user_role)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn create_assignment(
+        &self,
+        property_id: Uuid,
+        role: VerifierRole,
+        verifier_id: Uuid,
+        assigned_by: Option<Uuid>,
+        due_at: DateTime<Utc>,
+    ) -> Result<Option<(Property, VerificationAssignment)>, sqlx::Error> {
+        let (column, waiting) = holder(role);
+        let mut tx = self.pool.begin().await?;
+
+        let query = format!(
+            r#"
+            UPDATE properties
+            SET {} = $1, updated_at = NOW()
+            WHERE id = $2 AND status = $3
+            RETURNING {}
+            "#,
+            column, PROPERTY_COLUMNS
+        );
+
+        let property = sqlx::query_as::<_, Property>(&query)
+            .bind(verifier_id)
+            .bind(property_id)
+            .bind(waiting)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        let Some(property) = property else {
+            return Ok(None);
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE property_verification_assignments
+            SET status = $3, closed_at = NOW()
+            WHERE property_id = $1 AND verifier_type = $2 AND status = 'active'
+            "#
+        )
+        .bind(property_id)
+        .bind(role.to_str())
+        .bind(AssignmentStatus::Released)
+        .execute(&mut *tx)
+        .await?;
+
+        let query = format!(
+            r#"
+            INSERT INTO property_verification_assignments (property_id, verifier_id, verifier_type, assigned_by, due_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING {}
+            "#,
+            ASSIGNMENT_COLUMNS
+        );
+
+        let assignment = sqlx::query_as::<_, VerificationAssignment>(&query)
+            .bind(property_id)
+            .bind(verifier_id)
+            .bind(role.to_str())
+            .bind(assigned_by)
+            .bind(due_at)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(Some((property, assignment)))
+    }
+
+    async fn lapse_assignment(
+        &self,
+        assignment_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE property_verification_assignments
+            SET status = $2, closed_at = NOW()
+            WHERE id = $1 AND status = 'active'
+            "#
+        )
+        .bind(assignment_id)
+        .bind(AssignmentStatus::Lapsed)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_assignments_to_remind(
+        &self,
+        due_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<VerificationAssignment>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM property_verification_assignments
+            WHERE status = 'active' AND reminded_at IS NULL
+                AND due_at <= $1 AND due_at > NOW()
+            ORDER BY due_at
+            LIMIT $2
+            "#,
+            ASSIGNMENT_COLUMNS
+        );
+
+        sqlx::query_as::<_, VerificationAssignment>(&query)
+            .bind(due_before)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn mark_assignment_reminded(
+        &self,
+        assignment_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE property_verification_assignments SET reminded_at = NOW() WHERE id = $1")
+            .bind(assignment_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_lapsed_assignments(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<VerificationAssignment>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM property_verification_assignments
+            WHERE status = 'active' AND due_at <= NOW()
+            ORDER BY due_at
+            LIMIT $1
+            "#,
+            ASSIGNMENT_COLUMNS
+        );
+
+        sqlx::query_as::<_, VerificationAssignment>(&query)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn get_unassigned_properties(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<Property>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM properties
+            WHERE status IN ($1, $2)
+                AND NOT EXISTS (
+                    SELECT 1 FROM property_verification_assignments a
+                    WHERE a.property_id = properties.id AND a.status = 'active'
+                        AND a.verifier_type = CASE WHEN properties.status = $1 THEN 'agent' ELSE 'lawyer' END
+                )
+            ORDER BY updated_at
+            LIMIT $3
+            "#,
+            PROPERTY_COLUMNS
+        );
+
+        sqlx::query_as::<_, Property>(&query)
+            .bind(PropertyStatus::AwaitingAgent)
+            .bind(PropertyStatus::AwaitingLawyer)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn get_lapsed_verifier_ids(
+        &self,
+        property_id: Uuid,
+        role: VerifierRole,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT verifier_id
+            FROM property_verification_assignments
+            WHERE property_id = $1 AND verifier_type = $2 AND status = 'lapsed'
+            "#
+        )
+        .bind(property_id)
+        .bind(role.to_str())
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn get_active_assignments_for_verifier(
+        &self,
+        verifier_id: Uuid,
+    ) -> Result<Vec<VerificationAssignment>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM property_verification_assignments
+            WHERE verifier_id = $1 AND status = 'active'
+            ORDER BY due_at
+            "#,
+            ASSIGNMENT_COLUMNS
+        );
+
+        sqlx::query_as::<_, VerificationAssignment>(&query)
+            .bind(verifier_id)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn get_verifier_metrics(
+        &self,
+        since: DateTime<Utc>,
+        role: Option<VerifierRole>,
+        verifier_id: Option<Uuid>,
+    ) -> Result<Vec<VerifierMetrics>, sqlx::Error> {
+        sqlx::query_as::<_, VerifierMetrics>(
+            r#"
+            SELECT
+                u.id AS verifier_id, u.name, a.verifier_type,
+                COUNT(*) FILTER (WHERE a.status = 'active') AS active,
+                COUNT(*) FILTER (WHERE a.status = 'completed') AS completed,
+                COUNT(*) FILTER (WHERE a.status = 'completed' AND a.closed_at <= a.due_at) AS completed_on_time,
+                COUNT(*) FILTER (WHERE a.status = 'lapsed') AS lapsed,
+                (AVG(EXTRACT(EPOCH FROM a.closed_at - a.assigned_at)::FLOAT8)
+                    FILTER (WHERE a.status = 'completed')) / 3600 AS avg_turnaround_hours,
+                (PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY EXTRACT(EPOCH FROM a.closed_at - a.assigned_at)::FLOAT8)
+                    FILTER (WHERE a.status = 'completed')) / 3600 AS median_turnaround_hours
+            FROM property_verification_assignments a
+            JOIN users u ON u.id = a.verifier_id
+            WHERE a.assigned_at >= $1
+                AND ($2::TEXT IS NULL OR a.verifier_type = $2)
+                AND ($3::UUID IS NULL OR a.verifier_id = $3)
+            GROUP BY u.id, u.name, a.verifier_type
+            ORDER BY a.verifier_type, completed DESC
+            "#
+        )
+        .bind(since)
+        .bind(role.map(|r| r.to_str()))
+        .bind(verifier_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
@@ -0,0 +1,164 @@
+// db/certificatedb.rs
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    db::{db::DBClient, propertydb::PROPERTY_COLUMNS},
+    models::{
+        certificatemodels::PropertyCertificate,
+        propertymodel::{Property, PropertyStatus},
+    },
+};
+
+const CERTIFICATE_COLUMNS: &str = r#"
+    id, property_id, reference, payload, signature, key_id, issued_at, revoked_at, revocation_reason
+"#;
+
+#[async_trait]
+pub trait CertificateExt {
+    async fn insert_certificate(
+        &self,
+        certificate: &PropertyCertificate,
+    ) -> Result<PropertyCertificate, sqlx::Error>;
+
+    /// The unrevoked certificate, if any
+    async fn get_current_certificate(
+        &self,
+        property_id: Uuid,
+    ) -> Result<Option<PropertyCertificate>, sqlx::Error>;
+
+    /// The unrevoked certificate for the reference, else the latest revoked one
+    async fn get_certificate_by_reference(
+        &self,
+        reference: &str,
+    ) -> Result<Option<PropertyCertificate>, sqlx::Error>;
+
+    /// Revokes the current certificate. None if there was none.
+    async fn revoke_certificate(
+        &self,
+        property_id: Uuid,
+        reason: &str,
+    ) -> Result<Option<PropertyCertificate>, sqlx::Error>;
+
+    /// Live listings that passed review but hold no current certificate
+    async fn get_properties_missing_certificates(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<Property>, sqlx::Error>;
+}
+
+#[async_trait]
+impl CertificateExt for DBClient {
+    async fn insert_certificate(
+        &self,
+        certificate: &PropertyCertificate,
+    ) -> Result<PropertyCertificate, sqlx::Error> {
+        let query = format!(
+            r#"
+            INSERT INTO property_certificates (id, property_id, reference, payload, signature, key_id, issued_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING {}
+            "#,
+            CERTIFICATE_COLUMNS
+        );
+
+        sqlx::query_as::<_, PropertyCertificate>(&query)
+            .bind(certificate.id)
+            .bind(certificate.property_id)
+            .bind(&certificate.reference)
+            .bind(&certificate.payload)
+            .bind(&certificate.signature)
+            .bind(&certificate.key_id)
+            .bind(certificate.issued_at)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    async fn get_current_certificate(
+        &self,
+        property_id: Uuid,
+    ) -> Result<Option<PropertyCertificate>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM property_certificates
+            WHERE property_id = $1 AND revoked_at IS NULL
+            "#,
+            CERTIFICATE_COLUMNS
+        );
+
+        sqlx::query_as::<_, PropertyCertificate>(&query)
+            .bind(property_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn get_certificate_by_reference(
+        &self,
+        reference: &str,
+    ) -> Result<Option<PropertyCertificate>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM property_certificates
+            WHERE reference = $1
+            ORDER BY revoked_at IS NULL DESC, issued_at DESC
+            LIMIT 1
+            "#,
+            CERTIFICATE_COLUMNS
+        );
+
+        sqlx::query_as::<_, PropertyCertificate>(&query)
+            .bind(reference)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn revoke_certificate(
+        &self,
+        property_id: Uuid,
+        reason: &str,
+    ) -> Result<Option<PropertyCertificate>, sqlx::Error> {
+        let query = format!(
+            r#"
+            UPDATE property_certificates
+            SET revoked_at = NOW(), revocation_reason = $2
+            WHERE property_id = $1 AND revoked_at IS NULL
+            RETURNING {}
+            "#,
+            CERTIFICATE_COLUMNS
+        );
+
+        sqlx::query_as::<_, PropertyCertificate>(&query)
+            .bind(property_id)
+            .bind(reason)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn get_properties_missing_certificates(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<Property>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM properties
+            WHERE status = $1 AND lawyer_verified_at IS NOT NULL
+                AND NOT EXISTS (
+                    SELECT 1 FROM property_certificates c
+                    WHERE c.property_id = properties.id AND c.revoked_at IS NULL
+                )
+            ORDER BY lawyer_verified_at
+            LIMIT $2
+            "#,
+            PROPERTY_COLUMNS
+        );
+
+        sqlx::query_as::<_, Property>(&query)
+            .bind(PropertyStatus::Active)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+    }
+}
@@ -0,0 +1,417 @@
+// db/emaildb.rs
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::types::Json;
+use uuid::Uuid;
+
+use super::db::DBClient;
+use crate::models::emailmodels::*;
+
+#[async_trait]
+pub trait EmailOutboxExt {
+    /// Returns None when a message with the same idempotency key already exists
+    async fn enqueue_email(
+        &self,
+        idempotency_key: &str,
+        to_email: &str,
+        subject: &str,
+        template_path: &str,
+        placeholders: &[(String, String)],
+        status: EmailStatus,
+    ) -> Result<Option<OutboxEmail>, sqlx::Error>;
+
+    /// Lease due messages (and ones whose previous lease expired) for sending
+    async fn claim_due_emails(
+        &self,
+        limit: i64,
+        lease_seconds: i64,
+    ) -> Result<Vec<OutboxEmail>, sqlx::Error>;
+
+    async fn mark_email_sent(
+        &self,
+        email_id: Uuid,
+        provider_message_id: Option<&str>,
+    ) -> Result<(), sqlx::Error>;
+
+    async fn mark_email_failed(
+        &self,
+        email_id: Uuid,
+        error: &str,
+        next_attempt_at: DateTime<Utc>,
+    ) -> Result<OutboxEmail, sqlx::Error>;
+
+    async fn mark_email_suppressed(&self, email_id: Uuid) -> Result<(), sqlx::Error>;
+
+    /// None also when the message lost its one-time codes and cannot be resent
+    async fn requeue_email(&self, email_id: Uuid) -> Result<Option<OutboxEmail>, sqlx::Error>;
+
+    async fn get_outbox_emails(
+        &self,
+        status: Option<EmailStatus>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<OutboxEmail>, sqlx::Error>;
+
+    /// Messages whose lease expired or that have sat past their due time
+    async fn get_stuck_emails(
+        &self,
+        stuck_after_minutes: i64,
+        limit: i64,
+    ) -> Result<Vec<OutboxEmail>, sqlx::Error>;
+
+    async fn get_email_suppression(
+        &self,
+        email: &str,
+    ) -> Result<Option<EmailSuppression>, sqlx::Error>;
+
+    async fn upsert_email_suppression(
+        &self,
+        email: &str,
+        reason: SuppressionReason,
+        details: Option<&str>,
+    ) -> Result<EmailSuppression, sqlx::Error>;
+
+    async fn delete_email_suppression(&self, email: &str) -> Result<bool, sqlx::Error>;
+
+    async fn get_email_suppressions(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<EmailSuppression>, sqlx::Error>;
+
+    async fn record_email_event(
+        &self,
+        provider_message_id: Option<&str>,
+        event_type: &str,
+        email: Option<&str>,
+        payload: serde_json::Value,
+    ) -> Result<EmailEvent, sqlx::Error>;
+}
+
+#[async_trait]
+impl EmailOutboxExt for DBClient {
+    async fn enqueue_email(
+        &self,
+        idempotency_key: &str,
+        to_email: &str,
+        subject: &str,
+        template_path: &str,
+        placeholders: &[(String, String)],
+        status: EmailStatus,
+    ) -> Result<Option<OutboxEmail>, sqlx::Error> {
+        sqlx::query_as::<_, OutboxEmail>(
+            r#"
+            INSERT INTO email_outbox (idempotency_key, to_email, subject, template_path, placeholders, status)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (idempotency_key) DO NOTHING
+            RETURNING id, idempotency_key, to_email, subject, template_path, placeholders, status,
+                      attempts, max_attempts, last_error, next_attempt_at, locked_until,
+                      provider_message_id, created_at, updated_at, sent_at
+            "#
+        )
+        .bind(idempotency_key)
+        .bind(to_email)
+        .bind(subject)
+        .bind(template_path)
+        .bind(Json(placeholders))
+        .bind(status)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn claim_due_emails(
+        &self,
+        limit: i64,
+        lease_seconds: i64,
+    ) -> Result<Vec<OutboxEmail>, sqlx::Error> {
+        sqlx::query_as::<_, OutboxEmail>(
+            r#"
+            UPDATE email_outbox
+            SET status = 'sending',
+                locked_until = NOW() + make_interval(secs => $2),
+                updated_at = NOW()
+            WHERE id IN (
+                SELECT id FROM email_outbox
+                WHERE (status = 'queued' AND next_attempt_at <= NOW())
+                   OR (status = 'sending' AND locked_until < NOW())
+                ORDER BY next_attempt_at ASC
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, idempotency_key, to_email, subject, template_path, placeholders, status,
+                      attempts, max_attempts, last_error, next_attempt_at, locked_until,
+                      provider_message_id, created_at, updated_at, sent_at
+            "#
+        )
+        .bind(limit)
+        .bind(lease_seconds as f64)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn mark_email_sent(
+        &self,
+        email_id: Uuid,
+        provider_message_id: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE email_outbox
+            SET status = 'sent',
+                attempts = attempts + 1,
+                provider_message_id = $2,
+                last_error = NULL,
+                locked_until = NULL,
+                sent_at = NOW(),
+                updated_at = NOW(),
+                placeholders = COALESCE(
+                    (SELECT jsonb_agg(p) FROM jsonb_array_elements(placeholders) p WHERE NOT (p->>0 = ANY($3))),
+                    '[]'::jsonb
+                ),
+                secrets_cleared = secrets_cleared OR EXISTS (
+                    SELECT 1 FROM jsonb_array_elements(placeholders) p WHERE p->>0 = ANY($3)
+                )
+            WHERE id = $1
+            "#
+        )
+        .bind(email_id)
+        .bind(provider_message_id)
+        .bind(SECRET_PLACEHOLDERS)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn mark_email_failed(
+        &self,
+        email_id: Uuid,
+        error: &str,
+        next_attempt_at: DateTime<Utc>,
+    ) -> Result<OutboxEmail, sqlx::Error> {
+        sqlx::query_as::<_, OutboxEmail>(
+            r#"
+            UPDATE email_outbox
+            SET attempts = attempts + 1,
+                last_error = $2,
+                next_attempt_at = $3,
+                locked_until = NULL,
+                updated_at = NOW(),
+                status = CASE
+                    WHEN attempts + 1 >= max_attempts THEN 'failed'::email_outbox_status
+                    ELSE 'queued'::email_outbox_status
+                END,
+                placeholders = CASE
+                    WHEN attempts + 1 >= max_attempts THEN COALESCE(
+                        (SELECT jsonb_agg(p) FROM jsonb_array_elements(placeholders) p WHERE NOT (p->>0 = ANY($4))),
+                        '[]'::jsonb
+                    )
+                    ELSE placeholders
+                END,
+                secrets_cleared = secrets_cleared OR (attempts + 1 >= max_attempts AND EXISTS (
+                    SELECT 1 FROM jsonb_array_elements(placeholders) p WHERE p->>0 = ANY($4)
+                ))
+            WHERE id = $1
+            RETURNING id, idempotency_key, to_email, subject, template_path, placeholders, status,
+                      attempts, max_attempts, last_error, next_attempt_at, locked_until,
+                      provider_message_id, created_at, updated_at, sent_at
+            "#
+        )
+        .bind(email_id)
+        .bind(error)
+        .bind(next_attempt_at)
+        .bind(SECRET_PLACEHOLDERS)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    async fn mark_email_suppressed(&self, email_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE email_outbox
+            SET status = 'suppressed',
+                locked_until = NULL,
+                updated_at = NOW(),
+                placeholders = COALESCE(
+                    (SELECT jsonb_agg(p) FROM jsonb_array_elements(placeholders) p WHERE NOT (p->>0 = ANY($2))),
+                    '[]'::jsonb
+                ),
+                secrets_cleared = secrets_cleared OR EXISTS (
+                    SELECT 1 FROM jsonb_array_elements(placeholders) p WHERE p->>0 = ANY($2)
+                )
+            WHERE id = $1
+            "#
+        )
+        .bind(email_id)
+        .bind(SECRET_PLACEHOLDERS)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn requeue_email(&self, email_id: Uuid) -> Result<Option<OutboxEmail>, sqlx::Error> {
+        sqlx::query_as::<_, OutboxEmail>(
+            r#"
+            UPDATE email_outbox
+            SET status = 'queued',
+                attempts = 0,
+                next_attempt_at = NOW(),
+                locked_until = NULL,
+                updated_at = NOW()
+            WHERE id = $1 AND status IN ('failed', 'sending', 'queued') AND NOT secrets_cleared
+            RETURNING id, idempotency_key, to_email, subject, template_path, placeholders, status,
+                      attempts, max_attempts, last_error, next_attempt_at, locked_until,
+                      provider_message_id, created_at, updated_at, sent_at
+            "#
+        )
+        .bind(email_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn get_outbox_emails(
+        &self,
+        status: Option<EmailStatus>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<OutboxEmail>, sqlx::Error> {
+        sqlx::query_as::<_, OutboxEmail>(
+            r#"
+            SELECT id, idempotency_key, to_email, subject, template_path, placeholders, status,
+                   attempts, max_attempts, last_error, next_attempt_at, locked_until,
+                   provider_message_id, created_at, updated_at, sent_at
+            FROM email_outbox
+            WHERE ($1::email_outbox_status IS NULL OR status = $1)
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#
+        )
+        .bind(status)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn get_stuck_emails(
+        &self,
+        stuck_after_minutes: i64,
+        limit: i64,
+    ) -> Result<Vec<OutboxEmail>, sqlx::Error> {
+        sqlx::query_as::<_, OutboxEmail>(
+            r#"
+            SELECT id, idempotency_key, to_email, subject, template_path, placeholders, status,
+                   attempts, max_attempts, last_error, next_attempt_at, locked_until,
+                   provider_message_id, created_at, updated_at, sent_at
+            FROM email_outbox
+            WHERE (status = 'sending' AND locked_until < NOW())
+               OR (status = 'queued' AND next_attempt_at < NOW() - make_interval(mins => $1::int))
+            ORDER BY next_attempt_at ASC
+            LIMIT $2
+            "#
+        )
+        .bind(stuck_after_minutes as i32)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn get_email_suppression(
+        &self,
+        email: &str,
+    ) -> Result<Option<EmailSuppression>, sqlx::Error> {
+        sqlx::query_as::<_, EmailSuppression>(
+            r#"
+            SELECT email, user_id, reason, details, created_at
+            FROM email_suppressions
+            WHERE email = LOWER($1)
+            "#
+        )
+        .bind(email)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn upsert_email_suppression(
+        &self,
+        email: &str,
+        reason: SuppressionReason,
+        details: Option<&str>,
+    ) -> Result<EmailSuppression, sqlx::Error> {
+        sqlx::query_as::<_, EmailSuppression>(
+            r#"
+            INSERT INTO email_suppressions (email, user_id, reason, details)
+            VALUES (
+                LOWER($1),
+                (SELECT id FROM users WHERE LOWER(email) = LOWER($1) LIMIT 1),
+                $2,
+                $3
+            )
+            ON CONFLICT (email) DO UPDATE SET
+                reason = EXCLUDED.reason,
+                details = EXCLUDED.details,
+                user_id = COALESCE(EXCLUDED.user_id, email_suppressions.user_id)
+            RETURNING email, user_id, reason, details, created_at
+            "#
+        )
+        .bind(email)
+        .bind(reason)
+        .bind(details)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    async fn delete_email_suppression(&self, email: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM email_suppressions WHERE email = LOWER($1)")
+            .bind(email)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_email_suppressions(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<EmailSuppression>, sqlx::Error> {
+        sqlx::query_as::<_, EmailSuppression>(
+            r#"
+            SELECT email, user_id, reason, details, created_at
+            FROM email_suppressions
+            ORDER BY created_at DESC
+            LIMIT $1 OFFSET $2
+            "#
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn record_email_event(
+        &self,
+        provider_message_id: Option<&str>,
+        event_type: &str,
+        email: Option<&str>,
+        payload: serde_json::Value,
+    ) -> Result<EmailEvent, sqlx::Error> {
+        sqlx::query_as::<_, EmailEvent>(
+            r#"
+            INSERT INTO email_events (outbox_id, provider_message_id, event_type, email, payload)
+            VALUES (
+                (SELECT id FROM email_outbox WHERE provider_message_id = $1 LIMIT 1),
+                $1, $2, $3, $4
+            )
+            RETURNING id, outbox_id, provider_message_id, event_type, email, payload, created_at
+            "#
+        )
+        .bind(provider_message_id)
+        .bind(event_type)
+        .bind(email)
+        .bind(payload)
+        .fetch_one(&self.pool)
+        .await
+    }
+}
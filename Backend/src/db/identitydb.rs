@@ -0,0 +1,82 @@
+// db/identitydb.rs
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    db::db::DBClient,
+    models::identitymodels::{IdentityCheck, IdentityKind},
+};
+
+const IDENTITY_CHECK_COLUMNS: &str = r#"
+    id, user_id, kind, number_hash, number_last4, provider, outcome, name_score, dob_match, created_at
+"#;
+
+#[async_trait]
+pub trait IdentityCheckExt {
+    async fn insert_identity_check(
+        &self,
+        check: &IdentityCheck,
+    ) -> Result<IdentityCheck, sqlx::Error>;
+
+    /// Someone other than `user_id` whose account this number already matched
+    async fn get_identity_number_owner(
+        &self,
+        kind: IdentityKind,
+        number_hash: &str,
+        user_id: Uuid,
+    ) -> Result<Option<Uuid>, sqlx::Error>;
+}
+
+#[async_trait]
+impl IdentityCheckExt for DBClient {
+    async fn insert_identity_check(
+        &self,
+        check: &IdentityCheck,
+    ) -> Result<IdentityCheck, sqlx::Error> {
+        let query = format!(
+            r#"
+            INSERT INTO identity_checks (
+                id, user_id, kind, number_hash, number_last4, provider, outcome, name_score, dob_match, created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING {}
+            "#,
+            IDENTITY_CHECK_COLUMNS
+        );
+
+        sqlx::query_as::<_, IdentityCheck>(&query)
+            .bind(check.id)
+            .bind(check.user_id)
+            .bind(check.kind)
+            .bind(&check.number_hash)
+            .bind(&check.number_last4)
+            .bind(&check.provider)
+            .bind(check.outcome)
+            .bind(check.name_score)
+            .bind(check.dob_match)
+            .bind(check.created_at)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    async fn get_identity_number_owner(
+        &self,
+        kind: IdentityKind,
+        number_hash: &str,
+        user_id: Uuid,
+    ) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT user_id FROM identity_checks
+            WHERE kind = $1 AND number_hash = $2 AND outcome = 'matched' AND user_id <> $3
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#
+        )
+        .bind(kind)
+        .bind(number_hash)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+}
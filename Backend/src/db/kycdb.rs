@@ -0,0 +1,245 @@
+// db/kycdb.rs
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    db::db::DBClient,
+    models::{
+        kycmodels::{AddressProof, KycCheck, KycFacts},
+        usermodel::VerificationStatus,
+    },
+};
+
+const KYC_CHECK_COLUMNS: &str = r#"
+    id, verification_id, document_quality, selfie_quality, document_faces, selfie_faces,
+    face_similarity, liveness_score, flags, risk_score, decision, error, model_version, created_at
+"#;
+
+const ADDRESS_PROOF_COLUMNS: &str = r#"
+    id, user_id, document_url, address, status, reviewed_by, review_notes, created_at, reviewed_at
+"#;
+
+#[async_trait]
+pub trait KycCheckExt {
+    async fn insert_kyc_check(
+        &self,
+        check: &KycCheck,
+    ) -> Result<KycCheck, sqlx::Error>;
+
+    /// The most recent check for each submission that has one
+    async fn get_latest_kyc_checks(
+        &self,
+        verification_ids: &[Uuid],
+    ) -> Result<Vec<KycCheck>, sqlx::Error>;
+}
+
+#[async_trait]
+impl KycCheckExt for DBClient {
+    async fn insert_kyc_check(
+        &self,
+        check: &KycCheck,
+    ) -> Result<KycCheck, sqlx::Error> {
+        let query = format!(
+            r#"
+            INSERT INTO kyc_checks (
+                id, verification_id, document_quality, selfie_quality, document_faces, selfie_faces,
+                face_similarity, liveness_score, flags, risk_score, decision, error, model_version, created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            RETURNING {}
+            "#,
+            KYC_CHECK_COLUMNS
+        );
+
+        sqlx::query_as::<_, KycCheck>(&query)
+            .bind(check.id)
+            .bind(check.verification_id)
+            .bind(check.document_quality)
+            .bind(check.selfie_quality)
+            .bind(check.document_faces)
+            .bind(check.selfie_faces)
+            .bind(check.face_similarity)
+            .bind(check.liveness_score)
+            .bind(&check.flags)
+            .bind(check.risk_score)
+            .bind(check.decision)
+            .bind(&check.error)
+            .bind(&check.model_version)
+            .bind(check.created_at)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    async fn get_latest_kyc_checks(
+        &self,
+        verification_ids: &[Uuid],
+    ) -> Result<Vec<KycCheck>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT DISTINCT ON (verification_id) {}
+            FROM kyc_checks
+            WHERE verification_id = ANY($1)
+            ORDER BY verification_id, created_at DESC
+            "#,
+            KYC_CHECK_COLUMNS
+        );
+
+        sqlx::query_as::<_, KycCheck>(&query)
+            .bind(verification_ids)
+            .fetch_all(&self.pool)
+            .await
+    }
+}
+
+#[async_trait]
+pub trait KycLevelExt {
+    /// Everything the user's KYC level is worked out from
+    async fn get_kyc_facts(
+        &self,
+        user_id: Uuid,
+    ) -> Result<KycFacts, sqlx::Error>;
+
+    async fn create_address_proof(
+        &self,
+        user_id: Uuid,
+        document_url: &str,
+        address: &str,
+    ) -> Result<AddressProof, sqlx::Error>;
+
+    async fn get_address_proof(
+        &self,
+        proof_id: Uuid,
+    ) -> Result<Option<AddressProof>, sqlx::Error>;
+
+    async fn get_user_address_proofs(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<AddressProof>, sqlx::Error>;
+
+    async fn get_pending_address_proofs(&self) -> Result<Vec<AddressProof>, sqlx::Error>;
+
+    async fn review_address_proof(
+        &self,
+        proof_id: Uuid,
+        status: VerificationStatus,
+        reviewed_by: Uuid,
+        review_notes: Option<String>,
+    ) -> Result<AddressProof, sqlx::Error>;
+}
+
+#[async_trait]
+impl KycLevelExt for DBClient {
+    async fn get_kyc_facts(
+        &self,
+        user_id: Uuid,
+    ) -> Result<KycFacts, sqlx::Error> {
+        let facts = sqlx::query_as::<_, KycFacts>(
+            r#"
+            SELECT
+                u.verified AS email_verified,
+                u.phone_verified_at IS NOT NULL AS phone_verified,
+                COALESCE(u.verification_status = 'approved', false) AS identity_verified,
+                COALESCE(w.bvn_verified, false) AS bvn_verified,
+                EXISTS (
+                    SELECT 1 FROM address_proofs a
+                    WHERE a.user_id = u.id AND a.status = 'approved'
+                ) AS address_verified
+            FROM users u
+            LEFT JOIN naira_wallets w ON w.user_id = u.id
+            WHERE u.id = $1
+            "#
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(facts.unwrap_or_default())
+    }
+
+    async fn create_address_proof(
+        &self,
+        user_id: Uuid,
+        document_url: &str,
+        address: &str,
+    ) -> Result<AddressProof, sqlx::Error> {
+        let query = format!(
+            r#"
+            INSERT INTO address_proofs (user_id, document_url, address)
+            VALUES ($1, $2, $3)
+            RETURNING {}
+            "#,
+            ADDRESS_PROOF_COLUMNS
+        );
+
+        sqlx::query_as::<_, AddressProof>(&query)
+            .bind(user_id)
+            .bind(document_url)
+            .bind(address)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    async fn get_address_proof(
+        &self,
+        proof_id: Uuid,
+    ) -> Result<Option<AddressProof>, sqlx::Error> {
+        let query = format!("SELECT {} FROM address_proofs WHERE id = $1", ADDRESS_PROOF_COLUMNS);
+
+        sqlx::query_as::<_, AddressProof>(&query)
+            .bind(proof_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn get_user_address_proofs(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<AddressProof>, sqlx::Error> {
+        let query = format!(
+            "SELECT {} FROM address_proofs WHERE user_id = $1 ORDER BY created_at DESC",
+            ADDRESS_PROOF_COLUMNS
+        );
+
+        sqlx::query_as::<_, AddressProof>(&query)
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn get_pending_address_proofs(&self) -> Result<Vec<AddressProof>, sqlx::Error> {
+        let query = format!(
+            "SELECT {} FROM address_proofs WHERE status = 'submitted' ORDER BY created_at",
+            ADDRESS_PROOF_COLUMNS
+        );
+
+        sqlx::query_as::<_, AddressProof>(&query)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn review_address_proof(
+        &self,
+        proof_id: Uuid,
+        status: VerificationStatus,
+        reviewed_by: Uuid,
+        review_notes: Option<String>,
+    ) -> Result<AddressProof, sqlx::Error> {
+        let query = format!(
+            r#"
+            UPDATE address_proofs
+            SET status = $2, reviewed_by = $3, review_notes = $4, reviewed_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            ADDRESS_PROOF_COLUMNS
+        );
+
+        sqlx::query_as::<_, AddressProof>(&query)
+            .bind(proof_id)
+            .bind(status)
+            .bind(reviewed_by)
+            .bind(review_notes)
+            .fetch_one(&self.pool)
+            .await
+    }
+}
@@ -0,0 +1,118 @@
+// db/logindb.rs
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    db::db::DBClient,
+    models::sessionmodels::{LoginEvent, LoginFamiliarity, LoginOutcome, SessionClient},
+};
+
+const LOGIN_EVENT_COLUMNS: &str = r#"
+    id, user_id, identifier, outcome, ip_address, user_agent, device_name, device_id, country,
+    new_device, new_location, created_at
+"#;
+
+#[async_trait]
+pub trait LoginEventExt {
+    async fn create_login_event(
+        &self,
+        user_id: Option<Uuid>,
+        identifier: &str,
+        outcome: LoginOutcome,
+        client: &SessionClient,
+        new_device: bool,
+        new_location: bool,
+    ) -> Result<LoginEvent, sqlx::Error>;
+
+    /// Most recent first
+    async fn get_login_events(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<LoginEvent>, sqlx::Error>;
+
+    /// Whether the account has signed in from this device and country before
+    async fn get_login_familiarity(
+        &self,
+        user_id: Uuid,
+        client: &SessionClient,
+    ) -> Result<LoginFamiliarity, sqlx::Error>;
+}
+
+#[async_trait]
+impl LoginEventExt for DBClient {
+    async fn create_login_event(
+        &self,
+        user_id: Option<Uuid>,
+        identifier: &str,
+        outcome: LoginOutcome,
+        client: &SessionClient,
+        new_device: bool,
+        new_location: bool,
+    ) -> Result<LoginEvent, sqlx::Error> {
+        let query = format!(
+            r#"
+            INSERT INTO login_events
+                (user_id, identifier, outcome, ip_address, user_agent, device_name, device_id, country,
+                 new_device, new_location)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING {}
+            "#,
+            LOGIN_EVENT_COLUMNS
+        );
+
+        sqlx::query_as::<_, LoginEvent>(&query)
+            .bind(user_id)
+            .bind(identifier.chars().take(255).collect::<String>())
+            .bind(outcome)
+            .bind(&client.ip_address)
+            .bind(&client.user_agent)
+            .bind(&client.device_name)
+            .bind(&client.device_id)
+            .bind(&client.country)
+            .bind(new_device)
+            .bind(new_location)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    async fn get_login_events(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<LoginEvent>, sqlx::Error> {
+        let query = format!(
+            "SELECT {} FROM login_events WHERE user_id = $1 ORDER BY created_at DESC LIMIT $2",
+            LOGIN_EVENT_COLUMNS
+        );
+
+        sqlx::query_as::<_, LoginEvent>(&query)
+            .bind(user_id)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn get_login_familiarity(
+        &self,
+        user_id: Uuid,
+        client: &SessionClient,
+    ) -> Result<LoginFamiliarity, sqlx::Error> {
+        sqlx::query_as::<_, LoginFamiliarity>(
+            r#"
+            SELECT
+                COUNT(*) AS successful_logins,
+                COALESCE(BOOL_OR(device_id = $2), FALSE) AS known_device,
+                COALESCE(BOOL_OR(country = $3), FALSE) AS known_country,
+                COALESCE(BOOL_OR(country IS NOT NULL), FALSE) AS any_country
+            FROM login_events
+            WHERE user_id = $1 AND outcome = 'success'
+            "#
+        )
+        .bind(user_id)
+        .bind(&client.device_id)
+        .bind(&client.country)
+        .fetch_one(&self.pool)
+        .await
+    }
+}
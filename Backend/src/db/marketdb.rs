@@ -0,0 +1,206 @@
+// db/marketdb.rs
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    db::db::DBClient,
+    models::propertymodel::{ComparableListing, ListingType, MarketSummary, MarketTrendPoint, PropertyType},
+};
+
+const SUMMARY_COLUMNS: &str = r#"
+    state, lga, property_type, listing_type, listings, closed_listings, median_price,
+    low_price, high_price, median_price_per_sqm, median_days_on_market, refreshed_at
+"#;
+
+/// What a comparable listing has to resemble
+#[derive(Debug, Clone)]
+pub struct ComparableFilters<'a> {
+    pub exclude_property_id: Option<Uuid>,
+    pub state: &'a str,
+    pub lga: &'a str,
+    pub property_type: PropertyType,
+    pub listing_type: ListingType,
+    pub price: i64,
+    pub bedrooms: Option<i32>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+#[async_trait]
+pub trait MarketExt {
+    /// Recomputes the market summaries and trends from the listings table
+    async fn refresh_market_summaries(&self) -> Result<(), sqlx::Error>;
+
+    /// The LGA's figures, or the state-wide ones when `lga` is None
+    async fn get_market_summary(
+        &self,
+        state: &str,
+        lga: Option<&str>,
+        property_type: PropertyType,
+        listing_type: ListingType,
+    ) -> Result<Option<MarketSummary>, sqlx::Error>;
+
+    /// Every market in the state matching the filters, busiest first
+    async fn get_market_summaries(
+        &self,
+        state: &str,
+        lga: Option<&str>,
+        property_type: Option<PropertyType>,
+        listing_type: Option<ListingType>,
+    ) -> Result<Vec<MarketSummary>, sqlx::Error>;
+
+    /// Monthly points for the last `months` months, oldest first
+    async fn get_market_trend(
+        &self,
+        state: &str,
+        property_type: PropertyType,
+        listing_type: ListingType,
+        months: i32,
+    ) -> Result<Vec<MarketTrendPoint>, sqlx::Error>;
+
+    /// Public listings like the one described: same state, type and listing
+    /// type, bedrooms within one. Same LGA first, then nearest, then closest in price.
+    async fn get_comparable_listings(
+        &self,
+        filters: &ComparableFilters<'_>,
+        limit: i64,
+    ) -> Result<Vec<ComparableListing>, sqlx::Error>;
+}
+
+#[async_trait]
+impl MarketExt for DBClient {
+    async fn refresh_market_summaries(&self) -> Result<(), sqlx::Error> {
+        // CONCURRENTLY keeps the views readable while they rebuild
+        sqlx::query("REFRESH MATERIALIZED VIEW CONCURRENTLY property_market_summaries")
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("REFRESH MATERIALIZED VIEW CONCURRENTLY property_market_trends")
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_market_summary(
+        &self,
+        state: &str,
+        lga: Option<&str>,
+        property_type: PropertyType,
+        listing_type: ListingType,
+    ) -> Result<Option<MarketSummary>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM property_market_summaries
+            WHERE LOWER(state) = LOWER($1) AND LOWER(lga) = LOWER($2)
+                AND property_type = $3 AND listing_type = $4
+            "#,
+            SUMMARY_COLUMNS
+        );
+
+        sqlx::query_as::<_, MarketSummary>(&query)
+            .bind(state)
+            .bind(lga.unwrap_or(""))
+            .bind(property_type)
+            .bind(listing_type)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn get_market_summaries(
+        &self,
+        state: &str,
+        lga: Option<&str>,
+        property_type: Option<PropertyType>,
+        listing_type: Option<ListingType>,
+    ) -> Result<Vec<MarketSummary>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM property_market_summaries
+            WHERE LOWER(state) = LOWER($1)
+                AND ($2::TEXT IS NULL OR LOWER(lga) = LOWER($2))
+                AND ($3::property_type IS NULL OR property_type = $3)
+                AND ($4::listing_type IS NULL OR listing_type = $4)
+            ORDER BY lga = '' DESC, listings DESC
+            "#,
+            SUMMARY_COLUMNS
+        );
+
+        sqlx::query_as::<_, MarketSummary>(&query)
+            .bind(state)
+            .bind(lga)
+            .bind(property_type)
+            .bind(listing_type)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn get_market_trend(
+        &self,
+        state: &str,
+        property_type: PropertyType,
+        listing_type: ListingType,
+        months: i32,
+    ) -> Result<Vec<MarketTrendPoint>, sqlx::Error> {
+        sqlx::query_as::<_, MarketTrendPoint>(
+            r#"
+            SELECT month, listings, median_price, median_price_per_sqm
+            FROM property_market_trends
+            WHERE LOWER(state) = LOWER($1) AND property_type = $2 AND listing_type = $3
+                AND month >= (date_trunc('month', NOW()) - make_interval(months => $4))::DATE
+            ORDER BY month
+            "#
+        )
+        .bind(state)
+        .bind(property_type)
+        .bind(listing_type)
+        .bind(months)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn get_comparable_listings(
+        &self,
+        filters: &ComparableFilters<'_>,
+        limit: i64,
+    ) -> Result<Vec<ComparableListing>, sqlx::Error> {
+        sqlx::query_as::<_, ComparableListing>(
+            r#"
+            SELECT * FROM (
+                SELECT
+                    id AS property_id, title, address, lga, status, price,
+                    size_sqm::FLOAT8 AS size_sqm, bedrooms,
+                    CASE WHEN $8::FLOAT8 IS NOT NULL AND $9::FLOAT8 IS NOT NULL
+                        AND latitude IS NOT NULL AND longitude IS NOT NULL THEN
+                        6371 * 2 * ASIN(SQRT(
+                            POWER(SIN(RADIANS(latitude::FLOAT8 - $8) / 2), 2)
+                            + COS(RADIANS($8)) * COS(RADIANS(latitude::FLOAT8))
+                            * POWER(SIN(RADIANS(longitude::FLOAT8 - $9) / 2), 2)
+                        ))
+                    END AS distance_km
+                FROM properties
+                WHERE status IN ('active', 'sold', 'rented')
+                    AND ($1::UUID IS NULL OR id != $1)
+                    AND LOWER(state) = LOWER($2)
+                    AND property_type = $4 AND listing_type = $5
+                    AND ($7::INT IS NULL OR bedrooms BETWEEN $7 - 1 AND $7 + 1)
+            ) comparables
+            ORDER BY LOWER(lga) = LOWER($3) DESC, distance_km NULLS LAST, ABS(price - $6)
+            LIMIT $10
+            "#
+        )
+        .bind(filters.exclude_property_id)
+        .bind(filters.state)
+        .bind(filters.lga)
+        .bind(filters.property_type)
+        .bind(filters.listing_type)
+        .bind(filters.price)
+        .bind(filters.bedrooms)
+        .bind(filters.latitude)
+        .bind(filters.longitude)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
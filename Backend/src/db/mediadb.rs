@@ -0,0 +1,116 @@
+// db/mediadb.rs
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    db::db::DBClient,
+    models::mediamodels::{MediaObject, MediaVisibility},
+};
+
+const MEDIA_COLUMNS: &str = r#"
+    id, owner_id, purpose, visibility, storage_key, content_type, size_bytes, sha256,
+    original_filename, created_at
+"#;
+
+#[async_trait]
+pub trait MediaExt {
+    /// Records an upload. The same file uploaded again for the same purpose
+    /// returns the existing row.
+    async fn insert_media_object(
+        &self,
+        owner_id: Uuid,
+        purpose: &str,
+        visibility: MediaVisibility,
+        storage_key: &str,
+        content_type: &str,
+        size_bytes: i64,
+        sha256: &str,
+        original_filename: Option<&str>,
+    ) -> Result<MediaObject, sqlx::Error>;
+
+    async fn get_media_object(
+        &self,
+        media_id: Uuid,
+    ) -> Result<Option<MediaObject>, sqlx::Error>;
+
+    /// The owner's upload stored under the key, if they made one
+    async fn get_owned_media_by_key(
+        &self,
+        owner_id: Uuid,
+        storage_key: &str,
+    ) -> Result<Option<MediaObject>, sqlx::Error>;
+}
+
+#[async_trait]
+impl MediaExt for DBClient {
+    async fn insert_media_object(
+        &self,
+        owner_id: Uuid,
+        purpose: &str,
+        visibility: MediaVisibility,
+        storage_key: &str,
+        content_type: &str,
+        size_bytes: i64,
+        sha256: &str,
+        original_filename: Option<&str>,
+    ) -> Result<MediaObject, sqlx::Error> {
+        let query = format!(
+            r#"
+            INSERT INTO media_objects
+                (owner_id, purpose, visibility, storage_key, content_type, size_bytes, sha256, original_filename)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (owner_id, storage_key, purpose)
+                DO UPDATE SET original_filename = COALESCE(EXCLUDED.original_filename, media_objects.original_filename)
+            RETURNING {}
+            "#,
+            MEDIA_COLUMNS
+        );
+
+        sqlx::query_as::<_, MediaObject>(&query)
+            .bind(owner_id)
+            .bind(purpose)
+            .bind(visibility)
+            .bind(storage_key)
+            .bind(content_type)
+            .bind(size_bytes)
+            .bind(sha256)
+            .bind(original_filename)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    async fn get_media_object(
+        &self,
+        media_id: Uuid,
+    ) -> Result<Option<MediaObject>, sqlx::Error> {
+        let query = format!("SELECT {} FROM media_objects WHERE id = $1", MEDIA_COLUMNS);
+
+        sqlx::query_as::<_, MediaObject>(&query)
+            .bind(media_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn get_owned_media_by_key(
+        &self,
+        owner_id: Uuid,
+        storage_key: &str,
+    ) -> Result<Option<MediaObject>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM media_objects
+            WHERE owner_id = $1 AND storage_key = $2
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+            MEDIA_COLUMNS
+        );
+
+        sqlx::query_as::<_, MediaObject>(&query)
+            .bind(owner_id)
+            .bind(storage_key)
+            .fetch_optional(&self.pool)
+            .await
+    }
+}
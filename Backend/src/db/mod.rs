@@ -1,6 +1,6 @@
 pub mod db;
 pub mod userdb;
-// pub mod propertydb;
+pub mod propertydb;
 pub mod labourdb;
 pub mod naira_walletdb;
 pub mod verificationdb;
@@ -8,4 +8,23 @@ pub mod chatdb;
 pub mod supportdb;
 pub mod vendordb;
 pub mod cache;
-pub mod subscriptiondb;
\ No newline at end of file
+pub mod subscriptiondb;
+pub mod notificationdb;
+pub mod emaildb;
+pub mod viewingdb;
+pub mod tenancydb;
+pub mod offerdb;
+pub mod photodb;
+pub mod marketdb;
+pub mod savedpropertydb;
+pub mod assignmentdb;
+pub mod certificatedb;
+pub mod mediadb;
+pub mod sessiondb;
+pub mod twofactordb;
+pub mod permissiondb;
+pub mod kycdb;
+pub mod identitydb;
+pub mod privacydb;
+pub mod phonedb;
+pub mod logindb;
\ No newline at end of file
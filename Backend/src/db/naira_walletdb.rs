@@ -7,8 +7,8 @@ use serde_json::Value as JsonValue;
 use num_traits::ToPrimitive;
 use bigdecimal::BigDecimal;
 
-use super::db::DBClient;
-use crate::models::{walletmodels::*, usermodel::User};
+use super::{db::DBClient, kycdb::KycLevelExt};
+use crate::models::{walletmodels::*, kycmodels::KycLevel};
 
 #[async_trait]
 pub trait NairaWalletExt {
@@ -20,6 +20,11 @@ pub trait NairaWalletExt {
         wallet_id: Uuid, 
         status: WalletStatus
     ) -> Result<NairaWallet, Error>;
+    async fn set_bvn_verified(
+        &self,
+        user_id: Uuid,
+        bvn_verified: bool
+    ) -> Result<Option<NairaWallet>, Error>;
     
     // Balance Operations
     async fn get_wallet_balance(&self, user_id: Uuid) -> Result<i64, Error>;
@@ -50,6 +55,33 @@ pub trait NairaWalletExt {
         transaction_id: Uuid,
     ) -> Result<WalletTransaction, Error>;
 
+    /// `credit_wallet` inside the caller's transaction, so it lands or rolls
+    /// back with whatever else the caller changes
+    async fn credit_wallet_tx(
+        &self,
+        user_id: Uuid,
+        amount: i64,
+        transaction_type: TransactionType,
+        description: String,
+        reference: String,
+        external_reference: Option<String>,
+        metadata: Option<JsonValue>,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<WalletTransaction, Error>;
+
+    /// `debit_wallet` inside the caller's transaction
+    async fn debit_wallet_tx(
+        &self,
+        user_id: Uuid,
+        amount: i64,
+        transaction_type: TransactionType,
+        description: String,
+        reference: String,
+        external_reference: Option<String>,
+        metadata: Option<JsonValue>,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<WalletTransaction, Error>;
+
     // Transfer Operations
     async fn transfer_funds(
         &self,
@@ -70,12 +102,31 @@ pub trait NairaWalletExt {
         expires_at: Option<DateTime<Utc>>
     ) -> Result<WalletHold, Error>;
 
+    /// `create_wallet_hold` inside the caller's transaction
+    async fn create_wallet_hold_tx(
+        &self,
+        wallet_id: Uuid,
+        job_id: Option<Uuid>,
+        amount: i64,
+        reason: String,
+        expires_at: Option<DateTime<Utc>>,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<WalletHold, Error>;
+
     async fn release_wallet_hold(
         &self,
         hold_id: Uuid,
         release_to_available: bool
     ) -> Result<(), Error>;
 
+    /// `release_wallet_hold` inside the caller's transaction
+    async fn release_wallet_hold_tx(
+        &self,
+        hold_id: Uuid,
+        release_to_available: bool,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<(), Error>;
+
     async fn get_wallet_holds(
         &self,
         wallet_id: Uuid,
@@ -158,9 +209,9 @@ pub trait NairaWalletExt {
 
     async fn get_wallet_limits(
         &self,
-        user_tier: &UserTier,
-        transaction_type: &TransactionType,
-    ) -> Result<WalletLimit, sqlx::Error>;
+        kyc_level: KycLevel,
+        transaction_type: TransactionType,
+    ) -> Result<Option<WalletLimit>, sqlx::Error>;
 
     async fn debit_wallet_with_validation(
         &self,
@@ -187,56 +238,22 @@ pub struct WalletSummary {
 impl NairaWalletExt for DBClient {
     async fn get_wallet_limits(
         &self,
-        user_tier: &UserTier,
-        transaction_type: &TransactionType,
-    ) -> Result<WalletLimit, sqlx::Error> {
-        // Try to get specific limit first
-        let result = sqlx::query_as::<_, WalletLimit>(
+        kyc_level: KycLevel,
+        transaction_type: TransactionType,
+    ) -> Result<Option<WalletLimit>, sqlx::Error> {
+        sqlx::query_as::<_, WalletLimit>(
             r#"
-            SELECT 
-                id, is_active, 
-                transaction_type as "transaction_type!: _",
-                user_tier as "user_tier!: _",
-                daily_limit as "daily_limit!: i64",
-                monthly_limit as "monthly_limit!: i64",
-                per_transaction_limit as "per_transaction_limit!: i64"
+            SELECT id, kyc_level, transaction_type, daily_limit, monthly_limit, per_transaction_limit, is_active
             FROM wallet_limits
-            WHERE user_tier = $1
+            WHERE kyc_level = $1
             AND transaction_type = $2
             AND is_active = true
             "#
         )
-        .bind(user_tier)
+        .bind(kyc_level)
         .bind(transaction_type)
         .fetch_optional(&self.pool)
-        .await?;
-
-        match result {
-            Some(limit) => Ok(limit),
-            None => {
-                // Return default limits for the tier
-                sqlx::query_as::<_, WalletLimit>(
-                    r#"
-                    SELECT 
-                        id, is_active, 
-                        transaction_type as "transaction_type!: _",
-                        user_tier as "user_tier!: _",
-                        daily_limit as "daily_limit!: i64",
-                        monthly_limit as "monthly_limit!: i64",
-                        per_transaction_limit as "per_transaction_limit!: i64"
-                    FROM wallet_limits
-                    WHERE user_tier = $1
-                    AND transaction_type = 'transfer'::transaction_type
-                    AND is_active = true
-                    ORDER BY id DESC
-                    LIMIT 1
-                    "#
-                )
-                .bind(user_tier)
-                .fetch_one(&self.pool)
-                .await
-            }
-        }
+        .await
     }
 
     async fn refund_transaction(
@@ -438,6 +455,39 @@ impl NairaWalletExt for DBClient {
         .await
     }
 
+    async fn set_bvn_verified(
+        &self,
+        user_id: Uuid,
+        bvn_verified: bool
+    ) -> Result<Option<NairaWallet>, Error> {
+        sqlx::query_as::<_, NairaWallet>(
+            r#"
+            UPDATE naira_wallets 
+            SET bvn_verified = $2, updated_at = NOW()
+            WHERE user_id = $1
+            RETURNING 
+                id, 
+                user_id, 
+                balance, 
+                available_balance, 
+                total_deposits, 
+                total_withdrawals, 
+                status, 
+                daily_limit, 
+                monthly_limit, 
+                is_verified, 
+                bvn_verified, 
+                created_at, 
+                updated_at, 
+                last_activity_at
+            "#
+        )
+        .bind(user_id)
+        .bind(bvn_verified)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
     async fn get_wallet_balance(&self, user_id: Uuid) -> Result<i64, Error> {
         let result = sqlx::query(
             "SELECT balance FROM naira_wallets WHERE user_id = $1"
@@ -460,13 +510,30 @@ impl NairaWalletExt for DBClient {
         metadata: Option<JsonValue>
     ) -> Result<WalletTransaction, Error> {
         let mut tx = self.pool.begin().await?;
-        
+        let transaction = self
+            .credit_wallet_tx(user_id, amount, transaction_type, description, reference, external_reference, metadata, &mut tx)
+            .await?;
+        tx.commit().await?;
+        Ok(transaction)
+    }
+
+    async fn credit_wallet_tx(
+        &self, 
+        user_id: Uuid, 
+        amount: i64, 
+        transaction_type: TransactionType, 
+        description: String,
+        reference: String,
+        external_reference: Option<String>,
+        metadata: Option<JsonValue>,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<WalletTransaction, Error> {
         // Get current wallet balance
         let wallet = sqlx::query(
             "SELECT id, balance, available_balance FROM naira_wallets WHERE user_id = $1 FOR UPDATE"
         )
         .bind(user_id)
-        .fetch_one(&mut *tx)
+        .fetch_one(&mut **tx)
         .await?;
 
         let balance_before = wallet.get::<i64, _>("balance");
@@ -490,7 +557,7 @@ impl NairaWalletExt for DBClient {
         .bind(available_after)
         .bind(transaction_type)
         .bind(amount)
-        .execute(&mut *tx)
+        .execute(&mut **tx)
         .await?;
 
         // Create transaction record
@@ -532,10 +599,9 @@ impl NairaWalletExt for DBClient {
         .bind(external_reference)
         .bind(description)
         .bind(metadata)
-        .fetch_one(&mut *tx)
+        .fetch_one(&mut **tx)
         .await?;
 
-        tx.commit().await?;
         Ok(transaction)
     }
 
@@ -550,13 +616,30 @@ impl NairaWalletExt for DBClient {
         metadata: Option<JsonValue>
     ) -> Result<WalletTransaction, Error> {
         let mut tx = self.pool.begin().await?;
-        
+        let transaction = self
+            .debit_wallet_tx(user_id, amount, transaction_type, description, reference, external_reference, metadata, &mut tx)
+            .await?;
+        tx.commit().await?;
+        Ok(transaction)
+    }
+
+    async fn debit_wallet_tx(
+        &self, 
+        user_id: Uuid, 
+        amount: i64, 
+        transaction_type: TransactionType, 
+        description: String,
+        reference: String,
+        external_reference: Option<String>,
+        metadata: Option<JsonValue>,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<WalletTransaction, Error> {
         // Get current wallet balance
         let wallet = sqlx::query(
             "SELECT id, balance, available_balance FROM naira_wallets WHERE user_id = $1 FOR UPDATE"
         )
         .bind(user_id)
-        .fetch_one(&mut *tx)
+        .fetch_one(&mut **tx)
         .await?;
 
         // Check sufficient balance
@@ -585,7 +668,7 @@ impl NairaWalletExt for DBClient {
         .bind(available_after)
         .bind(transaction_type)
         .bind(amount)
-        .execute(&mut *tx)
+        .execute(&mut **tx)
         .await?;
 
         // Create transaction record
@@ -627,10 +710,9 @@ impl NairaWalletExt for DBClient {
         .bind(external_reference)
         .bind(description)
         .bind(metadata)
-        .fetch_one(&mut *tx)
+        .fetch_one(&mut **tx)
         .await?;
 
-        tx.commit().await?;
         Ok(transaction)
     }
 
@@ -767,13 +849,28 @@ impl NairaWalletExt for DBClient {
         expires_at: Option<DateTime<Utc>>
     ) -> Result<WalletHold, Error> {
         let mut tx = self.pool.begin().await?;
+        let hold = self
+            .create_wallet_hold_tx(wallet_id, job_id, amount, reason, expires_at, &mut tx)
+            .await?;
+        tx.commit().await?;
+        Ok(hold)
+    }
 
+    async fn create_wallet_hold_tx(
+        &self,
+        wallet_id: Uuid,
+        job_id: Option<Uuid>,
+        amount: i64,
+        reason: String,
+        expires_at: Option<DateTime<Utc>>,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<WalletHold, Error> {
         // Get wallet and check available balance
         let wallet = sqlx::query(
             "SELECT available_balance FROM naira_wallets WHERE id = $1 FOR UPDATE"
         )
         .bind(wallet_id)
-        .fetch_one(&mut *tx)
+        .fetch_one(&mut **tx)
         .await?;
 
         if wallet.get::<i64, _>("available_balance") < amount {
@@ -787,7 +884,7 @@ impl NairaWalletExt for DBClient {
         )
         .bind(wallet_id)
         .bind(new_available_balance)
-        .execute(&mut *tx)
+        .execute(&mut **tx)
         .await?;
 
         // Create hold record
@@ -803,10 +900,9 @@ impl NairaWalletExt for DBClient {
         .bind(amount)
         .bind(reason)
         .bind(expires_at)
-        .fetch_one(&mut *tx)
+        .fetch_one(&mut **tx)
         .await?;
 
-        tx.commit().await?;
         Ok(hold)
     }
 
@@ -816,13 +912,23 @@ impl NairaWalletExt for DBClient {
         release_to_available: bool
     ) -> Result<(), Error> {
         let mut tx = self.pool.begin().await?;
+        self.release_wallet_hold_tx(hold_id, release_to_available, &mut tx).await?;
+        tx.commit().await?;
+        Ok(())
+    }
 
+    async fn release_wallet_hold_tx(
+        &self,
+        hold_id: Uuid,
+        release_to_available: bool,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<(), Error> {
         // Get hold details
         let hold = sqlx::query(
             "SELECT wallet_id, amount FROM wallet_holds WHERE id = $1 AND status = 'active'"
         )
         .bind(hold_id)
-        .fetch_one(&mut *tx)
+        .fetch_one(&mut **tx)
         .await?;
 
         if release_to_available {
@@ -832,7 +938,7 @@ impl NairaWalletExt for DBClient {
             )
             .bind(hold.get::<Uuid, _>("wallet_id"))
             .bind(hold.get::<i64, _>("amount"))
-            .execute(&mut *tx)
+            .execute(&mut **tx)
             .await?;
         } else {
             // Remove from total balance (funds used)
@@ -841,7 +947,7 @@ impl NairaWalletExt for DBClient {
             )
             .bind(hold.get::<Uuid, _>("wallet_id"))
             .bind(hold.get::<i64, _>("amount"))
-            .execute(&mut *tx)
+            .execute(&mut **tx)
             .await?;
         }
 
@@ -850,10 +956,9 @@ impl NairaWalletExt for DBClient {
             "UPDATE wallet_holds SET status = 'released', released_at = NOW() WHERE id = $1"
         )
         .bind(hold_id)
-        .execute(&mut *tx)
+        .execute(&mut **tx)
         .await?;
 
-        tx.commit().await?;
         Ok(())
     }
 
@@ -1170,55 +1275,20 @@ impl NairaWalletExt for DBClient {
     transaction_type: TransactionType,
     amount: i64
 ) -> Result<bool, Error> {
-    // Get user with proper tier determination
-    let user = sqlx::query_as::<_, User>(
-        "SELECT * FROM users WHERE id = $1"
-    )
-    .bind(user_id)
-    .fetch_one(&self.pool)
-    .await?;
-
-    // Determine user tier based on verification status
-    let user_tier = match user.verified {
-        true => UserTier::Verified,
-        _ => UserTier::Basic,
-    };
-
-    // Get limits for user tier and transaction type
-    let limits = sqlx::query(
-        r#"
-        SELECT daily_limit, monthly_limit, per_transaction_limit
-        FROM wallet_limits 
-        WHERE user_tier = $1 AND transaction_type = $2 AND is_active = true
-        "#
-    )
-    .bind(user_tier)
-    .bind(transaction_type)
-    .fetch_optional(&self.pool)
-    .await?;
+    // Limits follow the user's KYC level
+    let kyc_level = KycLevel::from_facts(&self.get_kyc_facts(user_id).await?);
 
-    // If no limits found, use generous defaults
-    let (per_transaction_limit, daily_limit, monthly_limit) = if let Some(limit) = limits {
-        (
-            limit.get::<i64, _>("per_transaction_limit"),
-            limit.get::<i64, _>("daily_limit"),
-            limit.get::<i64, _>("monthly_limit"),
-        )
-    } else {
-        // No limits configured - use generous defaults based on tier
-        let (daily, monthly, per_tx) = match user_tier {
-            UserTier::Basic => (50_000_000i64, 500_000_000i64, 10_000_000i64),
-            UserTier::Verified => (500_000_000i64, 5_000_000_000i64, 100_000_000i64),
-            UserTier::Premium => (1_000_000_000i64, 10_000_000_000i64, 500_000_000i64),
+    let (daily_limit, monthly_limit, per_transaction_limit) =
+        match self.get_wallet_limits(kyc_level, transaction_type).await? {
+            Some(limit) => (limit.daily_limit, limit.monthly_limit, limit.per_transaction_limit),
+            None => {
+                tracing::warn!(
+                    "No wallet limits found for kyc_level={:?}, transaction_type={:?}. Using defaults",
+                    kyc_level, transaction_type
+                );
+                kyc_level.default_wallet_limits()
+            }
         };
-        
-        tracing::warn!(
-            "No wallet limits found for user_tier={:?}, transaction_type={:?}. Using defaults",
-            user_tier, transaction_type
-        );
-        
-        (per_tx, daily, monthly)
-    };
 
     // Check per transaction limit
     if amount > per_transaction_limit {
@@ -0,0 +1,577 @@
+// db/notificationdb.rs
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::db::DBClient;
+use crate::models::notificationmodels::*;
+
+#[async_trait]
+pub trait NotificationExt {
+    async fn get_notification_preferences(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<NotificationPreference>, sqlx::Error>;
+
+    async fn upsert_notification_preference(
+        &self,
+        user_id: Uuid,
+        category: NotificationCategory,
+        channel: NotificationChannel,
+        enabled: bool,
+    ) -> Result<NotificationPreference, sqlx::Error>;
+
+    async fn get_notification_settings(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Option<NotificationSettings>, sqlx::Error>;
+
+    async fn upsert_notification_settings(
+        &self,
+        user_id: Uuid,
+        quiet_hours_enabled: bool,
+        quiet_hours_start: i16,
+        quiet_hours_end: i16,
+        utc_offset_minutes: i32,
+        digest_frequency: DigestFrequency,
+    ) -> Result<NotificationSettings, sqlx::Error>;
+
+    /// Preferences and settings in one go, falling back to defaults
+    async fn get_notification_preference_set(
+        &self,
+        user_id: Uuid,
+    ) -> Result<NotificationPreferenceSet, sqlx::Error>;
+
+    async fn enqueue_digest_item(
+        &self,
+        user_id: Uuid,
+        notification_type: &str,
+        title: &str,
+        message: &str,
+    ) -> Result<(), sqlx::Error>;
+
+    async fn get_users_with_pending_digest(&self) -> Result<Vec<Uuid>, sqlx::Error>;
+
+    async fn get_pending_digest_items(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<DigestQueueItem>, sqlx::Error>;
+
+    async fn mark_digest_sent(
+        &self,
+        user_id: Uuid,
+        item_ids: &[Uuid],
+        sent_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error>;
+
+    async fn create_notification_delivery(
+        &self,
+        user_id: Uuid,
+        channel: NotificationChannel,
+        template_key: &str,
+        recipient: Option<&str>,
+        payload: serde_json::Value,
+        status: DeliveryStatus,
+        next_attempt_at: DateTime<Utc>,
+    ) -> Result<NotificationDelivery, sqlx::Error>;
+
+    async fn mark_delivery_sent(&self, delivery_id: Uuid) -> Result<(), sqlx::Error>;
+
+    /// Count a failed attempt; the delivery stays pending until it runs out of attempts
+    async fn mark_delivery_failed(
+        &self,
+        delivery_id: Uuid,
+        error: &str,
+        next_attempt_at: DateTime<Utc>,
+    ) -> Result<NotificationDelivery, sqlx::Error>;
+
+    async fn update_delivery_status(
+        &self,
+        delivery_id: Uuid,
+        status: DeliveryStatus,
+        next_attempt_at: DateTime<Utc>,
+        last_error: Option<&str>,
+    ) -> Result<(), sqlx::Error>;
+
+    /// Lease pending deliveries that are due so concurrent workers don't pick the same rows
+    async fn claim_due_deliveries(&self, limit: i64) -> Result<Vec<NotificationDelivery>, sqlx::Error>;
+
+    async fn get_user_deliveries(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<NotificationDelivery>, sqlx::Error>;
+
+    async fn upsert_push_subscription(
+        &self,
+        user_id: Uuid,
+        token: &str,
+        platform: &str,
+    ) -> Result<PushSubscription, sqlx::Error>;
+
+    async fn delete_push_subscription(&self, user_id: Uuid, token: &str) -> Result<(), sqlx::Error>;
+
+    async fn get_push_subscriptions(&self, user_id: Uuid) -> Result<Vec<PushSubscription>, sqlx::Error>;
+
+    async fn create_notification(
+        &self,
+        user_id: Uuid,
+        title: &str,
+        message: &str,
+        notification_type: &str,
+        related_id: Option<Uuid>,
+    ) -> Result<Notification, sqlx::Error>;
+
+    async fn count_unread_notifications(&self, user_id: Uuid) -> Result<i64, sqlx::Error>;
+
+    /// Oldest first, for replaying what a reconnecting stream missed
+    async fn get_notifications_since(
+        &self,
+        user_id: Uuid,
+        since: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<Notification>, sqlx::Error>;
+}
+
+#[async_trait]
+impl NotificationExt for DBClient {
+    async fn get_notification_preferences(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<NotificationPreference>, sqlx::Error> {
+        sqlx::query_as::<_, NotificationPreference>(
+            r#"
+            SELECT user_id, category, channel, enabled, updated_at
+            FROM notification_preferences
+            WHERE user_id = $1
+            "#
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn upsert_notification_preference(
+        &self,
+        user_id: Uuid,
+        category: NotificationCategory,
+        channel: NotificationChannel,
+        enabled: bool,
+    ) -> Result<NotificationPreference, sqlx::Error> {
+        sqlx::query_as::<_, NotificationPreference>(
+            r#"
+            INSERT INTO notification_preferences (user_id, category, channel, enabled)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id, category, channel)
+            DO UPDATE SET enabled = EXCLUDED.enabled, updated_at = NOW()
+            RETURNING user_id, category, channel, enabled, updated_at
+            "#
+        )
+        .bind(user_id)
+        .bind(category)
+        .bind(channel)
+        .bind(enabled)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    async fn get_notification_settings(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Option<NotificationSettings>, sqlx::Error> {
+        sqlx::query_as::<_, NotificationSettings>(
+            r#"
+            SELECT user_id, quiet_hours_enabled, quiet_hours_start, quiet_hours_end,
+                   utc_offset_minutes, digest_frequency, last_digest_sent_at, updated_at
+            FROM notification_settings
+            WHERE user_id = $1
+            "#
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn upsert_notification_settings(
+        &self,
+        user_id: Uuid,
+        quiet_hours_enabled: bool,
+        quiet_hours_start: i16,
+        quiet_hours_end: i16,
+        utc_offset_minutes: i32,
+        digest_frequency: DigestFrequency,
+    ) -> Result<NotificationSettings, sqlx::Error> {
+        sqlx::query_as::<_, NotificationSettings>(
+            r#"
+            INSERT INTO notification_settings
+                (user_id, quiet_hours_enabled, quiet_hours_start, quiet_hours_end,
+                 utc_offset_minutes, digest_frequency)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (user_id) DO UPDATE SET
+                quiet_hours_enabled = EXCLUDED.quiet_hours_enabled,
+                quiet_hours_start = EXCLUDED.quiet_hours_start,
+                quiet_hours_end = EXCLUDED.quiet_hours_end,
+                utc_offset_minutes = EXCLUDED.utc_offset_minutes,
+                digest_frequency = EXCLUDED.digest_frequency,
+                updated_at = NOW()
+            RETURNING user_id, quiet_hours_enabled, quiet_hours_start, quiet_hours_end,
+                      utc_offset_minutes, digest_frequency, last_digest_sent_at, updated_at
+            "#
+        )
+        .bind(user_id)
+        .bind(quiet_hours_enabled)
+        .bind(quiet_hours_start)
+        .bind(quiet_hours_end)
+        .bind(utc_offset_minutes)
+        .bind(digest_frequency)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    async fn get_notification_preference_set(
+        &self,
+        user_id: Uuid,
+    ) -> Result<NotificationPreferenceSet, sqlx::Error> {
+        let settings = self
+            .get_notification_settings(user_id)
+            .await?
+            .unwrap_or_else(|| NotificationSettings::default_for(user_id));
+        let overrides = self.get_notification_preferences(user_id).await?;
+
+        Ok(NotificationPreferenceSet { settings, overrides })
+    }
+
+    async fn enqueue_digest_item(
+        &self,
+        user_id: Uuid,
+        notification_type: &str,
+        title: &str,
+        message: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO notification_digest_queue (user_id, notification_type, title, message)
+            VALUES ($1, $2, $3, $4)
+            "#
+        )
+        .bind(user_id)
+        .bind(notification_type)
+        .bind(title)
+        .bind(message)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_users_with_pending_digest(&self) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT DISTINCT user_id
+            FROM notification_digest_queue
+            WHERE sent_at IS NULL
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn get_pending_digest_items(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<DigestQueueItem>, sqlx::Error> {
+        sqlx::query_as::<_, DigestQueueItem>(
+            r#"
+            SELECT id, user_id, notification_type, title, message, created_at, sent_at
+            FROM notification_digest_queue
+            WHERE user_id = $1 AND sent_at IS NULL
+            ORDER BY created_at ASC
+            "#
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn mark_digest_sent(
+        &self,
+        user_id: Uuid,
+        item_ids: &[Uuid],
+        sent_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE notification_digest_queue
+            SET sent_at = $3
+            WHERE user_id = $1 AND id = ANY($2)
+            "#
+        )
+        .bind(user_id)
+        .bind(item_ids)
+        .bind(sent_at)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO notification_settings (user_id, last_digest_sent_at)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id) DO UPDATE SET last_digest_sent_at = EXCLUDED.last_digest_sent_at
+            "#
+        )
+        .bind(user_id)
+        .bind(sent_at)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn create_notification_delivery(
+        &self,
+        user_id: Uuid,
+        channel: NotificationChannel,
+        template_key: &str,
+        recipient: Option<&str>,
+        payload: serde_json::Value,
+        status: DeliveryStatus,
+        next_attempt_at: DateTime<Utc>,
+    ) -> Result<NotificationDelivery, sqlx::Error> {
+        sqlx::query_as::<_, NotificationDelivery>(
+            r#"
+            INSERT INTO notification_deliveries
+                (user_id, channel, template_key, recipient, payload, status, next_attempt_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, user_id, channel, template_key, recipient, payload, status,
+                      attempts, max_attempts, last_error, next_attempt_at, created_at, sent_at
+            "#
+        )
+        .bind(user_id)
+        .bind(channel)
+        .bind(template_key)
+        .bind(recipient)
+        .bind(payload)
+        .bind(status)
+        .bind(next_attempt_at)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    async fn mark_delivery_sent(&self, delivery_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE notification_deliveries
+            SET status = 'sent', attempts = attempts + 1, last_error = NULL, sent_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(delivery_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn mark_delivery_failed(
+        &self,
+        delivery_id: Uuid,
+        error: &str,
+        next_attempt_at: DateTime<Utc>,
+    ) -> Result<NotificationDelivery, sqlx::Error> {
+        sqlx::query_as::<_, NotificationDelivery>(
+            r#"
+            UPDATE notification_deliveries
+            SET attempts = attempts + 1,
+                last_error = $2,
+                next_attempt_at = $3,
+                status = CASE
+                    WHEN attempts + 1 >= max_attempts THEN 'failed'::notification_delivery_status
+                    ELSE 'pending'::notification_delivery_status
+                END
+            WHERE id = $1
+            RETURNING id, user_id, channel, template_key, recipient, payload, status,
+                      attempts, max_attempts, last_error, next_attempt_at, created_at, sent_at
+            "#
+        )
+        .bind(delivery_id)
+        .bind(error)
+        .bind(next_attempt_at)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    async fn update_delivery_status(
+        &self,
+        delivery_id: Uuid,
+        status: DeliveryStatus,
+        next_attempt_at: DateTime<Utc>,
+        last_error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE notification_deliveries
+            SET status = $2, next_attempt_at = $3, last_error = COALESCE($4, last_error)
+            WHERE id = $1
+            "#
+        )
+        .bind(delivery_id)
+        .bind(status)
+        .bind(next_attempt_at)
+        .bind(last_error)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn claim_due_deliveries(&self, limit: i64) -> Result<Vec<NotificationDelivery>, sqlx::Error> {
+        sqlx::query_as::<_, NotificationDelivery>(
+            r#"
+            UPDATE notification_deliveries
+            SET next_attempt_at = NOW() + INTERVAL '5 minutes'
+            WHERE id IN (
+                SELECT id FROM notification_deliveries
+                WHERE status = 'pending' AND next_attempt_at <= NOW()
+                ORDER BY next_attempt_at ASC
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, user_id, channel, template_key, recipient, payload, status,
+                      attempts, max_attempts, last_error, next_attempt_at, created_at, sent_at
+            "#
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn get_user_deliveries(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<NotificationDelivery>, sqlx::Error> {
+        sqlx::query_as::<_, NotificationDelivery>(
+            r#"
+            SELECT id, user_id, channel, template_key, recipient, payload, status,
+                   attempts, max_attempts, last_error, next_attempt_at, created_at, sent_at
+            FROM notification_deliveries
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#
+        )
+        .bind(user_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn upsert_push_subscription(
+        &self,
+        user_id: Uuid,
+        token: &str,
+        platform: &str,
+    ) -> Result<PushSubscription, sqlx::Error> {
+        sqlx::query_as::<_, PushSubscription>(
+            r#"
+            INSERT INTO push_subscriptions (user_id, token, platform)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (token) DO UPDATE SET user_id = EXCLUDED.user_id, platform = EXCLUDED.platform
+            RETURNING id, user_id, token, platform, created_at
+            "#
+        )
+        .bind(user_id)
+        .bind(token)
+        .bind(platform)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    async fn delete_push_subscription(&self, user_id: Uuid, token: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM push_subscriptions WHERE user_id = $1 AND token = $2")
+            .bind(user_id)
+            .bind(token)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_push_subscriptions(&self, user_id: Uuid) -> Result<Vec<PushSubscription>, sqlx::Error> {
+        sqlx::query_as::<_, PushSubscription>(
+            r#"
+            SELECT id, user_id, token, platform, created_at
+            FROM push_subscriptions
+            WHERE user_id = $1
+            "#
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn create_notification(
+        &self,
+        user_id: Uuid,
+        title: &str,
+        message: &str,
+        notification_type: &str,
+        related_id: Option<Uuid>,
+    ) -> Result<Notification, sqlx::Error> {
+        sqlx::query_as::<_, Notification>(
+            r#"
+            INSERT INTO notifications (user_id, title, message, notification_type, related_id)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, user_id, title, message, notification_type, related_id, is_read, created_at
+            "#
+        )
+        .bind(user_id)
+        .bind(title)
+        .bind(message)
+        .bind(notification_type)
+        .bind(related_id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    async fn count_unread_notifications(&self, user_id: Uuid) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM notifications
+            WHERE user_id = $1 AND is_read = false
+            "#
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    async fn get_notifications_since(
+        &self,
+        user_id: Uuid,
+        since: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<Notification>, sqlx::Error> {
+        sqlx::query_as::<_, Notification>(
+            r#"
+            SELECT id, user_id, title, message, notification_type, related_id, is_read, created_at
+            FROM notifications
+            WHERE user_id = $1 AND created_at > $2
+            ORDER BY created_at ASC
+            LIMIT $3
+            "#
+        )
+        .bind(user_id)
+        .bind(since)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
@@ -0,0 +1,423 @@
+// db/offerdb.rs
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    db::db::DBClient,
+    models::offermodels::{OfferBookSummary, OfferStatus, PropertyOffer},
+};
+
+const OFFER_COLUMNS: &str = r#"
+    id, property_id, buyer_id, landlord_id, amount, conditions, expires_at, status,
+    counter_amount, counter_conditions, response_note, agreed_amount, earnest_deposit,
+    deposit_hold_id, accepted_at, completed_at, created_at, updated_at
+"#;
+
+#[async_trait]
+pub trait OfferExt {
+    async fn create_offer(
+        &self,
+        buyer_id: Uuid,
+        landlord_id: Uuid,
+        property_id: Uuid,
+        amount: i64,
+        conditions: Option<&str>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<PropertyOffer, sqlx::Error>;
+
+    async fn get_offer(
+        &self,
+        offer_id: Uuid,
+    ) -> Result<Option<PropertyOffer>, sqlx::Error>;
+
+    async fn get_offers_for_buyer(
+        &self,
+        buyer_id: Uuid,
+        page: u32,
+        limit: usize,
+    ) -> Result<Vec<PropertyOffer>, sqlx::Error>;
+
+    /// Every offer on the listing, open ones first and highest first
+    async fn get_offers_for_property(
+        &self,
+        property_id: Uuid,
+    ) -> Result<Vec<PropertyOffer>, sqlx::Error>;
+
+    async fn get_offer_book_summary(
+        &self,
+        property_id: Uuid,
+    ) -> Result<OfferBookSummary, sqlx::Error>;
+
+    async fn get_accepted_offer(
+        &self,
+        property_id: Uuid,
+    ) -> Result<Option<PropertyOffer>, sqlx::Error>;
+
+    /// The landlord's counter. None if the offer is no longer pending.
+    async fn counter_offer(
+        &self,
+        offer_id: Uuid,
+        amount: i64,
+        conditions: Option<&str>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Option<PropertyOffer>, sqlx::Error>;
+
+    /// Records the agreed price and the earnest deposit hold. None if the offer
+    /// is no longer in `from`.
+    async fn accept_offer(
+        &self,
+        offer_id: Uuid,
+        from: OfferStatus,
+        agreed_amount: i64,
+        earnest_deposit: i64,
+        deposit_hold_id: Option<Uuid>,
+    ) -> Result<Option<PropertyOffer>, sqlx::Error>;
+
+    /// Compare-and-set on the status column. None if the offer is no longer in `from`.
+    async fn transition_offer_status(
+        &self,
+        offer_id: Uuid,
+        from: OfferStatus,
+        to: OfferStatus,
+        note: Option<&str>,
+    ) -> Result<Option<PropertyOffer>, sqlx::Error>;
+
+    /// `transition_offer_status` inside the caller's transaction, so the
+    /// deposit can settle with it
+    async fn transition_offer_status_tx(
+        &self,
+        offer_id: Uuid,
+        from: OfferStatus,
+        to: OfferStatus,
+        note: Option<&str>,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<Option<PropertyOffer>, sqlx::Error>;
+
+    /// Rejects every open offer on the property once it has gone
+    async fn reject_open_offers(
+        &self,
+        property_id: Uuid,
+        note: &str,
+    ) -> Result<Vec<PropertyOffer>, sqlx::Error>;
+
+    /// Expires open offers past their deadline
+    async fn expire_offers(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<PropertyOffer>, sqlx::Error>;
+
+    /// Sets the listing's bidding price to the best offer still standing
+    async fn refresh_bidding_price(
+        &self,
+        property_id: Uuid,
+    ) -> Result<(), sqlx::Error>;
+}
+
+#[async_trait]
+impl OfferExt for DBClient {
+    async fn create_offer(
+        &self,
+        buyer_id: Uuid,
+        landlord_id: Uuid,
+        property_id: Uuid,
+        amount: i64,
+        conditions: Option<&str>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<PropertyOffer, sqlx::Error> {
+        let query = format!(
+            r#"
+            INSERT INTO property_offers (
+                property_id, buyer_id, landlord_id, amount, conditions, expires_at, status
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING {}
+            "#,
+            OFFER_COLUMNS
+        );
+
+        sqlx::query_as::<_, PropertyOffer>(&query)
+            .bind(property_id)
+            .bind(buyer_id)
+            .bind(landlord_id)
+            .bind(amount)
+            .bind(conditions)
+            .bind(expires_at)
+            .bind(OfferStatus::Pending)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    async fn get_offer(
+        &self,
+        offer_id: Uuid,
+    ) -> Result<Option<PropertyOffer>, sqlx::Error> {
+        let query = format!("SELECT {} FROM property_offers WHERE id = $1", OFFER_COLUMNS);
+
+        sqlx::query_as::<_, PropertyOffer>(&query)
+            .bind(offer_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn get_offers_for_buyer(
+        &self,
+        buyer_id: Uuid,
+        page: u32,
+        limit: usize,
+    ) -> Result<Vec<PropertyOffer>, sqlx::Error> {
+        let offset = (page.saturating_sub(1) as i64) * limit as i64;
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM property_offers
+            WHERE buyer_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+            OFFER_COLUMNS
+        );
+
+        sqlx::query_as::<_, PropertyOffer>(&query)
+            .bind(buyer_id)
+            .bind(limit as i64)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn get_offers_for_property(
+        &self,
+        property_id: Uuid,
+    ) -> Result<Vec<PropertyOffer>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM property_offers
+            WHERE property_id = $1
+            ORDER BY status IN ('pending', 'countered', 'accepted') DESC, amount DESC, created_at
+            "#,
+            OFFER_COLUMNS
+        );
+
+        sqlx::query_as::<_, PropertyOffer>(&query)
+            .bind(property_id)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn get_offer_book_summary(
+        &self,
+        property_id: Uuid,
+    ) -> Result<OfferBookSummary, sqlx::Error> {
+        sqlx::query_as::<_, OfferBookSummary>(
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE status IN ('pending', 'countered')) AS open_offers,
+                MAX(amount) FILTER (WHERE status IN ('pending', 'countered')) AS highest_offer,
+                MIN(amount) FILTER (WHERE status IN ('pending', 'countered')) AS lowest_offer,
+                AVG(amount) FILTER (WHERE status IN ('pending', 'countered'))::BIGINT AS average_offer,
+                (ARRAY_AGG(id) FILTER (WHERE status = 'accepted'))[1] AS accepted_offer_id
+            FROM property_offers
+            WHERE property_id = $1
+            "#
+        )
+        .bind(property_id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    async fn get_accepted_offer(
+        &self,
+        property_id: Uuid,
+    ) -> Result<Option<PropertyOffer>, sqlx::Error> {
+        let query = format!(
+            "SELECT {} FROM property_offers WHERE property_id = $1 AND status = $2",
+            OFFER_COLUMNS
+        );
+
+        sqlx::query_as::<_, PropertyOffer>(&query)
+            .bind(property_id)
+            .bind(OfferStatus::Accepted)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn counter_offer(
+        &self,
+        offer_id: Uuid,
+        amount: i64,
+        conditions: Option<&str>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Option<PropertyOffer>, sqlx::Error> {
+        let query = format!(
+            r#"
+            UPDATE property_offers
+            SET status = $1, counter_amount = $2, counter_conditions = $3,
+                expires_at = $4, updated_at = NOW()
+            WHERE id = $5 AND status = $6
+            RETURNING {}
+            "#,
+            OFFER_COLUMNS
+        );
+
+        sqlx::query_as::<_, PropertyOffer>(&query)
+            .bind(OfferStatus::Countered)
+            .bind(amount)
+            .bind(conditions)
+            .bind(expires_at)
+            .bind(offer_id)
+            .bind(OfferStatus::Pending)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn accept_offer(
+        &self,
+        offer_id: Uuid,
+        from: OfferStatus,
+        agreed_amount: i64,
+        earnest_deposit: i64,
+        deposit_hold_id: Option<Uuid>,
+    ) -> Result<Option<PropertyOffer>, sqlx::Error> {
+        let query = format!(
+            r#"
+            UPDATE property_offers
+            SET status = $1, agreed_amount = $2, earnest_deposit = $3, deposit_hold_id = $4,
+                accepted_at = NOW(), updated_at = NOW()
+            WHERE id = $5 AND status = $6
+            RETURNING {}
+            "#,
+            OFFER_COLUMNS
+        );
+
+        sqlx::query_as::<_, PropertyOffer>(&query)
+            .bind(OfferStatus::Accepted)
+            .bind(agreed_amount)
+            .bind(earnest_deposit)
+            .bind(deposit_hold_id)
+            .bind(offer_id)
+            .bind(from)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn transition_offer_status(
+        &self,
+        offer_id: Uuid,
+        from: OfferStatus,
+        to: OfferStatus,
+        note: Option<&str>,
+    ) -> Result<Option<PropertyOffer>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let offer = self.transition_offer_status_tx(offer_id, from, to, note, &mut tx).await?;
+        tx.commit().await?;
+        Ok(offer)
+    }
+
+    async fn transition_offer_status_tx(
+        &self,
+        offer_id: Uuid,
+        from: OfferStatus,
+        to: OfferStatus,
+        note: Option<&str>,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<Option<PropertyOffer>, sqlx::Error> {
+        let query = format!(
+            r#"
+            UPDATE property_offers
+            SET status = $1,
+                response_note = COALESCE($2, response_note),
+                completed_at = CASE WHEN $1 = 'completed'::offer_status THEN NOW() ELSE completed_at END,
+                updated_at = NOW()
+            WHERE id = $3 AND status = $4
+            RETURNING {}
+            "#,
+            OFFER_COLUMNS
+        );
+
+        sqlx::query_as::<_, PropertyOffer>(&query)
+            .bind(to)
+            .bind(note)
+            .bind(offer_id)
+            .bind(from)
+            .fetch_optional(&mut **tx)
+            .await
+    }
+
+    async fn reject_open_offers(
+        &self,
+        property_id: Uuid,
+        note: &str,
+    ) -> Result<Vec<PropertyOffer>, sqlx::Error> {
+        let query = format!(
+            r#"
+            UPDATE property_offers
+            SET status = $1, response_note = $2, updated_at = NOW()
+            WHERE property_id = $3 AND status IN ($4, $5)
+            RETURNING {}
+            "#,
+            OFFER_COLUMNS
+        );
+
+        sqlx::query_as::<_, PropertyOffer>(&query)
+            .bind(OfferStatus::Rejected)
+            .bind(note)
+            .bind(property_id)
+            .bind(OfferStatus::Pending)
+            .bind(OfferStatus::Countered)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn expire_offers(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<PropertyOffer>, sqlx::Error> {
+        let query = format!(
+            r#"
+            UPDATE property_offers
+            SET status = $1, updated_at = NOW()
+            WHERE id IN (
+                SELECT id FROM property_offers
+                WHERE status IN ($2, $3) AND expires_at < NOW()
+                ORDER BY expires_at
+                LIMIT $4
+            )
+            AND status IN ($2, $3)
+            RETURNING {}
+            "#,
+            OFFER_COLUMNS
+        );
+
+        sqlx::query_as::<_, PropertyOffer>(&query)
+            .bind(OfferStatus::Expired)
+            .bind(OfferStatus::Pending)
+            .bind(OfferStatus::Countered)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn refresh_bidding_price(
+        &self,
+        property_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE properties
+            SET bidding_price = (
+                SELECT MAX(COALESCE(agreed_amount, amount))
+                FROM property_offers
+                WHERE property_id = $1 AND status IN ('pending', 'countered', 'accepted')
+            ),
+            updated_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(property_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
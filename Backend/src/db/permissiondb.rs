@@ -0,0 +1,203 @@
+// db/permissiondb.rs
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    db::db::DBClient,
+    models::{
+        permissionmodels::{RolePermissions, UserAccess, UserRoleGrant},
+        usermodel::UserRole,
+    },
+};
+
+/// Every role the user holds: the primary role on `users` plus `user_roles`
+const HELD_ROLES: &str = r#"
+    SELECT role FROM users WHERE id = $1
+    UNION
+    SELECT role FROM user_roles WHERE user_id = $1
+"#;
+
+#[async_trait]
+pub trait PermissionExt {
+    async fn get_user_access(
+        &self,
+        user_id: Uuid,
+    ) -> Result<UserAccess, sqlx::Error>;
+
+    /// The primary role first, then the others by when they were granted
+    async fn get_user_role_grants(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<UserRoleGrant>, sqlx::Error>;
+
+    async fn user_has_role(
+        &self,
+        user_id: Uuid,
+        role: UserRole,
+    ) -> Result<bool, sqlx::Error>;
+
+    /// False if the user already held the role
+    async fn grant_user_role(
+        &self,
+        user_id: Uuid,
+        role: UserRole,
+        granted_by: Option<Uuid>,
+    ) -> Result<bool, sqlx::Error>;
+
+    /// False if the user did not hold the role. Revoking the primary role
+    /// promotes another held role, or falls back to `user`.
+    async fn revoke_user_role(
+        &self,
+        user_id: Uuid,
+        role: UserRole,
+    ) -> Result<bool, sqlx::Error>;
+
+    async fn get_role_permissions(&self) -> Result<Vec<RolePermissions>, sqlx::Error>;
+}
+
+#[async_trait]
+impl PermissionExt for DBClient {
+    async fn get_user_access(
+        &self,
+        user_id: Uuid,
+    ) -> Result<UserAccess, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT held.role, rp.permission
+            FROM ({}) held
+            LEFT JOIN role_permissions rp ON rp.role = held.role
+            "#,
+            HELD_ROLES
+        );
+
+        let rows = sqlx::query_as::<_, (UserRole, Option<String>)>(&query)
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut access = UserAccess::default();
+        for (role, permission) in rows {
+            if !access.roles.contains(&role) {
+                access.roles.push(role);
+            }
+            access.permissions.extend(permission);
+        }
+
+        Ok(access)
+    }
+
+    async fn get_user_role_grants(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<UserRoleGrant>, sqlx::Error> {
+        sqlx::query_as::<_, UserRoleGrant>(
+            r#"
+            SELECT u.id AS user_id, u.role, ur.granted_by, COALESCE(ur.granted_at, u.created_at) AS granted_at,
+                TRUE AS is_primary
+            FROM users u
+            LEFT JOIN user_roles ur ON ur.user_id = u.id AND ur.role = u.role
+            WHERE u.id = $1
+            UNION ALL
+            SELECT ur.user_id, ur.role, ur.granted_by, ur.granted_at, FALSE AS is_primary
+            FROM user_roles ur
+            JOIN users u ON u.id = ur.user_id
+            WHERE ur.user_id = $1 AND ur.role <> u.role
+            ORDER BY is_primary DESC, granted_at
+            "#
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn user_has_role(
+        &self,
+        user_id: Uuid,
+        role: UserRole,
+    ) -> Result<bool, sqlx::Error> {
+        let query = format!("SELECT EXISTS (SELECT 1 FROM ({}) held WHERE held.role = $2)", HELD_ROLES);
+
+        sqlx::query_scalar::<_, bool>(&query)
+            .bind(user_id)
+            .bind(role)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    async fn grant_user_role(
+        &self,
+        user_id: Uuid,
+        role: UserRole,
+        granted_by: Option<Uuid>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO user_roles (user_id, role, granted_by)
+            SELECT $1, $2, $3
+            WHERE NOT EXISTS (SELECT 1 FROM users WHERE id = $1 AND role = $2)
+            ON CONFLICT (user_id, role) DO NOTHING
+            "#
+        )
+        .bind(user_id)
+        .bind(role)
+        .bind(granted_by)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn revoke_user_role(
+        &self,
+        user_id: Uuid,
+        role: UserRole,
+    ) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let removed = sqlx::query("DELETE FROM user_roles WHERE user_id = $1 AND role = $2")
+            .bind(user_id)
+            .bind(role)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected() > 0;
+
+        // The primary role lives on users, so it is replaced rather than deleted
+        let replaced = sqlx::query(
+            r#"
+            UPDATE users
+            SET role = COALESCE(
+                    (SELECT ur.role FROM user_roles ur WHERE ur.user_id = $1 ORDER BY ur.granted_at LIMIT 1),
+                    'user'
+                ),
+                updated_at = NOW()
+            WHERE id = $1 AND role = $2 AND role <> 'user'
+            "#
+        )
+        .bind(user_id)
+        .bind(role)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected() > 0;
+
+        tx.commit().await?;
+        Ok(removed || replaced)
+    }
+
+    async fn get_role_permissions(&self) -> Result<Vec<RolePermissions>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, (UserRole, Vec<String>)>(
+            r#"
+            SELECT role, ARRAY_AGG(permission ORDER BY permission)
+            FROM role_permissions
+            GROUP BY role
+            ORDER BY role
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(role, permissions)| RolePermissions { role, permissions })
+            .collect())
+    }
+}
@@ -0,0 +1,202 @@
+// db/phonedb.rs
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    db::db::DBClient,
+    models::verificationmodels::{OtpChannel, OtpPurpose, OtpRecord, PhoneStatus},
+};
+
+const OTP_COLUMNS: &str = r#"
+    id, user_id, email, phone_number, otp_code, purpose, expires_at, used, created_at
+"#;
+
+#[async_trait]
+pub trait PhoneExt {
+    async fn get_phone_status(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Option<PhoneStatus>, sqlx::Error>;
+
+    /// The account this number is verified on, if any
+    async fn get_verified_phone_owner(
+        &self,
+        phone_number: &str,
+    ) -> Result<Option<Uuid>, sqlx::Error>;
+
+    async fn set_verified_phone(
+        &self,
+        user_id: Uuid,
+        phone_number: &str,
+    ) -> Result<(), sqlx::Error>;
+
+    async fn set_otp_channel(
+        &self,
+        user_id: Uuid,
+        channel: OtpChannel,
+    ) -> Result<(), sqlx::Error>;
+
+    /// A code sent by SMS. `email` is the account's, so the code can also be
+    /// checked wherever emailed codes are.
+    async fn create_phone_otp(
+        &self,
+        user_id: Uuid,
+        email: &str,
+        phone_number: &str,
+        otp_code: &str,
+        purpose: OtpPurpose,
+        expires_at: DateTime<Utc>,
+    ) -> Result<OtpRecord, sqlx::Error>;
+
+    async fn get_valid_phone_otp(
+        &self,
+        user_id: Uuid,
+        phone_number: &str,
+        otp_code: &str,
+        purpose: OtpPurpose,
+    ) -> Result<Option<OtpRecord>, sqlx::Error>;
+
+    /// Codes sent to this number since `since`, for any account
+    async fn count_phone_otps_since(
+        &self,
+        phone_number: &str,
+        since: DateTime<Utc>,
+    ) -> Result<i64, sqlx::Error>;
+}
+
+#[async_trait]
+impl PhoneExt for DBClient {
+    async fn get_phone_status(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Option<PhoneStatus>, sqlx::Error> {
+        sqlx::query_as::<_, PhoneStatus>(
+            "SELECT phone_number, phone_verified_at, otp_channel FROM users WHERE id = $1"
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn get_verified_phone_owner(
+        &self,
+        phone_number: &str,
+    ) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar::<_, Uuid>(
+            "SELECT id FROM users WHERE phone_number = $1 AND phone_verified_at IS NOT NULL"
+        )
+        .bind(phone_number)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn set_verified_phone(
+        &self,
+        user_id: Uuid,
+        phone_number: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET phone_number = $2, phone_verified_at = NOW(), updated_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(user_id)
+        .bind(phone_number)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn set_otp_channel(
+        &self,
+        user_id: Uuid,
+        channel: OtpChannel,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE users SET otp_channel = $2, updated_at = NOW() WHERE id = $1")
+            .bind(user_id)
+            .bind(channel)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn create_phone_otp(
+        &self,
+        user_id: Uuid,
+        email: &str,
+        phone_number: &str,
+        otp_code: &str,
+        purpose: OtpPurpose,
+        expires_at: DateTime<Utc>,
+    ) -> Result<OtpRecord, sqlx::Error> {
+        let query = format!(
+            r#"
+            INSERT INTO otp_codes (user_id, email, phone_number, otp_code, purpose, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING {}
+            "#,
+            OTP_COLUMNS
+        );
+
+        sqlx::query_as::<_, OtpRecord>(&query)
+            .bind(user_id)
+            .bind(email)
+            .bind(phone_number)
+            .bind(otp_code)
+            .bind(purpose)
+            .bind(expires_at)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    async fn get_valid_phone_otp(
+        &self,
+        user_id: Uuid,
+        phone_number: &str,
+        otp_code: &str,
+        purpose: OtpPurpose,
+    ) -> Result<Option<OtpRecord>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM otp_codes
+            WHERE user_id = $1
+                AND phone_number = $2
+                AND otp_code = $3
+                AND purpose = $4
+                AND used = false
+                AND expires_at > NOW()
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+            OTP_COLUMNS
+        );
+
+        sqlx::query_as::<_, OtpRecord>(&query)
+            .bind(user_id)
+            .bind(phone_number)
+            .bind(otp_code)
+            .bind(purpose)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn count_phone_otps_since(
+        &self,
+        phone_number: &str,
+        since: DateTime<Utc>,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM otp_codes WHERE phone_number = $1 AND created_at > $2"
+        )
+        .bind(phone_number)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await
+    }
+}
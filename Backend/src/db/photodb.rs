@@ -0,0 +1,262 @@
+// db/photodb.rs
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    db::db::DBClient,
+    models::propertymodel::{FlaggedListing, PhotoMatch, PhotoSearchHit, PropertyPhotoHash, PropertyStatus},
+};
+
+const PHOTO_HASH_COLUMNS: &str = r#"
+    id, property_id, photo_url, content_hash, phash, error, created_at
+"#;
+
+/// Number of differing bits between `phash` and `$1`. Plain text functions so
+/// it runs on any Postgres version.
+const HAMMING_DISTANCE: &str = "length(replace(((h.phash # $1)::bit(64))::text, '0', ''))";
+
+#[async_trait]
+pub trait PhotoIndexExt {
+    /// Submitted listings whose photos have never been indexed, oldest first
+    async fn get_properties_needing_photo_index(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<Uuid>, sqlx::Error>;
+
+    async fn save_photo_hash(
+        &self,
+        property_id: Uuid,
+        photo_url: &str,
+        content_hash: Option<&str>,
+        phash: Option<i64>,
+        error: Option<&str>,
+    ) -> Result<PropertyPhotoHash, sqlx::Error>;
+
+    /// Indexed photos within `max_distance` bits of `phash`, or with the same
+    /// file contents, closest first
+    async fn find_similar_photos(
+        &self,
+        phash: i64,
+        content_hash: Option<&str>,
+        max_distance: i32,
+        exclude_property_id: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<PhotoSearchHit>, sqlx::Error>;
+
+    /// Records a match once. False if it was already known.
+    async fn record_photo_match(
+        &self,
+        property_id: Uuid,
+        photo_url: &str,
+        matched_property_id: Uuid,
+        matched_photo_url: &str,
+        distance: i32,
+        different_landlord: bool,
+    ) -> Result<bool, sqlx::Error>;
+
+    async fn get_photo_matches(
+        &self,
+        property_id: Uuid,
+    ) -> Result<Vec<PhotoMatch>, sqlx::Error>;
+
+    /// (photos indexed, photos that could not be read)
+    async fn get_photo_index_counts(
+        &self,
+        property_id: Uuid,
+    ) -> Result<(i64, i64), sqlx::Error>;
+
+    /// Listings still in review whose photos match another landlord's listing
+    async fn get_flagged_listings(
+        &self,
+        page: u32,
+        limit: usize,
+    ) -> Result<Vec<FlaggedListing>, sqlx::Error>;
+}
+
+#[async_trait]
+impl PhotoIndexExt for DBClient {
+    async fn get_properties_needing_photo_index(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT p.id
+            FROM properties p
+            WHERE p.status <> $1
+              AND jsonb_typeof(p.property_photos) = 'array'
+              AND jsonb_array_length(p.property_photos) > 0
+              AND NOT EXISTS (SELECT 1 FROM property_photo_hashes h WHERE h.property_id = p.id)
+            ORDER BY p.created_at
+            LIMIT $2
+            "#
+        )
+        .bind(PropertyStatus::Draft)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn save_photo_hash(
+        &self,
+        property_id: Uuid,
+        photo_url: &str,
+        content_hash: Option<&str>,
+        phash: Option<i64>,
+        error: Option<&str>,
+    ) -> Result<PropertyPhotoHash, sqlx::Error> {
+        let query = format!(
+            r#"
+            INSERT INTO property_photo_hashes (property_id, photo_url, content_hash, phash, error)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (property_id, photo_url) DO UPDATE
+            SET content_hash = EXCLUDED.content_hash, phash = EXCLUDED.phash, error = EXCLUDED.error
+            RETURNING {}
+            "#,
+            PHOTO_HASH_COLUMNS
+        );
+
+        sqlx::query_as::<_, PropertyPhotoHash>(&query)
+            .bind(property_id)
+            .bind(photo_url)
+            .bind(content_hash)
+            .bind(phash)
+            .bind(error)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    async fn find_similar_photos(
+        &self,
+        phash: i64,
+        content_hash: Option<&str>,
+        max_distance: i32,
+        exclude_property_id: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<PhotoSearchHit>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT property_id, photo_url, title, status, landlord_id, distance,
+                   1.0 - distance / 64.0::DOUBLE PRECISION AS similarity
+            FROM (
+                SELECT h.property_id, h.photo_url, p.title, p.status, p.landlord_id,
+                       CASE WHEN h.content_hash = $2 THEN 0 ELSE {} END AS distance
+                FROM property_photo_hashes h
+                JOIN properties p ON p.id = h.property_id
+                WHERE h.phash IS NOT NULL
+                  AND ($4::UUID IS NULL OR h.property_id <> $4)
+            ) candidates
+            WHERE distance <= $3
+            ORDER BY distance, property_id
+            LIMIT $5
+            "#,
+            HAMMING_DISTANCE
+        );
+
+        sqlx::query_as::<_, PhotoSearchHit>(&query)
+            .bind(phash)
+            .bind(content_hash)
+            .bind(max_distance)
+            .bind(exclude_property_id)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn record_photo_match(
+        &self,
+        property_id: Uuid,
+        photo_url: &str,
+        matched_property_id: Uuid,
+        matched_photo_url: &str,
+        distance: i32,
+        different_landlord: bool,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO property_photo_matches (
+                property_id, photo_url, matched_property_id, matched_photo_url, distance, different_landlord
+            ) VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (property_id, photo_url, matched_photo_url) DO NOTHING
+            "#
+        )
+        .bind(property_id)
+        .bind(photo_url)
+        .bind(matched_property_id)
+        .bind(matched_photo_url)
+        .bind(distance)
+        .bind(different_landlord)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_photo_matches(
+        &self,
+        property_id: Uuid,
+    ) -> Result<Vec<PhotoMatch>, sqlx::Error> {
+        sqlx::query_as::<_, PhotoMatch>(
+            r#"
+            SELECT m.photo_url, m.matched_property_id, m.matched_photo_url,
+                   p.title AS matched_title, p.status AS matched_status, p.landlord_id AS matched_landlord_id,
+                   m.distance, 1.0 - m.distance / 64.0::DOUBLE PRECISION AS similarity,
+                   m.different_landlord, m.created_at
+            FROM property_photo_matches m
+            JOIN properties p ON p.id = m.matched_property_id
+            WHERE m.property_id = $1
+            ORDER BY m.different_landlord DESC, m.distance, m.created_at
+            "#
+        )
+        .bind(property_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn get_photo_index_counts(
+        &self,
+        property_id: Uuid,
+    ) -> Result<(i64, i64), sqlx::Error> {
+        sqlx::query_as::<_, (i64, i64)>(
+            r#"
+            SELECT COUNT(*), COUNT(*) FILTER (WHERE phash IS NULL)
+            FROM property_photo_hashes
+            WHERE property_id = $1
+            "#
+        )
+        .bind(property_id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    async fn get_flagged_listings(
+        &self,
+        page: u32,
+        limit: usize,
+    ) -> Result<Vec<FlaggedListing>, sqlx::Error> {
+        let offset = (page.saturating_sub(1) as i64) * limit as i64;
+
+        sqlx::query_as::<_, FlaggedListing>(
+            r#"
+            SELECT p.id AS property_id, p.title, p.status, p.landlord_id, p.agent_id,
+                   COUNT(DISTINCT m.matched_property_id) AS matched_listings,
+                   MIN(m.distance) AS closest_distance,
+                   MIN(m.created_at) AS first_flagged_at
+            FROM property_photo_matches m
+            JOIN properties p ON p.id = m.property_id
+            WHERE m.different_landlord AND p.status IN ($1, $2, $3, $4)
+            GROUP BY p.id
+            ORDER BY MIN(m.distance), MIN(m.created_at)
+            LIMIT $5 OFFSET $6
+            "#
+        )
+        .bind(PropertyStatus::AwaitingAgent)
+        .bind(PropertyStatus::AgentVerified)
+        .bind(PropertyStatus::AwaitingLawyer)
+        .bind(PropertyStatus::LawyerVerified)
+        .bind(limit as i64)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
@@ -0,0 +1,292 @@
+// db/privacydb.rs
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    db::db::DBClient,
+    models::privacymodels::DeletionBlockers,
+};
+
+/// One file in a data export: the rows of `table` matching `filter`, where
+/// `$1` is the user's id
+pub struct ExportSection {
+    pub file: &'static str,
+    pub table: &'static str,
+    pub filter: &'static str,
+}
+
+/// Everything a data export holds, by folder in the archive
+pub const EXPORT_SECTIONS: &[ExportSection] = &[
+    ExportSection { file: "profile/account.json", table: "users", filter: "id = $1" },
+    ExportSection { file: "profile/roles.json", table: "user_roles", filter: "user_id = $1" },
+    ExportSection { file: "profile/sessions.json", table: "user_sessions", filter: "user_id = $1" },
+    ExportSection { file: "profile/login_history.json", table: "login_events", filter: "user_id = $1" },
+    ExportSection { file: "profile/notification_settings.json", table: "notification_preferences", filter: "user_id = $1" },
+    ExportSection { file: "profile/notifications.json", table: "notifications", filter: "user_id = $1" },
+    ExportSection { file: "profile/saved_searches.json", table: "saved_searches", filter: "user_id = $1" },
+    ExportSection { file: "wallet/wallet.json", table: "naira_wallets", filter: "user_id = $1" },
+    ExportSection { file: "wallet/transactions.json", table: "wallet_transactions", filter: "user_id = $1" },
+    ExportSection { file: "wallet/bank_accounts.json", table: "bank_accounts", filter: "user_id = $1" },
+    ExportSection { file: "jobs/worker_profile.json", table: "worker_profiles", filter: "user_id = $1" },
+    ExportSection {
+        file: "jobs/portfolio.json",
+        table: "worker_portfolios",
+        filter: "worker_id IN (SELECT id FROM worker_profiles WHERE user_id = $1)",
+    },
+    ExportSection {
+        file: "jobs/jobs.json",
+        table: "jobs",
+        filter: "employer_id = $1 OR assigned_worker_id IN (SELECT id FROM worker_profiles WHERE user_id = $1)",
+    },
+    ExportSection {
+        file: "jobs/applications.json",
+        table: "job_applications",
+        filter: "worker_id IN (SELECT id FROM worker_profiles WHERE user_id = $1)",
+    },
+    ExportSection { file: "jobs/contracts.json", table: "job_contracts", filter: "employer_id = $1 OR worker_id = $1" },
+    ExportSection { file: "jobs/escrows.json", table: "escrow_transactions", filter: "employer_id = $1 OR worker_id = $1" },
+    ExportSection { file: "jobs/disputes.json", table: "disputes", filter: "raised_by = $1 OR against = $1" },
+    ExportSection { file: "reviews/job_reviews.json", table: "job_reviews", filter: "reviewer_id = $1 OR reviewee_id = $1" },
+    ExportSection {
+        file: "reviews/service_reviews.json",
+        table: "service_reviews",
+        filter: "reviewer_id = $1 OR vendor_id IN (SELECT id FROM vendor_profiles WHERE user_id = $1)",
+    },
+    ExportSection { file: "chats/chats.json", table: "chats", filter: "participant_one_id = $1 OR participant_two_id = $1" },
+    ExportSection {
+        file: "chats/messages.json",
+        table: "messages",
+        filter: "chat_id IN (SELECT id FROM chats WHERE participant_one_id = $1 OR participant_two_id = $1)",
+    },
+    ExportSection { file: "marketplace/vendor_profile.json", table: "vendor_profiles", filter: "user_id = $1" },
+    ExportSection {
+        file: "marketplace/services.json",
+        table: "vendor_services",
+        filter: "vendor_id IN (SELECT id FROM vendor_profiles WHERE user_id = $1)",
+    },
+    ExportSection {
+        file: "marketplace/orders.json",
+        table: "service_orders",
+        filter: "buyer_id = $1 OR vendor_id IN (SELECT id FROM vendor_profiles WHERE user_id = $1)",
+    },
+    ExportSection { file: "marketplace/disputes.json", table: "service_disputes", filter: "raised_by = $1 OR against = $1" },
+    ExportSection { file: "properties/listings.json", table: "properties", filter: "landlord_id = $1" },
+    ExportSection { file: "properties/tenancies.json", table: "tenancies", filter: "tenant_id = $1 OR landlord_id = $1" },
+    ExportSection { file: "properties/offers.json", table: "property_offers", filter: "buyer_id = $1 OR landlord_id = $1" },
+    ExportSection { file: "properties/viewings.json", table: "property_viewings", filter: "requester_id = $1" },
+    ExportSection { file: "verification/documents.json", table: "verification_documents", filter: "user_id = $1" },
+    ExportSection {
+        file: "verification/automated_checks.json",
+        table: "kyc_checks",
+        filter: "verification_id IN (SELECT id FROM verification_documents WHERE user_id = $1)",
+    },
+    ExportSection { file: "verification/identity_checks.json", table: "identity_checks", filter: "user_id = $1" },
+    ExportSection { file: "verification/address_proofs.json", table: "address_proofs", filter: "user_id = $1" },
+    ExportSection { file: "support/tickets.json", table: "support_tickets", filter: "user_id = $1" },
+    ExportSection {
+        file: "support/messages.json",
+        table: "support_messages",
+        filter: "NOT is_internal AND ticket_id IN (SELECT id FROM support_tickets WHERE user_id = $1)",
+    },
+];
+
+/// Shown in place of a deleted user's chat messages
+pub const DELETED_MESSAGE: &str = "[message deleted]";
+
+#[async_trait]
+pub trait PrivacyExt {
+    /// The section's rows as a JSON array
+    async fn export_section(
+        &self,
+        section: &ExportSection,
+        user_id: Uuid,
+    ) -> Result<serde_json::Value, sqlx::Error>;
+
+    async fn get_deletion_blockers(
+        &self,
+        user_id: Uuid,
+    ) -> Result<DeletionBlockers, sqlx::Error>;
+
+    /// Strips everything that identifies the user in one transaction and ends
+    /// their sessions. Wallet, escrow and order records, reviews and KYC
+    /// submissions stay, pointing at the anonymised account.
+    async fn anonymise_user(
+        &self,
+        user_id: Uuid,
+        session_revoked_reason: &str,
+    ) -> Result<(), sqlx::Error>;
+}
+
+#[async_trait]
+impl PrivacyExt for DBClient {
+    async fn export_section(
+        &self,
+        section: &ExportSection,
+        user_id: Uuid,
+    ) -> Result<serde_json::Value, sqlx::Error> {
+        // Table and filter come from EXPORT_SECTIONS, never from a request
+        let query = format!(
+            "SELECT COALESCE(json_agg(row_to_json(t)), '[]'::json) FROM (SELECT * FROM {} WHERE {}) t",
+            section.table, section.filter
+        );
+
+        sqlx::query_scalar::<_, serde_json::Value>(&query)
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    async fn get_deletion_blockers(
+        &self,
+        user_id: Uuid,
+    ) -> Result<DeletionBlockers, sqlx::Error> {
+        sqlx::query_as::<_, DeletionBlockers>(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM escrow_transactions
+                    WHERE (employer_id = $1 OR worker_id = $1)
+                    AND status::text IN ('pending', 'escrowed', 'funded', 'partially_paid')) AS open_escrows,
+                (SELECT COUNT(*) FROM service_orders
+                    WHERE (buyer_id = $1 OR vendor_id IN (SELECT id FROM vendor_profiles WHERE user_id = $1))
+                    AND status::text IN ('paid', 'confirmed', 'processing', 'shipped', 'in_transit', 'delivered', 'disputed')) AS open_orders,
+                (SELECT COALESCE(SUM(balance), 0)::BIGINT FROM naira_wallets WHERE user_id = $1) AS wallet_balance,
+                (SELECT COUNT(*) FROM disputes
+                    WHERE (raised_by = $1 OR against = $1)
+                    AND status::text IN ('open', 'under_review', 'escalated'))
+                + (SELECT COUNT(*) FROM service_disputes
+                    WHERE (raised_by = $1 OR against = $1)
+                    AND status IN ('open', 'under_review')) AS active_disputes,
+                (SELECT COUNT(*) FROM tenancies
+                    WHERE (tenant_id = $1 OR landlord_id = $1)
+                    AND status::text IN ('active', 'move_out')) AS running_tenancies,
+                (SELECT COUNT(*) FROM property_offers
+                    WHERE (buyer_id = $1 OR landlord_id = $1)
+                    AND status::text = 'accepted') AS accepted_offers,
+                (SELECT COUNT(*) FROM property_viewings
+                    WHERE (requester_id = $1 OR host_id = $1
+                        OR property_id IN (SELECT id FROM properties WHERE landlord_id = $1 OR agent_id = $1))
+                    AND status::text IN ('requested', 'confirmed')) AS upcoming_viewings
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    async fn anonymise_user(
+        &self,
+        user_id: Uuid,
+        session_revoked_reason: &str,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        // Email and username stay unique, and no longer reach anyone
+        sqlx::query(
+            r#"
+            UPDATE users SET
+                name = 'Deleted user',
+                username = 'deleted_' || replace(id::text, '-', ''),
+                email = 'deleted_' || replace(id::text, '-', '') || '@deleted.invalid',
+                password = NULL,
+                verification_token = NULL,
+                token_expires_at = NULL,
+                google_id = NULL,
+                avatar_url = NULL,
+                wallet_address = NULL,
+                phone_number = NULL,
+                phone_verified_at = NULL,
+                otp_channel = 'email',
+                nin_number = NULL,
+                verification_document_id = NULL,
+                facial_verification_id = NULL,
+                nearest_landmark = NULL,
+                verification_number = NULL,
+                nationality = NULL,
+                dob = NULL,
+                lga = NULL,
+                transaction_pin = NULL,
+                transaction_pin_hash = NULL,
+                next_of_kin = NULL,
+                referral_code = NULL,
+                deleted_at = NOW(),
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE user_sessions
+            SET revoked_at = NOW(), revoked_reason = $2
+            WHERE user_id = $1 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(session_revoked_reason)
+        .execute(&mut *tx)
+        .await?;
+
+        for table in [
+            "user_two_factor",
+            "two_factor_recovery_codes",
+            "push_subscriptions",
+            "notification_preferences",
+            "notification_settings",
+            "notification_digest_queue",
+            "notifications",
+            "saved_searches",
+            "bank_accounts",
+            "login_events",
+        ] {
+            sqlx::query(&format!("DELETE FROM {} WHERE user_id = $1", table))
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        sqlx::query("DELETE FROM worker_portfolios WHERE worker_id IN (SELECT id FROM worker_profiles WHERE user_id = $1)")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("UPDATE worker_profiles SET description = '', is_available = false, updated_at = NOW() WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        // Listings and services come down; the records of past sales stay
+        sqlx::query("UPDATE properties SET status = 'suspended', updated_at = NOW() WHERE landlord_id = $1 AND status = 'active'")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE vendor_services SET status = 'removed', updated_at = NOW()
+            WHERE vendor_id IN (SELECT id FROM vendor_profiles WHERE user_id = $1)
+            "#,
+        )
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("UPDATE vendor_profiles SET description = NULL, updated_at = NOW() WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        // The other side of each conversation keeps the thread, not the words
+        sqlx::query("UPDATE messages SET content = $2, metadata = NULL WHERE sender_id = $1")
+            .bind(user_id)
+            .bind(DELETED_MESSAGE)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
@@ -1,23 +1,37 @@
-use chrono::Utc;
-use sha2::{Digest, Sha256};
-use uuid::Uuid;
-use sqlx::types::Json;
+// db/propertydb.rs
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use num_traits::ToPrimitive;
-use anyhow;
-
-// Import the correct BigDecimal type that SQLx uses
-use sqlx::types::BigDecimal as SqlxBigDecimal;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::types::{BigDecimal, Json};
+use uuid::Uuid;
 
 use crate::{
-    db::db::DBClient, 
-    dtos::propertydtos::{
-        AgentVerificationDto, CreatePropertyDto, LawyerVerificationDto
-    }, 
-    models::propertymodel::{ListingType, Property, PropertyStatus, CurrencyType, PropertyType, PropertyVerification}
+    db::db::DBClient,
+    dtos::propertydtos::CreatePropertyDto,
+    models::propertymodel::{ListingType, Property, PropertyStatus, PropertyType, PropertyVerification},
 };
 
-#[derive(Debug)]
+pub(crate) const PROPERTY_COLUMNS: &str = r#"
+    id, landlord_id, agent_id, lawyer_id, title, description, property_type, listing_type,
+    address, city, state, lga, country, latitude, longitude, landmark,
+    bedrooms, bathrooms, toilets, size_sqm, plot_size, price,
+    currency, price_negotiable, bidding_price, inspection_fee,
+    amenities, features, certificate_of_occupancy, deed_of_agreement,
+    survey_plan, building_plan_approval, property_photos,
+    agent_verification_photos, agent_verification_notes, lawyer_verification_notes,
+    property_hash, coordinates_hash, status,
+    agent_verified_at, lawyer_verified_at, listed_at,
+    created_at, updated_at
+"#;
+
+/// Stored for listings without coordinates. The unique index on
+/// `coordinates_hash` skips this value, so such listings never collide.
+pub const NO_COORDINATES_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Also stored as a saved search's filters
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PropertySearchFilters {
     pub property_type: Option<PropertyType>,
     pub listing_type: Option<ListingType>,
@@ -28,15 +42,56 @@ pub struct PropertySearchFilters {
     pub country: Option<String>,
     pub bedrooms: Option<i32>,
     pub bathrooms: Option<i32>,
+    /// Only listings that came onto the market after this
+    #[serde(skip)]
+    pub listed_after: Option<DateTime<Utc>>,
+}
+
+/// Identifies a listing by what it is and where, so the same property cannot be listed twice
+pub fn property_hash(property_data: &CreatePropertyDto) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(property_data.address.to_lowercase().as_bytes());
+    hasher.update(property_data.city.to_lowercase().as_bytes());
+    hasher.update(property_data.state.to_lowercase().as_bytes());
+    hasher.update(property_data.lga.to_lowercase().as_bytes());
+    hasher.update(property_data.country.to_lowercase().as_bytes());
+    hasher.update(format!("{:?}", property_data.property_type).as_bytes());
+    hasher.update(format!("{:?}", property_data.listing_type).as_bytes());
+
+    // Includes Size and bedrooms in hash for similar properties
+    if let Some(bedrooms) = property_data.bedrooms {
+        hasher.update(bedrooms.to_string().as_bytes());
+    }
+    if let Some(size) = &property_data.size_sqm {
+        hasher.update(size.to_string().as_bytes());
+    }
+
+    format!("{:x}", hasher.finalize())
+}
+
+pub fn coordinates_hash(lat: Option<&BigDecimal>, lng: Option<&BigDecimal>) -> String {
+    let (Some(latitude), Some(longitude)) = (lat, lng) else {
+        return NO_COORDINATES_HASH.to_string();
+    };
+
+    // Round to ~100m precision to catch very close properties
+    let rounded_lat = (latitude.to_f64().unwrap_or(0.0) * 1000.0).round() / 1000.0;
+    let rounded_lng = (longitude.to_f64().unwrap_or(0.0) * 1000.0).round() / 1000.0;
+
+    let mut hasher = Sha256::new();
+    hasher.update(rounded_lat.to_string().as_bytes());
+    hasher.update(rounded_lng.to_string().as_bytes());
+    format!("{:x}", hasher.finalize())
 }
 
 #[async_trait]
 pub trait PropertyExt {
+    /// Inserts the listing as a draft; submitting it for verification is a status transition
     async fn create_property(
         &self,
         landlord_id: Uuid,
-        property_data: CreatePropertyDto
-    ) -> Result<Property, anyhow::Error>;
+        property_data: &CreatePropertyDto,
+    ) -> Result<Property, sqlx::Error>;
 
     async fn get_property_by_id(
         &self,
@@ -56,22 +111,16 @@ pub trait PropertyExt {
         coordinates_hash: &str,
     ) -> Result<Option<Property>, sqlx::Error>;
 
-    async fn assign_agent_to_property(
-        &self,
-        property_id: Uuid,
-        agent_id: Uuid,
-    ) -> Result<Property, sqlx::Error>;
-
     async fn get_all_properties_within_agent_landmark(
         &self,
-        landmark:  String,
+        landmark: String,
         page: u32,
         limit: usize,
     ) -> Result<Vec<Property>, sqlx::Error>;
 
     async fn get_all_properties_within_lawyer_state(
         &self,
-        state:  String,
+        state: String,
         page: u32,
         limit: usize,
     ) -> Result<Vec<Property>, sqlx::Error>;
@@ -81,41 +130,57 @@ pub trait PropertyExt {
         agent_id: Uuid,
     ) -> Result<Vec<Property>, sqlx::Error>;
 
+    /// Records the agent's review and moves the property out of AwaitingAgent.
+    /// None if it is no longer awaiting this agent.
     async fn agent_verify_property(
         &self,
         agent_id: Uuid,
-        verification_data: AgentVerificationDto
-    ) -> Result<Property, sqlx::Error>;
+        property_id: Uuid,
+        new_status: PropertyStatus,
+        verification_status: &str,
+        notes: &str,
+        verification_photos: &[String],
+    ) -> Result<Option<Property>, sqlx::Error>;
 
     async fn get_properties_for_lawyer_verification(
         &self,
         lawyer_id: Uuid,
     ) -> Result<Vec<Property>, sqlx::Error>;
 
+    /// Records the lawyer's review and moves the property out of AwaitingLawyer.
+    /// None if it is no longer awaiting review or another lawyer holds it.
     async fn lawyer_verify_property(
         &self,
         lawyer_id: Uuid,
-        verification_data: LawyerVerificationDto,
-    ) -> Result<Property, sqlx::Error>;
+        property_id: Uuid,
+        new_status: PropertyStatus,
+        verification_status: &str,
+        notes: &str,
+    ) -> Result<Option<Property>, sqlx::Error>;
 
     async fn get_active_properties(
         &self,
-        filter: PropertySearchFilters,
+        filters: PropertySearchFilters,
         page: u32,
         limit: usize,
     ) -> Result<Vec<Property>, sqlx::Error>;
 
-    async fn update_property_status(
+    /// Compare-and-set on the status column. None if the property is no longer in `from`.
+    async fn transition_property_status(
         &self,
         property_id: Uuid,
-        status: PropertyStatus,
-    ) -> Result<Property, sqlx::Error>;
+        from: PropertyStatus,
+        to: PropertyStatus,
+    ) -> Result<Option<Property>, sqlx::Error>;
 
-    async fn update_property_bidding_price(
+    /// `transition_property_status` inside the caller's transaction
+    async fn transition_property_status_tx(
         &self,
         property_id: Uuid,
-        bidding_price: i64,
-    ) -> Result<Property, sqlx::Error>;
+        from: PropertyStatus,
+        to: PropertyStatus,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<Option<Property>, sqlx::Error>;
 
     async fn get_property_verification_history(
         &self,
@@ -123,334 +188,196 @@ pub trait PropertyExt {
     ) -> Result<Vec<PropertyVerification>, sqlx::Error>;
 }
 
-impl DBClient {
-    fn generate_property_hash(
-        &self,
-        property_data: &CreatePropertyDto
-    ) -> String {
-        let mut hasher = Sha256::new();
-        hasher.update(property_data.address.to_lowercase().as_bytes());
-        hasher.update(property_data.city.to_lowercase().as_bytes());
-        hasher.update(property_data.state.to_lowercase().as_bytes());
-        hasher.update(property_data.lga.to_lowercase().as_bytes());
-        hasher.update(property_data.country.to_lowercase().as_bytes());
-        hasher.update(format!("{:?}", property_data.property_type).as_bytes());
-        hasher.update(format!("{:?}", property_data.listing_type).as_bytes());
-
-        // Includes Size and bedrooms in hash for similar properties
-        if let Some(bedrooms) = property_data.bedrooms {
-            hasher.update(bedrooms.to_string().as_bytes());
-        }
-        if let Some(size) = &property_data.size_sqm {
-            hasher.update(size.to_string().as_bytes());
-        }
-
-        format!("{:x}", hasher.finalize())
-    }
-
-    fn generate_coordinates_hash(
-        &self,
-        lat: Option<SqlxBigDecimal>,
-        lng: Option<SqlxBigDecimal>,
-    ) -> String {
-        if let (Some(latitude), Some(longitude)) = (lat, lng) {
-            // Convert SQLx BigDecimal to f64
-            let lat_f64 = latitude.to_f64().unwrap_or(0.0);
-            let lng_f64 = longitude.to_f64().unwrap_or(0.0);
-            
-            // Round to ~100m precision to catch very close properties
-            let rounded_lat = (lat_f64 * 1000.0).round() / 1000.0;
-            let rounded_lng = (lng_f64 * 1000.0).round() / 1000.0;
-
-            let mut hasher = Sha256::new();
-            hasher.update(rounded_lat.to_string().as_bytes());
-            hasher.update(rounded_lng.to_string().as_bytes());
-            format!("{:x}", hasher.finalize())
-        } else {
-            "no_coordinates".to_string()
-        }
-    }
-}
-
 #[async_trait]
 impl PropertyExt for DBClient {
     async fn create_property(
         &self,
         landlord_id: Uuid,
-        property_data: CreatePropertyDto
-    ) -> Result<Property, anyhow::Error> {
-        let property_hash = self.generate_property_hash(&property_data);
-        let coordinates_hash = self.generate_coordinates_hash(
-            property_data.latitude,
-            property_data.longitude
-        );
-
-        // Check for duplicates
-        if let Some(_existing) = self.check_property_duplicate(&property_hash, &coordinates_hash).await? {
-            return Err(anyhow::anyhow!("Duplicate property listing"));
-        }
-
-        let property = sqlx::query_as!(
-            Property,
+        property_data: &CreatePropertyDto,
+    ) -> Result<Property, sqlx::Error> {
+        let query = format!(
             r#"
-                INSERT INTO properties (
-                    landlord_id, title, description, property_type, listing_type, address, city, state, lga, country, latitude, longitude, landmark,
-                    bedrooms, bathrooms, toilets, size_sqm, plot_size, price, currency, price_negotiable, amenities, features, certificate_of_occupancy, deed_of_agreement, survey_plan, building_plan_approval, property_photos, property_hash, coordinates_hash, status 
-                ) VALUES (
-                   $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, 
-                   $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29, $30, $31, $32
-                ) RETURNING 
-                    id, landlord_id, agent_id, lawyer_id, title, description, 
-                    property_type as "property_type: PropertyType", 
-                    listing_type as "listing_type: ListingType",
-                    address, city, state, lga, country, latitude, longitude, landmark, 
-                    bedrooms, bathrooms, toilets, size_sqm, plot_size, price, 
-                    currency as "currency: CurrencyType", price_negotiable, bidding_price, 
-                    amenities, features, certificate_of_occupancy, deed_of_agreement, 
-                    survey_plan, building_plan_approval, property_photos,
-                    agent_verification_photos, agent_verification_notes, lawyer_verification_notes, 
-                    property_hash, coordinates_hash, 
-                    status as "status: PropertyStatus", 
-                    agent_verified_at, lawyer_verified_at, listed_at,
-                    created_at, updated_at
+            INSERT INTO properties (
+                landlord_id, title, description, property_type, listing_type, address, city, state, lga, country,
+                latitude, longitude, landmark, bedrooms, bathrooms, toilets, size_sqm, plot_size, price, currency,
+                price_negotiable, amenities, features, certificate_of_occupancy, deed_of_agreement, survey_plan,
+                building_plan_approval, property_photos, property_hash, coordinates_hash, status, inspection_fee
+            ) VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16,
+                $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29, $30, $31, $32
+            )
+            RETURNING {}
             "#,
-            landlord_id,
-            property_data.title,
-            property_data.description,
-            property_data.property_type as PropertyType,
-            property_data.listing_type as ListingType,
-            property_data.address,
-            property_data.city,
-            property_data.state,
-            property_data.lga,
-            property_data.country,
-            property_data.latitude,
-            property_data.longitude,
-            property_data.landmark,
-            property_data.bedrooms,
-            property_data.bathrooms,
-            property_data.toilets,
-            property_data.size_sqm,
-            property_data.plot_size,
-            property_data.price,
-            property_data.currency as CurrencyType,
-            property_data.price_negotiable.unwrap_or(false),
-            Json(property_data.amenities.unwrap_or_default()) as Json<Vec<String>>,
-            Json(property_data.features.unwrap_or_default()) as Json<Vec<String>>,
-            property_data.certificate_of_occupancy,
-            property_data.deed_of_agreement,
-            property_data.survey_plan,
-            property_data.building_plan_approval,
-            Json(property_data.property_photos) as Json<Vec<String>>,
-            property_hash,
-            coordinates_hash,
-            PropertyStatus::AwaitingAgent as PropertyStatus,
-        )
-        .fetch_one(&self.pool)
-        .await?;
+            PROPERTY_COLUMNS
+        );
 
-        Ok(property)
+        sqlx::query_as::<_, Property>(&query)
+            .bind(landlord_id)
+            .bind(&property_data.title)
+            .bind(&property_data.description)
+            .bind(property_data.property_type)
+            .bind(property_data.listing_type)
+            .bind(&property_data.address)
+            .bind(&property_data.city)
+            .bind(&property_data.state)
+            .bind(&property_data.lga)
+            .bind(&property_data.country)
+            .bind(&property_data.latitude)
+            .bind(&property_data.longitude)
+            .bind(&property_data.landmark)
+            .bind(property_data.bedrooms)
+            .bind(property_data.bathrooms)
+            .bind(property_data.toilets)
+            .bind(&property_data.size_sqm)
+            .bind(&property_data.plot_size)
+            .bind(property_data.price)
+            .bind(property_data.currency)
+            .bind(property_data.price_negotiable.unwrap_or(false))
+            .bind(Json(property_data.amenities.clone().unwrap_or_default()))
+            .bind(Json(property_data.features.clone().unwrap_or_default()))
+            .bind(&property_data.certificate_of_occupancy)
+            .bind(&property_data.deed_of_agreement)
+            .bind(&property_data.survey_plan)
+            .bind(&property_data.building_plan_approval)
+            .bind(Json(&property_data.property_photos))
+            .bind(property_hash(property_data))
+            .bind(coordinates_hash(property_data.latitude.as_ref(), property_data.longitude.as_ref()))
+            .bind(PropertyStatus::Draft)
+            .bind(property_data.inspection_fee)
+            .fetch_one(&self.pool)
+            .await
     }
 
     async fn check_property_duplicate(
         &self,
         property_hash: &str,
-        coordinate_hash: &str,
+        coordinates_hash: &str,
     ) -> Result<Option<Property>, sqlx::Error> {
-        let property = sqlx::query_as!(
-            Property,
+        let query = format!(
             r#"
-                SELECT 
-                    id, landlord_id, agent_id, lawyer_id, title, description, 
-                    property_type as "property_type: PropertyType", 
-                    listing_type as "listing_type: ListingType",
-                    address, city, state, lga, country, latitude, longitude, landmark, 
-                    bedrooms, bathrooms, toilets, size_sqm, plot_size, price, 
-                    currency as "currency: CurrencyType", price_negotiable, bidding_price, 
-                    amenities, features, certificate_of_occupancy, deed_of_agreement, 
-                    survey_plan, building_plan_approval, property_photos, 
-                    agent_verification_photos, agent_verification_notes, lawyer_verification_notes, 
-                    property_hash, coordinates_hash,
-                    status as "status: PropertyStatus", 
-                    agent_verified_at, lawyer_verified_at, listed_at, 
-                    created_at, updated_at
-                FROM properties 
-                WHERE property_hash = $1 OR coordinates_hash = $2
+            SELECT {}
+            FROM properties
+            WHERE property_hash = $1 OR (coordinates_hash = $2 AND coordinates_hash <> $3)
+            LIMIT 1
             "#,
-            property_hash,
-            coordinate_hash
-        )
-        .fetch_optional(&self.pool)
-        .await?;
+            PROPERTY_COLUMNS
+        );
 
-        Ok(property)
+        sqlx::query_as::<_, Property>(&query)
+            .bind(property_hash)
+            .bind(coordinates_hash)
+            .bind(NO_COORDINATES_HASH)
+            .fetch_optional(&self.pool)
+            .await
     }
 
     async fn get_property_by_id(
         &self,
         property_id: Uuid,
     ) -> Result<Option<Property>, sqlx::Error> {
-        let property = sqlx::query_as!(
-            Property,
-            r#"
-            SELECT 
-                id, landlord_id, agent_id, lawyer_id, title, description, 
-                property_type as "property_type: PropertyType", 
-                listing_type as "listing_type: ListingType",
-                address, city, state, lga, country, latitude, longitude, landmark, 
-                bedrooms, bathrooms, toilets, size_sqm, plot_size, price, 
-                currency as "currency: CurrencyType", price_negotiable, bidding_price, 
-                amenities, features, certificate_of_occupancy, deed_of_agreement, 
-                survey_plan, building_plan_approval, property_photos, 
-                agent_verification_photos, agent_verification_notes, lawyer_verification_notes, 
-                property_hash, coordinates_hash,
-                status as "status: PropertyStatus", 
-                agent_verified_at, lawyer_verified_at, listed_at, 
-                created_at, updated_at
-            FROM properties
-            WHERE id = $1
-            "#,
-            property_id
-        ) 
-        .fetch_optional(&self.pool)
-        .await?;
+        let query = format!("SELECT {} FROM properties WHERE id = $1", PROPERTY_COLUMNS);
 
-        Ok(property)
-    }
-
-    async fn assign_agent_to_property(
-        &self,
-        property_id: Uuid,
-        agent_id: Uuid,
-    ) -> Result<Property, sqlx::Error> {
-        let property = sqlx::query_as!(
-            Property,
-            r#"
-            UPDATE properties
-            SET agent_id = $1, updated_at = NOW()
-            WHERE id = $2
-            RETURNING 
-                id, landlord_id, agent_id, lawyer_id, title, description, 
-                property_type as "property_type: PropertyType", 
-                listing_type as "listing_type: ListingType",
-                address, city, state, lga, country, latitude, longitude, landmark, 
-                bedrooms, bathrooms, toilets, size_sqm, plot_size, price, 
-                currency as "currency: CurrencyType", price_negotiable, bidding_price, 
-                amenities, features, certificate_of_occupancy, deed_of_agreement, 
-                survey_plan, building_plan_approval, property_photos, 
-                agent_verification_photos, agent_verification_notes, lawyer_verification_notes, 
-                property_hash, coordinates_hash,
-                status as "status: PropertyStatus", 
-                agent_verified_at, lawyer_verified_at, listed_at, 
-                created_at, updated_at
-            "#,
-            agent_id,
-            property_id
-        )
-        .fetch_one(&self.pool)
-        .await?;
-
-        Ok(property)
+        sqlx::query_as::<_, Property>(&query)
+            .bind(property_id)
+            .fetch_optional(&self.pool)
+            .await
     }
 
     async fn get_properties_for_agent_verification(
         &self,
         agent_id: Uuid,
     ) -> Result<Vec<Property>, sqlx::Error> {
-        let properties = sqlx::query_as!(
-            Property,
+        let query = format!(
             r#"
-            SELECT 
-                id, landlord_id, agent_id, lawyer_id, title, description, 
-                property_type as "property_type: PropertyType", 
-                listing_type as "listing_type: ListingType",
-                address, city, state, lga, country, latitude, longitude, landmark, 
-                bedrooms, bathrooms, toilets, size_sqm, plot_size, price, 
-                currency as "currency: CurrencyType", price_negotiable, bidding_price, 
-                amenities, features, certificate_of_occupancy, deed_of_agreement, 
-                survey_plan, building_plan_approval, property_photos, 
-                agent_verification_photos, agent_verification_notes, lawyer_verification_notes, 
-                property_hash, coordinates_hash,
-                status as "status: PropertyStatus", 
-                agent_verified_at, lawyer_verified_at, listed_at, 
-                created_at, updated_at
-            FROM properties 
+            SELECT {}
+            FROM properties
             WHERE agent_id = $1 AND status = $2
-            ORDER BY created_at DESC
+            -- Listings whose photos match another landlord's come first
+            ORDER BY EXISTS (
+                SELECT 1 FROM property_photo_matches m
+                WHERE m.property_id = properties.id AND m.different_landlord
+            ) DESC, created_at DESC
             "#,
-            agent_id,
-            PropertyStatus::AwaitingAgent as PropertyStatus,
-        )
-        .fetch_all(&self.pool)
-        .await?;
+            PROPERTY_COLUMNS
+        );
 
-        Ok(properties)
+        sqlx::query_as::<_, Property>(&query)
+            .bind(agent_id)
+            .bind(PropertyStatus::AwaitingAgent)
+            .fetch_all(&self.pool)
+            .await
     }
 
     async fn agent_verify_property(
         &self,
         agent_id: Uuid,
-        verification_data: AgentVerificationDto,
-    ) -> Result<Property, sqlx::Error> {
-        let new_status = if verification_data.verification_status == "approved" {
-            PropertyStatus::AwaitingLawyer
-        } else {
-            PropertyStatus::Rejected
-        };
+        property_id: Uuid,
+        new_status: PropertyStatus,
+        verification_status: &str,
+        notes: &str,
+        verification_photos: &[String],
+    ) -> Result<Option<Property>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
 
-        // Updating the property
-        let property = sqlx::query_as!(
-            Property,
+        let query = format!(
             r#"
             UPDATE properties
-            SET 
+            SET
                 agent_verification_photos = $1,
                 agent_verification_notes = $2,
                 status = $3,
                 agent_verified_at = NOW(),
                 updated_at = NOW()
-            WHERE id = $4 AND agent_id = $5
-            RETURNING 
-                id, landlord_id, agent_id, lawyer_id, title, description, 
-                property_type as "property_type: PropertyType", 
-                listing_type as "listing_type: ListingType",
-                address, city, state, lga, country, latitude, longitude, landmark, 
-                bedrooms, bathrooms, toilets, size_sqm, plot_size, price, 
-                currency as "currency: CurrencyType", price_negotiable, bidding_price, 
-                amenities, features, certificate_of_occupancy, deed_of_agreement, 
-                survey_plan, building_plan_approval, property_photos, 
-                agent_verification_photos, agent_verification_notes, lawyer_verification_notes, 
-                property_hash, coordinates_hash,
-                status as "status: PropertyStatus", 
-                agent_verified_at, lawyer_verified_at, listed_at, 
-                created_at, updated_at
+            WHERE id = $4 AND agent_id = $5 AND status = $6
+            RETURNING {}
             "#,
-            Json(verification_data.verification_photos) as Json<Vec<String>>,
-            verification_data.notes,
-            new_status as PropertyStatus,
-            verification_data.property_id,
-            agent_id
+            PROPERTY_COLUMNS
+        );
+
+        let property = sqlx::query_as::<_, Property>(&query)
+            .bind(Json(verification_photos))
+            .bind(notes)
+            .bind(new_status)
+            .bind(property_id)
+            .bind(agent_id)
+            .bind(PropertyStatus::AwaitingAgent)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        let Some(property) = property else {
+            return Ok(None);
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO property_verifications (property_id, verifier_id, verifier_type, verification_status, notes, verification_photos)
+            VALUES ($1, $2, 'agent', $3, $4, $5)
+            "#
         )
-        .fetch_one(&self.pool)
+        .bind(property_id)
+        .bind(agent_id)
+        .bind(verification_status)
+        .bind(notes)
+        .bind(Json(verification_photos))
+        .execute(&mut *tx)
         .await?;
 
-        // Create verification records
-        sqlx::query!(
+        // Closes the agent's SLA
+        sqlx::query(
             r#"
-            INSERT INTO property_verifications (property_id, verifier_id, verifier_type, verification_status, notes, verification_photos) 
-            VALUES ($1, $2, $3, $4, $5, $6)
-            "#,
-            verification_data.property_id,
-            agent_id,
-            "agent",
-            verification_data.verification_status,
-            verification_data.notes,
-            Json(verification_data.verification_photos) as Json<Vec<String>>
+            UPDATE property_verification_assignments
+            SET status = 'completed', closed_at = NOW()
+            WHERE property_id = $1 AND verifier_id = $2 AND verifier_type = 'agent' AND status = 'active'
+            "#
         )
-        .execute(&self.pool)
+        .bind(property_id)
+        .bind(agent_id)
+        .execute(&mut *tx)
         .await?;
 
-        Ok(property)
+        tx.commit().await?;
+
+        Ok(Some(property))
     }
 
     async fn get_all_properties_within_agent_landmark(
@@ -460,37 +387,23 @@ impl PropertyExt for DBClient {
         limit: usize,
     ) -> Result<Vec<Property>, sqlx::Error> {
         let offset = (page.saturating_sub(1)) * limit as u32;
-
-        let properties = sqlx::query_as!(
-            Property,
+        let query = format!(
             r#"
-            SELECT 
-                id, landlord_id, agent_id, lawyer_id, title, description, 
-                property_type as "property_type: PropertyType", 
-                listing_type as "listing_type: ListingType",
-                address, city, state, lga, country, latitude, longitude, landmark, 
-                bedrooms, bathrooms, toilets, size_sqm, plot_size, price, 
-                currency as "currency: CurrencyType", price_negotiable, bidding_price, 
-                amenities, features, certificate_of_occupancy, deed_of_agreement, 
-                survey_plan, building_plan_approval, property_photos, 
-                agent_verification_photos, agent_verification_notes, lawyer_verification_notes, 
-                property_hash, coordinates_hash,
-                status as "status: PropertyStatus", 
-                agent_verified_at, lawyer_verified_at, listed_at, 
-                created_at, updated_at
-            FROM properties 
+            SELECT {}
+            FROM properties
             WHERE landmark = $1
             ORDER BY created_at DESC
             LIMIT $2 OFFSET $3
             "#,
-            landmark,
-            limit as i64,
-            offset as i64
-        )
-        .fetch_all(&self.pool)
-        .await?;
+            PROPERTY_COLUMNS
+        );
 
-        Ok(properties)
+        sqlx::query_as::<_, Property>(&query)
+            .bind(landmark)
+            .bind(limit as i64)
+            .bind(offset as i64)
+            .fetch_all(&self.pool)
+            .await
     }
 
     async fn get_all_properties_within_lawyer_state(
@@ -500,143 +413,113 @@ impl PropertyExt for DBClient {
         limit: usize,
     ) -> Result<Vec<Property>, sqlx::Error> {
         let offset = (page.saturating_sub(1)) * limit as u32;
-
-        let properties = sqlx::query_as!(
-            Property,
+        let query = format!(
             r#"
-            SELECT 
-                id, landlord_id, agent_id, lawyer_id, title, description, 
-                property_type as "property_type: PropertyType", 
-                listing_type as "listing_type: ListingType",
-                address, city, state, lga, country, latitude, longitude, landmark, 
-                bedrooms, bathrooms, toilets, size_sqm, plot_size, price, 
-                currency as "currency: CurrencyType", price_negotiable, bidding_price, 
-                amenities, features, certificate_of_occupancy, deed_of_agreement, 
-                survey_plan, building_plan_approval, property_photos, 
-                agent_verification_photos, agent_verification_notes, lawyer_verification_notes, 
-                property_hash, coordinates_hash,
-                status as "status: PropertyStatus", 
-                agent_verified_at, lawyer_verified_at, listed_at, 
-                created_at, updated_at
-            FROM properties 
+            SELECT {}
+            FROM properties
             WHERE state = $1
             ORDER BY created_at DESC
             LIMIT $2 OFFSET $3
             "#,
-            state,
-            limit as i64,
-            offset as i64
-        )
-        .fetch_all(&self.pool)
-        .await?;
+            PROPERTY_COLUMNS
+        );
 
-        Ok(properties)
+        sqlx::query_as::<_, Property>(&query)
+            .bind(state)
+            .bind(limit as i64)
+            .bind(offset as i64)
+            .fetch_all(&self.pool)
+            .await
     }
 
     async fn get_properties_for_lawyer_verification(
         &self,
-        lawyer_id: Uuid
+        lawyer_id: Uuid,
     ) -> Result<Vec<Property>, sqlx::Error> {
-        let properties = sqlx::query_as!(
-            Property,
+        let query = format!(
             r#"
-            SELECT 
-                id, landlord_id, agent_id, lawyer_id, title, description, 
-                property_type as "property_type: PropertyType", 
-                listing_type as "listing_type: ListingType",
-                address, city, state, lga, country, latitude, longitude, landmark, 
-                bedrooms, bathrooms, toilets, size_sqm, plot_size, price, 
-                currency as "currency: CurrencyType", price_negotiable, bidding_price, 
-                amenities, features, certificate_of_occupancy, deed_of_agreement, 
-                survey_plan, building_plan_approval, property_photos, 
-                agent_verification_photos, agent_verification_notes, lawyer_verification_notes, 
-                property_hash, coordinates_hash,
-                status as "status: PropertyStatus", 
-                agent_verified_at, lawyer_verified_at, listed_at, 
-                created_at, updated_at
-            FROM properties 
-            WHERE status = $1 AND (lawyer_id = $2 OR lawyer_id is NULL)
+            SELECT {}
+            FROM properties
+            WHERE status = $1 AND (lawyer_id = $2 OR lawyer_id IS NULL)
             ORDER BY created_at DESC
             "#,
-            PropertyStatus::AwaitingLawyer as PropertyStatus,
-            lawyer_id
-        )
-        .fetch_all(&self.pool)
-        .await?;
+            PROPERTY_COLUMNS
+        );
 
-        Ok(properties)
+        sqlx::query_as::<_, Property>(&query)
+            .bind(PropertyStatus::AwaitingLawyer)
+            .bind(lawyer_id)
+            .fetch_all(&self.pool)
+            .await
     }
 
     async fn lawyer_verify_property(
         &self,
         lawyer_id: Uuid,
-        verification_data: LawyerVerificationDto,
-    ) -> Result<Property, sqlx::Error> {
-        let new_status = if verification_data.verification_status == "approved" {
-            PropertyStatus::Active
-        } else {
-            PropertyStatus::Rejected
-        };
-
-        let listed_at = if new_status == PropertyStatus::Active {
-            Some(Utc::now())
-        } else {
-            None
-        };
+        property_id: Uuid,
+        new_status: PropertyStatus,
+        verification_status: &str,
+        notes: &str,
+    ) -> Result<Option<Property>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
 
-        // Updating property
-        let property = sqlx::query_as!(
-            Property,
+        let query = format!(
             r#"
             UPDATE properties
-            SET 
+            SET
                 lawyer_id = $1,
                 lawyer_verification_notes = $2,
                 status = $3,
                 lawyer_verified_at = NOW(),
-                listed_at = $4,
                 updated_at = NOW()
-            WHERE id = $5
-            RETURNING 
-                id, landlord_id, agent_id, lawyer_id, title, description, 
-                property_type as "property_type: PropertyType", 
-                listing_type as "listing_type: ListingType",
-                address, city, state, lga, country, latitude, longitude, landmark, 
-                bedrooms, bathrooms, toilets, size_sqm, plot_size, price, 
-                currency as "currency: CurrencyType", price_negotiable, bidding_price, 
-                amenities, features, certificate_of_occupancy, deed_of_agreement, 
-                survey_plan, building_plan_approval, property_photos, 
-                agent_verification_photos, agent_verification_notes, lawyer_verification_notes, 
-                property_hash, coordinates_hash,
-                status as "status: PropertyStatus", 
-                agent_verified_at, lawyer_verified_at, listed_at, 
-                created_at, updated_at
+            WHERE id = $4 AND status = $5 AND (lawyer_id IS NULL OR lawyer_id = $1)
+            RETURNING {}
             "#,
-            lawyer_id,
-            verification_data.notes,
-            new_status as PropertyStatus,
-            listed_at,
-            verification_data.property_id
+            PROPERTY_COLUMNS
+        );
+
+        let property = sqlx::query_as::<_, Property>(&query)
+            .bind(lawyer_id)
+            .bind(notes)
+            .bind(new_status)
+            .bind(property_id)
+            .bind(PropertyStatus::AwaitingLawyer)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        let Some(property) = property else {
+            return Ok(None);
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO property_verifications (property_id, verifier_id, verifier_type, verification_status, notes)
+            VALUES ($1, $2, 'lawyer', $3, $4)
+            "#
         )
-        .fetch_one(&self.pool)
+        .bind(property_id)
+        .bind(lawyer_id)
+        .bind(verification_status)
+        .bind(notes)
+        .execute(&mut *tx)
         .await?;
 
-        // Create verification record
-        sqlx::query!(
+        // Closes the lawyer's SLA. None is open when they took the listing from the open queue.
+        sqlx::query(
             r#"
-            INSERT INTO property_verifications (property_id, verifier_id, verifier_type, verification_status, notes)
-            VALUES ($1, $2, $3, $4, $5)
-            "#,
-            verification_data.property_id,
-            lawyer_id,
-            "lawyer",
-            verification_data.verification_status,
-            verification_data.notes
+            UPDATE property_verification_assignments
+            SET status = 'completed', closed_at = NOW()
+            WHERE property_id = $1 AND verifier_id = $2 AND verifier_type = 'lawyer' AND status = 'active'
+            "#
         )
-        .execute(&self.pool)
+        .bind(property_id)
+        .bind(lawyer_id)
+        .execute(&mut *tx)
         .await?;
 
-        Ok(property)
+        tx.commit().await?;
+
+        Ok(Some(property))
     }
 
     async fn get_properties_by_landlord(
@@ -646,37 +529,23 @@ impl PropertyExt for DBClient {
         limit: usize,
     ) -> Result<Vec<Property>, sqlx::Error> {
         let offset = (page.saturating_sub(1)) * limit as u32;
-
-        let properties = sqlx::query_as!(
-            Property,
+        let query = format!(
             r#"
-            SELECT 
-                id, landlord_id, agent_id, lawyer_id, title, description, 
-                property_type as "property_type: PropertyType", 
-                listing_type as "listing_type: ListingType",
-                address, city, state, lga, country, latitude, longitude, landmark, 
-                bedrooms, bathrooms, toilets, size_sqm, plot_size, price, 
-                currency as "currency: CurrencyType", price_negotiable, bidding_price, 
-                amenities, features, certificate_of_occupancy, deed_of_agreement, 
-                survey_plan, building_plan_approval, property_photos, 
-                agent_verification_photos, agent_verification_notes, lawyer_verification_notes, 
-                property_hash, coordinates_hash,
-                status as "status: PropertyStatus", 
-                agent_verified_at, lawyer_verified_at, listed_at, 
-                created_at, updated_at
+            SELECT {}
             FROM properties
             WHERE landlord_id = $1
             ORDER BY created_at DESC
             LIMIT $2 OFFSET $3
             "#,
-            landlord_id,
-            limit as i64,
-            offset as i64
-        )
-        .fetch_all(&self.pool)
-        .await?;
+            PROPERTY_COLUMNS
+        );
 
-        Ok(properties)
+        sqlx::query_as::<_, Property>(&query)
+            .bind(landlord_id)
+            .bind(limit as i64)
+            .bind(offset as i64)
+            .fetch_all(&self.pool)
+            .await
     }
 
     async fn get_active_properties(
@@ -686,144 +555,97 @@ impl PropertyExt for DBClient {
         limit: usize,
     ) -> Result<Vec<Property>, sqlx::Error> {
         let offset = (page.saturating_sub(1)) * limit as u32;
-
-        let properties = sqlx::query_as!(
-            Property,
+        let query = format!(
             r#"
-            SELECT 
-                id, landlord_id, agent_id, lawyer_id, title, description, 
-                property_type as "property_type: PropertyType", 
-                listing_type as "listing_type: ListingType",
-                address, city, state, lga, country, latitude, longitude, landmark, 
-                bedrooms, bathrooms, toilets, size_sqm, plot_size, price, 
-                currency as "currency: CurrencyType", price_negotiable, bidding_price, 
-                amenities, features, certificate_of_occupancy, deed_of_agreement, 
-                survey_plan, building_plan_approval, property_photos, 
-                agent_verification_photos, agent_verification_notes, lawyer_verification_notes, 
-                property_hash, coordinates_hash,
-                status as "status: PropertyStatus", 
-                agent_verified_at, lawyer_verified_at, listed_at, 
-                created_at, updated_at
+            SELECT {}
             FROM properties
             WHERE status = $1
-            AND ($2::text IS NULL OR property_type = $2::property_type)
-            AND ($3::text IS NULL OR listing_type = $3::listing_type)
+            AND ($2::property_type IS NULL OR property_type = $2)
+            AND ($3::listing_type IS NULL OR listing_type = $3)
             AND ($4::bigint IS NULL OR price >= $4)
             AND ($5::bigint IS NULL OR price <= $5)
             AND ($6::text IS NULL OR city ILIKE $6)
             AND ($7::text IS NULL OR state ILIKE $7)
-            AND ($8::int IS NULL OR bedrooms >= $8)
-            AND ($9::int IS NULL OR bathrooms >= $9)
-            ORDER BY created_at DESC
-            LIMIT $10 OFFSET $11
+            AND ($8::text IS NULL OR country ILIKE $8)
+            AND ($9::int IS NULL OR bedrooms >= $9)
+            AND ($10::int IS NULL OR bathrooms >= $10)
+            AND ($13::timestamptz IS NULL OR market_entered_at > $13)
+            ORDER BY listed_at DESC NULLS LAST, created_at DESC
+            LIMIT $11 OFFSET $12
             "#,
-            PropertyStatus::Active as PropertyStatus,
-            filters.property_type.map(|t| format!("{:?}", t).to_lowercase()),
-            filters.listing_type.map(|t| format!("{:?}", t).to_lowercase()),
-            filters.min_price,
-            filters.max_price,
-            filters.city.as_ref().map(|c| format!("%{}%", c)),
-            filters.state.as_ref().map(|c| format!("%{}%", c)),
-            filters.bedrooms,
-            filters.bathrooms,
-            limit as i64,
-            offset as i64,
-        ) 
-        .fetch_all(&self.pool)
-        .await?;
+            PROPERTY_COLUMNS
+        );
 
-        Ok(properties)
+        sqlx::query_as::<_, Property>(&query)
+            .bind(PropertyStatus::Active)
+            .bind(filters.property_type)
+            .bind(filters.listing_type)
+            .bind(filters.min_price)
+            .bind(filters.max_price)
+            .bind(filters.city)
+            .bind(filters.state)
+            .bind(filters.country)
+            .bind(filters.bedrooms)
+            .bind(filters.bathrooms)
+            .bind(limit as i64)
+            .bind(offset as i64)
+            .bind(filters.listed_after)
+            .fetch_all(&self.pool)
+            .await
     }
 
-    async fn update_property_status(
+    async fn transition_property_status(
         &self,
         property_id: Uuid,
-        status: PropertyStatus,
-    ) -> Result<Property, sqlx::Error> {
-        let property = sqlx::query_as!(
-            Property,
-            r#"
-            UPDATE properties
-            SET status = $1, updated_at = NOW()
-            WHERE id = $2
-            RETURNING 
-                id, landlord_id, agent_id, lawyer_id, title, description, 
-                property_type as "property_type: PropertyType", 
-                listing_type as "listing_type: ListingType",
-                address, city, state, lga, country, latitude, longitude, landmark, 
-                bedrooms, bathrooms, toilets, size_sqm, plot_size, price, 
-                currency as "currency: CurrencyType", price_negotiable, bidding_price, 
-                amenities, features, certificate_of_occupancy, deed_of_agreement, 
-                survey_plan, building_plan_approval, property_photos, 
-                agent_verification_photos, agent_verification_notes, lawyer_verification_notes, 
-                property_hash, coordinates_hash,
-                status as "status: PropertyStatus", 
-                agent_verified_at, lawyer_verified_at, listed_at, 
-                created_at, updated_at
-            "#,
-            status as PropertyStatus,
-            property_id
-        )
-        .fetch_one(&self.pool)
-        .await?;
-
+        from: PropertyStatus,
+        to: PropertyStatus,
+    ) -> Result<Option<Property>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let property = self.transition_property_status_tx(property_id, from, to, &mut tx).await?;
+        tx.commit().await?;
         Ok(property)
     }
 
-    async fn update_property_bidding_price(
+    async fn transition_property_status_tx(
         &self,
         property_id: Uuid,
-        bidding_price: i64,
-    ) -> Result<Property, sqlx::Error> {
-        let property = sqlx::query_as!(
-            Property,
+        from: PropertyStatus,
+        to: PropertyStatus,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<Option<Property>, sqlx::Error> {
+        let query = format!(
             r#"
             UPDATE properties
-            SET bidding_price = $1, updated_at = NOW()
-            WHERE id = $2
-            RETURNING 
-                id, landlord_id, agent_id, lawyer_id, title, description, 
-                property_type as "property_type: PropertyType", 
-                listing_type as "listing_type: ListingType",
-                address, city, state, lga, country, latitude, longitude, landmark, 
-                bedrooms, bathrooms, toilets, size_sqm, plot_size, price, 
-                currency as "currency: CurrencyType", price_negotiable, bidding_price, 
-                amenities, features, certificate_of_occupancy, deed_of_agreement, 
-                survey_plan, building_plan_approval, property_photos, 
-                agent_verification_photos, agent_verification_notes, lawyer_verification_notes, 
-                property_hash, coordinates_hash,
-                status as "status: PropertyStatus", 
-                agent_verified_at, lawyer_verified_at, listed_at, 
-                created_at, updated_at
+            SET status = $1, updated_at = NOW()
+            WHERE id = $2 AND status = $3
+            RETURNING {}
             "#,
-            bidding_price,
-            property_id,
-        )
-        .fetch_one(&self.pool)
-        .await?;
+            PROPERTY_COLUMNS
+        );
 
-        Ok(property)
+        sqlx::query_as::<_, Property>(&query)
+            .bind(to)
+            .bind(property_id)
+            .bind(from)
+            .fetch_optional(&mut **tx)
+            .await
     }
 
     async fn get_property_verification_history(
         &self,
-        property_id: Uuid
+        property_id: Uuid,
     ) -> Result<Vec<PropertyVerification>, sqlx::Error> {
-        let verifications = sqlx::query_as!(
-            PropertyVerification,
+        sqlx::query_as::<_, PropertyVerification>(
             r#"
-            SELECT 
-                id, property_id, verifier_id, verifier_type, verification_status,
-                notes, verification_photos, created_at
+            SELECT id, property_id, verifier_id, verifier_type, verification_status,
+                   notes, verification_photos, created_at
             FROM property_verifications
             WHERE property_id = $1
             ORDER BY created_at DESC
-            "#,
-            property_id
+            "#
         )
+        .bind(property_id)
         .fetch_all(&self.pool)
-        .await?;
-
-        Ok(verifications)
+        .await
     }
-}
\ No newline at end of file
+}
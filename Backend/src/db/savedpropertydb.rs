@@ -0,0 +1,502 @@
+// db/savedpropertydb.rs
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+use crate::{
+    db::{db::DBClient, propertydb::PROPERTY_COLUMNS},
+    models::propertymodel::{
+        ListingActivity, ListingPerformance, Property, PropertyPriceChange, PropertyStatus, SavedSearch,
+    },
+};
+
+const SAVED_SEARCH_COLUMNS: &str = r#"
+    id, user_id, name, filters, alerts_enabled, last_checked_at, created_at
+"#;
+
+#[async_trait]
+pub trait SavedPropertyExt {
+    /// Records one view of a listing page, like `record_service_view` does for vendor services
+    async fn record_property_view(
+        &self,
+        property_id: Uuid,
+        viewer_id: Option<Uuid>,
+        viewer_ip: Option<String>,
+        user_agent: Option<String>,
+    ) -> Result<(), sqlx::Error>;
+
+    /// False if the user had already saved it
+    async fn save_property(
+        &self,
+        user_id: Uuid,
+        property_id: Uuid,
+    ) -> Result<bool, sqlx::Error>;
+
+    async fn unsave_property(
+        &self,
+        user_id: Uuid,
+        property_id: Uuid,
+    ) -> Result<bool, sqlx::Error>;
+
+    /// Saved listings that are still public, most recently saved first
+    async fn get_saved_properties(
+        &self,
+        user_id: Uuid,
+        page: u32,
+        limit: usize,
+    ) -> Result<Vec<Property>, sqlx::Error>;
+
+    async fn get_property_saver_ids(
+        &self,
+        property_id: Uuid,
+    ) -> Result<Vec<Uuid>, sqlx::Error>;
+
+    async fn count_saved_searches(
+        &self,
+        user_id: Uuid,
+    ) -> Result<i64, sqlx::Error>;
+
+    async fn create_saved_search(
+        &self,
+        user_id: Uuid,
+        name: &str,
+        filters: &JsonValue,
+        alerts_enabled: bool,
+    ) -> Result<SavedSearch, sqlx::Error>;
+
+    async fn get_saved_searches(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<SavedSearch>, sqlx::Error>;
+
+    async fn delete_saved_search(
+        &self,
+        user_id: Uuid,
+        search_id: Uuid,
+    ) -> Result<bool, sqlx::Error>;
+
+    /// Turning alerts back on starts from now, not from when they were turned off
+    async fn set_saved_search_alerts(
+        &self,
+        user_id: Uuid,
+        search_id: Uuid,
+        enabled: bool,
+    ) -> Result<Option<SavedSearch>, sqlx::Error>;
+
+    /// Searches with alerts on that were last checked before `checked_before`, oldest first
+    async fn get_saved_searches_to_check(
+        &self,
+        checked_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<SavedSearch>, sqlx::Error>;
+
+    async fn mark_saved_search_checked(
+        &self,
+        search_id: Uuid,
+        checked_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error>;
+
+    /// Reprices a live listing and records the change. None if the listing is
+    /// no longer live or its price moved since `old_price` was read.
+    async fn change_property_price(
+        &self,
+        property_id: Uuid,
+        old_price: i64,
+        new_price: i64,
+        changed_by: Uuid,
+    ) -> Result<Option<Property>, sqlx::Error>;
+
+    async fn get_price_history(
+        &self,
+        property_id: Uuid,
+    ) -> Result<Vec<PropertyPriceChange>, sqlx::Error>;
+
+    /// Views, saves, viewing requests and offers on each of the landlord's
+    /// listings since `since`, busiest first
+    async fn get_listing_performance(
+        &self,
+        landlord_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ListingPerformance>, sqlx::Error>;
+
+    /// One row per day for the last `days` days, oldest first, including empty days
+    async fn get_listing_activity(
+        &self,
+        property_id: Uuid,
+        days: i32,
+    ) -> Result<Vec<ListingActivity>, sqlx::Error>;
+}
+
+#[async_trait]
+impl SavedPropertyExt for DBClient {
+    async fn record_property_view(
+        &self,
+        property_id: Uuid,
+        viewer_id: Option<Uuid>,
+        viewer_ip: Option<String>,
+        user_agent: Option<String>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO property_views (property_id, viewer_id, viewer_ip, user_agent) VALUES ($1, $2, $3::INET, $4)"
+        )
+        .bind(property_id)
+        .bind(viewer_id)
+        .bind(viewer_ip)
+        .bind(user_agent)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn save_property(
+        &self,
+        user_id: Uuid,
+        property_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO property_favorites (user_id, property_id)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id, property_id) DO NOTHING
+            "#
+        )
+        .bind(user_id)
+        .bind(property_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn unsave_property(
+        &self,
+        user_id: Uuid,
+        property_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM property_favorites WHERE user_id = $1 AND property_id = $2")
+            .bind(user_id)
+            .bind(property_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn get_saved_properties(
+        &self,
+        user_id: Uuid,
+        page: u32,
+        limit: usize,
+    ) -> Result<Vec<Property>, sqlx::Error> {
+        let offset = (page.saturating_sub(1) as i64) * limit as i64;
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM properties
+            WHERE status IN ($2, $3, $4)
+                AND id IN (SELECT property_id FROM property_favorites WHERE user_id = $1)
+            ORDER BY (
+                SELECT f.created_at FROM property_favorites f
+                WHERE f.user_id = $1 AND f.property_id = properties.id
+            ) DESC
+            LIMIT $5 OFFSET $6
+            "#,
+            PROPERTY_COLUMNS
+        );
+
+        sqlx::query_as::<_, Property>(&query)
+            .bind(user_id)
+            .bind(PropertyStatus::Active)
+            .bind(PropertyStatus::Sold)
+            .bind(PropertyStatus::Rented)
+            .bind(limit as i64)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn get_property_saver_ids(
+        &self,
+        property_id: Uuid,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar::<_, Uuid>("SELECT user_id FROM property_favorites WHERE property_id = $1")
+            .bind(property_id)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn count_saved_searches(
+        &self,
+        user_id: Uuid,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM saved_searches WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    async fn create_saved_search(
+        &self,
+        user_id: Uuid,
+        name: &str,
+        filters: &JsonValue,
+        alerts_enabled: bool,
+    ) -> Result<SavedSearch, sqlx::Error> {
+        let query = format!(
+            r#"
+            INSERT INTO saved_searches (user_id, name, filters, alerts_enabled)
+            VALUES ($1, $2, $3, $4)
+            RETURNING {}
+            "#,
+            SAVED_SEARCH_COLUMNS
+        );
+
+        sqlx::query_as::<_, SavedSearch>(&query)
+            .bind(user_id)
+            .bind(name)
+            .bind(filters)
+            .bind(alerts_enabled)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    async fn get_saved_searches(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<SavedSearch>, sqlx::Error> {
+        let query = format!(
+            "SELECT {} FROM saved_searches WHERE user_id = $1 ORDER BY created_at DESC",
+            SAVED_SEARCH_COLUMNS
+        );
+
+        sqlx::query_as::<_, SavedSearch>(&query)
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn delete_saved_search(
+        &self,
+        user_id: Uuid,
+        search_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM saved_searches WHERE id = $1 AND user_id = $2")
+            .bind(search_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn set_saved_search_alerts(
+        &self,
+        user_id: Uuid,
+        search_id: Uuid,
+        enabled: bool,
+    ) -> Result<Option<SavedSearch>, sqlx::Error> {
+        let query = format!(
+            r#"
+            UPDATE saved_searches
+            SET alerts_enabled = $1,
+                last_checked_at = CASE WHEN $1 AND NOT alerts_enabled THEN NOW() ELSE last_checked_at END
+            WHERE id = $2 AND user_id = $3
+            RETURNING {}
+            "#,
+            SAVED_SEARCH_COLUMNS
+        );
+
+        sqlx::query_as::<_, SavedSearch>(&query)
+            .bind(enabled)
+            .bind(search_id)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn get_saved_searches_to_check(
+        &self,
+        checked_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<SavedSearch>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM saved_searches
+            WHERE alerts_enabled AND last_checked_at < $1
+            ORDER BY last_checked_at
+            LIMIT $2
+            "#,
+            SAVED_SEARCH_COLUMNS
+        );
+
+        sqlx::query_as::<_, SavedSearch>(&query)
+            .bind(checked_before)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn mark_saved_search_checked(
+        &self,
+        search_id: Uuid,
+        checked_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE saved_searches SET last_checked_at = $1 WHERE id = $2")
+            .bind(checked_at)
+            .bind(search_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn change_property_price(
+        &self,
+        property_id: Uuid,
+        old_price: i64,
+        new_price: i64,
+        changed_by: Uuid,
+    ) -> Result<Option<Property>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let query = format!(
+            r#"
+            UPDATE properties
+            SET price = $1, updated_at = NOW()
+            WHERE id = $2 AND price = $3 AND status = $4
+            RETURNING {}
+            "#,
+            PROPERTY_COLUMNS
+        );
+
+        let property = sqlx::query_as::<_, Property>(&query)
+            .bind(new_price)
+            .bind(property_id)
+            .bind(old_price)
+            .bind(PropertyStatus::Active)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        if property.is_some() {
+            sqlx::query(
+                r#"
+                INSERT INTO property_price_changes (property_id, old_price, new_price, changed_by)
+                VALUES ($1, $2, $3, $4)
+                "#
+            )
+            .bind(property_id)
+            .bind(old_price)
+            .bind(new_price)
+            .bind(changed_by)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(property)
+    }
+
+    async fn get_price_history(
+        &self,
+        property_id: Uuid,
+    ) -> Result<Vec<PropertyPriceChange>, sqlx::Error> {
+        sqlx::query_as::<_, PropertyPriceChange>(
+            r#"
+            SELECT id, property_id, old_price, new_price, changed_by, created_at
+            FROM property_price_changes
+            WHERE property_id = $1
+            ORDER BY created_at
+            "#
+        )
+        .bind(property_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn get_listing_performance(
+        &self,
+        landlord_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ListingPerformance>, sqlx::Error> {
+        sqlx::query_as::<_, ListingPerformance>(
+            r#"
+            SELECT
+                p.id AS property_id, p.title, p.status, p.price,
+                COALESCE(v.views, 0) AS views,
+                COALESCE(v.unique_viewers, 0) AS unique_viewers,
+                COALESCE(f.saves, 0) AS saves,
+                COALESCE(f.total_saves, 0) AS total_saves,
+                COALESCE(vr.viewing_requests, 0) AS viewing_requests,
+                COALESCE(o.offers, 0) AS offers
+            FROM properties p
+            LEFT JOIN (
+                SELECT
+                    property_id,
+                    COUNT(*) AS views,
+                    COUNT(DISTINCT COALESCE(viewer_id::TEXT, viewer_ip::TEXT)) AS unique_viewers
+                FROM property_views
+                WHERE created_at >= $2
+                GROUP BY property_id
+            ) v ON v.property_id = p.id
+            LEFT JOIN (
+                SELECT
+                    property_id,
+                    COUNT(*) FILTER (WHERE created_at >= $2) AS saves,
+                    COUNT(*) AS total_saves
+                FROM property_favorites
+                GROUP BY property_id
+            ) f ON f.property_id = p.id
+            LEFT JOIN (
+                SELECT property_id, COUNT(*) AS viewing_requests
+                FROM property_viewings
+                WHERE created_at >= $2
+                GROUP BY property_id
+            ) vr ON vr.property_id = p.id
+            LEFT JOIN (
+                SELECT property_id, COUNT(*) AS offers
+                FROM property_offers
+                WHERE created_at >= $2
+                GROUP BY property_id
+            ) o ON o.property_id = p.id
+            WHERE p.landlord_id = $1 AND p.status != 'draft'
+            ORDER BY views DESC, p.created_at DESC
+            "#
+        )
+        .bind(landlord_id)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn get_listing_activity(
+        &self,
+        property_id: Uuid,
+        days: i32,
+    ) -> Result<Vec<ListingActivity>, sqlx::Error> {
+        sqlx::query_as::<_, ListingActivity>(
+            r#"
+            SELECT
+                d.day::DATE AS day,
+                (SELECT COUNT(*) FROM property_views
+                    WHERE property_id = $1 AND created_at >= d.day AND created_at < d.day + INTERVAL '1 day') AS views,
+                (SELECT COUNT(*) FROM property_favorites
+                    WHERE property_id = $1 AND created_at >= d.day AND created_at < d.day + INTERVAL '1 day') AS saves,
+                (SELECT COUNT(*) FROM property_viewings
+                    WHERE property_id = $1 AND created_at >= d.day AND created_at < d.day + INTERVAL '1 day') AS viewing_requests,
+                (SELECT COUNT(*) FROM property_offers
+                    WHERE property_id = $1 AND created_at >= d.day AND created_at < d.day + INTERVAL '1 day') AS offers
+            FROM generate_series(
+                date_trunc('day', NOW()) - make_interval(days => $2 - 1),
+                date_trunc('day', NOW()),
+                INTERVAL '1 day'
+            ) AS d(day)
+            ORDER BY d.day
+            "#
+        )
+        .bind(property_id)
+        .bind(days)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
@@ -0,0 +1,287 @@
+// db/sessiondb.rs
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    db::db::DBClient,
+    models::sessionmodels::{RefreshToken, SessionClient, UserSession},
+};
+
+const SESSION_COLUMNS: &str = r#"
+    id, user_id, device_name, ip_address, user_agent, created_at, last_used_at, expires_at,
+    revoked_at, revoked_reason, stepped_up_at
+"#;
+
+#[async_trait]
+pub trait SessionExt {
+    /// Starts a session together with its first refresh token
+    async fn create_session(
+        &self,
+        user_id: Uuid,
+        client: &SessionClient,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<UserSession, sqlx::Error>;
+
+    async fn get_session(
+        &self,
+        session_id: Uuid,
+    ) -> Result<Option<UserSession>, sqlx::Error>;
+
+    async fn get_refresh_token(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<RefreshToken>, sqlx::Error>;
+
+    /// Marks the refresh token used and issues its successor, sliding the
+    /// session's expiry along. False if the token was already used, in which
+    /// case nothing changes.
+    async fn rotate_refresh_token(
+        &self,
+        refresh_token_id: Uuid,
+        session_id: Uuid,
+        client: &SessionClient,
+        new_token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<bool, sqlx::Error>;
+
+    /// Revokes one of the user's sessions. None if it is not theirs or already revoked.
+    async fn revoke_session(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        reason: &str,
+    ) -> Result<Option<UserSession>, sqlx::Error>;
+
+    /// Revokes all of the user's sessions, optionally sparing one. Returns how many.
+    async fn revoke_user_sessions(
+        &self,
+        user_id: Uuid,
+        except_session_id: Option<Uuid>,
+        reason: &str,
+    ) -> Result<u64, sqlx::Error>;
+
+    /// Unrevoked, unexpired sessions, most recently used first
+    async fn get_active_sessions(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<UserSession>, sqlx::Error>;
+
+    /// Records that the session just passed a second-factor check
+    async fn mark_session_stepped_up(
+        &self,
+        session_id: Uuid,
+    ) -> Result<(), sqlx::Error>;
+}
+
+#[async_trait]
+impl SessionExt for DBClient {
+    async fn create_session(
+        &self,
+        user_id: Uuid,
+        client: &SessionClient,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<UserSession, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let query = format!(
+            r#"
+            INSERT INTO user_sessions (user_id, device_name, ip_address, user_agent, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING {}
+            "#,
+            SESSION_COLUMNS
+        );
+        let session = sqlx::query_as::<_, UserSession>(&query)
+            .bind(user_id)
+            .bind(&client.device_name)
+            .bind(&client.ip_address)
+            .bind(&client.user_agent)
+            .bind(expires_at)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO refresh_tokens (session_id, token_hash, expires_at)
+            VALUES ($1, $2, $3)
+            "#
+        )
+        .bind(session.id)
+        .bind(token_hash)
+        .bind(expires_at)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(session)
+    }
+
+    async fn get_session(
+        &self,
+        session_id: Uuid,
+    ) -> Result<Option<UserSession>, sqlx::Error> {
+        let query = format!("SELECT {} FROM user_sessions WHERE id = $1", SESSION_COLUMNS);
+
+        sqlx::query_as::<_, UserSession>(&query)
+            .bind(session_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn get_refresh_token(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<RefreshToken>, sqlx::Error> {
+        sqlx::query_as::<_, RefreshToken>(
+            r#"
+            SELECT id, session_id, token_hash, created_at, expires_at, used_at
+            FROM refresh_tokens
+            WHERE token_hash = $1
+            "#
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn rotate_refresh_token(
+        &self,
+        refresh_token_id: Uuid,
+        session_id: Uuid,
+        client: &SessionClient,
+        new_token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        // Two refreshes racing with the same token: only one gets past here
+        let used = sqlx::query(
+            r#"
+            UPDATE refresh_tokens SET used_at = NOW()
+            WHERE id = $1 AND used_at IS NULL
+            "#
+        )
+        .bind(refresh_token_id)
+        .execute(&mut *tx)
+        .await?;
+
+        if used.rows_affected() == 0 {
+            tx.rollback().await?;
+            return Ok(false);
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO refresh_tokens (session_id, token_hash, expires_at)
+            VALUES ($1, $2, $3)
+            "#
+        )
+        .bind(session_id)
+        .bind(new_token_hash)
+        .bind(expires_at)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE user_sessions
+            SET last_used_at = NOW(),
+                expires_at = $2,
+                ip_address = COALESCE($3, ip_address),
+                user_agent = COALESCE($4, user_agent)
+            WHERE id = $1
+            "#
+        )
+        .bind(session_id)
+        .bind(expires_at)
+        .bind(&client.ip_address)
+        .bind(&client.user_agent)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn revoke_session(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        reason: &str,
+    ) -> Result<Option<UserSession>, sqlx::Error> {
+        let query = format!(
+            r#"
+            UPDATE user_sessions
+            SET revoked_at = NOW(), revoked_reason = $3
+            WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL
+            RETURNING {}
+            "#,
+            SESSION_COLUMNS
+        );
+
+        sqlx::query_as::<_, UserSession>(&query)
+            .bind(session_id)
+            .bind(user_id)
+            .bind(reason)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn revoke_user_sessions(
+        &self,
+        user_id: Uuid,
+        except_session_id: Option<Uuid>,
+        reason: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE user_sessions
+            SET revoked_at = NOW(), revoked_reason = $3
+            WHERE user_id = $1 AND revoked_at IS NULL
+                AND ($2::uuid IS NULL OR id <> $2)
+            "#
+        )
+        .bind(user_id)
+        .bind(except_session_id)
+        .bind(reason)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn get_active_sessions(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<UserSession>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM user_sessions
+            WHERE user_id = $1 AND revoked_at IS NULL AND expires_at > NOW()
+            ORDER BY last_used_at DESC
+            "#,
+            SESSION_COLUMNS
+        );
+
+        sqlx::query_as::<_, UserSession>(&query)
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn mark_session_stepped_up(
+        &self,
+        session_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE user_sessions SET stepped_up_at = NOW() WHERE id = $1")
+            .bind(session_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
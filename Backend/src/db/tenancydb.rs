@@ -0,0 +1,509 @@
+// db/tenancydb.rs
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+use crate::{
+    db::db::DBClient,
+    dtos::tenancydtos::TenancyOfferDto,
+    models::tenancymodels::{RentPayment, RentPaymentStatus, Tenancy, TenancyStatus},
+};
+
+const TENANCY_COLUMNS: &str = r#"
+    id, property_id, tenant_id, landlord_id, rent_amount, rent_frequency, caution_deposit,
+    start_date, end_date, grace_period_days, late_fee_percent, status, message, response_note,
+    deposit_hold_id, deposit_deduction, inspection_notes,
+    accepted_at, move_out_at, ended_at, created_at, updated_at
+"#;
+
+const RENT_PAYMENT_COLUMNS: &str = r#"
+    id, tenancy_id, due_date, amount, late_fee, status, attempts,
+    last_attempt_at, paid_at, receipt_number, created_at, updated_at
+"#;
+
+#[async_trait]
+pub trait TenancyExt {
+    async fn create_tenancy_offer(
+        &self,
+        tenant_id: Uuid,
+        landlord_id: Uuid,
+        offer: &TenancyOfferDto,
+        end_date: NaiveDate,
+    ) -> Result<Tenancy, sqlx::Error>;
+
+    async fn get_tenancy(
+        &self,
+        tenancy_id: Uuid,
+    ) -> Result<Option<Tenancy>, sqlx::Error>;
+
+    async fn get_tenancies_for_tenant(
+        &self,
+        tenant_id: Uuid,
+        page: u32,
+        limit: usize,
+    ) -> Result<Vec<Tenancy>, sqlx::Error>;
+
+    async fn get_tenancies_for_landlord(
+        &self,
+        landlord_id: Uuid,
+        page: u32,
+        limit: usize,
+    ) -> Result<Vec<Tenancy>, sqlx::Error>;
+
+    /// Activates an offer and writes its rent schedule in the caller's
+    /// transaction. None if the offer is no longer pending.
+    async fn accept_tenancy_tx(
+        &self,
+        tenancy_id: Uuid,
+        grace_period_days: i32,
+        late_fee_percent: i32,
+        note: Option<&str>,
+        deposit_hold_id: Option<Uuid>,
+        due_dates: &[NaiveDate],
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<Option<Tenancy>, sqlx::Error>;
+
+    /// Compare-and-set on the status column. None if the tenancy is no longer in `from`.
+    async fn transition_tenancy_status(
+        &self,
+        tenancy_id: Uuid,
+        from: TenancyStatus,
+        to: TenancyStatus,
+        note: Option<&str>,
+    ) -> Result<Option<Tenancy>, sqlx::Error>;
+
+    /// Declines every other pending offer on the property
+    async fn decline_competing_offers(
+        &self,
+        property_id: Uuid,
+        accepted_id: Uuid,
+        note: &str,
+    ) -> Result<Vec<Tenancy>, sqlx::Error>;
+
+    /// Cancels rent that has not fallen due yet
+    async fn cancel_future_rent_payments(
+        &self,
+        tenancy_id: Uuid,
+        after: NaiveDate,
+    ) -> Result<u64, sqlx::Error>;
+
+    /// Closes the tenancy with the inspection outcome. None if it is not awaiting inspection.
+    /// Runs in the caller's transaction so the deposit settles with it.
+    async fn record_move_out_inspection_tx(
+        &self,
+        tenancy_id: Uuid,
+        deduction: i64,
+        notes: &str,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<Option<Tenancy>, sqlx::Error>;
+
+    async fn get_rent_payments(
+        &self,
+        tenancy_id: Uuid,
+    ) -> Result<Vec<RentPayment>, sqlx::Error>;
+
+    async fn get_rent_payment(
+        &self,
+        payment_id: Uuid,
+    ) -> Result<Option<RentPayment>, sqlx::Error>;
+
+    /// Unpaid rent due on or before `today` on running tenancies, not attempted in the last day
+    async fn get_due_rent_payments(
+        &self,
+        today: NaiveDate,
+        limit: i64,
+    ) -> Result<Vec<RentPayment>, sqlx::Error>;
+
+    /// Marks the payment paid in the caller's transaction, which then moves the
+    /// money. A second collector waits on the row and finds it paid. None if it
+    /// was already paid or cancelled.
+    async fn claim_rent_payment_tx(
+        &self,
+        payment_id: Uuid,
+        late_fee: i64,
+        receipt_number: &str,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<Option<RentPayment>, sqlx::Error>;
+
+    /// Records an attempt the tenant's wallet could not cover. None if the
+    /// payment was settled in the meantime.
+    async fn record_rent_payment_failure(
+        &self,
+        payment_id: Uuid,
+        status: RentPaymentStatus,
+        late_fee: i64,
+    ) -> Result<Option<RentPayment>, sqlx::Error>;
+}
+
+#[async_trait]
+impl TenancyExt for DBClient {
+    async fn create_tenancy_offer(
+        &self,
+        tenant_id: Uuid,
+        landlord_id: Uuid,
+        offer: &TenancyOfferDto,
+        end_date: NaiveDate,
+    ) -> Result<Tenancy, sqlx::Error> {
+        let query = format!(
+            r#"
+            INSERT INTO tenancies (
+                property_id, tenant_id, landlord_id, rent_amount, rent_frequency,
+                caution_deposit, start_date, end_date, message, status
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING {}
+            "#,
+            TENANCY_COLUMNS
+        );
+
+        sqlx::query_as::<_, Tenancy>(&query)
+            .bind(offer.property_id)
+            .bind(tenant_id)
+            .bind(landlord_id)
+            .bind(offer.rent_amount)
+            .bind(offer.rent_frequency)
+            .bind(offer.caution_deposit.unwrap_or(0))
+            .bind(offer.start_date)
+            .bind(end_date)
+            .bind(&offer.message)
+            .bind(TenancyStatus::Offered)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    async fn get_tenancy(
+        &self,
+        tenancy_id: Uuid,
+    ) -> Result<Option<Tenancy>, sqlx::Error> {
+        let query = format!("SELECT {} FROM tenancies WHERE id = $1", TENANCY_COLUMNS);
+
+        sqlx::query_as::<_, Tenancy>(&query)
+            .bind(tenancy_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn get_tenancies_for_tenant(
+        &self,
+        tenant_id: Uuid,
+        page: u32,
+        limit: usize,
+    ) -> Result<Vec<Tenancy>, sqlx::Error> {
+        let offset = (page.saturating_sub(1) as i64) * limit as i64;
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM tenancies
+            WHERE tenant_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+            TENANCY_COLUMNS
+        );
+
+        sqlx::query_as::<_, Tenancy>(&query)
+            .bind(tenant_id)
+            .bind(limit as i64)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn get_tenancies_for_landlord(
+        &self,
+        landlord_id: Uuid,
+        page: u32,
+        limit: usize,
+    ) -> Result<Vec<Tenancy>, sqlx::Error> {
+        let offset = (page.saturating_sub(1) as i64) * limit as i64;
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM tenancies
+            WHERE landlord_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+            TENANCY_COLUMNS
+        );
+
+        sqlx::query_as::<_, Tenancy>(&query)
+            .bind(landlord_id)
+            .bind(limit as i64)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn accept_tenancy_tx(
+        &self,
+        tenancy_id: Uuid,
+        grace_period_days: i32,
+        late_fee_percent: i32,
+        note: Option<&str>,
+        deposit_hold_id: Option<Uuid>,
+        due_dates: &[NaiveDate],
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<Option<Tenancy>, sqlx::Error> {
+        let query = format!(
+            r#"
+            UPDATE tenancies
+            SET status = $1, grace_period_days = $2, late_fee_percent = $3, response_note = $4,
+                deposit_hold_id = $5, accepted_at = NOW(), updated_at = NOW()
+            WHERE id = $6 AND status = $7
+            RETURNING {}
+            "#,
+            TENANCY_COLUMNS
+        );
+
+        let Some(tenancy) = sqlx::query_as::<_, Tenancy>(&query)
+            .bind(TenancyStatus::Active)
+            .bind(grace_period_days)
+            .bind(late_fee_percent)
+            .bind(note)
+            .bind(deposit_hold_id)
+            .bind(tenancy_id)
+            .bind(TenancyStatus::Offered)
+            .fetch_optional(&mut **tx)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO rent_payments (tenancy_id, due_date, amount)
+            SELECT $1, due_date, $2 FROM UNNEST($3::date[]) AS due_date
+            "#
+        )
+        .bind(tenancy.id)
+        .bind(tenancy.rent_amount)
+        .bind(due_dates)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(Some(tenancy))
+    }
+
+    async fn transition_tenancy_status(
+        &self,
+        tenancy_id: Uuid,
+        from: TenancyStatus,
+        to: TenancyStatus,
+        note: Option<&str>,
+    ) -> Result<Option<Tenancy>, sqlx::Error> {
+        let query = format!(
+            r#"
+            UPDATE tenancies
+            SET status = $1,
+                response_note = COALESCE($2, response_note),
+                move_out_at = CASE WHEN $1 = 'move_out'::tenancy_status THEN NOW() ELSE move_out_at END,
+                updated_at = NOW()
+            WHERE id = $3 AND status = $4
+            RETURNING {}
+            "#,
+            TENANCY_COLUMNS
+        );
+
+        sqlx::query_as::<_, Tenancy>(&query)
+            .bind(to)
+            .bind(note)
+            .bind(tenancy_id)
+            .bind(from)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn decline_competing_offers(
+        &self,
+        property_id: Uuid,
+        accepted_id: Uuid,
+        note: &str,
+    ) -> Result<Vec<Tenancy>, sqlx::Error> {
+        let query = format!(
+            r#"
+            UPDATE tenancies
+            SET status = $1, response_note = $2, updated_at = NOW()
+            WHERE property_id = $3 AND id <> $4 AND status = $5
+            RETURNING {}
+            "#,
+            TENANCY_COLUMNS
+        );
+
+        sqlx::query_as::<_, Tenancy>(&query)
+            .bind(TenancyStatus::Declined)
+            .bind(note)
+            .bind(property_id)
+            .bind(accepted_id)
+            .bind(TenancyStatus::Offered)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn cancel_future_rent_payments(
+        &self,
+        tenancy_id: Uuid,
+        after: NaiveDate,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE rent_payments
+            SET status = $1, updated_at = NOW()
+            WHERE tenancy_id = $2 AND due_date > $3 AND status = $4
+            "#
+        )
+        .bind(RentPaymentStatus::Cancelled)
+        .bind(tenancy_id)
+        .bind(after)
+        .bind(RentPaymentStatus::Scheduled)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn record_move_out_inspection_tx(
+        &self,
+        tenancy_id: Uuid,
+        deduction: i64,
+        notes: &str,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<Option<Tenancy>, sqlx::Error> {
+        let query = format!(
+            r#"
+            UPDATE tenancies
+            SET status = $1, deposit_deduction = $2, inspection_notes = $3,
+                ended_at = NOW(), updated_at = NOW()
+            WHERE id = $4 AND status = $5
+            RETURNING {}
+            "#,
+            TENANCY_COLUMNS
+        );
+
+        sqlx::query_as::<_, Tenancy>(&query)
+            .bind(TenancyStatus::Ended)
+            .bind(deduction)
+            .bind(notes)
+            .bind(tenancy_id)
+            .bind(TenancyStatus::MoveOut)
+            .fetch_optional(&mut **tx)
+            .await
+    }
+
+    async fn get_rent_payments(
+        &self,
+        tenancy_id: Uuid,
+    ) -> Result<Vec<RentPayment>, sqlx::Error> {
+        let query = format!(
+            "SELECT {} FROM rent_payments WHERE tenancy_id = $1 ORDER BY due_date",
+            RENT_PAYMENT_COLUMNS
+        );
+
+        sqlx::query_as::<_, RentPayment>(&query)
+            .bind(tenancy_id)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn get_rent_payment(
+        &self,
+        payment_id: Uuid,
+    ) -> Result<Option<RentPayment>, sqlx::Error> {
+        let query = format!("SELECT {} FROM rent_payments WHERE id = $1", RENT_PAYMENT_COLUMNS);
+
+        sqlx::query_as::<_, RentPayment>(&query)
+            .bind(payment_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn get_due_rent_payments(
+        &self,
+        today: NaiveDate,
+        limit: i64,
+    ) -> Result<Vec<RentPayment>, sqlx::Error> {
+        let columns = RENT_PAYMENT_COLUMNS
+            .split(',')
+            .map(|column| format!("r.{}", column.trim()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM rent_payments r
+            JOIN tenancies t ON t.id = r.tenancy_id
+            WHERE r.status IN ($1, $2)
+                AND r.due_date <= $3
+                AND t.status IN ($4, $5)
+                AND (r.last_attempt_at IS NULL OR r.last_attempt_at < NOW() - INTERVAL '20 hours')
+            ORDER BY r.due_date
+            LIMIT $6
+            "#,
+            columns
+        );
+
+        sqlx::query_as::<_, RentPayment>(&query)
+            .bind(RentPaymentStatus::Scheduled)
+            .bind(RentPaymentStatus::Overdue)
+            .bind(today)
+            .bind(TenancyStatus::Active)
+            .bind(TenancyStatus::MoveOut)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn claim_rent_payment_tx(
+        &self,
+        payment_id: Uuid,
+        late_fee: i64,
+        receipt_number: &str,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<Option<RentPayment>, sqlx::Error> {
+        let query = format!(
+            r#"
+            UPDATE rent_payments
+            SET status = $1, late_fee = $2, receipt_number = $3, paid_at = NOW(),
+                attempts = attempts + 1, last_attempt_at = NOW(), updated_at = NOW()
+            WHERE id = $4 AND status IN ($5, $6)
+            RETURNING {}
+            "#,
+            RENT_PAYMENT_COLUMNS
+        );
+
+        sqlx::query_as::<_, RentPayment>(&query)
+            .bind(RentPaymentStatus::Paid)
+            .bind(late_fee)
+            .bind(receipt_number)
+            .bind(payment_id)
+            .bind(RentPaymentStatus::Scheduled)
+            .bind(RentPaymentStatus::Overdue)
+            .fetch_optional(&mut **tx)
+            .await
+    }
+
+    async fn record_rent_payment_failure(
+        &self,
+        payment_id: Uuid,
+        status: RentPaymentStatus,
+        late_fee: i64,
+    ) -> Result<Option<RentPayment>, sqlx::Error> {
+        let query = format!(
+            r#"
+            UPDATE rent_payments
+            SET status = $1, late_fee = $2, attempts = attempts + 1, last_attempt_at = NOW(), updated_at = NOW()
+            WHERE id = $3 AND status IN ($4, $5)
+            RETURNING {}
+            "#,
+            RENT_PAYMENT_COLUMNS
+        );
+
+        sqlx::query_as::<_, RentPayment>(&query)
+            .bind(status)
+            .bind(late_fee)
+            .bind(payment_id)
+            .bind(RentPaymentStatus::Scheduled)
+            .bind(RentPaymentStatus::Overdue)
+            .fetch_optional(&self.pool)
+            .await
+    }
+}
@@ -0,0 +1,245 @@
+// db/twofactordb.rs
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    db::db::DBClient,
+    models::twofactormodels::UserTwoFactor,
+};
+
+#[async_trait]
+pub trait TwoFactorExt {
+    async fn get_two_factor(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Option<UserTwoFactor>, sqlx::Error>;
+
+    /// Starts (or restarts) an enrolment. An enabled enrolment is left alone
+    /// and None is returned.
+    async fn save_pending_two_factor(
+        &self,
+        user_id: Uuid,
+        secret_encrypted: &str,
+    ) -> Result<Option<UserTwoFactor>, sqlx::Error>;
+
+    /// Confirms the pending enrolment and stores its first recovery codes
+    async fn enable_two_factor(
+        &self,
+        user_id: Uuid,
+        step: i64,
+        recovery_code_hashes: &[String],
+    ) -> Result<UserTwoFactor, sqlx::Error>;
+
+    /// Removes the enrolment and its recovery codes
+    async fn delete_two_factor(
+        &self,
+        user_id: Uuid,
+    ) -> Result<(), sqlx::Error>;
+
+    /// Moves the replay guard forward. False if the step was already used.
+    async fn record_totp_step(
+        &self,
+        user_id: Uuid,
+        step: i64,
+    ) -> Result<bool, sqlx::Error>;
+
+    async fn replace_recovery_codes(
+        &self,
+        user_id: Uuid,
+        recovery_code_hashes: &[String],
+    ) -> Result<(), sqlx::Error>;
+
+    /// Spends a recovery code. False if it does not exist or was used.
+    async fn use_recovery_code(
+        &self,
+        user_id: Uuid,
+        code_hash: &str,
+    ) -> Result<bool, sqlx::Error>;
+
+    async fn count_unused_recovery_codes(
+        &self,
+        user_id: Uuid,
+    ) -> Result<i64, sqlx::Error>;
+}
+
+#[async_trait]
+impl TwoFactorExt for DBClient {
+    async fn get_two_factor(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Option<UserTwoFactor>, sqlx::Error> {
+        sqlx::query_as::<_, UserTwoFactor>(
+            r#"
+            SELECT user_id, secret_encrypted, enabled_at, last_used_step, created_at, updated_at
+            FROM user_two_factor
+            WHERE user_id = $1
+            "#
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn save_pending_two_factor(
+        &self,
+        user_id: Uuid,
+        secret_encrypted: &str,
+    ) -> Result<Option<UserTwoFactor>, sqlx::Error> {
+        sqlx::query_as::<_, UserTwoFactor>(
+            r#"
+            INSERT INTO user_two_factor (user_id, secret_encrypted)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id) DO UPDATE
+            SET secret_encrypted = EXCLUDED.secret_encrypted,
+                last_used_step = 0,
+                updated_at = NOW()
+            WHERE user_two_factor.enabled_at IS NULL
+            RETURNING user_id, secret_encrypted, enabled_at, last_used_step, created_at, updated_at
+            "#
+        )
+        .bind(user_id)
+        .bind(secret_encrypted)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn enable_two_factor(
+        &self,
+        user_id: Uuid,
+        step: i64,
+        recovery_code_hashes: &[String],
+    ) -> Result<UserTwoFactor, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let two_factor = sqlx::query_as::<_, UserTwoFactor>(
+            r#"
+            UPDATE user_two_factor
+            SET enabled_at = NOW(), last_used_step = $2, updated_at = NOW()
+            WHERE user_id = $1 AND enabled_at IS NULL
+            RETURNING user_id, secret_encrypted, enabled_at, last_used_step, created_at, updated_at
+            "#
+        )
+        .bind(user_id)
+        .bind(step)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM two_factor_recovery_codes WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO two_factor_recovery_codes (user_id, code_hash)
+            SELECT $1, UNNEST($2::text[])
+            "#
+        )
+        .bind(user_id)
+        .bind(recovery_code_hashes)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(two_factor)
+    }
+
+    async fn delete_two_factor(
+        &self,
+        user_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM two_factor_recovery_codes WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM user_two_factor WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn record_totp_step(
+        &self,
+        user_id: Uuid,
+        step: i64,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE user_two_factor
+            SET last_used_step = $2, updated_at = NOW()
+            WHERE user_id = $1 AND last_used_step < $2
+            "#
+        )
+        .bind(user_id)
+        .bind(step)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn replace_recovery_codes(
+        &self,
+        user_id: Uuid,
+        recovery_code_hashes: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM two_factor_recovery_codes WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO two_factor_recovery_codes (user_id, code_hash)
+            SELECT $1, UNNEST($2::text[])
+            "#
+        )
+        .bind(user_id)
+        .bind(recovery_code_hashes)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn use_recovery_code(
+        &self,
+        user_id: Uuid,
+        code_hash: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE two_factor_recovery_codes
+            SET used_at = NOW()
+            WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL
+            "#
+        )
+        .bind(user_id)
+        .bind(code_hash)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn count_unused_recovery_codes(
+        &self,
+        user_id: Uuid,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM two_factor_recovery_codes WHERE user_id = $1 AND used_at IS NULL"
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await
+    }
+}
@@ -7,7 +7,7 @@ use sqlx::Row;
 use super::db::DBClient;
 
 use crate::models::{
-    referralmodel::{Referral, ReferralStats, ReferralUser}, usermodel::{User, UserRole, VerificationStatus, VerificationType}, verificationmodels::*, walletmodels::{UserWallet, WalletUpdateRequest}
+    referralmodel::{Referral, ReferralStats, ReferralUser}, usermodel::{Locale, User, UserRole, VerificationStatus, VerificationType}, verificationmodels::*, walletmodels::{UserWallet, WalletUpdateRequest}
 };
 
 use crate::db::verificationdb::VerificationExt;
@@ -54,6 +54,19 @@ pub trait UserExt {
         name: T,
     ) -> Result<User, sqlx::Error>;
 
+    async fn update_user_locale(
+        &self,
+        user_id: Uuid,
+        locale: Locale,
+    ) -> Result<User, sqlx::Error>;
+
+    /// Fills in the date of birth; one already on the account is never replaced
+    async fn set_user_dob_if_missing(
+        &self,
+        user_id: Uuid,
+        dob: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error>;
+
     async fn update_user_role(
         &self,
         target_id: Uuid,
@@ -111,6 +124,7 @@ pub trait UserExt {
         score_to_add: i32
     ) -> Result<User, sqlx::Error>;
 
+    /// By username, email or verified phone number
     async fn get_user_by_identifier(
         &self, 
         identifier: &str
@@ -332,7 +346,7 @@ impl UserExt for DBClient {
                 nin_number, verification_document_id, facial_verification_id, nearest_landmark,
                 verification_number, nationality, dob, lga, transaction_pin, transaction_pin_hash, next_of_kin,
                 verification_token, token_expires_at,
-                subscription_tier, role_change_count, role_change_reset_at,
+                subscription_tier, role_change_count, role_change_reset_at, locale,
                 created_at,
                 updated_at
             "#
@@ -356,11 +370,12 @@ impl UserExt for DBClient {
                 nin_number, verification_document_id, facial_verification_id, nearest_landmark,
                 verification_number, nationality, dob, lga, transaction_pin, transaction_pin_hash, next_of_kin,
                 verification_token, token_expires_at,
-                subscription_tier, role_change_count, role_change_reset_at,
+                subscription_tier, role_change_count, role_change_reset_at, locale,
                 created_at,
                 updated_at
             FROM users
-            WHERE role = 'verifier'::user_role
+            WHERE (role = 'verifier'::user_role
+                OR EXISTS (SELECT 1 FROM user_roles ur WHERE ur.user_id = users.id AND ur.role = 'verifier'::user_role))
             AND id NOT IN (
                 SELECT assigned_verifier 
                 FROM disputes 
@@ -384,7 +399,7 @@ impl UserExt for DBClient {
                 nin_number, verification_document_id, facial_verification_id, nearest_landmark,
                 verification_number, nationality, dob, lga, transaction_pin, transaction_pin_hash, next_of_kin,
                 verification_token, token_expires_at,
-                subscription_tier, role_change_count, role_change_reset_at,
+                subscription_tier, role_change_count, role_change_reset_at, locale,
                 created_at,
                 updated_at
             FROM users
@@ -434,7 +449,7 @@ impl UserExt for DBClient {
                     nin_number, verification_document_id, facial_verification_id, nearest_landmark,
                     verification_number, nationality, dob, lga, transaction_pin, transaction_pin_hash, next_of_kin,
                     verification_token, token_expires_at,
-                    subscription_tier, role_change_count, role_change_reset_at,
+                    subscription_tier, role_change_count, role_change_reset_at, locale,
                     created_at,
                     updated_at
                 FROM users
@@ -456,7 +471,7 @@ impl UserExt for DBClient {
                     nin_number, verification_document_id, facial_verification_id, nearest_landmark,
                     verification_number, nationality, dob, lga, transaction_pin, transaction_pin_hash, next_of_kin,
                     verification_token, token_expires_at,
-                    subscription_tier, role_change_count, role_change_reset_at,
+                    subscription_tier, role_change_count, role_change_reset_at, locale,
                     created_at,
                     updated_at
                 FROM users 
@@ -478,7 +493,7 @@ impl UserExt for DBClient {
                     nin_number, verification_document_id, facial_verification_id, nearest_landmark,
                     verification_number, nationality, dob, lga, transaction_pin, transaction_pin_hash, next_of_kin,
                     verification_token, token_expires_at,
-                    subscription_tier, role_change_count, role_change_reset_at,
+                    subscription_tier, role_change_count, role_change_reset_at, locale,
                     created_at,
                     updated_at
                 FROM users 
@@ -500,7 +515,7 @@ impl UserExt for DBClient {
                     nin_number, verification_document_id, facial_verification_id, nearest_landmark,
                     verification_number, nationality, dob, lga, transaction_pin, transaction_pin_hash, next_of_kin,
                     verification_token, token_expires_at,
-                    subscription_tier, role_change_count, role_change_reset_at,
+                    subscription_tier, role_change_count, role_change_reset_at, locale,
                     created_at,
                     updated_at
                 FROM users 
@@ -533,7 +548,7 @@ impl UserExt for DBClient {
                     nin_number, verification_document_id, facial_verification_id, nearest_landmark,
                     verification_number, nationality, dob, lga, transaction_pin, transaction_pin_hash, next_of_kin,
                     verification_token, token_expires_at,
-                    subscription_tier, role_change_count, role_change_reset_at,
+                    subscription_tier, role_change_count, role_change_reset_at, locale,
                     created_at,
                     updated_at
             FROM users
@@ -568,7 +583,7 @@ impl UserExt for DBClient {
                     nin_number, verification_document_id, facial_verification_id, nearest_landmark,
                     verification_number, nationality, dob, lga, transaction_pin, transaction_pin_hash, next_of_kin,
                     verification_token, token_expires_at,
-                    subscription_tier, role_change_count, role_change_reset_at,
+                    subscription_tier, role_change_count, role_change_reset_at, locale,
                     created_at,
                     updated_at
             "#
@@ -612,7 +627,7 @@ impl UserExt for DBClient {
                     nin_number, verification_document_id, facial_verification_id, nearest_landmark,
                     verification_number, nationality, dob, lga, transaction_pin, transaction_pin_hash, next_of_kin,
                     verification_token, token_expires_at,
-                    subscription_tier, role_change_count, role_change_reset_at,
+                    subscription_tier, role_change_count, role_change_reset_at, locale,
                     created_at,
                     updated_at
             "#
@@ -623,11 +638,45 @@ impl UserExt for DBClient {
         .await
     }
 
+    async fn update_user_locale(
+        &self,
+        user_id: Uuid,
+        locale: Locale,
+    ) -> Result<User, sqlx::Error> {
+        sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET locale = $1, updated_at = NOW()
+            WHERE id = $2
+            RETURNING *
+            "#
+        )
+        .bind(locale)
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    async fn set_user_dob_if_missing(
+        &self,
+        user_id: Uuid,
+        dob: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE users SET dob = $1, updated_at = NOW() WHERE id = $2 AND dob IS NULL")
+            .bind(dob)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
     async fn update_user_role(
         &self,
         target_id: Uuid,
         new_role: UserRole
     ) -> Result<User, sqlx::Error> {
+        // Only the primary role changes; RoleService decides what else the
+        // user keeps
         sqlx::query_as::<_, User>(
             r#"
             UPDATE users
@@ -642,7 +691,7 @@ impl UserExt for DBClient {
                     nin_number, verification_document_id, facial_verification_id, nearest_landmark,
                     verification_number, nationality, dob, lga, transaction_pin, transaction_pin_hash, next_of_kin,
                     verification_token, token_expires_at,
-                    subscription_tier, role_change_count, role_change_reset_at,
+                    subscription_tier, role_change_count, role_change_reset_at, locale,
                     created_at,
                     updated_at
             "#
@@ -680,7 +729,7 @@ impl UserExt for DBClient {
                     nin_number, verification_document_id, facial_verification_id, nearest_landmark,
                     verification_number, nationality, dob, lga, transaction_pin, transaction_pin_hash, next_of_kin,
                     verification_token, token_expires_at,
-                    subscription_tier, role_change_count, role_change_reset_at,
+                    subscription_tier, role_change_count, role_change_reset_at, locale,
                     created_at,
                     updated_at
             "#
@@ -710,7 +759,7 @@ impl UserExt for DBClient {
                     nin_number, verification_document_id, facial_verification_id, nearest_landmark,
                     verification_number, nationality, dob, lga, transaction_pin, transaction_pin_hash, next_of_kin,
                     verification_token, token_expires_at,
-                    subscription_tier, role_change_count, role_change_reset_at,
+                    subscription_tier, role_change_count, role_change_reset_at, locale,
                     created_at,
                     updated_at
             "#
@@ -739,7 +788,7 @@ impl UserExt for DBClient {
                     nin_number, verification_document_id, facial_verification_id, nearest_landmark,
             verification_number, nationality, dob, lga, transaction_pin, transaction_pin_hash, next_of_kin,
                     verification_token, token_expires_at,
-                    subscription_tier, role_change_count, role_change_reset_at,
+                    subscription_tier, role_change_count, role_change_reset_at, locale,
                     created_at,
                     updated_at
             "#,
@@ -768,7 +817,7 @@ impl UserExt for DBClient {
                     nin_number, verification_document_id, facial_verification_id, nearest_landmark,
                     verification_number, nationality, dob, lga, transaction_pin, transaction_pin_hash, next_of_kin,
                     verification_token, token_expires_at,
-                    subscription_tier, role_change_count, role_change_reset_at,
+                    subscription_tier, role_change_count, role_change_reset_at, locale,
                     created_at,
                     updated_at
             "#,
@@ -841,7 +890,7 @@ impl UserExt for DBClient {
                     nin_number, verification_document_id, facial_verification_id, nearest_landmark,
                     verification_number, nationality, dob, lga, transaction_pin, transaction_pin_hash, next_of_kin,
                     verification_token, token_expires_at,
-                    subscription_tier, role_change_count, role_change_reset_at,
+                    subscription_tier, role_change_count, role_change_reset_at, locale,
                     created_at,
                     updated_at
             "#
@@ -853,10 +902,19 @@ impl UserExt for DBClient {
     }
 
     async fn get_user_by_identifier(&self, identifier: &str) -> Result<Option<User>, sqlx::Error> {
+        // Only verified numbers count, so nobody can claim another's phone
         sqlx::query_as::<_, User>(
-            "SELECT * FROM users WHERE username = $1 OR email = $1"
+            r#"
+            SELECT * FROM users
+            WHERE username = $1
+                OR email = $1
+                OR (phone_number = $2 AND phone_verified_at IS NOT NULL)
+            ORDER BY (email = $1) DESC, (username = $1) DESC
+            LIMIT 1
+            "#
         )
         .bind(identifier)
+        .bind(crate::utils::phone::normalize(identifier))
         .fetch_optional(&self.pool)
         .await
     }
@@ -876,7 +934,7 @@ impl UserExt for DBClient {
                     nin_number, verification_document_id, facial_verification_id, nearest_landmark,
                     verification_number, nationality, dob, lga, transaction_pin, transaction_pin_hash, next_of_kin,
                     verification_token, token_expires_at,
-                    subscription_tier, role_change_count, role_change_reset_at,
+                    subscription_tier, role_change_count, role_change_reset_at, locale,
                     created_at,
                     updated_at
             FROM users 
@@ -904,7 +962,7 @@ impl UserExt for DBClient {
                     nin_number, verification_document_id, facial_verification_id, nearest_landmark,
                     verification_number, nationality, dob, lga, transaction_pin, transaction_pin_hash, next_of_kin,
                     verification_token, token_expires_at,
-                    subscription_tier, role_change_count, role_change_reset_at,
+                    subscription_tier, role_change_count, role_change_reset_at, locale,
                     created_at,
                     updated_at
             FROM users 
@@ -935,7 +993,7 @@ impl UserExt for DBClient {
                     nin_number, verification_document_id, facial_verification_id, nearest_landmark,
                     verification_number, nationality, dob, lga, transaction_pin, transaction_pin_hash, next_of_kin,
                     verification_token, token_expires_at,
-                    subscription_tier, role_change_count, role_change_reset_at,
+                    subscription_tier, role_change_count, role_change_reset_at, locale,
                     created_at,
                     updated_at
             "#
@@ -965,7 +1023,7 @@ impl UserExt for DBClient {
                     nin_number, verification_document_id, facial_verification_id, nearest_landmark,
                     verification_number, nationality, dob, lga, transaction_pin, transaction_pin_hash, next_of_kin,
                     verification_token, token_expires_at,
-                    subscription_tier, role_change_count, role_change_reset_at,
+                    subscription_tier, role_change_count, role_change_reset_at, locale,
                     created_at,
                     updated_at
             "#
@@ -1014,7 +1072,7 @@ impl UserExt for DBClient {
                     nin_number, verification_document_id, facial_verification_id, nearest_landmark,
                     verification_number, nationality, dob, lga, transaction_pin, transaction_pin_hash, next_of_kin,
                     verification_token, token_expires_at,
-                    subscription_tier, role_change_count, role_change_reset_at,
+                    subscription_tier, role_change_count, role_change_reset_at, locale,
                     created_at,
                     updated_at
             "#
@@ -1099,7 +1157,7 @@ impl UserExt for DBClient {
                     nin_number, verification_document_id, facial_verification_id, nearest_landmark,
                     verification_number, nationality, dob, lga, transaction_pin, transaction_pin_hash, next_of_kin,
                     verification_token, token_expires_at,
-                    subscription_tier, role_change_count, role_change_reset_at,
+                    subscription_tier, role_change_count, role_change_reset_at, locale,
                     created_at,
                     updated_at
             FROM users
@@ -1136,7 +1194,7 @@ impl UserExt for DBClient {
                     nin_number, verification_document_id, facial_verification_id, nearest_landmark,
                     verification_number, nationality, dob, lga, transaction_pin, next_of_kin,
                     verification_token, token_expires_at,
-                    subscription_tier, role_change_count, role_change_reset_at,
+                    subscription_tier, role_change_count, role_change_reset_at, locale,
                     created_at,
                     updated_at
             "#
@@ -1193,7 +1251,7 @@ impl UserExt for DBClient {
                     nin_number, verification_document_id, facial_verification_id, nearest_landmark,
                     verification_number, nationality, dob, lga, transaction_pin, next_of_kin,
                     verification_token, token_expires_at,
-                    subscription_tier, role_change_count, role_change_reset_at,
+                    subscription_tier, role_change_count, role_change_reset_at, locale,
                     created_at,
                     updated_at
             "#
@@ -1297,6 +1355,7 @@ impl UserExt for DBClient {
                 verification_number, nationality,
                 dob, lga, transaction_pin, transaction_pin_hash, next_of_kin,
                 verification_token, token_expires_at,
+                subscription_tier, role_change_count, role_change_reset_at, locale,
                 created_at,
                 updated_at
             FROM users 
@@ -1408,7 +1467,7 @@ impl UserExt for DBClient {
                     nin_number, verification_document_id, facial_verification_id, nearest_landmark,
                     verification_number, nationality, dob, lga, transaction_pin, next_of_kin,
                     verification_token, token_expires_at,
-                    subscription_tier, role_change_count, role_change_reset_at,
+                    subscription_tier, role_change_count, role_change_reset_at, locale,
                     created_at,
                     updated_at
             "#
@@ -1442,7 +1501,7 @@ impl UserExt for DBClient {
                     nin_number, verification_document_id, facial_verification_id, nearest_landmark,
                     verification_number, nationality, dob, lga, transaction_pin, next_of_kin,
                     verification_token, token_expires_at,
-                    subscription_tier, role_change_count, role_change_reset_at,
+                    subscription_tier, role_change_count, role_change_reset_at, locale,
                     created_at,
                     updated_at
             FROM users 
@@ -1474,7 +1533,7 @@ impl UserExt for DBClient {
                     nin_number, verification_document_id, facial_verification_id, nearest_landmark,
                     verification_number, nationality, dob, lga, transaction_pin, transaction_pin_hash, next_of_kin,
                     verification_token, token_expires_at,
-                    subscription_tier, role_change_count, role_change_reset_at,
+                    subscription_tier, role_change_count, role_change_reset_at, locale,
                     created_at,
                     updated_at
             "#
@@ -1603,6 +1662,9 @@ impl UserExt for DBClient {
             r#"
             UPDATE users 
             SET phone_number = COALESCE($2, phone_number),
+                -- A new number has to be verified again
+                phone_verified_at = CASE WHEN $2 IS NULL OR $2 = phone_number THEN phone_verified_at END,
+                otp_channel = CASE WHEN $2 IS NULL OR $2 = phone_number THEN otp_channel ELSE 'email' END,
                 lga = COALESCE($3, lga),
                 nearest_landmark = COALESCE($4, nearest_landmark),
                 updated_at = NOW()
@@ -1617,7 +1679,7 @@ impl UserExt for DBClient {
                 nin_number, verification_document_id, facial_verification_id, nearest_landmark,
                 verification_number, nationality, dob, lga, transaction_pin, transaction_pin_hash, next_of_kin,
                 verification_token, token_expires_at,
-                subscription_tier, role_change_count, role_change_reset_at,
+                subscription_tier, role_change_count, role_change_reset_at, locale,
                 created_at,
                 updated_at
             "#
@@ -33,6 +33,8 @@ pub trait VendorExt {
         location_city: Option<String>,
     ) -> Result<VendorProfile, Error>;
     
+    async fn set_vendor_verified(&self, vendor_id: Uuid, is_verified: bool) -> Result<VendorProfile, Error>;
+    
     // Subscription Management
     async fn upgrade_subscription(
         &self,
@@ -271,6 +273,8 @@ impl VendorExt for DBClient {
             r#"
             UPDATE vendor_profiles
             SET business_name = COALESCE($2, business_name),
+                -- The CAC check was against the old name
+                is_verified = CASE WHEN $2 IS NOT NULL AND $2 <> business_name THEN FALSE ELSE is_verified END,
                 description = COALESCE($3, description),
                 location_state = COALESCE($4, location_state),
                 location_city = COALESCE($5, location_city),
@@ -288,6 +292,21 @@ impl VendorExt for DBClient {
         .await
     }
     
+    async fn set_vendor_verified(&self, vendor_id: Uuid, is_verified: bool) -> Result<VendorProfile, Error> {
+        sqlx::query_as::<_, VendorProfile>(
+            r#"
+            UPDATE vendor_profiles
+            SET is_verified = $2, updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#
+        )
+        .bind(vendor_id)
+        .bind(is_verified)
+        .fetch_one(&self.pool)
+        .await
+    }
+    
     async fn upgrade_subscription(
         &self,
         vendor_id: Uuid,
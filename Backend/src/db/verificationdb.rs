@@ -91,7 +91,7 @@ impl VerificationExt for DBClient {
             INSERT INTO otp_codes (user_id, email, otp_code, purpose, expires_at)
             VALUES ($1, $2, $3, $4, $5)
             RETURNING 
-                id, user_id, email, otp_code, 
+                id, user_id, email, phone_number, otp_code, 
                 purpose,
                 expires_at, used, created_at
             "#
@@ -114,7 +114,7 @@ impl VerificationExt for DBClient {
         sqlx::query_as::<_, OtpRecord>(
             r#"
             SELECT 
-                id, user_id, email, otp_code, 
+                id, user_id, email, phone_number, otp_code, 
                 purpose,
                 expires_at, used, created_at
             FROM otp_codes
@@ -0,0 +1,311 @@
+// db/viewingdb.rs
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::types::Json;
+use uuid::Uuid;
+
+use crate::{
+    db::db::DBClient,
+    models::viewingmodels::{NoShowParty, PropertyViewing, ViewingStatus},
+};
+
+const VIEWING_COLUMNS: &str = r#"
+    id, property_id, requester_id, host_id, proposed_slots, scheduled_at, status,
+    message, response_note, inspection_fee, fee_hold_id, no_show_party,
+    reminder_sent_at, created_at, updated_at, responded_at, completed_at
+"#;
+
+#[async_trait]
+pub trait ViewingExt {
+    async fn create_viewing(
+        &self,
+        property_id: Uuid,
+        requester_id: Uuid,
+        proposed_slots: &[DateTime<Utc>],
+        message: Option<&str>,
+        inspection_fee: i64,
+        fee_hold_id: Option<Uuid>,
+    ) -> Result<PropertyViewing, sqlx::Error>;
+
+    async fn get_viewing(
+        &self,
+        viewing_id: Uuid,
+    ) -> Result<Option<PropertyViewing>, sqlx::Error>;
+
+    async fn get_viewings_for_requester(
+        &self,
+        requester_id: Uuid,
+        page: u32,
+        limit: usize,
+    ) -> Result<Vec<PropertyViewing>, sqlx::Error>;
+
+    /// Viewings of properties the user owns or is the assigned agent for
+    async fn get_viewings_for_host(
+        &self,
+        user_id: Uuid,
+        page: u32,
+        limit: usize,
+    ) -> Result<Vec<PropertyViewing>, sqlx::Error>;
+
+    /// Accepts a slot. None if the viewing is no longer awaiting a response.
+    async fn confirm_viewing(
+        &self,
+        viewing_id: Uuid,
+        host_id: Uuid,
+        scheduled_at: DateTime<Utc>,
+        note: Option<&str>,
+    ) -> Result<Option<PropertyViewing>, sqlx::Error>;
+
+    /// Compare-and-set on the status column. None if the viewing is no longer in `from`.
+    /// Runs in the caller's transaction so the fee settles with it.
+    async fn transition_viewing_status_tx(
+        &self,
+        viewing_id: Uuid,
+        from: ViewingStatus,
+        to: ViewingStatus,
+        note: Option<&str>,
+        no_show_party: Option<NoShowParty>,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<Option<PropertyViewing>, sqlx::Error>;
+
+    /// Confirmed viewings starting before `until` whose reminder has not gone out
+    async fn get_viewings_due_reminder(
+        &self,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<PropertyViewing>, sqlx::Error>;
+
+    async fn mark_viewing_reminded(
+        &self,
+        viewing_id: Uuid,
+    ) -> Result<(), sqlx::Error>;
+
+    /// Confirmed viewings scheduled before `cutoff` that nobody closed
+    async fn get_viewings_to_auto_complete(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> Result<Vec<PropertyViewing>, sqlx::Error>;
+}
+
+#[async_trait]
+impl ViewingExt for DBClient {
+    async fn create_viewing(
+        &self,
+        property_id: Uuid,
+        requester_id: Uuid,
+        proposed_slots: &[DateTime<Utc>],
+        message: Option<&str>,
+        inspection_fee: i64,
+        fee_hold_id: Option<Uuid>,
+    ) -> Result<PropertyViewing, sqlx::Error> {
+        let query = format!(
+            r#"
+            INSERT INTO property_viewings (
+                property_id, requester_id, proposed_slots, message, inspection_fee, fee_hold_id, status
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING {}
+            "#,
+            VIEWING_COLUMNS
+        );
+
+        sqlx::query_as::<_, PropertyViewing>(&query)
+            .bind(property_id)
+            .bind(requester_id)
+            .bind(Json(proposed_slots))
+            .bind(message)
+            .bind(inspection_fee)
+            .bind(fee_hold_id)
+            .bind(ViewingStatus::Requested)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    async fn get_viewing(
+        &self,
+        viewing_id: Uuid,
+    ) -> Result<Option<PropertyViewing>, sqlx::Error> {
+        let query = format!("SELECT {} FROM property_viewings WHERE id = $1", VIEWING_COLUMNS);
+
+        sqlx::query_as::<_, PropertyViewing>(&query)
+            .bind(viewing_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn get_viewings_for_requester(
+        &self,
+        requester_id: Uuid,
+        page: u32,
+        limit: usize,
+    ) -> Result<Vec<PropertyViewing>, sqlx::Error> {
+        let offset = (page.saturating_sub(1) as i64) * limit as i64;
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM property_viewings
+            WHERE requester_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+            VIEWING_COLUMNS
+        );
+
+        sqlx::query_as::<_, PropertyViewing>(&query)
+            .bind(requester_id)
+            .bind(limit as i64)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn get_viewings_for_host(
+        &self,
+        user_id: Uuid,
+        page: u32,
+        limit: usize,
+    ) -> Result<Vec<PropertyViewing>, sqlx::Error> {
+        let offset = (page.saturating_sub(1) as i64) * limit as i64;
+        let columns = VIEWING_COLUMNS
+            .split(',')
+            .map(|column| format!("v.{}", column.trim()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM property_viewings v
+            JOIN properties p ON p.id = v.property_id
+            WHERE p.landlord_id = $1 OR p.agent_id = $1
+            ORDER BY COALESCE(v.scheduled_at, v.created_at) DESC
+            LIMIT $2 OFFSET $3
+            "#,
+            columns
+        );
+
+        sqlx::query_as::<_, PropertyViewing>(&query)
+            .bind(user_id)
+            .bind(limit as i64)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn confirm_viewing(
+        &self,
+        viewing_id: Uuid,
+        host_id: Uuid,
+        scheduled_at: DateTime<Utc>,
+        note: Option<&str>,
+    ) -> Result<Option<PropertyViewing>, sqlx::Error> {
+        let query = format!(
+            r#"
+            UPDATE property_viewings
+            SET status = $1, host_id = $2, scheduled_at = $3, response_note = $4,
+                responded_at = NOW(), updated_at = NOW()
+            WHERE id = $5 AND status = $6
+            RETURNING {}
+            "#,
+            VIEWING_COLUMNS
+        );
+
+        sqlx::query_as::<_, PropertyViewing>(&query)
+            .bind(ViewingStatus::Confirmed)
+            .bind(host_id)
+            .bind(scheduled_at)
+            .bind(note)
+            .bind(viewing_id)
+            .bind(ViewingStatus::Requested)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn transition_viewing_status_tx(
+        &self,
+        viewing_id: Uuid,
+        from: ViewingStatus,
+        to: ViewingStatus,
+        note: Option<&str>,
+        no_show_party: Option<NoShowParty>,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<Option<PropertyViewing>, sqlx::Error> {
+        let query = format!(
+            r#"
+            UPDATE property_viewings
+            SET status = $1,
+                response_note = COALESCE($2, response_note),
+                no_show_party = COALESCE($3, no_show_party),
+                responded_at = CASE WHEN $1 = 'declined'::viewing_status THEN NOW() ELSE responded_at END,
+                completed_at = CASE WHEN $1 IN ('completed'::viewing_status, 'no_show'::viewing_status) THEN NOW() ELSE completed_at END,
+                updated_at = NOW()
+            WHERE id = $4 AND status = $5
+            RETURNING {}
+            "#,
+            VIEWING_COLUMNS
+        );
+
+        sqlx::query_as::<_, PropertyViewing>(&query)
+            .bind(to)
+            .bind(note)
+            .bind(no_show_party.map(|party| party.to_str().to_string()))
+            .bind(viewing_id)
+            .bind(from)
+            .fetch_optional(&mut **tx)
+            .await
+    }
+
+    async fn get_viewings_due_reminder(
+        &self,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<PropertyViewing>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM property_viewings
+            WHERE status = $1
+                AND reminder_sent_at IS NULL
+                AND scheduled_at > NOW()
+                AND scheduled_at <= $2
+            ORDER BY scheduled_at
+            "#,
+            VIEWING_COLUMNS
+        );
+
+        sqlx::query_as::<_, PropertyViewing>(&query)
+            .bind(ViewingStatus::Confirmed)
+            .bind(until)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn mark_viewing_reminded(
+        &self,
+        viewing_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE property_viewings SET reminder_sent_at = NOW(), updated_at = NOW() WHERE id = $1")
+            .bind(viewing_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_viewings_to_auto_complete(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> Result<Vec<PropertyViewing>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM property_viewings
+            WHERE status = $1 AND scheduled_at <= $2
+            ORDER BY scheduled_at
+            LIMIT 100
+            "#,
+            VIEWING_COLUMNS
+        );
+
+        sqlx::query_as::<_, PropertyViewing>(&query)
+            .bind(ViewingStatus::Confirmed)
+            .bind(cutoff)
+            .fetch_all(&self.pool)
+            .await
+    }
+}
@@ -0,0 +1,69 @@
+// dtos/emaildtos.rs
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::emailmodels::*;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OutboxQueryDto {
+    pub status: Option<EmailStatus>,
+    /// Only messages whose lease expired or that are long overdue
+    pub stuck: Option<bool>,
+    pub page: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+/// Outbox row as shown to admins. Placeholders are left out: they carry OTPs and reset links.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxEmailDto {
+    pub id: Uuid,
+    pub idempotency_key: String,
+    pub to_email: String,
+    pub subject: String,
+    pub template_path: String,
+    pub status: EmailStatus,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub locked_until: Option<DateTime<Utc>>,
+    pub provider_message_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub sent_at: Option<DateTime<Utc>>,
+}
+
+impl From<OutboxEmail> for OutboxEmailDto {
+    fn from(email: OutboxEmail) -> Self {
+        Self {
+            id: email.id,
+            idempotency_key: email.idempotency_key,
+            to_email: email.to_email,
+            subject: email.subject,
+            template_path: email.template_path,
+            status: email.status,
+            attempts: email.attempts,
+            max_attempts: email.max_attempts,
+            last_error: email.last_error,
+            next_attempt_at: email.next_attempt_at,
+            locked_until: email.locked_until,
+            provider_message_id: email.provider_message_id,
+            created_at: email.created_at,
+            updated_at: email.updated_at,
+            sent_at: email.sent_at,
+        }
+    }
+}
+
+#[derive(Validate, Debug, Clone, Serialize, Deserialize)]
+pub struct CreateSuppressionDto {
+    #[validate(email(message = "Invalid email address"))]
+    pub email: String,
+
+    pub reason: Option<SuppressionReason>,
+
+    #[validate(length(max = 500, message = "Details must be at most 500 characters"))]
+    pub details: Option<String>,
+}
@@ -4,6 +4,7 @@ use uuid::Uuid;
 use validator::Validate;
 
 use crate::models::labourmodel::*;
+use crate::utils::gazetteer::validate_state;
 
 //Worker Profile DTOs
 #[derive(Debug, Serialize, Deserialize, Validate)]
@@ -23,7 +24,7 @@ pub struct CreateWorkerProfileDto {
     #[validate(range(min = 0.0, message = "Daily rate must be positive"))]
     pub daily_rate: Option<f64>,
 
-    #[validate(length(min = 1, message = "State is required"))]
+    #[validate(length(min = 1, message = "State is required"), custom = "validate_state")]
     pub location_state: String,
 
     #[validate(length(min = 1, message = "City is required"))]
@@ -86,7 +87,7 @@ pub struct CreateJobDto {
     #[validate(length(min = 20, max = 2000, message = "Description must be between 20 and 2000"))]
     pub description: String,
 
-    #[validate(length(min = 1, message = "State is required"))]
+    #[validate(length(min = 1, message = "State is required"), custom = "validate_state")]
     pub location_state: String,
 
     #[validate(length(min = 1, message = "City is required"))]
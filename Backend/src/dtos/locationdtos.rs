@@ -0,0 +1,23 @@
+use serde::{Serialize, Deserialize};
+use validator::Validate;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct LocationSearchQueryDto {
+    #[validate(length(min = 1, max = 100, message = "Query must be between 1 and 100 characters"))]
+    pub q: String,
+
+    /// Restricts matches to one state
+    pub state: Option<String>,
+
+    #[validate(range(min = 1, max = 50))]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ResolveLocationQueryDto {
+    #[validate(length(min = 2, max = 100, message = "State is required"))]
+    pub state: String,
+
+    pub lga: Option<String>,
+    pub city: Option<String>,
+}
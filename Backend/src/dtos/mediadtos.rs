@@ -0,0 +1,8 @@
+use serde::{Serialize, Deserialize};
+
+/// The signature on a private object's URL
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MediaFileQueryDto {
+    pub expires: Option<i64>,
+    pub signature: Option<String>,
+}
@@ -3,4 +3,12 @@ pub mod propertydtos;
 pub mod labordtos;
 pub mod naira_walletdtos;
 pub mod verificationdtos;
-pub mod vendordtos;
\ No newline at end of file
+pub mod vendordtos;
+pub mod notificationdtos;pub mod emaildtos;
+
+pub mod viewingdtos;
+pub mod tenancydtos;
+pub mod offerdtos;
+pub mod locationdtos;
+pub mod mediadtos;
+pub mod twofactordtos;
\ No newline at end of file
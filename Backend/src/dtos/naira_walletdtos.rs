@@ -52,6 +52,8 @@ pub struct WithdrawalRequestDto {
     pub transaction_pin: Option<String>,
     #[serde(default)]
     pub email_otp: Option<String>,
+    #[serde(default)]
+    pub totp_code: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Validate)]
@@ -64,11 +66,13 @@ pub struct TransferRequestDto {
     
     #[validate(length(min = 1, max = 200, message = "Description must be between 1 and 200 characters"))]
     pub description: String,
-    // Security fields
+    // Security fields, needed for large transfers unless the session stepped up
     // #[serde(default)]
     // pub transaction_pin: Option<String>,
-    // #[serde(default)]
-    // pub email_otp: Option<String>,
+    #[serde(default)]
+    pub email_otp: Option<String>,
+    #[serde(default)]
+    pub totp_code: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -105,6 +109,23 @@ fn validate_account_number(account_number: &str) -> Result<(), validator::Valida
     }
 }
 
+fn validate_bvn(bvn: &str) -> Result<(), validator::ValidationError> {
+    if bvn.chars().all(|c| c.is_ascii_digit()) && bvn.len() == 11 {
+        Ok(())
+    } else {
+        Err(validator::ValidationError::new("bvn must be 11 digits"))
+    }
+}
+
+// BVN DTOs
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct VerifyBvnDto {
+    #[validate(custom = "validate_bvn")]
+    pub bvn: String,
+    // Only used, and then kept, when the account has no date of birth
+    pub dob: Option<chrono::NaiveDate>,
+}
+
 // Bank Account DTOs
 #[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct AddBankAccountDto {
@@ -0,0 +1,90 @@
+// dtos/notificationdtos.rs
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::models::notificationmodels::*;
+
+#[derive(Validate, Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelPreferenceDto {
+    pub category: NotificationCategory,
+    pub channel: NotificationChannel,
+    pub enabled: bool,
+}
+
+#[derive(Validate, Debug, Clone, Serialize, Deserialize)]
+pub struct QuietHoursDto {
+    pub enabled: bool,
+
+    #[validate(range(min = 0, max = 23, message = "Start hour must be between 0 and 23"))]
+    pub start_hour: i16,
+
+    #[validate(range(min = 0, max = 23, message = "End hour must be between 0 and 23"))]
+    pub end_hour: i16,
+
+    #[validate(range(min = -720, max = 840, message = "UTC offset must be between -720 and 840 minutes"))]
+    pub utc_offset_minutes: Option<i32>,
+}
+
+#[derive(Validate, Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateNotificationPreferencesDto {
+    #[validate]
+    pub preferences: Option<Vec<ChannelPreferenceDto>>,
+
+    #[validate]
+    pub quiet_hours: Option<QuietHoursDto>,
+
+    pub digest_frequency: Option<DigestFrequency>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategoryPreferencesDto {
+    pub category: NotificationCategory,
+    pub in_app: bool,
+    pub email: bool,
+    pub sms: bool,
+    pub whatsapp: bool,
+    pub push: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NotificationPreferencesResponseDto {
+    pub preferences: Vec<CategoryPreferencesDto>,
+    pub quiet_hours: QuietHoursDto,
+    pub digest_frequency: DigestFrequency,
+}
+
+impl NotificationPreferencesResponseDto {
+    pub fn from_preference_set(set: &NotificationPreferenceSet) -> Self {
+        let preferences = NotificationCategory::all()
+            .into_iter()
+            .map(|category| CategoryPreferencesDto {
+                category,
+                in_app: set.is_enabled(category, NotificationChannel::InApp),
+                email: set.is_enabled(category, NotificationChannel::Email),
+                sms: set.is_enabled(category, NotificationChannel::Sms),
+                whatsapp: set.is_enabled(category, NotificationChannel::WhatsApp),
+                push: set.is_enabled(category, NotificationChannel::Push),
+            })
+            .collect();
+
+        Self {
+            preferences,
+            quiet_hours: QuietHoursDto {
+                enabled: set.settings.quiet_hours_enabled,
+                start_hour: set.settings.quiet_hours_start,
+                end_hour: set.settings.quiet_hours_end,
+                utc_offset_minutes: Some(set.settings.utc_offset_minutes),
+            },
+            digest_frequency: set.settings.digest_frequency,
+        }
+    }
+}
+
+#[derive(Validate, Debug, Clone, Serialize, Deserialize)]
+pub struct PushSubscriptionDto {
+    #[validate(length(min = 10, max = 4096, message = "Invalid push token"))]
+    pub token: String,
+
+    #[validate(length(min = 1, max = 20, message = "Platform must be between 1 and 20 characters"))]
+    pub platform: Option<String>,
+}
@@ -0,0 +1,36 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use validator::Validate;
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct MakeOfferDto {
+    pub property_id: Uuid,
+
+    #[validate(range(min = 1000, message = "Offer must be at least 1000"))]
+    pub amount: i64,
+
+    #[validate(length(max = 2000, message = "Conditions must be at most 2000 characters"))]
+    pub conditions: Option<String>,
+
+    /// Defaults to a week from now
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CounterOfferDto {
+    #[validate(range(min = 1000, message = "Counter offer must be at least 1000"))]
+    pub amount: i64,
+
+    #[validate(length(max = 2000, message = "Conditions must be at most 2000 characters"))]
+    pub conditions: Option<String>,
+
+    /// Defaults to a week from now
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct OfferNoteDto {
+    #[validate(length(min = 3, max = 1000, message = "Reason must be between 3 and 1000 characters"))]
+    pub reason: String,
+}
@@ -3,8 +3,9 @@ use uuid::Uuid;
 use validator::Validate;
 use chrono::{DateTime, Utc};
 use crate::models::propertymodel::{
-    CurrencyType, ListingType, Property, PropertyType
+    CurrencyType, ListingType, Property, PropertyType, VerifierRole
 };
+use crate::utils::gazetteer::{self, validate_state, Location};
 use sqlx::types::BigDecimal;
 use serde_json::Value as JsonValue;
 
@@ -27,7 +28,7 @@ pub struct CreatePropertyDto {
     #[validate(length(min = 2, max = 100, message = "City is required"))]
     pub city: String,
 
-    #[validate(length(min = 2, max = 100, message = "State is required"))]
+    #[validate(length(min = 2, max = 100, message = "State is required"), custom = "validate_state")]
     pub state: String,
 
     #[validate(length(min = 2, max = 100, message = "LGA is required"))]
@@ -52,6 +53,9 @@ pub struct CreatePropertyDto {
     pub price: i64,
     pub currency: CurrencyType,
     pub price_negotiable: Option<bool>,
+    /// Charged to anyone booking a viewing, paid to the agent once it happens
+    #[validate(range(min = 0, max = 1000000, message = "Inspection fee must be between 0 and 1,000,000"))]
+    pub inspection_fee: Option<i64>,
 
     //features
     pub amenities: Option<Vec<String>>,
@@ -68,22 +72,158 @@ pub struct CreatePropertyDto {
     pub property_photos: Vec<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewDecision {
+    Approved,
+    Rejected,
+}
+
+impl ReviewDecision {
+    pub fn to_str(&self) -> &str {
+        match self {
+            ReviewDecision::Approved => "approved",
+            ReviewDecision::Rejected => "rejected",
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct AgentVerificationDto {
-    pub property_id: Uuid,
-    pub verification_status: String,  //"approved" || "rejected"
+    pub verification_status: ReviewDecision,
+
+    #[validate(length(min = 10, max = 2000, message = "Notes must be between 10 and 2000 characters"))]
     pub notes: String,
+
+    #[validate(length(min = 1, message = "At least one verification photo is required"))]
     pub verification_photos: Vec<String>, //URLs of verification photos
 }
 
 #[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct LawyerVerificationDto {
-    pub property_id: Uuid,
-    pub verification_status: String,  //"approved" || "rejected"
+    pub verification_status: ReviewDecision,
+
+    #[validate(length(min = 10, max = 2000, message = "Notes must be between 10 and 2000 characters"))]
     pub notes: String,
     pub document_issues: Option<Vec<String>>,   //List of documents with issues if any
 }
 
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct AssignAgentDto {
+    pub agent_id: Uuid,
+}
+
+/// Admin search for listings that use a given picture. Send either a URL or
+/// the image itself, base64 encoded.
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_photo_search"))]
+pub struct PhotoSearchDto {
+    #[validate(url(message = "Image URL must be a valid URL"))]
+    pub image_url: Option<String>,
+
+    #[validate(length(max = 14_000_000, message = "Image must be under 10MB"))]
+    pub image_base64: Option<String>,
+
+    /// Maximum number of differing hash bits, 0-16
+    #[validate(range(min = 0, max = 16))]
+    pub max_distance: Option<i32>,
+
+    #[validate(range(min = 1, max = 50))]
+    pub limit: Option<i64>,
+}
+
+fn validate_photo_search(dto: &PhotoSearchDto) -> Result<(), validator::ValidationError> {
+    match (&dto.image_url, &dto.image_base64) {
+        (Some(_), None) | (None, Some(_)) => Ok(()),
+        _ => Err(validator::ValidationError::new("provide exactly one of image_url or image_base64")),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct PropertySearchQueryDto {
+    #[validate(range(min = 1))]
+    pub page: Option<usize>,
+    #[validate(range(min = 1, max = 50))]
+    pub limit: Option<usize>,
+
+    pub property_type: Option<PropertyType>,
+    pub listing_type: Option<ListingType>,
+    pub min_price: Option<i64>,
+    pub max_price: Option<i64>,
+    pub city: Option<String>,
+    pub state: Option<String>,
+    pub country: Option<String>,
+    pub bedrooms: Option<i32>,
+    pub bathrooms: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct MarketSummaryQueryDto {
+    #[validate(length(min = 2, max = 100, message = "State is required"), custom = "validate_state")]
+    pub state: String,
+    pub lga: Option<String>,
+    pub property_type: Option<PropertyType>,
+    pub listing_type: Option<ListingType>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct MarketTrendQueryDto {
+    #[validate(length(min = 2, max = 100, message = "State is required"), custom = "validate_state")]
+    pub state: String,
+    pub property_type: PropertyType,
+    pub listing_type: ListingType,
+}
+
+/// Same filters as the active listings search, under a name
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct SavedSearchDto {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: String,
+
+    pub property_type: Option<PropertyType>,
+    pub listing_type: Option<ListingType>,
+    #[validate(range(min = 0))]
+    pub min_price: Option<i64>,
+    #[validate(range(min = 0))]
+    pub max_price: Option<i64>,
+    #[validate(length(min = 2, max = 100))]
+    pub city: Option<String>,
+    #[validate(custom = "validate_state")]
+    pub state: Option<String>,
+    pub country: Option<String>,
+    #[validate(range(min = 0, max = 50))]
+    pub bedrooms: Option<i32>,
+    #[validate(range(min = 0, max = 50))]
+    pub bathrooms: Option<i32>,
+
+    /// Defaults to on
+    pub alerts_enabled: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct SearchAlertsDto {
+    pub alerts_enabled: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct UpdatePriceDto {
+    #[validate(range(min = 1000, message = "Price must be at least 1000"))]
+    pub price: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ListingStatsQueryDto {
+    #[validate(range(min = 1, max = 365))]
+    pub days: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct VerifierMetricsQueryDto {
+    pub verifier_type: Option<VerifierRole>,
+    #[validate(range(min = 1, max = 365))]
+    pub days: Option<i32>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PropertyFilterDto {
     pub id: Uuid,
@@ -94,9 +234,13 @@ pub struct PropertyFilterDto {
     pub landmark: Option<String>,
     pub city: String,
     pub state: String,
+    pub lga: String,
     pub country: String,
+    /// Gazetteer coordinates for the area, for maps when the listing has no pin
+    pub approximate_location: Option<Location>,
     pub price: i64,
     pub bidding_price: Option<i64>,
+    pub inspection_fee: Option<i64>,
     pub currency: String,
     pub status: String,
     pub bedroom: Option<i32>,
@@ -117,9 +261,12 @@ impl PropertyFilterDto {
             landmark: property.landmark.clone(), 
             city: property.city.clone(), 
             state: property.state.clone(), 
+            lga: property.lga.clone(),
             country: property.country.clone(), 
+            approximate_location: gazetteer::resolve(&property.state, Some(&property.lga), Some(&property.city)).ok(),
             price: property.price,
             bidding_price: property.bidding_price, 
+            inspection_fee: property.inspection_fee,
             currency: format!("{:?}", property.currency), 
             status: format!("{:?}", property.status), 
             bedroom: property.bedrooms, 
@@ -0,0 +1,71 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use validator::Validate;
+use chrono::{DateTime, NaiveDate, Utc};
+use crate::models::tenancymodels::RentFrequency;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct TenancyOfferDto {
+    pub property_id: Uuid,
+
+    #[validate(range(min = 1000, message = "Rent must be at least 1000"))]
+    pub rent_amount: i64,
+    pub rent_frequency: RentFrequency,
+
+    #[validate(range(min = 0, message = "Caution deposit cannot be negative"))]
+    pub caution_deposit: Option<i64>,
+
+    pub start_date: NaiveDate,
+
+    #[validate(range(min = 1, max = 120, message = "Tenancy must run between 1 and 120 months"))]
+    pub duration_months: u32,
+
+    #[validate(length(max = 1000, message = "Message must be at most 1000 characters"))]
+    pub message: Option<String>,
+}
+
+/// The landlord sets the collection terms when accepting
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct AcceptTenancyDto {
+    #[validate(range(min = 0, max = 30, message = "Grace period must be between 0 and 30 days"))]
+    pub grace_period_days: Option<i32>,
+
+    #[validate(range(min = 0, max = 25, message = "Late fee must be between 0 and 25 percent"))]
+    pub late_fee_percent: Option<i32>,
+
+    #[validate(length(max = 1000, message = "Note must be at most 1000 characters"))]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct TenancyNoteDto {
+    #[validate(length(min = 3, max = 1000, message = "Reason must be between 3 and 1000 characters"))]
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct MoveOutInspectionDto {
+    /// Naira kept from the caution deposit for damage or arrears
+    #[validate(range(min = 0, message = "Deduction cannot be negative"))]
+    pub deduction: i64,
+
+    #[validate(length(min = 10, max = 2000, message = "Notes must be between 10 and 2000 characters"))]
+    pub notes: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RentReceiptDto {
+    pub receipt_number: String,
+    pub payment_id: Uuid,
+    pub tenancy_id: Uuid,
+    pub property_title: String,
+    pub property_address: String,
+    pub tenant_name: String,
+    pub landlord_name: String,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub rent: i64,
+    pub late_fee: i64,
+    pub total: i64,
+    pub paid_at: DateTime<Utc>,
+}
@@ -0,0 +1,29 @@
+use serde::{Serialize, Deserialize};
+use validator::Validate;
+
+/// A code from the authenticator app, or a recovery code where allowed
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct TwoFactorCodeDto {
+    #[validate(length(min = 6, max = 20, message = "Code must be between 6 and 20 characters"))]
+    pub code: String,
+}
+
+/// Second step of a login for accounts with two-factor enabled
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct TwoFactorLoginDto {
+    #[validate(length(min = 1, message = "Challenge token is required"))]
+    pub challenge_token: String,
+    #[validate(length(min = 6, max = 20, message = "Code must be between 6 and 20 characters"))]
+    pub code: String,
+    #[validate(length(max = 100, message = "Device name must be at most 100 characters"))]
+    pub device_name: Option<String>,
+}
+
+/// Either code confirms a sensitive action
+#[derive(Debug, Default, Serialize, Deserialize, Validate)]
+pub struct StepUpDto {
+    #[validate(length(equal = 6, message = "Authenticator code must be 6 digits"))]
+    pub totp_code: Option<String>,
+    #[validate(length(equal = 6, message = "Email code must be 6 digits"))]
+    pub email_otp: Option<String>,
+}
@@ -8,7 +8,8 @@ use serde_json;
 use std::borrow::Cow;
 
 
-use crate::models::{subscriptionmodels::SubscriptionTier, usermodel::*};
+use crate::models::{sessionmodels::UserSession, subscriptionmodels::SubscriptionTier, usermodel::*};
+use crate::utils::gazetteer::validate_lga;
 
 #[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
 pub struct UpdateUserProfileDto {
@@ -18,7 +19,8 @@ pub struct UpdateUserProfileDto {
     pub phone_number: Option<String>,
 
     #[validate(
-        length(min = 2, max = 100, message = "LGA must be between 2-100 characters")
+        length(min = 2, max = 100, message = "LGA must be between 2-100 characters"),
+        custom = "validate_lga"
     )]
     pub lga: Option<String>,
 
@@ -32,13 +34,9 @@ pub struct UpdateUserProfileDto {
 impl UpdateUserProfileDto {
     pub fn validate_phone_number(&self) -> Result<(), ValidationError> {
         if let Some(phone) = &self.phone_number {
-            // Basic phone number validation - supports international formats
-            let phone_regex = regex::Regex::new(r"^(\+?[0-9]{1,3}[- ]?)?[0-9]{3}[- ]?[0-9]{3}[- ]?[0-9]{4}$")
-                .map_err(|_| ValidationError::new("Invalid phone regex"))?;
-            
-            if !phone_regex.is_match(phone) {
+            if crate::utils::phone::normalize(phone).is_none() {
                 let mut error = ValidationError::new("invalid_phone");
-                error.message = Some(Cow::from("Phone number must be in a valid format (e.g., +1234567890 or 123-456-7890)"));
+                error.message = Some(Cow::from("Phone number must be a valid mobile number (e.g., 08031234567 or +2348031234567)"));
                 return Err(error);
             }
         }
@@ -73,6 +71,10 @@ pub struct RegisterUserWithReferralDto {
     pub password_confirm: String,
 
     pub referral_code: Option<String>, //added
+
+    /// When given, a code is texted to verify it straight away
+    #[validate(length(max = 20, message = "Phone number must be at most 20 characters"))]
+    pub phone_number: Option<String>,
 }
 
 #[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
@@ -86,13 +88,52 @@ pub struct ResendVerificationEmailDto {
 
 #[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
 pub struct LoginUserDto {
-    #[validate(length(min = 1, message = "Email is required"), email(message = "Email is invalid"))]
-    pub email: String,
+    /// Email, username or verified phone number
+    #[serde(alias = "email")]
+    #[validate(length(min = 1, max = 255, message = "Email, username or phone number is required"))]
+    pub identifier: String,
     #[validate(
         length(min = 1, message = "Password is required"),
         length(min = 6, message = "Password must be at least 6 characters")
     )]
     pub password: String,
+    /// Shown in the sessions list; guessed from the user agent when absent
+    #[validate(length(max = 100, message = "Device name must be at most 100 characters"))]
+    pub device_name: Option<String>,
+}
+
+/// The refresh token may come in the body or the refresh_token cookie
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct RefreshTokenDto {
+    pub refresh_token: Option<String>,
+}
+
+/// A signed-in device, as shown in the user's sessions list
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionDto {
+    pub id: Uuid,
+    pub device_name: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub current: bool, // The session making this request
+}
+
+impl SessionDto {
+    pub fn from_session(session: UserSession, current_session_id: Uuid) -> Self {
+        Self {
+            current: session.id == current_session_id,
+            id: session.id,
+            device_name: session.device_name,
+            ip_address: session.ip_address,
+            user_agent: session.user_agent,
+            created_at: session.created_at,
+            last_used_at: session.last_used_at,
+            expires_at: session.expires_at,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Validate)]
@@ -127,6 +168,7 @@ pub struct FilterUserDto {
     pub verification_status: Option<String>,
     pub wallet_address: Option<String>,
     pub avatar_url: Option<String>,
+    pub locale: String,
     #[serde(rename = "createdAt")]
     pub created_at: DateTime<Utc>,
     #[serde(rename = "updatedAt")]
@@ -158,6 +200,7 @@ impl FilterUserDto {
             wallet_address: user.wallet_address.clone(),
             avatar_url: user.avatar_url.clone(),
             role: user.role.to_str().to_string(),
+            locale: user.locale.to_str().to_string(),
             created_at: user.created_at,
             updated_at: user.updated_at,
         }
@@ -186,6 +229,9 @@ pub struct UserListResponseDto {
 pub struct UserLoginResponseDto {
     pub status: String,
     pub token: String,
+    pub refresh_token: String,
+    pub session_id: Uuid,
+    pub expires_in: i64, // Seconds until `token` expires
 }
 
 #[derive(Serialize, Deserialize)]
@@ -200,6 +246,13 @@ pub struct NameUpdateDto {
     pub name: String,
 }
 
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct LocaleUpdateDto {
+    /// Language tag: en, pcm, yo, ha or ig (regional forms like yo-NG are accepted)
+    #[validate(length(min = 2, max = 10, message = "Locale is required"))]
+    pub locale: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct RoleUpdateDto {
     #[validate(custom = "validate_user_role")]
@@ -207,6 +260,13 @@ pub struct RoleUpdateDto {
     pub target_user_id: Uuid, 
 }
 
+/// An admin granting a role on top of the ones a user already holds
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct GrantRoleDto {
+    #[validate(custom = "validate_user_role")]
+    pub role: UserRole,
+}
+
 fn validate_user_role(role: &UserRole) -> Result<(), validator::ValidationError> {
     match role {
         UserRole::Admin |
@@ -440,6 +500,11 @@ pub struct CheckUsernameQuery {
     pub username: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct LoginHistoryQuery {
+    pub limit: Option<i64>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct UsernameCheckResponse {
     pub available: bool,
@@ -4,16 +4,21 @@ use serde::{Deserialize, Serialize};
 use validator::Validate;
 
 use crate::models::{
+    kycmodels::{KycCheck, KycEntitlements, KycFacts, KycLevel, KycRequirement},
     usermodel::{VerificationStatus, VerificationType, User},
     verificationmodels::*,
 };
 
 #[derive(Validate, Debug, Clone, Serialize, Deserialize)]
 pub struct OtpRequestDto {
+    /// Defaults to the account's address
     #[validate(email(message = "Valid email is required"))]
-    pub email: String,
+    pub email: Option<String>,
     
     pub purpose: OtpPurpose,
+
+    /// Defaults to the user's saved channel
+    pub channel: Option<OtpChannel>,
 }
 
 #[derive(Validate, Debug, Clone, Serialize, Deserialize)]
@@ -75,6 +80,82 @@ pub struct ReviewVerificationDto {
     pub review_notes: Option<String>,
 }
 
+/// A submission in the reviewer queue with what the automated checks found
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PendingVerificationDto {
+    #[serde(flatten)]
+    pub verification: VerificationDocument,
+    pub automated_check: Option<KycCheck>,
+}
+
+impl PendingVerificationDto {
+    pub fn attach(verifications: Vec<VerificationDocument>, checks: Vec<KycCheck>) -> Vec<Self> {
+        verifications
+            .into_iter()
+            .map(|verification| {
+                let automated_check = checks.iter().find(|check| check.verification_id == verification.id).cloned();
+                Self { verification, automated_check }
+            })
+            .collect()
+    }
+}
+
+#[derive(Validate, Debug, Clone, Serialize, Deserialize)]
+pub struct AddressProofDto {
+    #[validate(length(min = 1, message = "Document image is required"))]
+    pub document_url: String,
+
+    #[validate(length(min = 10, max = 300, message = "Address must be between 10 and 300 characters"))]
+    pub address: String,
+}
+
+#[derive(Validate, Debug, Clone, Serialize, Deserialize)]
+pub struct PhoneNumberDto {
+    #[validate(length(min = 7, max = 20, message = "Phone number must be between 7 and 20 characters"))]
+    pub phone_number: String,
+}
+
+#[derive(Validate, Debug, Clone, Serialize, Deserialize)]
+pub struct ConfirmPhoneDto {
+    #[validate(length(min = 7, max = 20, message = "Phone number must be between 7 and 20 characters"))]
+    pub phone_number: String,
+
+    #[validate(length(min = 6, max = 6, message = "OTP must be 6 digits"))]
+    pub otp_code: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OtpChannelDto {
+    pub channel: OtpChannel,
+}
+
+/// Where a user stands on KYC and exactly what the next level needs
+#[derive(Debug, Serialize)]
+pub struct KycProgressDto {
+    pub level: KycLevel,
+    pub entitlements: KycEntitlements,
+    pub next_level: Option<KycLevel>,
+    pub next_entitlements: Option<KycEntitlements>,
+    pub requirements: Vec<KycRequirement>,
+}
+
+impl KycProgressDto {
+    pub fn from_facts(facts: &KycFacts) -> Self {
+        let level = KycLevel::from_facts(facts);
+        let next_level = level.next();
+
+        Self {
+            level,
+            entitlements: level.entitlements(),
+            next_level,
+            next_entitlements: next_level.map(|next| next.entitlements()),
+            requirements: next_level
+                .map(|next| KycLevel::requirements_for(next, facts))
+                .unwrap_or_default(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VerificationStatusDto {
     pub verification_status: Option<VerificationStatus>,
@@ -0,0 +1,73 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+use chrono::{DateTime, Utc};
+use crate::models::viewingmodels::NoShowParty;
+
+fn validate_future_slots(slots: &[DateTime<Utc>]) -> Result<(), ValidationError> {
+    let now = Utc::now();
+    if slots.iter().any(|slot| *slot <= now) {
+        let mut error = ValidationError::new("past_slot");
+        error.message = Some("Proposed viewing times must be in the future".into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct RequestViewingDto {
+    pub property_id: Uuid,
+
+    #[validate(
+        length(min = 1, max = 5, message = "Propose between 1 and 5 viewing times"),
+        custom = "validate_future_slots"
+    )]
+    pub proposed_slots: Vec<DateTime<Utc>>,
+
+    #[validate(length(max = 1000, message = "Message must be at most 1000 characters"))]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ConfirmViewingDto {
+    /// Must be one of the requester's proposed slots
+    pub scheduled_at: DateTime<Utc>,
+
+    #[validate(length(max = 1000, message = "Note must be at most 1000 characters"))]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ViewingNoteDto {
+    #[validate(length(min = 3, max = 1000, message = "Reason must be between 3 and 1000 characters"))]
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ReportNoShowDto {
+    /// Who did not turn up; each side can only report the other
+    pub absent_party: NoShowParty,
+
+    #[validate(length(max = 1000, message = "Note must be at most 1000 characters"))]
+    pub note: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_viewing_request_rejects_past_or_missing_slots() {
+        let request = |slots: Vec<DateTime<Utc>>| RequestViewingDto {
+            property_id: Uuid::new_v4(),
+            proposed_slots: slots,
+            message: None,
+        };
+
+        assert!(request(vec![Utc::now() + Duration::days(1)]).validate().is_ok());
+        assert!(request(vec![]).validate().is_err());
+        assert!(request(vec![Utc::now() + Duration::days(1), Utc::now() - Duration::hours(1)]).validate().is_err());
+        assert!(request((1..=6).map(|day| Utc::now() + Duration::days(day)).collect()).validate().is_err());
+    }
+}
@@ -1,35 +1,43 @@
 //12
-use std::sync::Arc;
+use std::{convert::Infallible, net::{IpAddr, SocketAddr}, sync::Arc};
 
-use axum::{extract::Query, http::{header, HeaderMap}, response::{IntoResponse, Redirect}, routing::{get, post}, Extension, Json, Router};
-use axum_extra::extract::cookie::Cookie;
+use axum::{async_trait, extract::{ConnectInfo, FromRequestParts, Query}, http::{header, request::Parts, HeaderMap}, response::{IntoResponse, Redirect}, routing::{get, post}, Extension, Json, Router};
+use axum_extra::extract::cookie::{Cookie, CookieJar};
 use chrono::{Utc, Duration};
-use redis::aio::ConnectionManager;
 use validator::Validate;
 use uuid::Uuid;
 use regex;
 
 use crate::{
-    AppState, db::userdb::UserExt, dtos::userdtos::{
-        CheckUsernameQuery, FilterUserDto, ForgotPasswordRequestDto, LoginUserDto, RegisterUserWithReferralDto, ResendVerificationEmailDto, ResetPasswordRequestDto, Response, UserData, UserLoginResponseDto, UserResponseDto, UsernameCheckResponse, VerifyEmailQueryDto,
-    }, error::{ErrorMessage, HttpError}, mail::mails::{
+    AppState, db::userdb::UserExt, dtos::twofactordtos::TwoFactorLoginDto, dtos::userdtos::{
+        CheckUsernameQuery, FilterUserDto, ForgotPasswordRequestDto, LoginUserDto, RefreshTokenDto, RegisterUserWithReferralDto, ResendVerificationEmailDto, ResetPasswordRequestDto, Response, UserData, UserLoginResponseDto, UserResponseDto, UsernameCheckResponse, VerifyEmailQueryDto,
+    }, error::{ErrorMessage, HttpError}, models::usermodel::Locale, mail::mails::{
         send_forgot_password_email, send_verification_email, send_welcome_email
-    }, service::referral::generate_referral_code, utils::{password, token}};
+    }, models::sessionmodels::{LoginOutcome, SessionClient}, service::{attempt_guard::AttemptKind, referral::generate_referral_code, session_service::{IssuedTokens, REVOKED_LOGOUT, REVOKED_PASSWORD_CHANGE}, two_factor_service::TWO_FACTOR_CHALLENGE_MINUTES}, utils::{password, phone, token}};
+
+/// Header and cookie naming a browser or app install across logins
+const DEVICE_ID_HEADER: &str = "x-device-id";
+const DEVICE_ID_COOKIE: &str = "device_id";
+/// Set by Cloudflare in front of the API
+const COUNTRY_HEADER: &str = "cf-ipcountry";
 
 pub fn auth_handler() -> Router {
     Router::new()
         .route("/register", post(register))
         .route("/login", post(login))
+        .route("/login/2fa", post(login_two_factor))
         .route("/verify", get(verify_email))
         .route("/check-username", get(check_username_availability))
         .route("/resend-verification", post(resend_verification_email))
         .route("/forgot-password", post(forgot_password))
         .route("/reset-password", post(reset_password))
+        .route("/refresh", post(refresh_session))
         .route("/logout", post(logout))  
 }
 
 pub async fn register(
     Extension(app_state): Extension<Arc<AppState>>,
+    headers: HeaderMap,
     Json(body): Json<RegisterUserWithReferralDto>,
 ) -> Result<impl IntoResponse, HttpError> {
     body.validate()
@@ -45,6 +53,14 @@ pub async fn register(
         return Err(HttpError::bad_request("Email already registered"));
     }
 
+    let phone_number = body.phone_number
+        .as_deref()
+        .filter(|phone_number| !phone_number.trim().is_empty())
+        .map(|phone_number| {
+            phone::normalize(phone_number).ok_or_else(|| HttpError::bad_request("Phone number is invalid"))
+        })
+        .transpose()?;
+
     // Check if referral code exists and get referrer
     let mut referrer_id: Option<Uuid> = None;
     if let Some(ref code) = body.referral_code {
@@ -84,11 +100,24 @@ pub async fn register(
 
     // Generate and save referral code for the new user
     let referral_code = generate_referral_code();
-    let user_with_code = app_state.db_client
+    let mut user_with_code = app_state.db_client
         .update_user_referral_code(user.id, referral_code.clone())
         .await
         .map_err(|e| HttpError::server_error(e.to_string()))?;
 
+    // Start new users in their browser's language when we support it
+    let locale = headers
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(Locale::from_accept_language)
+        .unwrap_or_default();
+    if locale != user_with_code.locale {
+        user_with_code = app_state.db_client
+            .update_user_locale(user.id, locale)
+            .await
+            .map_err(|e| HttpError::server_error(e.to_string()))?;
+    }
+
     // Process referral if applicable
     if let Some(ref_id) = referrer_id {
         // Add points to referrer
@@ -119,10 +148,17 @@ pub async fn register(
     
     // Send verification email
     tracing::info!("sending a verification email");
-    send_verification_email(&user.email, &user.username,&verification_token)
+    send_verification_email(&user.email, &user.username, &verification_token, user_with_code.locale)
         .await
         .map_err(|e| HttpError::server_error(e.to_string()))?;
 
+    // The account exists either way; the number can be verified later
+    if let Some(phone_number) = phone_number {
+        if let Err(e) = app_state.otp_service.start_phone_verification(&user_with_code, &phone_number).await {
+            tracing::warn!("Could not start phone verification for {}: {}", user_with_code.id, e);
+        }
+    }
+
     let filtered_user = FilterUserDto::filter_user(&user_with_code);
     
     Ok(Json(UserResponseDto {
@@ -133,60 +169,278 @@ pub async fn register(
 
 pub async fn login(
     Extension(app_state): Extension<Arc<AppState>>,
+    headers: HeaderMap,
+    ip: ClientIp,
     Json(body): Json<LoginUserDto>
 ) -> Result<impl IntoResponse, HttpError> {
     body.validate()
        .map_err(|e| HttpError::bad_request(e.to_string()))?;
 
+    let client = session_client(&headers, ip, body.device_name.clone());
+    let identifier = body.identifier.trim();
+
     let result = app_state.db_client
-        .get_user(None, None, Some(&body.email), None)
+        .get_user_by_identifier(identifier)
         .await
         .map_err(|e| HttpError::server_error(e.to_string()))?;
+    let user_id = result.as_ref().map(|user| user.id);
+
+    // Failures count against the account rather than the identifier, so
+    // switching between email, username and phone earns no extra tries
+    let account = user_id.map(|id| id.to_string()).unwrap_or_else(|| identifier.to_lowercase());
+    if let Err(e) = app_state.attempt_guard
+        .check(AttemptKind::Login, &account, client.ip_address.as_deref())
+        .await
+    {
+        app_state.login_activity_service
+            .record_failure(user_id, identifier, LoginOutcome::Blocked, &client)
+            .await;
+        return Err(e.into());
+    }
+
+    let password_matched = match &result {
+        Some(user) => password::compare(&body.password, Some(user.password.as_deref().unwrap_or("")))
+            .map_err(|e| HttpError::bad_request(e.to_string()))?,
+        None => false,
+    };
+
+    let Some(user) = result.filter(|_| password_matched) else {
+        if app_state.attempt_guard
+            .record_failure(AttemptKind::Login, &account, client.ip_address.as_deref())
+            .await
+        {
+            tracing::warn!("Login locked out for {} after repeated failures", account);
+        }
+        app_state.login_activity_service
+            .record_failure(user_id, identifier, LoginOutcome::Failed, &client)
+            .await;
+        return Err(HttpError::bad_request(ErrorMessage::WrongCredentials.to_string()));
+    };
+    app_state.attempt_guard.record_success(AttemptKind::Login, &account).await;
+
+    // The session only starts once the second factor checks out
+    if app_state.two_factor_service.is_enabled(user.id).await? {
+        let challenge_token = app_state.two_factor_service.login_challenge(user.id)?;
+
+        let response = Json(serde_json::json!({
+            "status": "two_factor_required",
+            "challenge_token": challenge_token,
+            "expires_in": TWO_FACTOR_CHALLENGE_MINUTES * 60
+        }))
+        .into_response();
+        return Ok(with_device_cookie(response, &client));
+    }
 
-    let user = result.ok_or(HttpError::bad_request(ErrorMessage::WrongCredentials.to_string()))?;
+    let tokens = app_state.session_service
+        .start(user.id, client.clone())
+        .await?;
+    app_state.login_activity_service.record_success(&user, &client).await;
 
-    let password_matched = password::compare(&body.password, Some(user.password.as_deref().unwrap_or("")))
+    Ok(with_device_cookie(session_response(&app_state, tokens), &client))
+}
+
+/// Finishes a login for accounts with two-factor enabled, taking an
+/// authenticator code or a recovery code
+pub async fn login_two_factor(
+    Extension(app_state): Extension<Arc<AppState>>,
+    headers: HeaderMap,
+    ip: ClientIp,
+    Json(body): Json<TwoFactorLoginDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
         .map_err(|e| HttpError::bad_request(e.to_string()))?;
 
-    if password_matched {
-        let token = token::create_token(
-            &user.id.to_string(), 
-            &app_state.env.jwt_secret.as_bytes(), 
-            app_state.env.jwt_maxage
-        )
-        .map_err(|e| HttpError::server_error(e.to_string()))?;
+    let client = session_client(&headers, ip, body.device_name);
+    let user_id = app_state.two_factor_service
+        .complete_login(&body.challenge_token, &body.code)
+        .await?;
 
-        let cookie_duration = time::Duration::minutes(app_state.env.jwt_maxage * 60);
-        let cookie = Cookie::build(("token", token.clone()))
-            .path("/")
-            .max_age(cookie_duration)
-            .http_only(true)
-            .build();
+    let tokens = app_state.session_service
+        .start(user_id, client.clone())
+        .await?;
 
-        let response = axum::response::Json(UserLoginResponseDto {
-            status: "success".to_string(),
-            token,
-        });
+    if let Some(user) = app_state.db_client
+        .get_user(Some(user_id), None, None, None)
+        .await
+        .map_err(|e| HttpError::server_error(e.to_string()))?
+    {
+        app_state.login_activity_service.record_success(&user, &client).await;
+    }
 
-        let mut headers = HeaderMap::new();
+    Ok(with_device_cookie(session_response(&app_state, tokens), &client))
+}
+
+/// Rotates the refresh token and issues a new access token. The refresh
+/// token is single use; replaying an old one signs the session out.
+pub async fn refresh_session(
+    Extension(app_state): Extension<Arc<AppState>>,
+    headers: HeaderMap,
+    ip: ClientIp,
+    jar: CookieJar,
+    body: Option<Json<RefreshTokenDto>>,
+) -> Result<impl IntoResponse, HttpError> {
+    let refresh_token = body
+        .and_then(|Json(body)| body.refresh_token)
+        .or_else(|| jar.get("refresh_token").map(|cookie| cookie.value().to_string()))
+        .ok_or_else(|| HttpError::unauthorized("Refresh token not provided"))?;
+
+    let (_, tokens) = app_state.session_service
+        .refresh(&refresh_token, session_client(&headers, ip, None))
+        .await?;
+
+    Ok(session_response(&app_state, tokens))
+}
+
+/// The device a request comes from, for the sessions list and login history.
+/// Requests without a device id get a fresh one, kept by `device_cookie`.
+pub(crate) fn session_client(headers: &HeaderMap, ip: ClientIp, device_name: Option<String>) -> SessionClient {
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.chars().take(500).collect::<String>());
+
+    // Apps send a header; browsers carry the cookie
+    let device_id = headers
+        .get(DEVICE_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+        .or_else(|| CookieJar::from_headers(headers).get(DEVICE_ID_COOKIE).map(|c| c.value().to_string()))
+        .filter(|id| (8..=64).contains(&id.len()) && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'))
+        .unwrap_or_else(|| Uuid::new_v4().simple().to_string());
+
+    // "XX" is unknown; "T1" (Tor) fails the letters check
+    let country = headers
+        .get(COUNTRY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim().to_ascii_uppercase())
+        .filter(|code| code.len() == 2 && code.chars().all(|c| c.is_ascii_uppercase()) && code != "XX");
+
+    SessionClient {
+        device_name,
+        ip_address: ip.as_string(),
+        user_agent,
+        device_id: Some(device_id),
+        country,
+    }
+}
+
+/// The caller's IP, for attempt limits and login history. Behind
+/// `TRUSTED_PROXY_HOPS` proxies it is the X-Forwarded-For entry the outermost
+/// one added; with none it is the socket peer.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ClientIp(pub Option<IpAddr>);
+
+impl ClientIp {
+    pub fn as_string(&self) -> Option<String> {
+        self.0.map(|ip| ip.to_string())
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ClientIp {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let trusted_hops = parts.extensions
+            .get::<Arc<AppState>>()
+            .map(|app_state| app_state.env.trusted_proxy_hops)
+            .unwrap_or(0);
+        let peer = parts.extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+
+        Ok(ClientIp(client_ip(&parts.headers, peer, trusted_hops)))
+    }
+}
+
+/// Each proxy appends the address it was reached from, so everything left of
+/// the last `trusted_hops` entries came from the client and can be forged
+fn client_ip(headers: &HeaderMap, peer: Option<IpAddr>, trusted_hops: usize) -> Option<IpAddr> {
+    if trusted_hops == 0 {
+        return peer;
+    }
 
-        headers.append(
+    let forwarded: Vec<&str> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(str::trim)
+        .collect();
+
+    forwarded
+        .len()
+        .checked_sub(trusted_hops)
+        .and_then(|index| forwarded.get(index))
+        .and_then(|ip| ip.parse().ok())
+}
+
+/// Names the browser so later logins from it are recognised
+pub(crate) fn device_cookie(device_id: &str) -> Cookie<'static> {
+    Cookie::build((DEVICE_ID_COOKIE, device_id.to_string()))
+        .path("/")
+        .max_age(time::Duration::days(400))
+        .http_only(true)
+        .build()
+}
+
+fn with_device_cookie(mut response: axum::response::Response, client: &SessionClient) -> axum::response::Response {
+    if let Some(device_id) = &client.device_id {
+        response.headers_mut().append(
             header::SET_COOKIE,
-            cookie.to_string().parse().unwrap(), 
+            device_cookie(device_id).to_string().parse().unwrap(),
         );
+    }
+    response
+}
 
-        let mut response = response.into_response();
-        response.headers_mut().extend(headers);
+/// The access token cookie, living as long as the token
+pub(crate) fn access_token_cookie(app_state: &AppState, token: &str) -> Cookie<'static> {
+    Cookie::build(("token", token.to_string()))
+        .path("/")
+        .max_age(time::Duration::minutes(app_state.session_service.access_token_minutes()))
+        .http_only(true)
+        .build()
+}
 
-        Ok(response)
-    } else {
-        Err(HttpError::bad_request(ErrorMessage::WrongCredentials.to_string()))
-    }
+/// The refresh token cookie, only ever sent back to the auth routes
+pub(crate) fn refresh_token_cookie(app_state: &AppState, token: &str) -> Cookie<'static> {
+    Cookie::build(("refresh_token", token.to_string()))
+        .path("/api/auth")
+        .max_age(time::Duration::days(app_state.session_service.refresh_token_days()))
+        .http_only(true)
+        .build()
+}
+
+fn session_response(app_state: &AppState, tokens: IssuedTokens) -> axum::response::Response {
+    let mut headers = HeaderMap::new();
+    headers.append(
+        header::SET_COOKIE,
+        access_token_cookie(app_state, &tokens.access_token).to_string().parse().unwrap(),
+    );
+    headers.append(
+        header::SET_COOKIE,
+        refresh_token_cookie(app_state, &tokens.refresh_token).to_string().parse().unwrap(),
+    );
+
+    let mut response = Json(UserLoginResponseDto {
+        status: "success".to_string(),
+        token: tokens.access_token,
+        refresh_token: tokens.refresh_token,
+        session_id: tokens.session_id,
+        expires_in: tokens.expires_in,
+    })
+    .into_response();
+    response.headers_mut().extend(headers);
+
+    response
 }
 
 pub async fn verify_email(
     Query(query_params): Query<VerifyEmailQueryDto>,
-    Extension(app_state): Extension<Arc<AppState>>
+    Extension(app_state): Extension<Arc<AppState>>,
+    request_headers: HeaderMap,
+    ip: ClientIp,
 ) -> Result<impl IntoResponse, HttpError> {
     query_params.validate()
         .map_err(|e| HttpError::bad_request(e.to_string()))?;
@@ -209,30 +463,25 @@ pub async fn verify_email(
     app_state.db_client.verifed_token(&query_params.token).await
         .map_err(|e| HttpError::server_error(e.to_string()))?;
 
-    let send_welcome_email_result = send_welcome_email(&user.email, &user.username).await;
-
-    if let Err(e) = send_welcome_email_result {
+    if let Err(e) = send_welcome_email(&user.email, &user.username, user.locale).await {
         eprintln!("Failed to send welcome email: {}", e);
     }
 
-    let token = token::create_token(
-        &user.id.to_string(), 
-        app_state.env.jwt_secret.as_bytes(),
-        app_state.env.jwt_maxage 
-    ).map_err(|e| HttpError::server_error(e.to_string()))?;
-
-    let cookie_duration = time::Duration::minutes(app_state.env.jwt_maxage * 60);
-    let cookie = Cookie::build(("token", token.clone()))
-        .path("/")
-        .max_age(cookie_duration)
-        .http_only(true)
-        .build();
+    let client = session_client(&request_headers, ip, None);
+    let tokens = app_state.session_service
+        .start(user.id, client.clone())
+        .await?;
+    app_state.login_activity_service.record_success(&user, &client).await;
 
     let mut headers = HeaderMap::new();
 
     headers.append(
         header::SET_COOKIE,
-        cookie.to_string().parse().unwrap() 
+        access_token_cookie(&app_state, &tokens.access_token).to_string().parse().unwrap()
+    );
+    headers.append(
+        header::SET_COOKIE,
+        refresh_token_cookie(&app_state, &tokens.refresh_token).to_string().parse().unwrap()
     );
 
     let frontend_url = format!("https://verinest.xyz/login");
@@ -243,7 +492,7 @@ pub async fn verify_email(
 
     response.headers_mut().extend(headers);
 
-    Ok(response)
+    Ok(with_device_cookie(response, &client))
 }
 
 pub async fn forgot_password(
@@ -272,7 +521,7 @@ pub async fn forgot_password(
 
     let reset_link = format!("https://verinest.xyz/reset-password?token={}", &verification_token);
 
-    let email_sent = send_forgot_password_email(&user.email, &reset_link, &user.username).await;
+    let email_sent = send_forgot_password_email(&user.email, &reset_link, &user.username, user.locale).await;
 
     if let Err(e) = email_sent {
         eprintln!("Failed to send forgot password email: {}", e);
@@ -324,6 +573,11 @@ pub async fn reset_password(
         .await
         .map_err(|e| HttpError::server_error(e.to_string()))?;
 
+    // Whoever knew the old password is signed out everywhere
+    app_state.session_service
+        .revoke_all(user_id, None, REVOKED_PASSWORD_CHANGE)
+        .await?;
+
     let response = Response {
         message: "Password has been successfully reset.".to_string(),
         status: "success",
@@ -362,7 +616,7 @@ pub async fn resend_verification_email(
         .map_err(|e| HttpError::server_error(e.to_string()))?;
 
     // Send verification email
-    send_verification_email(&updated_user.email, &updated_user.username, &verification_token)
+    send_verification_email(&updated_user.email, &updated_user.username, &verification_token, updated_user.locale)
         .await
         .map_err(|e| HttpError::server_error(e.to_string()))?;
 
@@ -375,47 +629,39 @@ pub async fn resend_verification_email(
 }
 
 
-// Adds token to Redis blacklist with expiration matching token's exp claim
+/// Signs out the session the access token belongs to; other devices stay signed in
 pub async fn logout(
     Extension(app_state): Extension<Arc<AppState>>,
     header_map: HeaderMap,
+    jar: CookieJar,
 ) -> Result<impl IntoResponse, HttpError> {
-    // Extract token from Authorization header
     let token = header_map
         .get(header::AUTHORIZATION)
         .and_then(|h| h.to_str().ok())
         .and_then(|h| h.strip_prefix("Bearer "))
+        .map(str::to_string)
+        .or_else(|| jar.get("token").map(|cookie| cookie.value().to_string()))
         .ok_or_else(|| HttpError::bad_request("Missing or invalid authorization token"))?;
 
-    // Decode token to get expiration
     let token_claims = token::decode_token_claims(token, app_state.env.jwt_secret.as_bytes())
         .map_err(|e| HttpError::unauthorized(format!("Invalid token: {}", e)))?;
-    
-    let exp = token_claims.exp;
-    let now = Utc::now().timestamp() as usize;
-    
-    if exp <= now {
-        return Err(HttpError::bad_request("Token has already expired"));
+
+    let (user_id, session_id) = match (Uuid::parse_str(&token_claims.sub), Uuid::parse_str(&token_claims.sid)) {
+        (Ok(user_id), Ok(session_id)) => (user_id, session_id),
+        _ => return Err(HttpError::unauthorized(ErrorMessage::InvalidToken.to_string())),
+    };
+
+    // Already signed out is fine
+    if let Err(e) = app_state.session_service.revoke(user_id, session_id, REVOKED_LOGOUT).await {
+        tracing::debug!("Logout of session {}: {}", session_id, e);
     }
-    
-    // Calculate TTL (seconds until expiration)
-    let ttl_seconds = exp - now;
-    let user_id = &token_claims.sub;
-    
-    // Add token to Redis blacklist with expiration equal to token expiration
-    if let Some(redis_client) = &app_state.db_client.redis_client {
-        let blacklist_key = format!("token_blacklist:{}", user_id);
-        let mut conn = ConnectionManager::clone(redis_client);
-        
-        let _ = redis::cmd("SET")
-            .arg(&blacklist_key)
-            .arg("blacklisted")
-            .arg("EX")
-            .arg(ttl_seconds)
-            .query_async::<_, String>(&mut conn)
-            .await;
-        
-        tracing::info!("✓ User {} logged out - token blacklisted until Unix timestamp {}", user_id, exp);
+    tracing::info!("✓ User {} logged out of session {}", user_id, session_id);
+
+    let mut headers = HeaderMap::new();
+    for cookie in [access_token_cookie(&app_state, ""), refresh_token_cookie(&app_state, "")] {
+        let mut cookie = cookie;
+        cookie.make_removal();
+        headers.append(header::SET_COOKIE, cookie.to_string().parse().unwrap());
     }
 
     let response = Response {
@@ -423,7 +669,10 @@ pub async fn logout(
         status: "success",
     };
 
-    Ok(Json(response))
+    let mut response = Json(response).into_response();
+    response.headers_mut().extend(headers);
+
+    Ok(response)
 }
 
 pub async fn check_username_availability(
@@ -474,3 +723,22 @@ pub async fn check_username_availability(
         message: "Username is available".to_string(),
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_ip_skips_entries_the_client_can_forge() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", "6.6.6.6, 203.0.113.7".parse().unwrap());
+        headers.append("x-forwarded-for", "10.0.0.2".parse().unwrap());
+        let peer: Option<IpAddr> = "10.0.0.1".parse().ok();
+
+        assert_eq!(client_ip(&headers, peer, 0), peer);
+        assert_eq!(client_ip(&headers, peer, 1), "10.0.0.2".parse().ok());
+        assert_eq!(client_ip(&headers, peer, 2), "203.0.113.7".parse().ok());
+        assert_eq!(client_ip(&headers, peer, 4), None);
+        assert_eq!(client_ip(&HeaderMap::new(), peer, 1), None);
+    }
+}
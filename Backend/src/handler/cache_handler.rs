@@ -15,7 +15,7 @@ use crate::{
     }, 
     error::HttpError, 
     middleware::main_middleware::JWTAuthMiddeware, 
-    models::usermodel::UserRole
+    models::permissionmodels::Permission
 };
 
 pub fn cache_handler() -> Router {
@@ -146,7 +146,7 @@ pub async fn clear_cache(
         .await
         .map_err(|e| HttpError::server_error(e.to_string()))?;
 
-    if auth.user.role != UserRole::Admin {
+    if !auth.has_permission(Permission::CacheManage) {
         return Err(HttpError::unauthorized("Admin access required"));
     }
     
@@ -0,0 +1,44 @@
+// handler/certificates.rs
+use std::sync::Arc;
+
+use axum::{
+    extract::Path,
+    response::IntoResponse,
+    routing::get,
+    Extension, Json, Router,
+};
+
+use crate::{error::HttpError, AppState};
+
+/// Public certificate checks. A verification certificate's QR code points at
+/// `/verify/property/:reference`.
+pub fn certificate_handler() -> Router {
+    Router::new()
+        .route("/property/:reference", get(verify_property_certificate))
+        .route("/keys", get(get_certificate_keys))
+}
+
+pub async fn verify_property_certificate(
+    Path(reference): Path<String>,
+    Extension(app_state): Extension<Arc<AppState>>,
+) -> Result<impl IntoResponse, HttpError> {
+    let check = app_state.certificate_service
+        .check(reference.trim())
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": check
+    })))
+}
+
+pub async fn get_certificate_keys(
+    Extension(app_state): Extension<Arc<AppState>>,
+) -> Result<impl IntoResponse, HttpError> {
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "keys": app_state.certificate_service.public_keys()
+        }
+    })))
+}
@@ -0,0 +1,270 @@
+// handler/email_handler.rs
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::{Path, Query},
+    http::{HeaderMap, StatusCode},
+    middleware,
+    response::IntoResponse,
+    routing::{delete, get, post},
+    Extension, Json, Router,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    db::emaildb::EmailOutboxExt,
+    dtos::emaildtos::*,
+    error::HttpError,
+    handler::notification_handler::{ApiResponse, PaginationParams},
+    middleware::main_middleware::permission_check,
+    models::{emailmodels::SuppressionReason, permissionmodels::Permission},
+    AppState,
+};
+
+/// Webhook timestamps older (or newer) than this are rejected as replays
+const WEBHOOK_TOLERANCE_SECONDS: i64 = 300;
+
+/// Queued messages this far past due show up as stuck
+const STUCK_AFTER_MINUTES: i64 = 15;
+
+/// Provider callbacks, mounted without auth
+pub fn email_webhook_handler() -> Router {
+    Router::new().route("/resend", post(resend_webhook))
+}
+
+/// Outbox and suppression-list administration
+pub fn email_admin_handler() -> Router {
+    Router::new()
+        .route("/emails", get(get_outbox_emails))
+        .route("/emails/:email_id/retry", post(retry_outbox_email))
+        .route(
+            "/email-suppressions",
+            get(get_email_suppressions).post(add_email_suppression),
+        )
+        .route("/email-suppressions/:email", delete(remove_email_suppression))
+        .layer(middleware::from_fn(|state, req, next| {
+            permission_check(state, req, next, Permission::EmailAdmin)
+        }))
+}
+
+/// Verify a Svix-style signature as sent by Resend. `signatures` is the
+/// space-separated `v1,<base64>` list from the `svix-signature` header.
+fn verify_webhook_signature(
+    secret: &str,
+    message_id: &str,
+    timestamp: &str,
+    body: &[u8],
+    signatures: &str,
+    now: i64,
+) -> bool {
+    let Ok(sent_at) = timestamp.parse::<i64>() else {
+        return false;
+    };
+    if (now - sent_at).abs() > WEBHOOK_TOLERANCE_SECONDS {
+        return false;
+    }
+
+    let Ok(key) = BASE64.decode(secret.trim_start_matches("whsec_")) else {
+        return false;
+    };
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(&key)
+        .expect("HMAC can take key of any size");
+    mac.update(message_id.as_bytes());
+    mac.update(b".");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    let expected = BASE64.encode(mac.finalize().into_bytes());
+
+    signatures
+        .split_whitespace()
+        .filter_map(|entry| entry.strip_prefix("v1,"))
+        .any(|candidate| bool::from(candidate.as_bytes().ct_eq(expected.as_bytes())))
+}
+
+async fn resend_webhook(
+    Extension(app_state): Extension<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<impl IntoResponse, HttpError> {
+    let secret = &app_state.env.resend_webhook_secret;
+    if secret.is_empty() {
+        return Err(HttpError::new(
+            "Email webhooks are not configured".to_string(),
+            StatusCode::SERVICE_UNAVAILABLE,
+        ));
+    }
+
+    let header = |name: &str| headers.get(name).and_then(|h| h.to_str().ok()).unwrap_or_default();
+
+    if !verify_webhook_signature(
+        secret,
+        header("svix-id"),
+        header("svix-timestamp"),
+        &body,
+        header("svix-signature"),
+        Utc::now().timestamp(),
+    ) {
+        tracing::warn!("Invalid email webhook signature received");
+        return Err(HttpError::unauthorized("Invalid webhook signature"));
+    }
+
+    let payload: serde_json::Value = serde_json::from_slice(&body)
+        .map_err(|_| HttpError::bad_request("Invalid webhook payload"))?;
+
+    let event_type = payload["type"]
+        .as_str()
+        .ok_or_else(|| HttpError::bad_request("Missing event type in webhook payload"))?
+        .to_string();
+    let data = &payload["data"];
+    let message_id = data["email_id"].as_str();
+    let recipients: Vec<String> = match &data["to"] {
+        serde_json::Value::Array(list) => list.iter().filter_map(|v| v.as_str().map(String::from)).collect(),
+        serde_json::Value::String(to) => vec![to.clone()],
+        _ => Vec::new(),
+    };
+
+    app_state.db_client
+        .record_email_event(message_id, &event_type, recipients.first().map(String::as_str), payload.clone())
+        .await
+        .map_err(|e| HttpError::server_error(format!("Failed to record email event: {}", e)))?;
+
+    let reason = match event_type.as_str() {
+        "email.bounced" => Some(SuppressionReason::Bounce),
+        "email.complained" => Some(SuppressionReason::Complaint),
+        _ => None,
+    };
+
+    if let Some(reason) = reason {
+        let details = data["bounce"]["message"].as_str().unwrap_or(&event_type);
+        for recipient in &recipients {
+            app_state.db_client
+                .upsert_email_suppression(recipient, reason, Some(details))
+                .await
+                .map_err(|e| HttpError::server_error(format!("Failed to suppress {}: {}", recipient, e)))?;
+            tracing::info!("Suppressed {} after {}", recipient, event_type);
+        }
+    }
+
+    Ok(Json(serde_json::json!({"status": "success"})))
+}
+
+async fn get_outbox_emails(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Query(query): Query<OutboxQueryDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(50).min(200) as i64;
+    let offset = ((page - 1) as i64) * limit;
+
+    let emails = if query.stuck.unwrap_or(false) {
+        app_state.db_client.get_stuck_emails(STUCK_AFTER_MINUTES, limit).await
+    } else {
+        app_state.db_client.get_outbox_emails(query.status, limit, offset).await
+    }
+    .map_err(|e| HttpError::server_error(format!("Failed to load outbox: {}", e)))?;
+    let emails: Vec<OutboxEmailDto> = emails.into_iter().map(OutboxEmailDto::from).collect();
+
+    Ok(Json(ApiResponse::success("Outbox emails retrieved", emails)))
+}
+
+async fn retry_outbox_email(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Path(email_id): Path<Uuid>,
+) -> Result<impl IntoResponse, HttpError> {
+    let email = app_state.db_client
+        .requeue_email(email_id)
+        .await
+        .map_err(|e| HttpError::server_error(format!("Failed to requeue email: {}", e)))?
+        .ok_or_else(|| HttpError::not_found("Email not found, already sent or no longer resendable"))?;
+
+    Ok(Json(ApiResponse::success("Email requeued", OutboxEmailDto::from(email))))
+}
+
+async fn get_email_suppressions(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Query(pagination): Query<PaginationParams>,
+) -> Result<impl IntoResponse, HttpError> {
+    let page = pagination.page.unwrap_or(1).max(1);
+    let limit = pagination.limit.unwrap_or(50).min(200) as i64;
+    let offset = ((page - 1) as i64) * limit;
+
+    let suppressions = app_state.db_client
+        .get_email_suppressions(limit, offset)
+        .await
+        .map_err(|e| HttpError::server_error(format!("Failed to load suppressions: {}", e)))?;
+
+    Ok(Json(ApiResponse::success("Email suppressions retrieved", suppressions)))
+}
+
+async fn add_email_suppression(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Json(body): Json<CreateSuppressionDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let suppression = app_state.db_client
+        .upsert_email_suppression(
+            &body.email,
+            body.reason.unwrap_or(SuppressionReason::Manual),
+            body.details.as_deref(),
+        )
+        .await
+        .map_err(|e| HttpError::server_error(format!("Failed to add suppression: {}", e)))?;
+
+    Ok(Json(ApiResponse::success("Email suppressed", suppression)))
+}
+
+async fn remove_email_suppression(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Path(email): Path<String>,
+) -> Result<impl IntoResponse, HttpError> {
+    let removed = app_state.db_client
+        .delete_email_suppression(&email)
+        .await
+        .map_err(|e| HttpError::server_error(format!("Failed to remove suppression: {}", e)))?;
+
+    if !removed {
+        return Err(HttpError::not_found("Email is not suppressed"));
+    }
+
+    Ok(Json(ApiResponse::success("Email suppression removed", serde_json::json!({}))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sign(secret: &str, id: &str, timestamp: &str, body: &[u8]) -> String {
+        let key = BASE64.decode(secret.trim_start_matches("whsec_")).unwrap();
+        let mut mac = Hmac::<Sha256>::new_from_slice(&key).unwrap();
+        mac.update(format!("{}.{}.", id, timestamp).as_bytes());
+        mac.update(body);
+        format!("v1,{}", BASE64.encode(mac.finalize().into_bytes()))
+    }
+
+    #[test]
+    fn test_webhook_signature_verification() {
+        let secret = format!("whsec_{}", BASE64.encode(b"test-webhook-secret"));
+        let body = br#"{"type":"email.bounced"}"#;
+        let signature = sign(&secret, "msg_1", "1700000000", body);
+        let header = format!("v1,bm90LXRoaXMtb25l {}", signature);
+
+        assert!(verify_webhook_signature(&secret, "msg_1", "1700000000", body, &header, 1700000010));
+        // Tampered body
+        assert!(!verify_webhook_signature(&secret, "msg_1", "1700000000", b"{}", &header, 1700000010));
+        // Replayed outside the tolerance window
+        assert!(!verify_webhook_signature(&secret, "msg_1", "1700000000", body, &header, 1700001000));
+        // Different message id
+        assert!(!verify_webhook_signature(&secret, "msg_2", "1700000000", body, &header, 1700000010));
+    }
+}
@@ -210,9 +210,10 @@ use chrono;
 use crate::{
     db::userdb::UserExt, 
     error::HttpError, 
+    handler::auth::{device_cookie, refresh_token_cookie, session_client, ClientIp},
     middleware::main_middleware::JWTAuthMiddeware, 
     service::google_oauth::GoogleAuthService, 
-    utils::token, AppState
+    AppState
 };
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -335,6 +336,8 @@ pub async fn google_login(
 
 pub async fn google_callback(
     Extension(app_state): Extension<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
+    ip: ClientIp,
     jar: CookieJar,
     Query(query): Query<GoogleAuthQuery>,
 ) -> Result<impl IntoResponse, HttpError> {
@@ -420,13 +423,26 @@ pub async fn google_callback(
         }
     };
 
-    // Generate JWT token
-    let token = token::create_token(
-        &user.id.to_string(), 
-        app_state.env.jwt_secret.as_bytes(), 
-        app_state.env.jwt_maxage
-    )
-    .map_err(|e| HttpError::server_error(e.to_string()))?;
+    // Accounts with two-factor finish signing in on the frontend's code screen
+    if app_state.two_factor_service.is_enabled(user.id).await? {
+        let challenge_token = app_state.two_factor_service.login_challenge(user.id)?;
+        let redirect_url = format!("{}/auth/2fa?challenge_token={}", &app_state.env.app_url, challenge_token);
+
+        return Ok((jar, Redirect::to(&redirect_url)));
+    }
+
+    // Start a session; the refresh token travels in its cookie, not the URL
+    let client = session_client(&headers, ip, None);
+    let tokens = app_state.session_service
+        .start(user.id, client.clone())
+        .await?;
+    app_state.login_activity_service.record_success(&user, &client).await;
+    let token = tokens.access_token;
+    let jar = jar.add(refresh_token_cookie(&app_state, &tokens.refresh_token));
+    let jar = match &client.device_id {
+        Some(device_id) => jar.add(device_cookie(device_id)),
+        None => jar,
+    };
 
     // FIX: Redirect to the correct frontend callback route
     let redirect_url = format!("{}/auth/callback?token={}", &app_state.env.app_url, token);
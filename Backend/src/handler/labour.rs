@@ -20,8 +20,9 @@ use crate::{
         userdb::UserExt,
     }, dtos::{labordtos::*, userdtos::FilterUserDto}, 
     error::HttpError, middleware::main_middleware::JWTAuthMiddeware,
-    models::{labourmodel::*, 
-        usermodel::{User, VerificationStatus}}
+    models::{labourmodel::*, mediamodels::MediaPurpose,
+        usermodel::{User, VerificationStatus}},
+    utils::gazetteer,
 };
 
 pub fn labour_handler() -> Router {
@@ -99,6 +100,8 @@ pub async fn create_worker_profile(
         return Err(HttpError::bad_request("Worker profile already exists"));
     }
 
+    let (location_state, location_city) = gazetteer::normalise_place(&body.location_state, &body.location_city);
+
     let worker_profile = app_state.db_client
         .create_worker_profile(
             auth.user.id,
@@ -107,8 +110,8 @@ pub async fn create_worker_profile(
             body.description,
             body.hourly_rate,
             body.daily_rate,
-            body.location_state,
-            body.location_city,
+            location_state,
+            location_city,
         )
         .await
         .map_err(|e| HttpError::server_error(e.to_string()))?;
@@ -164,6 +167,10 @@ pub async fn add_portfolio_item(
     body.validate()
         .map_err(|e| HttpError::bad_request(e.to_string()))?;
 
+    app_state.storage_service
+        .ensure_owned(auth.user.id, &body.image_url, &[MediaPurpose::Portfolio])
+        .await?;
+
     // Get worker profile for the authenticated user
     let worker_profile = app_state.db_client
         .get_worker_profile(auth.user.id)
@@ -298,6 +305,8 @@ pub async fn create_job(
     body.validate()
         .map_err(|e| HttpError::bad_request(e.to_string()))?;
 
+    app_state.kyc_service.ensure_job_budget(auth.user.id, body.budget).await?;
+
     let result = app_state.labour_service
         .create_job_with_escrow(auth.user.id, body)
         .await
@@ -315,10 +324,11 @@ pub async fn create_job(
 // In labour.rs - Add detailed debugging
 pub async fn search_jobs(
     Extension(app_state): Extension<Arc<AppState>>,
-    Query(params): Query<SearchJobsDto>,
+    Query(mut params): Query<SearchJobsDto>,
 ) -> Result<impl IntoResponse, HttpError> {
     params.validate()
         .map_err(|e| HttpError::bad_request(e.to_string()))?;
+    params.location_state = params.location_state.map(|state| gazetteer::normalise_state(&state));
 
     println!("🔍 Search jobs called with params: {:?}", params);
 
@@ -769,10 +779,14 @@ pub async fn submit_job_progress(
     body.validate()
         .map_err(|e| HttpError::bad_request(e.to_string()))?;
 
-    if auth.user.role != UserRole::Worker {
+    if !auth.has_role(UserRole::Worker) {
         return Err(HttpError::unauthorized("Only Workers are allowed to submit job progress"));
     }
-        
+
+    app_state.storage_service
+        .ensure_all_owned(auth.user.id, &body.image_urls, &[MediaPurpose::JobProgress])
+        .await?;
+
     let result = app_state.labour_service
         .submit_job_progress(job_id, auth.user.id, body)
         .await
@@ -1014,6 +1028,10 @@ pub async fn create_dispute(
     body.validate()
         .map_err(|e| HttpError::bad_request(e.to_string()))?;
 
+    app_state.storage_service
+        .ensure_all_owned(auth.user.id, &body.evidence_urls, &[MediaPurpose::DisputeEvidence])
+        .await?;
+
     // Determine who the dispute is against
     let job = app_state.db_client
         .get_job_by_id(job_id)
@@ -1108,10 +1126,11 @@ pub async fn get_pending_verifications(
 // Search and Discovery Handlers
 pub async fn search_workers(
     Extension(app_state): Extension<Arc<AppState>>,
-    Query(params): Query<SearchWorkersDto>,
+    Query(mut params): Query<SearchWorkersDto>,
 ) -> Result<impl IntoResponse, HttpError> {
     params.validate()
         .map_err(|e| HttpError::bad_request(e.to_string()))?;
+    params.location_state = params.location_state.map(|state| gazetteer::normalise_state(&state));
 
     let page = params.page.unwrap_or(1);
     let limit = params.limit.unwrap_or(20);
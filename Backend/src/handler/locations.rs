@@ -0,0 +1,92 @@
+// handler/locations.rs
+use axum::{
+    extract::{Path, Query},
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use validator::Validate;
+
+use crate::{
+    dtos::locationdtos::{LocationSearchQueryDto, ResolveLocationQueryDto},
+    error::HttpError,
+    utils::gazetteer,
+};
+
+/// Public lookups over the bundled gazetteer, for forms and autocomplete
+pub fn location_handler() -> Router {
+    Router::new()
+        .route("/states", get(get_states))
+        .route("/states/:state/lgas", get(get_state_lgas))
+        .route("/search", get(search_locations))
+        .route("/resolve", get(resolve_location))
+}
+
+pub async fn get_states() -> Result<impl IntoResponse, HttpError> {
+    let states = gazetteer::states();
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "states": states,
+            "total": states.len()
+        }
+    })))
+}
+
+pub async fn get_state_lgas(
+    Path(state): Path<String>,
+) -> Result<impl IntoResponse, HttpError> {
+    let found = gazetteer::find_state(&state)
+        .ok_or_else(|| HttpError::not_found(format!("'{}' is not a Nigerian state", state)))?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "state": found,
+            "lgas": found.lgas,
+            "towns": found.towns
+        }
+    })))
+}
+
+pub async fn search_locations(
+    Query(query_params): Query<LocationSearchQueryDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    query_params.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let results = gazetteer::search(
+        &query_params.q,
+        query_params.state.as_deref(),
+        query_params.limit.unwrap_or(10),
+    );
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "results": results
+        }
+    })))
+}
+
+pub async fn resolve_location(
+    Query(query_params): Query<ResolveLocationQueryDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    query_params.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let location = gazetteer::resolve(
+        &query_params.state,
+        query_params.lga.as_deref(),
+        query_params.city.as_deref(),
+    )
+    .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "location": location
+        }
+    })))
+}
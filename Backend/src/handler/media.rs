@@ -0,0 +1,165 @@
+// handler/media.rs
+use std::sync::Arc;
+
+use axum::{
+    extract::{DefaultBodyLimit, Multipart, Path, Query},
+    http::header,
+    response::{IntoResponse, Redirect, Response},
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use uuid::Uuid;
+
+use crate::{
+    dtos::mediadtos::MediaFileQueryDto,
+    error::HttpError,
+    middleware::main_middleware::JWTAuthMiddeware,
+    models::{
+        mediamodels::{MediaObject, MediaPurpose},
+        permissionmodels::Permission,
+    },
+    service::{
+        storage_backends::StagedUpload,
+        storage_service::{StoredFile, MAX_UPLOAD_MB},
+    },
+    AppState,
+};
+
+/// Serves stored files. Public objects are open; private ones need the
+/// signature from a signed URL.
+pub fn public_media_handler() -> Router {
+    Router::new()
+        .route("/files/*key", get(get_media_file))
+}
+
+/// Uploads and upload metadata, mounted behind auth
+pub fn media_handler() -> Router {
+    Router::new()
+        .route(
+            "/upload",
+            // Leave room for the multipart framing around the file
+            post(upload_media).layer(DefaultBodyLimit::max(MAX_UPLOAD_MB * 1024 * 1024 + 64 * 1024)),
+        )
+        .route("/:media_id", get(get_media))
+}
+
+/// Multipart form with a `purpose` field and a `file` field. The file is
+/// streamed to a temporary file and hashed chunk by chunk, and refused as
+/// soon as it passes the purpose's size limit.
+pub async fn upload_media(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, HttpError> {
+    let mut purpose: Option<MediaPurpose> = None;
+    let mut upload: Option<(Option<String>, StagedUpload)> = None;
+
+    while let Some(mut field) = multipart.next_field().await
+        .map_err(|e| HttpError::bad_request(format!("Invalid multipart body: {}", e)))?
+    {
+        match field.name() {
+            Some("purpose") => {
+                let value = field.text().await
+                    .map_err(|e| HttpError::bad_request(e.to_string()))?;
+                purpose = Some(
+                    serde_json::from_value(serde_json::Value::String(value.trim().to_string()))
+                        .map_err(|_| HttpError::bad_request(format!("Unknown upload purpose '{}'", value.trim())))?,
+                );
+            }
+            Some("file") => {
+                let filename = field.file_name().map(str::to_string);
+                let limit = purpose.map(|p| p.max_size_mb()).unwrap_or(MAX_UPLOAD_MB) * 1024 * 1024;
+
+                let mut staged = StagedUpload::new().await
+                    .map_err(|e| HttpError::server_error(e.to_string()))?;
+                while let Some(chunk) = field.chunk().await
+                    .map_err(|e| HttpError::bad_request(format!("Upload interrupted: {}", e)))?
+                {
+                    if staged.size() + chunk.len() > limit {
+                        return Err(HttpError::bad_request(format!(
+                            "File is larger than {}MB",
+                            limit / (1024 * 1024)
+                        )));
+                    }
+                    staged.write(&chunk).await
+                        .map_err(|e| HttpError::server_error(e.to_string()))?;
+                }
+
+                upload = Some((filename, staged));
+            }
+            _ => {}
+        }
+    }
+
+    let purpose = purpose.ok_or_else(|| HttpError::bad_request("The purpose field is required"))?;
+    let (filename, staged) = upload.ok_or_else(|| HttpError::bad_request("The file field is required"))?;
+
+    let object = app_state.storage_service
+        .store(auth.user.id, purpose, filename.as_deref(), staged)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "File uploaded",
+        "data": media_json(&app_state, &object)
+    })))
+}
+
+pub async fn get_media(
+    Path(media_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    let object = app_state.storage_service
+        .get_media(auth.user.id, media_id, auth.has_permission(Permission::MediaReadAll))
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": media_json(&app_state, &object)
+    })))
+}
+
+pub async fn get_media_file(
+    Path(key): Path<String>,
+    Query(query_params): Query<MediaFileQueryDto>,
+    Extension(app_state): Extension<Arc<AppState>>,
+) -> Result<Response, HttpError> {
+    let storage = &app_state.storage_service;
+    if !storage.authorize(&key, query_params.expires, query_params.signature.as_deref()) {
+        return Err(HttpError::unauthorized("This link is invalid or has expired"));
+    }
+
+    match storage.open(&key).await? {
+        Some(StoredFile::Redirect(url)) => Ok(Redirect::temporary(&url).into_response()),
+        Some(StoredFile::Contents { content_type, contents }) => {
+            // Content addressed, so a key's bytes never change
+            let cache_control = if key.starts_with("public/") {
+                "public, max-age=31536000, immutable"
+            } else {
+                "private, no-store"
+            };
+
+            Ok((
+                [
+                    (header::CONTENT_TYPE, content_type),
+                    (header::CACHE_CONTROL, cache_control),
+                    (header::X_CONTENT_TYPE_OPTIONS, "nosniff"),
+                ],
+                contents,
+            )
+                .into_response())
+        }
+        None => Err(HttpError::not_found("File not found")),
+    }
+}
+
+fn media_json(app_state: &AppState, object: &MediaObject) -> serde_json::Value {
+    serde_json::json!({
+        "media": object,
+        // Store this against the listing, profile or submission
+        "url": app_state.storage_service.url(object),
+        // Open this now; for private files it expires
+        "access_url": app_state.storage_service.access_url(object)
+    })
+}
@@ -3,7 +3,7 @@ pub mod users;
 pub mod google_oauth;
 // pub mod wallet;
 pub mod verification;
-// pub mod properties;
+pub mod properties;
 pub mod naira_wallet;
 pub mod labour;
 pub mod notification_handler;
@@ -12,4 +12,14 @@ pub mod support_handler;
 pub mod vendor;
 pub mod cache_handler;
 pub mod feed;
-pub mod debug;
\ No newline at end of file
+pub mod debug;
+pub mod email_handler;
+pub mod viewings;
+pub mod tenancies;
+pub mod offers;
+pub mod locations;
+pub mod saved;
+pub mod certificates;
+pub mod media;
+pub mod two_factor;
+pub mod privacy;
\ No newline at end of file
@@ -8,8 +8,6 @@ use axum::{
     routing::{get, post, put},
     Extension, Json, Router,
 };
-use chrono::Utc;
-use rand::Rng;
 use uuid::Uuid;
 use validator::Validate;
 use hmac::{Hmac, Mac};
@@ -21,18 +19,19 @@ use crate::{
     db::{
         userdb::UserExt,
         naira_walletdb::NairaWalletExt,
-        verificationdb::VerificationExt
     },
     dtos::naira_walletdtos::*,
     error::HttpError,
+    handler::users::check_transaction_pin,
     middleware::{
         main_middleware::JWTAuthMiddeware,
         rate_limit::{rate_limit_middleware, wallet_rate_limiter, deposit_rate_limiter, webhook_rate_limiter}
     },
-    models::{walletmodels::*, verificationmodels::OtpPurpose, usermodel::User},
+    models::{walletmodels::*, usermodel::User, identitymodels::IdentityKind, verificationmodels::OtpPurpose},
     service::payment_provider::PaymentProviderService,
+    service::identity_service::IdentityService,
+    service::{error::ServiceError, two_factor_service::LARGE_TRANSFER_NAIRA},
     service::notification_service::NotificationService,
-    mail::mails,
     AppState,
 };
 
@@ -111,6 +110,12 @@ pub fn naira_wallet_handler() -> Router {
             rate_limit_middleware
         )))
         
+        // BVN
+        .route("/bvn/verify", post(verify_bvn).layer(axum::middleware::from_fn_with_state(
+            Arc::new(wallet_rate_limiter()),
+            rate_limit_middleware
+        )))
+        
         // Webhooks - higher rate limit but still protected
         .route("/webhook/paystack", post(paystack_webhook).layer(axum::middleware::from_fn_with_state(
             Arc::new(webhook_rate_limiter()),
@@ -419,6 +424,8 @@ pub async fn withdraw_funds(
 
     let amount_kobo = naira_to_kobo(body.amount);
 
+    app_state.kyc_service.ensure_can_withdraw(auth.user.id).await?;
+
     // Check transaction limits
     let can_transact = app_state
         .db_client
@@ -466,47 +473,34 @@ pub async fn withdraw_funds(
     // Generate reference
     let reference = generate_transaction_reference();
 
-    // --- SECURITY: verify transaction PIN or email OTP for transfer ---
+    // --- SECURITY: a PIN, if given, must match; a second factor is always required ---
     if let Some(pin_str) = &body.transaction_pin {
         // Verify against stored hashed PIN
         let stored_hash = auth.user.transaction_pin_hash.as_deref()
             .ok_or_else(|| HttpError::bad_request("Transaction PIN not set on account"))?;
 
-        let pin_ok = crate::utils::password::compare(pin_str, Some(stored_hash))
-            .map_err(|e| HttpError::server_error(e.to_string()))?;
+        let pin_ok = check_transaction_pin(&app_state, auth.user.id, pin_str, stored_hash).await?;
 
         if !pin_ok {
             return Err(HttpError::unauthorized("Invalid transaction pin"));
         }
-    } else if let Some(otp_code) = &body.email_otp {
-        let otp = app_state
-            .db_client
-            .get_valid_otp(&auth.user.email, otp_code, OtpPurpose::Transaction)
-            .await
-            .map_err(|e| HttpError::server_error(e.to_string()))?;
+    }
 
-        if let Some(otp_record) = otp {
-            let _ = app_state
-                .db_client
-                .mark_otp_used(otp_record.id)
-                .await
-                .map_err(|e| HttpError::server_error(e.to_string()))?;
-        } else {
-            return Err(HttpError::unauthorized("Invalid or expired OTP"));
+    match app_state.two_factor_service
+        .require_step_up(&auth.user, auth.session_id, body.totp_code.as_deref(), body.email_otp.as_deref())
+        .await
+    {
+        Ok(()) => {}
+        Err(ServiceError::StepUpRequired) => {
+            // No code and no recent step-up - send an OTP by the user's channel, return 202 Accepted
+            app_state.otp_service
+                .send(&auth.user, OtpPurpose::Transaction, None, None)
+                .await?;
+
+            let resp = (StatusCode::ACCEPTED, Json(WalletApiResponse::<()>::error("OTP sent; confirm with email_otp or totp_code"))).into_response();
+            return Ok(resp);
         }
-    } else {
-        // No auth provided - generate OTP and send email, return 202 Accepted
-    let otp_code = format!("{:06}", rand::rng().random_range(0..1_000_000));
-        let expires_at = Utc::now() + chrono::Duration::minutes(10);
-        let _ = app_state
-            .db_client
-            .create_otp(auth.user.id, auth.user.email.clone(), otp_code.clone(), OtpPurpose::Transaction, expires_at)
-            .await
-            .map_err(|e| HttpError::server_error(e.to_string()))?;
-        let _ = mails::send_otp_email(&auth.user.email, &otp_code, &OtpPurpose::Transaction).await;
-
-        let resp = (StatusCode::ACCEPTED, Json(WalletApiResponse::<()>::error("OTP sent to your email; confirm with email_otp or use transaction_pin"))).into_response();
-        return Ok(resp);
+        Err(e) => return Err(e.into()),
     }
 
     // Initiate transfer with payment provider
@@ -597,6 +591,13 @@ pub async fn transfer_funds(
         return Err(HttpError::bad_request("Cannot transfer to yourself"));
     }
 
+    // Large transfers need a recent second factor
+    if body.amount >= LARGE_TRANSFER_NAIRA {
+        app_state.two_factor_service
+            .require_step_up(&auth.user, auth.session_id, body.totp_code.as_deref(), body.email_otp.as_deref())
+            .await?;
+    }
+
     let amount_kobo = naira_to_kobo(body.amount);
 
     // // Check transaction limits
@@ -769,6 +770,49 @@ pub async fn verify_bank_account(
     )))
 }
 
+pub async fn verify_bvn(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+    Json(body): Json<VerifyBvnDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    app_state
+        .db_client
+        .get_naira_wallet(auth.user.id)
+        .await
+        .map_err(|e| HttpError::server_error(e.to_string()))?
+        .ok_or_else(|| HttpError::not_found("Wallet not found"))?;
+
+    // The account's date of birth is the one checked, so a caller cannot pick
+    // one that suits the BVN
+    let account_dob = auth.user.dob.map(|dob| dob.date_naive());
+    let dob = account_dob.or(body.dob);
+    let check = app_state.identity_service
+        .verify_person(auth.user.id, IdentityKind::Bvn, &body.bvn, &auth.user.name, dob)
+        .await?;
+    IdentityService::ensure_matched(&check)?;
+
+    if let (None, Some(dob)) = (account_dob, body.dob) {
+        app_state
+            .db_client
+            .set_user_dob_if_missing(auth.user.id, dob.and_time(chrono::NaiveTime::MIN).and_utc())
+            .await
+            .map_err(|e| HttpError::server_error(e.to_string()))?;
+    }
+
+    let wallet = app_state
+        .db_client
+        .set_bvn_verified(auth.user.id, true)
+        .await
+        .map_err(|e| HttpError::server_error(e.to_string()))?
+        .ok_or_else(|| HttpError::not_found("Wallet not found"))?;
+
+    let response: WalletResponseDto = wallet.into();
+    Ok(Json(WalletApiResponse::success("BVN verified successfully", response)))
+}
+
 pub async fn add_bank_account(
     Extension(app_state): Extension<Arc<AppState>>,
     Extension(auth): Extension<JWTAuthMiddeware>,
@@ -777,6 +821,11 @@ pub async fn add_bank_account(
     body.validate()
         .map_err(|e| HttpError::bad_request(e.to_string()))?;
 
+    // Where withdrawals go is as sensitive as the withdrawals themselves
+    app_state.two_factor_service
+        .require_step_up(&auth.user, auth.session_id, None, None)
+        .await?;
+
     // Resolve account number to get account name
     let payment_service = PaymentProviderService::new(&app_state.env);
     let verification = payment_service
@@ -816,6 +865,10 @@ pub async fn set_primary_account(
     Extension(auth): Extension<JWTAuthMiddeware>,
     Path(account_id): Path<Uuid>,
 ) -> Result<impl IntoResponse, HttpError> {
+    app_state.two_factor_service
+        .require_step_up(&auth.user, auth.session_id, None, None)
+        .await?;
+
     // Set the bank account as primary
     let primary_account = app_state
         .db_client
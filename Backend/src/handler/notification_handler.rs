@@ -178,22 +178,28 @@
 
 
 // src/handlers/notification_handler.rs - FIXED VERSION
-use std::sync::Arc;
+use std::{convert::Infallible, sync::Arc};
 use axum::{
     extract::{Path, Query},
-    http::StatusCode,
-    response::IntoResponse,
+    http::{HeaderMap, StatusCode},
+    response::{sse::{Event, KeepAlive, Sse}, IntoResponse},
     routing::{delete, get, post, put},
     Extension, Json, Router,
 };
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use chrono::{DateTime, Utc};
+use futures::{stream, Stream, StreamExt};
+use tokio::sync::broadcast::error::RecvError;
+
+use validator::Validate;
 
 use crate::{
-    db::db::DBClient,
+    db::{db::DBClient, notificationdb::NotificationExt},
+    dtos::notificationdtos::*,
     error::HttpError,
     middleware::main_middleware::JWTAuthMiddeware,
+    models::notificationmodels::Notification,
+    service::notification_stream::{self, StreamEvent},
     AppState,
 };
 
@@ -208,18 +214,6 @@ pub struct MarkReadRequest {
     pub notification_ids: Option<Vec<Uuid>>,
 }
 
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
-pub struct Notification {
-    pub id: Uuid,
-    pub user_id: Uuid,
-    pub title: String,
-    pub message: String,
-    pub notification_type: String,
-    pub related_id: Option<Uuid>,
-    pub is_read: bool,
-    pub created_at: DateTime<Utc>,
-}
-
 #[derive(Debug, Serialize)]
 pub struct NotificationResponse {
     pub notifications: Vec<Notification>,
@@ -250,6 +244,16 @@ pub fn notification_routes() -> Router {
     Router::new()
         .route("/", get(get_user_notifications))
         .route("/unread-count", get(get_unread_count))
+        .route("/stream", get(stream_notifications))
+        .route(
+            "/preferences",
+            get(get_notification_preferences).put(update_notification_preferences),
+        )
+        .route("/deliveries", get(get_notification_deliveries))
+        .route(
+            "/push-subscriptions",
+            post(register_push_subscription).delete(remove_push_subscription),
+        )
         .route("/read", post(mark_notifications_read))
         .route("/read-all", post(mark_all_notifications_read))
         .route("/:id/read", put(mark_single_notification_read))
@@ -381,6 +385,99 @@ async fn get_unread_count(
     })))
 }
 
+/// Notifications missed while disconnected that are replayed on resume
+const STREAM_REPLAY_LIMIT: i64 = 100;
+
+fn sse_event(event: &StreamEvent) -> Option<Event> {
+    let (name, data) = match event {
+        StreamEvent::Notification { notification, unread_count, .. } => (
+            "notification",
+            serde_json::json!({ "notification": notification, "unread_count": unread_count }),
+        ),
+        StreamEvent::UnreadCount { unread_count, .. } => (
+            "unread_count",
+            serde_json::json!({ "unread_count": unread_count }),
+        ),
+    };
+
+    let mut sse = Event::default().event(name).json_data(data).ok()?;
+    if let Some(cursor) = event.cursor() {
+        sse = sse.id(cursor.to_string());
+    }
+    Some(sse)
+}
+
+// Push new notifications and unread-count changes as server-sent events.
+// Reconnecting clients send `Last-Event-ID` and get what they missed replayed first.
+async fn stream_notifications(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, HttpError> {
+    let user_id = auth.user.id;
+
+    // Subscribe before reading the backlog so nothing lands in between
+    let receiver = notification_stream::subscribe();
+
+    let since = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(notification_stream::parse_cursor);
+
+    let missed: Vec<Notification> = match since {
+        Some(since) => app_state.db_client
+            .get_notifications_since(user_id, since, STREAM_REPLAY_LIMIT)
+            .await
+            .map_err(|e| HttpError::server_error(format!("Failed to load missed notifications: {}", e)))?,
+        None => Vec::new(),
+    };
+
+    let unread_count = app_state.db_client
+        .count_unread_notifications(user_id)
+        .await
+        .map_err(|e| HttpError::server_error(format!("Failed to count notifications: {}", e)))?;
+
+    // Live events at or before this point were already replayed
+    let replayed_up_to = missed
+        .last()
+        .map(|notification| notification_stream::event_cursor(notification.created_at))
+        .or_else(|| since.map(notification_stream::event_cursor));
+
+    let mut backlog: Vec<StreamEvent> = missed
+        .into_iter()
+        .map(|notification| StreamEvent::Notification { user_id, notification, unread_count })
+        .collect();
+    backlog.push(StreamEvent::UnreadCount { user_id, unread_count });
+
+    let live = stream::unfold(receiver, move |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) if event.user_id() != user_id => continue,
+                Ok(event) => {
+                    if let (Some(cursor), Some(replayed)) = (event.cursor(), replayed_up_to) {
+                        if cursor <= replayed {
+                            continue;
+                        }
+                    }
+                    return Some((event, receiver));
+                }
+                // Dropping the connection makes the client reconnect and replay from its last id
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Notification stream for user {} lagged by {} events, closing", user_id, skipped);
+                    return None;
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    let events = stream::iter(backlog)
+        .chain(live)
+        .filter_map(|event| async move { sse_event(&event).map(Ok) });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
 // Mark specific notifications as read with authorization
 async fn mark_notifications_read(
     Extension(app_state): Extension<Arc<AppState>>,
@@ -440,6 +537,7 @@ async fn mark_notifications_read(
 
         println!("✅ [mark_notifications_read] Marked {} notifications as read", notification_ids.len());
         tracing::info!("User {} successfully marked {} notifications as read", user_id, notification_ids.len());
+        notification_stream::publish_unread_count(&app_state.db_client, user_id).await;
     }
 
     Ok(Json(ApiResponse::success(
@@ -471,6 +569,9 @@ async fn mark_all_notifications_read(
     })?;
 
     println!("✅ [mark_all_notifications_read] Marked {} notifications as read", result.rows_affected());
+    if result.rows_affected() > 0 {
+        notification_stream::publish_unread_count(&app_state.db_client, auth.user.id).await;
+    }
 
     Ok(Json(ApiResponse::success(
         "All notifications marked as read",
@@ -505,6 +606,7 @@ async fn mark_single_notification_read(
     })?;
 
     println!("✅ [mark_single_notification_read] Marked as read");
+    notification_stream::publish_unread_count(&app_state.db_client, auth.user.id).await;
 
     Ok(Json(ApiResponse::success(
         "Notification marked as read",
@@ -568,9 +670,151 @@ async fn delete_notification(
 
     println!("✅ [delete_notification] Deleted successfully");
     tracing::info!("User {} successfully deleted notification {}", user_id, notification_id);
+    notification_stream::publish_unread_count(&app_state.db_client, user_id).await;
 
     Ok(Json(ApiResponse::success(
         "Notification deleted",
         serde_json::json!({}),
     )))
-}
\ No newline at end of file
+}
+
+// Get the caller's notification preference matrix, quiet hours and digest setting
+async fn get_notification_preferences(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    let preference_set = app_state.db_client
+        .get_notification_preference_set(auth.user.id)
+        .await
+        .map_err(|e| HttpError::server_error(format!("Failed to load notification preferences: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(
+        "Notification preferences retrieved",
+        NotificationPreferencesResponseDto::from_preference_set(&preference_set),
+    )))
+}
+
+// Update channel preferences and/or quiet hours and digest frequency
+async fn update_notification_preferences(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+    Json(body): Json<UpdateNotificationPreferencesDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let user_id = auth.user.id;
+
+    if let Some(preferences) = &body.preferences {
+        for preference in preferences {
+            app_state.db_client
+                .upsert_notification_preference(user_id, preference.category, preference.channel, preference.enabled)
+                .await
+                .map_err(|e| HttpError::server_error(format!("Failed to update notification preference: {}", e)))?;
+        }
+    }
+
+    if body.quiet_hours.is_some() || body.digest_frequency.is_some() {
+        let current = app_state.db_client
+            .get_notification_preference_set(user_id)
+            .await
+            .map_err(|e| HttpError::server_error(format!("Failed to load notification preferences: {}", e)))?
+            .settings;
+
+        let (quiet_hours_enabled, start_hour, end_hour, utc_offset_minutes) = match &body.quiet_hours {
+            Some(quiet_hours) => (
+                quiet_hours.enabled,
+                quiet_hours.start_hour,
+                quiet_hours.end_hour,
+                quiet_hours.utc_offset_minutes.unwrap_or(current.utc_offset_minutes),
+            ),
+            None => (
+                current.quiet_hours_enabled,
+                current.quiet_hours_start,
+                current.quiet_hours_end,
+                current.utc_offset_minutes,
+            ),
+        };
+
+        app_state.db_client
+            .upsert_notification_settings(
+                user_id,
+                quiet_hours_enabled,
+                start_hour,
+                end_hour,
+                utc_offset_minutes,
+                body.digest_frequency.unwrap_or(current.digest_frequency),
+            )
+            .await
+            .map_err(|e| HttpError::server_error(format!("Failed to update notification settings: {}", e)))?;
+    }
+
+    tracing::info!("User {} updated notification preferences", user_id);
+
+    let preference_set = app_state.db_client
+        .get_notification_preference_set(user_id)
+        .await
+        .map_err(|e| HttpError::server_error(format!("Failed to load notification preferences: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(
+        "Notification preferences updated",
+        NotificationPreferencesResponseDto::from_preference_set(&preference_set),
+    )))
+}
+
+// Per-channel delivery status for the caller's recent notifications
+async fn get_notification_deliveries(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+    Query(pagination): Query<PaginationParams>,
+) -> Result<impl IntoResponse, HttpError> {
+    let page = pagination.page.unwrap_or(1).max(1);
+    let limit = pagination.limit.unwrap_or(20).min(100) as i64;
+    let offset = ((page - 1) as i64) * limit;
+
+    let deliveries = app_state.db_client
+        .get_user_deliveries(auth.user.id, limit, offset)
+        .await
+        .map_err(|e| HttpError::server_error(format!("Failed to load notification deliveries: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(
+        "Notification deliveries retrieved",
+        deliveries,
+    )))
+}
+
+// Register a browser or device token for push notifications
+async fn register_push_subscription(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+    Json(body): Json<PushSubscriptionDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let subscription = app_state.db_client
+        .upsert_push_subscription(auth.user.id, &body.token, body.platform.as_deref().unwrap_or("web"))
+        .await
+        .map_err(|e| HttpError::server_error(format!("Failed to save push subscription: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(
+        "Push subscription registered",
+        subscription,
+    )))
+}
+
+async fn remove_push_subscription(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+    Json(body): Json<PushSubscriptionDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    app_state.db_client
+        .delete_push_subscription(auth.user.id, &body.token)
+        .await
+        .map_err(|e| HttpError::server_error(format!("Failed to remove push subscription: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(
+        "Push subscription removed",
+        serde_json::json!({}),
+    )))
+}
@@ -0,0 +1,241 @@
+// handler/offers.rs
+use std::sync::Arc;
+use axum::{
+    extract::{Path, Query},
+    response::IntoResponse,
+    routing::{get, post, put},
+    Extension, Json, Router,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    db::offerdb::OfferExt,
+    dtos::{
+        userdtos::RequestQueryDto,
+        offerdtos::{CounterOfferDto, MakeOfferDto, OfferNoteDto},
+    },
+    error::HttpError,
+    middleware::main_middleware::JWTAuthMiddeware,
+    AppState,
+};
+
+/// The offer book on sale and lease listings, mounted behind auth
+pub fn offer_handler() -> Router {
+    Router::new()
+        .route("/", post(make_offer))
+        .route("/mine", get(get_my_offers))
+        .route("/property/:property_id", get(get_offer_book))
+        .route("/:offer_id", get(get_offer))
+        .route("/:offer_id/counter", put(counter_offer))
+        .route("/:offer_id/accept", put(accept_offer))
+        .route("/:offer_id/reject", put(reject_offer))
+        .route("/:offer_id/withdraw", put(withdraw_offer))
+        .route("/:offer_id/cancel", put(cancel_offer))
+        .route("/:offer_id/complete", put(complete_offer))
+}
+
+pub async fn make_offer(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+    Json(body): Json<MakeOfferDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let offer = app_state.offer_service
+        .make_offer(user.user.id, body)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Offer sent to the landlord",
+        "data": {
+            "offer": offer
+        }
+    })))
+}
+
+pub async fn get_my_offers(
+    Query(query_params): Query<RequestQueryDto>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    query_params.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let page = query_params.page.unwrap_or(1) as u32;
+    let limit = query_params.limit.unwrap_or(10);
+
+    let offers = app_state.db_client
+        .get_offers_for_buyer(user.user.id, page, limit)
+        .await
+        .map_err(|e| HttpError::server_error(e.to_string()))?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "offers": offers,
+            "pagination": {
+                "page": page,
+                "limit": limit,
+                "total": offers.len()
+            }
+        }
+    })))
+}
+
+pub async fn get_offer_book(
+    Path(property_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    let (summary, offers) = app_state.offer_service
+        .offer_book(user.user.id, property_id)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "summary": summary,
+            "offers": offers
+        }
+    })))
+}
+
+pub async fn get_offer(
+    Path(offer_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    let offer = app_state.offer_service
+        .get_offer_for(user.user.id, offer_id)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "offer": offer
+        }
+    })))
+}
+
+pub async fn counter_offer(
+    Path(offer_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+    Json(body): Json<CounterOfferDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let offer = app_state.offer_service
+        .counter(user.user.id, offer_id, body)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Counter offer sent to the buyer",
+        "data": {
+            "offer": offer
+        }
+    })))
+}
+
+pub async fn accept_offer(
+    Path(offer_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    let offer = app_state.offer_service
+        .accept(user.user.id, offer_id)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Offer accepted. The earnest deposit is held until the transaction completes",
+        "data": {
+            "offer": offer
+        }
+    })))
+}
+
+pub async fn reject_offer(
+    Path(offer_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+    Json(body): Json<OfferNoteDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let offer = app_state.offer_service
+        .reject(user.user.id, offer_id, &body.reason)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Offer rejected",
+        "data": {
+            "offer": offer
+        }
+    })))
+}
+
+pub async fn withdraw_offer(
+    Path(offer_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    let offer = app_state.offer_service
+        .withdraw(user.user.id, offer_id)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Offer withdrawn",
+        "data": {
+            "offer": offer
+        }
+    })))
+}
+
+pub async fn cancel_offer(
+    Path(offer_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+    Json(body): Json<OfferNoteDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let offer = app_state.offer_service
+        .cancel(user.user.id, offer_id, &body.reason)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Deal cancelled and earnest deposit returned to the buyer",
+        "data": {
+            "offer": offer
+        }
+    })))
+}
+
+pub async fn complete_offer(
+    Path(offer_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    let offer = app_state.offer_service
+        .complete(user.user.id, offer_id)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Transaction completed and listing closed",
+        "data": {
+            "offer": offer
+        }
+    })))
+}
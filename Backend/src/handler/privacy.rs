@@ -0,0 +1,75 @@
+// handler/privacy.rs
+use std::sync::Arc;
+
+use axum::{
+    http::header,
+    response::IntoResponse,
+    routing::{delete, get},
+    Extension, Json, Router,
+};
+use serde_json::json;
+
+use crate::{
+    error::HttpError,
+    middleware::main_middleware::JWTAuthMiddeware,
+    AppState,
+};
+
+/// Data export and account deletion for the signed-in user, mounted behind auth
+pub fn privacy_handler() -> Router {
+    Router::new()
+        .route("/export", get(export_data))
+        .route("/account/deletion", get(get_deletion_status))
+        .route("/account", delete(delete_account))
+}
+
+/// The user's data as a ZIP of JSON files
+pub async fn export_data(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    let export = app_state.privacy_service.export(&auth.user).await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", export.file_name)),
+            (header::CACHE_CONTROL, "private, no-store".to_string()),
+        ],
+        export.contents,
+    ))
+}
+
+/// Whether the account can be deleted now, and what to settle first if not
+pub async fn get_deletion_status(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    let blockers = app_state.privacy_service.deletion_blockers(auth.user.id).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": {
+            "can_delete": blockers.is_clear(),
+            "blockers": blockers.reasons(),
+            "details": blockers
+        }
+    })))
+}
+
+/// Anonymises the account and signs it out everywhere. Needs a recent step-up.
+pub async fn delete_account(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    app_state.two_factor_service
+        .require_step_up(&auth.user, auth.session_id, None, None)
+        .await?;
+
+    app_state.privacy_service.delete_account(auth.user.id).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "message": "Your account has been deleted. Payment and verification records we are required to keep have been detached from your personal details."
+    })))
+}
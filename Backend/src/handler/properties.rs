@@ -1,78 +1,167 @@
+// handler/properties.rs
 use std::sync::Arc;
 use axum::{
     extract::{Path, Query},
+    http::HeaderMap,
     middleware,
     response::IntoResponse,
     routing::{get, post, put},
     Extension, Json, Router,
 };
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
-    db::{userdb::UserExt, propertydb::{PropertyExt, PropertySearchFilters}},
+    db::{
+        userdb::UserExt, photodb::PhotoIndexExt,
+        propertydb::{PropertyExt, PropertySearchFilters}, savedpropertydb::SavedPropertyExt,
+    },
     dtos::{
-        userdtos::{RequestQueryDto, Response},
-        propertydtos::{CreatePropertyDto, PropertyFilterDto, AgentVerificationDto, LawyerVerificationDto}
+        userdtos::RequestQueryDto,
+        propertydtos::{
+            AgentVerificationDto, AssignAgentDto, CreatePropertyDto, LawyerVerificationDto,
+            ListingStatsQueryDto, MarketSummaryQueryDto, MarketTrendQueryDto, PhotoSearchDto,
+            PropertyFilterDto, PropertySearchQueryDto, UpdatePriceDto, VerifierMetricsQueryDto,
+        },
     },
     error::HttpError,
-    middleware::{role_check, JWTAuthMiddeware},
+    middleware::main_middleware::{permission_check, JWTAuthMiddeware},
+    service::{
+        certificate_service::CertificateService,
+        property_service::{describe_trend, PHOTO_MATCH_MAX_DISTANCE},
+    },
     models::{
-        propertymodel::{
-            PropertyStatus, ListingType, PropertyType, CurrencyType
-        },
+        mediamodels::MediaPurpose,
+        permissionmodels::Permission,
+        propertymodel::{Property, PropertyStatus, VerifierRole},
         usermodel::UserRole,
     },
     AppState,
 };
 
+/// Browsing listed properties, mounted without auth
+pub fn public_property_handler() -> Router {
+    Router::new()
+        .route("/active", get(get_active_properties))
+        .route("/market/summary", get(get_market_summary))
+        .route("/market/trends", get(get_market_trends))
+        .route("/:property_id", get(get_property_by_id))
+        .route("/:property_id/comparables", get(get_comparable_properties))
+        .route("/:property_id/price-history", get(get_price_history))
+        .route("/:property_id/certificate", get(get_property_certificate))
+}
+
+/// The listing and verification pipeline, mounted behind auth
 pub fn property_handler() -> Router {
     Router::new()
         .route(
-            "/create", 
+            "/create",
             post(create_property).layer(middleware::from_fn(|state, req, next| {
-                role_check(state, req, next, vec![UserRole::Landlord])
+                permission_check(state, req, next, Permission::PropertiesManageOwn)
+            })),
+        )
+        .route(
+            "/my-properties",
+            get(get_landlord_properties).layer(middleware::from_fn(|state, req, next| {
+                permission_check(state, req, next, Permission::PropertiesManageOwn)
+            })),
+        )
+        .route(
+            "/dashboard",
+            get(get_landlord_dashboard).layer(middleware::from_fn(|state, req, next| {
+                permission_check(state, req, next, Permission::PropertiesManageOwn)
             })),
         )
         .route(
-           "/my-properties",
-           get(get_landlord_properties).layer(middleware::from_fn(|state, req, next| {
-            role_check(state, req, next, vec![UserRole::Landlord])
-           })),
+            "/:property_id/price",
+            put(update_property_price).layer(middleware::from_fn(|state, req, next| {
+                permission_check(state, req, next, Permission::PropertiesManageOwn)
+            })),
         )
         .route(
             "/for-agent-verification",
             get(get_properties_for_agent).layer(middleware::from_fn(|state, req, next| {
-                role_check(state, req, next, vec![UserRole::Agent])
+                permission_check(state, req, next, Permission::PropertiesAgentReview)
             })),
         )
         .route(
-            "/agent-verfy/:property_id", 
+            "/agent-verify/:property_id",
             post(agent_verify_property).layer(middleware::from_fn(|state, req, next| {
-                role_check(state, req, next, vec![UserRole::Agent])
+                permission_check(state, req, next, Permission::PropertiesAgentReview)
             })),
         )
         .route(
             "/assign-agent/:property_id",
             put(assign_agent_to_property).layer(middleware::from_fn(|state, req, next| {
-                role_check(state, req, next, vec![UserRole::Admin, UserRole::Moderator])
-            }))
+                permission_check(state, req, next, Permission::PropertiesModerate)
+            })),
         )
         .route(
             "/for-lawyer-verification",
             get(get_properties_for_lawyer).layer(middleware::from_fn(|state, req, next| {
-                role_check(state, req, next, vec![UserRole::Lawyer])
+                permission_check(state, req, next, Permission::PropertiesLawyerReview)
             })),
         )
         .route(
             "/lawyer-verify/:property_id",
             post(lawyer_verify_property).layer(middleware::from_fn(|state, req, next| {
-                role_check(state, req, next, vec![UserRole::Lawyer])
-            }))
+                permission_check(state, req, next, Permission::PropertiesLawyerReview)
+            })),
+        )
+        .route(
+            "/admin/:property_id/suspend",
+            put(suspend_property).layer(middleware::from_fn(|state, req, next| {
+                permission_check(state, req, next, Permission::PropertiesModerate)
+            })),
+        )
+        .route(
+            "/admin/:property_id/reinstate",
+            put(reinstate_property).layer(middleware::from_fn(|state, req, next| {
+                permission_check(state, req, next, Permission::PropertiesModerate)
+            })),
+        )
+        .route(
+            "/admin/verifier-metrics",
+            get(get_verifier_metrics).layer(middleware::from_fn(|state, req, next| {
+                permission_check(state, req, next, Permission::PropertiesModerate)
+            })),
+        )
+        .route(
+            "/my-assignments",
+            get(get_my_assignments).layer(middleware::from_fn(|state, req, next| {
+                permission_check(state, req, next, Permission::PropertiesVerificationAssignments)
+            })),
+        )
+        .route(
+            "/admin/photo-search",
+            post(search_by_photo).layer(middleware::from_fn(|state, req, next| {
+                permission_check(state, req, next, Permission::PropertiesModerate)
+            })),
+        )
+        .route(
+            "/admin/photo-flags",
+            get(get_photo_flagged_listings).layer(middleware::from_fn(|state, req, next| {
+                permission_check(state, req, next, Permission::PropertiesModerate)
+            })),
         )
-        .route("/active", get(get_active_properties))
-        .route("/:property_id", get(get_property_by_id))
         .route("/:property_id/verification-history", get(get_verification_history))
+        .route("/:property_id/photo-report", get(get_photo_report))
+        .route("/:property_id/analytics", get(get_listing_analytics))
+}
+
+pub(crate) fn is_admin(auth: &JWTAuthMiddeware) -> bool {
+    auth.has_permission(Permission::PropertiesModerate)
+}
+
+pub(crate) async fn landlord_name(app_state: &AppState, property: &Property) -> Result<String, HttpError> {
+    let landlord = app_state.db_client
+        .get_user(Some(property.landlord_id), None, None, None)
+        .await
+        .map_err(|e| HttpError::server_error(e.to_string()))?
+        .ok_or_else(|| HttpError::server_error("Landlord not found"))?;
+
+    Ok(landlord.username)
 }
 
 //Landlord creates property
@@ -84,25 +173,28 @@ pub async fn create_property(
     body.validate()
         .map_err(|e| HttpError::bad_request(e.to_string()))?;
 
-    //Verify the user is a landlord
-    if user.user.role != UserRole::Landlord {
-        return Err(HttpError::unauthorized("Only landlords can create properties"));
-    }
-
-    let property = app_state.db_client
-        .create_property(user.user.id, body)
-        .await
-        .map_err(|e| {
-            if e.to_string().contains("unique_violation") || e.to_string().contains("Property already exists") {
-                HttpError::bad_request("A similar property already exists at this location")
-            } else {
-                HttpError::server_error(e.to_string())
-            }
-        })?;
-
-    //Get landlord info
-    let landlord_username = user.user.username.clone();
-    let filtered_property = PropertyFilterDto::from_property(&property, landlord_username);
+    app_state.storage_service
+        .ensure_all_owned(user.user.id, &body.property_photos, &[MediaPurpose::PropertyPhoto])
+        .await?;
+    let documents: Vec<String> = [
+        &body.certificate_of_occupancy,
+        &body.deed_of_agreement,
+        &body.survey_plan,
+        &body.building_plan_approval,
+    ]
+    .into_iter()
+    .flatten()
+    .cloned()
+    .collect();
+    app_state.storage_service
+        .ensure_all_owned(user.user.id, &documents, &[MediaPurpose::PropertyDocument])
+        .await?;
+
+    let property = app_state.property_service
+        .create_listing(user.user.id, body)
+        .await?;
+
+    let filtered_property = PropertyFilterDto::from_property(&property, user.user.username.clone());
 
     Ok(Json(serde_json::json!({
         "status": "success",
@@ -130,7 +222,7 @@ pub async fn get_landlord_properties(
         .map_err(|e| HttpError::server_error(e.to_string()))?;
 
     let landlord_username = user.user.username.clone();
-    let filtered_properies:Vec<PropertyFilterDto> = properties
+    let filtered_properties: Vec<PropertyFilterDto> = properties
         .iter()
         .map(|p| PropertyFilterDto::from_property(p, landlord_username.clone()))
         .collect();
@@ -138,15 +230,14 @@ pub async fn get_landlord_properties(
     Ok(Json(serde_json::json!({
         "status": "success",
         "data": {
-            "properties": filtered_properies,
+            "properties": filtered_properties,
             "pagination": {
                 "page": page,
                 "limit": limit,
-                "total": filtered_properies.len()
+                "total": filtered_properties.len()
             }
         }
     })))
-
 }
 
 pub async fn get_properties_for_agent(
@@ -165,12 +256,15 @@ pub async fn get_properties_for_agent(
             .get_user(Some(property.landlord_id), None, None, None)
             .await
             .map_err(|e| HttpError::server_error(e.to_string()))?
-            .ok_or_else(|| HttpError::server_error("Landlord not Found"))?;
+            .ok_or_else(|| HttpError::server_error("Landlord not found"))?;
 
-        let mut filtered_property = PropertyFilterDto::from_property(&property, landlord.name);
+        let filtered_property = PropertyFilterDto::from_property(&property, landlord.username.clone());
+        let photo_report = app_state.property_service
+            .photo_report(property.id)
+            .await?;
 
         //Adding additional verification info for agents
-        let property_info = serde_json::json!({
+        property_data.push(serde_json::json!({
             "property": filtered_property,
             "verification_info": {
                 "address": property.address,
@@ -180,14 +274,13 @@ pub async fn get_properties_for_agent(
                     "longitude": property.longitude
                 },
                 "landlord_contact": {
-                    "name": landlord.name.clone(),
-                    
+                    "name": landlord.name,
+                    "phone_number": landlord.phone_number
                 },
-                "property_photos": property.property_photos.0,
-            }
-        });
-
-        property_data.push(property_info);
+                "property_photos": property.property_photos,
+            },
+            "photo_report": photo_report
+        }));
     }
 
     Ok(Json(serde_json::json!({
@@ -208,37 +301,18 @@ pub async fn agent_verify_property(
     body.validate()
         .map_err(|e| HttpError::bad_request(e.to_string()))?;
 
-    //Set property_id from path
-   let property_id = body.property_id;
-
-    //Verifying the property is assigned to this agent
-    let property = app_state.db_client
-        .get_property_by_id(property_id)
-        .await
-        .map_err(|e| HttpError::server_error(e.to_string()))?
-        .ok_or_else(|| HttpError::server_error("Property not found"))?;
-
-    if property.agent_id != Some(user.user.id) {
-        return Err(HttpError::unauthorized("You are not assigned to verify this property"))
-    }
-
-    if property.status != PropertyStatus::AwaitingAgent {
-        return Err(HttpError::bad_request("Property is not awaiting agent verification"));
-    }
+    app_state.storage_service
+        .ensure_all_owned(user.user.id, &body.verification_photos, &[MediaPurpose::PropertyPhoto])
+        .await?;
 
-    let updated_property = app_state.db_client
-        .agent_verify_property(user.user.id, body)
-        .await
-        .map_err(|e| HttpError::server_error(e.to_string()))?;
+    let updated_property = app_state.property_service
+        .agent_review(user.user.id, property_id, body)
+        .await?;
 
-    //Get landlord info
-    let landlord = app_state.db_client
-        .get_user(Some(updated_property.landlord_id), None, None, None)
-        .await
-        .map_err(|e| HttpError::server_error(e.to_string()))?
-        .ok_or_else(|| HttpError::server_error("Landlord not found"))?;
-
-    let filtered_property = PropertyFilterDto::from_property(&updated_property, landlord.name);
+    let filtered_property = PropertyFilterDto::from_property(
+        &updated_property,
+        landlord_name(&app_state, &updated_property).await?,
+    );
 
     Ok(Json(serde_json::json!({
         "status": "success",
@@ -257,28 +331,22 @@ pub async fn assign_agent_to_property(
     Path(property_id): Path<Uuid>,
     Extension(app_state): Extension<Arc<AppState>>,
     Extension(user): Extension<JWTAuthMiddeware>,
-    Json(body): Json<serde_json::Value>,
+    Json(body): Json<AssignAgentDto>,
 ) -> Result<impl IntoResponse, HttpError> {
-    let agent_id = body["agent_id"]
-        .as_str()
-        .and_then(|id| Uuid::parse_str(id).ok())
-        .ok_or_else(|| HttpError::bad_request("Valid agent_id is required"))?;
-
     //verify agent exist and has correct role
     let agent = app_state.db_client
-        .get_user(Some(agent_id), None, None, None)
+        .get_user(Some(body.agent_id), None, None, None)
         .await
         .map_err(|e| HttpError::server_error(e.to_string()))?
-        .ok_or_else(||HttpError::bad_request("Agent not found"))?;
+        .ok_or_else(|| HttpError::bad_request("Agent not found"))?;
 
-    if agent.role != UserRole::Agent {
+    if !app_state.role_service.has_role(agent.id, UserRole::Agent).await? {
         return Err(HttpError::bad_request("User is not an agent"));
     }
 
-    let updated_property = app_state.db_client
-        .assign_agent_to_property(property_id, agent_id)
-        .await
-        .map_err(|e| HttpError::server_error(e.to_string()))?;
+    let updated_property = app_state.property_service
+        .assign_agent(property_id, agent.id, user.user.id)
+        .await?;
 
     Ok(Json(serde_json::json!({
         "status": "success",
@@ -313,41 +381,39 @@ pub async fn get_properties_for_lawyer(
             .map_err(|e| HttpError::server_error(e.to_string()))?
             .ok_or_else(|| HttpError::server_error("Landlord not found"))?;
 
-        let agent = if let Some(agent_id) = property.agent_id {
-            app_state.db_client
-        .get_user(Some(agent_id), None, None, None)
-        .await
-        .map_err(|e| HttpError::server_error(e.to_string()))?
-    } else {
-        None
-    };
+        let agent = match property.agent_id {
+            Some(agent_id) => app_state.db_client
+                .get_user(Some(agent_id), None, None, None)
+                .await
+                .map_err(|e| HttpError::server_error(e.to_string()))?,
+            None => None,
+        };
 
-    let filtered_property = PropertyFilterDto::from_property(&property, landlord.name.clone());
+        let filtered_property = PropertyFilterDto::from_property(&property, landlord.username.clone());
 
-    let property_info = serde_json::json!({
-        "property": filtered_property,
-        "document_verification_info": {
-            "documents": {
-                "certificate_of_ownership": property.certificate_of_occupancy,
-                "deed_of_agreement": property.deed_of_agreement,
-                "survey_plan": property.survey_plan,
-                "building_plan_approval": property.building_plan_approval
-            },
-            "landlord_info": {
-                "name": landlord.name.clone(),
-                "email": landlord.email,
-                "verification_status": landlord.verification_status,
-                "nin_number": landlord.nin_number
-            },
-            "agent_verification": {
-                "agent_name": agent.as_ref().map(|a| a.name.clone()),
-                "verification_notes": property.agent_verification_notes,
-                "verification_photos": property.agent_verification_photos,
-                "verified_at": property.agent_verified_at
+        property_data.push(serde_json::json!({
+            "property": filtered_property,
+            "document_verification_info": {
+                "documents": {
+                    "certificate_of_occupancy": property.certificate_of_occupancy,
+                    "deed_of_agreement": property.deed_of_agreement,
+                    "survey_plan": property.survey_plan,
+                    "building_plan_approval": property.building_plan_approval
+                },
+                "landlord_info": {
+                    "name": landlord.name,
+                    "email": landlord.email,
+                    "verification_status": landlord.verification_status,
+                    "nin_number": landlord.nin_number
+                },
+                "agent_verification": {
+                    "agent_name": agent.as_ref().map(|a| a.name.clone()),
+                    "verification_notes": property.agent_verification_notes,
+                    "verification_photos": property.agent_verification_photos,
+                    "verified_at": property.agent_verified_at
+                }
             }
-        }
-    });
-        property_data.push(property_info);
+        }));
     }
 
     Ok(Json(serde_json::json!({
@@ -368,34 +434,14 @@ pub async fn lawyer_verify_property(
     body.validate()
         .map_err(|e| HttpError::bad_request(e.to_string()))?;
 
-    //Set property_id from path
-    let property_id= body.property_id;
-
-    //Verify the property is awaiting lawyer verification
-
-    let property = app_state.db_client
-        .get_property_by_id(property_id)
-        .await
-        .map_err(|e| HttpError::server_error(e.to_string()))?
-        .ok_or_else(|| HttpError::bad_request("Property not Found"))?;
-
-    if property.status != PropertyStatus::AwaitingLawyer {
-        return Err(HttpError::unauthorized("Property is not awaiting lawyer verification"));
-    }
-
-    let updated_property = app_state.db_client
-        .lawyer_verify_property(user.user.id, body)
-        .await
-        .map_err(|e| HttpError::server_error(e.to_string()))?;
-
-    //Get landlord info
-    let landlord = app_state.db_client
-        .get_user(Some(updated_property.landlord_id), None, None, None)
-        .await
-        .map_err(|e| HttpError::server_error(e.to_string()))?
-        .ok_or_else(|| HttpError::server_error("Landlord not found"))?;
+    let updated_property = app_state.property_service
+        .lawyer_review(user.user.id, property_id, body)
+        .await?;
 
-    let filtered_property = PropertyFilterDto::from_property(&updated_property, landlord.name);
+    let filtered_property = PropertyFilterDto::from_property(
+        &updated_property,
+        landlord_name(&app_state, &updated_property).await?,
+    );
 
     Ok(Json(serde_json::json!({
         "status": "success",
@@ -410,10 +456,45 @@ pub async fn lawyer_verify_property(
     })))
 }
 
+pub async fn suspend_property(
+    Path(property_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+) -> Result<impl IntoResponse, HttpError> {
+    let property = app_state.property_service
+        .set_suspended(property_id, true)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Property suspended",
+        "data": {
+            "property_id": property.id,
+            "status": property.status
+        }
+    })))
+}
+
+pub async fn reinstate_property(
+    Path(property_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+) -> Result<impl IntoResponse, HttpError> {
+    let property = app_state.property_service
+        .set_suspended(property_id, false)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Property reinstated",
+        "data": {
+            "property_id": property.id,
+            "status": property.status
+        }
+    })))
+}
+
 pub async fn get_active_properties(
-    Query(query_params): Query<RequestQueryDto>,
+    Query(query_params): Query<PropertySearchQueryDto>,
     Extension(app_state): Extension<Arc<AppState>>,
-    Query(filters): Query<serde_json::Value>,
 ) -> Result<impl IntoResponse, HttpError> {
     query_params.validate()
         .map_err(|e| HttpError::bad_request(e.to_string()))?;
@@ -421,17 +502,17 @@ pub async fn get_active_properties(
     let page = query_params.page.unwrap_or(1) as u32;
     let limit = query_params.limit.unwrap_or(10);
 
-    //Parse search FIlters
     let search_filters = PropertySearchFilters {
-        property_type: filters["property_type"].as_str().and_then(|t| serde_json::from_str(&format!("\"{}\"", t)).ok()),
-        listing_type: filters["listing_type"].as_str().and_then(|t| serde_json::from_str(&format!("\"{}\"", t)).ok()),
-        min_price: filters["min_price"].as_i64(),
-        max_price: filters["max_price"].as_i64(),
-        city: filters["city"].as_str().map(String::from),
-        state: filters["state"].as_str().map(String::from),
-        country: filters["country"].as_str().map(String::from),
-        bedrooms: filters["bedrooms"].as_i64().map(|b| b as i32),
-        bathrooms: filters["bathrooms"].as_i64().map(|b| b as i32),
+        property_type: query_params.property_type,
+        listing_type: query_params.listing_type,
+        min_price: query_params.min_price,
+        max_price: query_params.max_price,
+        city: query_params.city,
+        state: query_params.state,
+        country: query_params.country,
+        bedrooms: query_params.bedrooms,
+        bathrooms: query_params.bathrooms,
+        listed_after: None,
     };
 
     let properties = app_state.db_client
@@ -440,16 +521,9 @@ pub async fn get_active_properties(
         .map_err(|e| HttpError::server_error(e.to_string()))?;
 
     let mut property_data = Vec::new();
-
     for property in properties {
-        let landlord = app_state.db_client
-            .get_user(Some(property.landlord_id), None, None, None)
-            .await
-            .map_err(|e| HttpError::server_error(e.to_string()))?
-            .ok_or_else(|| HttpError::server_error("Landlord not found"))?;
-
-        let filtered_property = PropertyFilterDto::from_property(&property, landlord.name);
-        property_data.push(filtered_property);
+        let landlord_username = landlord_name(&app_state, &property).await?;
+        property_data.push(PropertyFilterDto::from_property(&property, landlord_username));
     }
 
     Ok(Json(serde_json::json!({
@@ -468,20 +542,41 @@ pub async fn get_active_properties(
 pub async fn get_property_by_id(
     Path(property_id): Path<Uuid>,
     Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<Option<JWTAuthMiddeware>>,
+    headers: HeaderMap,
 ) -> Result<impl IntoResponse, HttpError> {
-    let property = app_state.db_client
-        .get_property_by_id(property_id)
-        .await
-        .map_err(|e| HttpError::server_error(e.to_string()))?
-        .ok_or_else(|| HttpError::bad_request("Property not found"))?;
-    
-    let landlord = app_state.db_client
-        .get_user(Some(property.landlord_id), None, None, None)
-        .await
-        .map_err(|e| HttpError::server_error(e.to_string()))?
-        .ok_or_else(|| HttpError::server_error("Landlord not found"))?;
+    let property = app_state.property_service
+        .get_property(property_id)
+        .await?;
 
-    let filtered_property = PropertyFilterDto::from_property(&property, landlord.username);
+    // Listings still in verification are not public
+    if !property.status.is_public() {
+        return Err(HttpError::not_found("Property not found"));
+    }
+
+    // Record view, except the landlord looking at their own listing
+    let viewer_id = auth.as_ref().map(|a| a.user.id);
+    if viewer_id != Some(property.landlord_id) {
+        let viewer_ip = headers
+            .get("x-forwarded-for")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(',').next())
+            .and_then(|v| v.trim().parse::<std::net::IpAddr>().ok())
+            .map(|ip| ip.to_string());
+        let user_agent = headers
+            .get("user-agent")
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.chars().take(500).collect::<String>());
+
+        if let Err(e) = app_state.db_client
+            .record_property_view(property_id, viewer_id, viewer_ip, user_agent)
+            .await
+        {
+            tracing::warn!("Failed to record view of property {}: {}", property_id, e);
+        }
+    }
+
+    let filtered_property = PropertyFilterDto::from_property(&property, landlord_name(&app_state, &property).await?);
 
     Ok(Json(serde_json::json!({
         "status": "success",
@@ -491,10 +586,237 @@ pub async fn get_property_by_id(
     })))
 }
 
+/// Past asking prices of a public listing, oldest first
+pub async fn get_price_history(
+    Path(property_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+) -> Result<impl IntoResponse, HttpError> {
+    let property = app_state.property_service
+        .get_property(property_id)
+        .await?;
+
+    if !property.status.is_public() {
+        return Err(HttpError::not_found("Property not found"));
+    }
+
+    let history = app_state.property_service
+        .price_history(property_id)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "property_id": property_id,
+            "current_price": property.price,
+            "changes": history
+        }
+    })))
+}
+
+/// The listing's verification certificate, checked the same way as the
+/// public `/verify/property/:reference` endpoint
+pub async fn get_property_certificate(
+    Path(property_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+) -> Result<impl IntoResponse, HttpError> {
+    let property = app_state.property_service
+        .get_property(property_id)
+        .await?;
+
+    if !property.status.is_public() {
+        return Err(HttpError::not_found("Property not found"));
+    }
+
+    let check = app_state.certificate_service
+        .check(&CertificateService::reference(&property))
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": check
+    })))
+}
+
+pub async fn update_property_price(
+    Path(property_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+    Json(body): Json<UpdatePriceDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let property = app_state.property_service
+        .change_price(user.user.id, property_id, body.price)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Price updated",
+        "data": {
+            "property_id": property.id,
+            "price": property.price
+        }
+    })))
+}
+
+/// Views, saves, viewing requests and offers on each of the landlord's listings
+pub async fn get_landlord_dashboard(
+    Query(query_params): Query<ListingStatsQueryDto>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    query_params.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let days = query_params.days.unwrap_or(30);
+    let listings = app_state.property_service
+        .landlord_dashboard(user.user.id, days)
+        .await?;
+
+    let totals = serde_json::json!({
+        "views": listings.iter().map(|l| l.views).sum::<i64>(),
+        "unique_viewers": listings.iter().map(|l| l.unique_viewers).sum::<i64>(),
+        "saves": listings.iter().map(|l| l.saves).sum::<i64>(),
+        "viewing_requests": listings.iter().map(|l| l.viewing_requests).sum::<i64>(),
+        "offers": listings.iter().map(|l| l.offers).sum::<i64>(),
+    });
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "days": days,
+            "totals": totals,
+            "listings": listings
+        }
+    })))
+}
+
+/// Day-by-day activity on one listing, for its landlord or an admin
+pub async fn get_listing_analytics(
+    Path(property_id): Path<Uuid>,
+    Query(query_params): Query<ListingStatsQueryDto>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    query_params.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let property = app_state.property_service
+        .get_property(property_id)
+        .await?;
+
+    if property.landlord_id != user.user.id && !is_admin(&user) {
+        return Err(HttpError::unauthorized("You cannot view this property's analytics"));
+    }
+
+    let days = query_params.days.unwrap_or(30);
+    let activity = app_state.property_service
+        .listing_activity(property_id, days)
+        .await?;
+    let price_history = app_state.property_service
+        .price_history(property_id)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "property_id": property_id,
+            "days": days,
+            "activity": activity,
+            "price_history": price_history
+        }
+    })))
+}
+
+/// Going rates per LGA and property type in a state, from verified listings
+pub async fn get_market_summary(
+    Query(query_params): Query<MarketSummaryQueryDto>,
+    Extension(app_state): Extension<Arc<AppState>>,
+) -> Result<impl IntoResponse, HttpError> {
+    query_params.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let summaries = app_state.property_service
+        .get_market_summaries(
+            &query_params.state,
+            query_params.lga.as_deref(),
+            query_params.property_type,
+            query_params.listing_type,
+        )
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "markets": summaries,
+            "total": summaries.len()
+        }
+    })))
+}
+
+pub async fn get_market_trends(
+    Query(query_params): Query<MarketTrendQueryDto>,
+    Extension(app_state): Extension<Arc<AppState>>,
+) -> Result<impl IntoResponse, HttpError> {
+    query_params.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let points = app_state.property_service
+        .market_trend(&query_params.state, query_params.property_type, query_params.listing_type)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "summary": describe_trend(&points),
+            "months": points
+        }
+    })))
+}
+
+/// Similar listings nearby and where this one sits in its market
+pub async fn get_comparable_properties(
+    Path(property_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+) -> Result<impl IntoResponse, HttpError> {
+    let property = app_state.property_service
+        .get_property(property_id)
+        .await?;
+
+    if !property.status.is_public() {
+        return Err(HttpError::not_found("Property not found"));
+    }
+
+    let analysis = app_state.property_service
+        .property_market_analysis(&property)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "analysis": analysis
+        }
+    })))
+}
+
 pub async fn get_verification_history(
     Path(property_id): Path<Uuid>,
     Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
 ) -> Result<impl IntoResponse, HttpError> {
+    let property = app_state.property_service
+        .get_property(property_id)
+        .await?;
+
+    let user_id = user.user.id;
+    let involved = property.landlord_id == user_id
+        || property.agent_id == Some(user_id)
+        || property.lawyer_id == Some(user_id);
+    if !involved && !is_admin(&user) {
+        return Err(HttpError::unauthorized("You cannot view this property's verification history"));
+    }
+
     let verification = app_state.db_client
         .get_property_verification_history(property_id)
         .await
@@ -506,4 +828,148 @@ pub async fn get_verification_history(
             "verification": verification
         }
     })))
-}
\ No newline at end of file
+}
+
+/// Photo similarity report for the agent reviewing the listing
+pub async fn get_photo_report(
+    Path(property_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    let property = app_state.property_service
+        .get_property(property_id)
+        .await?;
+
+    let involved = property.agent_id == Some(user.user.id)
+        || property.lawyer_id == Some(user.user.id);
+    if !involved && !is_admin(&user) {
+        return Err(HttpError::unauthorized("You cannot view this property's photo report"));
+    }
+
+    let report = app_state.property_service
+        .photo_report(property_id)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "report": report
+        }
+    })))
+}
+
+/// Turnaround, on-time rate and lapses per agent and lawyer
+pub async fn get_verifier_metrics(
+    Query(query_params): Query<VerifierMetricsQueryDto>,
+    Extension(app_state): Extension<Arc<AppState>>,
+) -> Result<impl IntoResponse, HttpError> {
+    query_params.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let days = query_params.days.unwrap_or(30);
+    let metrics = app_state.property_service
+        .verifier_metrics(query_params.verifier_type, None, days)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "days": days,
+            "verifiers": metrics
+        }
+    })))
+}
+
+/// The listings an agent or lawyer holds, soonest due first, with their own metrics
+pub async fn get_my_assignments(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    // Someone who is both agent and lawyer sees metrics across both
+    let role = match (user.has_role(UserRole::Agent), user.has_role(UserRole::Lawyer)) {
+        (true, false) => Some(VerifierRole::Agent),
+        (false, true) => Some(VerifierRole::Lawyer),
+        _ => None,
+    };
+
+    let assignments = app_state.property_service
+        .verifier_assignments(user.user.id)
+        .await?;
+    let metrics = app_state.property_service
+        .verifier_metrics(role, Some(user.user.id), 90)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "assignments": assignments,
+            "metrics": metrics.into_iter().next()
+        }
+    })))
+}
+
+pub async fn search_by_photo(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Json(body): Json<PhotoSearchDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let max_distance = body.max_distance.unwrap_or(PHOTO_MATCH_MAX_DISTANCE);
+    let limit = body.limit.unwrap_or(20);
+
+    let matches = match (&body.image_url, &body.image_base64) {
+        (Some(url), _) => app_state.property_service
+            .search_by_image_url(url, max_distance, limit)
+            .await?,
+        (None, Some(encoded)) => {
+            // Accept data URLs as pasted from the browser
+            let encoded = encoded.split_once(',').map_or(encoded.as_str(), |(_, data)| data);
+            let image_data = BASE64
+                .decode(encoded.trim())
+                .map_err(|_| HttpError::bad_request("Image is not valid base64"))?;
+
+            app_state.property_service
+                .search_by_image(&image_data, max_distance, limit)
+                .await?
+        }
+        (None, None) => return Err(HttpError::bad_request("Provide image_url or image_base64")),
+    };
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "matches": matches,
+            "total": matches.len()
+        }
+    })))
+}
+
+/// Listings in review whose photos appear on another landlord's listing
+pub async fn get_photo_flagged_listings(
+    Query(query_params): Query<RequestQueryDto>,
+    Extension(app_state): Extension<Arc<AppState>>,
+) -> Result<impl IntoResponse, HttpError> {
+    query_params.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let page = query_params.page.unwrap_or(1) as u32;
+    let limit = query_params.limit.unwrap_or(10);
+
+    let listings = app_state.db_client
+        .get_flagged_listings(page, limit)
+        .await
+        .map_err(|e| HttpError::server_error(e.to_string()))?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "listings": listings,
+            "pagination": {
+                "page": page,
+                "limit": limit,
+                "total": listings.len()
+            }
+        }
+    })))
+}
@@ -0,0 +1,189 @@
+// handler/saved.rs
+use std::sync::Arc;
+use axum::{
+    extract::{Path, Query},
+    response::IntoResponse,
+    routing::{get, put},
+    Extension, Json, Router,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    db::propertydb::PropertySearchFilters,
+    dtos::{
+        userdtos::RequestQueryDto,
+        propertydtos::{PropertyFilterDto, SavedSearchDto, SearchAlertsDto},
+    },
+    error::HttpError,
+    handler::properties::landlord_name,
+    middleware::main_middleware::JWTAuthMiddeware,
+    AppState,
+};
+
+/// Saved properties and saved searches, mounted behind auth
+pub fn saved_handler() -> Router {
+    Router::new()
+        .route("/properties", get(get_saved_properties))
+        .route("/properties/:property_id", put(save_property).delete(unsave_property))
+        .route("/searches", get(get_saved_searches).post(create_saved_search))
+        .route("/searches/:search_id", axum::routing::delete(delete_saved_search))
+        .route("/searches/:search_id/alerts", put(set_search_alerts))
+}
+
+pub async fn get_saved_properties(
+    Query(query_params): Query<RequestQueryDto>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    query_params.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let page = query_params.page.unwrap_or(1) as u32;
+    let limit = query_params.limit.unwrap_or(10);
+
+    let properties = app_state.saved_property_service
+        .saved_properties(user.user.id, page, limit)
+        .await?;
+
+    let mut property_data = Vec::new();
+    for property in properties {
+        let landlord_username = landlord_name(&app_state, &property).await?;
+        property_data.push(PropertyFilterDto::from_property(&property, landlord_username));
+    }
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "properties": property_data,
+            "pagination": {
+                "page": page,
+                "limit": limit,
+                "total": property_data.len()
+            }
+        }
+    })))
+}
+
+pub async fn save_property(
+    Path(property_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    let saved = app_state.saved_property_service
+        .save_property(user.user.id, property_id)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": if saved { "Property saved" } else { "Property already saved" },
+    })))
+}
+
+pub async fn unsave_property(
+    Path(property_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    let removed = app_state.saved_property_service
+        .unsave_property(user.user.id, property_id)
+        .await?;
+
+    if !removed {
+        return Err(HttpError::not_found("Property is not in your saved list"));
+    }
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Property removed from your saved list",
+    })))
+}
+
+pub async fn get_saved_searches(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    let searches = app_state.saved_property_service
+        .searches(user.user.id)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "searches": searches,
+            "total": searches.len()
+        }
+    })))
+}
+
+pub async fn create_saved_search(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+    Json(body): Json<SavedSearchDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let filters = PropertySearchFilters {
+        property_type: body.property_type,
+        listing_type: body.listing_type,
+        min_price: body.min_price,
+        max_price: body.max_price,
+        city: body.city,
+        state: body.state,
+        country: body.country,
+        bedrooms: body.bedrooms,
+        bathrooms: body.bathrooms,
+        listed_after: None,
+    };
+
+    let search = app_state.saved_property_service
+        .create_search(user.user.id, &body.name, filters, body.alerts_enabled.unwrap_or(true))
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": if search.alerts_enabled {
+            "Search saved. We will let you know when new listings match"
+        } else {
+            "Search saved"
+        },
+        "data": {
+            "search": search
+        }
+    })))
+}
+
+pub async fn delete_saved_search(
+    Path(search_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    app_state.saved_property_service
+        .delete_search(user.user.id, search_id)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Saved search deleted",
+    })))
+}
+
+pub async fn set_search_alerts(
+    Path(search_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+    Json(body): Json<SearchAlertsDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    let search = app_state.saved_property_service
+        .set_search_alerts(user.user.id, search_id, body.alerts_enabled)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": if search.alerts_enabled { "Alerts turned on" } else { "Alerts turned off" },
+        "data": {
+            "search": search
+        }
+    })))
+}
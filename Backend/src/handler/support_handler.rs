@@ -15,7 +15,7 @@ use crate::{
     error::HttpError,
     middleware::main_middleware::JWTAuthMiddeware,
     models::{
-        usermodel::UserRole,
+        permissionmodels::Permission,
         supportmodel::*
     },
 
@@ -92,8 +92,7 @@ pub async fn get_tickets(
     Query(params): Query<SupportQueryParams>,
 ) -> Result<impl IntoResponse, HttpError> {
     // Only customer care and admin can access all tickets
-    if auth.user.role != UserRole::CustomerCare 
-        && auth.user.role != UserRole::Admin {
+    if !auth.has_permission(Permission::SupportHandle) {
         return Err(HttpError::unauthorized("Not authorized"));
     }
 
@@ -150,8 +149,7 @@ pub async fn add_message(
         .map_err(|e| HttpError::server_error(e.to_string()))?
         .ok_or_else(|| HttpError::not_found("Ticket not found"))?;
 
-    let is_customer_care = auth.user.role == UserRole::CustomerCare 
-        || auth.user.role == UserRole::Admin;
+    let is_customer_care = auth.has_permission(Permission::SupportHandle);
 
     if !is_customer_care && ticket.user_id != auth.user.id {
         return Err(HttpError::unauthorized("Not authorized to access this ticket"));
@@ -189,8 +187,7 @@ pub async fn update_ticket_status(
     Json(body): Json<UpdateTicketStatusDto>,
 ) -> Result<impl IntoResponse, HttpError> {
     // Only customer care and admin can update status
-    if auth.user.role != UserRole::CustomerCare 
-        && auth.user.role != UserRole::Admin {
+    if !auth.has_permission(Permission::SupportHandle) {
         return Err(HttpError::unauthorized("Not authorized"));
     }
 
@@ -213,8 +210,7 @@ pub async fn assign_ticket(
     Json(body): Json<AssignTicketDto>,
 ) -> Result<impl IntoResponse, HttpError> {
     // Only customer care and admin can assign tickets
-    if auth.user.role != UserRole::CustomerCare 
-        && auth.user.role != UserRole::Admin {
+    if !auth.has_permission(Permission::SupportHandle) {
         return Err(HttpError::unauthorized("Not authorized"));
     }
 
@@ -241,8 +237,7 @@ pub async fn get_ticket(
         .map_err(|e| HttpError::server_error(e.to_string()))?
         .ok_or_else(|| HttpError::not_found("Ticket not found"))?;
 
-    let is_customer_care = auth.user.role == UserRole::CustomerCare 
-        || auth.user.role == UserRole::Admin;
+    let is_customer_care = auth.has_permission(Permission::SupportHandle);
 
     if !is_customer_care && ticket.ticket.user_id != auth.user.id {
         return Err(HttpError::unauthorized("Not authorized to access this ticket"));
@@ -266,8 +261,7 @@ pub async fn get_ticket_messages(
         .map_err(|e| HttpError::server_error(e.to_string()))?
         .ok_or_else(|| HttpError::not_found("Ticket not found"))?;
 
-    let is_customer_care = auth.user.role == UserRole::CustomerCare 
-        || auth.user.role == UserRole::Admin;
+    let is_customer_care = auth.has_permission(Permission::SupportHandle);
 
     if !is_customer_care && ticket.user_id != auth.user.id {
         return Err(HttpError::unauthorized("Not authorized to access this ticket"));
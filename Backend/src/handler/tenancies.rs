@@ -0,0 +1,292 @@
+// handler/tenancies.rs
+use std::sync::Arc;
+use axum::{
+    extract::{Path, Query},
+    response::IntoResponse,
+    routing::{get, post, put},
+    Extension, Json, Router,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    db::tenancydb::TenancyExt,
+    dtos::{
+        userdtos::RequestQueryDto,
+        tenancydtos::{AcceptTenancyDto, MoveOutInspectionDto, TenancyNoteDto, TenancyOfferDto},
+    },
+    error::HttpError,
+    middleware::main_middleware::JWTAuthMiddeware,
+    AppState,
+};
+
+/// Rental offers, running tenancies and rent collection, mounted behind auth
+pub fn tenancy_handler() -> Router {
+    Router::new()
+        .route("/", post(make_offer))
+        .route("/mine", get(get_my_tenancies))
+        .route("/managing", get(get_managed_tenancies))
+        .route("/payments/:payment_id/pay", post(pay_rent))
+        .route("/payments/:payment_id/receipt", get(get_rent_receipt))
+        .route("/:tenancy_id", get(get_tenancy))
+        .route("/:tenancy_id/payments", get(get_rent_schedule))
+        .route("/:tenancy_id/accept", put(accept_offer))
+        .route("/:tenancy_id/decline", put(decline_offer))
+        .route("/:tenancy_id/withdraw", put(withdraw_offer))
+        .route("/:tenancy_id/notice", put(give_notice))
+        .route("/:tenancy_id/inspection", put(record_inspection))
+}
+
+pub async fn make_offer(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+    Json(body): Json<TenancyOfferDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let tenancy = app_state.tenancy_service
+        .make_offer(user.user.id, body)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Offer sent to the landlord",
+        "data": {
+            "tenancy": tenancy
+        }
+    })))
+}
+
+pub async fn get_my_tenancies(
+    Query(query_params): Query<RequestQueryDto>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    query_params.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let page = query_params.page.unwrap_or(1) as u32;
+    let limit = query_params.limit.unwrap_or(10);
+
+    let tenancies = app_state.db_client
+        .get_tenancies_for_tenant(user.user.id, page, limit)
+        .await
+        .map_err(|e| HttpError::server_error(e.to_string()))?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "tenancies": tenancies,
+            "pagination": {
+                "page": page,
+                "limit": limit,
+                "total": tenancies.len()
+            }
+        }
+    })))
+}
+
+pub async fn get_managed_tenancies(
+    Query(query_params): Query<RequestQueryDto>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    query_params.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let page = query_params.page.unwrap_or(1) as u32;
+    let limit = query_params.limit.unwrap_or(10);
+
+    let tenancies = app_state.db_client
+        .get_tenancies_for_landlord(user.user.id, page, limit)
+        .await
+        .map_err(|e| HttpError::server_error(e.to_string()))?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "tenancies": tenancies,
+            "pagination": {
+                "page": page,
+                "limit": limit,
+                "total": tenancies.len()
+            }
+        }
+    })))
+}
+
+pub async fn get_tenancy(
+    Path(tenancy_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    let tenancy = app_state.tenancy_service
+        .get_tenancy_for(user.user.id, tenancy_id)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "tenancy": tenancy
+        }
+    })))
+}
+
+pub async fn get_rent_schedule(
+    Path(tenancy_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    let payments = app_state.tenancy_service
+        .get_rent_schedule(user.user.id, tenancy_id)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "payments": payments
+        }
+    })))
+}
+
+pub async fn accept_offer(
+    Path(tenancy_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+    Json(body): Json<AcceptTenancyDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let tenancy = app_state.tenancy_service
+        .accept(user.user.id, tenancy_id, body)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Offer accepted. The caution deposit is held until move-out",
+        "data": {
+            "tenancy": tenancy
+        }
+    })))
+}
+
+pub async fn decline_offer(
+    Path(tenancy_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+    Json(body): Json<TenancyNoteDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let tenancy = app_state.tenancy_service
+        .decline(user.user.id, tenancy_id, &body.reason)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Offer declined",
+        "data": {
+            "tenancy": tenancy
+        }
+    })))
+}
+
+pub async fn withdraw_offer(
+    Path(tenancy_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    let tenancy = app_state.tenancy_service
+        .withdraw(user.user.id, tenancy_id)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Offer withdrawn",
+        "data": {
+            "tenancy": tenancy
+        }
+    })))
+}
+
+pub async fn give_notice(
+    Path(tenancy_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+    Json(body): Json<TenancyNoteDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let tenancy = app_state.tenancy_service
+        .give_notice(user.user.id, tenancy_id, &body.reason)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Notice recorded. The deposit is settled after the move-out inspection",
+        "data": {
+            "tenancy": tenancy
+        }
+    })))
+}
+
+pub async fn record_inspection(
+    Path(tenancy_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+    Json(body): Json<MoveOutInspectionDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let tenancy = app_state.tenancy_service
+        .record_inspection(user.user.id, tenancy_id, body)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Inspection recorded and caution deposit settled",
+        "data": {
+            "tenancy": tenancy
+        }
+    })))
+}
+
+pub async fn pay_rent(
+    Path(payment_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    let payment = app_state.tenancy_service
+        .pay_rent(user.user.id, payment_id)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Rent paid",
+        "data": {
+            "payment": payment
+        }
+    })))
+}
+
+pub async fn get_rent_receipt(
+    Path(payment_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    let receipt = app_state.tenancy_service
+        .receipt(user.user.id, payment_id)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "receipt": receipt
+        }
+    })))
+}
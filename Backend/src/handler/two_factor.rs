@@ -0,0 +1,153 @@
+// handler/two_factor.rs
+use std::sync::Arc;
+
+use axum::{
+    response::IntoResponse,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use serde_json::json;
+use validator::Validate;
+
+use crate::{
+    dtos::twofactordtos::{StepUpDto, TwoFactorCodeDto},
+    error::HttpError,
+    middleware::main_middleware::JWTAuthMiddeware,
+    models::verificationmodels::OtpPurpose,
+    service::two_factor_service::STEP_UP_WINDOW_MINUTES,
+    AppState,
+};
+
+/// Authenticator app enrolment and step-up, mounted behind auth
+pub fn two_factor_handler() -> Router {
+    Router::new()
+        .route("/", get(get_two_factor_status))
+        .route("/enroll", post(begin_enrolment))
+        .route("/enable", post(enable_two_factor))
+        .route("/disable", post(disable_two_factor))
+        .route("/recovery-codes", post(regenerate_recovery_codes))
+        .route("/step-up", post(step_up))
+        .route("/step-up/email", post(send_step_up_code))
+}
+
+pub async fn get_two_factor_status(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    let status = app_state.two_factor_service.status(auth.user.id).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": status
+    })))
+}
+
+/// A fresh secret and `otpauth://` URI for the client to show as a QR code
+pub async fn begin_enrolment(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    let enrolment = app_state.two_factor_service.begin_enrolment(&auth.user).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "message": "Scan the QR code with your authenticator app, then confirm a code to enable two-factor authentication",
+        "data": enrolment
+    })))
+}
+
+pub async fn enable_two_factor(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+    Json(body): Json<TwoFactorCodeDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let recovery_codes = app_state.two_factor_service.enable(auth.user.id, &body.code).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "message": "Two-factor authentication enabled. Store these recovery codes somewhere safe; they will not be shown again.",
+        "data": {
+            "recovery_codes": recovery_codes
+        }
+    })))
+}
+
+pub async fn disable_two_factor(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+    Json(body): Json<TwoFactorCodeDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    app_state.two_factor_service.disable(auth.user.id, &body.code).await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "message": "Two-factor authentication disabled"
+    })))
+}
+
+pub async fn regenerate_recovery_codes(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+    Json(body): Json<TwoFactorCodeDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let recovery_codes = app_state.two_factor_service
+        .regenerate_recovery_codes(auth.user.id, &body.code)
+        .await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "message": "New recovery codes generated; the old ones no longer work",
+        "data": {
+            "recovery_codes": recovery_codes
+        }
+    })))
+}
+
+/// Confirms a second factor for this session, unlocking withdrawals, large
+/// transfers, bank account and role changes for a few minutes
+pub async fn step_up(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+    Json(body): Json<StepUpDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let expires_at = app_state.two_factor_service
+        .step_up(&auth.user, auth.session_id, body.totp_code.as_deref(), body.email_otp.as_deref())
+        .await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "data": {
+            "expires_at": expires_at,
+            "window_minutes": STEP_UP_WINDOW_MINUTES
+        }
+    })))
+}
+
+/// For users without an authenticator app, or away from it. The code goes
+/// by email or SMS, whichever the user chose.
+pub async fn send_step_up_code(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    let delivery = app_state.otp_service
+        .send(&auth.user, OtpPurpose::Transaction, None, None)
+        .await?;
+
+    Ok(Json(json!({
+        "status": "success",
+        "message": "A confirmation code has been sent",
+        "data": delivery
+    })))
+}
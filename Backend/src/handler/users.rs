@@ -2,7 +2,7 @@
 use std::{env, sync::Arc};
 
 use axum::{extract::{Query, Path}, 
-middleware, response::IntoResponse, routing::{get, post, put}, Extension, Json, Router};
+middleware, response::IntoResponse, routing::{delete, get, post, put}, Extension, Json, Router};
 use chrono::Utc;
 use validator::Validate;
 use uuid::Uuid;
@@ -15,61 +15,89 @@ use crate::{
     dtos::userdtos::*, 
     error::{ErrorMessage, HttpError}, 
     handler::{
+        auth::ClientIp,
         google_oauth::get_google_user, 
         // wallet::{
         //     generate_verification_message, get_wallet, 
         //     get_wallet_verification_status, update_wallet, 
         //     verify_wallet
         // }
-        }, middleware::main_middleware::{role_check, JWTAuthMiddeware}, 
-        models::{usermodel::*, subscriptionmodels::SubscriptionTier}, 
-        service::{referral::generate_referral_link, subscription_service::SubscriptionService}, 
-        utils::password, AppState};
+        }, middleware::main_middleware::{permission_check, JWTAuthMiddeware}, 
+        models::{usermodel::*, permissionmodels::Permission, subscriptionmodels::SubscriptionTier}, 
+        service::{
+            attempt_guard::AttemptKind,
+            login_activity_service::LOGIN_HISTORY_LIMIT,
+            referral::generate_referral_link,
+            role_service::{ensure_can_manage, SELF_SERVICE_ROLES},
+            session_service::{REVOKED_ALL, REVOKED_BY_USER, REVOKED_PASSWORD_CHANGE},
+            subscription_service::SubscriptionService,
+        }, 
+        utils::{gazetteer, password, phone}, AppState};
 
 
 pub fn users_handler() -> Router {
     Router::new()
-        .route(
-            "/me", 
-            get(get_me)
-            .layer(middleware::from_fn(|state, req, next| {
-                role_check(state, req, next, vec![UserRole::Admin, UserRole::User, UserRole::Moderator, UserRole::Verifier, UserRole::Lawyer,
-                    UserRole::Agent, UserRole::Landlord, UserRole::Whistleblower, UserRole::CustomerCare, UserRole::Dev, UserRole::Worker, UserRole::Employer ])
-            }))
-    )
+        .route("/me", get(get_me))
     .route(
         "/users", 
         get(get_users)
         .layer(middleware::from_fn(|state, req, next| {
-            role_check(state, req, next, vec![UserRole::User, UserRole::Admin])
+            permission_check(state, req, next, Permission::UsersList)
         }))
     )
      .route("/avatar", put(update_user_avatar))
     .route("/name", put(update_user_name))
+    .route("/locale", put(update_user_locale))
     .route("/profile", put(update_user_profile))
     .route("/role", put(update_user_role))
     .route("/role/upgrade", put(upgrade_user_role)) // Self-upgrade route
     .route("/role/available", get(get_available_roles)) // Get available roles
     .route("/password", put(update_user_password))
+    .route("/sessions", get(get_my_sessions))
+    .route("/sessions/revoke-all", post(revoke_all_sessions))
+    .route("/sessions/:session_id", delete(revoke_session))
+    .route("/logins", get(get_my_login_history))
     .route(
         "/trust_point", 
         put(update_trust_point)
         .layer(middleware::from_fn(|state, req, next| {
-            role_check(state, req, next, vec![UserRole::User, UserRole::Admin])
+            permission_check(state, req, next, Permission::UsersTrustPoints)
         }))
     )
     .route(
             "/admin/users", 
             get(get_users_admin)
             .layer(middleware::from_fn(|state, req, next| {
-                role_check(state, req, next, vec![UserRole::Admin, UserRole::SuperAdmin])
+                permission_check(state, req, next, Permission::UsersAdmin)
             }))
         )
         .route(
             "/admin/users/:user_id", 
             get(get_user_admin)
             .layer(middleware::from_fn(|state, req, next| {
-                role_check(state, req, next, vec![UserRole::Admin, UserRole::SuperAdmin])
+                permission_check(state, req, next, Permission::UsersAdmin)
+            }))
+        )
+        .route(
+            "/admin/roles",
+            get(get_role_permissions)
+            .layer(middleware::from_fn(|state, req, next| {
+                permission_check(state, req, next, Permission::RolesManage)
+            }))
+        )
+        .route(
+            "/admin/users/:user_id/roles",
+            get(get_user_roles)
+            .post(grant_user_role)
+            .layer(middleware::from_fn(|state, req, next| {
+                permission_check(state, req, next, Permission::RolesManage)
+            }))
+        )
+        .route(
+            "/admin/users/:user_id/roles/:role",
+            delete(revoke_user_role)
+            .layer(middleware::from_fn(|state, req, next| {
+                permission_check(state, req, next, Permission::RolesManage)
             }))
         )
     .route(
@@ -134,8 +162,7 @@ pub async fn verify_transaction_pin(
         .ok_or_else(|| HttpError::bad_request("Transaction PIN not set. Please set a PIN first."))?;
 
     // Verify the provided PIN using Argon2 compare
-    let pin_ok = crate::utils::password::compare(&body.transaction_pin, Some(stored_hash))
-        .map_err(|e| HttpError::server_error(e.to_string()))?;
+    let pin_ok = check_transaction_pin(&app_state, user_id, &body.transaction_pin, stored_hash).await?;
 
     if !pin_ok {
         return Ok(Json(TransactionPinResponse {
@@ -170,6 +197,54 @@ pub async fn verify_transaction_pin(
     }))
 }
 
+/// Compares a transaction PIN under the PIN attempt limits, so repeated
+/// wrong PINs lock PIN checks on the account for a while
+pub(crate) async fn check_transaction_pin(
+    app_state: &AppState,
+    user_id: Uuid,
+    pin: &str,
+    stored_hash: &str,
+) -> Result<bool, HttpError> {
+    let account = user_id.to_string();
+    app_state.attempt_guard.check(AttemptKind::TransactionPin, &account, None).await?;
+
+    let pin_ok = password::compare(pin, Some(stored_hash))
+        .map_err(|e| HttpError::server_error(e.to_string()))?;
+
+    if pin_ok {
+        app_state.attempt_guard.record_success(AttemptKind::TransactionPin, &account).await;
+    } else if app_state.attempt_guard.record_failure(AttemptKind::TransactionPin, &account, None).await {
+        tracing::warn!("Transaction PIN locked for user {} after repeated failures", user_id);
+    }
+
+    Ok(pin_ok)
+}
+
+/// Compares the account password under the login attempt limits, so
+/// password checks behind sign-in can't be used to guess it without limit
+pub(crate) async fn check_account_password(
+    app_state: &AppState,
+    user_id: Uuid,
+    password: &str,
+    stored_hash: Option<&str>,
+    ip: &ClientIp,
+) -> Result<bool, HttpError> {
+    let account = user_id.to_string();
+    let ip = ip.as_string();
+    app_state.attempt_guard.check(AttemptKind::Login, &account, ip.as_deref()).await?;
+
+    let password_ok = password::compare(password, Some(stored_hash.unwrap_or("")))
+        .map_err(|e| HttpError::server_error(e.to_string()))?;
+
+    if password_ok {
+        app_state.attempt_guard.record_success(AttemptKind::Login, &account).await;
+    } else if app_state.attempt_guard.record_failure(AttemptKind::Login, &account, ip.as_deref()).await {
+        tracing::warn!("Password checks locked for user {} after repeated failures", user_id);
+    }
+
+    Ok(password_ok)
+}
+
 // Update your existing transaction PIN setup endpoint
 pub async fn set_transaction_pin(
     Extension(app_state): Extension<Arc<AppState>>,
@@ -201,8 +276,7 @@ pub async fn set_transaction_pin(
     if let Some(existing_hash) = current_user.transaction_pin_hash.clone() {
         // Require current PIN for PIN changes - verify against stored hash
         if let Some(provided_pin) = &body.current_pin {
-            let current_ok = crate::utils::password::compare(provided_pin, Some(&existing_hash))
-                .map_err(|e| HttpError::server_error(e.to_string()))?;
+            let current_ok = check_transaction_pin(&app_state, user_id, provided_pin, &existing_hash).await?;
 
             if !current_ok {
                 return Err(HttpError::unauthorized("Invalid current transaction PIN"));
@@ -243,6 +317,7 @@ pub async fn set_transaction_pin(
 pub async fn verify_password(
     Extension(app_state): Extension<Arc<AppState>>,
     Extension(user): Extension<JWTAuthMiddeware>,
+    ip: ClientIp,
     Json(body): Json<VerifyPasswordDto>,
 ) -> Result<impl IntoResponse, HttpError> {
     body.validate()
@@ -258,10 +333,13 @@ pub async fn verify_password(
         .ok_or_else(|| HttpError::not_found("User not found"))?;
 
     // Verify password
-    let password_match = crate::utils::password::compare(
-        &body.password, 
-        Some(current_user.password.as_deref().unwrap_or(""))
-    ).map_err(|e| HttpError::server_error(e.to_string()))?;
+    let password_match = check_account_password(
+        &app_state,
+        user_id,
+        &body.password,
+        current_user.password.as_deref(),
+        &ip,
+    ).await?;
 
     if !password_match {
         return Ok(Json(VerifyPasswordResponse {
@@ -342,14 +420,14 @@ pub async fn get_me(
 
     let filtered_user = FilterUserDto::filter_user(&user.user);
 
-    let response_data = UserResponseDto {
-        status: "success".to_string(),
-        data: UserData {
-            user: filtered_user,
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "user": filtered_user,
+            "roles": user.access.roles,
+            "permissions": user.access.permissions
         }
-    };
-
-    Ok(Json(response_data))
+    })))
 }
 
 pub async fn get_users(
@@ -410,6 +488,32 @@ pub async fn update_user_name(
     Ok(Json(response))
 }
 
+pub async fn update_user_locale(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+    Json(body): Json<LocaleUpdateDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+       .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let locale = Locale::from_tag(&body.locale)
+        .ok_or_else(|| HttpError::bad_request("Unsupported locale. Use one of: en, pcm, yo, ha, ig"))?;
+
+    let result = app_state.db_client
+        .update_user_locale(user.user.id, locale)
+        .await
+        .map_err(|e| HttpError::server_error(e.to_string()))?;
+
+    let response = UserResponseDto {
+        data: UserData {
+            user: FilterUserDto::filter_user(&result),
+        },
+        status: "success".to_string(),
+    };
+
+    Ok(Json(response))
+}
+
 pub async fn update_user_role(
     Extension(app_state): Extension<Arc<AppState>>,
     Extension(auth_user): Extension<JWTAuthMiddeware>,  // Renamed for clarity
@@ -419,22 +523,50 @@ pub async fn update_user_role(
     body.validate()
         .map_err(|e| HttpError::bad_request(e.to_string()))?;
 
-    // Verify requesting user is admin
-    if auth_user.user.role != UserRole::Admin {
-        return Err(HttpError::unauthorized("Only Admins can update roles"));
-    }
+    auth_user.require_permission(Permission::RolesManage)?;
+
+    app_state.two_factor_service
+        .require_step_up(&auth_user.user, auth_user.session_id, None, None)
+        .await?;
+
+    let is_admin_role = |role: UserRole| matches!(role, UserRole::Admin | UserRole::SuperAdmin);
+
+    let target = app_state.db_client
+        .get_user(Some(body.target_user_id), None, None, None)
+        .await
+        .map_err(|e| HttpError::server_error(e.to_string()))?
+        .ok_or_else(|| HttpError::not_found("User not found"))?;
 
     // Prevent self-demotion
-    if auth_user.user.id == body.target_user_id && body.role != UserRole::Admin {
+    if auth_user.user.id == target.id && is_admin_role(target.role) && !is_admin_role(body.role) {
         return Err(HttpError::unauthorized("Admins cannot remove their own admin status"));
     }
 
-    // Update target user
-    let updated_user = app_state.db_client
-        .update_user_role( body.target_user_id, body.role)
+    // This replaces the primary role, so the caller must be allowed to take
+    // the old one away as well as hand out the new one
+    ensure_can_manage(&auth_user.access, target.role)?;
+
+    app_state.role_service
+        .grant(auth_user.user.id, &auth_user.access, target.id, body.role)
+        .await?;
+
+    let mut updated_user = app_state.db_client
+        .update_user_role(target.id, body.role)
         .await
         .map_err(|e| HttpError::server_error(e.to_string()))?;
 
+    if target.role != body.role && target.role != UserRole::User {
+        app_state.role_service
+            .revoke(auth_user.user.id, &auth_user.access, target.id, target.role)
+            .await?;
+
+        updated_user = app_state.db_client
+            .get_user(Some(target.id), None, None, None)
+            .await
+            .map_err(|e| HttpError::server_error(e.to_string()))?
+            .ok_or_else(|| HttpError::not_found("User not found"))?;
+    }
+
     Ok(Json(UserResponseDto {
         status: "success".to_string(),
         data: UserData {
@@ -445,13 +577,14 @@ pub async fn update_user_role(
 
 pub async fn update_user_password(
     Extension(app_state): Extension<Arc<AppState>>,
-    Extension(user): Extension<JWTAuthMiddeware>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+    ip: ClientIp,
     Json(body): Json<UserPasswordUpdateDto>,
 ) -> Result<impl IntoResponse, HttpError> {
     body.validate()
        .map_err(|e| HttpError::bad_request(e.to_string()))?;
 
-    let user = &user.user;
+    let user = &auth.user;
 
     let user_id = uuid::Uuid::parse_str(&user.id.to_string()).unwrap();
 
@@ -462,8 +595,13 @@ pub async fn update_user_password(
 
     let user = result.ok_or(HttpError::unauthorized(ErrorMessage::InvalidToken.to_string()))?;
 
-    let password_match = password::compare(&body.old_password, Some(user.password.as_deref().unwrap_or("")))
-            .map_err(|e| HttpError::server_error(e.to_string()))?;
+    let password_match = check_account_password(
+        &app_state,
+        user_id,
+        &body.old_password,
+        user.password.as_deref(),
+        &ip,
+    ).await?;
 
     if !password_match {
         return Err(HttpError::bad_request("Old password is incorrect".to_string()));
@@ -477,6 +615,11 @@ pub async fn update_user_password(
         .await
         .map_err(|e| HttpError::server_error(e.to_string()))?;
 
+    // Other devices signed in with the old password are signed out
+    app_state.session_service
+        .revoke_all(user_id, Some(auth.session_id), REVOKED_PASSWORD_CHANGE)
+        .await?;
+
     let response = Response {
         message: "Password updated Successfully".to_string(),
         status: "success",
@@ -486,6 +629,80 @@ pub async fn update_user_password(
 
 }
 
+pub async fn get_my_sessions(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    let sessions: Vec<SessionDto> = app_state.session_service
+        .active_sessions(auth.user.id)
+        .await?
+        .into_iter()
+        .map(|session| SessionDto::from_session(session, auth.session_id))
+        .collect();
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "sessions": sessions,
+        }
+    })))
+}
+
+/// Recent sign-in attempts on the account, including refused ones
+pub async fn get_my_login_history(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+    Query(query): Query<LoginHistoryQuery>,
+) -> Result<impl IntoResponse, HttpError> {
+    let logins = app_state.login_activity_service
+        .history(auth.user.id, query.limit.unwrap_or(LOGIN_HISTORY_LIMIT))
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "logins": logins,
+        }
+    })))
+}
+
+pub async fn revoke_session(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+    Path(session_id): Path<Uuid>,
+) -> Result<impl IntoResponse, HttpError> {
+    app_state.session_service
+        .revoke(auth.user.id, session_id, REVOKED_BY_USER)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Session signed out",
+        "data": {
+            "session_id": session_id,
+            "current": session_id == auth.session_id,
+        }
+    })))
+}
+
+/// Signs out every other device; the one making the request stays signed in
+pub async fn revoke_all_sessions(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    let revoked = app_state.session_service
+        .revoke_all(auth.user.id, Some(auth.session_id), REVOKED_ALL)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Signed out of all other devices",
+        "data": {
+            "revoked": revoked,
+        }
+    })))
+}
+
 pub async fn update_trust_point (
     Extension(app_state): Extension<Arc<AppState>>,
     Extension(user): Extension<JWTAuthMiddeware>,
@@ -642,12 +859,15 @@ pub async fn upgrade_user_role(
         return Err(HttpError::unauthorized("You can only upgrade your own role"));
     }
 
+    app_state.two_factor_service
+        .require_step_up(&auth_user.user, auth_user.session_id, None, None)
+        .await?;
+
     SubscriptionService::check_role_change_limit(app_state.clone(), user_id).await?;
 
 
-    // Only allow upgrading to Worker or Employer
-    let allowed_roles = vec![UserRole::Worker, UserRole::Employer, UserRole::Vendor];
-    if !allowed_roles.contains(&body.new_role) {
+    // Only allow upgrading to Worker, Employer or Vendor
+    if !SELF_SERVICE_ROLES.contains(&body.new_role) {
         return Err(HttpError::bad_request(
             "You can only upgrade to Worker, Employer or Vendor role"
         ));
@@ -675,6 +895,11 @@ pub async fn upgrade_user_role(
         }
     }
 
+    // Record the new role alongside the ones already held, then make it primary
+    app_state.role_service
+        .add_own_role(user_id, body.new_role)
+        .await?;
+
     // Start database transaction for atomic role change
     let mut tx = app_state.db_client.pool.begin().await
         .map_err(|e| HttpError::server_error(e.to_string()))?;
@@ -689,6 +914,13 @@ pub async fn upgrade_user_role(
     tx.commit().await
         .map_err(|e| HttpError::server_error(e.to_string()))?;
 
+    // A self-service role stepping down from primary is still held
+    if user.role != body.new_role && SELF_SERVICE_ROLES.contains(&user.role) {
+        app_state.role_service
+            .add_own_role(user_id, user.role)
+            .await?;
+    }
+
     let filtered_user = FilterUserDto::filter_user(&updated_user);
 
     Ok(Json(UserResponseDto {
@@ -783,6 +1015,77 @@ pub async fn get_user_admin(
     })))
 }
 
+/// Every role and the permissions it carries
+pub async fn get_role_permissions(
+    Extension(app_state): Extension<Arc<AppState>>,
+) -> Result<impl IntoResponse, HttpError> {
+    let roles = app_state.role_service.role_permissions().await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": roles
+    })))
+}
+
+pub async fn get_user_roles(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Path(user_id): Path<Uuid>,
+) -> Result<impl IntoResponse, HttpError> {
+    let roles = app_state.role_service.roles(user_id).await?;
+    if roles.is_empty() {
+        return Err(HttpError::not_found("User not found"));
+    }
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": roles
+    })))
+}
+
+pub async fn grant_user_role(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth_user): Extension<JWTAuthMiddeware>,
+    Path(user_id): Path<Uuid>,
+    Json(body): Json<GrantRoleDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    app_state.two_factor_service
+        .require_step_up(&auth_user.user, auth_user.session_id, None, None)
+        .await?;
+
+    let roles = app_state.role_service
+        .grant(auth_user.user.id, &auth_user.access, user_id, body.role)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": format!("{} role granted", body.role.to_str()),
+        "data": roles
+    })))
+}
+
+pub async fn revoke_user_role(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth_user): Extension<JWTAuthMiddeware>,
+    Path((user_id, role)): Path<(Uuid, UserRole)>,
+) -> Result<impl IntoResponse, HttpError> {
+    app_state.two_factor_service
+        .require_step_up(&auth_user.user, auth_user.session_id, None, None)
+        .await?;
+
+    let roles = app_state.role_service
+        .revoke(auth_user.user.id, &auth_user.access, user_id, role)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": format!("{} role revoked", role.to_str()),
+        "data": roles
+    })))
+}
+
 pub async fn get_available_roles(
     Extension(app_state): Extension<Arc<AppState>>,
     Extension(auth_user): Extension<JWTAuthMiddeware>,
@@ -793,85 +1096,38 @@ pub async fn get_available_roles(
         .map_err(|e| HttpError::server_error(e.to_string()))?
         .ok_or_else(|| HttpError::not_found("User not found"))?;
 
-    let mut available_roles = vec![];
-
-    // User can always upgrade to Worker or Employer from basic User role
-    if user.role == UserRole::User {
-        available_roles.push(RoleInfo {
-            role: UserRole::Worker,
-            name: "Worker".to_string(),
-            description: "Find and apply for jobs".to_string(),
-            requires_verification: true,
-        });
-
-        available_roles.push(RoleInfo {
-            role: UserRole::Employer,
-            name: "Employer".to_string(),
-            description: "Post jobs and hire workers".to_string(),
-            requires_verification: false,
-        });
-
-        available_roles.push(RoleInfo {
-            role: UserRole::Vendor,
-            name: "Vendor".to_string(),
-            description: "Post services, sell items  and recieve payments".to_string(),
-            requires_verification: false,
-        });
-    }
-
-    // If user is already a Worker, they can become Employer (and vice versa)
-    if user.role == UserRole::Worker {
-        available_roles.push(RoleInfo {
-            role: UserRole::Employer,
-            name: "Employer".to_string(),
-            description: "Post jobs and hire workers".to_string(),
-            requires_verification: false,
-        });
-
-        available_roles.push(RoleInfo {
-            role: UserRole::Vendor,
-            name: "Vendor".to_string(),
-            description: "Post services, sell items  and recieve payments".to_string(),
-            requires_verification: false,
-        });
-    }
-
-    if user.role == UserRole::Employer {
-        available_roles.push(RoleInfo {
-            role: UserRole::Worker,
-            name: "Worker".to_string(),
-            description: "Find and apply for jobs".to_string(),
-            requires_verification: true,
-        });
-
-        available_roles.push(RoleInfo {
-            role: UserRole::Vendor,
-            name: "Vendor".to_string(),
-            description: "Post services, sell items  and recieve payments".to_string(),
-            requires_verification: false,
-        });
-    }
-
-    if user.role == UserRole::Vendor {
-        available_roles.push(RoleInfo {
-            role: UserRole::Worker,
-            name: "Worker".to_string(),
-            description: "Find and apply for jobs".to_string(),
-            requires_verification: true,
-        });
-
-        available_roles.push(RoleInfo {
-            role: UserRole::Employer,
-            name: "Employer".to_string(),
-            description: "Post jobs and hire workers".to_string(),
-            requires_verification: false,
-        });
-    }
+    // Any self-service role the user does not hold yet can be added alongside
+    // the ones they have
+    let available_roles: Vec<RoleInfo> = SELF_SERVICE_ROLES
+        .into_iter()
+        .filter(|role| !auth_user.has_role(*role))
+        .map(|role| match role {
+            UserRole::Worker => RoleInfo {
+                role,
+                name: "Worker".to_string(),
+                description: "Find and apply for jobs".to_string(),
+                requires_verification: true,
+            },
+            UserRole::Employer => RoleInfo {
+                role,
+                name: "Employer".to_string(),
+                description: "Post jobs and hire workers".to_string(),
+                requires_verification: false,
+            },
+            _ => RoleInfo {
+                role,
+                name: "Vendor".to_string(),
+                description: "Post services, sell items  and recieve payments".to_string(),
+                requires_verification: false,
+            },
+        })
+        .collect();
 
     Ok(Json(serde_json::json!({
         "status": "success",
         "data": {
             "current_role": user.role.to_str(),
+            "roles": auth_user.access.roles,
             "available_roles": available_roles
         }
     })))
@@ -1081,12 +1337,21 @@ pub async fn update_user_profile(
         return Err(HttpError::bad_request(format!("Phone number validation error: {}", phone_error)));
     }
 
+    let lga = payload.lga.as_deref().map(gazetteer::normalise_lga);
+    let phone_number = payload.phone_number
+        .as_deref()
+        .filter(|phone_number| !phone_number.trim().is_empty())
+        .map(|phone_number| {
+            phone::normalize(phone_number).ok_or_else(|| HttpError::bad_request("Phone number is invalid"))
+        })
+        .transpose()?;
+
     // Update user profile
     let updated_user = app_state.db_client
         .update_user_profile(
             auth.user.id,
-            payload.phone_number.as_deref(),
-            payload.lga.as_deref(),
+            phone_number.as_deref(),
+            lga.as_deref(),
             payload.nearest_landmark.as_deref(),
         )
         .await
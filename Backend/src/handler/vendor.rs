@@ -19,7 +19,8 @@ use crate::{
     AppState, db::{labourdb::LaborExt, naira_walletdb::NairaWalletExt, userdb::UserExt, vendordb::VendorExt}, 
     dtos::vendordtos::ConfirmDeliveryDto, error::HttpError, 
     middleware::main_middleware::JWTAuthMiddeware, 
-    models::{labourmodel::EscrowTransaction, usermodel::UserRole, vendormodels::*, walletmodels::*}, service::vendor_order_service::VendorOrderService
+    models::{labourmodel::EscrowTransaction, mediamodels::MediaPurpose, permissionmodels::Permission, usermodel::UserRole, vendormodels::*, walletmodels::*}, service::{identity_service::IdentityService, vendor_order_service::VendorOrderService},
+    utils::gazetteer::{self, validate_state},
 };
 use crate::recommendation_models::{Interaction, FeedItemType, InteractionType};
 use crate::services::reco_db::RecoDB;
@@ -29,6 +30,7 @@ pub fn vendor_handler() -> Router {
         // Vendor Profile Management
         .route("/vendor/profile", post(create_vendor_profile).get(get_vendor_profile))
         .route("/vendor/profile", put(update_vendor_profile))
+        .route("/vendor/profile/cac", post(verify_cac_registration))
         
         // Subscription Management
         .route("/vendor/subscription/upgrade", post(upgrade_subscription))
@@ -90,10 +92,18 @@ pub fn vendor_handler() -> Router {
 pub struct UpdateVendorProfileDto {
     pub business_name: Option<String>,
     pub description: Option<String>,
+    #[validate(custom = "validate_state")]
     pub location_state: Option<String>,
     pub location_city: Option<String>,
 }
 
+#[derive(Debug, Deserialize, Validate)]
+pub struct VerifyCacDto {
+    // RC number for companies, BN number for business names
+    #[validate(length(min = 3, max = 20, message = "RC or BN number is required"))]
+    pub rc_number: String,
+}
+
 #[derive(Debug, Deserialize, Validate)]
 pub struct UpgradeSubscriptionDto {
     pub tier: SubscriptionTier,
@@ -170,20 +180,22 @@ pub async fn create_vendor_profile(
         return Err(HttpError::bad_request("Vendor profile already exists"));
     }
     
+    let (location_state, location_city) = gazetteer::normalise_place(&body.location_state, &body.location_city);
+
     let profile = app_state.db_client
         .create_vendor_profile(
             auth.user.id,
             body.business_name,
             body.description,
-            body.location_state,
-            body.location_city,
+            location_state,
+            location_city,
         )
         .await
         .map_err(|e| HttpError::server_error(e.to_string()))?;
     
-    // Add Vendor role to user
-    let _ = app_state.db_client
-        .update_user_role(auth.user.id, UserRole::Vendor)
+    // Vendor joins whatever roles the user already holds
+    let _ = app_state.role_service
+        .add_own_role(auth.user.id, UserRole::Vendor)
         .await;
     
     Ok(Json(serde_json::json!({
@@ -280,16 +292,19 @@ pub async fn create_service(
         .map_err(|e| HttpError::server_error(e.to_string()))?
         .ok_or_else(|| HttpError::not_found("Vendor profile not found. Create one first."))?;
 
+    let current_services = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM vendor_services WHERE vendor_id = $1 AND status = 'active'"
+    )
+    .bind(vendor.id)
+    .fetch_one(&app_state.db_client.pool)
+    .await
+    .map_err(|e| HttpError::server_error(e.to_string()))?;
+
+    // KYC caps listings whatever the subscription allows
+    app_state.kyc_service.ensure_can_list(auth.user.id, current_services).await?;
+
     // Check subscription limits
     if let Some(max_services) = vendor.subscription_tier.max_services() {
-        let current_services = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(*) FROM vendor_services WHERE vendor_id = $1 AND status = 'active'"
-        )
-        .bind(vendor.id)
-        .fetch_one(&app_state.db_client.pool)
-        .await
-        .map_err(|e| HttpError::server_error(e.to_string()))?;
-
         if current_services >= max_services as i64 {
             return Err(HttpError::bad_request(
                 format!("Service limit reached. Upgrade to list more services. Current limit: {}", max_services)
@@ -297,6 +312,8 @@ pub async fn create_service(
         }
     }
 
+    let (location_state, location_city) = gazetteer::normalise_place(&body.location_state, &body.location_city);
+
     let service = app_state.db_client
         .create_service(
             vendor.id,
@@ -305,8 +322,8 @@ pub async fn create_service(
             body.category,
             body.price,
             body.images.unwrap_or_default(),
-            body.location_state,
-            body.location_city,
+            location_state,
+            location_city,
             body.tags,
             body.stock_quantity.unwrap_or(1),
             body.is_negotiable.unwrap_or(false),
@@ -516,11 +533,18 @@ pub async fn search_services(
     let limit = params.limit.unwrap_or(20).min(100) as i64;
     let offset = ((page - 1) * limit as u32) as i64;
     
+    let location_city = match (&params.location_state, params.location_city) {
+        (Some(state), Some(city)) => Some(gazetteer::normalise_city(state, &city)),
+        (None, city) => city.map(|city| city.trim().to_string()),
+        (_, None) => None,
+    };
+    let location_state = params.location_state.map(|state| gazetteer::normalise_state(&state));
+
     let services = app_state.db_client
         .search_services(
             params.category,
-            params.location_state,
-            params.location_city,
+            location_state,
+            location_city,
             params.min_price,
             params.max_price,
             params.search,
@@ -827,19 +851,29 @@ pub async fn update_vendor_profile(
     Extension(auth): Extension<JWTAuthMiddeware>,
     Json(body): Json<UpdateVendorProfileDto>,
 ) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
     let profile = app_state.db_client
         .get_vendor_profile_by_user(auth.user.id)
         .await
         .map_err(|e| HttpError::server_error(e.to_string()))?
         .ok_or_else(|| HttpError::not_found("Vendor profile not found"))?;
+
+    // A new city is checked against the new state if one came with it
+    let location_city = body.location_city.map(|city| {
+        let state = body.location_state.as_deref().unwrap_or(&profile.location_state);
+        gazetteer::normalise_city(state, &city)
+    });
+    let location_state = body.location_state.map(|state| gazetteer::normalise_state(&state));
     
     let updated = app_state.db_client
         .update_vendor_profile(
             profile.id,
             body.business_name,
             body.description,
-            body.location_state,
-            body.location_city,
+            location_state,
+            location_city,
         )
         .await
         .map_err(|e| HttpError::server_error(e.to_string()))?;
@@ -851,6 +885,39 @@ pub async fn update_vendor_profile(
     })))
 }
 
+/// Checks the vendor's CAC registration and marks the profile verified when
+/// the registered name matches the business name
+pub async fn verify_cac_registration(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+    Json(body): Json<VerifyCacDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let profile = app_state.db_client
+        .get_vendor_profile_by_user(auth.user.id)
+        .await
+        .map_err(|e| HttpError::server_error(e.to_string()))?
+        .ok_or_else(|| HttpError::not_found("Vendor profile not found"))?;
+
+    let rc_number = body.rc_number.trim().to_uppercase();
+    let check = app_state.identity_service
+        .verify_business(auth.user.id, &rc_number, &profile.business_name)
+        .await?;
+    IdentityService::ensure_matched(&check)?;
+
+    let updated = app_state.db_client
+        .set_vendor_verified(profile.id, true)
+        .await
+        .map_err(|e| HttpError::server_error(e.to_string()))?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Business registration verified",
+        "data": updated
+    })))
+}
 
 pub async fn initiate_purchase(
     Extension(app_state): Extension<Arc<AppState>>,
@@ -1119,7 +1186,7 @@ pub async fn verify_service_purchase(
         .map_err(|e| HttpError::bad_request(e.to_string()))?;
     
     // Only admins can verify payments (for manual verification)
-    if auth.user.role != UserRole::Admin && auth.user.role != UserRole::SuperAdmin {
+    if !auth.has_permission(Permission::VendorAdmin) {
         return Err(HttpError::unauthorized("Not authorized to verify payments"));
     }
     
@@ -1704,6 +1771,11 @@ pub async fn create_service_dispute(
     body.validate()
         .map_err(|e| HttpError::bad_request(e.to_string()))?;
 
+    let evidence_urls = body.evidence_urls.unwrap_or_default();
+    app_state.storage_service
+        .ensure_all_owned(auth.user.id, &evidence_urls, &[MediaPurpose::DisputeEvidence])
+        .await?;
+
     let order_service = VendorOrderService::new(
         app_state.db_client.clone(),
         app_state.notification_service.clone(),
@@ -1715,7 +1787,7 @@ pub async fn create_service_dispute(
             order_id,
             body.reason,
             body.description,
-            body.evidence_urls.unwrap_or_default(),
+            evidence_urls,
         )
         .await
         .map_err(|e| HttpError::server_error(e.to_string()))?;
@@ -1735,7 +1807,7 @@ pub struct CreateVendorProfileDto {
     
     pub description: Option<String>,
     
-    #[validate(length(min = 1, message = "State is required"))]
+    #[validate(length(min = 1, message = "State is required"), custom = "validate_state")]
     pub location_state: String,
     
     #[validate(length(min = 1, message = "City is required"))]
@@ -1757,7 +1829,7 @@ pub struct CreateServiceDto {
     
     pub images: Option<Vec<String>>,
     
-    #[validate(length(min = 1, message = "State is required"))]
+    #[validate(length(min = 1, message = "State is required"), custom = "validate_state")]
     pub location_state: String,
     
     #[validate(length(min = 1, message = "City is required"))]
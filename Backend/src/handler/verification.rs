@@ -7,7 +7,6 @@ use axum::{
     routing::{get, post, put},
     Extension, Json, Router,
 };
-use chrono::{Duration, Utc};
 use uuid::Uuid;
 use validator::Validate;
 
@@ -15,10 +14,18 @@ use crate::{
     db::{userdb::UserExt, verificationdb::VerificationExt},
     dtos::verificationdtos::*,
     error::HttpError,
-    mail::mails::send_otp_email,
+    handler::auth::ClientIp,
     middleware::main_middleware::JWTAuthMiddeware,
-    models::usermodel::{VerificationStatus, VerificationType, UserRole},
-    utils::{otp_generator},
+    models::{
+        identitymodels::{IdentityKind, IdentityOutcome},
+        kycmodels::{AddressProof, KycDecision},
+        mediamodels::MediaPurpose,
+        permissionmodels::Permission,
+        usermodel::{VerificationStatus, VerificationType},
+        verificationmodels::VerificationDocument,
+    },
+    service::error::ServiceError,
+    utils::gazetteer,
     AppState,
 };
 
@@ -27,6 +34,12 @@ pub fn verification_handler() -> Router {
         // OTP Routes
         .route("/otp/send", post(send_otp))
         .route("/otp/verify", post(verify_otp))
+        .route("/otp/channel", put(set_otp_channel))
+
+        // Phone Routes
+        .route("/phone", get(get_phone_status))
+        .route("/phone/send", post(send_phone_code))
+        .route("/phone/confirm", post(confirm_phone))
         
         // Verification Routes
         .route("/nin", post(submit_nin_verification))
@@ -34,10 +47,14 @@ pub fn verification_handler() -> Router {
         .route("/documents", get(get_user_verifications))
         .route("/status", get(get_verification_status))
         .route("/complete-status", get(get_complete_verification_status))
+        .route("/kyc-level", get(get_kyc_level))
+        .route("/address", post(submit_address_proof))
         
         // Admin Routes
         .route("/admin/pending", get(get_pending_verifications))
         .route("/admin/:verification_id/review", put(review_verification))
+        .route("/admin/address/pending", get(get_pending_address_proofs))
+        .route("/admin/address/:proof_id/review", put(review_address_proof))
 }
 
 // OTP Handlers
@@ -49,53 +66,29 @@ pub async fn send_otp(
     body.validate()
         .map_err(|e| HttpError::bad_request(e.to_string()))?;
 
-    // Generate OTP
-    let otp_code = otp_generator::generate_otp();
-    let expires_at = Utc::now() + Duration::minutes(10); // OTP valid for 10 minutes
-
-    // Store OTP in database
-    let otp_record = app_state.db_client
-        .create_otp(
-            auth.user.id,
-            body.email.clone(),
-            otp_code.clone(),
-            body.purpose.clone(),
-            expires_at,
-        )
-        .await
-        .map_err(|e| HttpError::server_error(e.to_string()))?;
-
-    // Send OTP via email
-    send_otp_email(&body.email, &otp_code, &body.purpose)
-        .await
-        .map_err(|e| HttpError::server_error(e.to_string()))?;
+    let delivery = app_state.otp_service
+        .send(&auth.user, body.purpose, body.channel, body.email.as_deref())
+        .await?;
 
     Ok(Json(serde_json::json!({
         "status": "success",
         "message": "OTP sent successfully",
-        "expires_at": expires_at
+        "expires_at": delivery.expires_at,
+        "data": delivery
     })))
 }
 
 pub async fn verify_otp(
     Extension(app_state): Extension<Arc<AppState>>,
+    ip: ClientIp,
     Json(body): Json<OtpVerificationDto>,
 ) -> Result<impl IntoResponse, HttpError> {
     body.validate()
         .map_err(|e| HttpError::bad_request(e.to_string()))?;
 
-    // Verify OTP
-    let otp_record = app_state.db_client
-        .get_valid_otp(&body.email, &body.otp_code, body.purpose.clone())
-        .await
-        .map_err(|e| HttpError::server_error(e.to_string()))?
-        .ok_or_else(|| HttpError::bad_request("Invalid or expired OTP"))?;
-
-    // Mark OTP as used
-    app_state.db_client
-        .mark_otp_used(otp_record.id)
-        .await
-        .map_err(|e| HttpError::server_error(e.to_string()))?;
+    app_state.otp_service
+        .verify(&body.email, &body.otp_code, body.purpose.clone(), ip.as_string().as_deref())
+        .await?;
 
     Ok(Json(serde_json::json!({
         "status": "success",
@@ -104,6 +97,73 @@ pub async fn verify_otp(
     })))
 }
 
+/// Where codes go by default: email, or SMS once a phone is verified
+pub async fn set_otp_channel(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+    Json(body): Json<OtpChannelDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    let status = app_state.otp_service.set_channel(auth.user.id, body.channel).await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "OTP delivery preference updated",
+        "data": status
+    })))
+}
+
+// Phone Handlers
+pub async fn get_phone_status(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    let status = app_state.otp_service.phone_status(auth.user.id).await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": status
+    })))
+}
+
+/// Texts a code to the number; confirming it makes the number verified
+pub async fn send_phone_code(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+    Json(body): Json<PhoneNumberDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let delivery = app_state.otp_service
+        .start_phone_verification(&auth.user, &body.phone_number)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Verification code sent by SMS",
+        "data": delivery
+    })))
+}
+
+pub async fn confirm_phone(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+    Json(body): Json<ConfirmPhoneDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let status = app_state.otp_service
+        .confirm_phone(&auth.user, &body.phone_number, &body.otp_code)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Phone number verified. You can now sign in with it.",
+        "data": status
+    })))
+}
+
 // Verification Handlers
 pub async fn submit_nin_verification(
     Extension(app_state): Extension<Arc<AppState>>,
@@ -125,6 +185,12 @@ pub async fn submit_nin_verification(
         }
     }
 
+    // Check the NIN against NIMC before taking the images
+    let registry_match = check_nin(&app_state, &auth, &body).await?;
+
+    // KYC images must be private uploads the user made themselves
+    ensure_kyc_uploads(&app_state, auth.user.id, &body.document_url, &body.selfie_url).await?;
+
     // Create verification document
     let verification = app_state.db_client
         .create_verification_document(
@@ -148,16 +214,24 @@ pub async fn submit_nin_verification(
             Some(body.selfie_url.clone()),   // Store selfie URL
             Some(body.nationality.clone()),
             body.dob,
-            body.lga.clone(),
+            body.lga.as_deref().map(gazetteer::normalise_lga),
             body.nearest_landmark.clone(),
         )
         .await
         .map_err(|e| HttpError::server_error(e.to_string()))?;
 
+    let verification_status = screen_submission(&app_state, &verification, registry_match).await;
+    let message = if verification_status == VerificationStatus::Approved {
+        "NIN verification approved"
+    } else {
+        "NIN verification submitted successfully"
+    };
+
     Ok(Json(serde_json::json!({
         "status": "success",
-        "message": "NIN verification submitted successfully",
-        "verification_id": verification.id
+        "message": message,
+        "verification_id": verification.id,
+        "verification_status": verification_status
     })))
 }
 
@@ -182,6 +256,9 @@ pub async fn submit_document_verification(
         }
     }
 
+    // KYC images must be private uploads the user made themselves
+    ensure_kyc_uploads(&app_state, auth.user.id, &body.document_url, &body.selfie_url).await?;
+
     // Create verification document
     let verification = app_state.db_client
         .create_verification_document(
@@ -205,16 +282,24 @@ pub async fn submit_document_verification(
             Some(body.selfie_url.clone()),   // Store selfie URL
             Some(body.nationality.clone()),
             body.dob,
-            body.lga.clone(),
+            body.lga.as_deref().map(gazetteer::normalise_lga),
             body.nearest_landmark.clone(),
         )
         .await
         .map_err(|e| HttpError::server_error(e.to_string()))?;
 
+    let verification_status = screen_submission(&app_state, &verification, None).await;
+    let message = if verification_status == VerificationStatus::Approved {
+        "Document verification approved"
+    } else {
+        "Document verification submitted successfully"
+    };
+
     Ok(Json(serde_json::json!({
         "status": "success",
-        "message": "Document verification submitted successfully",
-        "verification_id": verification.id
+        "message": message,
+        "verification_id": verification.id,
+        "verification_status": verification_status
     })))
 }
 
@@ -226,6 +311,7 @@ pub async fn get_user_verifications(
         .get_user_verification_documents(auth.user.id)
         .await
         .map_err(|e| HttpError::server_error(e.to_string()))?;
+    let verifications = with_signed_urls(&app_state, verifications);
 
     Ok(Json(serde_json::json!({
         "status": "success",
@@ -256,8 +342,7 @@ pub async fn get_pending_verifications(
     Extension(auth): Extension<JWTAuthMiddeware>,
 ) -> Result<impl IntoResponse, HttpError> {
     // Check if user is admin or verifier
-    if auth.user.role != UserRole::Admin 
-        && auth.user.role != UserRole::Verifier {
+    if !auth.has_permission(Permission::VerificationReview) {
         return Err(HttpError::unauthorized("Insufficient permissions"));
     }
 
@@ -265,6 +350,11 @@ pub async fn get_pending_verifications(
         .get_pending_document_verifications()
         .await
         .map_err(|e| HttpError::server_error(e.to_string()))?;
+    let checks = app_state.kyc_service.checks_for(&pending_verifications).await?;
+    let pending_verifications = PendingVerificationDto::attach(
+        with_signed_urls(&app_state, pending_verifications),
+        checks,
+    );
 
     Ok(Json(serde_json::json!({
         "status": "success",
@@ -282,8 +372,7 @@ pub async fn review_verification(
         .map_err(|e| HttpError::bad_request(e.to_string()))?;
 
     // Check if user is admin or verifier
-    if auth.user.role != UserRole::Admin 
-        && auth.user.role != UserRole::Verifier {
+    if !auth.has_permission(Permission::VerificationReview) {
         return Err(HttpError::unauthorized("Insufficient permissions"));
     }
 
@@ -294,6 +383,18 @@ pub async fn review_verification(
         .map_err(|e| HttpError::server_error(e.to_string()))?
         .ok_or_else(|| HttpError::not_found("Verification document not found"))?;
 
+    if body.status == VerificationStatus::Approved {
+        let updated_verification = app_state.kyc_service
+            .approve(&verification, Some(auth.user.id), body.review_notes.clone())
+            .await?;
+
+        return Ok(Json(serde_json::json!({
+            "status": "success",
+            "message": "Verification reviewed successfully",
+            "data": updated_verification
+        })));
+    }
+
     // Update verification status
     let updated_verification = app_state.db_client
         .update_verification_status(
@@ -318,97 +419,43 @@ pub async fn review_verification(
         &user.name,
         &body.status,
         body.review_notes.as_deref(),
+        user.locale,
     ).await {
         tracing::error!("Failed to send verification status email: {}", e);
         // Don't fail the request if email fails
     }
 
-    // If approved, update user verification status AND populate user data
-    if body.status == VerificationStatus::Approved {
-        match verification.document_type {
-            VerificationType::NationalId => {
-                app_state.db_client
-                    .update_user_verification_data(
-                        verification.user_id,
-                        VerificationStatus::Approved,
-                        Some(verification.document_id.clone()),
-                        verification.document_type,
-                        Some(verification.document_url.clone()),
-                        Some(verification.selfie_url.clone()),
-                        None,
-                        None,
-                        None,
-                        None,
-                    )
-                    .await
-                    .map_err(|e| HttpError::server_error(e.to_string()))?;
-            }
-            VerificationType::DriverLicense | VerificationType::Passport => {
-                app_state.db_client
-                    .update_user_verification_data(
-                        verification.user_id,
-                        VerificationStatus::Approved,
-                        Some(verification.document_id.clone()),
-                        verification.document_type,
-                        Some(verification.document_url.clone()),
-                        Some(verification.selfie_url.clone()),
-                        None,
-                        None,
-                        None,
-                        None,
-                    )
-                    .await
-                    .map_err(|e| HttpError::server_error(e.to_string()))?;
-            }
-        }
-
-        // Also update the general verification status
+    if body.status == VerificationStatus::Rejected {
+        // Clear the submitted data from the user
         app_state.db_client
-            .update_user_verification_status(verification.user_id, VerificationStatus::Approved)
+            .update_user_verification_data(
+                verification.user_id,
+                VerificationStatus::Rejected,
+                Some("".to_string()),
+                VerificationType::NationalId,
+                Some("".to_string()),
+                Some("".to_string()),
+                None,
+                None,
+                None,
+                None,
+            )
             .await
             .map_err(|e| HttpError::server_error(e.to_string()))?;
 
-       match app_state.notification_service.notify_verification_accepted(&verification)
-        .await{
+        match app_state.notification_service.notify_verification_rejected(
+            &updated_verification
+        ).await {
             Ok(_) => tracing::info!("verification notification sent to {}", &verification.user_id),
             Err(e) => tracing::error!("Failed to notify verification request: {:?}", e),
         };
-
-        
     }
 
-    if body.status == VerificationStatus::Rejected {
-        // Update user with verification data based on document type
-                app_state.db_client
-                    .update_user_verification_data(
-                        verification.user_id,
-                        VerificationStatus::Rejected,
-                        Some("".to_string()),
-                        VerificationType::NationalId,
-                        Some("".to_string()),
-                        Some("".to_string()),
-                        None,
-                        None,
-                        None,
-                        None,
-                    )
-                    .await
-                    .map_err(|e| HttpError::server_error(e.to_string()))?;
-
-            match app_state.notification_service.notify_verification_rejected(
-                &verification
-            ).await{
-            Ok(_) => tracing::info!("verification notification sent to {}", &verification.user_id),
-            Err(e) => tracing::error!("Failed to notify verification request: {:?}", e),
-            };
-
-        }
-
-        // Also update the general verification status
-        app_state.db_client
-            .update_user_verification_status(verification.user_id, VerificationStatus::Unverified)
-            .await
-            .map_err(|e| HttpError::server_error(e.to_string()))?;
+    // Also update the general verification status
+    app_state.db_client
+        .update_user_verification_status(verification.user_id, VerificationStatus::Unverified)
+        .await
+        .map_err(|e| HttpError::server_error(e.to_string()))?;
 
     Ok(Json(serde_json::json!({
         "status": "success",
@@ -442,4 +489,159 @@ pub async fn get_complete_verification_status(
         "status": "success",
         "data": response
     })))
-}
\ No newline at end of file
+}
+
+/// The user's KYC level, what it allows, and what the next level needs
+pub async fn get_kyc_level(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    let progress = app_state.kyc_service.progress(auth.user.id).await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": progress
+    })))
+}
+
+pub async fn submit_address_proof(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+    Json(body): Json<AddressProofDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    app_state.storage_service
+        .ensure_owned(auth.user.id, &body.document_url, &[MediaPurpose::KycDocument])
+        .await?;
+
+    let proof = app_state.kyc_service
+        .submit_address_proof(auth.user.id, &body.document_url, body.address.trim())
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Proof of address submitted for review",
+        "data": proof
+    })))
+}
+
+pub async fn get_pending_address_proofs(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    auth.require_permission(Permission::VerificationReview)?;
+
+    let proofs: Vec<AddressProof> = app_state.kyc_service
+        .pending_address_proofs()
+        .await?
+        .into_iter()
+        .map(|proof| AddressProof {
+            document_url: app_state.storage_service.presign_url(&proof.document_url),
+            ..proof
+        })
+        .collect();
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": proofs
+    })))
+}
+
+pub async fn review_address_proof(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(auth): Extension<JWTAuthMiddeware>,
+    Path(proof_id): Path<Uuid>,
+    Json(body): Json<ReviewVerificationDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+    auth.require_permission(Permission::VerificationReview)?;
+
+    let proof = app_state.kyc_service
+        .review_address_proof(proof_id, body.status, auth.user.id, body.review_notes)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Proof of address reviewed",
+        "data": proof
+    })))
+}
+
+/// Runs the automated checks. Anything they do not clear, including a check
+/// that fails to run, stays with reviewers.
+async fn screen_submission(
+    app_state: &AppState,
+    verification: &VerificationDocument,
+    registry_match: Option<bool>,
+) -> VerificationStatus {
+    match app_state.kyc_service.screen(verification, registry_match).await {
+        Ok(check) if check.decision == KycDecision::AutoApproved => VerificationStatus::Approved,
+        Ok(_) => VerificationStatus::Submitted,
+        Err(e) => {
+            tracing::error!("KYC checks failed for verification {}: {}", verification.id, e);
+            VerificationStatus::Submitted
+        }
+    }
+}
+
+/// Looks the NIN up when an identity provider is configured. A number NIMC
+/// does not know, or one already matched to another account, is refused; a
+/// record that does not match the account sends the submission to a
+/// reviewer. If the lookup itself fails the reviewer decides without it.
+async fn check_nin(
+    app_state: &AppState,
+    auth: &JWTAuthMiddeware,
+    body: &NinVerificationDto,
+) -> Result<Option<bool>, HttpError> {
+    if !app_state.identity_service.is_enabled() {
+        return Ok(None);
+    }
+
+    let dob = body.dob.or(auth.user.dob).map(|dob| dob.date_naive());
+    let check = app_state.identity_service
+        .verify_person(auth.user.id, IdentityKind::Nin, &body.nin_number, &auth.user.name, dob)
+        .await;
+
+    match check {
+        Ok(check) if check.outcome == IdentityOutcome::NotFound => {
+            Err(ServiceError::IdentityNotFound(check.kind.label()).into())
+        }
+        Ok(check) => Ok(Some(check.outcome == IdentityOutcome::Matched)),
+        Err(e @ ServiceError::IdentityInUse(_)) => Err(e.into()),
+        Err(e) => {
+            tracing::error!("NIN lookup failed for user {}: {}", auth.user.id, e);
+            Ok(None)
+        }
+    }
+}
+
+async fn ensure_kyc_uploads(
+    app_state: &AppState,
+    user_id: Uuid,
+    document_url: &str,
+    selfie_url: &str,
+) -> Result<(), HttpError> {
+    app_state.storage_service
+        .ensure_owned(user_id, document_url, &[MediaPurpose::KycDocument])
+        .await?;
+    app_state.storage_service
+        .ensure_owned(user_id, selfie_url, &[MediaPurpose::KycSelfie])
+        .await?;
+
+    Ok(())
+}
+
+/// KYC images are private; reviewers get links that expire
+fn with_signed_urls(app_state: &AppState, documents: Vec<VerificationDocument>) -> Vec<VerificationDocument> {
+    documents
+        .into_iter()
+        .map(|document| VerificationDocument {
+            document_url: app_state.storage_service.presign_url(&document.document_url),
+            selfie_url: app_state.storage_service.presign_url(&document.selfie_url),
+            ..document
+        })
+        .collect()
+}
@@ -0,0 +1,238 @@
+// handler/viewings.rs
+use std::sync::Arc;
+use axum::{
+    extract::{Path, Query},
+    response::IntoResponse,
+    routing::{get, post, put},
+    Extension, Json, Router,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    db::viewingdb::ViewingExt,
+    dtos::{
+        userdtos::RequestQueryDto,
+        viewingdtos::{ConfirmViewingDto, ReportNoShowDto, RequestViewingDto, ViewingNoteDto},
+    },
+    error::HttpError,
+    middleware::main_middleware::JWTAuthMiddeware,
+    AppState,
+};
+
+/// Viewing appointments for live listings, mounted behind auth
+pub fn viewing_handler() -> Router {
+    Router::new()
+        .route("/", post(request_viewing))
+        .route("/mine", get(get_my_viewings))
+        .route("/hosting", get(get_hosted_viewings))
+        .route("/:viewing_id", get(get_viewing))
+        .route("/:viewing_id/confirm", put(confirm_viewing))
+        .route("/:viewing_id/decline", put(decline_viewing))
+        .route("/:viewing_id/cancel", put(cancel_viewing))
+        .route("/:viewing_id/attended", put(mark_viewing_attended))
+        .route("/:viewing_id/no-show", put(report_no_show))
+}
+
+pub async fn request_viewing(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+    Json(body): Json<RequestViewingDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let viewing = app_state.viewing_service
+        .request_viewing(user.user.id, body)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Viewing requested. You will be notified when the landlord or agent responds",
+        "data": {
+            "viewing": viewing
+        }
+    })))
+}
+
+pub async fn get_my_viewings(
+    Query(query_params): Query<RequestQueryDto>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    query_params.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let page = query_params.page.unwrap_or(1) as u32;
+    let limit = query_params.limit.unwrap_or(10);
+
+    let viewings = app_state.db_client
+        .get_viewings_for_requester(user.user.id, page, limit)
+        .await
+        .map_err(|e| HttpError::server_error(e.to_string()))?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "viewings": viewings,
+            "pagination": {
+                "page": page,
+                "limit": limit,
+                "total": viewings.len()
+            }
+        }
+    })))
+}
+
+/// Viewings of properties the user owns or is the agent for
+pub async fn get_hosted_viewings(
+    Query(query_params): Query<RequestQueryDto>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    query_params.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let page = query_params.page.unwrap_or(1) as u32;
+    let limit = query_params.limit.unwrap_or(10);
+
+    let viewings = app_state.db_client
+        .get_viewings_for_host(user.user.id, page, limit)
+        .await
+        .map_err(|e| HttpError::server_error(e.to_string()))?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "viewings": viewings,
+            "pagination": {
+                "page": page,
+                "limit": limit,
+                "total": viewings.len()
+            }
+        }
+    })))
+}
+
+pub async fn get_viewing(
+    Path(viewing_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    let viewing = app_state.viewing_service
+        .get_viewing_for(user.user.id, viewing_id)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "data": {
+            "viewing": viewing
+        }
+    })))
+}
+
+pub async fn confirm_viewing(
+    Path(viewing_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+    Json(body): Json<ConfirmViewingDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let viewing = app_state.viewing_service
+        .confirm(user.user.id, viewing_id, body)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Viewing confirmed",
+        "data": {
+            "viewing": viewing
+        }
+    })))
+}
+
+pub async fn decline_viewing(
+    Path(viewing_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+    Json(body): Json<ViewingNoteDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let viewing = app_state.viewing_service
+        .decline(user.user.id, viewing_id, &body.reason)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Viewing declined",
+        "data": {
+            "viewing": viewing
+        }
+    })))
+}
+
+pub async fn cancel_viewing(
+    Path(viewing_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+    Json(body): Json<ViewingNoteDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let viewing = app_state.viewing_service
+        .cancel(user.user.id, viewing_id, &body.reason)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Viewing cancelled",
+        "data": {
+            "viewing": viewing
+        }
+    })))
+}
+
+pub async fn mark_viewing_attended(
+    Path(viewing_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+) -> Result<impl IntoResponse, HttpError> {
+    let viewing = app_state.viewing_service
+        .mark_attended(user.user.id, viewing_id)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "Viewing completed",
+        "data": {
+            "viewing": viewing
+        }
+    })))
+}
+
+pub async fn report_no_show(
+    Path(viewing_id): Path<Uuid>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(user): Extension<JWTAuthMiddeware>,
+    Json(body): Json<ReportNoShowDto>,
+) -> Result<impl IntoResponse, HttpError> {
+    body.validate()
+        .map_err(|e| HttpError::bad_request(e.to_string()))?;
+
+    let viewing = app_state.viewing_service
+        .report_no_show(user.user.id, viewing_id, body)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "message": "No-show recorded",
+        "data": {
+            "viewing": viewing
+        }
+    })))
+}
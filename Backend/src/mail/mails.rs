@@ -2,8 +2,8 @@
 use super::secure_sendmail::send_email;
 use crate::{models::{
     verificationmodels::OtpPurpose,
-    usermodel::VerificationStatus
-}
+    usermodel::{Locale, VerificationStatus}
+}, utils::i18n
 };
 
 /// Helper function to find template file path
@@ -20,13 +20,21 @@ fn find_template_path(template_name: &str) -> Result<String, Box<dyn std::error:
         .ok_or(format!("Template not found: {}", template_name).into())
 }
 
+/// Translated template copy followed by the caller's placeholders
+fn localized(locale: Locale, template_name: &str, placeholders: Vec<(String, String)>) -> Vec<(String, String)> {
+    let mut localized = i18n::email_strings(locale, template_name);
+    localized.extend(placeholders);
+    localized
+}
+
 /// Send verification email with proper error handling
 pub async fn send_verification_email(
     to_email: &str,
     username: &str,
-    token: &str
+    token: &str,
+    locale: Locale,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let subject = "Email Verification";
+    let subject = i18n::t(locale, "email.verification_email.subject");
     let template_path = find_template_path("Verification-email.html")?;
     
     let base_url = std::env::var("APP_URL").unwrap_or_else(|_| "https://verinest.xyz".to_string());
@@ -35,8 +43,9 @@ pub async fn send_verification_email(
         ("{{username}}".to_string(), username.to_string()),
         ("{{verification_link}}".to_string(), verification_link)
     ];
+    let placeholders = localized(locale, "Verification-email.html", placeholders);
 
-    send_email(to_email, subject, &template_path, &placeholders).await
+    send_email(to_email, &subject, &template_path, &placeholders).await
 }
 
 fn create_verification_link(base_url: &str, token: &str) -> String {
@@ -46,51 +55,59 @@ fn create_verification_link(base_url: &str, token: &str) -> String {
 
 pub async fn send_welcome_email(
     to_email: &str,
-    username: &str
+    username: &str,
+    locale: Locale,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let subject = "Welcome to Application";
+    let subject = i18n::t(locale, "email.welcome_email.subject");
     let template_path = find_template_path("Welcome-email.html")?;
     let placeholders = vec![
         ("{{username}}".to_string(), username.to_string())
     ];
+    let placeholders = localized(locale, "Welcome-email.html", placeholders);
 
-    send_email(to_email, subject, &template_path, &placeholders).await
+    send_email(to_email, &subject, &template_path, &placeholders).await
 }
 
 pub async fn send_forgot_password_email(
     to_email: &str,
     rest_link: &str,
-    username: &str
+    username: &str,
+    locale: Locale,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let subject = "Rest your Password";
+    let subject = i18n::t(locale, "email.restpassword_email.subject");
     let template_path = find_template_path("RestPassword-email.html")?;
     let placeholders = vec![
         ("{{username}}".to_string(), username.to_string()),
         ("{{rest_link}}".to_string(), rest_link.to_string())
     ];
+    let placeholders = localized(locale, "RestPassword-email.html", placeholders);
 
-    send_email(to_email, subject, &template_path, &placeholders).await
+    send_email(to_email, &subject, &template_path, &placeholders).await
 }
 
 pub async fn send_otp_email(
     to_email: &str,
     otp_code: &str,
     purpose: &OtpPurpose,
+    locale: Locale,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let subject = match purpose {
-        OtpPurpose::AccountVerification => "Account Verification OTP",
-        OtpPurpose::PasswordReset => "Password Reset OTP",
-        OtpPurpose::Transaction => "Transaction OTP",
-        OtpPurpose::VerificationUpdate => "Verification Update OTP",
-        OtpPurpose::SensitiveAction => "Sensitive Action OTP",
+    let subject_key = match purpose {
+        OtpPurpose::AccountVerification => "email.otp_email.subject_account_verification",
+        OtpPurpose::PasswordReset => "email.otp_email.subject_password_reset",
+        OtpPurpose::Transaction => "email.otp_email.subject_transaction",
+        OtpPurpose::VerificationUpdate => "email.otp_email.subject_verification_update",
+        OtpPurpose::SensitiveAction => "email.otp_email.subject_sensitive_action",
+        OtpPurpose::PhoneVerification => "email.otp_email.subject_phone_verification",
     };
+    let subject = i18n::t(locale, subject_key);
     let template_path = find_template_path("OTP-email.html")?;
     let placeholders = vec![
         ("{{otp_code}}".to_string(), otp_code.to_string()),
         ("{{purpose}}".to_string(), format!("{:?}", purpose)),
     ];
+    let placeholders = localized(locale, "OTP-email.html", placeholders);
 
-    send_email(to_email, subject, &template_path, &placeholders).await
+    send_email(to_email, &subject, &template_path, &placeholders).await
 }
 
 // In mails.rs - Add this function
@@ -99,39 +116,42 @@ pub async fn send_verification_status_email(
     username: &str,
     status: &VerificationStatus,
     review_notes: Option<&str>,
+    locale: Locale,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let subject = match status {
-        VerificationStatus::Approved => "Verification Approved",
-        VerificationStatus::Rejected => "Verification Rejected", 
-        VerificationStatus::Processing => "Verification Under Review",
-        VerificationStatus::Submitted => "Verification Submitted",
-        _ => "Verification Status Update",
+    let subject_key = match status {
+        VerificationStatus::Approved => "email.verification_status.subject_approved",
+        VerificationStatus::Rejected => "email.verification_status.subject_rejected",
+        VerificationStatus::Processing => "email.verification_status.subject_processing",
+        VerificationStatus::Submitted => "email.verification_status.subject_submitted",
+        _ => "email.verification_status.subject_default",
     };
+    let subject = i18n::t(locale, subject_key);
 
-    let template_path = "src/mail/templates/Verification-Status.html";
-    
-    let status_display = match status {
+    let template_path = find_template_path("Verification-status.html")?;
+
+    let status = match status {
         VerificationStatus::Unverified => "unverified",
-        VerificationStatus::Approved => "Approved",
-        VerificationStatus::Rejected => "Rejected",
-        VerificationStatus::Processing => "Under Review",
-        VerificationStatus::Submitted => "Submitted",
-        VerificationStatus::Pending => "Pending",
-        VerificationStatus::Expired => "Expired",
+        VerificationStatus::Approved => "approved",
+        VerificationStatus::Rejected => "rejected",
+        VerificationStatus::Processing => "processing",
+        VerificationStatus::Submitted => "submitted",
+        VerificationStatus::Pending => "pending",
+        VerificationStatus::Expired => "expired",
     };
+    let status_display = i18n::t(locale, &format!("value.status.{}", status));
 
     let app_url = std::env::var("APP_URL").unwrap_or_else(|_| "https://verinest.xyz/".to_string());
     let dashboard_url = format!("{}/dashboard", app_url);
     let verification_url = format!("{}/verification", app_url);
 
-    let is_approved = status == &VerificationStatus::Approved;
-    let is_rejected = status == &VerificationStatus::Rejected;
-    let is_under_review = status == &VerificationStatus::Processing;
+    let is_approved = status == "approved";
+    let is_rejected = status == "rejected";
+    let is_under_review = status == "processing";
 
     let mut placeholders = vec![
         ("{{username}}".to_string(), username.to_string()),
-        ("{{status}}".to_string(), status_display.to_lowercase()),
-        ("{{status_display}}".to_string(), status_display.to_string()),
+        ("{{status}}".to_string(), status.to_string()),
+        ("{{status_display}}".to_string(), status_display),
         ("{{dashboard_url}}".to_string(), dashboard_url),
         ("{{verification_url}}".to_string(), verification_url),
         ("{{is_approved}}".to_string(), is_approved.to_string()),
@@ -144,119 +164,85 @@ pub async fn send_verification_status_email(
     } else {
         placeholders.push(("{{review_notes}}".to_string(), "".to_string()));
     }
+    let placeholders = localized(locale, "Verification-status.html", placeholders);
 
-    send_email(to_email, subject, template_path, &placeholders).await
-}
-
-// Job / Application / Assignment Emails
-pub async fn send_job_application_email(
-    to_email: &str,
-    username: &str,
-    job_title: &str,
-    applicant_name: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let subject = "New Job Application Received";
-    let template_path = "src/mail/templates/Job-Application.html";
-    let placeholders = vec![
-        ("{{username}}".to_string(), username.to_string()),
-        ("{{job_title}}".to_string(), job_title.to_string()),
-        ("{{applicant_name}}".to_string(), applicant_name.to_string()),
-    ];
-
-    send_email(to_email, subject, template_path, &placeholders).await
+    send_email(to_email, &subject, &template_path, &placeholders).await
 }
 
-pub async fn send_job_assignment_email(
+/// Render one of the HTML templates by file name; used by the notification dispatcher
+pub async fn send_templated_email(
     to_email: &str,
-    username: &str,
-    job_title: &str,
+    subject: &str,
+    template_name: &str,
+    placeholders: &[(String, String)],
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let subject = "You've been assigned a job";
-    let template_path = "src/mail/templates/Job-Assignment.html";
-    let placeholders = vec![
-        ("{{username}}".to_string(), username.to_string()),
-        ("{{job_title}}".to_string(), job_title.to_string()),
-    ];
-
-    send_email(to_email, subject, template_path, &placeholders).await
+    let template_path = find_template_path(template_name)?;
+    send_email(to_email, subject, &template_path, placeholders).await
 }
 
-pub async fn send_job_completion_email(
+/// Combined email for notifications held back by quiet hours or a digest preference
+pub async fn send_notification_digest_email(
     to_email: &str,
     username: &str,
-    job_title: &str,
+    locale: Locale,
+    entries: &[(String, String)],
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let subject = "Job Completed";
-    let template_path = "src/mail/templates/Job-Completion.html";
+    let count = entries.len().to_string();
+    let subject = i18n::format(locale, "email.mail_notification.digest_subject", &[("count", &count)]);
+    let template_path = "src/mail/templates/Mail_notification.html";
+    let message = entries
+        .iter()
+        .map(|(title, body)| format!("<p><strong>{}</strong><br>{}</p>", title, body))
+        .collect::<Vec<_>>()
+        .join("");
     let placeholders = vec![
         ("{{username}}".to_string(), username.to_string()),
-        ("{{job_title}}".to_string(), job_title.to_string()),
+        ("{{title}}".to_string(), i18n::t(locale, "email.mail_notification.digest_title")),
+        ("{{message}}".to_string(), message),
     ];
+    let placeholders = localized(locale, "Mail_notification.html", placeholders);
 
-    send_email(to_email, subject, template_path, &placeholders).await
+    send_email(to_email, &subject, template_path, &placeholders).await
 }
 
-pub async fn send_notification_email(
+/// Sent when an account signs in from a device or country it has not used before
+pub async fn send_login_alert_email(
     to_email: &str,
     username: &str,
-    title: &str,
-    message: &str,
-    notification_type: &str
+    locale: Locale,
+    device: Option<&str>,
+    location: Option<&str>,
+    ip_address: Option<&str>,
+    signed_in_at: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let subject = notification_type;
-    let template_path = "src/mail/templates/Mail_notification.html";
-    let placeholders = vec![
-        ("{{username}}".to_string(), username.to_string()),
-        ("{{title}}".to_string(), title.to_string()),
-        ("{{message}}".to_string(), message.to_string()),
-    ];
+    let subject = i18n::t(locale, "email.mail_notification.login_alert_subject");
+    let template_path = find_template_path("Mail_notification.html")?;
+
+    let device = device
+        .map(str::to_string)
+        .unwrap_or_else(|| i18n::t(locale, "email.mail_notification.login_alert_unknown_device"));
+    let location = location
+        .map(str::to_string)
+        .unwrap_or_else(|| i18n::t(locale, "email.mail_notification.login_alert_unknown_location"));
+    let body = i18n::format(
+        locale,
+        "email.mail_notification.login_alert_body",
+        &[("device", &device), ("location", &location), ("ip", ip_address.unwrap_or("-")), ("time", signed_in_at)],
+    );
+    let message = format!(
+        "<p>{}</p><p>{}</p>",
+        body,
+        i18n::t(locale, "email.mail_notification.login_alert_advice")
+    );
 
-    send_email(to_email, subject, template_path, &placeholders).await
-}
-
-// Transaction Emails
-pub async fn send_payment_released_email(to_email: &str, username: &str, amount: f64) -> Result<(), Box<dyn std::error::Error>> {
-    let subject = "Payment Released";
-    let template_path = "src/mail/templates/Payment-Released.html";
     let placeholders = vec![
         ("{{username}}".to_string(), username.to_string()),
-        ("{{amount}}".to_string(), format!("{:.2}", amount)),
-    ];
-    send_email(to_email, subject, template_path, &placeholders).await
-}
-
-pub async fn send_deposit_email(to_email: &str, username: &str, amount: f64, reference: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let subject = "Deposit Successful";
-    let template_path = "src/mail/templates/Deposit.html";
-    let placeholders = vec![
-        ("{{username}}".to_string(), username.to_string()),
-        ("{{amount}}".to_string(), format!("{:.2}", amount)),
-        ("{{reference}}".to_string(), reference.to_string()),
-    ];
-    send_email(to_email, subject, template_path, &placeholders).await
-}
-
-pub async fn send_withdrawal_email(to_email: &str, username: &str, amount: f64, reference: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let subject = "Withdrawal Processed";
-    let template_path = "src/mail/templates/Withdrawal.html";
-    let placeholders = vec![
-        ("{{username}}".to_string(), username.to_string()),
-        ("{{amount}}".to_string(), format!("{:.2}", amount)),
-        ("{{reference}}".to_string(), reference.to_string()),
+        ("{{title}}".to_string(), i18n::t(locale, "email.mail_notification.login_alert_title")),
+        ("{{message}}".to_string(), message),
     ];
-    send_email(to_email, subject, template_path, &placeholders).await
-}
+    let placeholders = localized(locale, "Mail_notification.html", placeholders);
 
-pub async fn send_transfer_email(to_email: &str, username: &str, amount: f64, reference: &str, direction: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let subject = if direction == "sent" { "Transfer Sent" } else { "Transfer Received" };
-    let template_path = "src/mail/templates/Transfer.html";
-    let placeholders = vec![
-        ("{{username}}".to_string(), username.to_string()),
-        ("{{amount}}".to_string(), format!("{:.2}", amount)),
-        ("{{reference}}".to_string(), reference.to_string()),
-        ("{{direction}}".to_string(), direction.to_string()),
-    ];
-    send_email(to_email, subject, template_path, &placeholders).await
+    send_email(to_email, &subject, &template_path, &placeholders).await
 }
 
 // In mails.rs - Add progress update email function
@@ -267,8 +253,9 @@ pub async fn send_progress_update_email(
     job_title: &str,
     progress_percentage: i32,
     progress_description: &str,
+    locale: Locale,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let subject = format!("Progress Update: {}", job_title);
+    let subject = i18n::format(locale, "email.progress_update.subject", &[("job_title", job_title)]);
     let template_path = "src/mail/templates/Progress-Update.html";
     
     let app_url = std::env::var("APP_URL")
@@ -285,6 +272,7 @@ pub async fn send_progress_update_email(
         ("{{update_date}}".to_string(), update_date),
         ("{{dashboard_url}}".to_string(), dashboard_url),
     ];
+    let placeholders = localized(locale, "Progress-Update.html", placeholders);
 
     send_email(to_email, &subject, template_path, &placeholders).await
 }
@@ -295,11 +283,12 @@ pub async fn send_dispute_notification_email(
     dispute_reason: &str,
     job_title: &str,
     is_raised_by: bool,
+    locale: Locale,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let subject = if is_raised_by {
-        "Dispute Created - Under Review"
+        i18n::t(locale, "email.dispute_notification.subject_raised_by")
     } else {
-        "Dispute Raised Against You"
+        i18n::t(locale, "email.dispute_notification.subject_against")
     };
     
     let template_path = "src/mail/templates/Dispute-Notification.html";
@@ -309,9 +298,9 @@ pub async fn send_dispute_notification_email(
     let disputes_url = format!("{}/disputes", app_url);
     
     let message = if is_raised_by {
-        format!("Your dispute has been created and is being reviewed by our team. We will notify you once a resolution has been reached.")
+        i18n::t(locale, "email.dispute_notification.message_raised_by")
     } else {
-        format!("A dispute has been raised against you regarding the job: {}. Please review the details and provide your response.", job_title)
+        i18n::format(locale, "email.dispute_notification.message_against", &[("job_title", job_title)])
     };
     
     let placeholders = vec![
@@ -321,8 +310,9 @@ pub async fn send_dispute_notification_email(
         ("{{message}}".to_string(), message),
         ("{{disputes_url}}".to_string(), disputes_url),
     ];
+    let placeholders = localized(locale, "Dispute-Notification.html", placeholders);
 
-    send_email(to_email, subject, template_path, &placeholders).await
+    send_email(to_email, &subject, template_path, &placeholders).await
 }
 
 pub async fn send_contract_proposal_email(
@@ -332,8 +322,9 @@ pub async fn send_contract_proposal_email(
     job_title: &str,
     agreed_rate: f64,
     agreed_timeline: i32,
+    locale: Locale,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let subject = format!("Contract Proposal: {}", job_title);
+    let subject = i18n::format(locale, "email.contract_proposal.subject", &[("job_title", job_title)]);
     let template_path = "src/mail/templates/Contract-Proposal.html";
     
     let app_url = std::env::var("APP_URL")
@@ -345,9 +336,10 @@ pub async fn send_contract_proposal_email(
         ("{{proposer_name}}".to_string(), proposer_name.to_string()),
         ("{{job_title}}".to_string(), job_title.to_string()),
         ("{{agreed_rate}}".to_string(), format!("₦{:.2}", agreed_rate)),
-        ("{{agreed_timeline}}".to_string(), format!("{} days", agreed_timeline)),
+        ("{{agreed_timeline}}".to_string(), i18n::format(locale, "email.common.days", &[("count", &agreed_timeline.to_string())])),
         ("{{chat_url}}".to_string(), chat_url),
     ];
+    let placeholders = localized(locale, "Contract-Proposal.html", placeholders);
 
     send_email(to_email, &subject, template_path, &placeholders).await
 }
@@ -357,8 +349,9 @@ pub async fn send_new_message_notification_email(
     username: &str,
     sender_name: &str,
     message_preview: &str,
+    locale: Locale,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let subject = format!("New message from {}", sender_name);
+    let subject = i18n::format(locale, "email.new_message.subject", &[("sender_name", sender_name)]);
     let template_path = "src/mail/templates/New-Message.html";
     
     let app_url = std::env::var("APP_URL")
@@ -371,19 +364,20 @@ pub async fn send_new_message_notification_email(
         ("{{message_preview}}".to_string(), message_preview.to_string()),
         ("{{chat_url}}".to_string(), chat_url),
     ];
+    let placeholders = localized(locale, "New-Message.html", placeholders);
 
     send_email(to_email, &subject, template_path, &placeholders).await
 }
 
-
 pub async fn send_contract_signature_otp_email(
     to_email: &str,
     username: &str,
     otp_code: &str,
     agreed_rate: &f64,
     agreed_timeline: i32,
+    locale: Locale,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let subject = "Contract Signature Verification";
+    let subject = i18n::t(locale, "email.contract_signature_otp.subject");
     let template_path = "src/mail/templates/Contract-Signature-OTP.html";
     
     let app_url = std::env::var("APP_URL")
@@ -393,35 +387,12 @@ pub async fn send_contract_signature_otp_email(
         ("{{username}}".to_string(), username.to_string()),
         ("{{otp_code}}".to_string(), otp_code.to_string()),
         ("{{agreed_rate}}".to_string(), format!("₦{:.2}", agreed_rate)),
-        ("{{agreed_timeline}}".to_string(), format!("{} days", agreed_timeline)),
+        ("{{agreed_timeline}}".to_string(), i18n::format(locale, "email.common.days", &[("count", &agreed_timeline.to_string())])),
         ("{{app_url}}".to_string(), app_url),
     ];
+    let placeholders = localized(locale, "Contract-Signature-OTP.html", placeholders);
 
-    send_email(to_email, subject, template_path, &placeholders).await
-}
-
-pub async fn send_service_inquiry_email(
-    to_email: &str,
-    username: &str,
-    inquirer_name: &str,
-    service_title: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let subject = "New Inquiry for Your Service";
-    let template_path = "src/mail/templates/Service-Inquiry.html";
-    
-    let app_url = std::env::var("APP_URL")
-        .unwrap_or_else(|_| "https://verinest.xyz".to_string());
-    let inquiries_url = format!("{}/vendor/inquiries", app_url);
-    
-    let placeholders = vec![
-        ("{{username}}".to_string(), username.to_string()),
-        ("{{inquirer_name}}".to_string(), inquirer_name.to_string()),
-        ("{{service_title}}".to_string(), service_title.to_string()),
-        ("{{inquiries_url}}".to_string(), inquiries_url),
-    ];
-
-    send_email(to_email, subject, template_path, &placeholders).await
-
+    send_email(to_email, &subject, template_path, &placeholders).await
 }
 
 //
\ No newline at end of file
@@ -1,3 +1,4 @@
 pub mod sendmail;
 pub mod secure_sendmail;
-pub mod mails;
\ No newline at end of file
+pub mod mails;
+pub mod outbox;
\ No newline at end of file
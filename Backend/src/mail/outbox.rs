@@ -0,0 +1,183 @@
+// mail/outbox.rs
+use std::sync::{Arc, LazyLock, OnceLock};
+
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+use tokio::sync::Notify;
+use tracing::{info, warn};
+
+use super::secure_sendmail::deliver_email;
+use crate::{
+    db::{db::DBClient, emaildb::EmailOutboxExt},
+    models::emailmodels::{EmailStatus, OutboxEmail, SECRET_PLACEHOLDERS},
+};
+
+/// How long a worker may hold a claimed message before another worker can take it
+const LEASE_SECONDS: i64 = 300;
+
+/// Identical messages enqueued within this window collapse into one
+const DEDUP_WINDOW_SECONDS: i64 = 600;
+
+static OUTBOX_DB: OnceLock<Arc<DBClient>> = OnceLock::new();
+static WAKE_WORKER: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+/// Route `send_email` through the outbox. Called once at startup.
+pub fn init(db_client: Arc<DBClient>) {
+    let _ = OUTBOX_DB.set(db_client);
+}
+
+pub fn is_enabled() -> bool {
+    OUTBOX_DB.get().is_some()
+}
+
+/// Resolves when something was enqueued, letting the worker skip its poll interval
+pub async fn wait_for_work() {
+    WAKE_WORKER.notified().await;
+}
+
+pub fn idempotency_key(
+    to_email: &str,
+    subject: &str,
+    template_path: &str,
+    placeholders: &[(String, String)],
+    bucket: i64,
+) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(to_email.to_lowercase().as_bytes());
+    hasher.update(b"|");
+    hasher.update(subject.as_bytes());
+    hasher.update(b"|");
+    hasher.update(template_path.as_bytes());
+    for (key, value) in placeholders {
+        hasher.update(b"|");
+        hasher.update(key.as_bytes());
+        hasher.update(b"=");
+        hasher.update(value.as_bytes());
+    }
+    hasher.update(b"|");
+    hasher.update(bucket.to_string().as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+pub async fn enqueue(
+    to_email: &str,
+    subject: &str,
+    template_path: &str,
+    placeholders: &[(String, String)],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let bucket = Utc::now().timestamp() / DEDUP_WINDOW_SECONDS;
+    let key = idempotency_key(to_email, subject, template_path, placeholders, bucket);
+    enqueue_with_key(&key, to_email, subject, template_path, placeholders).await
+}
+
+/// Enqueue under a caller-chosen idempotency key. A repeated key is a no-op.
+pub async fn enqueue_with_key(
+    idempotency_key: &str,
+    to_email: &str,
+    subject: &str,
+    template_path: &str,
+    placeholders: &[(String, String)],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let db_client = OUTBOX_DB.get().ok_or("Email outbox is not initialised")?;
+
+    // Suppressed recipients still get a row so the admin view shows what was withheld
+    let suppressed = db_client.get_email_suppression(to_email).await?.is_some();
+    let (status, placeholders) = if suppressed {
+        (EmailStatus::Suppressed, without_secrets(placeholders))
+    } else {
+        (EmailStatus::Queued, placeholders.to_vec())
+    };
+
+    let inserted = db_client
+        .enqueue_email(idempotency_key, to_email, subject, template_path, &placeholders, status)
+        .await?;
+
+    if inserted.is_none() {
+        info!("Skipping duplicate email to {} (key {})", to_email, idempotency_key);
+        return Ok(());
+    }
+
+    if suppressed {
+        return Err(format!("{} is on the email suppression list", to_email).into());
+    }
+
+    WAKE_WORKER.notify_one();
+    Ok(())
+}
+
+/// Drop placeholders holding one-time codes and links
+fn without_secrets(placeholders: &[(String, String)]) -> Vec<(String, String)> {
+    placeholders
+        .iter()
+        .filter(|(key, _)| !SECRET_PLACEHOLDERS.contains(&key.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// Claim and send one batch of due messages. Returns how many were attempted.
+pub async fn process_due_emails(db_client: &DBClient, batch_size: i64) -> Result<usize, sqlx::Error> {
+    let emails = db_client.claim_due_emails(batch_size, LEASE_SECONDS).await?;
+    let count = emails.len();
+
+    for email in emails {
+        send_outbox_email(db_client, email).await?;
+    }
+
+    Ok(count)
+}
+
+async fn send_outbox_email(db_client: &DBClient, email: OutboxEmail) -> Result<(), sqlx::Error> {
+    // A bounce may have landed since the message was queued
+    if db_client.get_email_suppression(&email.to_email).await?.is_some() {
+        db_client.mark_email_suppressed(email.id).await?;
+        return Ok(());
+    }
+
+    let result = deliver_email(&email.to_email, &email.subject, &email.template_path, &email.placeholders)
+        .await
+        .map_err(|e| e.to_string());
+
+    match result {
+        Ok(message_id) => {
+            db_client.mark_email_sent(email.id, message_id.as_deref()).await?;
+        }
+        Err(error) => {
+            let next_attempt_at = Utc::now() + OutboxEmail::retry_delay(email.attempts + 1);
+            let updated = db_client.mark_email_failed(email.id, &error, next_attempt_at).await?;
+            if updated.status == EmailStatus::Failed {
+                warn!("Email {} to {} failed permanently: {}", email.id, email.to_email, error);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idempotency_key_is_stable_within_bucket() {
+        let placeholders = vec![("{{username}}".to_string(), "ada".to_string())];
+        let a = idempotency_key("Ada@Example.com", "Hi", "t.html", &placeholders, 1);
+        let b = idempotency_key("ada@example.com", "Hi", "t.html", &placeholders, 1);
+        let c = idempotency_key("ada@example.com", "Hi", "t.html", &placeholders, 2);
+        let d = idempotency_key("ada@example.com", "Hello", "t.html", &placeholders, 1);
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_ne!(a, d);
+    }
+
+    #[test]
+    fn test_without_secrets_drops_codes_and_links() {
+        let placeholders = vec![
+            ("{{username}}".to_string(), "ada".to_string()),
+            ("{{otp_code}}".to_string(), "123456".to_string()),
+            ("{{rest_link}}".to_string(), "https://example.com/reset?token=abc".to_string()),
+        ];
+
+        assert_eq!(without_secrets(&placeholders), vec![("{{username}}".to_string(), "ada".to_string())]);
+    }
+}
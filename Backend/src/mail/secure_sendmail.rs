@@ -6,6 +6,8 @@ use ammonia::{Builder, UrlRelative};
 use regex::Regex;
 use tracing::{info, warn, error};
 
+use super::outbox;
+
 const MAX_RETRIES: u32 = 3;
 const RETRY_DELAY_MS: u64 = 1000;
 
@@ -156,8 +158,26 @@ pub async fn send_email(
     validate_email(to_email).map_err(|e| e.to_string())?;
     validate_template_path(template_path).map_err(|e| e.to_string())?;
 
-    // Read and process template
-    let mut html_template = match fs::read_to_string(template_path) {
+    // Hand off to the outbox worker when it is running; otherwise send inline
+    if outbox::is_enabled() {
+        return outbox::enqueue(to_email, subject, template_path, placeholders).await;
+    }
+
+    deliver_email(to_email, subject, template_path, placeholders).await?;
+    Ok(())
+}
+
+/// Render and send immediately, returning the provider message id when the
+/// provider reports one. Used by the outbox worker and as the inline fallback.
+pub async fn deliver_email(
+    to_email: &str,
+    subject: &str,
+    template_path: &str,
+    placeholders: &[(String, String)]
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    validate_template_path(template_path).map_err(|e| e.to_string())?;
+
+    let html_template = match render_email(template_path, placeholders) {
         Ok(content) => content,
         Err(e) => {
             let sanitized_error = sanitize_error(&e.to_string(), "template");
@@ -166,49 +186,47 @@ pub async fn send_email(
         }
     };
 
-    // Sanitize all placeholder values to prevent HTML injection
-    let sanitized_placeholders: Vec<(String, String)> = placeholders
-        .iter()
-        .map(|(key, value)| {
-            let sanitized_value = sanitize_html(value);
-            (key.clone(), sanitized_value)
-        })
-        .collect();
-
-    // Replace placeholders with sanitized values
-    for (key, value) in &sanitized_placeholders {
-        html_template = html_template.replace(key, value);
-    }
-
     // Send with both Resend and SMTP fallback
     match send_with_fallback(to_email, subject, &html_template).await {
-        Ok(_) => {
+        Ok(message_id) => {
             log_email_operation(to_email, subject, template_path, true, None);
-            Ok(())
+            Ok(message_id)
         }
         Err(e) => {
             let sanitized_error = sanitize_error(&e.to_string(), "network");
             log_email_operation(to_email, subject, template_path, false, Some(&sanitized_error));
-            
-            // Even if email fails, we still want to continue with notification
-            warn!("Email sending failed but continuing with notification: {}", sanitized_error);
             Err(sanitized_error.into())
         }
     }
 }
 
+fn render_email(
+    template_path: &str,
+    placeholders: &[(String, String)]
+) -> Result<String, std::io::Error> {
+    let mut html_template = fs::read_to_string(template_path)?;
+
+    // Sanitize all placeholder values to prevent HTML injection
+    for (key, value) in placeholders {
+        html_template = html_template.replace(key, &sanitize_html(value));
+    }
+
+    Ok(html_template)
+}
+
+/// Returns the Resend message id, or None when the SMTP fallback was used
 async fn send_with_fallback(
     to_email: &str,
     subject: &str,
     html_body: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let mut last_error = None;
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let last_error;
 
     // Try Resend first
     match send_via_resend(to_email, subject, html_body).await {
-        Ok(_) => {
+        Ok(message_id) => {
             info!("Email sent successfully via Resend to {}", to_email);
-            return Ok(());
+            return Ok(Some(message_id).filter(|id| id != "success"));
         }
         Err(e) => {
             warn!("Resend failed, trying SMTP fallback: {}", e);
@@ -220,7 +238,7 @@ async fn send_with_fallback(
     match send_via_smtp(to_email, subject, html_body).await {
         Ok(_) => {
             info!("Email sent successfully via SMTP to {}", to_email);
-            Ok(())
+            Ok(None)
         }
         Err(e) => {
             error!("Both Resend and SMTP failed for {}: {}", to_email, e);
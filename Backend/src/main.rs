@@ -35,6 +35,23 @@ use service::{
     audit_service::AuditService,
     matching_service::MatchingService,
     verification_service::VerificationService,
+    property_service::PropertyService,
+    viewing_service::ViewingService,
+    tenancy_service::TenancyService,
+    offer_service::OfferService,
+    saved_property_service::SavedPropertyService,
+    certificate_service::CertificateService,
+    storage_service::StorageService,
+    session_service::SessionService,
+    two_factor_service::TwoFactorService,
+    role_service::RoleService,
+    kyc_service::KycService,
+    identity_service::IdentityService,
+    privacy_service::PrivacyService,
+    otp_service::OtpService,
+    attempt_guard::AttemptGuard,
+    login_activity_service::LoginActivityService,
+    sms,
 };
 
 #[derive(Debug, Clone)]
@@ -50,6 +67,22 @@ pub struct AppState {
     pub audit_service: Arc<AuditService>,
     pub matching_service: Arc<MatchingService>,
     pub verification_service: Arc<VerificationService>,
+    pub property_service: Arc<PropertyService>,
+    pub viewing_service: Arc<ViewingService>,
+    pub tenancy_service: Arc<TenancyService>,
+    pub offer_service: Arc<OfferService>,
+    pub saved_property_service: Arc<SavedPropertyService>,
+    pub certificate_service: Arc<CertificateService>,
+    pub storage_service: Arc<StorageService>,
+    pub session_service: Arc<SessionService>,
+    pub two_factor_service: Arc<TwoFactorService>,
+    pub role_service: Arc<RoleService>,
+    pub kyc_service: Arc<KycService>,
+    pub identity_service: Arc<IdentityService>,
+    pub privacy_service: Arc<PrivacyService>,
+    pub otp_service: Arc<OtpService>,
+    pub attempt_guard: Arc<AttemptGuard>,
+    pub login_activity_service: Arc<LoginActivityService>,
 }
 
 impl AppState {
@@ -85,6 +118,74 @@ impl AppState {
             trust_service.clone(),
         ));
 
+        let storage_service = Arc::new(
+            StorageService::from_config(db_client_arc.clone(), &config)
+                .expect("Invalid media storage configuration"),
+        );
+
+        let certificate_signer = utils::certificate::CertificateSigner::from_config(
+            config.certificate_signing_key.as_deref(),
+            &config.certificate_retired_public_keys,
+        )
+        .expect("Invalid or missing certificate signing key");
+        let certificate_service = Arc::new(CertificateService::new(
+            db_client_arc.clone(),
+            certificate_signer,
+            storage_service.clone(),
+            &config.app_url,
+        ));
+
+        let property_service = Arc::new(PropertyService::new(
+            db_client_arc.clone(),
+            notification_service.clone(),
+            certificate_service.clone(),
+        ));
+
+        let viewing_service = Arc::new(ViewingService::new(
+            db_client_arc.clone(),
+            notification_service.clone(),
+            trust_service.clone(),
+        ));
+
+        let tenancy_service = Arc::new(TenancyService::new(
+            db_client_arc.clone(),
+            property_service.clone(),
+            notification_service.clone(),
+        ));
+
+        let offer_service = Arc::new(OfferService::new(
+            db_client_arc.clone(),
+            property_service.clone(),
+            notification_service.clone(),
+        ));
+
+        let saved_property_service = Arc::new(SavedPropertyService::new(
+            db_client_arc.clone(),
+            property_service.clone(),
+            notification_service.clone(),
+        ));
+
+        let session_service = Arc::new(SessionService::new(db_client_arc.clone(), &config));
+        let two_factor_service = Arc::new(TwoFactorService::new(db_client_arc.clone(), &config));
+        let role_service = Arc::new(RoleService::new(db_client_arc.clone(), audit_service.clone()));
+        let kyc_service = Arc::new(KycService::new(
+            db_client_arc.clone(),
+            storage_service.clone(),
+            notification_service.clone(),
+            &config.face_model_dir,
+        ));
+        let identity_service = Arc::new(
+            IdentityService::from_config(db_client_arc.clone(), &config)
+                .expect("Invalid identity provider configuration"),
+        );
+        let privacy_service = Arc::new(PrivacyService::new(db_client_arc.clone(), audit_service.clone()));
+        let otp_service = Arc::new(OtpService::new(
+            db_client_arc.clone(),
+            sms::from_env().expect("Invalid SMS configuration"),
+        ));
+        let attempt_guard = Arc::new(AttemptGuard::new(&db_client_arc));
+        let login_activity_service = Arc::new(LoginActivityService::new(db_client_arc.clone()));
+
         Self {
             env: config,
             db_client: db_client_arc,
@@ -96,6 +197,22 @@ impl AppState {
             audit_service,
             matching_service,
             verification_service,
+            property_service,
+            viewing_service,
+            tenancy_service,
+            offer_service,
+            saved_property_service,
+            certificate_service,
+            storage_service,
+            session_service,
+            two_factor_service,
+            role_service,
+            kyc_service,
+            identity_service,
+            privacy_service,
+            otp_service,
+            attempt_guard,
+            login_activity_service,
         }
     }
 }
@@ -212,6 +329,63 @@ async fn main() {
         service::background_jobs::start_service_expiry_job(app_state_clone).await;
     });
 
+    let app_state_clone = app_state.clone();
+    tokio::spawn(async move {
+        service::background_jobs::start_notification_digest_job(app_state_clone).await;
+    });
+
+    let app_state_clone = app_state.clone();
+    tokio::spawn(async move {
+        service::background_jobs::start_notification_retry_job(app_state_clone).await;
+    });
+
+    let app_state_clone = app_state.clone();
+    tokio::spawn(async move {
+        service::background_jobs::start_viewing_reminder_job(app_state_clone).await;
+    });
+
+    let app_state_clone = app_state.clone();
+    tokio::spawn(async move {
+        service::background_jobs::start_rent_collection_job(app_state_clone).await;
+    });
+
+    let app_state_clone = app_state.clone();
+    tokio::spawn(async move {
+        service::background_jobs::start_offer_expiry_job(app_state_clone).await;
+    });
+
+    let app_state_clone = app_state.clone();
+    tokio::spawn(async move {
+        service::background_jobs::start_photo_index_job(app_state_clone).await;
+    });
+
+    let app_state_clone = app_state.clone();
+    tokio::spawn(async move {
+        service::background_jobs::start_market_summary_job(app_state_clone).await;
+    });
+
+    let app_state_clone = app_state.clone();
+    tokio::spawn(async move {
+        service::background_jobs::start_saved_search_alert_job(app_state_clone).await;
+    });
+
+    let app_state_clone = app_state.clone();
+    tokio::spawn(async move {
+        service::background_jobs::start_verification_assignment_job(app_state_clone).await;
+    });
+
+    let app_state_clone = app_state.clone();
+    tokio::spawn(async move {
+        service::background_jobs::start_certificate_issue_job(app_state_clone).await;
+    });
+
+    mail::outbox::init(app_state.db_client.clone());
+    service::notification_stream::init(&app_state.db_client, config.redis_url.as_deref());
+    let app_state_clone = app_state.clone();
+    tokio::spawn(async move {
+        service::background_jobs::start_email_outbox_worker(app_state_clone).await;
+    });
+
     // Start vendor subscription expiry checker
     tokio::spawn(start_vendor_expiry_checker(app_state.clone()));
 
@@ -227,5 +401,8 @@ async fn main() {
         .await
         .unwrap();
 
-    axum::serve(listener, app).await.unwrap();
+    // The peer address is the client IP when no proxy sits in front
+    axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>())
+        .await
+        .unwrap();
 }
\ No newline at end of file
@@ -233,11 +233,10 @@ impl CacheInvalidationConfig {
                 vec![
                     "cache:*api/verification*",
                 ],
+                // Document listings carry signed links that expire, so they are not cached
                 vec![
-                    "GET /api/verification/documents",
                     "GET /api/verification/status",
                     "GET /api/verification/complete-status",
-                    "GET /api/verification/admin/pending",
                 ],
                 vec![
                     "POST /api/verification/otp/send",
@@ -11,12 +11,15 @@ use axum::{
 
 use axum_extra::extract::cookie::CookieJar;
 use serde::{Deserialize, Serialize};
-use redis::aio::ConnectionManager;
+use uuid::Uuid;
 
 use crate::{
     db::userdb::UserExt,
     error::{ErrorMessage, HttpError},
-    models::usermodel::{User, UserRole},
+    models::{
+        permissionmodels::{Permission, UserAccess},
+        usermodel::{User, UserRole},
+    },
     utils::token,
     AppState
 };
@@ -29,6 +32,63 @@ use serde_json::Value;
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct JWTAuthMiddeware {
     pub user: User,
+    pub session_id: Uuid,
+    pub access: UserAccess, // Every role the user holds and what they allow
+}
+
+impl JWTAuthMiddeware {
+    pub fn has_role(&self, role: UserRole) -> bool {
+        self.access.has_role(role)
+    }
+
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.access.has_permission(permission)
+    }
+
+    /// For checks inside handlers, where a route layer cannot decide alone
+    pub fn require_permission(&self, permission: Permission) -> Result<(), HttpError> {
+        if self.has_permission(permission) {
+            Ok(())
+        } else {
+            Err(HttpError::new(ErrorMessage::PermissionDenied.to_string(), StatusCode::FORBIDDEN))
+        }
+    }
+}
+
+/// Resolves an access token to its user, provided the session it was issued
+/// to is still signed in
+pub async fn authenticate_token(app_state: &AppState, token: &str) -> Result<JWTAuthMiddeware, HttpError> {
+    let claims = token::decode_token_claims(token, app_state.env.jwt_secret.as_bytes())
+        .map_err(|_| HttpError::unauthorized(ErrorMessage::InvalidToken.to_string()))?;
+
+    let (user_id, session_id) = match (Uuid::parse_str(&claims.sub), Uuid::parse_str(&claims.sid)) {
+        (Ok(user_id), Ok(session_id)) => (user_id, session_id),
+        _ => return Err(HttpError::unauthorized(ErrorMessage::InvalidToken.to_string())),
+    };
+
+    let active = app_state.session_service
+        .is_active(user_id, session_id)
+        .await
+        .map_err(|e| HttpError::server_error(e.to_string()))?;
+    if !active {
+        return Err(HttpError::unauthorized("Session has ended. Please login again.".to_string()));
+    }
+
+    let user = app_state.db_client.get_user(Some(user_id), None, None, None)
+            .await
+            .map_err(|_| {
+                HttpError::unauthorized(ErrorMessage::UserNoLongerExist.to_string())
+            })?
+            .ok_or_else(|| {
+                HttpError::unauthorized(ErrorMessage::UserNoLongerExist.to_string())
+            })?;
+
+    let access = app_state.role_service
+        .access(user_id)
+        .await
+        .map_err(|e| HttpError::server_error(e.to_string()))?;
+
+    Ok(JWTAuthMiddeware { user, session_id, access })
 }
 
 pub async fn auth(
@@ -57,59 +117,20 @@ pub async fn auth(
         HttpError::unauthorized(ErrorMessage::TokenNotProvided.to_string())
     })?;
 
-    let token_details = 
-        match token::decode_token(token, app_state.env.jwt_secret.as_bytes()) {
-            Ok(token_details) => token_details,
-            Err(_) => {
-                return Err(HttpError::unauthorized(ErrorMessage::InvalidToken.to_string()));
-            }
-        };
-
-    // ✅ FIX #6: Check if token is blacklisted (user logged out)
-    if let Some(redis_client) = &app_state.db_client.redis_client {
-        let blacklist_key = format!("token_blacklist:{}", token_details);
-        let mut conn = ConnectionManager::clone(redis_client);
-        
-        let is_blacklisted: bool = redis::cmd("EXISTS")
-            .arg(&blacklist_key)
-            .query_async(&mut conn)
-            .await
-            .unwrap_or(false);
-        
-        if is_blacklisted {
-            return Err(HttpError::unauthorized("Token has been revoked. Please login again.".to_string()));
-        }
-    }
-
-    let user_id = uuid::Uuid::parse_str(&token_details)
-            .map_err(|_| {
-                HttpError::unauthorized(ErrorMessage::InvalidToken.to_string())
-            })?;
-
-    let user = app_state.db_client.get_user(Some(user_id), None, None, None)
-            .await
-            .map_err(|_| {
-                HttpError::unauthorized(ErrorMessage::UserNoLongerExist.to_string())
-            })?;
-
-    let user = user.ok_or_else(|| {
-        HttpError::unauthorized(ErrorMessage::UserNoLongerExist.to_string())
-    })?;
-
-    req.extensions_mut().insert(JWTAuthMiddeware {
-        user: user.clone(),
-    });
+    let auth = authenticate_token(&app_state, &token).await?;
+    req.extensions_mut().insert(auth);
 
     Ok(next.run(req).await)
 
 }
 
 
-pub async fn role_check(
+/// Route layer letting through callers whose roles carry `permission`
+pub async fn permission_check(
     Extension(_app_state): Extension<Arc<AppState>>,
     req: Request,
     next: Next,
-    required_roles: Vec<UserRole>,
+    permission: Permission,
 ) -> Result<impl IntoResponse, HttpError> {
     let user = req
             .extensions()
@@ -117,17 +138,15 @@ pub async fn role_check(
             .ok_or_else(|| {
                 HttpError::unauthorized(ErrorMessage::UserNotAuthenticated.to_string())
             })?;
-    
-    if !required_roles.contains(&user.user.role) {
-        return Err(HttpError::new(ErrorMessage::PermissionDenied.to_string(), StatusCode::FORBIDDEN));
-    }
+
+    user.require_permission(permission)?;
 
     Ok(next.run(req).await)
 }
 
 /// Middleware that implements smart caching for GET requests and intelligent cache invalidation
-/// for POST/PUT/DELETE requests based on endpoint groupings. It also enforces rate limits
-/// for sensitive endpoints.
+/// for POST/PUT/DELETE requests based on endpoint groupings. Password and PIN attempts are
+/// limited per account by `AttemptGuard` in the handlers.
 pub async fn cache_and_rate_limit(mut req: Request, next: Next) -> Result<impl IntoResponse, HttpError> {
     // Initialize cache invalidation config
     let cache_config = CacheInvalidationConfig::new();
@@ -148,53 +167,6 @@ pub async fn cache_and_rate_limit(mut req: Request, next: Next) -> Result<impl I
     
     tracing::info!("🔍 Request: {} {}", method, path);
 
-    // Rate limiting for sensitive endpoints
-    if let Some(ref redis_arc) = app_state.db_client.redis_client {
-        let ip = req
-            .headers()
-            .get("x-forwarded-for")
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or("unknown")
-            .to_string();
-
-        // Apply rate limits for specific endpoints
-        if method == Method::POST && path == "/api/auth/login" {
-            let key = format!("rl:login:{}", ip);
-            let mut conn = ConnectionManager::clone(redis_arc);
-            let count: i64 = redis::cmd("INCR").arg(&key).query_async(&mut conn).await.map_err(|e| HttpError::server_error(e.to_string()))?;
-            if count == 1 {
-                let _ : () = redis::cmd("EXPIRE").arg(&key).arg(3600).query_async(&mut conn).await.map_err(|e| HttpError::server_error(e.to_string()))?;
-            }
-            if count > 5 {
-                return Err(HttpError::new(format!("Too many attempts"), StatusCode::TOO_MANY_REQUESTS));
-            }
-        }
-
-        if method == Method::PUT && path == "/api/users/password" {
-            let key = format!("rl:change_password:{}", ip);
-            let mut conn = ConnectionManager::clone(redis_arc);
-            let count: i64 = redis::cmd("INCR").arg(&key).query_async(&mut conn).await.map_err(|e| HttpError::server_error(e.to_string()))?;
-            if count == 1 {
-                let _ : () = redis::cmd("EXPIRE").arg(&key).arg(3600).query_async(&mut conn).await.map_err(|e| HttpError::server_error(e.to_string()))?;
-            }
-            if count > 3 {
-                return Err(HttpError::new(format!("Too many attempts"), StatusCode::TOO_MANY_REQUESTS));
-            }
-        }
-
-        if method == Method::POST && path == "/api/users/transaction-pin/verify" {
-            let key = format!("rl:verify_pin:{}", ip);
-            let mut conn = ConnectionManager::clone(redis_arc);
-            let count: i64 = redis::cmd("INCR").arg(&key).query_async(&mut conn).await.map_err(|e| HttpError::server_error(e.to_string()))?;
-            if count == 1 {
-                let _ : () = redis::cmd("EXPIRE").arg(&key).arg(3600).query_async(&mut conn).await.map_err(|e| HttpError::server_error(e.to_string()))?;
-            }
-            if count > 3 {
-                return Err(HttpError::new(format!("Too many attempts"), StatusCode::TOO_MANY_REQUESTS));
-            }
-        }
-    }
-
     // Handle GET requests - serve from cache if available
     if method == Method::GET {
         // Check if this endpoint should be cached
@@ -211,27 +183,19 @@ pub async fn cache_and_rate_limit(mut req: Request, next: Next) -> Result<impl I
             } else if let Some(auth_header) = req.headers().get(header::AUTHORIZATION).and_then(|h| h.to_str().ok()) {
                 if auth_header.starts_with("Bearer ") {
                     let token_str = &auth_header[7..];
-                    if let Ok(token_details) = crate::utils::token::decode_token(token_str.to_string(), app_state.env.jwt_secret.as_bytes()) {
-                        if let Ok(user_uuid) = uuid::Uuid::parse_str(&token_details) {
-                            if let Ok(Some(user)) = app_state.db_client.get_user(Some(user_uuid), None, None, None).await {
-                                req.extensions_mut().insert(JWTAuthMiddeware { user: user.clone() });
-                                req.extensions_mut().insert::<Option<JWTAuthMiddeware>>(Some(JWTAuthMiddeware { user: user.clone() }));
-                                user_tag = user_uuid.to_string();
-                            }
-                        }
+                    if let Ok(auth) = authenticate_token(&app_state, token_str).await {
+                        user_tag = auth.user.id.to_string();
+                        req.extensions_mut().insert(auth.clone());
+                        req.extensions_mut().insert::<Option<JWTAuthMiddeware>>(Some(auth));
                     }
                 }
             } else if let Some(cookie_header) = req.headers().get(header::COOKIE).and_then(|h| h.to_str().ok()) {
                 if let Some(pair) = cookie_header.split(';').map(|s| s.trim()).find(|s| s.starts_with("token=")) {
                     if let Some(tok) = pair.strip_prefix("token=") {
-                        if let Ok(token_details) = crate::utils::token::decode_token(tok.to_string(), app_state.env.jwt_secret.as_bytes()) {
-                            if let Ok(user_uuid) = uuid::Uuid::parse_str(&token_details) {
-                                if let Ok(Some(user)) = app_state.db_client.get_user(Some(user_uuid), None, None, None).await {
-                                    req.extensions_mut().insert(JWTAuthMiddeware { user: user.clone() });
-                                    req.extensions_mut().insert::<Option<JWTAuthMiddeware>>(Some(JWTAuthMiddeware { user: user.clone() }));
-                                    user_tag = user_uuid.to_string();
-                                }
-                            }
+                        if let Ok(auth) = authenticate_token(&app_state, tok).await {
+                            user_tag = auth.user.id.to_string();
+                            req.extensions_mut().insert(auth.clone());
+                            req.extensions_mut().insert::<Option<JWTAuthMiddeware>>(Some(auth));
                         }
                     }
                 }
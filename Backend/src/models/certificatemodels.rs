@@ -0,0 +1,63 @@
+use sqlx::types::chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::models::propertymodel::{ListingType, PropertyType};
+
+/// A signed certificate as stored. `payload` is the exact JSON text the
+/// signature covers.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PropertyCertificate {
+    pub id: Uuid,
+    pub property_id: Uuid,
+    pub reference: String,
+    pub payload: String,
+    pub signature: String,
+    pub key_id: String,
+    pub issued_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub revocation_reason: Option<String>,
+}
+
+/// What a certificate attests to. Field order is the signed order, so new
+/// fields go at the end with a new `version`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CertificatePayload {
+    pub version: u32,
+    pub certificate_id: Uuid,
+    pub reference: String,
+    pub property: CertifiedProperty,
+    pub agent: CertifiedVerifier,
+    pub lawyer: CertifiedVerifier,
+    pub documents: Vec<CertifiedDocument>,
+    pub issued_at: DateTime<Utc>,
+    pub key_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CertifiedProperty {
+    pub id: Uuid,
+    pub title: String,
+    pub property_type: PropertyType,
+    pub listing_type: ListingType,
+    pub address: String,
+    pub lga: String,
+    pub state: String,
+    pub landlord_id: Uuid,
+}
+
+/// The agent or lawyer who signed off, and when
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CertifiedVerifier {
+    pub id: Uuid,
+    pub name: String,
+    pub verified_at: DateTime<Utc>,
+}
+
+/// A title document as the lawyer reviewed it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CertifiedDocument {
+    pub kind: String, // certificate_of_occupancy, deed_of_agreement, survey_plan, building_plan_approval
+    pub sha256: String,
+}
@@ -0,0 +1,87 @@
+// models/emailmodels.rs
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, PartialEq)]
+#[sqlx(type_name = "email_outbox_status", rename_all = "snake_case")]
+pub enum EmailStatus {
+    Queued,
+    Sending,
+    Sent,
+    Failed,
+    Suppressed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, PartialEq)]
+#[sqlx(type_name = "email_suppression_reason", rename_all = "snake_case")]
+pub enum SuppressionReason {
+    Bounce,
+    Complaint,
+    Manual,
+}
+
+/// Placeholders holding one-time codes and links. They are cleared once a
+/// message is sent, fails for good or is suppressed.
+pub const SECRET_PLACEHOLDERS: &[&str] = &["{{otp_code}}", "{{rest_link}}", "{{verification_link}}"];
+
+#[derive(Debug, Serialize, Deserialize, Clone, sqlx::FromRow)]
+pub struct OutboxEmail {
+    pub id: Uuid,
+    pub idempotency_key: String,
+    pub to_email: String,
+    pub subject: String,
+    pub template_path: String,
+    pub placeholders: Json<Vec<(String, String)>>,
+    pub status: EmailStatus,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub locked_until: Option<DateTime<Utc>>,
+    pub provider_message_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub sent_at: Option<DateTime<Utc>>,
+}
+
+impl OutboxEmail {
+    /// 30s, 1m, 2m, 4m ... between attempts, capped at an hour
+    pub fn retry_delay(attempts: i32) -> Duration {
+        let seconds = 30i64 * 2i64.pow(attempts.clamp(1, 12) as u32 - 1);
+        Duration::seconds(seconds.min(3600))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, sqlx::FromRow)]
+pub struct EmailSuppression {
+    pub email: String,
+    pub user_id: Option<Uuid>,
+    pub reason: SuppressionReason,
+    pub details: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, sqlx::FromRow)]
+pub struct EmailEvent {
+    pub id: Uuid,
+    pub outbox_id: Option<Uuid>,
+    pub provider_message_id: Option<String>,
+    pub event_type: String,
+    pub email: Option<String>,
+    pub payload: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_is_capped() {
+        assert_eq!(OutboxEmail::retry_delay(1), Duration::seconds(30));
+        assert_eq!(OutboxEmail::retry_delay(3), Duration::seconds(120));
+        assert_eq!(OutboxEmail::retry_delay(10), Duration::seconds(3600));
+    }
+}
@@ -0,0 +1,80 @@
+use sqlx::types::chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "identity_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum IdentityKind {
+    Nin, // National Identification Number, from NIMC
+    Bvn, // Bank Verification Number
+    Cac, // Corporate Affairs Commission RC or BN number
+}
+
+impl IdentityKind {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            IdentityKind::Nin => "nin",
+            IdentityKind::Bvn => "bvn",
+            IdentityKind::Cac => "cac",
+        }
+    }
+
+    /// How the number is named to users
+    pub fn label(&self) -> &'static str {
+        match self {
+            IdentityKind::Nin => "NIN",
+            IdentityKind::Bvn => "BVN",
+            IdentityKind::Cac => "CAC registration",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "identity_outcome", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum IdentityOutcome {
+    Matched,  // The registry record is the person or business that gave us the number
+    Mismatch, // The number exists but belongs to someone else, as far as we can tell
+    NotFound, // The registry has no such number
+}
+
+/// What a registry holds for a number. People have names and a date of
+/// birth; businesses have `business_name`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct IdentityRecord {
+    pub first_name: Option<String>,
+    pub middle_name: Option<String>,
+    pub last_name: Option<String>,
+    pub date_of_birth: Option<NaiveDate>,
+    pub business_name: Option<String>,
+}
+
+impl IdentityRecord {
+    pub fn full_name(&self) -> String {
+        [&self.first_name, &self.middle_name, &self.last_name]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// One lookup of a number against a registry and how it compared with the
+/// account. The number itself is kept only as a hash and its last digits.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct IdentityCheck {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: IdentityKind,
+    #[serde(skip_serializing)]
+    pub number_hash: String,
+    pub number_last4: String,
+    pub provider: String,
+    pub outcome: IdentityOutcome,
+    pub name_score: Option<f64>,
+    pub dob_match: Option<bool>,
+    pub created_at: DateTime<Utc>,
+}
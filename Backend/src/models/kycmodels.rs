@@ -0,0 +1,223 @@
+use sqlx::types::chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::models::usermodel::VerificationStatus;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, PartialEq)]
+#[sqlx(type_name = "kyc_decision", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum KycDecision {
+    AutoApproved, // Cleared without a reviewer
+    ManualReview, // Waiting in the reviewer queue
+}
+
+/// What the automated pipeline found for one submission
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct KycCheck {
+    pub id: Uuid,
+    pub verification_id: Uuid,
+    pub document_quality: Option<f64>,
+    pub selfie_quality: Option<f64>,
+    pub document_faces: i32,
+    pub selfie_faces: i32,
+    pub face_similarity: Option<f64>,
+    pub liveness_score: Option<f64>,
+    pub flags: Vec<String>,
+    pub risk_score: f64,
+    pub decision: KycDecision,
+    pub error: Option<String>,
+    pub model_version: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// How much of KYC a user has completed. Each level needs every level below
+/// it, so a verified BVN without an approved ID still leaves a user at `Contact`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, PartialEq, Eq, PartialOrd, Ord)]
+#[sqlx(type_name = "kyc_level", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum KycLevel {
+    None,         // Signed up, nothing confirmed
+    Contact,      // Email or phone confirmed
+    GovernmentId, // NIN or ID document approved
+    Bvn,          // BVN matched to the account
+    Address,      // Proof of address approved
+}
+
+/// What the account has completed, read from users, naira_wallets and address_proofs
+#[derive(Debug, Default, Clone, Copy, FromRow)]
+pub struct KycFacts {
+    pub email_verified: bool,
+    pub phone_verified: bool,
+    pub identity_verified: bool,
+    pub bvn_verified: bool,
+    pub address_verified: bool,
+}
+
+/// What a level allows outside the wallet. Amounts are in naira; None means
+/// no cap from KYC.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct KycEntitlements {
+    pub max_job_budget: Option<f64>,
+    pub max_active_listings: Option<i64>,
+    pub can_withdraw: bool,
+}
+
+/// One step a user still has to take
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct KycRequirement {
+    pub step: &'static str,
+    pub description: &'static str,
+    pub endpoint: &'static str,
+}
+
+impl KycLevel {
+    pub const ALL: [KycLevel; 5] = [
+        KycLevel::None,
+        KycLevel::Contact,
+        KycLevel::GovernmentId,
+        KycLevel::Bvn,
+        KycLevel::Address,
+    ];
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            KycLevel::None => "none",
+            KycLevel::Contact => "contact",
+            KycLevel::GovernmentId => "government_id",
+            KycLevel::Bvn => "bvn",
+            KycLevel::Address => "address",
+        }
+    }
+
+    /// The highest level whose steps, and every step below it, are done
+    pub fn from_facts(facts: &KycFacts) -> KycLevel {
+        KycLevel::ALL
+            .into_iter()
+            .take_while(|level| level.is_met(facts))
+            .last()
+            .unwrap_or(KycLevel::None)
+    }
+
+    pub fn next(&self) -> Option<KycLevel> {
+        KycLevel::ALL.into_iter().find(|level| level > self)
+    }
+
+    /// Whether this level's own step is done
+    fn is_met(&self, facts: &KycFacts) -> bool {
+        match self {
+            KycLevel::None => true,
+            KycLevel::Contact => facts.email_verified || facts.phone_verified,
+            KycLevel::GovernmentId => facts.identity_verified,
+            KycLevel::Bvn => facts.bvn_verified,
+            KycLevel::Address => facts.address_verified,
+        }
+    }
+
+    fn requirement(&self) -> Option<KycRequirement> {
+        let (step, description, endpoint) = match self {
+            KycLevel::None => return None,
+            KycLevel::Contact => ("contact", "Confirm your email address from the link we sent you, or verify your phone number", "POST /api/verification/phone/send"),
+            KycLevel::GovernmentId => ("government_id", "Submit your NIN or an ID document with a selfie", "POST /api/verification/nin"),
+            KycLevel::Bvn => ("bvn", "Verify your BVN against your wallet", "POST /api/wallet/bvn/verify"),
+            KycLevel::Address => ("address", "Upload a recent utility bill or bank statement showing your address", "POST /api/verification/address"),
+        };
+
+        Some(KycRequirement { step, description, endpoint })
+    }
+
+    /// Every step still missing to reach `target`, in the order to take them
+    pub fn requirements_for(target: KycLevel, facts: &KycFacts) -> Vec<KycRequirement> {
+        KycLevel::ALL
+            .into_iter()
+            .filter(|level| *level <= target && !level.is_met(facts))
+            .filter_map(|level| level.requirement())
+            .collect()
+    }
+
+    pub fn entitlements(&self) -> KycEntitlements {
+        let (max_job_budget, max_active_listings, can_withdraw) = match self {
+            KycLevel::None => (Some(0.0), Some(0), false),
+            KycLevel::Contact => (Some(50_000.0), Some(1), false),
+            KycLevel::GovernmentId => (Some(500_000.0), Some(5), true),
+            KycLevel::Bvn => (Some(5_000_000.0), Some(20), true),
+            KycLevel::Address => (None, None, true),
+        };
+
+        KycEntitlements { max_job_budget, max_active_listings, can_withdraw }
+    }
+
+    /// Daily, monthly and per-transaction limits in kobo, for when
+    /// `wallet_limits` has no row for the level
+    pub fn default_wallet_limits(&self) -> (i64, i64, i64) {
+        match self {
+            KycLevel::None => (2_000_000, 5_000_000, 1_000_000),
+            KycLevel::Contact => (5_000_000, 30_000_000, 5_000_000),
+            KycLevel::GovernmentId => (50_000_000, 500_000_000, 20_000_000),
+            KycLevel::Bvn => (500_000_000, 5_000_000_000, 100_000_000),
+            KycLevel::Address => (1_000_000_000, 10_000_000_000, 500_000_000),
+        }
+    }
+}
+
+/// A utility bill or bank statement, reviewed by hand
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct AddressProof {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub document_url: String,
+    pub address: String,
+    pub status: VerificationStatus,
+    pub reviewed_by: Option<Uuid>,
+    pub review_notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels_need_every_step_below() {
+        let bvn_without_id = KycFacts { email_verified: true, bvn_verified: true, ..Default::default() };
+        assert_eq!(KycLevel::from_facts(&bvn_without_id), KycLevel::Contact);
+
+        let phone_only = KycFacts { phone_verified: true, ..Default::default() };
+        assert_eq!(KycLevel::from_facts(&phone_only), KycLevel::Contact);
+
+        let everything = KycFacts {
+            email_verified: true,
+            phone_verified: true,
+            identity_verified: true,
+            bvn_verified: true,
+            address_verified: true,
+        };
+        assert_eq!(KycLevel::from_facts(&everything), KycLevel::Address);
+        assert_eq!(KycLevel::from_facts(&KycFacts::default()), KycLevel::None);
+    }
+
+    #[test]
+    fn test_requirements_list_only_missing_steps() {
+        let facts = KycFacts { email_verified: true, bvn_verified: true, ..Default::default() };
+        let steps: Vec<&str> = KycLevel::requirements_for(KycLevel::Bvn, &facts)
+            .into_iter()
+            .map(|requirement| requirement.step)
+            .collect();
+
+        assert_eq!(steps, vec!["government_id"]);
+        assert_eq!(KycLevel::Address.next(), None);
+        assert_eq!(KycLevel::Contact.next(), Some(KycLevel::GovernmentId));
+    }
+
+    #[test]
+    fn test_entitlements_grow_with_level() {
+        for pair in KycLevel::ALL.windows(2) {
+            let (lower, higher) = (pair[0].default_wallet_limits(), pair[1].default_wallet_limits());
+            assert!(higher.0 > lower.0 && higher.1 > lower.1 && higher.2 > lower.2);
+        }
+        assert!(!KycLevel::Contact.entitlements().can_withdraw);
+        assert!(KycLevel::GovernmentId.entitlements().can_withdraw);
+    }
+}
@@ -0,0 +1,89 @@
+use sqlx::types::chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, PartialEq)]
+#[sqlx(type_name = "media_visibility", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum MediaVisibility {
+    Public,  // Anyone with the URL
+    Private, // Only through a signed, expiring URL
+}
+
+impl MediaVisibility {
+    pub fn to_str(&self) -> &str {
+        match self {
+            MediaVisibility::Public => "public",
+            MediaVisibility::Private => "private",
+        }
+    }
+}
+
+/// What an upload is for, which decides who may see it and how large it may be
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaPurpose {
+    KycDocument,
+    KycSelfie,
+    Portfolio,
+    JobProgress,
+    DisputeEvidence,
+    PropertyPhoto,
+    PropertyDocument,
+}
+
+impl MediaPurpose {
+    pub fn to_str(&self) -> &str {
+        match self {
+            MediaPurpose::KycDocument => "kyc_document",
+            MediaPurpose::KycSelfie => "kyc_selfie",
+            MediaPurpose::Portfolio => "portfolio",
+            MediaPurpose::JobProgress => "job_progress",
+            MediaPurpose::DisputeEvidence => "dispute_evidence",
+            MediaPurpose::PropertyPhoto => "property_photo",
+            MediaPurpose::PropertyDocument => "property_document",
+        }
+    }
+
+    pub fn visibility(&self) -> MediaVisibility {
+        match self {
+            MediaPurpose::Portfolio | MediaPurpose::PropertyPhoto => MediaVisibility::Public,
+            MediaPurpose::KycDocument
+            | MediaPurpose::KycSelfie
+            | MediaPurpose::JobProgress
+            | MediaPurpose::DisputeEvidence
+            | MediaPurpose::PropertyDocument => MediaVisibility::Private,
+        }
+    }
+
+    /// Upload cap in megabytes
+    pub fn max_size_mb(&self) -> usize {
+        match self {
+            MediaPurpose::PropertyDocument | MediaPurpose::DisputeEvidence => 20,
+            _ => 10,
+        }
+    }
+
+    /// Whether PDFs are accepted as well as images
+    pub fn accepts_pdf(&self) -> bool {
+        matches!(
+            self,
+            MediaPurpose::KycDocument | MediaPurpose::DisputeEvidence | MediaPurpose::PropertyDocument
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct MediaObject {
+    pub id: Uuid,
+    pub owner_id: Uuid,
+    pub purpose: String,
+    pub visibility: MediaVisibility,
+    pub storage_key: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub sha256: String,
+    pub original_filename: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
@@ -7,4 +7,17 @@ pub mod labourmodel;
 pub mod chatnodels;
 pub mod supportmodel;
 pub mod vendormodels;
-pub mod subscriptionmodels;
\ No newline at end of file
+pub mod subscriptionmodels;
+pub mod notificationmodels;
+pub mod emailmodels;
+pub mod viewingmodels;
+pub mod tenancymodels;
+pub mod offermodels;
+pub mod certificatemodels;
+pub mod mediamodels;
+pub mod sessionmodels;
+pub mod twofactormodels;
+pub mod permissionmodels;
+pub mod kycmodels;
+pub mod identitymodels;
+pub mod privacymodels;
\ No newline at end of file
@@ -0,0 +1,408 @@
+// models/notificationmodels.rs
+use chrono::{DateTime, Duration, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, PartialEq, Eq, Hash)]
+#[sqlx(type_name = "notification_channel", rename_all = "snake_case")]
+pub enum NotificationChannel {
+    InApp,
+    Email,
+    Sms,
+    #[sqlx(rename = "whatsapp")]
+    WhatsApp,
+    Push,
+}
+
+/// Groups the free-form `notification_type` strings written by
+/// `NotificationService` into the buckets users set preferences on.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, PartialEq, Eq, Hash)]
+#[sqlx(type_name = "notification_category", rename_all = "snake_case")]
+pub enum NotificationCategory {
+    JobAlerts,
+    Jobs,
+    Payments,
+    Disputes,
+    Messages,
+    Orders,
+    Properties,
+    Account,
+}
+
+impl NotificationCategory {
+    pub fn all() -> [NotificationCategory; 8] {
+        [
+            NotificationCategory::JobAlerts,
+            NotificationCategory::Jobs,
+            NotificationCategory::Payments,
+            NotificationCategory::Disputes,
+            NotificationCategory::Messages,
+            NotificationCategory::Orders,
+            NotificationCategory::Properties,
+            NotificationCategory::Account,
+        ]
+    }
+
+    pub fn from_notification_type(notification_type: &str) -> Self {
+        let t = notification_type.to_lowercase();
+
+        if t == "new_job" {
+            NotificationCategory::JobAlerts
+        } else if t.contains("dispute") {
+            NotificationCategory::Disputes
+        } else if t == "new_message" {
+            NotificationCategory::Messages
+        } else if t.starts_with("payment")
+            || t.starts_with("deposit")
+            || t.starts_with("withdrawal")
+            || t.starts_with("transfer")
+            || t.contains("wallet")
+        {
+            NotificationCategory::Payments
+        } else if t.starts_with("property") {
+            NotificationCategory::Properties
+        } else if t.contains("order")
+            || t.starts_with("delivery")
+            || t == "service_inquiry"
+        {
+            NotificationCategory::Orders
+        } else if t.starts_with("job")
+            || t.starts_with("contract")
+            || t.starts_with("progress")
+            || t.contains("worker assigned")
+        {
+            NotificationCategory::Jobs
+        } else {
+            NotificationCategory::Account
+        }
+    }
+
+    /// Channel defaults for users who have not saved a preference. High-volume
+    /// categories stay out of email and SMS unless the user opts in.
+    pub fn default_enabled(&self, channel: NotificationChannel) -> bool {
+        match channel {
+            NotificationChannel::InApp | NotificationChannel::Push => true,
+            NotificationChannel::Email => !matches!(
+                self,
+                NotificationCategory::JobAlerts | NotificationCategory::Messages
+            ),
+            NotificationChannel::Sms => matches!(self, NotificationCategory::Payments),
+            NotificationChannel::WhatsApp => false,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, PartialEq)]
+#[sqlx(type_name = "digest_frequency", rename_all = "snake_case")]
+pub enum DigestFrequency {
+    Off,
+    Daily,
+    Weekly,
+}
+
+impl DigestFrequency {
+    pub fn interval(&self) -> Option<Duration> {
+        match self {
+            DigestFrequency::Off => None,
+            DigestFrequency::Daily => Some(Duration::days(1)),
+            DigestFrequency::Weekly => Some(Duration::days(7)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, sqlx::FromRow)]
+pub struct NotificationPreference {
+    pub user_id: Uuid,
+    pub category: NotificationCategory,
+    pub channel: NotificationChannel,
+    pub enabled: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, sqlx::FromRow)]
+pub struct NotificationSettings {
+    pub user_id: Uuid,
+    pub quiet_hours_enabled: bool,
+    pub quiet_hours_start: i16,
+    pub quiet_hours_end: i16,
+    pub utc_offset_minutes: i32,
+    pub digest_frequency: DigestFrequency,
+    pub last_digest_sent_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl NotificationSettings {
+    /// Settings used when the user has never saved any (WAT, no quiet hours, no digest)
+    pub fn default_for(user_id: Uuid) -> Self {
+        Self {
+            user_id,
+            quiet_hours_enabled: false,
+            quiet_hours_start: 22,
+            quiet_hours_end: 7,
+            utc_offset_minutes: 60,
+            digest_frequency: DigestFrequency::Off,
+            last_digest_sent_at: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    pub fn in_quiet_hours(&self, now: DateTime<Utc>) -> bool {
+        if !self.quiet_hours_enabled || self.quiet_hours_start == self.quiet_hours_end {
+            return false;
+        }
+
+        let local = now + Duration::minutes(self.utc_offset_minutes as i64);
+        let hour = local.hour() as i16;
+
+        if self.quiet_hours_start < self.quiet_hours_end {
+            hour >= self.quiet_hours_start && hour < self.quiet_hours_end
+        } else {
+            // Window wraps past midnight, e.g. 22:00 - 07:00
+            hour >= self.quiet_hours_start || hour < self.quiet_hours_end
+        }
+    }
+
+    /// The next moment quiet hours end, used to hold SMS, WhatsApp and push until morning
+    pub fn quiet_hours_end_after(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let offset = Duration::minutes(self.utc_offset_minutes as i64);
+        let local = (now + offset).naive_utc();
+        let mut end = local
+            .date()
+            .and_hms_opt(self.quiet_hours_end as u32, 0, 0)
+            .unwrap_or(local);
+        if end <= local {
+            end += Duration::days(1);
+        }
+
+        DateTime::<Utc>::from_naive_utc_and_offset(end, Utc) - offset
+    }
+
+    /// Whether queued emails for this user should be flushed now
+    pub fn digest_due(&self, now: DateTime<Utc>) -> bool {
+        if self.in_quiet_hours(now) {
+            return false;
+        }
+
+        match (self.digest_frequency.interval(), self.last_digest_sent_at) {
+            (None, _) => true,
+            (Some(_), None) => true,
+            (Some(interval), Some(last_sent)) => now - last_sent >= interval,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeliveryDecision {
+    Send,
+    /// Hold until quiet hours end or the next digest goes out
+    Defer,
+    Skip,
+}
+
+/// A user's saved preferences plus settings, resolved once per dispatch
+#[derive(Debug, Clone)]
+pub struct NotificationPreferenceSet {
+    pub settings: NotificationSettings,
+    pub overrides: Vec<NotificationPreference>,
+}
+
+impl NotificationPreferenceSet {
+    pub fn is_enabled(&self, category: NotificationCategory, channel: NotificationChannel) -> bool {
+        self.overrides
+            .iter()
+            .find(|p| p.category == category && p.channel == channel)
+            .map(|p| p.enabled)
+            .unwrap_or_else(|| category.default_enabled(channel))
+    }
+
+    pub fn decide(
+        &self,
+        category: NotificationCategory,
+        channel: NotificationChannel,
+        now: DateTime<Utc>,
+    ) -> DeliveryDecision {
+        if !self.is_enabled(category, channel) {
+            return DeliveryDecision::Skip;
+        }
+
+        match channel {
+            NotificationChannel::InApp => DeliveryDecision::Send,
+            NotificationChannel::Email => {
+                if self.settings.digest_frequency != DigestFrequency::Off
+                    || self.settings.in_quiet_hours(now)
+                {
+                    DeliveryDecision::Defer
+                } else {
+                    DeliveryDecision::Send
+                }
+            }
+            NotificationChannel::Sms | NotificationChannel::WhatsApp | NotificationChannel::Push => {
+                if self.settings.in_quiet_hours(now) {
+                    DeliveryDecision::Defer
+                } else {
+                    DeliveryDecision::Send
+                }
+            }
+        }
+    }
+}
+
+/// An in-app notification row
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, sqlx::FromRow)]
+pub struct Notification {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub title: String,
+    pub message: String,
+    pub notification_type: String,
+    pub related_id: Option<Uuid>,
+    pub is_read: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, sqlx::FromRow)]
+pub struct DigestQueueItem {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub notification_type: String,
+    pub title: String,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+    pub sent_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, PartialEq)]
+#[sqlx(type_name = "notification_delivery_status", rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    Pending,
+    Sent,
+    Failed,
+    RateLimited,
+    Skipped,
+}
+
+/// One attempt record per (notification, channel) for everything except in-app,
+/// which is its own record in `notifications`
+#[derive(Debug, Serialize, Deserialize, Clone, sqlx::FromRow)]
+pub struct NotificationDelivery {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub channel: NotificationChannel,
+    pub template_key: String,
+    pub recipient: Option<String>,
+    pub payload: serde_json::Value,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub sent_at: Option<DateTime<Utc>>,
+}
+
+impl NotificationDelivery {
+    /// Exponential backoff between attempts: 2, 4, 8 ... minutes, capped at about 4 hours
+    pub fn retry_delay(attempts: i32) -> Duration {
+        Duration::minutes(2i64.pow(attempts.clamp(1, 8) as u32))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, sqlx::FromRow)]
+pub struct PushSubscription {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub token: String,
+    pub platform: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn settings_with_quiet_hours(start: i16, end: i16) -> NotificationSettings {
+        let mut settings = NotificationSettings::default_for(Uuid::new_v4());
+        settings.quiet_hours_enabled = true;
+        settings.quiet_hours_start = start;
+        settings.quiet_hours_end = end;
+        settings
+    }
+
+    #[test]
+    fn test_category_from_notification_type() {
+        assert_eq!(NotificationCategory::from_notification_type("new_job"), NotificationCategory::JobAlerts);
+        assert_eq!(NotificationCategory::from_notification_type("job_assigned"), NotificationCategory::Jobs);
+        assert_eq!(NotificationCategory::from_notification_type("Contract awaiting signature"), NotificationCategory::Jobs);
+        assert_eq!(NotificationCategory::from_notification_type("transfer_received"), NotificationCategory::Payments);
+        assert_eq!(NotificationCategory::from_notification_type("service_dispute"), NotificationCategory::Disputes);
+        assert_eq!(NotificationCategory::from_notification_type("order_shipped"), NotificationCategory::Orders);
+        assert_eq!(NotificationCategory::from_notification_type("new_message"), NotificationCategory::Messages);
+        assert_eq!(NotificationCategory::from_notification_type("property_live"), NotificationCategory::Properties);
+        assert_eq!(NotificationCategory::from_notification_type("verification declined"), NotificationCategory::Account);
+    }
+
+    #[test]
+    fn test_quiet_hours_wrap_midnight() {
+        // 22:00 - 07:00 WAT (UTC+1)
+        let settings = settings_with_quiet_hours(22, 7);
+        assert!(settings.in_quiet_hours(Utc.with_ymd_and_hms(2025, 1, 1, 22, 30, 0).unwrap()));
+        assert!(settings.in_quiet_hours(Utc.with_ymd_and_hms(2025, 1, 1, 5, 0, 0).unwrap()));
+        assert!(!settings.in_quiet_hours(Utc.with_ymd_and_hms(2025, 1, 1, 6, 0, 0).unwrap()));
+        assert!(!settings.in_quiet_hours(Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_decide_respects_overrides_and_quiet_hours() {
+        let user_id = Uuid::new_v4();
+        let set = NotificationPreferenceSet {
+            settings: settings_with_quiet_hours(22, 7),
+            overrides: vec![NotificationPreference {
+                user_id,
+                category: NotificationCategory::Jobs,
+                channel: NotificationChannel::Email,
+                enabled: false,
+                updated_at: Utc::now(),
+            }],
+        };
+        let midday = Utc.with_ymd_and_hms(2025, 1, 1, 11, 0, 0).unwrap();
+        let night = Utc.with_ymd_and_hms(2025, 1, 1, 23, 0, 0).unwrap();
+
+        assert_eq!(set.decide(NotificationCategory::Jobs, NotificationChannel::Email, midday), DeliveryDecision::Skip);
+        assert_eq!(set.decide(NotificationCategory::Payments, NotificationChannel::Email, midday), DeliveryDecision::Send);
+        assert_eq!(set.decide(NotificationCategory::Payments, NotificationChannel::Email, night), DeliveryDecision::Defer);
+        assert_eq!(set.decide(NotificationCategory::Payments, NotificationChannel::InApp, night), DeliveryDecision::Send);
+        assert_eq!(set.decide(NotificationCategory::Messages, NotificationChannel::Email, midday), DeliveryDecision::Skip);
+    }
+
+    #[test]
+    fn test_quiet_hours_end_after() {
+        // Quiet hours end at 07:00 WAT, which is 06:00 UTC
+        let settings = settings_with_quiet_hours(22, 7);
+        let late = Utc.with_ymd_and_hms(2025, 1, 1, 23, 0, 0).unwrap();
+        let early = Utc.with_ymd_and_hms(2025, 1, 2, 3, 0, 0).unwrap();
+
+        assert_eq!(settings.quiet_hours_end_after(late), Utc.with_ymd_and_hms(2025, 1, 2, 6, 0, 0).unwrap());
+        assert_eq!(settings.quiet_hours_end_after(early), Utc.with_ymd_and_hms(2025, 1, 2, 6, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off() {
+        assert_eq!(NotificationDelivery::retry_delay(1), Duration::minutes(2));
+        assert_eq!(NotificationDelivery::retry_delay(3), Duration::minutes(8));
+        assert_eq!(NotificationDelivery::retry_delay(20), Duration::minutes(256));
+    }
+
+    #[test]
+    fn test_digest_due() {
+        let mut settings = NotificationSettings::default_for(Uuid::new_v4());
+        settings.digest_frequency = DigestFrequency::Daily;
+        let now = Utc.with_ymd_and_hms(2025, 1, 2, 12, 0, 0).unwrap();
+
+        assert!(settings.digest_due(now));
+        settings.last_digest_sent_at = Some(now - Duration::hours(3));
+        assert!(!settings.digest_due(now));
+        settings.last_digest_sent_at = Some(now - Duration::hours(25));
+        assert!(settings.digest_due(now));
+    }
+}
@@ -0,0 +1,151 @@
+use sqlx::types::chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, PartialEq)]
+#[sqlx(type_name = "offer_status", rename_all = "lowercase")]
+pub enum OfferStatus {
+    Pending,    // Waiting on the landlord
+    Countered,  // Landlord proposed other terms, waiting on the buyer
+    Accepted,   // Earnest deposit held, transaction in progress
+    Completed,  // Transaction done, listing closed
+    Rejected,
+    Withdrawn,  // Buyer took the offer back
+    Expired,
+    Cancelled,  // Accepted deal fell through, deposit refunded
+}
+
+impl OfferStatus {
+    pub fn can_transition_to(&self, next: OfferStatus) -> bool {
+        use OfferStatus::*;
+
+        matches!(
+            (self, next),
+            (Pending, Countered)
+                | (Pending, Accepted)
+                | (Pending, Rejected)
+                | (Pending, Withdrawn)
+                | (Pending, Expired)
+                | (Countered, Accepted)
+                | (Countered, Rejected)
+                | (Countered, Withdrawn)
+                | (Countered, Expired)
+                | (Accepted, Completed)
+                | (Accepted, Cancelled)
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PropertyOffer {
+    pub id: Uuid,
+    pub property_id: Uuid,
+    pub buyer_id: Uuid,
+    pub landlord_id: Uuid,
+
+    // Buyer's terms, amounts in naira
+    pub amount: i64,
+    pub conditions: Option<String>,
+    pub expires_at: DateTime<Utc>,
+
+    pub status: OfferStatus,
+    pub counter_amount: Option<i64>,
+    pub counter_conditions: Option<String>,
+    pub response_note: Option<String>,
+
+    pub agreed_amount: Option<i64>,
+    pub earnest_deposit: Option<i64>,
+    pub deposit_hold_id: Option<Uuid>,
+
+    pub accepted_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl PropertyOffer {
+    /// The price on the table: the landlord's counter if there is one, otherwise the buyer's offer
+    pub fn standing_amount(&self) -> i64 {
+        match self.status {
+            OfferStatus::Countered => self.counter_amount.unwrap_or(self.amount),
+            _ => self.agreed_amount.unwrap_or(self.amount),
+        }
+    }
+
+    /// The party who has to respond next on an open offer
+    pub fn awaiting(&self) -> Option<Uuid> {
+        match self.status {
+            OfferStatus::Pending => Some(self.landlord_id),
+            OfferStatus::Countered => Some(self.buyer_id),
+            _ => None,
+        }
+    }
+}
+
+/// What the landlord sees of the competition on a listing
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct OfferBookSummary {
+    pub open_offers: i64,
+    pub highest_offer: Option<i64>,
+    pub lowest_offer: Option<i64>,
+    pub average_offer: Option<i64>,
+    pub accepted_offer_id: Option<Uuid>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_counter_is_answered_by_the_buyer() {
+        let mut offer = PropertyOffer {
+            id: Uuid::new_v4(),
+            property_id: Uuid::new_v4(),
+            buyer_id: Uuid::new_v4(),
+            landlord_id: Uuid::new_v4(),
+            amount: 40_000_000,
+            conditions: None,
+            expires_at: Utc::now() + Duration::days(7),
+            status: OfferStatus::Pending,
+            counter_amount: None,
+            counter_conditions: None,
+            response_note: None,
+            agreed_amount: None,
+            earnest_deposit: None,
+            deposit_hold_id: None,
+            accepted_at: None,
+            completed_at: None,
+            created_at: None,
+            updated_at: None,
+        };
+        assert_eq!(offer.awaiting(), Some(offer.landlord_id));
+        assert_eq!(offer.standing_amount(), 40_000_000);
+
+        offer.status = OfferStatus::Countered;
+        offer.counter_amount = Some(45_000_000);
+        assert_eq!(offer.awaiting(), Some(offer.buyer_id));
+        assert_eq!(offer.standing_amount(), 45_000_000);
+
+        offer.status = OfferStatus::Accepted;
+        offer.agreed_amount = Some(45_000_000);
+        assert_eq!(offer.awaiting(), None);
+        assert_eq!(offer.standing_amount(), 45_000_000);
+    }
+
+    #[test]
+    fn test_only_accepted_offers_complete() {
+        use OfferStatus::*;
+
+        assert!(Pending.can_transition_to(Accepted));
+        assert!(Countered.can_transition_to(Accepted));
+        assert!(Accepted.can_transition_to(Completed));
+        assert!(Accepted.can_transition_to(Cancelled));
+
+        assert!(!Pending.can_transition_to(Completed));
+        assert!(!Countered.can_transition_to(Countered));
+        assert!(!Accepted.can_transition_to(Withdrawn));
+        assert!(!Expired.can_transition_to(Accepted));
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::BTreeSet;
+
+use sqlx::types::chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::models::usermodel::UserRole;
+
+/// Named permissions. Which roles hold them lives in `role_permissions`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    PropertiesManageOwn,
+    PropertiesAgentReview,
+    PropertiesLawyerReview,
+    PropertiesVerificationAssignments,
+    PropertiesModerate,
+    MediaReadAll,
+    UsersList,
+    UsersTrustPoints,
+    UsersAdmin,
+    RolesManage,
+    RolesManagePrivileged,
+    VerificationReview,
+    SupportHandle,
+    EmailAdmin,
+    CacheManage,
+    VendorAdmin,
+}
+
+impl Permission {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Permission::PropertiesManageOwn => "properties.manage_own",
+            Permission::PropertiesAgentReview => "properties.agent_review",
+            Permission::PropertiesLawyerReview => "properties.lawyer_review",
+            Permission::PropertiesVerificationAssignments => "properties.verification_assignments",
+            Permission::PropertiesModerate => "properties.moderate",
+            Permission::MediaReadAll => "media.read_all",
+            Permission::UsersList => "users.list",
+            Permission::UsersTrustPoints => "users.trust_points",
+            Permission::UsersAdmin => "users.admin",
+            Permission::RolesManage => "roles.manage",
+            Permission::RolesManagePrivileged => "roles.manage_privileged",
+            Permission::VerificationReview => "verification.review",
+            Permission::SupportHandle => "support.handle",
+            Permission::EmailAdmin => "email.admin",
+            Permission::CacheManage => "cache.manage",
+            Permission::VendorAdmin => "vendor.admin",
+        }
+    }
+}
+
+/// Every role a user holds and everything those roles allow
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UserAccess {
+    pub roles: Vec<UserRole>,
+    pub permissions: BTreeSet<String>,
+}
+
+impl UserAccess {
+    pub fn has_role(&self, role: UserRole) -> bool {
+        self.roles.contains(&role)
+    }
+
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.permissions.contains(permission.to_str())
+    }
+}
+
+/// A role a user holds, and who gave it to them
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct UserRoleGrant {
+    pub user_id: Uuid,
+    pub role: UserRole,
+    pub granted_by: Option<Uuid>, // None for self-service roles and the role the user signed up with
+    pub granted_at: Option<DateTime<Utc>>,
+    pub is_primary: bool,
+}
+
+/// A role and the permissions it carries
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RolePermissions {
+    pub role: UserRole,
+    pub permissions: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_access_checks_named_permissions() {
+        let access = UserAccess {
+            roles: vec![UserRole::Worker, UserRole::Employer],
+            permissions: ["users.list".to_string()].into_iter().collect(),
+        };
+
+        assert!(access.has_role(UserRole::Employer));
+        assert!(!access.has_role(UserRole::Admin));
+        assert!(access.has_permission(Permission::UsersList));
+        assert!(!access.has_permission(Permission::RolesManage));
+    }
+}
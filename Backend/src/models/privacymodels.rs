@@ -0,0 +1,80 @@
+// models/privacymodels.rs
+use serde::Serialize;
+
+use crate::utils::currency::format_kobo_as_naira;
+
+/// What still ties an account to money or an open case. Any of these stops
+/// the account being deleted until it is settled.
+#[derive(Debug, Default, Clone, Serialize, sqlx::FromRow)]
+pub struct DeletionBlockers {
+    /// Job escrows the user funds or is paid from that have not been released or refunded
+    pub open_escrows: i64,
+    /// Marketplace orders, as buyer or vendor, that have been paid but not completed
+    pub open_orders: i64,
+    /// Kobo still in the user's wallet
+    pub wallet_balance: i64,
+    /// Job and order disputes the user raised or is named in that are not resolved
+    pub active_disputes: i64,
+    /// Tenancies, as tenant or landlord, still running or awaiting move-out
+    pub running_tenancies: i64,
+    /// Accepted property offers, as buyer or landlord, with an earnest deposit held
+    pub accepted_offers: i64,
+    /// Property viewings the user asked for or hosts that have not happened yet
+    pub upcoming_viewings: i64,
+}
+
+impl DeletionBlockers {
+    pub fn is_clear(&self) -> bool {
+        self.reasons().is_empty()
+    }
+
+    /// What the user has to settle first, one line each
+    pub fn reasons(&self) -> Vec<String> {
+        let mut reasons = Vec::new();
+
+        if self.open_escrows > 0 {
+            reasons.push(format!("{} job escrow(s) are still open", self.open_escrows));
+        }
+        if self.open_orders > 0 {
+            reasons.push(format!("{} marketplace order(s) are still in progress", self.open_orders));
+        }
+        if self.wallet_balance > 0 {
+            reasons.push(format!("your wallet still holds {}; withdraw it first", format_kobo_as_naira(self.wallet_balance)));
+        }
+        if self.active_disputes > 0 {
+            reasons.push(format!("{} dispute(s) are still open", self.active_disputes));
+        }
+        if self.running_tenancies > 0 {
+            reasons.push(format!("{} tenancy(ies) have not ended", self.running_tenancies));
+        }
+        if self.accepted_offers > 0 {
+            reasons.push(format!("{} accepted property offer(s) are not completed or cancelled", self.accepted_offers));
+        }
+        if self.upcoming_viewings > 0 {
+            reasons.push(format!("{} property viewing(s) are still booked", self.upcoming_viewings));
+        }
+
+        reasons
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blockers_explain_themselves() {
+        assert!(DeletionBlockers::default().is_clear());
+
+        let blockers = DeletionBlockers { open_escrows: 1, wallet_balance: 250_000, ..Default::default() };
+        let reasons = blockers.reasons();
+
+        assert!(!blockers.is_clear());
+        assert_eq!(reasons.len(), 2);
+        assert!(reasons[0].contains("escrow"));
+        assert!(reasons[1].contains("wallet"));
+
+        let property_deals = DeletionBlockers { running_tenancies: 1, accepted_offers: 1, upcoming_viewings: 2, ..Default::default() };
+        assert_eq!(property_deals.reasons().len(), 3);
+    }
+}
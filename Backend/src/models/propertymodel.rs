@@ -1,11 +1,11 @@
 use sqlx::types::chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, types::{Json, BigDecimal}};
+use sqlx::{FromRow, types::BigDecimal};
 use uuid::Uuid;
 use serde_json::Value as JsonValue;
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, PartialEq)]
-#[sqlx(type_name = "property_status", rename_all = "lowercase")]
+#[sqlx(type_name = "property_status", rename_all = "snake_case")]
 pub enum PropertyStatus {
     Draft, // Landlord is creating
     AwaitingAgent,  //Awaiting agent to verify
@@ -19,6 +19,37 @@ pub enum PropertyStatus {
     Rented,
 }
 
+impl PropertyStatus {
+    /// The verification pipeline runs Draft -> AwaitingAgent -> AgentVerified ->
+    /// AwaitingLawyer -> LawyerVerified -> Active, and either review can reject.
+    /// Every status change goes through this check.
+    pub fn can_transition_to(&self, next: PropertyStatus) -> bool {
+        use PropertyStatus::*;
+
+        matches!(
+            (self, next),
+            (Draft, AwaitingAgent)
+                | (AwaitingAgent, AgentVerified)
+                | (AwaitingAgent, Rejected)
+                | (AgentVerified, AwaitingLawyer)
+                | (AwaitingLawyer, LawyerVerified)
+                | (AwaitingLawyer, Rejected)
+                | (LawyerVerified, Active)
+                | (Active, Suspended)
+                | (Active, Sold)
+                | (Active, Rented)
+                | (Suspended, Active)
+                | (Rented, Active)
+                | (Rejected, Draft)
+        )
+    }
+
+    /// Listings anyone may see, signed in or not
+    pub fn is_public(&self) -> bool {
+        matches!(self, PropertyStatus::Active | PropertyStatus::Sold | PropertyStatus::Rented)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, PartialEq)]
 #[sqlx(type_name = "property_type", rename_all = "lowercase")]
 pub enum PropertyType {
@@ -34,6 +65,23 @@ pub enum PropertyType {
     Hotel,
 }
 
+impl PropertyType {
+    pub fn to_str(&self) -> &str {
+        match self {
+            PropertyType::Apartment => "apartment",
+            PropertyType::House => "house",
+            PropertyType::Duplex => "duplex",
+            PropertyType::Bungalow => "bungalow",
+            PropertyType::Commercial => "commercial",
+            PropertyType::Land => "land",
+            PropertyType::Warehouse => "warehouse",
+            PropertyType::Office => "office",
+            PropertyType::Shop => "shop",
+            PropertyType::Hotel => "hotel",
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, PartialEq)]
 #[sqlx(type_name = "listing_type", rename_all = "lowercase")]
 pub enum ListingType {
@@ -43,6 +91,17 @@ pub enum ListingType {
     Asset,
 }
 
+impl ListingType {
+    pub fn to_str(&self) -> &str {
+        match self {
+            ListingType::Sale => "sale",
+            ListingType::Rent => "rent",
+            ListingType::Lease => "lease",
+            ListingType::Asset => "asset",
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, PartialEq)]     //
 #[sqlx(type_name = "currency_type", rename_all = "lowercase")]
 pub enum CurrencyType {
@@ -86,6 +145,7 @@ pub struct Property {
     pub currency: CurrencyType,
     pub price_negotiable: Option<bool>,
     pub bidding_price: Option<i64>,
+    pub inspection_fee: Option<i64>,
 
     // Property Features - Use JsonValue instead of serde_json::Value
     pub amenities: Option<JsonValue>,
@@ -126,4 +186,269 @@ pub struct PropertyVerification {
     pub notes: String,
     pub verification_photos: Option<JsonValue>, // Use serde_json::Value
     pub created_at: Option<DateTime<Utc>>
-}
\ No newline at end of file
+}
+
+/// Asking prices of verified naira listings in one market. An empty `lga`
+/// means the figures cover the whole state.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct MarketSummary {
+    pub state: String,
+    pub lga: String,
+    pub property_type: PropertyType,
+    pub listing_type: ListingType,
+    pub listings: i64,
+    pub closed_listings: i64,
+    pub median_price: i64,
+    pub low_price: i64,  // 10th percentile
+    pub high_price: i64, // 90th percentile
+    pub median_price_per_sqm: Option<f64>,
+    pub median_days_on_market: Option<f64>, // Sold and rented listings only
+    pub refreshed_at: DateTime<Utc>,
+}
+
+/// Listings that came onto the market in one month
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct MarketTrendPoint {
+    pub month: chrono::NaiveDate,
+    pub listings: i64,
+    pub median_price: i64,
+    pub median_price_per_sqm: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ComparableListing {
+    pub property_id: Uuid,
+    pub title: String,
+    pub address: String,
+    pub lga: String,
+    pub status: PropertyStatus,
+    pub price: i64,
+    pub size_sqm: Option<f64>,
+    pub bedrooms: Option<i32>,
+    pub distance_km: Option<f64>, // None unless both listings have coordinates
+}
+
+/// A buyer's search, re-run as listings come onto the market. `filters` holds
+/// the same fields as the active listings search.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct SavedSearch {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub filters: JsonValue,
+    pub alerts_enabled: bool,
+    pub last_checked_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PropertyPriceChange {
+    pub id: Uuid,
+    pub property_id: Uuid,
+    pub old_price: i64,
+    pub new_price: i64,
+    pub changed_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// How one of a landlord's listings did over the dashboard window
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ListingPerformance {
+    pub property_id: Uuid,
+    pub title: String,
+    pub status: PropertyStatus,
+    pub price: i64,
+    pub views: i64,
+    pub unique_viewers: i64,
+    pub saves: i64,
+    pub total_saves: i64, // Everyone who has it saved now, whenever they saved it
+    pub viewing_requests: i64,
+    pub offers: i64,
+}
+
+/// A day of activity on a listing
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ListingActivity {
+    pub day: chrono::NaiveDate,
+    pub views: i64,
+    pub saves: i64,
+    pub viewing_requests: i64,
+    pub offers: i64,
+}
+
+/// Who reviews a listing at each stage of the pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VerifierRole {
+    Agent,
+    Lawyer,
+}
+
+impl VerifierRole {
+    /// As stored in `verifier_type` columns
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            VerifierRole::Agent => "agent",
+            VerifierRole::Lawyer => "lawyer",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, PartialEq)]
+#[sqlx(type_name = "verification_assignment_status", rename_all = "lowercase")]
+pub enum AssignmentStatus {
+    Active,    // The verifier holds the listing
+    Completed, // They submitted their review
+    Lapsed,    // The SLA ran out and the listing moved on
+    Released,  // An admin handed it to someone else
+}
+
+/// One verifier's turn with a listing
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct VerificationAssignment {
+    pub id: Uuid,
+    pub property_id: Uuid,
+    pub verifier_id: Uuid,
+    pub verifier_type: String,
+    pub assigned_by: Option<Uuid>,
+    pub status: AssignmentStatus,
+    pub assigned_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
+    pub reminded_at: Option<DateTime<Utc>>,
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+/// An agent or lawyer who could take a listing, with what they already hold
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct VerifierCandidate {
+    pub id: Uuid,
+    pub name: String,
+    pub lga: Option<String>,
+    pub nearest_landmark: Option<String>,
+    pub active_assignments: i64,
+}
+
+/// A verifier's record over the metrics window
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct VerifierMetrics {
+    pub verifier_id: Uuid,
+    pub name: String,
+    pub verifier_type: String,
+    pub active: i64,
+    pub completed: i64,
+    pub completed_on_time: i64,
+    pub lapsed: i64,
+    pub avg_turnaround_hours: Option<f64>,
+    pub median_turnaround_hours: Option<f64>,
+}
+
+/// A listing photo and its fingerprints. Both hashes are empty when the
+/// photo could not be fetched or decoded; `error` says why.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PropertyPhotoHash {
+    pub id: Uuid,
+    pub property_id: Uuid,
+    pub photo_url: String,
+    pub content_hash: Option<String>,
+    pub phash: Option<i64>,
+    pub error: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// A photo on one listing that looks like a photo on another, with the
+/// other listing's details for the reviewer
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PhotoMatch {
+    pub photo_url: String,
+    pub matched_property_id: Uuid,
+    pub matched_photo_url: String,
+    pub matched_title: String,
+    pub matched_status: PropertyStatus,
+    pub matched_landlord_id: Uuid,
+    pub distance: i32,
+    pub similarity: f64,
+    pub different_landlord: bool,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// What the reviewing agent sees about a listing's photos
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PhotoSimilarityReport {
+    pub property_id: Uuid,
+    pub photos_indexed: i64,
+    pub unreadable_photos: i64,
+    /// Some photo matches a listing by another landlord
+    pub suspicious: bool,
+    pub matches: Vec<PhotoMatch>,
+}
+
+/// A listing photo that looks like a searched image
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PhotoSearchHit {
+    pub property_id: Uuid,
+    pub photo_url: String,
+    pub title: String,
+    pub status: PropertyStatus,
+    pub landlord_id: Uuid,
+    pub distance: i32,
+    pub similarity: f64,
+}
+
+/// A listing with photos matching another landlord's listing
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct FlaggedListing {
+    pub property_id: Uuid,
+    pub title: String,
+    pub status: PropertyStatus,
+    pub landlord_id: Uuid,
+    pub agent_id: Option<Uuid>,
+    pub matched_listings: i64,
+    pub closest_distance: i32,
+    pub first_flagged_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verification_pipeline_cannot_skip_a_stage() {
+        use PropertyStatus::*;
+
+        assert!(Draft.can_transition_to(AwaitingAgent));
+        assert!(AwaitingAgent.can_transition_to(AgentVerified));
+        assert!(AgentVerified.can_transition_to(AwaitingLawyer));
+        assert!(AwaitingLawyer.can_transition_to(LawyerVerified));
+        assert!(LawyerVerified.can_transition_to(Active));
+
+        assert!(!Draft.can_transition_to(Active));
+        assert!(!AwaitingAgent.can_transition_to(AwaitingLawyer));
+        assert!(!AgentVerified.can_transition_to(Active));
+        assert!(!AwaitingLawyer.can_transition_to(Active));
+        assert!(!AgentVerified.can_transition_to(Rejected));
+        assert!(!Rejected.can_transition_to(Active));
+        assert!(!Sold.can_transition_to(Active));
+        assert!(!Active.can_transition_to(Active));
+    }
+
+    #[test]
+    fn test_only_listed_properties_are_public() {
+        let public: Vec<PropertyStatus> = [
+            PropertyStatus::Draft,
+            PropertyStatus::AwaitingAgent,
+            PropertyStatus::AgentVerified,
+            PropertyStatus::AwaitingLawyer,
+            PropertyStatus::LawyerVerified,
+            PropertyStatus::Active,
+            PropertyStatus::Suspended,
+            PropertyStatus::Rejected,
+            PropertyStatus::Sold,
+            PropertyStatus::Rented,
+        ]
+        .into_iter()
+        .filter(PropertyStatus::is_public)
+        .collect();
+
+        assert_eq!(public, vec![PropertyStatus::Active, PropertyStatus::Sold, PropertyStatus::Rented]);
+    }
+}
@@ -0,0 +1,124 @@
+use sqlx::types::chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// One signed-in device
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct UserSession {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub device_name: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub revoked_reason: Option<String>,
+    pub stepped_up_at: Option<DateTime<Utc>>, // Last second-factor check on this session
+}
+
+impl UserSession {
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none() && self.expires_at > Utc::now()
+    }
+}
+
+/// Where a request came from, as recorded against its session
+#[derive(Debug, Clone, Default)]
+pub struct SessionClient {
+    pub device_name: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub device_id: Option<String>, // Stable per browser or app install
+    pub country: Option<String>,   // ISO code from the edge proxy
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "login_outcome", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum LoginOutcome {
+    Success,
+    Failed,  // Wrong password or unknown account
+    Blocked, // Refused while locked out
+}
+
+/// One sign-in attempt, kept for the user's login history
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct LoginEvent {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    #[serde(skip_serializing)]
+    pub identifier: String,
+    pub outcome: LoginOutcome,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub device_name: Option<String>,
+    #[serde(skip_serializing)]
+    pub device_id: Option<String>,
+    pub country: Option<String>,
+    pub new_device: bool,
+    pub new_location: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// What an account's earlier successful sign-ins say about a new one
+#[derive(Debug, Default, Clone, Copy, FromRow)]
+pub struct LoginFamiliarity {
+    pub successful_logins: i64,
+    pub known_device: bool,
+    pub known_country: bool,
+    pub any_country: bool, // Whether earlier sign-ins had a country at all
+}
+
+impl LoginFamiliarity {
+    /// A first sign-in is nothing to warn about
+    pub fn is_new_device(&self, client: &SessionClient) -> bool {
+        self.successful_logins > 0 && client.device_id.is_some() && !self.known_device
+    }
+
+    pub fn is_new_location(&self, client: &SessionClient) -> bool {
+        self.successful_logins > 0 && self.any_country && client.country.is_some() && !self.known_country
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct RefreshToken {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    pub token_hash: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_accounts_with_history_get_new_device_or_location_warnings() {
+        let client = SessionClient {
+            device_id: Some("b7d3".to_string()),
+            country: Some("GB".to_string()),
+            ..Default::default()
+        };
+
+        let first_login = LoginFamiliarity::default();
+        assert!(!first_login.is_new_device(&client));
+        assert!(!first_login.is_new_location(&client));
+
+        let seen_elsewhere = LoginFamiliarity { successful_logins: 4, any_country: true, ..Default::default() };
+        assert!(seen_elsewhere.is_new_device(&client));
+        assert!(seen_elsewhere.is_new_location(&client));
+
+        let familiar = LoginFamiliarity { successful_logins: 4, known_device: true, known_country: true, any_country: true };
+        assert!(!familiar.is_new_device(&client));
+        assert!(!familiar.is_new_location(&client));
+
+        // Countries only compare once the proxy has been sending them
+        let no_countries_yet = LoginFamiliarity { successful_logins: 4, known_device: true, ..Default::default() };
+        assert!(!no_countries_yet.is_new_location(&client));
+    }
+}
@@ -0,0 +1,236 @@
+use sqlx::types::chrono::{DateTime, Utc};
+use chrono::{Duration, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, PartialEq)]
+#[sqlx(type_name = "tenancy_status", rename_all = "snake_case")]
+pub enum TenancyStatus {
+    Offered,    // Tenant made an offer, waiting on the landlord
+    Declined,
+    Withdrawn,  // Tenant took the offer back
+    Active,     // Agreement running, rent being collected
+    MoveOut,    // Notice given, waiting on the move-out inspection
+    Ended,      // Inspection done, deposit settled
+}
+
+impl TenancyStatus {
+    pub fn can_transition_to(&self, next: TenancyStatus) -> bool {
+        use TenancyStatus::*;
+
+        matches!(
+            (self, next),
+            (Offered, Active)
+                | (Offered, Declined)
+                | (Offered, Withdrawn)
+                | (Active, MoveOut)
+                | (MoveOut, Ended)
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, PartialEq)]
+#[sqlx(type_name = "rent_frequency", rename_all = "lowercase")]
+pub enum RentFrequency {
+    Monthly,
+    Quarterly,
+    Yearly,
+}
+
+impl RentFrequency {
+    pub fn months(&self) -> u32 {
+        match self {
+            RentFrequency::Monthly => 1,
+            RentFrequency::Quarterly => 3,
+            RentFrequency::Yearly => 12,
+        }
+    }
+
+    pub fn to_str(&self) -> &str {
+        match self {
+            RentFrequency::Monthly => "monthly",
+            RentFrequency::Quarterly => "quarterly",
+            RentFrequency::Yearly => "yearly",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, PartialEq)]
+#[sqlx(type_name = "rent_payment_status", rename_all = "lowercase")]
+pub enum RentPaymentStatus {
+    Scheduled,
+    Paid,
+    Overdue,    // Past the grace period, late fee applied
+    Cancelled,  // Tenancy ended before it fell due
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Tenancy {
+    pub id: Uuid,
+    pub property_id: Uuid,
+    pub tenant_id: Uuid,
+    pub landlord_id: Uuid,
+
+    // Terms, amounts in naira
+    pub rent_amount: i64,
+    pub rent_frequency: RentFrequency,
+    pub caution_deposit: i64,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub grace_period_days: i32,
+    pub late_fee_percent: i32,
+
+    pub status: TenancyStatus,
+    pub message: Option<String>,
+    pub response_note: Option<String>,
+
+    // Move-out
+    pub deposit_hold_id: Option<Uuid>,
+    pub deposit_deduction: Option<i64>,
+    pub inspection_notes: Option<String>,
+
+    pub accepted_at: Option<DateTime<Utc>>,
+    pub move_out_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl Tenancy {
+    /// Rent falls due at the start of every period until the agreement ends
+    pub fn rent_schedule(&self) -> Vec<NaiveDate> {
+        rent_schedule(self.start_date, self.end_date, self.rent_frequency)
+    }
+
+    /// The late fee owed on a rent payment as of `today`; nothing inside the grace period
+    pub fn late_fee_on(&self, payment: &RentPayment, today: NaiveDate) -> i64 {
+        let grace_ends = payment.due_date + Duration::days(self.grace_period_days as i64);
+        if today > grace_ends {
+            payment.amount * self.late_fee_percent as i64 / 100
+        } else {
+            0
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct RentPayment {
+    pub id: Uuid,
+    pub tenancy_id: Uuid,
+    pub due_date: NaiveDate,
+    pub amount: i64,
+    pub late_fee: i64,
+    pub status: RentPaymentStatus,
+    pub attempts: i32,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub paid_at: Option<DateTime<Utc>>,
+    pub receipt_number: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl RentPayment {
+    pub fn total_due(&self) -> i64 {
+        self.amount + self.late_fee
+    }
+}
+
+pub fn rent_schedule(start: NaiveDate, end: NaiveDate, frequency: RentFrequency) -> Vec<NaiveDate> {
+    (0..)
+        .map_while(|period| start.checked_add_months(Months::new(period * frequency.months())))
+        .take_while(|due| *due < end)
+        .collect()
+}
+
+pub fn receipt_number(payment_id: Uuid, paid_on: NaiveDate) -> String {
+    format!(
+        "RR-{}-{}",
+        paid_on.format("%Y%m%d"),
+        payment_id.simple().to_string()[..8].to_uppercase()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_rent_schedule_covers_each_period_of_the_agreement() {
+        assert_eq!(
+            rent_schedule(date(2025, 1, 31), date(2025, 5, 1), RentFrequency::Monthly),
+            vec![date(2025, 1, 31), date(2025, 2, 28), date(2025, 3, 31), date(2025, 4, 30)]
+        );
+        assert_eq!(
+            rent_schedule(date(2025, 3, 1), date(2027, 3, 1), RentFrequency::Yearly),
+            vec![date(2025, 3, 1), date(2026, 3, 1)]
+        );
+        assert_eq!(
+            rent_schedule(date(2025, 1, 1), date(2025, 8, 1), RentFrequency::Quarterly),
+            vec![date(2025, 1, 1), date(2025, 4, 1), date(2025, 7, 1)]
+        );
+    }
+
+    #[test]
+    fn test_late_fee_only_after_grace_period() {
+        let payment = RentPayment {
+            id: Uuid::new_v4(),
+            tenancy_id: Uuid::new_v4(),
+            due_date: date(2025, 6, 1),
+            amount: 200_000,
+            late_fee: 0,
+            status: RentPaymentStatus::Scheduled,
+            attempts: 0,
+            last_attempt_at: None,
+            paid_at: None,
+            receipt_number: None,
+            created_at: None,
+            updated_at: None,
+        };
+        let tenancy = Tenancy {
+            id: payment.tenancy_id,
+            property_id: Uuid::new_v4(),
+            tenant_id: Uuid::new_v4(),
+            landlord_id: Uuid::new_v4(),
+            rent_amount: 200_000,
+            rent_frequency: RentFrequency::Monthly,
+            caution_deposit: 0,
+            start_date: date(2025, 6, 1),
+            end_date: date(2026, 6, 1),
+            grace_period_days: 7,
+            late_fee_percent: 5,
+            status: TenancyStatus::Active,
+            message: None,
+            response_note: None,
+            deposit_hold_id: None,
+            deposit_deduction: None,
+            inspection_notes: None,
+            accepted_at: None,
+            move_out_at: None,
+            ended_at: None,
+            created_at: None,
+            updated_at: None,
+        };
+
+        assert_eq!(tenancy.late_fee_on(&payment, date(2025, 6, 1)), 0);
+        assert_eq!(tenancy.late_fee_on(&payment, date(2025, 6, 8)), 0);
+        assert_eq!(tenancy.late_fee_on(&payment, date(2025, 6, 9)), 10_000);
+    }
+
+    #[test]
+    fn test_tenancy_cannot_end_without_move_out() {
+        use TenancyStatus::*;
+
+        assert!(Offered.can_transition_to(Active));
+        assert!(Active.can_transition_to(MoveOut));
+        assert!(MoveOut.can_transition_to(Ended));
+
+        assert!(!Active.can_transition_to(Ended));
+        assert!(!Declined.can_transition_to(Active));
+        assert!(!Ended.can_transition_to(Active));
+    }
+}
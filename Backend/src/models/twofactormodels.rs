@@ -0,0 +1,29 @@
+use sqlx::types::chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// A user's authenticator app enrolment
+#[derive(Debug, Deserialize, FromRow, Clone)]
+pub struct UserTwoFactor {
+    pub user_id: Uuid,
+    pub secret_encrypted: String,
+    pub enabled_at: Option<DateTime<Utc>>, // None until the first code is confirmed
+    pub last_used_step: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl UserTwoFactor {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled_at.is_some()
+    }
+}
+
+/// Two-factor state as shown to the user
+#[derive(Debug, Serialize)]
+pub struct TwoFactorStatus {
+    pub enabled: bool,
+    pub enabled_at: Option<DateTime<Utc>>,
+    pub recovery_codes_remaining: i64,
+}
@@ -87,6 +87,81 @@ impl VerificationStatus {
     }
 }
 
+/// Language used for emails and notifications. Nigerian Pidgin is `pcm`.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, sqlx::Type, PartialEq, Eq, Hash, Default)]
+#[sqlx(type_name = "user_locale", rename_all = "snake_case")]
+pub enum Locale {
+    #[default]
+    En,
+    Pcm,
+    Yo,
+    Ha,
+    Ig,
+}
+
+impl Locale {
+    pub fn all() -> [Locale; 5] {
+        [Locale::En, Locale::Pcm, Locale::Yo, Locale::Ha, Locale::Ig]
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Pcm => "pcm",
+            Locale::Yo => "yo",
+            Locale::Ha => "ha",
+            Locale::Ig => "ig",
+        }
+    }
+
+    /// Where a missing translation is looked up next. Every language falls
+    /// back to English; English has nowhere further to go.
+    pub fn fallback(&self) -> Option<Locale> {
+        match self {
+            Locale::En => None,
+            _ => Some(Locale::En),
+        }
+    }
+
+    /// Parse a language tag such as `yo`, `yo-NG`, `ha_NG` or `pidgin`
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let primary = tag
+            .trim()
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+
+        match primary.as_str() {
+            "en" | "eng" | "english" => Some(Locale::En),
+            "pcm" | "pidgin" => Some(Locale::Pcm),
+            "yo" | "yor" | "yoruba" => Some(Locale::Yo),
+            "ha" | "hau" | "hausa" => Some(Locale::Ha),
+            "ig" | "ibo" | "igbo" => Some(Locale::Ig),
+            _ => None,
+        }
+    }
+
+    /// First supported language in an `Accept-Language` header, by q-value
+    pub fn from_accept_language(header: &str) -> Option<Locale> {
+        let mut candidates: Vec<(f32, Locale)> = header
+            .split(',')
+            .filter_map(|part| {
+                let mut pieces = part.split(';');
+                let locale = Locale::from_tag(pieces.next()?)?;
+                let quality = pieces
+                    .find_map(|piece| piece.trim().strip_prefix("q="))
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .unwrap_or(1.0);
+                Some((quality, locale))
+            })
+            .collect();
+
+        candidates.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        candidates.first().map(|(_, locale)| *locale)
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, sqlx::FromRow, sqlx::Type, Clone)]
 pub struct User {
     pub id: uuid::Uuid, 
@@ -145,6 +220,8 @@ pub struct User {
     
     pub token_expires_at: Option<DateTime<Utc>>,
 
+    pub locale: Locale,
+
     #[serde(rename = "createdAt")]
     pub created_at: DateTime<Utc>,
 
@@ -83,6 +83,31 @@ pub enum OtpPurpose {
     Transaction,
     VerificationUpdate,
     SensitiveAction,
+    PhoneVerification,
+}
+
+/// Where one-time codes go. SMS needs a verified phone number.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default, sqlx::Type)]
+#[sqlx(type_name = "otp_channel", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum OtpChannel {
+    #[default]
+    Email,
+    Sms,
+}
+
+/// A user's phone number and where they want their codes
+#[derive(Debug, Default, Serialize, sqlx::FromRow)]
+pub struct PhoneStatus {
+    pub phone_number: Option<String>,
+    pub phone_verified_at: Option<DateTime<Utc>>,
+    pub otp_channel: OtpChannel,
+}
+
+impl PhoneStatus {
+    pub fn verified_phone(&self) -> Option<&str> {
+        self.phone_verified_at.and(self.phone_number.as_deref())
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Validate)]
@@ -101,6 +126,8 @@ pub struct OtpRecord {
     pub id: Uuid,
     pub user_id: Uuid,
     pub email: String,
+    /// Set when the code went out by SMS
+    pub phone_number: Option<String>,
     pub otp_code: String,
     pub purpose: OtpPurpose,
     pub expires_at: Option<DateTime<Utc>>,
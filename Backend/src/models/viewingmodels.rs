@@ -0,0 +1,122 @@
+use sqlx::types::chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use serde_json::Value as JsonValue;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, PartialEq)]
+#[sqlx(type_name = "viewing_status", rename_all = "snake_case")]
+pub enum ViewingStatus {
+    Requested,  // Waiting on the landlord or agent
+    Confirmed,  // A slot was accepted
+    Declined,
+    Cancelled,
+    Completed,  // The viewing took place
+    NoShow,     // One side did not turn up
+}
+
+impl ViewingStatus {
+    /// Requested -> Confirmed -> Completed is the happy path. Either side can
+    /// back out before the viewing, and only a confirmed viewing can end in a
+    /// no-show.
+    pub fn can_transition_to(&self, next: ViewingStatus) -> bool {
+        use ViewingStatus::*;
+
+        matches!(
+            (self, next),
+            (Requested, Confirmed)
+                | (Requested, Declined)
+                | (Requested, Cancelled)
+                | (Confirmed, Cancelled)
+                | (Confirmed, Completed)
+                | (Confirmed, NoShow)
+        )
+    }
+
+    /// Still holding a slot and, if there is one, the inspection fee
+    pub fn is_open(&self) -> bool {
+        matches!(self, ViewingStatus::Requested | ViewingStatus::Confirmed)
+    }
+}
+
+/// Who failed to turn up to a viewing
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum NoShowParty {
+    Requester,
+    Host,
+}
+
+impl NoShowParty {
+    pub fn to_str(&self) -> &str {
+        match self {
+            NoShowParty::Requester => "requester",
+            NoShowParty::Host => "host",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PropertyViewing {
+    pub id: Uuid,
+    pub property_id: Uuid,
+    pub requester_id: Uuid,
+    pub host_id: Option<Uuid>,
+
+    pub proposed_slots: JsonValue,
+    pub scheduled_at: Option<DateTime<Utc>>,
+    pub status: ViewingStatus,
+    pub message: Option<String>,
+    pub response_note: Option<String>,
+
+    // Inspection fee in naira, held from the requester until the viewing
+    pub inspection_fee: i64,
+    pub fee_hold_id: Option<Uuid>,
+    pub no_show_party: Option<String>,
+
+    pub reminder_sent_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub responded_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl PropertyViewing {
+    pub fn proposed_slots(&self) -> Vec<DateTime<Utc>> {
+        serde_json::from_value(self.proposed_slots.clone()).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_viewing_must_be_confirmed_before_it_completes() {
+        use ViewingStatus::*;
+
+        assert!(Requested.can_transition_to(Confirmed));
+        assert!(Confirmed.can_transition_to(Completed));
+        assert!(Confirmed.can_transition_to(NoShow));
+        assert!(Confirmed.can_transition_to(Cancelled));
+
+        assert!(!Requested.can_transition_to(Completed));
+        assert!(!Requested.can_transition_to(NoShow));
+        assert!(!Confirmed.can_transition_to(Declined));
+        assert!(!Declined.can_transition_to(Confirmed));
+        assert!(!Completed.can_transition_to(NoShow));
+        assert!(!NoShow.can_transition_to(Completed));
+    }
+
+    #[test]
+    fn test_only_pending_viewings_are_open() {
+        use ViewingStatus::*;
+
+        let open: Vec<ViewingStatus> = [Requested, Confirmed, Declined, Cancelled, Completed, NoShow]
+            .into_iter()
+            .filter(ViewingStatus::is_open)
+            .collect();
+
+        assert_eq!(open, vec![Requested, Confirmed]);
+    }
+}
@@ -4,13 +4,7 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use validator::Validate;
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, PartialEq)]
-#[sqlx(type_name = "user_tier", rename_all = "lowercase")]
-pub enum UserTier {
-    Basic,
-    Verified,
-    Premium,
-}
+use crate::models::kycmodels::KycLevel;
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, sqlx::Type, PartialEq)]
 #[sqlx(type_name = "transaction_type", rename_all = "lowercase")]
@@ -186,7 +180,7 @@ pub struct TransactionFee {
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct WalletLimit {
     pub id: Uuid,
-    pub user_tier: UserTier, // basic, verified, premium
+    pub kyc_level: KycLevel,
     pub transaction_type: TransactionType,
     pub daily_limit: i64,
     pub monthly_limit: i64,
@@ -10,19 +10,30 @@ use crate::{
     handler::{
         auth::auth_handler, 
         cache_handler::cache_handler, 
+        certificates::certificate_handler,
         chat::chat_handler, 
+        email_handler::{email_admin_handler, email_webhook_handler},
         feed::get_feed,
         google_oauth::oauth_handler, 
+        locations::location_handler,
+        media::{media_handler, public_media_handler},
         labour::{
             get_job_details, get_public_worker_portfolio, get_public_worker_profile, get_worker_details, search_jobs, search_workers
         }, 
+        properties::{property_handler, public_property_handler},
         naira_wallet::{
             flutterwave_webhook, 
             paystack_webhook
         }, 
         users::users_handler, 
         vendor::vendor_handler, 
-        verification::verification_handler
+        offers::offer_handler,
+        saved::saved_handler,
+        tenancies::tenancy_handler,
+        two_factor::two_factor_handler,
+        privacy::privacy_handler,
+        verification::verification_handler,
+        viewings::viewing_handler,
     }, 
     middleware::main_middleware::auth
 };
@@ -57,6 +68,7 @@ pub fn create_router(app_state: Arc<AppState>) -> Router {
         .route("/bank-accounts/:account_id/verify", post(crate::handler::naira_wallet::verify_bank_account))
         .route("/bank-accounts/:account_id/primary", put(crate::handler::naira_wallet::set_primary_account))
         .route("/bank-accounts/resolve", post(crate::handler::naira_wallet::resolve_account_number))
+        .route("/bvn/verify", post(crate::handler::naira_wallet::verify_bvn))
         .layer(middleware::from_fn(auth));
 
     // Public wallet routes (no auth required but secure)
@@ -129,17 +141,47 @@ let protected_labour_routes = Router::new()
     let notification_routes = crate::handler::notification_handler::notification_routes()
         .layer(middleware::from_fn(auth));
 
+    // Property routes: browsing is public, listing and verification require auth
+    let property_routes = Router::new()
+        .merge(public_property_handler())
+        .merge(property_handler().layer(middleware::from_fn(auth)));
+
+    // Viewing appointment routes (protected)
+    let viewing_routes = viewing_handler()
+        .layer(middleware::from_fn(auth));
+
+    // Tenancy and rent collection routes (protected)
+    let tenancy_routes = tenancy_handler()
+        .layer(middleware::from_fn(auth));
+
+    // Offer book routes (protected)
+    let offer_routes = offer_handler()
+        .layer(middleware::from_fn(auth));
+
+    // Saved properties and saved search routes (protected)
+    let saved_routes = saved_handler()
+        .layer(middleware::from_fn(auth));
+
+    // Media routes: files are served publicly (private ones need a signed URL), uploads require auth
+    let media_routes = Router::new()
+        .merge(public_media_handler())
+        .merge(media_handler().layer(middleware::from_fn(auth)));
+
     // Verification routes (protected)
     let verification_routes = verification_handler()
         .layer(middleware::from_fn(auth));
 
-    // Cache monitoring routes (protected - admin only)
-    let cache_routes = cache_handler()
+    // Cache monitoring and email outbox routes (protected - admin only)
+    let admin_routes = Router::new()
+        .merge(cache_handler())
+        .merge(email_admin_handler())
         .layer(middleware::from_fn(auth));
 
     let api_route = Router::new()
         .nest("/auth", auth_handler())
         .nest("/oauth", oauth_handler())
+        .nest("/2fa", two_factor_handler().layer(middleware::from_fn(auth)))
+        .nest("/privacy", privacy_handler().layer(middleware::from_fn(auth)))
         .nest("/verification", verification_routes)
         .nest(
             "/users", 
@@ -149,9 +191,18 @@ let protected_labour_routes = Router::new()
         .nest("/api/vendor", vendor_handler())
         .nest("/wallet", wallet_routes)
         .nest("/labour", labour_routes)
+        .nest("/properties", property_routes)
+        .nest("/viewings", viewing_routes)
+        .nest("/tenancies", tenancy_routes)
+        .nest("/offers", offer_routes)
+        .nest("/saved", saved_routes)
+        .nest("/locations", location_handler())
+        .nest("/verify", certificate_handler())
+        .nest("/media", media_routes)
         .nest("/chat", chat_routes)
         .nest("/notifications", notification_routes)
-        .nest("/admin", cache_routes)  
+        .nest("/admin", admin_routes)  
+        .nest("/webhooks/email", email_webhook_handler())
         .nest("/debug", Router::new().route("/reco/push", post(crate::handler::debug::push_reco_event)))
         .layer(Extension(app_state.clone()))  // Move Extension layer BEFORE middleware
         .layer(TraceLayer::new_for_http())
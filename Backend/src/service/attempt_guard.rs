@@ -0,0 +1,215 @@
+// service/attempt_guard.rs
+//! Failed-attempt counters for anything that can be guessed: passwords,
+//! transaction PINs and one-time codes. Counts live in Redis so every
+//! instance sees the same numbers. A few failures are free; after that each
+//! one makes the account wait longer before its next try, until it is locked
+//! out for a while. IPs get a much higher ceiling, as many users can share
+//! one. Without Redis nothing is counted.
+use std::sync::Arc;
+
+use redis::{aio::ConnectionManager, RedisResult};
+
+use crate::{db::db::DBClient, service::error::ServiceError};
+
+/// The longest wait between attempts before lockout
+const MAX_DELAY_SECONDS: i64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttemptKind {
+    Login,
+    TransactionPin,
+    Otp,
+}
+
+struct AttemptPolicy {
+    /// Failures allowed before any wait
+    free: i64,
+    /// Failures that lock the account for `lockout_seconds`
+    lockout_after: i64,
+    lockout_seconds: i64,
+    /// Failures from one IP that lock it for `window_seconds`
+    ip_lockout_after: i64,
+    /// How long failures are remembered, counted from the first
+    window_seconds: i64,
+}
+
+impl AttemptKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            AttemptKind::Login => "login",
+            AttemptKind::TransactionPin => "pin",
+            AttemptKind::Otp => "otp",
+        }
+    }
+
+    fn policy(&self) -> AttemptPolicy {
+        match self {
+            AttemptKind::Login => AttemptPolicy {
+                free: 3,
+                lockout_after: 10,
+                lockout_seconds: 15 * 60,
+                ip_lockout_after: 50,
+                window_seconds: 15 * 60,
+            },
+            // Four or six digits, so far fewer tries
+            AttemptKind::TransactionPin => AttemptPolicy {
+                free: 2,
+                lockout_after: 5,
+                lockout_seconds: 30 * 60,
+                ip_lockout_after: 20,
+                window_seconds: 60 * 60,
+            },
+            AttemptKind::Otp => AttemptPolicy {
+                free: 2,
+                lockout_after: 5,
+                lockout_seconds: 15 * 60,
+                ip_lockout_after: 30,
+                window_seconds: 15 * 60,
+            },
+        }
+    }
+}
+
+impl AttemptPolicy {
+    /// Seconds to wait after `failures` failures in a row, doubling from 2
+    fn wait_after(&self, failures: i64) -> Option<i64> {
+        if failures >= self.lockout_after {
+            Some(self.lockout_seconds)
+        } else if failures > self.free {
+            Some((1_i64 << (failures - self.free).min(6)).min(MAX_DELAY_SECONDS))
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AttemptGuard {
+    redis: Option<Arc<ConnectionManager>>,
+}
+
+impl std::fmt::Debug for AttemptGuard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AttemptGuard")
+            .field("redis", &self.redis.is_some())
+            .finish()
+    }
+}
+
+impl AttemptGuard {
+    pub fn new(db_client: &DBClient) -> Self {
+        Self { redis: db_client.redis_client.clone() }
+    }
+
+    /// Refuses while the account or the IP still has to wait
+    pub async fn check(&self, kind: AttemptKind, account: &str, ip: Option<&str>) -> Result<(), ServiceError> {
+        let Some(redis) = &self.redis else {
+            return Ok(());
+        };
+        let mut conn = ConnectionManager::clone(redis);
+
+        let mut keys = vec![key(kind, "wait", account)];
+        if let Some(ip) = ip {
+            keys.push(key(kind, "ip_wait", ip));
+        }
+
+        for key in keys {
+            match redis::cmd("TTL").arg(&key).query_async::<_, i64>(&mut conn).await {
+                Ok(seconds) if seconds > 0 => return Err(ServiceError::TooManyAttempts(seconds)),
+                Ok(_) => {}
+                // Better to let people in than lock everyone out when Redis is down
+                Err(e) => tracing::warn!("Attempt check failed for {}: {}", key, e),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Counts a failure against the account and IP. True if the account is
+    /// now locked out.
+    pub async fn record_failure(&self, kind: AttemptKind, account: &str, ip: Option<&str>) -> bool {
+        let Some(redis) = &self.redis else {
+            return false;
+        };
+        let mut conn = ConnectionManager::clone(redis);
+        let policy = kind.policy();
+
+        let result: RedisResult<bool> = async {
+            let failures = count(&mut conn, &key(kind, "failures", account), policy.window_seconds).await?;
+            if let Some(wait) = policy.wait_after(failures) {
+                set_wait(&mut conn, &key(kind, "wait", account), wait).await?;
+            }
+
+            if let Some(ip) = ip {
+                let ip_failures = count(&mut conn, &key(kind, "ip_failures", ip), policy.window_seconds).await?;
+                if ip_failures >= policy.ip_lockout_after {
+                    set_wait(&mut conn, &key(kind, "ip_wait", ip), policy.window_seconds).await?;
+                }
+            }
+
+            Ok(failures >= policy.lockout_after)
+        }
+        .await;
+
+        result.unwrap_or_else(|e| {
+            tracing::warn!("Could not record a failed {} attempt: {}", kind.as_str(), e);
+            false
+        })
+    }
+
+    /// Clears the account's failures. The IP's stay, so one good login does
+    /// not reset a spray across many accounts.
+    pub async fn record_success(&self, kind: AttemptKind, account: &str) {
+        let Some(redis) = &self.redis else {
+            return;
+        };
+        let mut conn = ConnectionManager::clone(redis);
+
+        let result: RedisResult<()> = redis::cmd("DEL")
+            .arg(key(kind, "failures", account))
+            .arg(key(kind, "wait", account))
+            .query_async(&mut conn)
+            .await;
+        if let Err(e) = result {
+            tracing::warn!("Could not clear {} attempts: {}", kind.as_str(), e);
+        }
+    }
+}
+
+fn key(kind: AttemptKind, counter: &str, subject: &str) -> String {
+    format!("attempts:{}:{}:{}", kind.as_str(), counter, subject.trim().to_lowercase())
+}
+
+/// Adds one to a counter that expires `window_seconds` after its first hit
+async fn count(conn: &mut ConnectionManager, key: &str, window_seconds: i64) -> RedisResult<i64> {
+    let count: i64 = redis::cmd("INCR").arg(key).query_async(conn).await?;
+    if count == 1 {
+        let _: () = redis::cmd("EXPIRE").arg(key).arg(window_seconds).query_async(conn).await?;
+    }
+    Ok(count)
+}
+
+async fn set_wait(conn: &mut ConnectionManager, key: &str, seconds: i64) -> RedisResult<()> {
+    redis::cmd("SET").arg(key).arg(1).arg("EX").arg(seconds).query_async(conn).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_waits_double_after_the_free_attempts_until_lockout() {
+        let login = AttemptKind::Login.policy();
+        let waits: Vec<Option<i64>> = (1..=11).map(|failures| login.wait_after(failures)).collect();
+
+        assert_eq!(
+            waits,
+            vec![None, None, None, Some(2), Some(4), Some(8), Some(16), Some(32), Some(60), Some(900), Some(900)]
+        );
+
+        let pin = AttemptKind::TransactionPin.policy();
+        assert_eq!(pin.wait_after(2), None);
+        assert_eq!(pin.wait_after(4), Some(4));
+        assert_eq!(pin.wait_after(5), Some(30 * 60));
+    }
+}
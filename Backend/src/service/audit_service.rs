@@ -7,7 +7,7 @@ use serde::Serialize;
 
 use crate::{
     db::db::DBClient,
-    models::labourmodel::*,
+    models::{labourmodel::*, usermodel::UserRole},
     service::error::ServiceError,
 };
 
@@ -190,6 +190,54 @@ impl AuditService {
         ).await
     }
 
+    /// A role granted or revoked. `actor_id` is the user themselves for
+    /// self-service roles.
+    pub async fn log_role_change(
+        &self,
+        actor_id: Uuid,
+        target_user_id: Uuid,
+        role: UserRole,
+        granted: bool,
+    ) -> Result<(), ServiceError> {
+        let action = if granted { "granted" } else { "revoked" };
+
+        self.log_audit_event(
+            actor_id,
+            format!("role_{}", action),
+            None,
+            Some(target_user_id),
+            Some(serde_json::json!({
+                "role": role.to_str(),
+                "self_service": actor_id == target_user_id,
+            })),
+            format!("Role {} {}", role.to_str(), action),
+        ).await
+    }
+
+    /// A user downloaded an archive of their data
+    pub async fn log_data_export(&self, user_id: Uuid, sections: usize) -> Result<(), ServiceError> {
+        self.log_audit_event(
+            user_id,
+            "data_exported".to_string(),
+            None,
+            None,
+            Some(serde_json::json!({ "sections": sections })),
+            "Personal data exported".to_string(),
+        ).await
+    }
+
+    /// A user deleted their account; the row is anonymised, not removed
+    pub async fn log_account_deletion(&self, user_id: Uuid) -> Result<(), ServiceError> {
+        self.log_audit_event(
+            user_id,
+            "account_deleted".to_string(),
+            None,
+            None,
+            None,
+            "Account deleted and personal data anonymised".to_string(),
+        ).await
+    }
+
     // async fn log_audit_event(
     //     &self,
     //     user_id: Uuid,
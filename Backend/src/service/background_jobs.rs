@@ -4,6 +4,8 @@ use chrono::Utc;
 use tokio::time::{interval, Duration};
 
 use crate::{
+    db::notificationdb::NotificationExt,
+    mail::outbox,
     service::vendor_order_service::VendorOrderService,
     AppState,
 };
@@ -82,4 +84,202 @@ pub async fn start_service_expiry_job(app_state: Arc<AppState>) {
             Err(e) => tracing::error!("Failed to fetch expiring services: {}", e),
         }
     }
-}
\ No newline at end of file
+}
+/// Start background job that flushes emails held back by quiet hours or digest preferences
+pub async fn start_notification_digest_job(app_state: Arc<AppState>) {
+    let mut interval = interval(Duration::from_secs(900)); // Run every 15 minutes
+    
+    loop {
+        interval.tick().await;
+        
+        tracing::info!("Running notification digest job at {}", Utc::now());
+        
+        let user_ids = match app_state.db_client.get_users_with_pending_digest().await {
+            Ok(user_ids) => user_ids,
+            Err(e) => {
+                tracing::error!("Failed to fetch pending notification digests: {}", e);
+                continue;
+            }
+        };
+        
+        for user_id in user_ids {
+            if let Err(e) = app_state.notification_service.send_pending_digest(user_id).await {
+                tracing::error!("Failed to send notification digest to user {}: {}", user_id, e);
+            }
+        }
+    }
+}
+
+pub async fn start_notification_retry_job(app_state: Arc<AppState>) {
+    let mut interval = interval(Duration::from_secs(60)); // Run every minute
+    
+    loop {
+        interval.tick().await;
+        
+        match app_state.notification_service.retry_due_deliveries().await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!("Retried {} notification deliveries", count),
+            Err(e) => tracing::error!("Failed to retry notification deliveries: {}", e),
+        }
+    }
+}
+
+/// Remind both sides of viewings in the next 24 hours and close out
+/// confirmed viewings nobody reported on
+pub async fn start_viewing_reminder_job(app_state: Arc<AppState>) {
+    let mut interval = interval(Duration::from_secs(900)); // Run every 15 minutes
+
+    loop {
+        interval.tick().await;
+
+        match app_state.viewing_service.send_due_reminders().await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!("Sent reminders for {} viewings", count),
+            Err(e) => tracing::error!("Failed to send viewing reminders: {}", e),
+        }
+
+        match app_state.viewing_service.complete_overdue().await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!("Auto-completed {} viewings", count),
+            Err(e) => tracing::error!("Failed to auto-complete viewings: {}", e),
+        }
+    }
+}
+
+/// Debit rent that has fallen due from tenants' wallets. Failed payments
+/// are retried daily and pick up the late fee once the grace period ends.
+pub async fn start_rent_collection_job(app_state: Arc<AppState>) {
+    let mut interval = interval(Duration::from_secs(3600)); // Run every hour
+
+    loop {
+        interval.tick().await;
+
+        match app_state.tenancy_service.collect_due_rent().await {
+            Ok((0, 0)) => {}
+            Ok((collected, failed)) => tracing::info!("Collected {} rent payments, {} failed", collected, failed),
+            Err(e) => tracing::error!("Failed to collect due rent: {}", e),
+        }
+    }
+}
+
+/// Expire offers whose deadline has passed so the bidding price only reflects live offers
+pub async fn start_offer_expiry_job(app_state: Arc<AppState>) {
+    let mut interval = interval(Duration::from_secs(3600)); // Run every hour
+
+    loop {
+        interval.tick().await;
+
+        match app_state.offer_service.expire_offers().await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!("Expired {} property offers", count),
+            Err(e) => tracing::error!("Failed to expire property offers: {}", e),
+        }
+    }
+}
+
+/// Fingerprint photos on newly submitted listings and check them against the index
+pub async fn start_photo_index_job(app_state: Arc<AppState>) {
+    let mut interval = interval(Duration::from_secs(300)); // Run every 5 minutes
+
+    loop {
+        interval.tick().await;
+
+        match app_state.property_service.index_pending_photos(20).await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!("Indexed photos of {} listings", count),
+            Err(e) => tracing::error!("Failed to index listing photos: {}", e),
+        }
+    }
+}
+
+/// Recompute the market figures behind price checks and market analysis
+pub async fn start_market_summary_job(app_state: Arc<AppState>) {
+    let mut interval = interval(Duration::from_secs(3600)); // Run every hour
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = app_state.property_service.refresh_market_summaries().await {
+            tracing::error!("Failed to refresh market summaries: {}", e);
+        }
+    }
+}
+
+/// Tell buyers about new listings matching their saved searches
+pub async fn start_saved_search_alert_job(app_state: Arc<AppState>) {
+    let mut interval = interval(Duration::from_secs(300)); // Run every 5 minutes
+
+    loop {
+        interval.tick().await;
+
+        match app_state.saved_property_service.send_saved_search_alerts(500).await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!("Sent {} saved search alerts", count),
+            Err(e) => tracing::error!("Failed to send saved search alerts: {}", e),
+        }
+    }
+}
+
+/// Remind agents and lawyers of verification SLAs, reassign listings whose
+/// SLA lapsed, and assign listings nobody has picked up
+pub async fn start_verification_assignment_job(app_state: Arc<AppState>) {
+    let mut interval = interval(Duration::from_secs(900)); // Run every 15 minutes
+
+    loop {
+        interval.tick().await;
+
+        match app_state.property_service.run_assignment_sweep(200).await {
+            Ok(sweep) if sweep.reminded + sweep.lapsed + sweep.assigned > 0 => tracing::info!(
+                "Verification assignments: {} reminded, {} lapsed, {} assigned",
+                sweep.reminded, sweep.lapsed, sweep.assigned
+            ),
+            Ok(_) => {}
+            Err(e) => tracing::error!("Verification assignment sweep failed: {}", e),
+        }
+    }
+}
+
+/// Issue certificates for live listings that went live without one, e.g.
+/// because a title document could not be fetched at the time
+pub async fn start_certificate_issue_job(app_state: Arc<AppState>) {
+    let mut interval = interval(Duration::from_secs(3600)); // Run every hour
+
+    loop {
+        interval.tick().await;
+
+        match app_state.certificate_service.issue_missing(100).await {
+            Ok(issued) if issued > 0 => tracing::info!("Issued {} property certificates", issued),
+            Ok(_) => {}
+            Err(e) => tracing::error!("Certificate issuing failed: {}", e),
+        }
+    }
+}
+
+/// Drain the email outbox. Polls every 10 seconds and wakes early when
+/// something is enqueued.
+pub async fn start_email_outbox_worker(app_state: Arc<AppState>) {
+    let mut interval = interval(Duration::from_secs(10));
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = outbox::wait_for_work() => {}
+        }
+
+        // Keep going while full batches come back so a backlog drains quickly
+        loop {
+            match outbox::process_due_emails(&app_state.db_client, 50).await {
+                Ok(50) => continue,
+                Ok(0) => break,
+                Ok(count) => {
+                    tracing::info!("Processed {} outbox emails", count);
+                    break;
+                }
+                Err(e) => {
+                    tracing::error!("Email outbox worker failed: {}", e);
+                    break;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,257 @@
+// src/service/certificate_service.rs
+use std::sync::Arc;
+use chrono::Utc;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    db::{certificatedb::CertificateExt, db::DBClient, userdb::UserExt},
+    models::{
+        certificatemodels::{
+            CertificatePayload, CertifiedDocument, CertifiedProperty, CertifiedVerifier, PropertyCertificate,
+        },
+        propertymodel::{Property, PropertyStatus},
+    },
+    service::{
+        error::ServiceError,
+        image_verification::ImageVerificationService,
+        property_service::PropertyService,
+        storage_service::StorageService,
+    },
+    utils::certificate::{document_hash, CertificateSigner, PublicKeyInfo},
+};
+
+pub const CERTIFICATE_VERSION: u32 = 1;
+
+/// Largest title document downloaded for hashing
+const MAX_DOCUMENT_SIZE_MB: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CertificateState {
+    Valid,
+    Revoked,
+    InvalidSignature, // The stored certificate does not match its signature
+}
+
+/// What anyone checking a reference is told
+#[derive(Debug, Serialize)]
+pub struct CertificateCheck {
+    pub reference: String,
+    pub state: CertificateState,
+    pub certificate: Option<CertificatePayload>,
+    pub revoked_at: Option<chrono::DateTime<Utc>>,
+    pub revocation_reason: Option<String>,
+    pub signed_payload: String,
+    pub signature: String,
+    pub key_id: String,
+    pub qr_payload: String,
+}
+
+#[derive(Debug)]
+pub struct CertificateService {
+    db_client: Arc<DBClient>,
+    signer: CertificateSigner,
+    storage: Arc<StorageService>,
+    // Title documents hosted elsewhere are fetched the same way as listing photos
+    documents: ImageVerificationService,
+    app_url: String,
+}
+
+impl CertificateService {
+    pub fn new(
+        db_client: Arc<DBClient>,
+        signer: CertificateSigner,
+        storage: Arc<StorageService>,
+        app_url: &str,
+    ) -> Self {
+        Self {
+            db_client,
+            signer,
+            storage,
+            documents: ImageVerificationService::new(None, MAX_DOCUMENT_SIZE_MB),
+            app_url: app_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    pub fn reference(property: &Property) -> String {
+        PropertyService::generate_property_reference(
+            property.property_type.to_str(),
+            &property.state,
+            &property.lga,
+            &property.id,
+            property.created_at.or(property.lawyer_verified_at).unwrap_or_else(Utc::now),
+        )
+    }
+
+    /// Signs a certificate for a live listing that passed both reviews. A
+    /// listing that already holds one keeps it.
+    pub async fn issue(&self, property: &Property) -> Result<PropertyCertificate, ServiceError> {
+        if property.status != PropertyStatus::Active {
+            return Err(ServiceError::Validation("Certificates are only issued for live listings".to_string()));
+        }
+
+        if let Some(current) = self.db_client.get_current_certificate(property.id).await? {
+            return Ok(current);
+        }
+
+        let (Some(agent_id), Some(agent_verified_at), Some(lawyer_id), Some(lawyer_verified_at)) = (
+            property.agent_id,
+            property.agent_verified_at,
+            property.lawyer_id,
+            property.lawyer_verified_at,
+        ) else {
+            return Err(ServiceError::Validation("Property has not passed agent and lawyer review".to_string()));
+        };
+
+        let certificate_id = Uuid::new_v4();
+        let issued_at = Utc::now();
+        let reference = Self::reference(property);
+
+        let payload = CertificatePayload {
+            version: CERTIFICATE_VERSION,
+            certificate_id,
+            reference: reference.clone(),
+            property: CertifiedProperty {
+                id: property.id,
+                title: property.title.clone(),
+                property_type: property.property_type,
+                listing_type: property.listing_type,
+                address: property.address.clone(),
+                lga: property.lga.clone(),
+                state: property.state.clone(),
+                landlord_id: property.landlord_id,
+            },
+            agent: self.verifier(agent_id, agent_verified_at).await?,
+            lawyer: self.verifier(lawyer_id, lawyer_verified_at).await?,
+            documents: self.hash_documents(property).await?,
+            issued_at,
+            key_id: self.signer.key_id().to_string(),
+        };
+
+        let payload = serde_json::to_string(&payload)
+            .map_err(|e| ServiceError::Other(e.to_string()))?;
+
+        let certificate = PropertyCertificate {
+            id: certificate_id,
+            property_id: property.id,
+            reference,
+            signature: self.signer.sign(&payload),
+            payload,
+            key_id: self.signer.key_id().to_string(),
+            issued_at,
+            revoked_at: None,
+            revocation_reason: None,
+        };
+
+        Ok(self.db_client.insert_certificate(&certificate).await?)
+    }
+
+    /// Withdraws the listing's current certificate. None if it had none.
+    pub async fn revoke(&self, property_id: Uuid, reason: &str) -> Result<Option<PropertyCertificate>, ServiceError> {
+        Ok(self.db_client.revoke_certificate(property_id, reason).await?)
+    }
+
+    /// The public answer for a reference: the certificate, whether it still
+    /// stands, and whether its signature checks out
+    pub async fn check(&self, reference: &str) -> Result<CertificateCheck, ServiceError> {
+        if !PropertyService::is_valid_property_reference(reference) {
+            return Err(ServiceError::Validation("Not a Verinest property reference".to_string()));
+        }
+
+        let certificate = self.db_client
+            .get_certificate_by_reference(reference)
+            .await?
+            .ok_or_else(|| ServiceError::CertificateNotFound(reference.to_string()))?;
+
+        let signature_valid = self.signer.verify(&certificate.key_id, &certificate.payload, &certificate.signature);
+        let state = match (signature_valid, certificate.revoked_at) {
+            (false, _) => CertificateState::InvalidSignature,
+            (true, Some(_)) => CertificateState::Revoked,
+            (true, None) => CertificateState::Valid,
+        };
+
+        Ok(CertificateCheck {
+            qr_payload: self.qr_payload(&certificate.reference),
+            reference: certificate.reference,
+            state,
+            certificate: serde_json::from_str(&certificate.payload).ok(),
+            revoked_at: certificate.revoked_at,
+            revocation_reason: certificate.revocation_reason,
+            signed_payload: certificate.payload,
+            signature: certificate.signature,
+            key_id: certificate.key_id,
+        })
+    }
+
+    /// Keys to check certificates against offline
+    pub fn public_keys(&self) -> Vec<PublicKeyInfo> {
+        self.signer.public_keys()
+    }
+
+    /// Issues certificates for live listings that missed one, e.g. because a
+    /// document could not be fetched when they went live
+    pub async fn issue_missing(&self, limit: i64) -> Result<usize, ServiceError> {
+        let mut issued = 0;
+        for property in self.db_client.get_properties_missing_certificates(limit).await? {
+            match self.issue(&property).await {
+                Ok(_) => issued += 1,
+                Err(e) => tracing::warn!("Failed to issue certificate for property {}: {}", property.id, e),
+            }
+        }
+
+        Ok(issued)
+    }
+
+    /// What the QR code on a printed certificate encodes
+    fn qr_payload(&self, reference: &str) -> String {
+        format!("{}/verify/property/{}", self.app_url, reference)
+    }
+
+    async fn verifier(
+        &self,
+        user_id: Uuid,
+        verified_at: chrono::DateTime<Utc>,
+    ) -> Result<CertifiedVerifier, ServiceError> {
+        let user = self.db_client
+            .get_user(Some(user_id), None, None, None)
+            .await?
+            .ok_or_else(|| ServiceError::Other(format!("Verifier {} not found", user_id)))?;
+
+        Ok(CertifiedVerifier {
+            id: user.id,
+            name: user.name,
+            verified_at,
+        })
+    }
+
+    async fn hash_documents(&self, property: &Property) -> Result<Vec<CertifiedDocument>, ServiceError> {
+        let documents = [
+            ("certificate_of_occupancy", &property.certificate_of_occupancy),
+            ("deed_of_agreement", &property.deed_of_agreement),
+            ("survey_plan", &property.survey_plan),
+            ("building_plan_approval", &property.building_plan_approval),
+        ];
+
+        let mut hashed = Vec::new();
+        for (kind, url) in documents {
+            let Some(url) = url.as_deref() else {
+                continue;
+            };
+            let contents = match self.storage.read_url(url).await? {
+                Some(contents) => contents,
+                None => self.documents
+                    .fetch_image(url)
+                    .await
+                    .map_err(|e| ServiceError::Other(format!("Could not fetch {} to hash it: {}", kind, e)))?,
+            };
+
+            hashed.push(CertifiedDocument {
+                kind: kind.to_string(),
+                sha256: document_hash(&contents),
+            });
+        }
+
+        Ok(hashed)
+    }
+}
@@ -1,7 +1,7 @@
 use thiserror::Error;
 use uuid::Uuid;
 use crate::{
-    models::labourmodel::*,
+    models::{labourmodel::*, offermodels::OfferStatus, propertymodel::PropertyStatus, tenancymodels::TenancyStatus, viewingmodels::ViewingStatus},
     error::HttpError,
 };
 use axum::http::StatusCode;
@@ -35,6 +35,111 @@ pub enum ServiceError {
     #[error("Dispute {0} is not in status {1:?}")]
     InvalidDisputeStatus(Uuid, DisputeStatus),
     
+    #[error("Property {0} not found")]
+    PropertyNotFound(Uuid),
+
+    #[error("Property {0} cannot move from {1:?} to {2:?}")]
+    InvalidPropertyTransition(Uuid, PropertyStatus, PropertyStatus),
+
+    #[error("User {0} is not authorized to perform this action on property {1}")]
+    UnauthorizedPropertyAccess(Uuid, Uuid),
+
+    #[error("A similar property is already listed at this location")]
+    DuplicateProperty,
+
+    #[error("Viewing {0} not found")]
+    ViewingNotFound(Uuid),
+
+    #[error("Viewing {0} cannot move from {1:?} to {2:?}")]
+    InvalidViewingTransition(Uuid, ViewingStatus, ViewingStatus),
+
+    #[error("User {0} is not authorized to perform this action on viewing {1}")]
+    UnauthorizedViewingAccess(Uuid, Uuid),
+
+    #[error("Tenancy {0} not found")]
+    TenancyNotFound(Uuid),
+
+    #[error("Rent payment {0} not found")]
+    RentPaymentNotFound(Uuid),
+
+    #[error("Tenancy {0} cannot move from {1:?} to {2:?}")]
+    InvalidTenancyTransition(Uuid, TenancyStatus, TenancyStatus),
+
+    #[error("User {0} is not authorized to perform this action on tenancy {1}")]
+    UnauthorizedTenancyAccess(Uuid, Uuid),
+
+    #[error("Offer {0} not found")]
+    OfferNotFound(Uuid),
+
+    #[error("Offer {0} cannot move from {1:?} to {2:?}")]
+    InvalidOfferTransition(Uuid, OfferStatus, OfferStatus),
+
+    #[error("User {0} is not authorized to perform this action on offer {1}")]
+    UnauthorizedOfferAccess(Uuid, Uuid),
+
+    #[error("Saved search {0} not found")]
+    SavedSearchNotFound(Uuid),
+
+    #[error("No certificate has been issued for {0}")]
+    CertificateNotFound(String),
+
+    #[error("Media {0} not found")]
+    MediaNotFound(Uuid),
+
+    #[error("User {0} is not authorized to access media {1}")]
+    UnauthorizedMediaAccess(Uuid, Uuid),
+
+    #[error("Session {0} not found")]
+    SessionNotFound(Uuid),
+
+    #[error("Proof of address {0} not found")]
+    AddressProofNotFound(Uuid),
+
+    #[error("Refresh token is invalid or has expired")]
+    InvalidRefreshToken,
+
+    #[error("Two-factor code is invalid or has expired")]
+    InvalidTwoFactorCode,
+
+    #[error("Confirm this action with your authenticator app or an emailed code")]
+    StepUpRequired,
+
+    #[error("Missing permission: {0}")]
+    MissingPermission(&'static str),
+
+    #[error("No {0} record matches that number")]
+    IdentityNotFound(&'static str),
+
+    #[error("This {0} is already verified on another account")]
+    IdentityInUse(&'static str),
+
+    #[error("The {0} record does not match your account details")]
+    IdentityMismatch(&'static str),
+
+    #[error("Identity lookup failed: {0}")]
+    IdentityProvider(String),
+
+    #[error("{0} needs KYC level {1}; see /api/verification/kyc-level")]
+    KycLevelRequired(&'static str, &'static str),
+
+    #[error("This phone number is already verified on another account")]
+    PhoneInUse,
+
+    #[error("Too many codes sent to this number; try again in {0} minute(s)")]
+    TooManyCodes(i64),
+
+    #[error("Too many failed attempts; try again in {0} second(s)")]
+    TooManyAttempts(i64),
+
+    #[error("SMS delivery is not available")]
+    SmsUnavailable,
+
+    #[error("Your account cannot be deleted yet: {0}")]
+    AccountDeletionBlocked(String),
+
+    #[error("Storage error: {0}")]
+    Storage(String),
+
     #[error("Database error: {0}")]
     Database(#[from] sqlx::Error),
     
@@ -53,15 +158,53 @@ impl From<ServiceError> for HttpError {
         match error {
             ServiceError::WorkerProfileNotFound(_) 
             | ServiceError::JobNotFound(_) 
-            | ServiceError::DisputeNotFound(_) => HttpError::not_found(error.to_string()),
+            | ServiceError::DisputeNotFound(_)
+            | ServiceError::PropertyNotFound(_)
+            | ServiceError::ViewingNotFound(_)
+            | ServiceError::TenancyNotFound(_)
+            | ServiceError::RentPaymentNotFound(_)
+            | ServiceError::OfferNotFound(_)
+            | ServiceError::SavedSearchNotFound(_)
+            | ServiceError::CertificateNotFound(_)
+            | ServiceError::MediaNotFound(_)
+            | ServiceError::SessionNotFound(_)
+            | ServiceError::AddressProofNotFound(_) => HttpError::not_found(error.to_string()),
             
             ServiceError::InvalidJobStatus(_, _)
             | ServiceError::InvalidEscrowTransition(_)
             | ServiceError::InvalidDisputeStatus(_, _)
+            | ServiceError::InvalidPropertyTransition(_, _, _)
+            | ServiceError::InvalidViewingTransition(_, _, _)
+            | ServiceError::InvalidTenancyTransition(_, _, _)
+            | ServiceError::InvalidOfferTransition(_, _, _)
+            | ServiceError::IdentityNotFound(_)
+            | ServiceError::IdentityMismatch(_)
             | ServiceError::Validation(_) => HttpError::bad_request(error.to_string()),
+
+            ServiceError::DuplicateProperty
+            | ServiceError::IdentityInUse(_)
+            | ServiceError::AccountDeletionBlocked(_)
+            | ServiceError::PhoneInUse => HttpError::new(error.to_string(), StatusCode::CONFLICT),
+
+            ServiceError::TooManyCodes(_)
+            | ServiceError::TooManyAttempts(_) => HttpError::new(error.to_string(), StatusCode::TOO_MANY_REQUESTS),
+
+            ServiceError::IdentityProvider(_)
+            | ServiceError::SmsUnavailable => HttpError::new(error.to_string(), StatusCode::SERVICE_UNAVAILABLE),
             
             ServiceError::UnauthorizedJobAccess(_, _) 
-            | ServiceError::UnauthorizedServiceAccess(_, _)=> HttpError::unauthorized(error.to_string()),
+            | ServiceError::UnauthorizedServiceAccess(_, _)
+            | ServiceError::UnauthorizedPropertyAccess(_, _)
+            | ServiceError::UnauthorizedViewingAccess(_, _)
+            | ServiceError::UnauthorizedTenancyAccess(_, _)
+            | ServiceError::UnauthorizedOfferAccess(_, _)
+            | ServiceError::UnauthorizedMediaAccess(_, _)
+            | ServiceError::InvalidRefreshToken
+            | ServiceError::InvalidTwoFactorCode => HttpError::unauthorized(error.to_string()),
+
+            ServiceError::StepUpRequired
+            | ServiceError::MissingPermission(_)
+            | ServiceError::KycLevelRequired(_, _) => HttpError::new(error.to_string(), StatusCode::FORBIDDEN),
             
             ServiceError::InsufficientEscrowFunds { .. } => HttpError::payment_required(error.to_string()),
             
@@ -87,15 +230,53 @@ impl ServiceError {
         match self {
             ServiceError::WorkerProfileNotFound(_) 
             | ServiceError::JobNotFound(_) 
-            | ServiceError::DisputeNotFound(_) => StatusCode::NOT_FOUND,
+            | ServiceError::DisputeNotFound(_)
+            | ServiceError::PropertyNotFound(_)
+            | ServiceError::ViewingNotFound(_)
+            | ServiceError::TenancyNotFound(_)
+            | ServiceError::RentPaymentNotFound(_)
+            | ServiceError::OfferNotFound(_)
+            | ServiceError::SavedSearchNotFound(_)
+            | ServiceError::CertificateNotFound(_)
+            | ServiceError::MediaNotFound(_)
+            | ServiceError::SessionNotFound(_)
+            | ServiceError::AddressProofNotFound(_) => StatusCode::NOT_FOUND,
             
             ServiceError::InvalidJobStatus(_, _)
             | ServiceError::InvalidEscrowTransition(_)
             | ServiceError::InvalidDisputeStatus(_, _)
+            | ServiceError::InvalidPropertyTransition(_, _, _)
+            | ServiceError::InvalidViewingTransition(_, _, _)
+            | ServiceError::InvalidTenancyTransition(_, _, _)
+            | ServiceError::InvalidOfferTransition(_, _, _)
+            | ServiceError::IdentityNotFound(_)
+            | ServiceError::IdentityMismatch(_)
             | ServiceError::Validation(_) => StatusCode::BAD_REQUEST,
+
+            ServiceError::DuplicateProperty
+            | ServiceError::IdentityInUse(_)
+            | ServiceError::AccountDeletionBlocked(_)
+            | ServiceError::PhoneInUse => StatusCode::CONFLICT,
+
+            ServiceError::TooManyCodes(_)
+            | ServiceError::TooManyAttempts(_) => StatusCode::TOO_MANY_REQUESTS,
+
+            ServiceError::IdentityProvider(_)
+            | ServiceError::SmsUnavailable => StatusCode::SERVICE_UNAVAILABLE,
             
             ServiceError::UnauthorizedJobAccess(_, _)
-            | ServiceError::UnauthorizedServiceAccess(_, _) => StatusCode::UNAUTHORIZED,
+            | ServiceError::UnauthorizedServiceAccess(_, _)
+            | ServiceError::UnauthorizedPropertyAccess(_, _)
+            | ServiceError::UnauthorizedViewingAccess(_, _)
+            | ServiceError::UnauthorizedTenancyAccess(_, _)
+            | ServiceError::UnauthorizedOfferAccess(_, _)
+            | ServiceError::UnauthorizedMediaAccess(_, _)
+            | ServiceError::InvalidRefreshToken
+            | ServiceError::InvalidTwoFactorCode => StatusCode::UNAUTHORIZED,
+
+            ServiceError::StepUpRequired
+            | ServiceError::MissingPermission(_)
+            | ServiceError::KycLevelRequired(_, _) => StatusCode::FORBIDDEN,
             
             ServiceError::InsufficientEscrowFunds { .. } => StatusCode::PAYMENT_REQUIRED,
             
@@ -103,6 +284,8 @@ impl ServiceError {
             
             ServiceError::Notification(_) => StatusCode::INTERNAL_SERVER_ERROR,
 
+            ServiceError::Storage(_) => StatusCode::INTERNAL_SERVER_ERROR,
+
             ServiceError::Other(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
@@ -0,0 +1,244 @@
+// service/face_model.rs
+//! Face detection and embedding, run in-process with tract.
+//!
+//! Two ONNX models are read from `FACE_MODEL_DIR` (the tracked `models/`
+//! directory by default) at startup:
+//! - `face_detector.onnx`: Ultra-Light-Fast-Generic-Face-Detector, version-RFB-320
+//!   from the ONNX model zoo. Takes 1x3x240x320 RGB as (pixel - 127) / 128 and
+//!   returns `scores` (1xNx2, background/face) and `boxes` (1xNx4 corners
+//!   relative to the image).
+//! - `face_embedding.onnx`: an ArcFace-style recogniser (e.g. MobileFaceNet)
+//!   taking a 1x3x112x112 RGB crop as (pixel - 127.5) / 127.5 and returning one
+//!   embedding vector.
+use std::fmt;
+use std::path::Path;
+
+use image::{imageops::FilterType, DynamicImage, GenericImageView};
+use tract_onnx::prelude::*;
+
+const DETECTOR_FILE: &str = "face_detector.onnx";
+const EMBEDDER_FILE: &str = "face_embedding.onnx";
+
+/// Recorded with every check so results can be traced to the models behind them
+pub const MODEL_VERSION: &str = "ultraface-rfb-320+arcface-112";
+
+const DETECTOR_WIDTH: u32 = 320;
+const DETECTOR_HEIGHT: u32 = 240;
+const EMBEDDER_SIZE: u32 = 112;
+
+/// Detector confidence above which a box counts as a face
+const FACE_CONFIDENCE: f32 = 0.7;
+/// Boxes overlapping more than this are the same face
+const NMS_IOU: f32 = 0.3;
+/// Context kept around a face when cropping it for the embedder
+const CROP_MARGIN: f32 = 0.15;
+
+type Plan = TypedRunnableModel<TypedModel>;
+
+/// A detected face, corners relative to the image (0..1)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FaceBox {
+    pub x1: f32,
+    pub y1: f32,
+    pub x2: f32,
+    pub y2: f32,
+    pub score: f32,
+}
+
+impl FaceBox {
+    /// Share of the image the face covers
+    pub fn area(&self) -> f32 {
+        (self.x2 - self.x1).max(0.0) * (self.y2 - self.y1).max(0.0)
+    }
+
+    fn iou(&self, other: &FaceBox) -> f32 {
+        let overlap = FaceBox {
+            x1: self.x1.max(other.x1),
+            y1: self.y1.max(other.y1),
+            x2: self.x2.min(other.x2),
+            y2: self.y2.min(other.y2),
+            score: 0.0,
+        }
+        .area();
+        let union = self.area() + other.area() - overlap;
+
+        if union <= 0.0 { 0.0 } else { overlap / union }
+    }
+}
+
+pub struct FaceModel {
+    detector: Plan,
+    embedder: Plan,
+}
+
+impl fmt::Debug for FaceModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FaceModel").field("version", &MODEL_VERSION).finish()
+    }
+}
+
+impl FaceModel {
+    pub fn load(dir: &str) -> TractResult<Self> {
+        let dir = Path::new(dir);
+
+        let detector = tract_onnx::onnx()
+            .model_for_path(dir.join(DETECTOR_FILE))?
+            .with_input_fact(0, f32::fact([1, 3, DETECTOR_HEIGHT as usize, DETECTOR_WIDTH as usize]).into())?
+            .into_optimized()?
+            .into_runnable()?;
+
+        let embedder = tract_onnx::onnx()
+            .model_for_path(dir.join(EMBEDDER_FILE))?
+            .with_input_fact(0, f32::fact([1, 3, EMBEDDER_SIZE as usize, EMBEDDER_SIZE as usize]).into())?
+            .into_optimized()?
+            .into_runnable()?;
+
+        Ok(Self { detector, embedder })
+    }
+
+    /// Every face in the image, most confident first
+    pub fn detect(&self, image: &DynamicImage) -> TractResult<Vec<FaceBox>> {
+        let input = to_tensor(image, DETECTOR_WIDTH, DETECTOR_HEIGHT, 127.0, 128.0);
+        let outputs = self.detector.run(tvec!(input.into()))?;
+
+        let scores: Vec<f32> = outputs[0].to_array_view::<f32>()?.iter().copied().collect();
+        let boxes: Vec<f32> = outputs[1].to_array_view::<f32>()?.iter().copied().collect();
+
+        Ok(select_faces(&scores, &boxes, FACE_CONFIDENCE))
+    }
+
+    /// A unit-length embedding of the face, so a dot product is the cosine similarity
+    pub fn embed(&self, image: &DynamicImage, face: &FaceBox) -> TractResult<Vec<f32>> {
+        let (width, height) = image.dimensions();
+        let (x, y, size) = crop_square(face, width, height);
+        let crop = image.crop_imm(x, y, size, size);
+
+        let input = to_tensor(&crop, EMBEDDER_SIZE, EMBEDDER_SIZE, 127.5, 127.5);
+        let outputs = self.embedder.run(tvec!(input.into()))?;
+
+        let embedding: Vec<f32> = outputs[0].to_array_view::<f32>()?.iter().copied().collect();
+        Ok(normalize(embedding))
+    }
+}
+
+/// NCHW RGB tensor of the image resized to `width` x `height`
+fn to_tensor(image: &DynamicImage, width: u32, height: u32, mean: f32, scale: f32) -> Tensor {
+    let rgb = image.resize_exact(width, height, FilterType::Triangle).to_rgb8();
+
+    tract_ndarray::Array4::from_shape_fn((1, 3, height as usize, width as usize), |(_, c, y, x)| {
+        (rgb.get_pixel(x as u32, y as u32)[c] as f32 - mean) / scale
+    })
+    .into()
+}
+
+/// Faces above `threshold` with overlapping boxes merged into the strongest.
+/// `scores` holds background/face pairs and `boxes` x1, y1, x2, y2 per anchor.
+pub fn select_faces(scores: &[f32], boxes: &[f32], threshold: f32) -> Vec<FaceBox> {
+    let mut candidates: Vec<FaceBox> = scores
+        .chunks_exact(2)
+        .zip(boxes.chunks_exact(4))
+        .filter(|(score, _)| score[1] >= threshold)
+        .map(|(score, corners)| FaceBox {
+            x1: corners[0].clamp(0.0, 1.0),
+            y1: corners[1].clamp(0.0, 1.0),
+            x2: corners[2].clamp(0.0, 1.0),
+            y2: corners[3].clamp(0.0, 1.0),
+            score: score[1],
+        })
+        .collect();
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+    let mut faces: Vec<FaceBox> = Vec::new();
+    for candidate in candidates {
+        if faces.iter().all(|face| face.iou(&candidate) < NMS_IOU) {
+            faces.push(candidate);
+        }
+    }
+
+    faces
+}
+
+/// A square around the face with some margin, in pixels and inside the image
+fn crop_square(face: &FaceBox, width: u32, height: u32) -> (u32, u32, u32) {
+    let (w, h) = (width as f32, height as f32);
+    let center_x = (face.x1 + face.x2) / 2.0 * w;
+    let center_y = (face.y1 + face.y2) / 2.0 * h;
+    let side = ((face.x2 - face.x1) * w).max((face.y2 - face.y1) * h) * (1.0 + 2.0 * CROP_MARGIN);
+    let side = side.min(w).min(h).max(1.0);
+
+    let x = (center_x - side / 2.0).clamp(0.0, w - side);
+    let y = (center_y - side / 2.0).clamp(0.0, h - side);
+
+    (x as u32, y as u32, side as u32)
+}
+
+fn normalize(mut vector: Vec<f32>) -> Vec<f32> {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+    vector
+}
+
+/// Cosine similarity of two unit-length embeddings
+pub fn similarity(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlapping_detections_collapse_to_one_face() {
+        let scores = [0.1, 0.9, 0.2, 0.8, 0.05, 0.95, 0.7, 0.3];
+        let boxes = [
+            0.10, 0.10, 0.40, 0.50, // face A
+            0.12, 0.11, 0.41, 0.52, // face A again, weaker
+            0.60, 0.20, 0.90, 0.60, // face B
+            0.00, 0.00, 1.00, 1.00, // background
+        ];
+
+        let faces = select_faces(&scores, &boxes, 0.7);
+        assert_eq!(faces.len(), 2);
+        assert_eq!(faces[0].score, 0.95);
+        assert_eq!(faces[1].score, 0.9);
+    }
+
+    #[test]
+    fn test_crop_stays_inside_the_image() {
+        let face = FaceBox { x1: 0.8, y1: 0.0, x2: 1.0, y2: 0.3, score: 1.0 };
+        let (x, y, side) = crop_square(&face, 640, 480);
+
+        assert!(x + side <= 640);
+        assert!(y + side <= 480);
+        assert!(side >= 144);
+    }
+
+    #[test]
+    fn test_similarity_of_normalized_embeddings() {
+        let a = normalize(vec![3.0, 4.0]);
+        let b = normalize(vec![6.0, 8.0]);
+        let c = normalize(vec![-4.0, 3.0]);
+
+        assert!((similarity(&a, &b) - 1.0).abs() < 1e-6);
+        assert!(similarity(&a, &c).abs() < 1e-6);
+    }
+
+    #[test]
+    #[ignore = "needs face_detector.onnx and face_embedding.onnx in models/"]
+    fn test_bundled_models_detect_and_embed() {
+        let model = FaceModel::load(concat!(env!("CARGO_MANIFEST_DIR"), "/models")).unwrap();
+        let image = DynamicImage::ImageRgb8(image::RgbImage::from_fn(640, 480, |x, y| {
+            image::Rgb([(x % 256) as u8, (y % 256) as u8, 128])
+        }));
+
+        let faces = model.detect(&image).unwrap();
+        assert!(faces.iter().all(|face| face.score >= FACE_CONFIDENCE && face.area() <= 1.0));
+
+        let whole = FaceBox { x1: 0.25, y1: 0.1, x2: 0.75, y2: 0.9, score: 1.0 };
+        let embedding = model.embed(&image, &whole).unwrap();
+        assert!(!embedding.is_empty());
+        assert!((similarity(&embedding, &embedding) - 1.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,179 @@
+// service/identity_providers.rs
+use std::collections::HashMap;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{
+    config::IdentityApiConfig,
+    models::identitymodels::{IdentityKind, IdentityRecord},
+};
+
+#[derive(Debug, Error)]
+pub enum IdentityError {
+    #[error("Identity provider failed: {0}")]
+    Provider(String),
+    #[error("Identity fixtures could not be loaded: {0}")]
+    Fixtures(String),
+}
+
+/// Looks numbers up in the NIMC, BVN and CAC registries. `None` means the
+/// registry has no such number; an error means we could not find out.
+#[async_trait]
+pub trait IdentityProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn lookup(&self, kind: IdentityKind, number: &str) -> Result<Option<IdentityRecord>, IdentityError>;
+}
+
+/// A Dojah-compatible KYC API. Other aggregators (Prembly, Youverify) can be
+/// added as further adapters behind the same trait.
+pub struct DojahIdentityProvider {
+    client: Client,
+    config: IdentityApiConfig,
+}
+
+impl DojahIdentityProvider {
+    pub fn new(config: IdentityApiConfig) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(20))
+            .build()
+            .unwrap_or_default();
+
+        Self { client, config }
+    }
+
+    fn url(&self, kind: IdentityKind, number: &str) -> String {
+        let base = self.config.base_url.trim_end_matches('/');
+        match kind {
+            IdentityKind::Nin => format!("{}/api/v1/kyc/nin?nin={}", base, number),
+            IdentityKind::Bvn => format!("{}/api/v1/kyc/bvn/full?bvn={}", base, number),
+            IdentityKind::Cac => format!("{}/api/v1/kyc/cac?rc_number={}", base, number),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DojahResponse {
+    entity: DojahEntity,
+}
+
+#[derive(Debug, Deserialize)]
+struct DojahEntity {
+    first_name: Option<String>,
+    middle_name: Option<String>,
+    last_name: Option<String>,
+    date_of_birth: Option<String>,
+    company_name: Option<String>,
+}
+
+#[async_trait]
+impl IdentityProvider for DojahIdentityProvider {
+    fn name(&self) -> &'static str {
+        "dojah"
+    }
+
+    async fn lookup(&self, kind: IdentityKind, number: &str) -> Result<Option<IdentityRecord>, IdentityError> {
+        let response = self.client
+            .get(self.url(kind, number))
+            .header("AppId", &self.config.app_id)
+            .header("Authorization", &self.config.secret_key)
+            .send()
+            .await
+            .map_err(|e| IdentityError::Provider(e.to_string()))?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(IdentityError::Provider(format!("{} lookup returned {}", kind.to_str(), response.status())));
+        }
+
+        let body: DojahResponse = response
+            .json()
+            .await
+            .map_err(|e| IdentityError::Provider(format!("Unexpected {} response: {}", kind.to_str(), e)))?;
+        let entity = body.entity;
+
+        Ok(Some(IdentityRecord {
+            first_name: entity.first_name,
+            middle_name: entity.middle_name,
+            last_name: entity.last_name,
+            date_of_birth: entity.date_of_birth.as_deref().and_then(parse_registry_date),
+            business_name: entity.company_name,
+        }))
+    }
+}
+
+/// Registries disagree on date formats: 1990-04-07, 07-04-1990, 07-Apr-1990
+pub fn parse_registry_date(value: &str) -> Option<NaiveDate> {
+    ["%Y-%m-%d", "%d-%m-%Y", "%d-%b-%Y", "%d/%m/%Y"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value.trim(), format).ok())
+}
+
+/// Records from a JSON file, for local development and tests. The file maps
+/// "nin", "bvn" and "cac" to objects keyed by number; see
+/// `data/identity_fixtures.json`.
+pub struct FakeIdentityProvider {
+    records: HashMap<String, HashMap<String, IdentityRecord>>,
+}
+
+impl FakeIdentityProvider {
+    pub fn load(path: &str) -> Result<Self, IdentityError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| IdentityError::Fixtures(format!("{}: {}", path, e)))?;
+        Self::from_json(&contents)
+    }
+
+    pub fn from_json(contents: &str) -> Result<Self, IdentityError> {
+        let records = serde_json::from_str(contents)
+            .map_err(|e| IdentityError::Fixtures(e.to_string()))?;
+        Ok(Self { records })
+    }
+}
+
+#[async_trait]
+impl IdentityProvider for FakeIdentityProvider {
+    fn name(&self) -> &'static str {
+        "fake"
+    }
+
+    async fn lookup(&self, kind: IdentityKind, number: &str) -> Result<Option<IdentityRecord>, IdentityError> {
+        Ok(self.records
+            .get(kind.to_str())
+            .and_then(|records| records.get(number))
+            .cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_dates_in_every_format() {
+        let expected = NaiveDate::from_ymd_opt(1990, 4, 7);
+
+        assert_eq!(parse_registry_date("1990-04-07"), expected);
+        assert_eq!(parse_registry_date("07-04-1990"), expected);
+        assert_eq!(parse_registry_date("07-Apr-1990"), expected);
+        assert_eq!(parse_registry_date("not a date"), None);
+    }
+
+    #[tokio::test]
+    async fn test_fake_provider_reads_fixtures_by_kind() {
+        let provider = FakeIdentityProvider::from_json(
+            r#"{"nin": {"12345678901": {"first_name": "Ada", "last_name": "Obi", "date_of_birth": "1990-04-07"}}}"#,
+        )
+        .unwrap();
+
+        let record = provider.lookup(IdentityKind::Nin, "12345678901").await.unwrap().unwrap();
+        assert_eq!(record.full_name(), "Ada Obi");
+        assert!(provider.lookup(IdentityKind::Bvn, "12345678901").await.unwrap().is_none());
+    }
+}
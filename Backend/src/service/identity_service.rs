@@ -0,0 +1,262 @@
+// service/identity_service.rs
+use std::fmt;
+use std::sync::Arc;
+
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::{
+    config::Config,
+    db::{cache::CacheHelper, db::DBClient, identitydb::IdentityCheckExt},
+    models::identitymodels::{IdentityCheck, IdentityKind, IdentityOutcome, IdentityRecord},
+    service::{
+        error::ServiceError,
+        identity_providers::{DojahIdentityProvider, FakeIdentityProvider, IdentityError, IdentityProvider},
+    },
+    utils::name_match,
+};
+
+/// Name similarity at or above which the registry record is the account holder
+pub const NAME_MATCH_THRESHOLD: f64 = 0.8;
+
+/// Registry records rarely change, and every lookup is billed
+const IDENTITY_CACHE_TTL: usize = 86400; // 24 hours
+
+impl From<IdentityError> for ServiceError {
+    fn from(err: IdentityError) -> Self {
+        ServiceError::IdentityProvider(err.to_string())
+    }
+}
+
+/// A cached lookup, so "no such number" is cached as well as records
+#[derive(Serialize, Deserialize)]
+struct CachedLookup {
+    record: Option<IdentityRecord>,
+}
+
+/// Checks NIN, BVN and CAC numbers against their registries and compares
+/// what comes back with the account. Each check is stored, and a number
+/// matched to one account cannot be matched to another.
+pub struct IdentityService {
+    db_client: Arc<DBClient>,
+    provider: Option<Arc<dyn IdentityProvider>>,
+}
+
+impl fmt::Debug for IdentityService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IdentityService")
+            .field("provider", &self.provider.as_ref().map(|provider| provider.name()))
+            .finish()
+    }
+}
+
+impl IdentityService {
+    pub fn new(db_client: Arc<DBClient>, provider: Option<Arc<dyn IdentityProvider>>) -> Self {
+        Self { db_client, provider }
+    }
+
+    /// The provider named by `IDENTITY_PROVIDER`
+    pub fn from_config(db_client: Arc<DBClient>, config: &Config) -> Result<Self, IdentityError> {
+        let provider: Option<Arc<dyn IdentityProvider>> = match config.identity_provider.as_str() {
+            "api" => {
+                let api = config.identity_api.clone().ok_or_else(|| IdentityError::Provider(
+                    "IDENTITY_PROVIDER is api but IDENTITY_APP_ID or IDENTITY_SECRET_KEY is missing".to_string(),
+                ))?;
+                Some(Arc::new(DojahIdentityProvider::new(api)))
+            }
+            "fake" => Some(Arc::new(FakeIdentityProvider::load(&config.identity_fixtures_path)?)),
+            "none" => {
+                tracing::warn!("No identity provider configured; NIN, BVN and CAC numbers will not be checked");
+                None
+            }
+            other => return Err(IdentityError::Provider(format!("Unknown IDENTITY_PROVIDER '{}'", other))),
+        };
+
+        Ok(Self::new(db_client, provider))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.provider.is_some()
+    }
+
+    /// Looks a person's NIN or BVN up and compares the record with the name
+    /// and date of birth they gave us
+    pub async fn verify_person(
+        &self,
+        user_id: Uuid,
+        kind: IdentityKind,
+        number: &str,
+        name: &str,
+        dob: Option<NaiveDate>,
+    ) -> Result<IdentityCheck, ServiceError> {
+        self.verify(user_id, kind, number, |record| assess_person(name, dob, record)).await
+    }
+
+    /// Looks a CAC number up and compares the registered name with the one
+    /// the business trades under here
+    pub async fn verify_business(
+        &self,
+        user_id: Uuid,
+        rc_number: &str,
+        business_name: &str,
+    ) -> Result<IdentityCheck, ServiceError> {
+        self.verify(user_id, IdentityKind::Cac, rc_number, |record| assess_business(business_name, record)).await
+    }
+
+    /// Turns anything but a match into the error to show the user
+    pub fn ensure_matched(check: &IdentityCheck) -> Result<(), ServiceError> {
+        match check.outcome {
+            IdentityOutcome::Matched => Ok(()),
+            IdentityOutcome::Mismatch => Err(ServiceError::IdentityMismatch(check.kind.label())),
+            IdentityOutcome::NotFound => Err(ServiceError::IdentityNotFound(check.kind.label())),
+        }
+    }
+
+    async fn verify(
+        &self,
+        user_id: Uuid,
+        kind: IdentityKind,
+        number: &str,
+        assess: impl FnOnce(&IdentityRecord) -> Assessment,
+    ) -> Result<IdentityCheck, ServiceError> {
+        let provider = self.provider.as_ref().ok_or_else(|| {
+            ServiceError::IdentityProvider(format!("{} checks are not available", kind.label()))
+        })?;
+        let number = number.trim();
+        let number_hash = hex::encode(Sha256::digest(number.as_bytes()));
+
+        if self.db_client.get_identity_number_owner(kind, &number_hash, user_id).await?.is_some() {
+            return Err(ServiceError::IdentityInUse(kind.label()));
+        }
+
+        let record = self.lookup(provider.as_ref(), kind, number, &number_hash).await?;
+        let assessment = match &record {
+            Some(record) => assess(record),
+            None => Assessment { outcome: IdentityOutcome::NotFound, name_score: None, dob_match: None },
+        };
+
+        let check = self.db_client
+            .insert_identity_check(&IdentityCheck {
+                id: Uuid::new_v4(),
+                user_id,
+                kind,
+                number_hash,
+                number_last4: number.chars().rev().take(4).collect::<Vec<_>>().into_iter().rev().collect(),
+                provider: provider.name().to_string(),
+                outcome: assessment.outcome,
+                name_score: assessment.name_score,
+                dob_match: assessment.dob_match,
+                created_at: Utc::now(),
+            })
+            .await?;
+
+        Ok(check)
+    }
+
+    /// The registry record, from Redis when this number was looked up recently
+    async fn lookup(
+        &self,
+        provider: &dyn IdentityProvider,
+        kind: IdentityKind,
+        number: &str,
+        number_hash: &str,
+    ) -> Result<Option<IdentityRecord>, ServiceError> {
+        let cache_key = format!("identity:{}:{}:{}", provider.name(), kind.to_str(), number_hash);
+
+        if let Some(redis) = &self.db_client.redis_client {
+            if let Ok(Some(cached)) = CacheHelper::get::<CachedLookup>(redis, &cache_key).await {
+                return Ok(cached.record);
+            }
+        }
+
+        let record = provider.lookup(kind, number).await?;
+
+        if let Some(redis) = &self.db_client.redis_client {
+            let cached = CachedLookup { record: record.clone() };
+            if let Err(e) = CacheHelper::set(redis, &cache_key, &cached, IDENTITY_CACHE_TTL).await {
+                tracing::warn!("Failed to cache {} lookup: {}", kind.to_str(), e);
+            }
+        }
+
+        Ok(record)
+    }
+}
+
+/// How a registry record compared with what we were told
+#[derive(Debug, PartialEq)]
+pub struct Assessment {
+    pub outcome: IdentityOutcome,
+    pub name_score: Option<f64>,
+    pub dob_match: Option<bool>,
+}
+
+/// A match needs the name to agree and the date of birth not to disagree;
+/// either side may lack a date
+pub fn assess_person(name: &str, dob: Option<NaiveDate>, record: &IdentityRecord) -> Assessment {
+    let name_score = name_match::name_score(name, &record.full_name());
+    let dob_match = dob.zip(record.date_of_birth).map(|(claimed, official)| name_match::dob_matches(claimed, official));
+    let matched = name_score >= NAME_MATCH_THRESHOLD && dob_match != Some(false);
+
+    Assessment {
+        outcome: if matched { IdentityOutcome::Matched } else { IdentityOutcome::Mismatch },
+        name_score: Some(name_score),
+        dob_match,
+    }
+}
+
+pub fn assess_business(business_name: &str, record: &IdentityRecord) -> Assessment {
+    let name_score = record
+        .business_name
+        .as_deref()
+        .map(|official| name_match::business_name_score(business_name, official))
+        .unwrap_or(0.0);
+
+    Assessment {
+        outcome: if name_score >= NAME_MATCH_THRESHOLD { IdentityOutcome::Matched } else { IdentityOutcome::Mismatch },
+        name_score: Some(name_score),
+        dob_match: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record() -> IdentityRecord {
+        IdentityRecord {
+            first_name: Some("ADAEZE".to_string()),
+            middle_name: Some("CHIOMA".to_string()),
+            last_name: Some("OBI".to_string()),
+            date_of_birth: NaiveDate::from_ymd_opt(1992, 3, 14),
+            business_name: None,
+        }
+    }
+
+    #[test]
+    fn test_person_needs_name_and_date_of_birth_to_agree() {
+        let dob = NaiveDate::from_ymd_opt(1992, 3, 14);
+
+        assert_eq!(assess_person("Adaeze Obi", dob, &record()).outcome, IdentityOutcome::Matched);
+        assert_eq!(assess_person("Adaeze Obi", None, &record()).outcome, IdentityOutcome::Matched);
+
+        let wrong_dob = assess_person("Adaeze Obi", NaiveDate::from_ymd_opt(1993, 3, 14), &record());
+        assert_eq!(wrong_dob.outcome, IdentityOutcome::Mismatch);
+        assert_eq!(wrong_dob.dob_match, Some(false));
+
+        assert_eq!(assess_person("Emeka Obi", dob, &record()).outcome, IdentityOutcome::Mismatch);
+    }
+
+    #[test]
+    fn test_business_matches_registered_name() {
+        let registered = IdentityRecord {
+            business_name: Some("VERINEST HOMES NIGERIA LIMITED".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(assess_business("Verinest Homes", &registered).outcome, IdentityOutcome::Matched);
+        assert_eq!(assess_business("Lekki Gardens", &registered).outcome, IdentityOutcome::Mismatch);
+        assert_eq!(assess_business("Verinest", &IdentityRecord::default()).outcome, IdentityOutcome::Mismatch);
+    }
+}
@@ -1,13 +1,11 @@
 // src/service/image_verification.rs
 use std::error::Error;
 use std::fmt;
-use std::collections::HashMap;
-use reqwest::Client;
+use std::net::IpAddr;
+use reqwest::{Client, Url};
 use serde::{Deserialize, Serialize};
 use sha2::{Sha256, Digest};
 use image::{ImageFormat, DynamicImage, GenericImageView};
-use std::io::Cursor;
-use base64::{Engine as _, engine::general_purpose};
 
 // Custom error types for image verification
 #[derive(Debug)]
@@ -78,6 +76,7 @@ pub struct BatchVerificationResult {
     pub recommendations: Vec<String>,
 }
 
+#[derive(Debug)]
 pub struct ImageVerificationService {
     client: Client,
     google_vision_api_key: Option<String>,
@@ -234,7 +233,7 @@ impl ImageVerificationService {
     }
 
     /// Assess image quality using multiple metrics
-    fn assess_image_quality(&self, image: &DynamicImage) -> f64 {
+    pub fn assess_image_quality(&self, image: &DynamicImage) -> f64 {
         let mut quality_score = 0.0;
 
         // 1. Resolution score (30%)
@@ -512,6 +511,54 @@ impl ImageVerificationService {
         Ok(hash)
     }
 
+    /// Download an image for fingerprinting. Only public http(s) hosts are
+    /// fetched, and nothing larger than the configured maximum is read.
+    pub async fn fetch_image(&self, url: &str) -> Result<Vec<u8>, ImageVerificationError> {
+        let parsed = Url::parse(url)
+            .map_err(|e| ImageVerificationError::InvalidFormat(format!("Invalid image URL: {}", e)))?;
+
+        if !matches!(parsed.scheme(), "http" | "https") || !is_public_host(&parsed) {
+            return Err(ImageVerificationError::InvalidFormat(
+                "Image URL must be a public http(s) address".to_string()
+            ));
+        }
+
+        let response = self.client.get(parsed)
+            .timeout(std::time::Duration::from_secs(20))
+            .send()
+            .await
+            .map_err(|e| ImageVerificationError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(ImageVerificationError::NetworkError(
+                format!("Image download failed with status {}", response.status())
+            ));
+        }
+
+        let max_size_bytes = (self.max_file_size_mb * 1024 * 1024) as u64;
+        if response.content_length().is_some_and(|length| length > max_size_bytes) {
+            return Err(ImageVerificationError::FileSizeExceeded(
+                format!("Image is larger than {} MB", self.max_file_size_mb)
+            ));
+        }
+
+        let bytes = response.bytes()
+            .await
+            .map_err(|e| ImageVerificationError::NetworkError(e.to_string()))?;
+        self.validate_file_size(&bytes)?;
+
+        Ok(bytes.to_vec())
+    }
+
+    /// The SHA-256 of the file and its perceptual hash as 64 bits, for indexing
+    pub fn fingerprint(&self, image_data: &[u8]) -> Result<(String, i64), ImageVerificationError> {
+        let image = self.load_image(image_data)?;
+        let phash = perceptual_hash_bits(&self.generate_perceptual_hash(&image)?)
+            .ok_or_else(|| ImageVerificationError::ProcessingError("Malformed perceptual hash".to_string()))?;
+
+        Ok((self.generate_image_hash(image_data), phash))
+    }
+
     /// Compare two images for similarity
     pub async fn compare_images(
         &self,
@@ -720,7 +767,7 @@ impl ImageVerificationService {
         let image = self.load_image(image_data)?;
         let metadata = self.validate_and_analyze_image(image_data, "temp.jpg").await?;
         
-        let mut artificial_score = 0.0;
+        let mut artificial_score: f64 = 0.0;
 
         // Check for perfect symmetry (common in AI-generated images)
         let symmetry_score = self.calculate_symmetry_score(&image);
@@ -744,7 +791,7 @@ impl ImageVerificationService {
             artificial_score += 0.2;
         }
 
-        artificial_score.min(1.0)
+        Ok(artificial_score.min(1.0))
     }
 
     /// Calculate image symmetry score
@@ -777,4 +824,81 @@ impl ImageVerificationService {
             0.0
         }
     }
-}
\ No newline at end of file
+}
+
+/// Packs the '0'/'1' string from `generate_perceptual_hash` into 64 bits
+pub fn perceptual_hash_bits(hash: &str) -> Option<i64> {
+    if hash.len() != 64 {
+        return None;
+    }
+    u64::from_str_radix(hash, 2).ok().map(|bits| bits as i64)
+}
+
+/// Flat images (blank walls, solid placeholders, screenshots of text) hash to
+/// nearly all zeros or ones and would match each other; they say nothing
+/// about which property is pictured
+pub fn is_distinctive_hash(phash: i64) -> bool {
+    (8..=56).contains(&phash.count_ones())
+}
+
+fn is_public_host(url: &Url) -> bool {
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+
+    if host.eq_ignore_ascii_case("localhost") || host.ends_with(".local") || host.ends_with(".internal") {
+        return false;
+    }
+
+    match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => !(ip.is_private() || ip.is_loopback() || ip.is_link_local()
+            || ip.is_unspecified() || ip.is_broadcast()),
+        Ok(IpAddr::V6(ip)) => !(ip.is_loopback() || ip.is_unspecified()
+            || (ip.segments()[0] & 0xfe00) == 0xfc00
+            || (ip.segments()[0] & 0xffc0) == 0xfe80),
+        Err(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageBuffer, Luma};
+
+    fn gradient(width: u32, height: u32, shift: u8) -> DynamicImage {
+        DynamicImage::ImageLuma8(ImageBuffer::from_fn(width, height, |x, y| {
+            Luma([((x * 255 / width) as u8 ^ (y * 255 / height) as u8).wrapping_add(shift)])
+        }))
+    }
+
+    #[test]
+    fn test_resized_copy_hashes_close_to_original() {
+        let service = ImageVerificationService::new(None, 10);
+        let original = gradient(640, 480, 0);
+        let resized = original.resize_exact(320, 240, image::imageops::FilterType::Triangle);
+
+        let a = perceptual_hash_bits(&service.generate_perceptual_hash(&original).unwrap()).unwrap();
+        let b = perceptual_hash_bits(&service.generate_perceptual_hash(&resized).unwrap()).unwrap();
+        let distance = (a ^ b).count_ones();
+        assert!(distance <= 4, "distance {}", distance);
+        assert!(is_distinctive_hash(a));
+
+        let flat = DynamicImage::ImageLuma8(ImageBuffer::from_pixel(64, 64, Luma([200u8])));
+        let flat_hash = perceptual_hash_bits(&service.generate_perceptual_hash(&flat).unwrap()).unwrap();
+        assert!(!is_distinctive_hash(flat_hash));
+    }
+
+    #[test]
+    fn test_only_public_hosts_are_fetched() {
+        let public = |url: &str| is_public_host(&Url::parse(url).unwrap());
+
+        assert!(public("https://res.cloudinary.com/verinest/front.jpg"));
+        assert!(public("https://8.8.8.8/photo.jpg"));
+        assert!(!public("http://localhost:8000/photo.jpg"));
+        assert!(!public("http://127.0.0.1/photo.jpg"));
+        assert!(!public("http://169.254.169.254/latest/meta-data"));
+        assert!(!public("http://10.0.0.5/photo.jpg"));
+        assert!(!public("http://[::1]/photo.jpg"));
+    }
+}
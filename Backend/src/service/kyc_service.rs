@@ -0,0 +1,545 @@
+// service/kyc_service.rs
+use std::sync::Arc;
+
+use chrono::Utc;
+use image::DynamicImage;
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        db::DBClient,
+        kycdb::{KycCheckExt, KycLevelExt},
+        userdb::UserExt,
+        verificationdb::VerificationExt,
+    },
+    dtos::verificationdtos::KycProgressDto,
+    models::{
+        kycmodels::{AddressProof, KycCheck, KycDecision, KycEntitlements, KycLevel},
+        usermodel::VerificationStatus,
+        verificationmodels::VerificationDocument,
+    },
+    service::{
+        error::ServiceError,
+        face_model::{self, FaceModel, MODEL_VERSION},
+        image_verification::ImageVerificationService,
+        notification_service::NotificationService,
+        storage_service::StorageService,
+    },
+};
+
+/// The largest KYC image we will decode
+const MAX_KYC_IMAGE_MB: usize = 10;
+
+/// Face similarity at or above which the selfie is taken to show the document holder
+pub const MATCH_THRESHOLD: f32 = 0.5;
+/// Similarity at which matching adds no risk, and where it adds all of it
+const STRONG_MATCH: f32 = 0.7;
+const NO_MATCH: f32 = 0.3;
+/// Faces this alike are the same photo, not the same person photographed twice
+const IDENTICAL_FACE: f32 = 0.97;
+
+/// Below this `assess_image_quality` score an image is too poor to trust
+const MIN_IMAGE_QUALITY: f64 = 0.5;
+/// A live selfie taken at arm's length fills more of the frame than this
+const MIN_SELFIE_FACE_AREA: f32 = 0.04;
+/// Perceptual hashes this close mean the selfie is a picture of the document
+const MIN_IMAGE_DISTANCE: u32 = 10;
+
+/// Risk at or below which a submission is approved without a reviewer
+pub const AUTO_APPROVE_MAX_RISK: f64 = 0.25;
+
+/// Failed heuristics that point at a photo of a photo or a borrowed selfie
+const LIVENESS_FLAGS: [&str; 4] = [
+    "multiple_faces_in_selfie",
+    "face_too_small",
+    "selfie_matches_document",
+    "low_quality_selfie",
+];
+
+/// What the images showed, before scoring
+#[derive(Debug, Default, Clone)]
+pub struct KycFindings {
+    pub document_quality: Option<f64>,
+    pub selfie_quality: Option<f64>,
+    /// False when the face models did not run
+    pub faces_checked: bool,
+    pub document_faces: usize,
+    pub selfie_faces: usize,
+    /// Share of the selfie covered by the face
+    pub selfie_face_area: Option<f32>,
+    pub similarity: Option<f32>,
+    /// Hamming distance between the two images' perceptual hashes
+    pub image_distance: Option<u32>,
+    /// Whether the registry record for the document number matched the
+    /// account; None when there was no lookup
+    pub registry_match: Option<bool>,
+}
+
+/// Runs automated identity checks on each KYC submission: image quality,
+/// face detection and matching between the ID photo and the selfie, and
+/// liveness heuristics. Clear cases are approved at once; everything else
+/// waits for a reviewer with the check attached.
+#[derive(Debug)]
+pub struct KycService {
+    db_client: Arc<DBClient>,
+    storage_service: Arc<StorageService>,
+    notification_service: Arc<NotificationService>,
+    images: Arc<ImageVerificationService>,
+    faces: Option<Arc<FaceModel>>,
+}
+
+impl KycService {
+    pub fn new(
+        db_client: Arc<DBClient>,
+        storage_service: Arc<StorageService>,
+        notification_service: Arc<NotificationService>,
+        face_model_dir: &str,
+    ) -> Self {
+        let faces = match FaceModel::load(face_model_dir) {
+            Ok(model) => Some(Arc::new(model)),
+            Err(e) => {
+                tracing::warn!(
+                    "Face models not loaded from {}: {}. KYC submissions will all go to reviewers.",
+                    face_model_dir, e
+                );
+                None
+            }
+        };
+
+        Self {
+            db_client,
+            storage_service,
+            notification_service,
+            images: Arc::new(ImageVerificationService::new(None, MAX_KYC_IMAGE_MB)),
+            faces,
+        }
+    }
+
+    /// Checks a new submission and approves it if the result is clear. The
+    /// check is stored either way so reviewers see the evidence.
+    /// `registry_match` is the outcome of any NIN lookup made for it.
+    pub async fn screen(
+        &self,
+        verification: &VerificationDocument,
+        registry_match: Option<bool>,
+    ) -> Result<KycCheck, ServiceError> {
+        let document = self.storage_service.read_url(&verification.document_url).await?;
+        let selfie = self.storage_service.read_url(&verification.selfie_url).await?;
+
+        let (findings, error) = match (document, selfie) {
+            (Some(document), Some(selfie)) => {
+                let images = self.images.clone();
+                let faces = self.faces.clone();
+                tokio::task::spawn_blocking(move || analyse(&images, faces.as_deref(), &document, &selfie))
+                    .await
+                    .map_err(|e| ServiceError::Other(format!("KYC analysis failed: {}", e)))?
+            }
+            _ => (KycFindings::default(), Some("Document or selfie image is not stored".to_string())),
+        };
+        let findings = KycFindings { registry_match, ..findings };
+
+        let flags = flags(&findings);
+        let liveness = liveness_score(&flags);
+        let risk = if error.is_some() { 1.0 } else { risk_score(&findings, liveness) };
+        let decision = if error.is_none() { decide(&findings, &flags, risk) } else { KycDecision::ManualReview };
+
+        let check = self.db_client
+            .insert_kyc_check(&KycCheck {
+                id: Uuid::new_v4(),
+                verification_id: verification.id,
+                document_quality: findings.document_quality,
+                selfie_quality: findings.selfie_quality,
+                document_faces: findings.document_faces as i32,
+                selfie_faces: findings.selfie_faces as i32,
+                face_similarity: findings.similarity.map(f64::from),
+                liveness_score: error.is_none().then_some(liveness),
+                flags: flags.iter().map(|flag| flag.to_string()).collect(),
+                risk_score: risk,
+                decision,
+                error,
+                model_version: self.faces.as_ref().map(|_| MODEL_VERSION.to_string()),
+                created_at: Utc::now(),
+            })
+            .await?;
+
+        if decision == KycDecision::AutoApproved {
+            let notes = format!(
+                "Approved automatically: face match {:.2}, risk {:.2}",
+                check.face_similarity.unwrap_or_default(),
+                check.risk_score
+            );
+            self.approve(verification, None, Some(notes)).await?;
+        }
+
+        Ok(check)
+    }
+
+    /// Marks a submission approved and copies it onto the user, whether a
+    /// reviewer or the automated check cleared it
+    pub async fn approve(
+        &self,
+        verification: &VerificationDocument,
+        reviewed_by: Option<Uuid>,
+        review_notes: Option<String>,
+    ) -> Result<VerificationDocument, ServiceError> {
+        let updated = self.db_client
+            .update_verification_status(verification.id, VerificationStatus::Approved, reviewed_by, review_notes)
+            .await?;
+
+        self.db_client
+            .update_user_verification_data(
+                verification.user_id,
+                VerificationStatus::Approved,
+                Some(verification.document_id.clone()),
+                verification.document_type,
+                Some(verification.document_url.clone()),
+                Some(verification.selfie_url.clone()),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await?;
+        self.db_client
+            .update_user_verification_status(verification.user_id, VerificationStatus::Approved)
+            .await?;
+
+        if let Some(user) = self.db_client.get_user(Some(verification.user_id), None, None, None).await? {
+            if let Err(e) = crate::mail::mails::send_verification_status_email(
+                &user.email,
+                &user.name,
+                &VerificationStatus::Approved,
+                updated.review_notes.as_deref(),
+                user.locale,
+            ).await {
+                tracing::error!("Failed to send verification status email: {}", e);
+            }
+        }
+
+        match self.notification_service.notify_verification_accepted(&updated).await {
+            Ok(_) => tracing::info!("verification notification sent to {}", &updated.user_id),
+            Err(e) => tracing::error!("Failed to notify verification request: {:?}", e),
+        };
+
+        Ok(updated)
+    }
+
+    pub async fn level(&self, user_id: Uuid) -> Result<KycLevel, ServiceError> {
+        Ok(KycLevel::from_facts(&self.db_client.get_kyc_facts(user_id).await?))
+    }
+
+    pub async fn progress(&self, user_id: Uuid) -> Result<KycProgressDto, ServiceError> {
+        Ok(KycProgressDto::from_facts(&self.db_client.get_kyc_facts(user_id).await?))
+    }
+
+    /// Jobs are escrowed up front, so bigger budgets need more KYC
+    pub async fn ensure_job_budget(&self, user_id: Uuid, budget: f64) -> Result<(), ServiceError> {
+        self.ensure_entitled(user_id, "Posting this budget", |entitlements| {
+            entitlements.max_job_budget.map_or(true, |max| budget <= max)
+        })
+        .await
+    }
+
+    /// Whether a vendor with `active_listings` live listings may add another
+    pub async fn ensure_can_list(&self, user_id: Uuid, active_listings: i64) -> Result<(), ServiceError> {
+        self.ensure_entitled(user_id, "Another listing", |entitlements| {
+            entitlements.max_active_listings.map_or(true, |max| active_listings < max)
+        })
+        .await
+    }
+
+    pub async fn ensure_can_withdraw(&self, user_id: Uuid) -> Result<(), ServiceError> {
+        self.ensure_entitled(user_id, "Withdrawing", |entitlements| entitlements.can_withdraw).await
+    }
+
+    /// Refuses with the lowest level that would allow the action
+    async fn ensure_entitled(
+        &self,
+        user_id: Uuid,
+        action: &'static str,
+        allowed: impl Fn(&KycEntitlements) -> bool,
+    ) -> Result<(), ServiceError> {
+        let level = self.level(user_id).await?;
+        if allowed(&level.entitlements()) {
+            return Ok(());
+        }
+
+        let required = KycLevel::ALL
+            .into_iter()
+            .find(|candidate| *candidate > level && allowed(&candidate.entitlements()))
+            .unwrap_or(KycLevel::Address);
+        Err(ServiceError::KycLevelRequired(action, required.to_str()))
+    }
+
+    /// A proof of address for the top KYC level. One can be waiting at a time.
+    pub async fn submit_address_proof(
+        &self,
+        user_id: Uuid,
+        document_url: &str,
+        address: &str,
+    ) -> Result<AddressProof, ServiceError> {
+        let proofs = self.db_client.get_user_address_proofs(user_id).await?;
+        if proofs.iter().any(|proof| proof.status == VerificationStatus::Submitted) {
+            return Err(ServiceError::Validation("A proof of address is already waiting for review".to_string()));
+        }
+
+        Ok(self.db_client.create_address_proof(user_id, document_url, address).await?)
+    }
+
+    pub async fn pending_address_proofs(&self) -> Result<Vec<AddressProof>, ServiceError> {
+        Ok(self.db_client.get_pending_address_proofs().await?)
+    }
+
+    pub async fn review_address_proof(
+        &self,
+        proof_id: Uuid,
+        status: VerificationStatus,
+        reviewed_by: Uuid,
+        review_notes: Option<String>,
+    ) -> Result<AddressProof, ServiceError> {
+        if !matches!(status, VerificationStatus::Approved | VerificationStatus::Rejected) {
+            return Err(ServiceError::Validation("A proof of address is either approved or rejected".to_string()));
+        }
+
+        let proof = self.db_client
+            .get_address_proof(proof_id)
+            .await?
+            .ok_or(ServiceError::AddressProofNotFound(proof_id))?;
+        if proof.status != VerificationStatus::Submitted {
+            return Err(ServiceError::Validation("This proof of address has already been reviewed".to_string()));
+        }
+
+        let reviewed = self.db_client
+            .review_address_proof(proof_id, status, reviewed_by, review_notes)
+            .await?;
+
+        if let Some(user) = self.db_client.get_user(Some(reviewed.user_id), None, None, None).await? {
+            if let Err(e) = crate::mail::mails::send_verification_status_email(
+                &user.email,
+                &user.name,
+                &status,
+                reviewed.review_notes.as_deref(),
+                user.locale,
+            ).await {
+                tracing::error!("Failed to send address review email: {}", e);
+            }
+        }
+
+        Ok(reviewed)
+    }
+
+    /// The latest check for each submission, to show reviewers alongside the queue
+    pub async fn checks_for(&self, verifications: &[VerificationDocument]) -> Result<Vec<KycCheck>, ServiceError> {
+        let ids: Vec<Uuid> = verifications.iter().map(|v| v.id).collect();
+        Ok(self.db_client.get_latest_kyc_checks(&ids).await?)
+    }
+}
+
+/// Image quality, faces and their similarity. Runs on a blocking thread;
+/// an error means the submission could not be checked and needs a person.
+fn analyse(
+    images: &ImageVerificationService,
+    faces: Option<&FaceModel>,
+    document: &[u8],
+    selfie: &[u8],
+) -> (KycFindings, Option<String>) {
+    let mut findings = KycFindings::default();
+
+    let (document, selfie) = match (image::load_from_memory(document), image::load_from_memory(selfie)) {
+        (Ok(document), Ok(selfie)) => (document, selfie),
+        _ => return (findings, Some("Document or selfie could not be decoded as an image".to_string())),
+    };
+
+    findings.document_quality = Some(images.assess_image_quality(&document));
+    findings.selfie_quality = Some(images.assess_image_quality(&selfie));
+    findings.image_distance = image_distance(images, &document, &selfie);
+
+    let Some(faces) = faces else {
+        return (findings, Some("Face models are not loaded".to_string()));
+    };
+
+    match match_faces(faces, &document, &selfie, &mut findings) {
+        Ok(()) => (findings, None),
+        Err(e) => (findings, Some(format!("Face analysis failed: {}", e))),
+    }
+}
+
+fn match_faces(
+    faces: &FaceModel,
+    document: &DynamicImage,
+    selfie: &DynamicImage,
+    findings: &mut KycFindings,
+) -> Result<(), tract_onnx::prelude::TractError> {
+    let document_faces = faces.detect(document)?;
+    let selfie_faces = faces.detect(selfie)?;
+    findings.faces_checked = true;
+    findings.document_faces = document_faces.len();
+    findings.selfie_faces = selfie_faces.len();
+    findings.selfie_face_area = selfie_faces.first().map(|face| face.area());
+
+    // The most confident face on each side; extra faces are flagged separately
+    if let (Some(on_document), Some(on_selfie)) = (document_faces.first(), selfie_faces.first()) {
+        let a = faces.embed(document, on_document)?;
+        let b = faces.embed(selfie, on_selfie)?;
+        findings.similarity = Some(face_model::similarity(&a, &b));
+    }
+
+    Ok(())
+}
+
+fn image_distance(images: &ImageVerificationService, a: &DynamicImage, b: &DynamicImage) -> Option<u32> {
+    let a = images.generate_perceptual_hash(a).ok()?;
+    let b = images.generate_perceptual_hash(b).ok()?;
+    Some(a.chars().zip(b.chars()).filter(|(x, y)| x != y).count() as u32)
+}
+
+/// Every heuristic the submission failed
+pub fn flags(findings: &KycFindings) -> Vec<&'static str> {
+    let mut flags = Vec::new();
+
+    if findings.document_quality.is_some_and(|q| q < MIN_IMAGE_QUALITY) {
+        flags.push("low_quality_document");
+    }
+    if findings.selfie_quality.is_some_and(|q| q < MIN_IMAGE_QUALITY) {
+        flags.push("low_quality_selfie");
+    }
+    if findings.faces_checked {
+        if findings.document_faces == 0 {
+            flags.push("no_face_on_document");
+        }
+        if findings.selfie_faces == 0 {
+            flags.push("no_face_in_selfie");
+        }
+    }
+    if findings.selfie_faces > 1 {
+        flags.push("multiple_faces_in_selfie");
+    }
+    if findings.selfie_face_area.is_some_and(|area| area < MIN_SELFIE_FACE_AREA) {
+        flags.push("face_too_small");
+    }
+    if findings.image_distance.is_some_and(|d| d <= MIN_IMAGE_DISTANCE)
+        || findings.similarity.is_some_and(|s| s >= IDENTICAL_FACE)
+    {
+        flags.push("selfie_matches_document");
+    }
+    if findings.similarity.is_some_and(|s| s < MATCH_THRESHOLD) {
+        flags.push("face_mismatch");
+    }
+    if findings.registry_match == Some(false) {
+        flags.push("registry_mismatch");
+    }
+
+    flags
+}
+
+/// 1 when no liveness heuristic failed, a quarter less for each that did
+pub fn liveness_score(flags: &[&str]) -> f64 {
+    let failed = flags.iter().filter(|flag| LIVENESS_FLAGS.contains(flag)).count();
+    (1.0 - 0.25 * failed as f64).max(0.0)
+}
+
+/// 0 for a strong match on good images with a live selfie, up to 1
+pub fn risk_score(findings: &KycFindings, liveness: f64) -> f64 {
+    let match_risk = match findings.similarity {
+        Some(similarity) => ((STRONG_MATCH - similarity) / (STRONG_MATCH - NO_MATCH)).clamp(0.0, 1.0) as f64,
+        None => 1.0,
+    };
+    let quality = findings.document_quality.unwrap_or(0.0).min(findings.selfie_quality.unwrap_or(0.0));
+
+    (0.5 * match_risk + 0.3 * (1.0 - liveness) + 0.2 * (1.0 - quality)).clamp(0.0, 1.0)
+}
+
+/// Only clean, confident matches skip the reviewer
+pub fn decide(findings: &KycFindings, flags: &[&str], risk: f64) -> KycDecision {
+    let matched = findings.similarity.is_some_and(|s| s >= MATCH_THRESHOLD);
+
+    if matched && flags.is_empty() && risk <= AUTO_APPROVE_MAX_RISK {
+        KycDecision::AutoApproved
+    } else {
+        KycDecision::ManualReview
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clear_case() -> KycFindings {
+        KycFindings {
+            document_quality: Some(0.8),
+            selfie_quality: Some(0.85),
+            faces_checked: true,
+            document_faces: 1,
+            selfie_faces: 1,
+            selfie_face_area: Some(0.2),
+            similarity: Some(0.72),
+            image_distance: Some(30),
+            registry_match: None,
+        }
+    }
+
+    fn evaluate(findings: &KycFindings) -> (Vec<&'static str>, f64, KycDecision) {
+        let flags = flags(findings);
+        let risk = risk_score(findings, liveness_score(&flags));
+        let decision = decide(findings, &flags, risk);
+        (flags, risk, decision)
+    }
+
+    #[test]
+    fn test_clear_match_is_approved() {
+        let (flags, risk, decision) = evaluate(&clear_case());
+
+        assert!(flags.is_empty());
+        assert!(risk <= AUTO_APPROVE_MAX_RISK, "risk {}", risk);
+        assert_eq!(decision, KycDecision::AutoApproved);
+    }
+
+    #[test]
+    fn test_weak_match_goes_to_a_reviewer() {
+        let findings = KycFindings { similarity: Some(0.4), ..clear_case() };
+        let (flags, _, decision) = evaluate(&findings);
+
+        assert_eq!(flags, vec!["face_mismatch"]);
+        assert_eq!(decision, KycDecision::ManualReview);
+    }
+
+    #[test]
+    fn test_liveness_heuristics_block_approval() {
+        let photo_of_id = KycFindings { image_distance: Some(4), similarity: Some(0.99), ..clear_case() };
+        let (flags, _, decision) = evaluate(&photo_of_id);
+        assert_eq!(flags, vec!["selfie_matches_document"]);
+        assert_eq!(decision, KycDecision::ManualReview);
+
+        let crowd = KycFindings { selfie_faces: 3, selfie_face_area: Some(0.01), ..clear_case() };
+        let (flags, risk, decision) = evaluate(&crowd);
+        assert_eq!(flags, vec!["multiple_faces_in_selfie", "face_too_small"]);
+        assert_eq!(liveness_score(&flags), 0.5);
+        assert!(risk > clear_case_risk());
+        assert_eq!(decision, KycDecision::ManualReview);
+    }
+
+    #[test]
+    fn test_registry_mismatch_goes_to_a_reviewer() {
+        let matched = KycFindings { registry_match: Some(true), ..clear_case() };
+        assert_eq!(evaluate(&matched).2, KycDecision::AutoApproved);
+
+        let mismatch = KycFindings { registry_match: Some(false), ..clear_case() };
+        let (flags, _, decision) = evaluate(&mismatch);
+        assert_eq!(flags, vec!["registry_mismatch"]);
+        assert_eq!(decision, KycDecision::ManualReview);
+    }
+
+    #[test]
+    fn test_missing_faces_are_flagged() {
+        let findings = KycFindings { document_faces: 0, similarity: None, ..clear_case() };
+        let (flags, risk, decision) = evaluate(&findings);
+
+        assert_eq!(flags, vec!["no_face_on_document"]);
+        assert!(risk >= 0.5);
+        assert_eq!(decision, KycDecision::ManualReview);
+    }
+
+    fn clear_case_risk() -> f64 {
+        evaluate(&clear_case()).1
+    }
+}
@@ -16,6 +16,7 @@ use crate::{
         error::ServiceError,
     },
     dtos::labordtos::*,
+    utils::gazetteer,
 };
 
 #[derive(Debug, Clone)]
@@ -50,13 +51,15 @@ pub async fn create_job_with_escrow(
     employer_id: Uuid,
     job_data: CreateJobDto,
 ) -> Result<Job, ServiceError> {
+    let (location_state, location_city) = gazetteer::normalise_place(&job_data.location_state, &job_data.location_city);
+
     let job = self.db_client.create_job(
         employer_id,
         job_data.category,
         job_data.title,
         job_data.description,
-        job_data.location_state,
-        job_data.location_city,
+        location_state,
+        location_city,
         job_data.location_address,
         job_data.budget,
         job_data.estimated_duration_days,
@@ -0,0 +1,112 @@
+// service/login_activity_service.rs
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::{
+    db::{db::DBClient, logindb::LoginEventExt},
+    mail::mails,
+    models::{
+        sessionmodels::{LoginEvent, LoginOutcome, SessionClient},
+        usermodel::User,
+    },
+    service::{error::ServiceError, session_service::describe_device},
+};
+
+/// Most login events shown to a user at once
+pub const LOGIN_HISTORY_LIMIT: i64 = 50;
+
+/// Keeps each account's sign-in history and emails the owner when a sign-in
+/// comes from a device or country the account has not used before. Recording
+/// never fails the login it describes.
+#[derive(Debug)]
+pub struct LoginActivityService {
+    db_client: Arc<DBClient>,
+}
+
+impl LoginActivityService {
+    pub fn new(db_client: Arc<DBClient>) -> Self {
+        Self { db_client }
+    }
+
+    /// A refused attempt; `user_id` is None when the identifier matched no account
+    pub async fn record_failure(
+        &self,
+        user_id: Option<Uuid>,
+        identifier: &str,
+        outcome: LoginOutcome,
+        client: &SessionClient,
+    ) {
+        let client = named(client);
+        if let Err(e) = self.db_client
+            .create_login_event(user_id, identifier, outcome, &client, false, false)
+            .await
+        {
+            tracing::warn!("Could not record login attempt: {}", e);
+        }
+    }
+
+    pub async fn record_success(&self, user: &User, client: &SessionClient) {
+        let client = named(client);
+
+        let familiarity = match self.db_client.get_login_familiarity(user.id, &client).await {
+            Ok(familiarity) => familiarity,
+            Err(e) => {
+                tracing::warn!("Could not read login history for {}: {}", user.id, e);
+                return;
+            }
+        };
+        let new_device = familiarity.is_new_device(&client);
+        let new_location = familiarity.is_new_location(&client);
+
+        let event = match self.db_client
+            .create_login_event(Some(user.id), &user.email, LoginOutcome::Success, &client, new_device, new_location)
+            .await
+        {
+            Ok(event) => event,
+            Err(e) => {
+                tracing::warn!("Could not record login for {}: {}", user.id, e);
+                return;
+            }
+        };
+
+        if new_device || new_location {
+            send_alert(user, event);
+        }
+    }
+
+    pub async fn history(&self, user_id: Uuid, limit: i64) -> Result<Vec<LoginEvent>, ServiceError> {
+        Ok(self.db_client.get_login_events(user_id, limit.clamp(1, LOGIN_HISTORY_LIMIT)).await?)
+    }
+}
+
+/// The client with a device name, guessed from the user agent if not given
+fn named(client: &SessionClient) -> SessionClient {
+    let mut client = client.clone();
+    if client.device_name.is_none() {
+        client.device_name = client.user_agent.as_deref().and_then(describe_device);
+    }
+    client
+}
+
+/// Emailed in the background so the login does not wait on the mail server
+fn send_alert(user: &User, event: LoginEvent) {
+    let (email, username, locale) = (user.email.clone(), user.username.clone(), user.locale);
+
+    tokio::spawn(async move {
+        let signed_in_at = event.created_at.format("%d %b %Y, %H:%M UTC").to_string();
+        if let Err(e) = mails::send_login_alert_email(
+            &email,
+            &username,
+            locale,
+            event.device_name.as_deref(),
+            event.country.as_deref(),
+            event.ip_address.as_deref(),
+            &signed_in_at,
+        )
+        .await
+        {
+            tracing::warn!("Could not send login alert for {}: {}", event.id, e);
+        }
+    });
+}
@@ -1,13 +1,17 @@
 pub mod referral;
 pub mod google_oauth;
 pub mod wallet_verification;
-// pub mod property_service;
+pub mod property_service;
 pub mod payment_provider;
 pub mod error;
 pub mod labour_service;
 pub mod escrow_service;
 pub mod dispute_service;
 pub mod notification_service;
+pub mod notification_dispatcher;
+pub mod notification_channels;
+pub mod notification_templates;
+pub mod notification_stream;
 pub mod audit_service;
 pub mod matching_service;
 pub mod trust_service;
@@ -15,4 +19,24 @@ pub mod verification_service;
 pub mod subscriptions;
 pub mod background_jobs;
 pub mod vendor_order_service;
-pub mod subscription_service;
\ No newline at end of file
+pub mod subscription_service;
+pub mod viewing_service;
+pub mod tenancy_service;
+pub mod offer_service;
+pub mod image_verification;
+pub mod saved_property_service;
+pub mod certificate_service;
+pub mod storage_backends;
+pub mod storage_service;
+pub mod session_service;
+pub mod two_factor_service;
+pub mod role_service;
+pub mod face_model;
+pub mod kyc_service;
+pub mod identity_providers;
+pub mod identity_service;
+pub mod privacy_service;
+pub mod sms;
+pub mod otp_service;
+pub mod attempt_guard;
+pub mod login_activity_service;
\ No newline at end of file
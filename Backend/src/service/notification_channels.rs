@@ -0,0 +1,310 @@
+// service/notification_channels.rs
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    db::{db::DBClient, notificationdb::NotificationExt},
+    mail::mails,
+    models::{notificationmodels::NotificationChannel, usermodel::User},
+    service::{
+        notification_stream::{self, StreamEvent},
+        sms::{self, SmsSender},
+    },
+};
+
+/// A notification after template rendering. Stored as the delivery payload so
+/// retries replay exactly what the first attempt would have sent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenderedNotification {
+    pub template_key: String,
+    pub notification_type: String,
+    pub related_id: Option<Uuid>,
+    pub title: String,
+    pub body: String,
+    pub short_text: String,
+    pub email_template: String,
+    pub email_subject: String,
+    pub email_placeholders: Vec<(String, String)>,
+}
+
+#[async_trait]
+pub trait ChannelAdapter: Send + Sync {
+    fn channel(&self) -> NotificationChannel;
+
+    /// Where this channel reaches the user, or None if they have nothing on file
+    async fn recipient(&self, user: &User) -> Option<String>;
+
+    async fn deliver(
+        &self,
+        user_id: Uuid,
+        recipient: &str,
+        message: &RenderedNotification,
+    ) -> Result<(), String>;
+}
+
+pub struct InAppAdapter {
+    db_client: Arc<DBClient>,
+}
+
+impl InAppAdapter {
+    pub fn new(db_client: Arc<DBClient>) -> Self {
+        Self { db_client }
+    }
+}
+
+#[async_trait]
+impl ChannelAdapter for InAppAdapter {
+    fn channel(&self) -> NotificationChannel {
+        NotificationChannel::InApp
+    }
+
+    async fn recipient(&self, user: &User) -> Option<String> {
+        Some(user.id.to_string())
+    }
+
+    async fn deliver(
+        &self,
+        user_id: Uuid,
+        _recipient: &str,
+        message: &RenderedNotification,
+    ) -> Result<(), String> {
+        let notification = self.db_client
+            .create_notification(
+                user_id,
+                &message.title,
+                &message.body,
+                &message.notification_type,
+                message.related_id,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        // The row is stored; a failed count only costs connected clients an accurate badge
+        let unread_count = self.db_client.count_unread_notifications(user_id).await.unwrap_or_default();
+        notification_stream::publish(StreamEvent::Notification {
+            user_id,
+            notification,
+            unread_count,
+        })
+        .await;
+
+        Ok(())
+    }
+}
+
+pub struct EmailAdapter;
+
+#[async_trait]
+impl ChannelAdapter for EmailAdapter {
+    fn channel(&self) -> NotificationChannel {
+        NotificationChannel::Email
+    }
+
+    async fn recipient(&self, user: &User) -> Option<String> {
+        Some(user.email.clone())
+    }
+
+    async fn deliver(
+        &self,
+        _user_id: Uuid,
+        recipient: &str,
+        message: &RenderedNotification,
+    ) -> Result<(), String> {
+        mails::send_templated_email(
+            recipient,
+            &message.email_subject,
+            &message.email_template,
+            &message.email_placeholders,
+        )
+        .await
+        .map_err(|e| e.to_string())
+    }
+}
+
+/// SMS through whichever sender `SMS_PROVIDER` names; see `service::sms`
+pub struct SmsAdapter {
+    sender: Arc<dyn SmsSender>,
+}
+
+impl SmsAdapter {
+    pub fn from_env() -> Option<Self> {
+        match sms::from_env() {
+            Ok(sender) => sender.map(|sender| Self { sender }),
+            Err(e) => {
+                tracing::error!("SMS notifications disabled: {}", e);
+                None
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ChannelAdapter for SmsAdapter {
+    fn channel(&self) -> NotificationChannel {
+        NotificationChannel::Sms
+    }
+
+    async fn recipient(&self, user: &User) -> Option<String> {
+        user.phone_number.clone().filter(|phone| !phone.is_empty())
+    }
+
+    async fn deliver(
+        &self,
+        _user_id: Uuid,
+        recipient: &str,
+        message: &RenderedNotification,
+    ) -> Result<(), String> {
+        self.sender
+            .send(recipient, &message.short_text)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// WhatsApp Business Cloud API, enabled when `WHATSAPP_ACCESS_TOKEN` and
+/// `WHATSAPP_PHONE_NUMBER_ID` are set
+pub struct WhatsAppAdapter {
+    client: Client,
+    access_token: String,
+    phone_number_id: String,
+}
+
+impl WhatsAppAdapter {
+    pub fn from_env() -> Option<Self> {
+        let access_token = std::env::var("WHATSAPP_ACCESS_TOKEN").ok().filter(|t| !t.is_empty())?;
+        let phone_number_id = std::env::var("WHATSAPP_PHONE_NUMBER_ID").ok().filter(|id| !id.is_empty())?;
+
+        Some(Self { client: Client::new(), access_token, phone_number_id })
+    }
+}
+
+#[async_trait]
+impl ChannelAdapter for WhatsAppAdapter {
+    fn channel(&self) -> NotificationChannel {
+        NotificationChannel::WhatsApp
+    }
+
+    async fn recipient(&self, user: &User) -> Option<String> {
+        user.phone_number.clone().filter(|phone| !phone.is_empty())
+    }
+
+    async fn deliver(
+        &self,
+        _user_id: Uuid,
+        recipient: &str,
+        message: &RenderedNotification,
+    ) -> Result<(), String> {
+        let payload = serde_json::json!({
+            "messaging_product": "whatsapp",
+            "to": recipient,
+            "type": "text",
+            "text": { "body": message.short_text }
+        });
+
+        let response = self.client
+            .post(format!("https://graph.facebook.com/v18.0/{}/messages", self.phone_number_id))
+            .bearer_auth(&self.access_token)
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if !response.status().is_success() {
+            return Err(format!("WhatsApp API returned {}", response.status()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Web and mobile push through Firebase Cloud Messaging, enabled when
+/// `FCM_SERVER_KEY` is set. Targets every token the user has registered.
+pub struct WebPushAdapter {
+    client: Client,
+    server_key: String,
+    db_client: Arc<DBClient>,
+}
+
+impl WebPushAdapter {
+    pub fn from_env(db_client: Arc<DBClient>) -> Option<Self> {
+        let server_key = std::env::var("FCM_SERVER_KEY").ok().filter(|key| !key.is_empty())?;
+
+        Some(Self { client: Client::new(), server_key, db_client })
+    }
+}
+
+#[async_trait]
+impl ChannelAdapter for WebPushAdapter {
+    fn channel(&self) -> NotificationChannel {
+        NotificationChannel::Push
+    }
+
+    async fn recipient(&self, user: &User) -> Option<String> {
+        match self.db_client.get_push_subscriptions(user.id).await {
+            Ok(subscriptions) if !subscriptions.is_empty() => Some(user.id.to_string()),
+            _ => None,
+        }
+    }
+
+    async fn deliver(
+        &self,
+        user_id: Uuid,
+        _recipient: &str,
+        message: &RenderedNotification,
+    ) -> Result<(), String> {
+        let tokens: Vec<String> = self.db_client
+            .get_push_subscriptions(user_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|subscription| subscription.token)
+            .collect();
+        if tokens.is_empty() {
+            return Err("No push subscriptions registered".to_string());
+        }
+
+        let payload = serde_json::json!({
+            "registration_ids": tokens,
+            "notification": {
+                "title": message.title,
+                "body": message.short_text,
+            },
+            "data": {
+                "notification_type": message.notification_type,
+                "related_id": message.related_id,
+            }
+        });
+
+        let response = self.client
+            .post("https://fcm.googleapis.com/fcm/send")
+            .header("Authorization", format!("key={}", self.server_key))
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if !response.status().is_success() {
+            return Err(format!("FCM returned {}", response.status()));
+        }
+
+        let body: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+        let results = body["results"].as_array().cloned().unwrap_or_default();
+
+        // Drop tokens FCM no longer recognises so they stop counting as recipients
+        for (token, result) in tokens.iter().zip(results.iter()) {
+            if matches!(result["error"].as_str(), Some("NotRegistered") | Some("InvalidRegistration")) {
+                let _ = self.db_client.delete_push_subscription(user_id, token).await;
+            }
+        }
+
+        if body["success"].as_i64().unwrap_or(0) == 0 {
+            return Err("FCM accepted none of the user's push tokens".to_string());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,476 @@
+// service/notification_dispatcher.rs
+use std::{collections::HashMap, fmt, sync::{Arc, LazyLock}};
+
+use chrono::{Duration, Utc};
+use redis::aio::ConnectionManager;
+use uuid::Uuid;
+
+use crate::{
+    db::{db::DBClient, notificationdb::NotificationExt, userdb::UserExt},
+    mail::secure_sendmail::RateLimiter,
+    models::{notificationmodels::*, usermodel::Locale},
+    service::{
+        notification_channels::*,
+        notification_templates::{render, NotificationTemplate},
+    },
+    utils::i18n,
+};
+
+const RETRY_BATCH_SIZE: i64 = 100;
+
+/// Per-user caps as (messages, window in minutes). In-app is never limited.
+fn channel_rate_limit(channel: NotificationChannel) -> Option<(usize, i64)> {
+    match channel {
+        NotificationChannel::InApp => None,
+        NotificationChannel::Email => Some((20, 60)),
+        NotificationChannel::Sms => Some((5, 60)),
+        NotificationChannel::WhatsApp => Some((10, 60)),
+        NotificationChannel::Push => Some((30, 60)),
+    }
+}
+
+/// When a delivery held back by the channel's cap is tried again
+fn rate_limit_resume_at(channel: NotificationChannel) -> chrono::DateTime<Utc> {
+    let window = channel_rate_limit(channel).map(|(_, window)| window).unwrap_or(60);
+    Utc::now() + Duration::minutes(window)
+}
+
+// Used when Redis is unavailable; only accurate per instance
+static FALLBACK_LIMITERS: LazyLock<Vec<(NotificationChannel, RateLimiter)>> = LazyLock::new(|| {
+    [
+        NotificationChannel::Email,
+        NotificationChannel::Sms,
+        NotificationChannel::WhatsApp,
+        NotificationChannel::Push,
+    ]
+    .into_iter()
+    .filter_map(|channel| {
+        channel_rate_limit(channel).map(|(max, window)| (channel, RateLimiter::new(max, window)))
+    })
+    .collect()
+});
+
+/// What to send and to whom; rendered against the template registry
+#[derive(Debug, Clone)]
+pub struct NotificationRequest {
+    pub user_id: Uuid,
+    pub template_key: &'static str,
+    pub variables: HashMap<String, String>,
+    /// Variables holding catalogue keys, translated into the recipient's locale at render time
+    pub translated_variables: HashMap<String, String>,
+    pub related_id: Option<Uuid>,
+}
+
+impl NotificationRequest {
+    pub fn new(user_id: Uuid, template_key: &'static str) -> Self {
+        Self {
+            user_id,
+            template_key,
+            variables: HashMap::new(),
+            translated_variables: HashMap::new(),
+            related_id: None,
+        }
+    }
+
+    pub fn var(mut self, key: &str, value: impl ToString) -> Self {
+        self.variables.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    pub fn var_t(mut self, key: &str, catalogue_key: &str) -> Self {
+        self.translated_variables.insert(key.to_string(), catalogue_key.to_string());
+        self
+    }
+
+    pub fn has_var(&self, key: &str) -> bool {
+        self.variables.contains_key(key) || self.translated_variables.contains_key(key)
+    }
+
+    pub fn related(mut self, related_id: Option<Uuid>) -> Self {
+        self.related_id = related_id;
+        self
+    }
+}
+
+pub fn render_notification(
+    template: &NotificationTemplate,
+    locale: Locale,
+    recipient_name: &str,
+    request: &NotificationRequest,
+) -> RenderedNotification {
+    let mut variables = request.variables.clone();
+    for (key, catalogue_key) in &request.translated_variables {
+        variables.insert(key.clone(), i18n::t(locale, catalogue_key));
+    }
+    let variables = &variables;
+
+    let title = render(&template.title(locale), variables);
+    let body = render(&template.body(locale), variables);
+    let short_text = template
+        .short_text(locale)
+        .map(|text| render(text, variables))
+        .unwrap_or_else(|| format!("{}: {}", title, body));
+
+    let app_url = std::env::var("APP_URL").unwrap_or_else(|_| "https://verinest.xyz".to_string());
+    let app_url = app_url.trim_end_matches('/');
+
+    let email_template = template.email_template.unwrap_or("Mail_notification.html").to_string();
+    let email_subject = template
+        .email_subject(locale)
+        .map(|subject| render(subject, variables))
+        .unwrap_or_else(|| title.clone());
+
+    // Translated copy first: it may contain the placeholders filled below
+    let mut email_placeholders = i18n::email_strings(locale, &email_template);
+    email_placeholders.extend(
+        variables
+            .iter()
+            .map(|(key, value)| (format!("{{{{{}}}}}", key), value.clone())),
+    );
+    email_placeholders.extend([
+        ("{{username}}".to_string(), recipient_name.to_string()),
+        ("{{title}}".to_string(), title.clone()),
+        ("{{message}}".to_string(), body.replace('\n', "<br>")),
+        ("{{dashboard_url}}".to_string(), format!("{}/dashboard", app_url)),
+        ("{{verification_url}}".to_string(), format!("{}/verification", app_url)),
+    ]);
+
+    RenderedNotification {
+        template_key: template.key.to_string(),
+        notification_type: template.notification_type.to_string(),
+        related_id: request.related_id,
+        title,
+        body,
+        short_text,
+        email_template,
+        email_subject,
+        email_placeholders,
+    }
+}
+
+/// Fans a notification out to every channel adapter the user's preferences allow,
+/// tracking each non in-app delivery so failures can be retried
+#[derive(Clone)]
+pub struct NotificationDispatcher {
+    db_client: Arc<DBClient>,
+    adapters: Vec<Arc<dyn ChannelAdapter>>,
+}
+
+impl fmt::Debug for NotificationDispatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NotificationDispatcher")
+            .field("channels", &self.adapters.iter().map(|a| a.channel()).collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl NotificationDispatcher {
+    /// In-app and email are always available; SMS, WhatsApp and push are
+    /// registered only when their provider credentials are configured
+    pub fn new(db_client: Arc<DBClient>) -> Self {
+        let mut adapters: Vec<Arc<dyn ChannelAdapter>> = vec![
+            Arc::new(InAppAdapter::new(db_client.clone())),
+            Arc::new(EmailAdapter),
+        ];
+        if let Some(sms) = SmsAdapter::from_env() {
+            adapters.push(Arc::new(sms));
+        }
+        if let Some(whatsapp) = WhatsAppAdapter::from_env() {
+            adapters.push(Arc::new(whatsapp));
+        }
+        if let Some(push) = WebPushAdapter::from_env(db_client.clone()) {
+            adapters.push(Arc::new(push));
+        }
+
+        Self { db_client, adapters }
+    }
+
+    fn adapter(&self, channel: NotificationChannel) -> Option<&Arc<dyn ChannelAdapter>> {
+        self.adapters.iter().find(|adapter| adapter.channel() == channel)
+    }
+
+    pub async fn dispatch(&self, request: NotificationRequest) -> Result<(), String> {
+        let template = NotificationTemplate::find(request.template_key)
+            .ok_or_else(|| format!("Unknown notification template: {}", request.template_key))?;
+
+        let user = self.db_client
+            .get_user(Some(request.user_id), None, None, None)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("User {} not found", request.user_id))?;
+
+        let preferences = match self.db_client.get_notification_preference_set(user.id).await {
+            Ok(preferences) => preferences,
+            Err(e) => {
+                tracing::warn!("Failed to load notification preferences for user {}: {}, using defaults", user.id, e);
+                NotificationPreferenceSet {
+                    settings: NotificationSettings::default_for(user.id),
+                    overrides: vec![],
+                }
+            }
+        };
+
+        for name in template.variables {
+            if !request.has_var(name) {
+                tracing::warn!("Notification {} sent without variable {}", template.key, name);
+            }
+        }
+
+        let message = render_notification(template, user.locale, &user.name, &request);
+        let category = NotificationCategory::from_notification_type(template.notification_type);
+        let now = Utc::now();
+
+        for adapter in &self.adapters {
+            let channel = adapter.channel();
+            let decision = preferences.decide(category, channel, now);
+            if decision == DeliveryDecision::Skip {
+                continue;
+            }
+
+            // The in-app row is the notification itself, so its failure is the caller's failure
+            if channel == NotificationChannel::InApp {
+                adapter.deliver(user.id, "", &message).await?;
+                continue;
+            }
+
+            let Some(recipient) = adapter.recipient(&user).await else {
+                continue;
+            };
+
+            match decision {
+                DeliveryDecision::Defer if channel == NotificationChannel::Email => {
+                    if let Err(e) = self.db_client
+                        .enqueue_digest_item(user.id, template.notification_type, &message.title, &message.body)
+                        .await
+                    {
+                        tracing::warn!("Failed to queue digest email for user {}: {}", user.id, e);
+                    }
+                }
+                DeliveryDecision::Defer => {
+                    let resume_at = preferences.settings.quiet_hours_end_after(now);
+                    self.record_delivery(channel, user.id, &recipient, &message, DeliveryStatus::Pending, resume_at)
+                        .await;
+                }
+                _ => self.send_tracked(adapter.as_ref(), user.id, &recipient, &message).await,
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn record_delivery(
+        &self,
+        channel: NotificationChannel,
+        user_id: Uuid,
+        recipient: &str,
+        message: &RenderedNotification,
+        status: DeliveryStatus,
+        next_attempt_at: chrono::DateTime<Utc>,
+    ) -> Option<NotificationDelivery> {
+        let payload = serde_json::to_value(message).unwrap_or_default();
+
+        match self.db_client
+            .create_notification_delivery(
+                user_id,
+                channel,
+                &message.template_key,
+                Some(recipient),
+                payload,
+                status,
+                next_attempt_at,
+            )
+            .await
+        {
+            Ok(delivery) => Some(delivery),
+            Err(e) => {
+                tracing::warn!("Failed to record {:?} delivery for user {}: {}", channel, user_id, e);
+                None
+            }
+        }
+    }
+
+    async fn send_tracked(
+        &self,
+        adapter: &dyn ChannelAdapter,
+        user_id: Uuid,
+        recipient: &str,
+        message: &RenderedNotification,
+    ) {
+        let channel = adapter.channel();
+
+        // Held back, not dropped: the retry job sends it once the window has passed
+        if !self.check_rate_limit(channel, user_id).await {
+            tracing::warn!("{:?} rate limit reached for user {}, delaying {}", channel, user_id, message.template_key);
+            self.record_delivery(channel, user_id, recipient, message, DeliveryStatus::Pending, rate_limit_resume_at(channel))
+                .await;
+            return;
+        }
+
+        if let Some(delivery) = self
+            .record_delivery(channel, user_id, recipient, message, DeliveryStatus::Pending, Utc::now())
+            .await
+        {
+            self.attempt(adapter, &delivery, message).await;
+        }
+    }
+
+    async fn attempt(
+        &self,
+        adapter: &dyn ChannelAdapter,
+        delivery: &NotificationDelivery,
+        message: &RenderedNotification,
+    ) {
+        let recipient = delivery.recipient.as_deref().unwrap_or_default();
+
+        match adapter.deliver(delivery.user_id, recipient, message).await {
+            Ok(()) => {
+                if let Err(e) = self.db_client.mark_delivery_sent(delivery.id).await {
+                    tracing::warn!("Failed to mark delivery {} as sent: {}", delivery.id, e);
+                }
+            }
+            Err(error) => {
+                let retry_at = Utc::now() + NotificationDelivery::retry_delay(delivery.attempts + 1);
+                match self.db_client.mark_delivery_failed(delivery.id, &error, retry_at).await {
+                    Ok(updated) if updated.status == DeliveryStatus::Failed => tracing::error!(
+                        "{:?} delivery {} failed after {} attempts: {}",
+                        updated.channel, updated.id, updated.attempts, error
+                    ),
+                    Ok(updated) => tracing::warn!(
+                        "{:?} delivery {} failed, retrying at {}: {}",
+                        updated.channel, updated.id, retry_at, error
+                    ),
+                    Err(e) => tracing::warn!("Failed to record failure for delivery {}: {}", delivery.id, e),
+                }
+            }
+        }
+    }
+
+    async fn check_rate_limit(&self, channel: NotificationChannel, user_id: Uuid) -> bool {
+        let Some((max_messages, window_minutes)) = channel_rate_limit(channel) else {
+            return true;
+        };
+
+        if let Some(redis) = &self.db_client.redis_client {
+            let key = format!("notification_rate:{:?}:{}", channel, user_id);
+            let mut conn = ConnectionManager::clone(redis);
+            let count: Result<i64, redis::RedisError> = redis::cmd("INCR").arg(&key).query_async(&mut conn).await;
+
+            if let Ok(count) = count {
+                if count == 1 {
+                    let _: Result<(), redis::RedisError> = redis::cmd("EXPIRE")
+                        .arg(&key)
+                        .arg(window_minutes * 60)
+                        .query_async(&mut conn)
+                        .await;
+                }
+                return count <= max_messages as i64;
+            }
+        }
+
+        FALLBACK_LIMITERS
+            .iter()
+            .find(|(limited, _)| *limited == channel)
+            .map(|(_, limiter)| limiter.is_allowed(&user_id.to_string()))
+            .unwrap_or(true)
+    }
+
+    /// Retry failed deliveries and send the ones held back by quiet hours or rate limits
+    pub async fn retry_due_deliveries(&self) -> Result<usize, String> {
+        let due = self.db_client
+            .claim_due_deliveries(RETRY_BATCH_SIZE)
+            .await
+            .map_err(|e| e.to_string())?;
+        let count = due.len();
+
+        for delivery in due {
+            let Some(adapter) = self.adapter(delivery.channel) else {
+                let _ = self.db_client
+                    .update_delivery_status(delivery.id, DeliveryStatus::Skipped, Utc::now(), Some("Channel is not configured"))
+                    .await;
+                continue;
+            };
+
+            let message: RenderedNotification = match serde_json::from_value(delivery.payload.clone()) {
+                Ok(message) => message,
+                Err(e) => {
+                    let _ = self.db_client
+                        .update_delivery_status(delivery.id, DeliveryStatus::Failed, Utc::now(), Some(&e.to_string()))
+                        .await;
+                    continue;
+                }
+            };
+
+            if !self.check_rate_limit(delivery.channel, delivery.user_id).await {
+                let _ = self.db_client
+                    .update_delivery_status(delivery.id, DeliveryStatus::Pending, rate_limit_resume_at(delivery.channel), None)
+                    .await;
+                continue;
+            }
+
+            self.attempt(adapter.as_ref(), &delivery, &message).await;
+        }
+
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_uses_dedicated_email_template() {
+        let template = NotificationTemplate::find("deposit").unwrap();
+        let request = NotificationRequest::new(Uuid::new_v4(), "deposit")
+            .var("amount", "5000.00")
+            .var("reference", "REF123");
+
+        let message = render_notification(template, Locale::En, "Ada", &request);
+
+        assert_eq!(message.title, "Deposit Successful");
+        assert_eq!(message.email_template, "Deposit.html");
+        assert_eq!(message.short_text, "VeriNest: Deposit of NGN5000.00 successful. Ref: REF123");
+        assert!(message.email_placeholders.contains(&("{{amount}}".to_string(), "5000.00".to_string())));
+        assert!(message.email_placeholders.contains(&("{{username}}".to_string(), "Ada".to_string())));
+    }
+
+    #[test]
+    fn test_render_falls_back_to_generic_email_and_short_text() {
+        let template = NotificationTemplate::find("order_shipped").unwrap();
+        let request = NotificationRequest::new(Uuid::new_v4(), "order_shipped").var("order_number", "VN-42");
+
+        let message = render_notification(template, Locale::En, "Ada", &request);
+
+        assert_eq!(message.email_template, "Mail_notification.html");
+        assert_eq!(message.email_subject, "Order Shipped");
+        assert_eq!(message.short_text, "Order Shipped: Your order #VN-42 has been shipped");
+    }
+
+    #[test]
+    fn test_render_uses_recipient_locale() {
+        let template = NotificationTemplate::find("transfer_sent").unwrap();
+        let request = NotificationRequest::new(Uuid::new_v4(), "transfer_sent")
+            .var("amount", "100.00")
+            .var("reference", "REF9")
+            .var_t("direction_label", "value.direction.sent");
+
+        let message = render_notification(template, Locale::Ha, "Musa", &request);
+
+        assert_eq!(message.title, "An Tura Kuɗi");
+        assert_eq!(message.email_subject, "An Tura Kuɗi");
+        assert!(message.email_placeholders.contains(&("{{direction_label}}".to_string(), "An Aika".to_string())));
+        assert!(message.email_placeholders.contains(&("{{t:common.hello}}".to_string(), "Sannu,".to_string())));
+
+        let hello = message.email_placeholders.iter().position(|(key, _)| key == "{{t:common.hello}}").unwrap();
+        let username = message.email_placeholders.iter().position(|(key, _)| key == "{{username}}").unwrap();
+        assert!(hello < username);
+    }
+
+    #[test]
+    fn test_every_limited_channel_has_a_fallback_limiter() {
+        for channel in [NotificationChannel::Email, NotificationChannel::Sms, NotificationChannel::WhatsApp, NotificationChannel::Push] {
+            assert!(FALLBACK_LIMITERS.iter().any(|(limited, _)| *limited == channel));
+        }
+        assert!(channel_rate_limit(NotificationChannel::InApp).is_none());
+    }
+}
@@ -1,110 +1,79 @@
 use std::sync::Arc;
 use uuid::Uuid;
+use chrono::{DateTime, NaiveDate, Utc};
 
 use crate::{
-    db::{db::DBClient, userdb::UserExt}, mail::mails, 
+    db::{db::DBClient, notificationdb::NotificationExt, userdb::UserExt}, mail::mails,
     models::{
-        chatnodels::Message, labourmodel::*, usermodel::VerificationStatus, vendormodels::{ServiceDispute, ServiceOrder, SubscriptionTier, VendorService}, verificationmodels::VerificationDocument
-    }
+        chatnodels::Message, labourmodel::*, offermodels::PropertyOffer, propertymodel::Property, tenancymodels::{RentPayment, Tenancy}, viewingmodels::PropertyViewing, vendormodels::{ServiceDispute, ServiceOrder, SubscriptionTier, VendorService}, verificationmodels::VerificationDocument
+    },
+    service::notification_dispatcher::{NotificationDispatcher, NotificationRequest},
 };
 use crate::db::labourdb::LaborExt;
 
 #[derive(Clone, Debug)]
 pub struct NotificationService {
     db_client: Arc<DBClient>,
+    dispatcher: NotificationDispatcher,
 }
 
 impl NotificationService {
     pub fn new(db_client: Arc<DBClient>) -> Self {
-        Self { db_client }
-    }
-
-    // Helper function to send emails with graceful error handling
-    async fn send_email_gracefully(
-        &self,
-        to_email: &str,
-        email_type: &str,
-        result: Result<(), String>,
-    ) {
-        match result {
-            Ok(_) => {
-                tracing::info!("{} email sent successfully to {}", email_type, to_email);
-            }
-            Err(e) => {
-                tracing::warn!("Failed to send {} email to {}: {}", email_type, to_email, e);
-                // Don't return error - notification was already created
-            }
-        }
+        let dispatcher = NotificationDispatcher::new(db_client.clone());
+        Self { db_client, dispatcher }
     }
-    
-    // Helper to create notification and send email
-    async fn create_notification_with_email(
-        &self,
-        user_id: Uuid,
-        title: String,
-        message: String,
-        notification_type: String,
-        related_id: Option<Uuid>,
-        send_email: bool,
-    ) -> Result<(), String> {
-        // Create in-app notification first
-        sqlx::query(
-            r#"
-            INSERT INTO notifications (user_id, title, message, notification_type, related_id)
-            VALUES ($1, $2, $3, $4, $5)
-            "#
-        )
-        .bind(user_id)
-        .bind(&title)
-        .bind(&message)
-        .bind(&notification_type)
-        .bind(related_id)
-        .execute(&self.db_client.pool)
-        .await
+
+    /// Retry failed deliveries and release those held back by quiet hours
+    pub async fn retry_due_deliveries(&self) -> Result<usize, String> {
+        self.dispatcher.retry_due_deliveries().await
+    }
+
+    /// Send the combined email for everything held back by quiet hours or a digest preference
+    pub async fn send_pending_digest(&self, user_id: Uuid) -> Result<(), String> {
+        let preferences = self.db_client
+            .get_notification_preference_set(user_id)
+            .await
             .map_err(|e| e.to_string())?;
-        
-        // Send email if requested (non-blocking, continue even if email fails)
-        if send_email {
-            if let Ok(Some(user)) = self.db_client.get_user(Some(user_id), None, None, None).await {
-                // Send email based on notification type
-                match self.send_notification_email(&user.email, &user.name, &title, &message, &notification_type).await {
-                    Ok(_) => {
-                        tracing::info!("Email sent successfully for notification to user {}", user_id);
-                    }
-                    Err(e) => {
-                        tracing::warn!("Email sending failed for notification to user {}: {}, but notification was created", user_id, e);
-                        // Continue execution even if email fails
-                    }
-                }
-            }
+        let now = Utc::now();
+
+        if !preferences.settings.digest_due(now) {
+            return Ok(());
         }
-        
-        Ok(())
-    }
-    
-    async fn send_notification_email(
-        &self,
-        to_email: &str,
-        username: &str,
-        title: &str,
-        message: &str,
-        notification_type: &str,
-    ) -> Result<(), String> {
-        // Use appropriate email template based on notification type
-        mails::send_notification_email(
-            to_email, 
-            username, 
-            title, 
-            message, 
-            notification_type
-        ).await
-        .map_err(|e| e.to_string())?;
 
+        let items = self.db_client
+            .get_pending_digest_items(user_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        if items.is_empty() {
+            return Ok(());
+        }
+
+        let user = self.db_client
+            .get_user(Some(user_id), None, None, None)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("User {} not found", user_id))?;
+
+        let entries: Vec<(String, String)> = items
+            .iter()
+            .map(|item| (item.title.clone(), item.message.clone()))
+            .collect();
+
+        mails::send_notification_digest_email(&user.email, &user.name, user.locale, &entries)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let item_ids: Vec<Uuid> = items.iter().map(|item| item.id).collect();
+        self.db_client
+            .mark_digest_sent(user_id, &item_ids, now)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        tracing::info!("Sent digest of {} notifications to user {}", item_ids.len(), user_id);
         Ok(())
-         
     }
-    
-    // Job-related notifications with email
+
+    // Job-related notifications
     pub async fn notify_new_job(&self, job: &Job) -> Result<(), String> {
         // Notify all workers in the same state and category
         let workers = self.db_client
@@ -116,57 +85,32 @@ impl NotificationService {
             )
             .await
             .map_err(|e| e.to_string())?;
-        
+
         for worker_profile in workers {
-            self.create_notification_with_email(
-                worker_profile.user_id,
-                "New Job Available".to_string(),
-                format!("A new {} job is available in {}: {}", 
-                    job.category.to_str(), job.location_city, job.title),
-                "new_job".to_string(),
-                Some(job.id),
-                false, // Don't send email for every new job
-            ).await
-            .map_err(|e| e.to_string())?;
+            self.dispatcher.dispatch(
+                NotificationRequest::new(worker_profile.user_id, "new_job")
+                    .var("category", job.category.to_str())
+                    .var("city", &job.location_city)
+                    .var("job_title", &job.title)
+                    .related(Some(job.id)),
+            ).await?;
         }
-        
+
         Ok(())
     }
-    
+
     pub async fn notify_job_application(
         &self,
         employer_id: Uuid,
         job: &Job,
         applicant_name: &str,
     ) -> Result<(), String> {
-        // Get employer details
-        if let Ok(Some(employer)) = self.db_client.get_user(Some(employer_id), None, None, None).await {
-            // Create notification
-            self.create_notification_with_email(
-                employer_id,
-                "New Job Application".to_string(),
-                format!("{} has applied for your job: {}", applicant_name, job.title),
-                "job_application".to_string(),
-                Some(job.id),
-                true, // Send email for applications
-            ).await
-            .map_err(|e| e.to_string())?;
-            
-            // Send dedicated email (non-blocking)
-            self.send_email_gracefully(
-                &employer.email,
-                "job application",
-                mails::send_job_application_email(
-                    &employer.email,
-                    &employer.name,
-                    &job.title,
-                    applicant_name,
-                ).await
-                .map_err(|e| e.to_string()),
-            ).await;
-        }
-        
-        Ok(())
+        self.dispatcher.dispatch(
+            NotificationRequest::new(employer_id, "job_application")
+                .var("applicant_name", applicant_name)
+                .var("job_title", &job.title)
+                .related(Some(job.id)),
+        ).await
     }
 
     pub async fn notify_application_rejected(
@@ -176,94 +120,52 @@ impl NotificationService {
         _application: &JobApplication,
         rejection_reason: &String,
     ) -> Result<(), String> {
-        if let Ok(Some(worker)) = self.db_client.get_user(Some(worker_id), None, None, None).await {
-            self.create_notification_with_email(
-                worker_id,
-                "Application was Rejected".to_string(),
-                format!("Unfortunately our esteemed worker your Application for {} was rejected due to {}, Make edits to your portfolio and keep hopes high and we would ensure to keep updating you with jobs close to you", job.title, rejection_reason),
-                "job_assigned".to_string(),
-                Some(job.id),
-                true, // Send email for assignment
-            ).await
-            .map_err(|e| e.to_string())?;
-            
-            // Send dedicated email (non-blocking)
-            self.send_email_gracefully(
-                &worker.email,
-                "job assignment",
-                mails::send_job_assignment_email(
-                    &worker.email,
-                    &worker.name,
-                    &job.title,
-                ).await
-                .map_err(|e| e.to_string()),
-            ).await;
-        }
-        
-        Ok(())
+        self.dispatcher.dispatch(
+            NotificationRequest::new(worker_id, "job_application_rejected")
+                .var("job_title", &job.title)
+                .var("rejection_reason", rejection_reason)
+                .related(Some(job.id)),
+        ).await
     }
 
     pub async fn notify_verification_rejected(
         &self,
         verification: &VerificationDocument,
     ) -> Result<(), String> {
-        if let Ok(Some(user)) = self.db_client.get_user(Some(verification.user_id), None, None, None).await {
-            self.create_notification_with_email(
-                user.id,
-                "Verification request was Rejected".to_string(),
-                format!("Unfortunately Dear {:?}, your Verification request was rejected due to {:?}",&user.name, verification.review_notes),
-                "verification declined".to_string(),
-                Some(verification.user_id),
-                true, 
-            ).await
-            .map_err(|e| e.to_string())?;
-            
-            // Send dedicated email (non-blocking)
-            match mails::send_verification_status_email(
-                &user.email, 
-                &user.username, 
-                &VerificationStatus::Rejected,
-                verification.review_notes.as_deref()
-            ).await {
-                Ok(_) => tracing::info!("Verification rejection email sent to {}", user.email),
-                Err(e) => tracing::warn!("Failed to send verification rejection email to {}: {}", user.email, e),
-            }
-        }
-        
-        Ok(())
-    }
+        let user = self.db_client
+            .get_user(Some(verification.user_id), None, None, None)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("User {} not found", verification.user_id))?;
 
+        self.dispatcher.dispatch(
+            NotificationRequest::new(user.id, "verification_rejected")
+                .var("name", &user.name)
+                .var("status", "rejected")
+                .var_t("status_display", "value.status.rejected")
+                .var("review_notes", verification.review_notes.as_deref().unwrap_or_default())
+                .related(Some(verification.user_id)),
+        ).await
+    }
 
     pub async fn notify_verification_accepted(
         &self,
         verification: &VerificationDocument,
     ) -> Result<(), String> {
-        if let Ok(Some(user)) = self.db_client.get_user(Some(verification.user_id), None, None, None).await {
-            self.create_notification_with_email(
-                user.id,
-                "Verification request was Rejected".to_string(),
-                format!("Dear {:?} your Verification request has been accepted and you have been granted access to explore our platform, Please ensure to leave your honest feedback to help us serve you better",user.name),
-                "verification declined".to_string(),
-                Some(verification.user_id),
-                true, 
-            ).await
-            .map_err(|e| e.to_string())?;
-            
-            // Send dedicated email (non-blocking)
-            self.send_email_gracefully(
-                &user.email,
-                "verification status",
-                mails::send_verification_status_email(
-                    &user.email, 
-                    &user.username, 
-                    &VerificationStatus::Approved,
-                    verification.review_notes.as_deref()
-                ).await
-                .map_err(|e| e.to_string()),
-            ).await;
-        }
-        
-        Ok(())
+        let user = self.db_client
+            .get_user(Some(verification.user_id), None, None, None)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("User {} not found", verification.user_id))?;
+
+        self.dispatcher.dispatch(
+            NotificationRequest::new(user.id, "verification_approved")
+                .var("name", &user.name)
+                .var("status", "approved")
+                .var_t("status_display", "value.status.approved")
+                .var("review_notes", verification.review_notes.as_deref().unwrap_or_default())
+                .related(Some(verification.user_id)),
+        ).await
     }
 
     pub async fn notify_application_reviewed(
@@ -272,150 +174,64 @@ impl NotificationService {
         job: &Job,
         _application: &JobApplication,
     ) -> Result<(), String> {
-        if let Ok(Some(worker)) = self.db_client.get_user(Some(worker_id), None, None, None).await {
-            self.create_notification_with_email(
-                worker_id,
-                "Job Review".to_string(),
-                format!("Your application is under Review"),
-                "job_assigned".to_string(),
-                Some(job.id),
-                true, // Send email for assignment
-            ).await
-            .map_err(|e| e.to_string())?;
-            
-            // Send dedicated email (non-blocking)
-            self.send_email_gracefully(
-                &worker.email,
-                "job assignment",
-                mails::send_job_assignment_email(
-                    &worker.email,
-                    &worker.name,
-                    &job.title,
-                ).await
-                .map_err(|e| e.to_string()),
-            ).await;
-        }
-        
-        Ok(())
+        self.dispatcher.dispatch(
+            NotificationRequest::new(worker_id, "job_application_reviewed")
+                .var("job_title", &job.title)
+                .related(Some(job.id)),
+        ).await
     }
-    
+
     pub async fn notify_job_assigned_to_worker(
         &self,
         worker_id: Uuid,
         job: &Job,
     ) -> Result<(), String> {
-        if let Ok(Some(worker)) = self.db_client.get_user(Some(worker_id), None, None, None).await {
-            self.create_notification_with_email(
-                worker_id,
-                "Job Assignment".to_string(),
-                format!("You have been assigned to the job: {}", job.title),
-                "job_assigned".to_string(),
-                Some(job.id),
-                true, // Send email for assignment
-            ).await
-            .map_err(|e| e.to_string())?;
-            
-            // Send dedicated email (non-blocking)
-            self.send_email_gracefully(
-                &worker.email,
-                "job assignment",
-                mails::send_job_assignment_email(
-                    &worker.email,
-                    &worker.name,
-                    &job.title,
-                ).await
-                .map_err(|e| e.to_string()),
-            ).await;
-        }
-        
-        Ok(())
+        self.dispatcher.dispatch(
+            NotificationRequest::new(worker_id, "job_assigned")
+                .var("job_title", &job.title)
+                .related(Some(job.id)),
+        ).await
     }
-    
+
     pub async fn notify_progress_update(
         &self,
         employer_id: Uuid,
         progress: &JobProgress,
     ) -> Result<(), String> {
-        self.create_notification_with_email(
-            employer_id,
-            "Job Progress Update".to_string(),
-            format!("Worker updated progress to {}%: {}", 
-                progress.progress_percentage, progress.description),
-            "progress_update".to_string(),
-            Some(progress.job_id),
-            true, // Send email for progress updates
+        self.dispatcher.dispatch(
+            NotificationRequest::new(employer_id, "progress_update")
+                .var("progress_percentage", progress.progress_percentage)
+                .var("progress_description", &progress.description)
+                .related(Some(progress.job_id)),
         ).await
-            .map_err(|e| e.to_string())?;
-        
-        Ok(())
     }
-    
+
     pub async fn notify_job_completion(
         &self,
         user_id: Uuid,
         job: &Job,
     ) -> Result<(), String> {
-        if let Ok(Some(user)) = self.db_client.get_user(Some(user_id), None, None, None).await {
-            self.create_notification_with_email(
-                user_id,
-                "Job Completed".to_string(),
-                format!("The job '{}' has been marked as completed", job.title),
-                "job_completion".to_string(),
-                Some(job.id),
-                true, // Send email for completion
-            ).await
-            .map_err(|e| e.to_string())?;
-            
-            // Send dedicated email (non-blocking)
-            self.send_email_gracefully(
-                &user.email,
-                "job completion",
-                mails::send_job_completion_email(
-                    &user.email,
-                    &user.name,
-                    &job.title,
-                ).await
-                .map_err(|e| e.to_string()),
-            ).await;
-        }
-        
-        Ok(())
+        self.dispatcher.dispatch(
+            NotificationRequest::new(user_id, "job_completion")
+                .var("job_title", &job.title)
+                .related(Some(job.id)),
+        ).await
     }
-    
+
     pub async fn notify_payment_release(
         &self,
         worker_id: Uuid,
         job_id: Uuid,
         amount: f64,
     ) -> Result<(), String> {
-        if let Ok(Some(worker)) = self.db_client.get_user(Some(worker_id), None, None, None).await {
-            self.create_notification_with_email(
-                worker_id,
-                "Payment Released".to_string(),
-                format!("Payment of ₦{:.2} has been released for your work", amount),
-                "payment_released".to_string(),
-                Some(job_id),
-                true, // Send email for payment release
-            ).await
-            .map_err(|e| e.to_string())?;
-            
-            // Send dedicated email (non-blocking)
-            self.send_email_gracefully(
-                &worker.email,
-                "payment released",
-                mails::send_payment_released_email(
-                    &worker.email,
-                    &worker.name,
-                    amount,
-                ).await
-                .map_err(|e| e.to_string()),
-            ).await;
-        }
-        
-        Ok(())
+        self.dispatcher.dispatch(
+            NotificationRequest::new(worker_id, "payment_released")
+                .var("amount", format!("{:.2}", amount))
+                .related(Some(job_id)),
+        ).await
     }
-    
-    // Dispute notifications with email
+
+    // Dispute notifications
     pub async fn notify_dispute_creation(
         &self,
         raised_by: Uuid,
@@ -423,30 +239,18 @@ impl NotificationService {
         dispute: &Dispute,
     ) -> Result<(), String> {
         // Notify the party being disputed against
-        self.create_notification_with_email(
-            against,
-            "Dispute Raised".to_string(),
-            format!("A dispute has been raised against you: {}", dispute.reason),
-            "dispute_created".to_string(),
-            Some(dispute.id),
-            true, // Send email for disputes
-        ).await
-            .map_err(|e| e.to_string())?;
-        
+        self.dispatcher.dispatch(
+            NotificationRequest::new(against, "dispute_created")
+                .var("dispute_reason", &dispute.reason)
+                .related(Some(dispute.id)),
+        ).await?;
+
         // Notify the party who raised the dispute (confirmation)
-        self.create_notification_with_email(
-            raised_by,
-            "Dispute Created".to_string(),
-            format!("Your dispute has been created and is under review"),
-            "dispute_confirmation".to_string(),
-            Some(dispute.id),
-            true,
+        self.dispatcher.dispatch(
+            NotificationRequest::new(raised_by, "dispute_confirmation").related(Some(dispute.id)),
         ).await
-            .map_err(|e| e.to_string())?;
-        
-        Ok(())
     }
-    
+
     pub async fn notify_dispute_resolution(
         &self,
         raised_by: Uuid,
@@ -454,24 +258,18 @@ impl NotificationService {
         dispute: &Dispute,
         decision: &str,
     ) -> Result<(), String> {
-        let message = format!("Dispute resolved: {}", decision);
-        
         // Notify both parties
         for user_id in [raised_by, against] {
-            self.create_notification_with_email(
-                user_id,
-                "Dispute Resolved".to_string(),
-                message.clone(),
-                "dispute_resolved".to_string(),
-                Some(dispute.id),
-                true, // Send email for resolution
-            ).await
-            .map_err(|e| e.to_string())?;
+            self.dispatcher.dispatch(
+                NotificationRequest::new(user_id, "dispute_resolved")
+                    .var("decision", decision)
+                    .related(Some(dispute.id)),
+            ).await?;
         }
-        
+
         Ok(())
     }
-    
+
     // Chat notifications
     pub async fn notify_new_message(
         &self,
@@ -479,42 +277,32 @@ impl NotificationService {
         sender_name: &str,
         message: &Message,
     ) -> Result<(), String> {
-        let content_preview = if message.content.len() > 50 {
-            format!("{}...", &message.content[..50])
+        let content_preview = if message.content.chars().count() > 50 {
+            format!("{}...", message.content.chars().take(50).collect::<String>())
         } else {
             message.content.clone()
         };
-        
-        self.create_notification_with_email(
-            recipient_id,
-            format!("New message from {}", sender_name),
-            content_preview,
-            "new_message".to_string(),
-            Some(message.chat_id),
-            false, // Don't send email for every message
+
+        self.dispatcher.dispatch(
+            NotificationRequest::new(recipient_id, "new_message")
+                .var("sender_name", sender_name)
+                .var("message_preview", content_preview)
+                .related(Some(message.chat_id)),
         ).await
-            .map_err(|e| e.to_string())?;
-        
-        Ok(())
     }
-    
+
     pub async fn notify_contract_proposal(
         &self,
         recipient_id: Uuid,
         proposer_name: &str,
         job: &Job,
     ) -> Result<(), String> {
-        self.create_notification_with_email(
-            recipient_id,
-            "Contract Proposal".to_string(),
-            format!("{} has sent you a contract proposal for: {}", proposer_name, job.title),
-            "contract_proposal".to_string(),
-            Some(job.id),
-            true, // Send email for contract proposals
+        self.dispatcher.dispatch(
+            NotificationRequest::new(recipient_id, "contract_proposal")
+                .var("proposer_name", proposer_name)
+                .var("job_title", &job.title)
+                .related(Some(job.id)),
         ).await
-            .map_err(|e| e.to_string())?;
-        
-        Ok(())
     }
 
     pub async fn notify_contract_fully_signed(
@@ -523,65 +311,37 @@ impl NotificationService {
         worker_id: Uuid,
         job: &Job,
     ) -> Result<(), String> {
-       let _worker_email =  self.create_notification_with_email(
-            worker_id, 
-            "Contract Accepted".to_string(), 
-            format!("Job Contract proposal for '{}' has been fully signed, please remember to update the job", job.title), 
-            "contract_signed".to_string(), 
-            Some(job.id), 
-            true
-        ).await
-            .map_err(|e| e.to_string())?;
-
-        let _employer_email =  self.create_notification_with_email(
-            employer_id, 
-            "Contract Accepted".to_string(), 
-            format!("Job Contract proposal for '{}' has been fully signed, please remember to update the job", job.title), 
-            "contract_signed".to_string(), 
-            Some(job.id), 
-            true
-        ).await
-            .map_err(|e| e.to_string())?;
+        for user_id in [worker_id, employer_id] {
+            self.dispatcher.dispatch(
+                NotificationRequest::new(user_id, "contract_signed")
+                    .var("job_title", &job.title)
+                    .related(Some(job.id)),
+            ).await?;
+        }
 
         Ok(())
     }
-    
+
     pub async fn notify_contract_accepted(
         &self,
         proposer_id: Uuid,
         job: &Job,
     ) -> Result<(), String> {
-        self.create_notification_with_email(
-            proposer_id,
-            "Contract Accepted".to_string(),
-            format!("Your contract proposal for '{}' has been accepted", job.title),
-            "contract_accepted".to_string(),
-            Some(job.id),
-            true, // Send email for acceptance
+        self.dispatcher.dispatch(
+            NotificationRequest::new(proposer_id, "contract_accepted")
+                .var("job_title", &job.title)
+                .related(Some(job.id)),
         ).await
-            .map_err(|e| e.to_string())?;
-        
-        Ok(())
     }
-    
+
     pub async fn notify_contract_rejected(
         &self,
         proposer_id: Uuid,
     ) -> Result<(), String> {
-        self.create_notification_with_email(
-            proposer_id,
-            "Contract Declined".to_string(),
-            "Your contract proposal has been declined".to_string(),
-            "contract_rejected".to_string(),
-            None,
-            true, // Send email for rejection
-        ).await
-            .map_err(|e| e.to_string())?;
-        
-        Ok(())
+        self.dispatcher.dispatch(NotificationRequest::new(proposer_id, "contract_rejected")).await
     }
-    
-    // Wallet notifications with email
+
+    // Wallet notifications
     pub async fn notify_wallet_transaction(
         &self,
         user_id: Uuid,
@@ -589,66 +349,27 @@ impl NotificationService {
         amount: f64,
         reference: &str,
     ) -> Result<(), String> {
-        if let Ok(Some(user)) = self.db_client.get_user(Some(user_id), None, None, None).await {
-            let title = match transaction_type {
-                "deposit" => "Deposit Successful",
-                "withdrawal" => "Withdrawal Processed",
-                "transfer_sent" => "Transfer Sent",
-                "transfer_received" => "Transfer Received",
-                _ => "Wallet Transaction",
-            };
-            
-            self.create_notification_with_email(
-                user_id,
-                title.to_string(),
-                format!("₦{:.2} - Ref: {}", amount, reference),
-                transaction_type.to_string(),
-                None,
-                true, // Send email for wallet transactions
-            ).await
-            .map_err(|e| e.to_string())?;
-            
-            // Send dedicated emails based on type (non-blocking)
-            match transaction_type {
-                "deposit" => {
-                    self.send_email_gracefully(
-                        &user.email,
-                        "deposit",
-                        mails::send_deposit_email(&user.email, &user.name, amount, reference).await
-                        .map_err(|e| e.to_string()),
-                    ).await;
-                }
-                "withdrawal" => {
-                    self.send_email_gracefully(
-                        &user.email,
-                        "withdrawal",
-                        mails::send_withdrawal_email(&user.email, &user.name, amount, reference).await
-                        .map_err(|e| e.to_string()),
-                    ).await;
-                }
-                "transfer_sent" => {
-                    self.send_email_gracefully(
-                        &user.email,
-                        "transfer sent",
-                        mails::send_transfer_email(&user.email, &user.name, amount, reference, "sent").await
-                        .map_err(|e| e.to_string()),
-                    ).await;
-                }
-                "transfer_received" => {
-                    self.send_email_gracefully(
-                        &user.email,
-                        "transfer received",
-                        mails::send_transfer_email(&user.email, &user.name, amount, reference, "received").await
-                        .map_err(|e| e.to_string()),
-                    ).await;
-                }
-                _ => {}
-            }
+        let (template_key, direction) = match transaction_type {
+            "deposit" => ("deposit", ""),
+            "withdrawal" => ("withdrawal", ""),
+            "transfer_sent" => ("transfer_sent", "sent"),
+            "transfer_received" => ("transfer_received", "received"),
+            _ => ("wallet_transaction", ""),
+        };
+
+        let mut request = NotificationRequest::new(user_id, template_key)
+            .var("amount", format!("{:.2}", amount))
+            .var("reference", reference)
+            .var("direction", direction);
+        if !direction.is_empty() {
+            request = request
+                .var_t("direction_label", &format!("value.direction.{}", direction))
+                .var_t("direction_note", &format!("value.direction.{}_note", direction));
         }
-        
-        Ok(())
+
+        self.dispatcher.dispatch(request).await
     }
-    
+
     // Existing method for getting user notifications
     pub async fn get_user_notifications(
         &self,
@@ -672,7 +393,7 @@ impl NotificationService {
         .fetch_all(&self.db_client.pool)
         .await
     }
-    
+
     pub async fn mark_notification_read(
         &self,
         notification_id: Uuid,
@@ -689,10 +410,10 @@ impl NotificationService {
         .bind(user_id)
         .execute(&self.db_client.pool)
         .await?;
-        
+
         Ok(())
     }
-    
+
     pub async fn mark_all_notifications_read(
         &self,
         user_id: Uuid,
@@ -708,7 +429,7 @@ impl NotificationService {
         .execute(&self.db_client.pool)
         .await
             .map_err(|e| e.to_string())?;
-        
+
         Ok(())
     }
 
@@ -718,16 +439,9 @@ impl NotificationService {
         job: &Job,
         _worker_profile: &WorkerProfile,
     ) -> Result<(), String> {
-        self.create_notification_with_email(
-            employer_id,
-            format!("Worker assigned to job {}", job.title),
-            format!("A worker has been assigned to your job: {}", job.title),
-            "worker assigned to job".to_string(),
-            None,
-            true
+        self.dispatcher.dispatch(
+            NotificationRequest::new(employer_id, "worker_assigned").var("job_title", &job.title),
         ).await
-            .map_err(|e| e.to_string())?;
-        Ok(())
     }
 
     pub async fn notify_contract_awaiting_signature(
@@ -735,41 +449,11 @@ impl NotificationService {
         user_id: Uuid,
         contract: &JobContract,
     ) -> Result<(), String> {
-        self.create_notification_with_email(
-            user_id,
-            "Contract needs signature".to_string(),
-            format!("A new contract is awaiting your signature for job ID: {}", contract.job_id),
-            "Contract awaiting signature".to_string(),
-            None,
-            true
+        self.dispatcher.dispatch(
+            NotificationRequest::new(user_id, "contract_awaiting_signature").var("job_id", contract.job_id),
         ).await
-            .map_err(|e| e.to_string())?;
-        Ok(())
     }
 
-    //  pub async fn notify_subscription_upgraded(
-    //     &self,
-    //     user_id: Uuid,
-    //     tier: SubscriptionTier,
-    // ) -> Result<(), Box<dyn std::error::Error>> {
-    //     let tier_name = match tier {
-    //         SubscriptionTier::Normal => "Normal",
-    //         SubscriptionTier::Pro => "Pro",
-    //         SubscriptionTier::Premium => "Premium",
-    //     };
-        
-    //     self.create_notification_with_email(
-    //         user_id,
-    //         format!("Subscription Upgraded to {}", tier_name),
-    //         format!("Your vendor subscription has been upgraded to {} tier", tier_name),
-    //         "subscription_upgraded".to_string(),
-    //         None,
-    //         true,
-    //     ).await?;
-    //        
-    //     Ok(())
-    // }
-
     pub async fn notify_new_order(
         &self,
         vendor_user_id: Uuid,
@@ -777,17 +461,12 @@ impl NotificationService {
         total_amount: f64,
         order_number: &str,
     ) -> Result<(), String> {
-        self.create_notification_with_email(
-            vendor_user_id,
-            "New Order Received".to_string(),
-            format!("New order #{} for '{}' - ₦{:.2}", order_number, service_title, total_amount),
-            "new_order".to_string(),
-            None,
-            true,
+        self.dispatcher.dispatch(
+            NotificationRequest::new(vendor_user_id, "new_order")
+                .var("order_number", order_number)
+                .var("service_title", service_title)
+                .var("amount", format!("{:.2}", total_amount)),
         ).await
-            .map_err(|e| e.to_string())?;
-        
-        Ok(())
     }
 
     pub async fn notify_order_placed(
@@ -797,17 +476,12 @@ impl NotificationService {
         total_amount: f64,
         order_number: &str,
     ) -> Result<(), String> {
-        self.create_notification_with_email(
-            buyer_id,
-            "Order Placed Successfully".to_string(),
-            format!("Your order #{} for '{}' has been placed - ₦{:.2}", order_number, service_title, total_amount),
-            "order_placed".to_string(),
-            None,
-            true,
+        self.dispatcher.dispatch(
+            NotificationRequest::new(buyer_id, "order_placed")
+                .var("order_number", order_number)
+                .var("service_title", service_title)
+                .var("amount", format!("{:.2}", total_amount)),
         ).await
-            .map_err(|e| e.to_string())?;
-        
-        Ok(())
     }
 
     pub async fn notify_order_confirmed(
@@ -816,17 +490,11 @@ impl NotificationService {
         service_title: &str,
         order_number: &str,
     ) -> Result<(), String> {
-        self.create_notification_with_email(
-            buyer_id,
-            "Order Confirmed".to_string(),
-            format!("Vendor confirmed your order #{} for '{}'", order_number, service_title),
-            "order_confirmed".to_string(),
-            None,
-            true,
+        self.dispatcher.dispatch(
+            NotificationRequest::new(buyer_id, "order_confirmed")
+                .var("order_number", order_number)
+                .var("service_title", service_title),
         ).await
-            .map_err(|e| e.to_string())?;
-        
-        Ok(())
     }
 
     pub async fn notify_order_completed(
@@ -835,88 +503,47 @@ impl NotificationService {
         service_title: &str,
         vendor_amount: f64,
     ) -> Result<(), String> {
-        self.create_notification_with_email(
-            vendor_user_id,
-            "Order Completed - Payment Released".to_string(),
-            format!("Order for '{}' completed. ₦{:.2} credited to your wallet", service_title, vendor_amount),
-            "order_completed".to_string(),
-            None,
-            true,
+        self.dispatcher.dispatch(
+            NotificationRequest::new(vendor_user_id, "order_completed")
+                .var("service_title", service_title)
+                .var("amount", format!("{:.2}", vendor_amount)),
         ).await
-            .map_err(|e| e.to_string())?;
-        
-        Ok(())
     }
-    
+
     pub async fn notify_service_inquiry(
         &self,
         vendor_user_id: Uuid,
         inquirer_name: &str,
         service_title: &str,
     ) -> Result<(), String> {
-        if let Ok(Some(user)) = self.db_client.get_user(Some(vendor_user_id), None, None, None).await {
-            self.create_notification_with_email(
-                vendor_user_id,
-                "New Service Inquiry".to_string(),
-                format!("{} is interested in your service: {}", inquirer_name, service_title),
-                "service_inquiry".to_string(),
-                None,
-                true,
-            ).await
-            .map_err(|e| e.to_string())?;
-            
-            // Send dedicated email (non-blocking)
-            self.send_email_gracefully(
-                &user.email,
-                "service inquiry",
-                mails::send_service_inquiry_email(
-                    &user.email,
-                    &user.name,
-                    inquirer_name,
-                    service_title,
-                ).await
-                .map_err(|e| e.to_string()),
-            ).await;
-        }
-        
-        Ok(())
+        self.dispatcher.dispatch(
+            NotificationRequest::new(vendor_user_id, "service_inquiry")
+                .var("inquirer_name", inquirer_name)
+                .var("service_title", service_title),
+        ).await
     }
-    
+
     pub async fn notify_service_expiring(
         &self,
         vendor_user_id: Uuid,
         service_title: &str,
         days_until_expiry: i32,
     ) -> Result<(), String> {
-        self.create_notification_with_email(
-            vendor_user_id,
-            "Service Expiring Soon".to_string(),
-            format!("Your service '{}' will expire in {} days", service_title, days_until_expiry),
-            "service_expiring".to_string(),
-            None,
-            true,
+        self.dispatcher.dispatch(
+            NotificationRequest::new(vendor_user_id, "service_expiring")
+                .var("service_title", service_title)
+                .var("days", days_until_expiry),
         ).await
-            .map_err(|e| e.to_string())?;
-        
-        Ok(())
     }
-    
+
     pub async fn notify_subscription_expiring(
         &self,
         vendor_user_id: Uuid,
         days_until_expiry: i32,
     ) -> Result<(), String> {
-        self.create_notification_with_email(
-            vendor_user_id,
-            "Subscription Expiring Soon".to_string(),
-            format!("Your vendor subscription will expire in {} days. Renew now to avoid service interruptions.", days_until_expiry),
-            "subscription_expiring".to_string(),
-            None,
-            true,
+        self.dispatcher.dispatch(
+            NotificationRequest::new(vendor_user_id, "subscription_expiring").var("days", days_until_expiry),
         ).await
-            .map_err(|e| e.to_string())?;
-        
-        Ok(())
     }
 
     pub async fn notify_service_purchase(
@@ -927,66 +554,45 @@ impl NotificationService {
         order: &ServiceOrder,
     ) -> Result<(), String> {
         // Notify vendor
-        self.create_notification_with_email(
-            vendor_user_id,
-            "New Order Received".to_string(),
-            format!("New order for: {} (Qty: {})", service.title, order.quantity),
-            "service_order".to_string(),
-            Some(order.id),
-            true,
-        ).await
-            .map_err(|e| e.to_string())?;
-        
+        self.dispatcher.dispatch(
+            NotificationRequest::new(vendor_user_id, "service_order")
+                .var("service_title", &service.title)
+                .var("quantity", order.quantity)
+                .related(Some(order.id)),
+        ).await?;
+
         // Notify buyer (confirmation)
-        self.create_notification_with_email(
-            buyer_id,
-            "Order Confirmed".to_string(),
-            format!("Your order for '{}' has been confirmed", service.title),
-            "order_confirmation".to_string(),
-            Some(order.id),
-            true,
+        self.dispatcher.dispatch(
+            NotificationRequest::new(buyer_id, "order_confirmation")
+                .var("service_title", &service.title)
+                .related(Some(order.id)),
         ).await
-            .map_err(|e| e.to_string())?;
-        
-        Ok(())
     }
-    
+
     pub async fn notify_order_shipped(
         &self,
         buyer_id: Uuid,
         order: &ServiceOrder,
     ) -> Result<(), String> {
-        self.create_notification_with_email(
-            buyer_id,
-            "Order Shipped".to_string(),
-            format!("Your order #{} has been shipped", order.order_number),
-            "order_shipped".to_string(),
-            Some(order.id),
-            true,
+        self.dispatcher.dispatch(
+            NotificationRequest::new(buyer_id, "order_shipped")
+                .var("order_number", &order.order_number)
+                .related(Some(order.id)),
         ).await
-            .map_err(|e| e.to_string())?;
-        
-        Ok(())
     }
-    
+
     pub async fn notify_delivery_confirmed(
         &self,
         vendor_user_id: Uuid,
         order: &ServiceOrder,
     ) -> Result<(), String> {
-        self.create_notification_with_email(
-            vendor_user_id,
-            "Delivery Confirmed".to_string(),
-            format!("Buyer confirmed delivery for order #{}", order.order_number),
-            "delivery_confirmed".to_string(),
-            Some(order.id),
-            true,
+        self.dispatcher.dispatch(
+            NotificationRequest::new(vendor_user_id, "delivery_confirmed")
+                .var("order_number", &order.order_number)
+                .related(Some(order.id)),
         ).await
-            .map_err(|e| e.to_string())?;
-        
-        Ok(())
     }
-    
+
     pub async fn notify_service_dispute_created(
         &self,
         raised_by: Uuid,
@@ -994,28 +600,16 @@ impl NotificationService {
         dispute: &ServiceDispute,
     ) -> Result<(), String> {
         // Notify party being disputed against
-        self.create_notification_with_email(
-            against,
-            "Service Dispute Raised".to_string(),
-            format!("A dispute has been raised for order: {}", dispute.reason),
-            "service_dispute".to_string(),
-            Some(dispute.id),
-            true,
-        ).await
-            .map_err(|e| e.to_string())?;
-        
+        self.dispatcher.dispatch(
+            NotificationRequest::new(against, "service_dispute")
+                .var("dispute_reason", &dispute.reason)
+                .related(Some(dispute.id)),
+        ).await?;
+
         // Confirm to party who raised it
-        self.create_notification_with_email(
-            raised_by,
-            "Dispute Created".to_string(),
-            "Your dispute has been submitted and is under review".to_string(),
-            "dispute_confirmation".to_string(),
-            Some(dispute.id),
-            true,
+        self.dispatcher.dispatch(
+            NotificationRequest::new(raised_by, "service_dispute_confirmation").related(Some(dispute.id)),
         ).await
-            .map_err(|e| e.to_string())?;
-        
-        Ok(())
     }
 
     pub async fn notify_subscription_upgraded(
@@ -1023,66 +617,575 @@ impl NotificationService {
         vendor_id: Uuid,
         subscription_tier: SubscriptionTier
     ) -> Result<(), String> {
-        self.create_notification_with_email(
-            vendor_id, 
-            "Subscription upgraded".to_string(), 
-            format!("Subscription upgrade to {:?} was successful", subscription_tier), 
-            "service upgrade".to_string(), 
-            None, 
-            true
+        self.dispatcher.dispatch(
+            NotificationRequest::new(vendor_id, "subscription_upgraded")
+                .var("tier", format!("{:?}", subscription_tier)),
         ).await
-            .map_err(|e| e.to_string())?;
+    }
 
-        Ok(())
+    // Property verification pipeline notifications
+    pub async fn notify_agent_property_assigned(
+        &self,
+        agent_id: Uuid,
+        property: &Property,
+        due_at: DateTime<Utc>,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(agent_id, "property_assigned")
+                .var("property_title", &property.title)
+                .var("property_address", &property.address)
+                .var("property_type", format!("{:?}", property.property_type))
+                .var("deadline", verification_deadline(due_at))
+                .var("property_reference", property_reference(property))
+                .related(Some(property.id)),
+        ).await
+    }
+
+    pub async fn notify_agent_photo_match(
+        &self,
+        agent_id: Uuid,
+        property: &Property,
+        matched_listings: usize,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(agent_id, "property_photo_match")
+                .var("property_title", &property.title)
+                .var("matched_listings", matched_listings)
+                .var("property_reference", property_reference(property))
+                .related(Some(property.id)),
+        ).await
+    }
+
+    pub async fn notify_lawyer_documents_ready(
+        &self,
+        lawyer_id: Uuid,
+        property: &Property,
+        agent_notes: &str,
+        due_at: DateTime<Utc>,
+    ) -> Result<(), String> {
+        let documents: Vec<&str> = [
+            (property.certificate_of_occupancy.is_some(), "Certificate of Occupancy"),
+            (property.deed_of_agreement.is_some(), "Deed of Agreement"),
+            (property.survey_plan.is_some(), "Survey Plan"),
+            (property.building_plan_approval.is_some(), "Building Plan Approval"),
+        ]
+        .into_iter()
+        .filter(|(present, _)| *present)
+        .map(|(_, name)| name)
+        .collect();
+
+        self.dispatcher.dispatch(
+            NotificationRequest::new(lawyer_id, "property_documents_ready")
+                .var("property_title", &property.title)
+                .var("property_address", &property.address)
+                .var("document_list", if documents.is_empty() { "none".to_string() } else { documents.join(", ") })
+                .var("agent_notes", agent_notes)
+                .var("deadline", verification_deadline(due_at))
+                .var("property_reference", property_reference(property))
+                .related(Some(property.id)),
+        ).await
+    }
+
+    pub async fn notify_property_live(
+        &self,
+        landlord_id: Uuid,
+        property: &Property,
+        verification_score: u32,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(landlord_id, "property_live")
+                .var("property_title", &property.title)
+                .var("property_address", &property.address)
+                .var("verification_score", verification_score)
+                .var("property_reference", property_reference(property))
+                .related(Some(property.id)),
+        ).await
+    }
+
+    pub async fn notify_property_rejected(
+        &self,
+        landlord_id: Uuid,
+        property: &Property,
+        rejection_stage: &str,
+        rejection_reason: &str,
+        rejection_details: &str,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(landlord_id, "property_rejected")
+                .var("property_title", &property.title)
+                .var("rejection_stage", rejection_stage)
+                .var("rejection_reason", rejection_reason)
+                .var("rejection_details", rejection_details)
+                .var("property_reference", property_reference(property))
+                .related(Some(property.id)),
+        ).await
+    }
+
+    pub async fn notify_viewing_requested(
+        &self,
+        host_id: Uuid,
+        property: &Property,
+        viewing: &PropertyViewing,
+        requester_name: &str,
+    ) -> Result<(), String> {
+        let slots = viewing.proposed_slots()
+            .into_iter()
+            .map(viewing_time)
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        self.dispatcher.dispatch(
+            NotificationRequest::new(host_id, "property_viewing_requested")
+                .var("property_title", &property.title)
+                .var("requester_name", requester_name)
+                .var("proposed_slots", slots)
+                .var("property_reference", property_reference(property))
+                .related(Some(viewing.id)),
+        ).await
+    }
+
+    pub async fn notify_viewing_confirmed(
+        &self,
+        requester_id: Uuid,
+        property: &Property,
+        viewing: &PropertyViewing,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(requester_id, "property_viewing_confirmed")
+                .var("property_title", &property.title)
+                .var("property_address", &property.address)
+                .var("scheduled_at", viewing.scheduled_at.map(viewing_time).unwrap_or_default())
+                .var("property_reference", property_reference(property))
+                .related(Some(viewing.id)),
+        ).await
+    }
+
+    pub async fn notify_viewing_declined(
+        &self,
+        requester_id: Uuid,
+        property: &Property,
+        viewing: &PropertyViewing,
+        reason: &str,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(requester_id, "property_viewing_declined")
+                .var("property_title", &property.title)
+                .var("reason", reason)
+                .related(Some(viewing.id)),
+        ).await
+    }
+
+    pub async fn notify_viewing_cancelled(
+        &self,
+        user_id: Uuid,
+        property: &Property,
+        viewing: &PropertyViewing,
+        cancelled_by: &str,
+        reason: &str,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(user_id, "property_viewing_cancelled")
+                .var("property_title", &property.title)
+                .var("cancelled_by", cancelled_by)
+                .var("reason", reason)
+                .related(Some(viewing.id)),
+        ).await
+    }
+
+    pub async fn notify_viewing_reminder(
+        &self,
+        user_id: Uuid,
+        property: &Property,
+        viewing: &PropertyViewing,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(user_id, "property_viewing_reminder")
+                .var("property_title", &property.title)
+                .var("property_address", &property.address)
+                .var("scheduled_at", viewing.scheduled_at.map(viewing_time).unwrap_or_default())
+                .related(Some(viewing.id)),
+        ).await
+    }
+
+    pub async fn notify_viewing_no_show(
+        &self,
+        user_id: Uuid,
+        property: &Property,
+        viewing: &PropertyViewing,
+        penalty_points: i32,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(user_id, "property_viewing_no_show")
+                .var("property_title", &property.title)
+                .var("scheduled_at", viewing.scheduled_at.map(viewing_time).unwrap_or_default())
+                .var("penalty_points", penalty_points)
+                .related(Some(viewing.id)),
+        ).await
+    }
+
+    pub async fn notify_inspection_fee_released(
+        &self,
+        user_id: Uuid,
+        property: &Property,
+        viewing: &PropertyViewing,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(user_id, "property_inspection_fee_released")
+                .var("property_title", &property.title)
+                .var("amount", viewing.inspection_fee)
+                .related(Some(viewing.id)),
+        ).await
+    }
+
+    pub async fn notify_tenancy_offer(
+        &self,
+        landlord_id: Uuid,
+        property: &Property,
+        tenancy: &Tenancy,
+        tenant_name: &str,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(landlord_id, "property_tenancy_offer")
+                .var("property_title", &property.title)
+                .var("tenant_name", tenant_name)
+                .var("rent_amount", tenancy.rent_amount)
+                .var_t("rent_frequency", &format!("value.rent_frequency.{}", tenancy.rent_frequency.to_str()))
+                .var("start_date", tenancy.start_date)
+                .var("property_reference", property_reference(property))
+                .related(Some(tenancy.id)),
+        ).await
+    }
+
+    pub async fn notify_tenancy_accepted(
+        &self,
+        tenant_id: Uuid,
+        property: &Property,
+        tenancy: &Tenancy,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(tenant_id, "property_tenancy_accepted")
+                .var("property_title", &property.title)
+                .var("start_date", tenancy.start_date)
+                .var("end_date", tenancy.end_date)
+                .var("caution_deposit", tenancy.caution_deposit)
+                .related(Some(tenancy.id)),
+        ).await
+    }
+
+    pub async fn notify_tenancy_declined(
+        &self,
+        tenant_id: Uuid,
+        property: &Property,
+        tenancy: &Tenancy,
+        reason: &str,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(tenant_id, "property_tenancy_declined")
+                .var("property_title", &property.title)
+                .var("reason", reason)
+                .related(Some(tenancy.id)),
+        ).await
+    }
+
+    pub async fn notify_tenancy_withdrawn(
+        &self,
+        landlord_id: Uuid,
+        property: &Property,
+        tenancy: &Tenancy,
+        tenant_name: &str,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(landlord_id, "property_tenancy_withdrawn")
+                .var("property_title", &property.title)
+                .var("tenant_name", tenant_name)
+                .related(Some(tenancy.id)),
+        ).await
+    }
+
+    pub async fn notify_tenancy_move_out(
+        &self,
+        user_id: Uuid,
+        property: &Property,
+        tenancy: &Tenancy,
+        given_by: &str,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(user_id, "property_tenancy_move_out")
+                .var("property_title", &property.title)
+                .var("given_by", given_by)
+                .related(Some(tenancy.id)),
+        ).await
     }
-    
-    // pub async fn notify_contract_fully_signed(
-    //     &self,
-    //     employer_id: Uuid,
-    //     worker_id: Uuid,
-    //     job: &Job,
-    // ) -> Result<(), Box<dyn std::error::Error>> {
-    //     // Notify employer
-    //     self.create_notification_with_email(
-    //         employer_id,
-    //         "Contract Activated".to_string(),
-    //         format!("Both parties have signed the contract for: {}", job.title),
-    //         "contract_active".to_string(),
-    //         Some(job.id),
-    //         true,
-    //     ).await
-            // .map_err(|e| e.to_string())?;
-        
-    //     // Notify worker
-    //     self.create_notification_with_email(
-    //         worker_id,
-    //         "Contract Activated".to_string(),
-    //         format!("Contract is now active for: {}", job.title),
-    //         "contract_active".to_string(),
-    //         Some(job.id),
-    //         true,
-    //     ).await
-            // .map_err(|e| e.to_string())?;
-        
-    //     Ok(())
-    // }
-
-    // pub async fn notify_job_assigned_to_worker(
-    //     &self,
-    //     worker_id: Uuid,
-    //     job: &Job,
-    // ) -> Result<(), Box<dyn std::error::Error>> {
-    //     self.create_notification_with_email(
-    //         worker_id,
-    //         "New job assignment".to_string(),
-    //         format!("You have been assigned to job: {}", job.title),
-    //         "Job assigned notification".to_string(),
-    //         None,
-    //         true,
-    //     ).await
-            // .map_err(|e| e.to_string())?;
-    //     Ok(())
-    // }
+
+    pub async fn notify_tenancy_ended(
+        &self,
+        tenant_id: Uuid,
+        property: &Property,
+        tenancy: &Tenancy,
+    ) -> Result<(), String> {
+        let deduction = tenancy.deposit_deduction.unwrap_or(0);
+
+        self.dispatcher.dispatch(
+            NotificationRequest::new(tenant_id, "property_tenancy_ended")
+                .var("property_title", &property.title)
+                .var("deposit_deduction", deduction)
+                .var("deposit_refund", tenancy.caution_deposit - deduction)
+                .var("inspection_notes", tenancy.inspection_notes.as_deref().unwrap_or_default())
+                .related(Some(tenancy.id)),
+        ).await
+    }
+
+    pub async fn notify_rent_paid(
+        &self,
+        tenant_id: Uuid,
+        property: &Property,
+        payment: &RentPayment,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(tenant_id, "property_rent_paid")
+                .var("property_title", &property.title)
+                .var("amount", payment.total_due())
+                .var("due_date", payment.due_date)
+                .var("receipt_number", payment.receipt_number.as_deref().unwrap_or_default())
+                .related(Some(payment.id)),
+        ).await
+    }
+
+    pub async fn notify_rent_received(
+        &self,
+        landlord_id: Uuid,
+        property: &Property,
+        payment: &RentPayment,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(landlord_id, "property_rent_received")
+                .var("property_title", &property.title)
+                .var("amount", payment.total_due())
+                .var("due_date", payment.due_date)
+                .var("receipt_number", payment.receipt_number.as_deref().unwrap_or_default())
+                .related(Some(payment.id)),
+        ).await
+    }
+
+    pub async fn notify_rent_payment_failed(
+        &self,
+        tenant_id: Uuid,
+        property: &Property,
+        payment: &RentPayment,
+        grace_deadline: NaiveDate,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(tenant_id, "property_rent_payment_failed")
+                .var("property_title", &property.title)
+                .var("amount", payment.total_due())
+                .var("due_date", payment.due_date)
+                .var("grace_deadline", grace_deadline)
+                .var("late_fee", payment.late_fee)
+                .related(Some(payment.id)),
+        ).await
+    }
+
+    pub async fn notify_offer_received(
+        &self,
+        landlord_id: Uuid,
+        property: &Property,
+        offer: &PropertyOffer,
+        buyer_name: &str,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(landlord_id, "property_offer_received")
+                .var("property_title", &property.title)
+                .var("buyer_name", buyer_name)
+                .var("amount", offer.amount)
+                .var("expires_at", viewing_time(offer.expires_at))
+                .var("property_reference", property_reference(property))
+                .related(Some(offer.id)),
+        ).await
+    }
+
+    pub async fn notify_offer_countered(
+        &self,
+        buyer_id: Uuid,
+        property: &Property,
+        offer: &PropertyOffer,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(buyer_id, "property_offer_countered")
+                .var("property_title", &property.title)
+                .var("counter_amount", offer.standing_amount())
+                .var("expires_at", viewing_time(offer.expires_at))
+                .related(Some(offer.id)),
+        ).await
+    }
+
+    pub async fn notify_offer_accepted(
+        &self,
+        user_id: Uuid,
+        property: &Property,
+        offer: &PropertyOffer,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(user_id, "property_offer_accepted")
+                .var("property_title", &property.title)
+                .var("amount", offer.standing_amount())
+                .var("earnest_deposit", offer.earnest_deposit.unwrap_or(0))
+                .related(Some(offer.id)),
+        ).await
+    }
+
+    pub async fn notify_offer_rejected(
+        &self,
+        buyer_id: Uuid,
+        property: &Property,
+        offer: &PropertyOffer,
+        reason: &str,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(buyer_id, "property_offer_rejected")
+                .var("property_title", &property.title)
+                .var("reason", reason)
+                .related(Some(offer.id)),
+        ).await
+    }
+
+    pub async fn notify_offer_withdrawn(
+        &self,
+        landlord_id: Uuid,
+        property: &Property,
+        offer: &PropertyOffer,
+        buyer_name: &str,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(landlord_id, "property_offer_withdrawn")
+                .var("property_title", &property.title)
+                .var("buyer_name", buyer_name)
+                .related(Some(offer.id)),
+        ).await
+    }
+
+    pub async fn notify_offer_expired(
+        &self,
+        buyer_id: Uuid,
+        property: &Property,
+        offer: &PropertyOffer,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(buyer_id, "property_offer_expired")
+                .var("property_title", &property.title)
+                .var("amount", offer.amount)
+                .related(Some(offer.id)),
+        ).await
+    }
+
+    pub async fn notify_offer_cancelled(
+        &self,
+        user_id: Uuid,
+        property: &Property,
+        offer: &PropertyOffer,
+        cancelled_by: &str,
+        reason: &str,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(user_id, "property_offer_cancelled")
+                .var("property_title", &property.title)
+                .var("cancelled_by", cancelled_by)
+                .var("reason", reason)
+                .related(Some(offer.id)),
+        ).await
+    }
+
+    pub async fn notify_offer_completed(
+        &self,
+        landlord_id: Uuid,
+        property: &Property,
+        offer: &PropertyOffer,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(landlord_id, "property_offer_completed")
+                .var("property_title", &property.title)
+                .var("amount", offer.standing_amount())
+                .var("earnest_deposit", offer.earnest_deposit.unwrap_or(0))
+                .related(Some(offer.id)),
+        ).await
+    }
+
+    /// `property` is the newest of `listing_count` new matches
+    pub async fn notify_saved_search_match(
+        &self,
+        user_id: Uuid,
+        search_name: &str,
+        property: &Property,
+        listing_count: usize,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(user_id, "property_saved_search_match")
+                .var("search_name", search_name)
+                .var("listing_count", listing_count)
+                .var("property_title", &property.title)
+                .var("property_price", property.price)
+                .var("property_reference", property_reference(property))
+                .related(Some(property.id)),
+        ).await
+    }
+
+    pub async fn notify_price_drop(
+        &self,
+        user_id: Uuid,
+        property: &Property,
+        old_price: i64,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(user_id, "property_price_drop")
+                .var("property_title", &property.title)
+                .var("old_price", old_price)
+                .var("new_price", property.price)
+                .var("property_reference", property_reference(property))
+                .related(Some(property.id)),
+        ).await
+    }
+
+    /// The verifier's SLA on a listing is close to running out
+    pub async fn notify_verification_reminder(
+        &self,
+        verifier_id: Uuid,
+        property: &Property,
+        due_at: DateTime<Utc>,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(verifier_id, "property_verification_reminder")
+                .var("property_title", &property.title)
+                .var("property_address", &property.address)
+                .var("deadline", verification_deadline(due_at))
+                .var("property_reference", property_reference(property))
+                .related(Some(property.id)),
+        ).await
+    }
+
+    /// The verifier's SLA ran out and the listing went to someone else
+    pub async fn notify_verification_reassigned(
+        &self,
+        verifier_id: Uuid,
+        property: &Property,
+    ) -> Result<(), String> {
+        self.dispatcher.dispatch(
+            NotificationRequest::new(verifier_id, "property_verification_reassigned")
+                .var("property_title", &property.title)
+                .var("property_reference", property_reference(property))
+                .related(Some(property.id)),
+        ).await
+    }
+}
+
+fn viewing_time(at: DateTime<Utc>) -> String {
+    at.format("%Y-%m-%d %H:%M UTC").to_string()
+}
+
+fn property_reference(property: &Property) -> String {
+    format!("VN-{}", property.id.to_string().chars().take(8).collect::<String>())
+}
+
+fn verification_deadline(due_at: DateTime<Utc>) -> String {
+    due_at.format("%Y-%m-%d %H:%M UTC").to_string()
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
@@ -1095,4 +1198,4 @@ pub struct Notification {
     pub related_id: Option<Uuid>,
     pub is_read: Option<bool>,
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
-}
\ No newline at end of file
+}
@@ -0,0 +1,219 @@
+// service/notification_stream.rs
+//! Live fan-out of in-app notifications to `GET /notifications/stream`.
+//!
+//! Every instance keeps one broadcast channel that its SSE connections listen
+//! on. With Redis configured, events are published to a pub/sub channel and
+//! each instance's subscriber forwards them into its local broadcast, so a
+//! notification created on one instance reaches clients connected to another.
+//! Without Redis the broadcast is fed directly and only local clients see it.
+use std::sync::{Arc, LazyLock, OnceLock};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use redis::aio::ConnectionManager;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::{
+    db::{db::DBClient, notificationdb::NotificationExt},
+    models::notificationmodels::Notification,
+};
+
+const REDIS_CHANNEL: &str = "notifications:stream";
+
+/// Events buffered per receiver before a slow client is considered lagged
+const BROADCAST_CAPACITY: usize = 1024;
+
+static LOCAL: LazyLock<broadcast::Sender<StreamEvent>> =
+    LazyLock::new(|| broadcast::channel(BROADCAST_CAPACITY).0);
+static PUBLISHER: OnceLock<Arc<ConnectionManager>> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamEvent {
+    Notification {
+        user_id: Uuid,
+        notification: Notification,
+        unread_count: i64,
+    },
+    UnreadCount {
+        user_id: Uuid,
+        unread_count: i64,
+    },
+}
+
+impl StreamEvent {
+    pub fn user_id(&self) -> Uuid {
+        match self {
+            StreamEvent::Notification { user_id, .. } | StreamEvent::UnreadCount { user_id, .. } => *user_id,
+        }
+    }
+
+    /// Resume position for `Last-Event-ID`. Only notifications carry one, so an
+    /// unread-count update never moves a client past a notification it missed.
+    pub fn cursor(&self) -> Option<i64> {
+        match self {
+            StreamEvent::Notification { notification, .. } => Some(event_cursor(notification.created_at)),
+            StreamEvent::UnreadCount { .. } => None,
+        }
+    }
+}
+
+pub fn event_cursor(created_at: DateTime<Utc>) -> i64 {
+    created_at.timestamp_micros()
+}
+
+pub fn parse_cursor(last_event_id: &str) -> Option<DateTime<Utc>> {
+    last_event_id
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .and_then(DateTime::from_timestamp_micros)
+}
+
+/// Start bridging through Redis pub/sub. Called once at startup; without it
+/// events stay on this instance.
+pub fn init(db_client: &DBClient, redis_url: Option<&str>) {
+    let (Some(publisher), Some(redis_url)) = (db_client.redis_client.clone(), redis_url) else {
+        tracing::info!("Notification stream running without Redis; events stay on this instance");
+        return;
+    };
+
+    let client = match redis::Client::open(redis_url) {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::warn!("Notification stream could not use Redis: {}", e);
+            return;
+        }
+    };
+
+    let _ = PUBLISHER.set(publisher);
+    tokio::spawn(run_redis_subscriber(client));
+}
+
+pub fn subscribe() -> broadcast::Receiver<StreamEvent> {
+    LOCAL.subscribe()
+}
+
+pub async fn publish(event: StreamEvent) {
+    if let Some(publisher) = PUBLISHER.get() {
+        let payload = match serde_json::to_string(&event) {
+            Ok(payload) => payload,
+            Err(e) => {
+                tracing::error!("Failed to serialise stream event: {}", e);
+                return;
+            }
+        };
+
+        let mut conn = publisher.as_ref().clone();
+        let published: Result<i64, redis::RedisError> = redis::cmd("PUBLISH")
+            .arg(REDIS_CHANNEL)
+            .arg(payload)
+            .query_async(&mut conn)
+            .await;
+
+        match published {
+            Ok(_) => return,
+            Err(e) => tracing::warn!("Failed to publish stream event to Redis, delivering locally: {}", e),
+        }
+    }
+
+    // No receivers is not an error: nobody is connected
+    let _ = LOCAL.send(event);
+}
+
+/// Push the user's current unread count, e.g. after notifications are read or deleted
+pub async fn publish_unread_count(db_client: &DBClient, user_id: Uuid) {
+    match db_client.count_unread_notifications(user_id).await {
+        Ok(unread_count) => publish(StreamEvent::UnreadCount { user_id, unread_count }).await,
+        Err(e) => tracing::warn!("Failed to count unread notifications for {}: {}", user_id, e),
+    }
+}
+
+async fn run_redis_subscriber(client: redis::Client) {
+    let mut backoff = Duration::from_secs(1);
+
+    loop {
+        match forward_redis_messages(&client).await {
+            Ok(()) => tracing::warn!("Notification stream subscription closed, reconnecting"),
+            Err(e) => tracing::warn!("Notification stream subscription failed: {}, retrying in {:?}", e, backoff),
+        }
+
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(Duration::from_secs(30));
+    }
+}
+
+async fn forward_redis_messages(client: &redis::Client) -> Result<(), redis::RedisError> {
+    let mut pubsub = client.get_async_connection().await?.into_pubsub();
+    pubsub.subscribe(REDIS_CHANNEL).await?;
+    tracing::info!("Notification stream subscribed to Redis channel {}", REDIS_CHANNEL);
+
+    let mut messages = pubsub.on_message();
+    while let Some(message) = messages.next().await {
+        let payload: String = match message.get_payload() {
+            Ok(payload) => payload,
+            Err(e) => {
+                tracing::warn!("Unreadable stream event payload: {}", e);
+                continue;
+            }
+        };
+
+        match serde_json::from_str::<StreamEvent>(&payload) {
+            Ok(event) => {
+                let _ = LOCAL.send(event);
+            }
+            Err(e) => tracing::warn!("Invalid stream event payload: {}", e),
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notification(user_id: Uuid) -> Notification {
+        Notification {
+            id: Uuid::new_v4(),
+            user_id,
+            title: "Deposit Successful".to_string(),
+            message: "₦5000.00 - Ref: REF123".to_string(),
+            notification_type: "deposit".to_string(),
+            related_id: None,
+            is_read: false,
+            created_at: DateTime::from_timestamp_micros(1_700_000_000_123_456).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_cursor_round_trips_through_last_event_id() {
+        let user_id = Uuid::new_v4();
+        let event = StreamEvent::Notification {
+            user_id,
+            notification: notification(user_id),
+            unread_count: 3,
+        };
+
+        let cursor = event.cursor().unwrap();
+        assert_eq!(parse_cursor(&cursor.to_string()), Some(notification(user_id).created_at));
+        assert_eq!(parse_cursor("not-a-cursor"), None);
+        assert_eq!(StreamEvent::UnreadCount { user_id, unread_count: 0 }.cursor(), None);
+    }
+
+    #[test]
+    fn test_event_survives_redis_serialisation() {
+        let user_id = Uuid::new_v4();
+        let event = StreamEvent::Notification {
+            user_id,
+            notification: notification(user_id),
+            unread_count: 1,
+        };
+
+        let payload = serde_json::to_string(&event).unwrap();
+        assert_eq!(serde_json::from_str::<StreamEvent>(&payload).unwrap(), event);
+    }
+}
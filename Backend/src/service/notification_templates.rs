@@ -0,0 +1,558 @@
+// service/notification_templates.rs
+use std::collections::HashMap;
+
+use crate::{models::usermodel::Locale, utils::i18n};
+
+/// A notification the dispatcher knows how to render. Copy lives in the
+/// translation catalogue under `notification.<key>.{title,body,short,subject}`;
+/// `{name}` placeholders there are filled from the request variables, and
+/// every variable a template uses must be listed in `variables`.
+#[derive(Debug)]
+pub struct NotificationTemplate {
+    pub key: &'static str,
+    /// Stored on the in-app row and used to pick the preference category
+    pub notification_type: &'static str,
+    /// HTML email used instead of the generic `Mail_notification.html` layout
+    pub email_template: Option<&'static str>,
+    pub variables: &'static [&'static str],
+}
+
+impl NotificationTemplate {
+    pub fn find(key: &str) -> Option<&'static NotificationTemplate> {
+        TEMPLATES.iter().find(|template| template.key == key)
+    }
+
+    pub fn title(&self, locale: Locale) -> String {
+        i18n::t(locale, &format!("notification.{}.title", self.key))
+    }
+
+    pub fn body(&self, locale: Locale) -> String {
+        i18n::t(locale, &format!("notification.{}.body", self.key))
+    }
+
+    /// SMS, WhatsApp and push text; the dispatcher falls back to "title: body"
+    pub fn short_text(&self, locale: Locale) -> Option<&'static str> {
+        i18n::translate(locale, &format!("notification.{}.short", self.key))
+    }
+
+    pub fn email_subject(&self, locale: Locale) -> Option<&'static str> {
+        self.email_template?;
+        i18n::translate(locale, &format!("notification.{}.subject", self.key))
+    }
+}
+
+pub fn render(text: &str, variables: &HashMap<String, String>) -> String {
+    let mut result = text.to_string();
+    for (key, value) in variables {
+        result = result.replace(&format!("{{{}}}", key), value);
+    }
+    result
+}
+
+static TEMPLATES: &[NotificationTemplate] = &[
+    // Jobs
+    NotificationTemplate {
+        key: "new_job",
+        notification_type: "new_job",
+        email_template: None,
+        variables: &["category", "city", "job_title"],
+    },
+    NotificationTemplate {
+        key: "job_application",
+        notification_type: "job_application",
+        email_template: Some("Job-Application.html"),
+        variables: &["applicant_name", "job_title"],
+    },
+    NotificationTemplate {
+        key: "job_application_rejected",
+        notification_type: "job_application_rejected",
+        email_template: None,
+        variables: &["job_title", "rejection_reason"],
+    },
+    NotificationTemplate {
+        key: "job_application_reviewed",
+        notification_type: "job_application_reviewed",
+        email_template: None,
+        variables: &["job_title"],
+    },
+    NotificationTemplate {
+        key: "job_assigned",
+        notification_type: "job_assigned",
+        email_template: Some("Job-Assignment.html"),
+        variables: &["job_title"],
+    },
+    NotificationTemplate {
+        key: "worker_assigned",
+        notification_type: "worker assigned to job",
+        email_template: None,
+        variables: &["job_title"],
+    },
+    NotificationTemplate {
+        key: "progress_update",
+        notification_type: "progress_update",
+        email_template: None,
+        variables: &["progress_percentage", "progress_description"],
+    },
+    NotificationTemplate {
+        key: "job_completion",
+        notification_type: "job_completion",
+        email_template: Some("Job-Completion.html"),
+        variables: &["job_title"],
+    },
+    // Contracts
+    NotificationTemplate {
+        key: "contract_proposal",
+        notification_type: "contract_proposal",
+        email_template: None,
+        variables: &["proposer_name", "job_title"],
+    },
+    NotificationTemplate {
+        key: "contract_awaiting_signature",
+        notification_type: "Contract awaiting signature",
+        email_template: None,
+        variables: &["job_id"],
+    },
+    NotificationTemplate {
+        key: "contract_signed",
+        notification_type: "contract_signed",
+        email_template: None,
+        variables: &["job_title"],
+    },
+    NotificationTemplate {
+        key: "contract_accepted",
+        notification_type: "contract_accepted",
+        email_template: None,
+        variables: &["job_title"],
+    },
+    NotificationTemplate {
+        key: "contract_rejected",
+        notification_type: "contract_rejected",
+        email_template: None,
+        variables: &[],
+    },
+    // Payments
+    NotificationTemplate {
+        key: "payment_released",
+        notification_type: "payment_released",
+        email_template: Some("Payment-Released.html"),
+        variables: &["amount"],
+    },
+    NotificationTemplate {
+        key: "deposit",
+        notification_type: "deposit",
+        email_template: Some("Deposit.html"),
+        variables: &["amount", "reference"],
+    },
+    NotificationTemplate {
+        key: "withdrawal",
+        notification_type: "withdrawal",
+        email_template: Some("Withdrawal.html"),
+        variables: &["amount", "reference"],
+    },
+    NotificationTemplate {
+        key: "transfer_sent",
+        notification_type: "transfer_sent",
+        email_template: Some("Transfer.html"),
+        variables: &["amount", "reference", "direction", "direction_label", "direction_note"],
+    },
+    NotificationTemplate {
+        key: "transfer_received",
+        notification_type: "transfer_received",
+        email_template: Some("Transfer.html"),
+        variables: &["amount", "reference", "direction", "direction_label", "direction_note"],
+    },
+    NotificationTemplate {
+        key: "wallet_transaction",
+        notification_type: "wallet_transaction",
+        email_template: None,
+        variables: &["amount", "reference"],
+    },
+    // Disputes
+    NotificationTemplate {
+        key: "dispute_created",
+        notification_type: "dispute_created",
+        email_template: None,
+        variables: &["dispute_reason"],
+    },
+    NotificationTemplate {
+        key: "dispute_confirmation",
+        notification_type: "dispute_confirmation",
+        email_template: None,
+        variables: &[],
+    },
+    NotificationTemplate {
+        key: "dispute_resolved",
+        notification_type: "dispute_resolved",
+        email_template: None,
+        variables: &["decision"],
+    },
+    NotificationTemplate {
+        key: "service_dispute",
+        notification_type: "service_dispute",
+        email_template: None,
+        variables: &["dispute_reason"],
+    },
+    NotificationTemplate {
+        key: "service_dispute_confirmation",
+        notification_type: "dispute_confirmation",
+        email_template: None,
+        variables: &[],
+    },
+    // Messages
+    NotificationTemplate {
+        key: "new_message",
+        notification_type: "new_message",
+        email_template: None,
+        variables: &["sender_name", "message_preview"],
+    },
+    // Orders and vendor services
+    NotificationTemplate {
+        key: "new_order",
+        notification_type: "new_order",
+        email_template: None,
+        variables: &["order_number", "service_title", "amount"],
+    },
+    NotificationTemplate {
+        key: "order_placed",
+        notification_type: "order_placed",
+        email_template: None,
+        variables: &["order_number", "service_title", "amount"],
+    },
+    NotificationTemplate {
+        key: "order_confirmed",
+        notification_type: "order_confirmed",
+        email_template: None,
+        variables: &["order_number", "service_title"],
+    },
+    NotificationTemplate {
+        key: "order_completed",
+        notification_type: "order_completed",
+        email_template: None,
+        variables: &["service_title", "amount"],
+    },
+    NotificationTemplate {
+        key: "service_order",
+        notification_type: "service_order",
+        email_template: None,
+        variables: &["service_title", "quantity"],
+    },
+    NotificationTemplate {
+        key: "order_confirmation",
+        notification_type: "order_confirmation",
+        email_template: None,
+        variables: &["service_title"],
+    },
+    NotificationTemplate {
+        key: "order_shipped",
+        notification_type: "order_shipped",
+        email_template: None,
+        variables: &["order_number"],
+    },
+    NotificationTemplate {
+        key: "delivery_confirmed",
+        notification_type: "delivery_confirmed",
+        email_template: None,
+        variables: &["order_number"],
+    },
+    NotificationTemplate {
+        key: "service_inquiry",
+        notification_type: "service_inquiry",
+        email_template: Some("Service-Inquiry.html"),
+        variables: &["inquirer_name", "service_title"],
+    },
+    NotificationTemplate {
+        key: "service_expiring",
+        notification_type: "service_expiring",
+        email_template: None,
+        variables: &["service_title", "days"],
+    },
+    NotificationTemplate {
+        key: "subscription_expiring",
+        notification_type: "subscription_expiring",
+        email_template: None,
+        variables: &["days"],
+    },
+    NotificationTemplate {
+        key: "subscription_upgraded",
+        notification_type: "subscription_upgraded",
+        email_template: None,
+        variables: &["tier"],
+    },
+    // Account
+    NotificationTemplate {
+        key: "verification_approved",
+        notification_type: "verification_approved",
+        email_template: Some("Verification-status.html"),
+        variables: &["name", "status", "status_display", "review_notes"],
+    },
+    NotificationTemplate {
+        key: "verification_rejected",
+        notification_type: "verification_rejected",
+        email_template: Some("Verification-status.html"),
+        variables: &["name", "status", "status_display", "review_notes"],
+    },
+    // Property verification pipeline
+    NotificationTemplate {
+        key: "property_assigned",
+        notification_type: "property_assigned",
+        email_template: None,
+        variables: &["property_title", "property_address", "property_type", "deadline", "property_reference"],
+    },
+    NotificationTemplate {
+        key: "property_photo_match",
+        notification_type: "property_photo_match",
+        email_template: None,
+        variables: &["property_title", "matched_listings", "property_reference"],
+    },
+    NotificationTemplate {
+        key: "property_documents_ready",
+        notification_type: "property_documents_ready",
+        email_template: None,
+        variables: &["property_title", "property_address", "document_list", "agent_notes", "deadline", "property_reference"],
+    },
+    NotificationTemplate {
+        key: "property_live",
+        notification_type: "property_live",
+        email_template: None,
+        variables: &["property_title", "property_address", "verification_score", "property_reference"],
+    },
+    NotificationTemplate {
+        key: "property_rejected",
+        notification_type: "property_rejected",
+        email_template: None,
+        variables: &["property_title", "rejection_stage", "rejection_reason", "rejection_details", "property_reference"],
+    },
+    NotificationTemplate {
+        key: "property_viewing_requested",
+        notification_type: "property_viewing_requested",
+        email_template: None,
+        variables: &["property_title", "requester_name", "proposed_slots", "property_reference"],
+    },
+    NotificationTemplate {
+        key: "property_viewing_confirmed",
+        notification_type: "property_viewing_confirmed",
+        email_template: None,
+        variables: &["property_title", "property_address", "scheduled_at", "property_reference"],
+    },
+    NotificationTemplate {
+        key: "property_viewing_declined",
+        notification_type: "property_viewing_declined",
+        email_template: None,
+        variables: &["property_title", "reason"],
+    },
+    NotificationTemplate {
+        key: "property_viewing_cancelled",
+        notification_type: "property_viewing_cancelled",
+        email_template: None,
+        variables: &["property_title", "cancelled_by", "reason"],
+    },
+    NotificationTemplate {
+        key: "property_viewing_reminder",
+        notification_type: "property_viewing_reminder",
+        email_template: None,
+        variables: &["property_title", "property_address", "scheduled_at"],
+    },
+    NotificationTemplate {
+        key: "property_viewing_no_show",
+        notification_type: "property_viewing_no_show",
+        email_template: None,
+        variables: &["property_title", "scheduled_at", "penalty_points"],
+    },
+    NotificationTemplate {
+        key: "property_inspection_fee_released",
+        notification_type: "property_inspection_fee_released",
+        email_template: None,
+        variables: &["property_title", "amount"],
+    },
+    NotificationTemplate {
+        key: "property_tenancy_offer",
+        notification_type: "property_tenancy_offer",
+        email_template: None,
+        variables: &["property_title", "tenant_name", "rent_amount", "rent_frequency", "start_date", "property_reference"],
+    },
+    NotificationTemplate {
+        key: "property_tenancy_accepted",
+        notification_type: "property_tenancy_accepted",
+        email_template: None,
+        variables: &["property_title", "start_date", "end_date", "caution_deposit"],
+    },
+    NotificationTemplate {
+        key: "property_tenancy_declined",
+        notification_type: "property_tenancy_declined",
+        email_template: None,
+        variables: &["property_title", "reason"],
+    },
+    NotificationTemplate {
+        key: "property_tenancy_withdrawn",
+        notification_type: "property_tenancy_withdrawn",
+        email_template: None,
+        variables: &["property_title", "tenant_name"],
+    },
+    NotificationTemplate {
+        key: "property_tenancy_move_out",
+        notification_type: "property_tenancy_move_out",
+        email_template: None,
+        variables: &["property_title", "given_by"],
+    },
+    NotificationTemplate {
+        key: "property_tenancy_ended",
+        notification_type: "property_tenancy_ended",
+        email_template: None,
+        variables: &["property_title", "deposit_refund", "deposit_deduction", "inspection_notes"],
+    },
+    NotificationTemplate {
+        key: "property_rent_paid",
+        notification_type: "property_rent_paid",
+        email_template: None,
+        variables: &["property_title", "amount", "due_date", "receipt_number"],
+    },
+    NotificationTemplate {
+        key: "property_rent_received",
+        notification_type: "property_rent_received",
+        email_template: None,
+        variables: &["property_title", "amount", "due_date", "receipt_number"],
+    },
+    NotificationTemplate {
+        key: "property_rent_payment_failed",
+        notification_type: "property_rent_payment_failed",
+        email_template: None,
+        variables: &["property_title", "amount", "due_date", "grace_deadline", "late_fee"],
+    },
+    NotificationTemplate {
+        key: "property_offer_received",
+        notification_type: "property_offer_received",
+        email_template: None,
+        variables: &["property_title", "buyer_name", "amount", "expires_at", "property_reference"],
+    },
+    NotificationTemplate {
+        key: "property_offer_countered",
+        notification_type: "property_offer_countered",
+        email_template: None,
+        variables: &["property_title", "counter_amount", "expires_at"],
+    },
+    NotificationTemplate {
+        key: "property_offer_accepted",
+        notification_type: "property_offer_accepted",
+        email_template: None,
+        variables: &["property_title", "amount", "earnest_deposit"],
+    },
+    NotificationTemplate {
+        key: "property_offer_rejected",
+        notification_type: "property_offer_rejected",
+        email_template: None,
+        variables: &["property_title", "reason"],
+    },
+    NotificationTemplate {
+        key: "property_offer_withdrawn",
+        notification_type: "property_offer_withdrawn",
+        email_template: None,
+        variables: &["property_title", "buyer_name"],
+    },
+    NotificationTemplate {
+        key: "property_offer_expired",
+        notification_type: "property_offer_expired",
+        email_template: None,
+        variables: &["property_title", "amount"],
+    },
+    NotificationTemplate {
+        key: "property_offer_cancelled",
+        notification_type: "property_offer_cancelled",
+        email_template: None,
+        variables: &["property_title", "cancelled_by", "reason"],
+    },
+    NotificationTemplate {
+        key: "property_offer_completed",
+        notification_type: "property_offer_completed",
+        email_template: None,
+        variables: &["property_title", "amount", "earnest_deposit"],
+    },
+    NotificationTemplate {
+        key: "property_saved_search_match",
+        notification_type: "property_saved_search_match",
+        email_template: None,
+        variables: &["search_name", "listing_count", "property_title", "property_price", "property_reference"],
+    },
+    NotificationTemplate {
+        key: "property_price_drop",
+        notification_type: "property_price_drop",
+        email_template: None,
+        variables: &["property_title", "old_price", "new_price", "property_reference"],
+    },
+    NotificationTemplate {
+        key: "property_verification_reminder",
+        notification_type: "property_verification_reminder",
+        email_template: None,
+        variables: &["property_title", "property_address", "deadline", "property_reference"],
+    },
+    NotificationTemplate {
+        key: "property_verification_reassigned",
+        notification_type: "property_verification_reassigned",
+        email_template: None,
+        variables: &["property_title", "property_reference"],
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_template_keys_are_unique() {
+        let mut seen = HashSet::new();
+        for template in TEMPLATES.iter() {
+            assert!(seen.insert(template.key), "duplicate template key {}", template.key);
+        }
+    }
+
+    #[test]
+    fn test_templates_have_catalogue_entries() {
+        for template in TEMPLATES.iter() {
+            for part in ["title", "body"] {
+                let key = format!("notification.{}.{}", template.key, part);
+                assert!(i18n::translate(Locale::En, &key).is_some(), "missing {}", key);
+            }
+            assert_eq!(
+                template.email_subject(Locale::En).is_some(),
+                template.email_template.is_some(),
+                "template {} subject does not match its email template",
+                template.key
+            );
+        }
+    }
+
+    #[test]
+    fn test_templates_only_use_declared_variables() {
+        let placeholder = Regex::new(r"\{([a-z_]+)\}").unwrap();
+
+        for template in TEMPLATES.iter() {
+            let mut texts = vec![template.title(Locale::En), template.body(Locale::En)];
+            texts.extend(template.short_text(Locale::En).map(String::from));
+            texts.extend(template.email_subject(Locale::En).map(String::from));
+
+            for text in texts {
+                for capture in placeholder.captures_iter(&text) {
+                    assert!(
+                        template.variables.contains(&&capture[1]),
+                        "template {} uses undeclared variable {}",
+                        template.key,
+                        &capture[1]
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_render_fills_variables() {
+        let template = NotificationTemplate::find("job_application").unwrap();
+        let variables = HashMap::from([
+            ("applicant_name".to_string(), "Ada".to_string()),
+            ("job_title".to_string(), "Tiling".to_string()),
+        ]);
+
+        assert_eq!(render(&template.body(Locale::En), &variables), "Ada has applied for your job: Tiling");
+        assert_eq!(render(&template.body(Locale::Pcm), &variables), "Ada don apply for your work: Tiling");
+    }
+}
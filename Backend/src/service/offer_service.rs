@@ -0,0 +1,519 @@
+// src/service/offer_service.rs
+use std::sync::Arc;
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use crate::{
+    db::{db::DBClient, naira_walletdb::NairaWalletExt, offerdb::OfferExt, userdb::UserExt},
+    dtos::offerdtos::{CounterOfferDto, MakeOfferDto},
+    models::{
+        offermodels::{OfferBookSummary, OfferStatus, PropertyOffer},
+        propertymodel::{ListingType, PropertyStatus},
+        walletmodels::{kobo_to_naira, naira_to_kobo, TransactionType},
+    },
+    service::{error::ServiceError, notification_service::NotificationService, property_service::PropertyService},
+};
+
+/// Share of the agreed price held from the buyer when an offer is accepted
+pub const EARNEST_DEPOSIT_PERCENT: i64 = 10;
+pub const DEFAULT_OFFER_DAYS: i64 = 7;
+pub const MAX_OFFER_DAYS: i64 = 30;
+
+/// Where a held earnest deposit goes when an accepted offer closes
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DepositSettlement {
+    /// To the landlord, as part of the price
+    Pay,
+    /// Back to the buyer's available balance
+    Refund,
+}
+
+#[derive(Debug)]
+pub struct OfferService {
+    db_client: Arc<DBClient>,
+    property_service: Arc<PropertyService>,
+    notification_service: Arc<NotificationService>,
+}
+
+impl OfferService {
+    pub fn new(
+        db_client: Arc<DBClient>,
+        property_service: Arc<PropertyService>,
+        notification_service: Arc<NotificationService>,
+    ) -> Self {
+        Self {
+            db_client,
+            property_service,
+            notification_service,
+        }
+    }
+
+    /// A buyer puts an offer on a live listing. Rentals go through tenancy offers instead.
+    pub async fn make_offer(
+        &self,
+        buyer_id: Uuid,
+        offer: MakeOfferDto,
+    ) -> Result<PropertyOffer, ServiceError> {
+        let property = self.property_service.get_property(offer.property_id).await?;
+        if property.status != PropertyStatus::Active || closing_status(property.listing_type).is_none() {
+            return Err(ServiceError::Validation("Offers can only be made on active sale or lease listings".to_string()));
+        }
+        if property.landlord_id == buyer_id {
+            return Err(ServiceError::Validation("You cannot make an offer on your own property".to_string()));
+        }
+        if !property.price_negotiable.unwrap_or(false) && offer.amount < property.price {
+            return Err(ServiceError::Validation("The price of this listing is not negotiable".to_string()));
+        }
+
+        let expires_at = offer_expiry(offer.expires_at, Utc::now())?;
+
+        let created = self.db_client
+            .create_offer(buyer_id, property.landlord_id, property.id, offer.amount, offer.conditions.as_deref(), expires_at)
+            .await
+            .map_err(|e| match &e {
+                sqlx::Error::Database(db_error) if db_error.is_unique_violation() => {
+                    ServiceError::Validation("You already have an open offer on this property".to_string())
+                }
+                _ => ServiceError::Database(e),
+            })?;
+
+        self.refresh_bidding_price(property.id).await;
+
+        let buyer_name = self.user_name(buyer_id).await?;
+        if let Err(e) = self.notification_service
+            .notify_offer_received(property.landlord_id, &property, &created, &buyer_name)
+            .await
+        {
+            tracing::warn!("Failed to notify landlord of offer {}: {}", created.id, e);
+        }
+
+        Ok(created)
+    }
+
+    pub async fn get_offer(&self, offer_id: Uuid) -> Result<PropertyOffer, ServiceError> {
+        self.db_client
+            .get_offer(offer_id)
+            .await?
+            .ok_or(ServiceError::OfferNotFound(offer_id))
+    }
+
+    /// An offer as seen by its buyer or the landlord
+    pub async fn get_offer_for(&self, user_id: Uuid, offer_id: Uuid) -> Result<PropertyOffer, ServiceError> {
+        let offer = self.get_offer(offer_id).await?;
+        if user_id != offer.buyer_id && user_id != offer.landlord_id {
+            return Err(ServiceError::UnauthorizedOfferAccess(user_id, offer_id));
+        }
+        Ok(offer)
+    }
+
+    /// The landlord's view of every offer on a listing
+    pub async fn offer_book(
+        &self,
+        landlord_id: Uuid,
+        property_id: Uuid,
+    ) -> Result<(OfferBookSummary, Vec<PropertyOffer>), ServiceError> {
+        let property = self.property_service.get_property(property_id).await?;
+        if property.landlord_id != landlord_id {
+            return Err(ServiceError::UnauthorizedPropertyAccess(landlord_id, property_id));
+        }
+
+        let summary = self.db_client.get_offer_book_summary(property.id).await?;
+        let offers = self.db_client.get_offers_for_property(property.id).await?;
+        Ok((summary, offers))
+    }
+
+    pub async fn counter(
+        &self,
+        landlord_id: Uuid,
+        offer_id: Uuid,
+        counter: CounterOfferDto,
+    ) -> Result<PropertyOffer, ServiceError> {
+        let offer = self.get_offer(offer_id).await?;
+        if offer.landlord_id != landlord_id {
+            return Err(ServiceError::UnauthorizedOfferAccess(landlord_id, offer_id));
+        }
+        ensure_transition(&offer, OfferStatus::Countered)?;
+
+        let expires_at = offer_expiry(counter.expires_at, Utc::now())?;
+        let countered = self.db_client
+            .counter_offer(offer.id, counter.amount, counter.conditions.as_deref(), expires_at)
+            .await?
+            .ok_or(ServiceError::InvalidOfferTransition(offer.id, offer.status, OfferStatus::Countered))?;
+
+        let property = self.property_service.get_property(countered.property_id).await?;
+        if let Err(e) = self.notification_service
+            .notify_offer_countered(countered.buyer_id, &property, &countered)
+            .await
+        {
+            tracing::warn!("Failed to notify buyer of counter on offer {}: {}", countered.id, e);
+        }
+
+        Ok(countered)
+    }
+
+    /// The landlord accepts a pending offer or the buyer accepts a counter. Either
+    /// way the earnest deposit is held in the buyer's wallet until completion.
+    pub async fn accept(&self, user_id: Uuid, offer_id: Uuid) -> Result<PropertyOffer, ServiceError> {
+        let offer = self.get_offer(offer_id).await?;
+        if offer.awaiting() != Some(user_id) {
+            return Err(ServiceError::UnauthorizedOfferAccess(user_id, offer_id));
+        }
+        ensure_transition(&offer, OfferStatus::Accepted)?;
+        if offer.expires_at < Utc::now() {
+            return Err(ServiceError::Validation("This offer has expired".to_string()));
+        }
+
+        let property = self.property_service.get_property(offer.property_id).await?;
+        if property.status != PropertyStatus::Active {
+            return Err(ServiceError::Validation("The property is no longer available".to_string()));
+        }
+
+        let agreed_amount = offer.standing_amount();
+        let deposit = earnest_deposit(agreed_amount);
+        let deposit_hold_id = if deposit > 0 {
+            Some(self.hold_deposit(&offer, deposit).await?)
+        } else {
+            None
+        };
+
+        let accepted = self.db_client
+            .accept_offer(offer.id, offer.status, agreed_amount, deposit, deposit_hold_id)
+            .await;
+
+        let accepted = match accepted {
+            Ok(Some(accepted)) => accepted,
+            other => {
+                if let Some(hold_id) = deposit_hold_id {
+                    if let Err(e) = self.db_client.release_wallet_hold(hold_id, true).await {
+                        tracing::error!("Failed to return earnest deposit hold {}: {:?}", hold_id, e);
+                    }
+                }
+                return Err(match other {
+                    Err(sqlx::Error::Database(db_error)) if db_error.is_unique_violation() => {
+                        ServiceError::Validation("Another offer on this property has already been accepted".to_string())
+                    }
+                    Err(e) => ServiceError::Database(e),
+                    Ok(_) => ServiceError::InvalidOfferTransition(offer.id, offer.status, OfferStatus::Accepted),
+                });
+            }
+        };
+
+        self.refresh_bidding_price(property.id).await;
+
+        let other_party = if user_id == accepted.buyer_id { accepted.landlord_id } else { accepted.buyer_id };
+        if let Err(e) = self.notification_service
+            .notify_offer_accepted(other_party, &property, &accepted)
+            .await
+        {
+            tracing::warn!("Failed to notify {} of accepted offer {}: {}", other_party, accepted.id, e);
+        }
+
+        Ok(accepted)
+    }
+
+    pub async fn reject(
+        &self,
+        landlord_id: Uuid,
+        offer_id: Uuid,
+        reason: &str,
+    ) -> Result<PropertyOffer, ServiceError> {
+        let offer = self.get_offer(offer_id).await?;
+        if offer.landlord_id != landlord_id {
+            return Err(ServiceError::UnauthorizedOfferAccess(landlord_id, offer_id));
+        }
+
+        let offer = self.transition(&offer, OfferStatus::Rejected, Some(reason)).await?;
+        self.refresh_bidding_price(offer.property_id).await;
+
+        let property = self.property_service.get_property(offer.property_id).await?;
+        if let Err(e) = self.notification_service
+            .notify_offer_rejected(offer.buyer_id, &property, &offer, reason)
+            .await
+        {
+            tracing::warn!("Failed to notify buyer of rejected offer {}: {}", offer.id, e);
+        }
+
+        Ok(offer)
+    }
+
+    pub async fn withdraw(&self, buyer_id: Uuid, offer_id: Uuid) -> Result<PropertyOffer, ServiceError> {
+        let offer = self.get_offer(offer_id).await?;
+        if offer.buyer_id != buyer_id {
+            return Err(ServiceError::UnauthorizedOfferAccess(buyer_id, offer_id));
+        }
+
+        let offer = self.transition(&offer, OfferStatus::Withdrawn, None).await?;
+        self.refresh_bidding_price(offer.property_id).await;
+
+        let property = self.property_service.get_property(offer.property_id).await?;
+        let buyer_name = self.user_name(buyer_id).await?;
+        if let Err(e) = self.notification_service
+            .notify_offer_withdrawn(offer.landlord_id, &property, &offer, &buyer_name)
+            .await
+        {
+            tracing::warn!("Failed to notify landlord of withdrawn offer {}: {}", offer.id, e);
+        }
+
+        Ok(offer)
+    }
+
+    /// Either side calls off an accepted deal. The earnest deposit goes back to the
+    /// buyer and the remaining offers on the listing can be taken up again.
+    pub async fn cancel(
+        &self,
+        user_id: Uuid,
+        offer_id: Uuid,
+        reason: &str,
+    ) -> Result<PropertyOffer, ServiceError> {
+        let offer = self.get_offer_for(user_id, offer_id).await?;
+        let offer = self.close(&offer, OfferStatus::Cancelled, Some(reason), DepositSettlement::Refund).await?;
+        self.refresh_bidding_price(offer.property_id).await;
+
+        let property = self.property_service.get_property(offer.property_id).await?;
+        let cancelled_by = self.user_name(user_id).await?;
+        let other_party = if user_id == offer.buyer_id { offer.landlord_id } else { offer.buyer_id };
+
+        if let Err(e) = self.notification_service
+            .notify_offer_cancelled(other_party, &property, &offer, &cancelled_by, reason)
+            .await
+        {
+            tracing::warn!("Failed to notify {} of cancelled offer {}: {}", other_party, offer.id, e);
+        }
+
+        Ok(offer)
+    }
+
+    /// The buyer confirms the transaction went through. The earnest deposit is paid
+    /// to the landlord as part of the price and the listing is closed as Sold or Rented.
+    pub async fn complete(&self, buyer_id: Uuid, offer_id: Uuid) -> Result<PropertyOffer, ServiceError> {
+        let offer = self.get_offer(offer_id).await?;
+        if offer.buyer_id != buyer_id {
+            return Err(ServiceError::UnauthorizedOfferAccess(buyer_id, offer_id));
+        }
+        ensure_transition(&offer, OfferStatus::Completed)?;
+
+        let property = self.property_service.get_property(offer.property_id).await?;
+        let closed_as = closing_status(property.listing_type)
+            .ok_or(ServiceError::Validation("This listing cannot be closed through an offer".to_string()))?;
+        if property.status != PropertyStatus::Active {
+            return Err(ServiceError::InvalidPropertyTransition(property.id, property.status, closed_as));
+        }
+
+        let completed = self.close(&offer, OfferStatus::Completed, None, DepositSettlement::Pay).await?;
+
+        if let Err(e) = self.property_service.transition(&property, closed_as).await {
+            tracing::error!("Offer {} completed but property {} could not be closed: {}", completed.id, property.id, e);
+        }
+
+        let note = "The property is no longer available";
+        let closed = self.db_client.reject_open_offers(property.id, note).await?;
+        for offer in closed {
+            if let Err(e) = self.notification_service
+                .notify_offer_rejected(offer.buyer_id, &property, &offer, note)
+                .await
+            {
+                tracing::warn!("Failed to notify buyer of rejected offer {}: {}", offer.id, e);
+            }
+        }
+
+        if let Err(e) = self.notification_service
+            .notify_offer_completed(completed.landlord_id, &property, &completed)
+            .await
+        {
+            tracing::warn!("Failed to notify landlord of completed offer {}: {}", completed.id, e);
+        }
+
+        Ok(completed)
+    }
+
+    /// Close open offers past their deadline. Run by the offer expiry job.
+    pub async fn expire_offers(&self) -> Result<usize, ServiceError> {
+        let expired = self.db_client.expire_offers(200).await?;
+
+        for offer in &expired {
+            self.refresh_bidding_price(offer.property_id).await;
+
+            let property = match self.property_service.get_property(offer.property_id).await {
+                Ok(property) => property,
+                Err(e) => {
+                    tracing::warn!("Failed to load property for expired offer {}: {}", offer.id, e);
+                    continue;
+                }
+            };
+            if let Err(e) = self.notification_service
+                .notify_offer_expired(offer.buyer_id, &property, offer)
+                .await
+            {
+                tracing::warn!("Failed to notify buyer of expired offer {}: {}", offer.id, e);
+            }
+        }
+
+        Ok(expired.len())
+    }
+
+    async fn hold_deposit(&self, offer: &PropertyOffer, deposit: i64) -> Result<Uuid, ServiceError> {
+        let wallet = self.db_client
+            .get_naira_wallet(offer.buyer_id)
+            .await?
+            .ok_or(ServiceError::Validation("The buyer has no Naira wallet for the earnest deposit".to_string()))?;
+
+        let hold = self.db_client
+            .create_wallet_hold(
+                wallet.id,
+                None,
+                naira_to_kobo(deposit as f64),
+                format!("Earnest deposit for offer {}", offer.id),
+                None,
+            )
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => ServiceError::InsufficientEscrowFunds {
+                    required: deposit as f64,
+                    available: kobo_to_naira(wallet.available_balance),
+                },
+                _ => ServiceError::Database(e),
+            })?;
+
+        Ok(hold.id)
+    }
+
+    /// Moves the offer to `to` and settles its earnest deposit in one
+    /// transaction, so a failed payout leaves the offer as it was to retry
+    async fn close(
+        &self,
+        offer: &PropertyOffer,
+        to: OfferStatus,
+        note: Option<&str>,
+        deposit: DepositSettlement,
+    ) -> Result<PropertyOffer, ServiceError> {
+        ensure_transition(offer, to)?;
+
+        let mut tx = self.db_client.pool.begin().await?;
+        let closed = self.db_client
+            .transition_offer_status_tx(offer.id, offer.status, to, note, &mut tx)
+            .await?
+            .ok_or(ServiceError::InvalidOfferTransition(offer.id, offer.status, to))?;
+
+        if let Err(e) = self.settle_deposit(&closed, deposit, &mut tx).await {
+            tracing::error!("Failed to settle earnest deposit for offer {}: {:?}", offer.id, e);
+            return Err(ServiceError::Database(e));
+        }
+        tx.commit().await?;
+
+        Ok(closed)
+    }
+
+    /// Release the held deposit back to the buyer, or take it from them and
+    /// pay it to the landlord as part of the price
+    async fn settle_deposit(
+        &self,
+        offer: &PropertyOffer,
+        deposit: DepositSettlement,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<(), sqlx::Error> {
+        let (Some(hold_id), Some(amount)) = (offer.deposit_hold_id, offer.earnest_deposit) else {
+            return Ok(());
+        };
+
+        if deposit == DepositSettlement::Refund {
+            return self.db_client.release_wallet_hold_tx(hold_id, true, tx).await;
+        }
+
+        self.db_client.release_wallet_hold_tx(hold_id, false, tx).await?;
+        self.db_client.credit_wallet_tx(
+            offer.landlord_id,
+            naira_to_kobo(amount as f64),
+            TransactionType::ServicePayment,
+            format!("Earnest deposit for offer {}", offer.id),
+            format!("earnest_deposit_{}", offer.id),
+            None,
+            None,
+            tx,
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn transition(
+        &self,
+        offer: &PropertyOffer,
+        to: OfferStatus,
+        note: Option<&str>,
+    ) -> Result<PropertyOffer, ServiceError> {
+        ensure_transition(offer, to)?;
+
+        self.db_client
+            .transition_offer_status(offer.id, offer.status, to, note)
+            .await?
+            .ok_or(ServiceError::InvalidOfferTransition(offer.id, offer.status, to))
+    }
+
+    async fn refresh_bidding_price(&self, property_id: Uuid) {
+        if let Err(e) = self.db_client.refresh_bidding_price(property_id).await {
+            tracing::warn!("Failed to refresh bidding price for property {}: {:?}", property_id, e);
+        }
+    }
+
+    async fn user_name(&self, user_id: Uuid) -> Result<String, ServiceError> {
+        Ok(self.db_client
+            .get_user(Some(user_id), None, None, None)
+            .await?
+            .map(|user| user.name)
+            .unwrap_or_default())
+    }
+}
+
+fn ensure_transition(offer: &PropertyOffer, to: OfferStatus) -> Result<(), ServiceError> {
+    if offer.status.can_transition_to(to) {
+        Ok(())
+    } else {
+        Err(ServiceError::InvalidOfferTransition(offer.id, offer.status, to))
+    }
+}
+
+pub fn earnest_deposit(agreed_amount: i64) -> i64 {
+    agreed_amount * EARNEST_DEPOSIT_PERCENT / 100
+}
+
+/// What a listing becomes once an offer on it completes. Rent listings are let
+/// through tenancy offers, not the offer book.
+fn closing_status(listing_type: ListingType) -> Option<PropertyStatus> {
+    match listing_type {
+        ListingType::Sale | ListingType::Asset => Some(PropertyStatus::Sold),
+        ListingType::Lease => Some(PropertyStatus::Rented),
+        ListingType::Rent => None,
+    }
+}
+
+fn offer_expiry(requested: Option<DateTime<Utc>>, now: DateTime<Utc>) -> Result<DateTime<Utc>, ServiceError> {
+    let expires_at = requested.unwrap_or(now + Duration::days(DEFAULT_OFFER_DAYS));
+    if expires_at <= now {
+        return Err(ServiceError::Validation("The offer must expire in the future".to_string()));
+    }
+    if expires_at > now + Duration::days(MAX_OFFER_DAYS) {
+        return Err(ServiceError::Validation(format!("Offers can stay open for at most {} days", MAX_OFFER_DAYS)));
+    }
+    Ok(expires_at)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offer_expiry_defaults_to_a_week_and_is_capped() {
+        let now = Utc::now();
+
+        assert_eq!(offer_expiry(None, now).unwrap(), now + Duration::days(DEFAULT_OFFER_DAYS));
+        assert!(offer_expiry(Some(now - Duration::hours(1)), now).is_err());
+        assert!(offer_expiry(Some(now + Duration::days(MAX_OFFER_DAYS + 1)), now).is_err());
+    }
+
+    #[test]
+    fn test_listings_close_by_listing_type() {
+        assert_eq!(closing_status(ListingType::Sale), Some(PropertyStatus::Sold));
+        assert_eq!(closing_status(ListingType::Lease), Some(PropertyStatus::Rented));
+        assert_eq!(closing_status(ListingType::Rent), None);
+        assert_eq!(earnest_deposit(45_000_000), 4_500_000);
+    }
+}
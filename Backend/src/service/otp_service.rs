@@ -0,0 +1,221 @@
+// service/otp_service.rs
+use std::fmt;
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    db::{db::DBClient, phonedb::PhoneExt, verificationdb::VerificationExt},
+    mail::mails,
+    models::{
+        usermodel::User,
+        verificationmodels::{OtpChannel, OtpPurpose, PhoneStatus},
+    },
+    service::{
+        attempt_guard::{AttemptGuard, AttemptKind},
+        error::ServiceError,
+        sms::SmsSender,
+    },
+    utils::{i18n, otp_generator::generate_otp, phone},
+};
+
+pub const OTP_VALID_MINUTES: i64 = 10;
+
+/// At most this many codes to one number in each window, as (codes, minutes)
+const PHONE_OTP_LIMITS: [(i64, i64); 3] = [(1, 1), (5, 60), (10, 1440)];
+
+/// Where a code went, for the client to tell the user
+#[derive(Debug, Serialize)]
+pub struct OtpDelivery {
+    pub channel: OtpChannel,
+    /// Masked for SMS
+    pub destination: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Sends one-time codes by email or SMS and verifies phone numbers. Codes
+/// go where the user asked, falling back to their saved choice.
+pub struct OtpService {
+    db_client: Arc<DBClient>,
+    sms: Option<Arc<dyn SmsSender>>,
+    attempt_guard: AttemptGuard,
+}
+
+impl fmt::Debug for OtpService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OtpService")
+            .field("sms", &self.sms.as_ref().map(|sms| sms.name()))
+            .finish()
+    }
+}
+
+impl OtpService {
+    pub fn new(db_client: Arc<DBClient>, sms: Option<Arc<dyn SmsSender>>) -> Self {
+        Self { attempt_guard: AttemptGuard::new(&db_client), db_client, sms }
+    }
+
+    pub async fn phone_status(&self, user_id: Uuid) -> Result<PhoneStatus, ServiceError> {
+        Ok(self.db_client.get_phone_status(user_id).await?.unwrap_or_default())
+    }
+
+    /// Sends a `purpose` code by `channel`, or the user's saved channel.
+    /// Emailed codes go to `email` when given, else the account's address.
+    pub async fn send(
+        &self,
+        user: &User,
+        purpose: OtpPurpose,
+        channel: Option<OtpChannel>,
+        email: Option<&str>,
+    ) -> Result<OtpDelivery, ServiceError> {
+        let status = self.phone_status(user.id).await?;
+
+        match channel.unwrap_or(status.otp_channel) {
+            OtpChannel::Email => {
+                let email = email.unwrap_or(&user.email);
+                let otp_code = generate_otp();
+                let expires_at = Utc::now() + Duration::minutes(OTP_VALID_MINUTES);
+
+                self.db_client
+                    .create_otp(user.id, email.to_string(), otp_code.clone(), purpose.clone(), expires_at)
+                    .await?;
+
+                if let Err(e) = mails::send_otp_email(email, &otp_code, &purpose, user.locale).await {
+                    return Err(ServiceError::Notification(e.to_string()));
+                }
+
+                Ok(OtpDelivery { channel: OtpChannel::Email, destination: email.to_string(), expires_at })
+            }
+            OtpChannel::Sms => {
+                let phone_number = status.verified_phone().ok_or_else(|| {
+                    ServiceError::Validation("Verify a phone number before receiving codes by SMS".to_string())
+                })?;
+                self.send_sms_code(user, phone_number, purpose).await
+            }
+        }
+    }
+
+    /// Texts a code to a number the user wants to add
+    pub async fn start_phone_verification(&self, user: &User, phone_number: &str) -> Result<OtpDelivery, ServiceError> {
+        let phone_number = normalize(phone_number)?;
+        self.ensure_phone_available(user.id, &phone_number).await?;
+
+        self.send_sms_code(user, &phone_number, OtpPurpose::PhoneVerification).await
+    }
+
+    /// Saves the number as verified once the code checks out
+    pub async fn confirm_phone(&self, user: &User, phone_number: &str, code: &str) -> Result<PhoneStatus, ServiceError> {
+        let phone_number = normalize(phone_number)?;
+        let account = user.id.to_string();
+        self.attempt_guard.check(AttemptKind::Otp, &account, None).await?;
+
+        let otp = match self.db_client
+            .get_valid_phone_otp(user.id, &phone_number, code.trim(), OtpPurpose::PhoneVerification)
+            .await?
+        {
+            Some(otp) => otp,
+            None => {
+                self.attempt_guard.record_failure(AttemptKind::Otp, &account, None).await;
+                return Err(ServiceError::Validation("Invalid or expired code".to_string()));
+            }
+        };
+        self.db_client.mark_otp_used(otp.id).await?;
+        self.attempt_guard.record_success(AttemptKind::Otp, &account).await;
+
+        self.ensure_phone_available(user.id, &phone_number).await?;
+        self.db_client.set_verified_phone(user.id, &phone_number).await?;
+
+        self.phone_status(user.id).await
+    }
+
+    /// Checks and uses up a code sent to `email`, or by SMS to the account
+    /// with that email. Failures count against the address and the IP.
+    pub async fn verify(
+        &self,
+        email: &str,
+        code: &str,
+        purpose: OtpPurpose,
+        ip_address: Option<&str>,
+    ) -> Result<(), ServiceError> {
+        self.attempt_guard.check(AttemptKind::Otp, email, ip_address).await?;
+
+        match self.db_client.get_valid_otp(email, code.trim(), purpose).await? {
+            Some(otp) => {
+                self.db_client.mark_otp_used(otp.id).await?;
+                self.attempt_guard.record_success(AttemptKind::Otp, email).await;
+                Ok(())
+            }
+            None => {
+                self.attempt_guard.record_failure(AttemptKind::Otp, email, ip_address).await;
+                Err(ServiceError::Validation("Invalid or expired OTP".to_string()))
+            }
+        }
+    }
+
+    pub async fn set_channel(&self, user_id: Uuid, channel: OtpChannel) -> Result<PhoneStatus, ServiceError> {
+        if channel == OtpChannel::Sms {
+            if self.sms.is_none() {
+                return Err(ServiceError::SmsUnavailable);
+            }
+            if self.phone_status(user_id).await?.verified_phone().is_none() {
+                return Err(ServiceError::Validation(
+                    "Verify a phone number before receiving codes by SMS".to_string(),
+                ));
+            }
+        }
+
+        self.db_client.set_otp_channel(user_id, channel).await?;
+        self.phone_status(user_id).await
+    }
+
+    async fn send_sms_code(&self, user: &User, phone_number: &str, purpose: OtpPurpose) -> Result<OtpDelivery, ServiceError> {
+        let sms = self.sms.as_ref().ok_or(ServiceError::SmsUnavailable)?;
+        self.check_send_limits(phone_number).await?;
+
+        let otp_code = generate_otp();
+        let expires_at = Utc::now() + Duration::minutes(OTP_VALID_MINUTES);
+        self.db_client
+            .create_phone_otp(user.id, &user.email, phone_number, &otp_code, purpose, expires_at)
+            .await?;
+
+        let body = i18n::format(
+            user.locale,
+            "sms.otp",
+            &[("code", &otp_code), ("minutes", &OTP_VALID_MINUTES.to_string())],
+        );
+        sms.send(phone_number, &body)
+            .await
+            .map_err(|e| ServiceError::Notification(e.to_string()))?;
+
+        Ok(OtpDelivery { channel: OtpChannel::Sms, destination: phone::mask(phone_number), expires_at })
+    }
+
+    /// Every SMS is paid for, and a number is someone's phone; both are
+    /// protected whichever account asks
+    async fn check_send_limits(&self, phone_number: &str) -> Result<(), ServiceError> {
+        for (limit, minutes) in PHONE_OTP_LIMITS {
+            let sent = self.db_client
+                .count_phone_otps_since(phone_number, Utc::now() - Duration::minutes(minutes))
+                .await?;
+            if sent >= limit {
+                return Err(ServiceError::TooManyCodes(minutes));
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn ensure_phone_available(&self, user_id: Uuid, phone_number: &str) -> Result<(), ServiceError> {
+        match self.db_client.get_verified_phone_owner(phone_number).await? {
+            Some(owner) if owner != user_id => Err(ServiceError::PhoneInUse),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn normalize(phone_number: &str) -> Result<String, ServiceError> {
+    phone::normalize(phone_number).ok_or_else(|| {
+        ServiceError::Validation("Enter a valid mobile number, e.g. 08031234567 or +2348031234567".to_string())
+    })
+}
@@ -0,0 +1,138 @@
+// service/privacy_service.rs
+//! Self-service data export and account deletion.
+//!
+//! Deleting an account anonymises it rather than removing the row. Wallet
+//! transactions, escrows and orders must be kept for tax and anti-money
+//! laundering purposes, and KYC submissions for five years after the
+//! relationship ends, so those stay and point at an account that no longer
+//! says who it belonged to.
+use std::sync::Arc;
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        db::DBClient,
+        privacydb::{PrivacyExt, EXPORT_SECTIONS},
+    },
+    models::{privacymodels::DeletionBlockers, usermodel::User},
+    service::{audit_service::AuditService, error::ServiceError, session_service::REVOKED_ACCOUNT_DELETED},
+    utils::zip::ZipWriter,
+};
+
+/// Columns that are credentials rather than information about the user
+const REDACTED_FIELDS: [&str; 6] = [
+    "password",
+    "transaction_pin",
+    "transaction_pin_hash",
+    "verification_token",
+    "token_expires_at",
+    "number_hash",
+];
+
+const README: &str = "\
+This archive holds the personal data Verinest keeps about your account.
+
+Each folder covers one part of the platform and each file is a JSON array of
+records. Amounts in the wallet folder are in kobo (100 kobo = 1 naira).
+Passwords, PINs and other credentials are left out.
+";
+
+/// A ready-to-download archive
+pub struct DataExport {
+    pub file_name: String,
+    pub contents: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub struct PrivacyService {
+    db_client: Arc<DBClient>,
+    audit_service: Arc<AuditService>,
+}
+
+impl PrivacyService {
+    pub fn new(db_client: Arc<DBClient>, audit_service: Arc<AuditService>) -> Self {
+        Self { db_client, audit_service }
+    }
+
+    /// A ZIP of the user's profile, wallet, jobs, chats, reviews and
+    /// verification records, one JSON file per section
+    pub async fn export(&self, user: &User) -> Result<DataExport, ServiceError> {
+        let now = Utc::now();
+        let mut zip = ZipWriter::new(now);
+        zip.add("README.txt", README.as_bytes()).map_err(archive_error)?;
+
+        for section in EXPORT_SECTIONS {
+            let mut rows = self.db_client.export_section(section, user.id).await?;
+            redact(&mut rows);
+
+            let contents = serde_json::to_vec_pretty(&rows)
+                .map_err(|e| ServiceError::Other(e.to_string()))?;
+            zip.add(section.file, &contents).map_err(archive_error)?;
+        }
+
+        let contents = zip.finish().map_err(archive_error)?;
+        self.audit_service.log_data_export(user.id, EXPORT_SECTIONS.len()).await?;
+
+        Ok(DataExport {
+            file_name: format!("verinest-data-{}.zip", now.format("%Y%m%d")),
+            contents,
+        })
+    }
+
+    pub async fn deletion_blockers(&self, user_id: Uuid) -> Result<DeletionBlockers, ServiceError> {
+        Ok(self.db_client.get_deletion_blockers(user_id).await?)
+    }
+
+    /// Anonymises the account and signs it out everywhere, once nothing
+    /// holds money or is under dispute
+    pub async fn delete_account(&self, user_id: Uuid) -> Result<(), ServiceError> {
+        let blockers = self.deletion_blockers(user_id).await?;
+        if !blockers.is_clear() {
+            return Err(ServiceError::AccountDeletionBlocked(blockers.reasons().join("; ")));
+        }
+
+        self.db_client.anonymise_user(user_id, REVOKED_ACCOUNT_DELETED).await?;
+        self.audit_service.log_account_deletion(user_id).await?;
+
+        Ok(())
+    }
+}
+
+fn archive_error(err: std::io::Error) -> ServiceError {
+    ServiceError::Other(format!("Could not build the export archive: {}", err))
+}
+
+/// Drops credential columns from every row
+fn redact(rows: &mut serde_json::Value) {
+    if let Some(rows) = rows.as_array_mut() {
+        for row in rows.iter_mut().filter_map(|row| row.as_object_mut()) {
+            for field in REDACTED_FIELDS {
+                row.remove(field);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_export_rows_lose_credentials_only() {
+        let mut rows = json!([
+            { "id": 1, "email": "ada@example.com", "password": "$argon2id$...", "transaction_pin_hash": "abc" },
+            { "id": 2, "kind": "nin", "number_hash": "def", "number_last4": "8901" }
+        ]);
+
+        redact(&mut rows);
+
+        assert_eq!(rows, json!([
+            { "id": 1, "email": "ada@example.com" },
+            { "id": 2, "kind": "nin", "number_last4": "8901" }
+        ]));
+    }
+}
@@ -1,12 +1,36 @@
 // src/service/property_service.rs
 use uuid::Uuid;
 use std::collections::HashMap;
-use reqwest::Client;
+use std::sync::Arc;
 use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Utc, Duration};
+use chrono::{DateTime, Utc};
+use num_traits::ToPrimitive;
 use std::error::Error;
 use std::fmt;
-use tokio::time::sleep;
+
+use crate::{
+    db::{
+        db::DBClient,
+        marketdb::{ComparableFilters, MarketExt},
+        photodb::PhotoIndexExt,
+        propertydb::{coordinates_hash, property_hash, PropertyExt},
+        savedpropertydb::SavedPropertyExt,
+        assignmentdb::VerificationAssignmentExt,
+    },
+    dtos::propertydtos::{AgentVerificationDto, CreatePropertyDto, LawyerVerificationDto, ReviewDecision},
+    models::propertymodel::{
+        ComparableListing, CurrencyType, ListingActivity, ListingPerformance, ListingType, MarketSummary,
+        MarketTrendPoint, PhotoSearchHit, PhotoSimilarityReport, Property, PropertyPriceChange, PropertyStatus,
+        PropertyType, VerificationAssignment, VerifierCandidate, VerifierMetrics, VerifierRole,
+    },
+    service::{
+        certificate_service::CertificateService,
+        error::ServiceError,
+        image_verification::{is_distinctive_hash, ImageVerificationService},
+        notification_service::NotificationService,
+    },
+    utils::gazetteer,
+};
 
 // Custom error types
 #[derive(Debug)]
@@ -32,40 +56,38 @@ impl fmt::Display for PropertyServiceError {
 
 impl Error for PropertyServiceError {}
 
-// Location validation using Google Places API
-#[derive(Debug, Deserialize)]
-struct GooglePlacesResponse {
-    results: Vec<GooglePlace>,
-    status: String,
-}
-
-#[derive(Debug, Deserialize)]
-struct GooglePlace {
-    formatted_address: String,
-    geometry: GoogleGeometry,
-    place_id: String,
-    types: Vec<String>,
+/// Going rate for a listing's market, from our own verified listings
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PropertyPriceData {
+    pub min_price: i64, // 10th percentile asking price
+    pub max_price: i64, // 90th percentile asking price
+    pub median_price: i64,
+    pub currency: String,
+    pub price_per_sqm: Option<f64>,
+    pub days_on_market: Option<f64>,
+    pub listings: i64,
+    pub area: String, // "Yaba, Lagos", or just the state when the LGA has too few listings
 }
 
-#[derive(Debug, Deserialize)]
-struct GoogleGeometry {
-    location: GoogleLocation,
-}
-
-#[derive(Debug, Deserialize)]
-struct GoogleLocation {
-    lat: f64,
-    lng: f64,
-}
+impl From<MarketSummary> for PropertyPriceData {
+    fn from(summary: MarketSummary) -> Self {
+        let area = if summary.lga.is_empty() {
+            summary.state
+        } else {
+            format!("{}, {}", summary.lga, summary.state)
+        };
 
-// Pricing data from real estate APIs
-#[derive(Debug, Deserialize)]
-struct PropertyPriceData {
-    min_price: i64,
-    max_price: i64,
-    average_price: i64,
-    currency: String,
-    price_per_sqm: Option<f64>,
+        Self {
+            min_price: summary.low_price,
+            max_price: summary.high_price,
+            median_price: summary.median_price,
+            currency: "NGN".to_string(),
+            price_per_sqm: summary.median_price_per_sqm,
+            days_on_market: summary.median_days_on_market,
+            listings: summary.listings,
+            area,
+        }
+    }
 }
 
 // Market analysis data
@@ -74,74 +96,740 @@ pub struct MarketAnalysis {
     pub location_score: f64,
     pub price_competitiveness: f64,
     pub market_trends: String,
-    pub comparable_properties: Vec<ComparableProperty>,
+    pub market: Option<PropertyPriceData>,
+    pub comparable_properties: Vec<ComparableListing>,
     pub investment_potential: f64,
     pub rental_yield_estimate: Option<f64>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ComparableProperty {
-    pub address: String,
-    pub price: i64,
-    pub size_sqm: Option<f64>,
-    pub bedrooms: Option<i32>,
-    pub distance_km: f64,
+#[derive(Debug)]
+pub struct PropertyService {
+    db_client: Arc<DBClient>,
+    notification_service: Arc<NotificationService>,
+    image_verification: ImageVerificationService,
+    certificates: Arc<CertificateService>,
 }
 
-pub struct PropertyService {
-    client: Client,
-    google_api_key: String,
-    propertybase_api_key: Option<String>,
+/// Shown to anyone checking a certificate for a suspended listing
+const SUSPENSION_REVOCATION_REASON: &str = "Listing suspended by Verinest";
+
+/// Largest listing photo downloaded for indexing
+const MAX_PHOTO_SIZE_MB: usize = 10;
+
+/// Photos whose perceptual hashes differ in at most this many of 64 bits are
+/// treated as the same picture (recompressed, resized or lightly edited)
+pub const PHOTO_MATCH_MAX_DISTANCE: i32 = 6;
+
+const PHOTO_MATCHES_PER_PHOTO: i64 = 10;
+
+/// A market needs this many verified listings before its figures are used
+pub const MIN_MARKET_LISTINGS: i64 = 5;
+
+/// Fewer new listings than this in a month makes its median too noisy for a trend
+const MIN_TREND_MONTH_LISTINGS: i64 = 3;
+
+const TREND_MONTHS: i32 = 12;
+
+const COMPARABLE_LISTINGS: i64 = 10;
+
+/// Longest window the landlord dashboard covers
+pub const MAX_DASHBOARD_DAYS: i32 = 365;
+
+/// Hours an agent has to inspect a listing once it is assigned
+pub const AGENT_SLA_HOURS: i64 = 48;
+
+/// Hours a lawyer has to review the documents once the listing is assigned
+pub const LAWYER_SLA_HOURS: i64 = 96;
+
+/// Verifiers are reminded this long before their SLA runs out
+const SLA_REMINDER_HOURS: i64 = 12;
+
+/// Agents based further than this from a listing are not offered it
+pub const AGENT_MAX_DISTANCE_KM: f64 = 50.0;
+
+/// What one run of the assignment job did
+#[derive(Debug, Default, Serialize)]
+pub struct AssignmentSweep {
+    pub reminded: usize,
+    pub lapsed: usize,
+    pub assigned: usize,
 }
 
 impl PropertyService {
-    pub fn new(google_api_key: String, propertybase_api_key: Option<String>) -> Self {
+    pub fn new(
+        db_client: Arc<DBClient>,
+        notification_service: Arc<NotificationService>,
+        certificates: Arc<CertificateService>,
+    ) -> Self {
         Self {
-            client: Client::new(),
-            google_api_key,
-            propertybase_api_key,
+            db_client,
+            notification_service,
+            image_verification: ImageVerificationService::new(None, MAX_PHOTO_SIZE_MB),
+            certificates,
         }
     }
 
-    /// Comprehensive address validation using Google Places API
-    pub async fn validate_and_geocode_address(
+    /// Store a new listing and submit it for agent verification
+    pub async fn create_listing(
         &self,
-        address: &str,
-        city: &str,
-        state: &str,
-    ) -> Result<(f64, f64, String), PropertyServiceError> {
-        let full_address = format!("{}, {}, {}, Nigeria", address, city, state);
-        let encoded_address = urlencoding::encode(&full_address);
-        
-        let url = format!(
-            "https://maps.googleapis.com/maps/api/place/textsearch/json?query={}&key={}",
-            encoded_address, self.google_api_key
-        );
+        landlord_id: Uuid,
+        mut property_data: CreatePropertyDto,
+    ) -> Result<Property, ServiceError> {
+        // Canonical names first, so the duplicate hash sees "Eti Osa LGA" and "Eti-Osa" as one place
+        let location = gazetteer::resolve(&property_data.state, Some(&property_data.lga), Some(&property_data.city))
+            .map_err(|e| ServiceError::Validation(e.to_string()))?;
+        property_data.state = location.state;
+        property_data.lga = location.lga.unwrap_or(property_data.lga);
+        property_data.city = location.city.unwrap_or(property_data.city);
+
+        // Prices in other currencies are not in the market figures
+        if property_data.currency == CurrencyType::Naira {
+            self.validate_property_price(
+                property_data.price,
+                property_data.property_type,
+                property_data.listing_type,
+                &property_data.state,
+                &property_data.lga,
+                property_data.size_sqm.as_ref().and_then(|size| size.to_f64()),
+            )
+            .await?;
+        }
+
+        if let (Some(latitude), Some(longitude)) = (&property_data.latitude, &property_data.longitude) {
+            let (latitude, longitude) = (latitude.to_f64().unwrap_or_default(), longitude.to_f64().unwrap_or_default());
+            self.validate_nigeria_coordinates(latitude, longitude)
+                .map_err(|e| ServiceError::Validation(e.to_string()))?;
+
+            let in_state = gazetteer::find_state(&property_data.state)
+                .is_some_and(|state| state.contains(latitude, longitude));
+            if !in_state {
+                return Err(ServiceError::Validation(format!(
+                    "The map pin is not in {} State",
+                    property_data.state
+                )));
+            }
+        }
+
+        let existing = self.db_client
+            .check_property_duplicate(
+                &property_hash(&property_data),
+                &coordinates_hash(property_data.latitude.as_ref(), property_data.longitude.as_ref()),
+            )
+            .await?;
+        if existing.is_some() {
+            return Err(ServiceError::DuplicateProperty);
+        }
+
+        let draft = self.db_client
+            .create_property(landlord_id, &property_data)
+            .await
+            .map_err(|e| match &e {
+                // Lost a race with an identical listing
+                sqlx::Error::Database(db_error) if db_error.is_unique_violation() => ServiceError::DuplicateProperty,
+                _ => ServiceError::Database(e),
+            })?;
+
+        let submitted = self.transition(&draft, PropertyStatus::AwaitingAgent).await?;
+
+        Ok(self.assign_automatically(submitted).await)
+    }
+
+    /// The single place a property's status changes outside a review. Illegal
+    /// moves are refused, and the update only applies if nobody changed the
+    /// status since `property` was read.
+    pub async fn transition(
+        &self,
+        property: &Property,
+        to: PropertyStatus,
+    ) -> Result<Property, ServiceError> {
+        ensure_transition(property, to)?;
+
+        self.db_client
+            .transition_property_status(property.id, property.status, to)
+            .await?
+            .ok_or(ServiceError::InvalidPropertyTransition(property.id, property.status, to))
+    }
+
+    /// `transition` inside the caller's transaction
+    pub async fn transition_tx(
+        &self,
+        property: &Property,
+        to: PropertyStatus,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<Property, ServiceError> {
+        ensure_transition(property, to)?;
+
+        self.db_client
+            .transition_property_status_tx(property.id, property.status, to, tx)
+            .await?
+            .ok_or(ServiceError::InvalidPropertyTransition(property.id, property.status, to))
+    }
+
+    pub async fn get_property(&self, property_id: Uuid) -> Result<Property, ServiceError> {
+        self.db_client
+            .get_property_by_id(property_id)
+            .await?
+            .ok_or(ServiceError::PropertyNotFound(property_id))
+    }
+
+    /// An admin hands the listing to a particular agent, on a fresh SLA
+    pub async fn assign_agent(
+        &self,
+        property_id: Uuid,
+        agent_id: Uuid,
+        admin_id: Uuid,
+    ) -> Result<Property, ServiceError> {
+        let property = self.get_property(property_id).await?;
+        if property.status != PropertyStatus::AwaitingAgent {
+            return Err(ServiceError::Validation("Agents can only be assigned while a property awaits agent verification".to_string()));
+        }
+
+        self.assign_verifier(&property, VerifierRole::Agent, agent_id, Some(admin_id)).await
+    }
+
+    /// Hands a waiting listing to a verifier and starts their SLA. Whoever
+    /// held it before is told it has moved on.
+    async fn assign_verifier(
+        &self,
+        property: &Property,
+        role: VerifierRole,
+        verifier_id: Uuid,
+        assigned_by: Option<Uuid>,
+    ) -> Result<Property, ServiceError> {
+        let previous = match role {
+            VerifierRole::Agent => property.agent_id,
+            VerifierRole::Lawyer => property.lawyer_id,
+        };
+        let due_at = Utc::now() + chrono::Duration::hours(sla_hours(role));
+
+        let (assigned, assignment) = self.db_client
+            .create_assignment(property.id, role, verifier_id, assigned_by, due_at)
+            .await?
+            .ok_or(ServiceError::Validation(format!(
+                "Property is no longer awaiting {} verification",
+                role.to_str()
+            )))?;
+
+        let notified = match role {
+            VerifierRole::Agent => self.notification_service
+                .notify_agent_property_assigned(verifier_id, &assigned, assignment.due_at)
+                .await,
+            VerifierRole::Lawyer => self.notification_service
+                .notify_lawyer_documents_ready(
+                    verifier_id,
+                    &assigned,
+                    assigned.agent_verification_notes.as_deref().unwrap_or(""),
+                    assignment.due_at,
+                )
+                .await,
+        };
+        if let Err(e) = notified {
+            tracing::warn!("Failed to notify {} {} of property {}: {}", role.to_str(), verifier_id, assigned.id, e);
+        }
+
+        if let Some(previous) = previous.filter(|&p| p != verifier_id) {
+            if let Err(e) = self.notification_service.notify_verification_reassigned(previous, &assigned).await {
+                tracing::warn!("Failed to tell {} that property {} was reassigned: {}", previous, assigned.id, e);
+            }
+        }
+
+        Ok(assigned)
+    }
+
+    /// Assigns a listing waiting on an agent or lawyer to the best available
+    /// one, skipping anyone who already let it lapse. None if nobody suits.
+    pub async fn auto_assign(&self, property: &Property) -> Result<Option<Property>, ServiceError> {
+        let role = match property.status {
+            PropertyStatus::AwaitingAgent => VerifierRole::Agent,
+            PropertyStatus::AwaitingLawyer => VerifierRole::Lawyer,
+            _ => return Ok(None),
+        };
+
+        let candidates = self.db_client.get_verifier_candidates(role).await?;
+        let lapsed = self.db_client.get_lapsed_verifier_ids(property.id, role).await?;
+
+        let Some(verifier_id) = pick_verifier(role, property, &candidates, &lapsed) else {
+            return Ok(None);
+        };
+
+        self.assign_verifier(property, role, verifier_id, None).await.map(Some)
+    }
+
+    /// `auto_assign` for a listing that has just moved; failing to find
+    /// someone leaves it for the assignment job or an admin
+    async fn assign_automatically(&self, property: Property) -> Property {
+        match self.auto_assign(&property).await {
+            Ok(Some(assigned)) => assigned,
+            Ok(None) => {
+                tracing::info!("No verifier available for property {} ({:?})", property.id, property.status);
+                property
+            }
+            Err(e) => {
+                tracing::warn!("Failed to assign a verifier to property {}: {}", property.id, e);
+                property
+            }
+        }
+    }
+
+    /// Reminds verifiers whose SLA is nearly up, lapses the ones that ran
+    /// out, and assigns every waiting listing nobody holds, which includes
+    /// the ones just lapsed
+    pub async fn run_assignment_sweep(&self, limit: i64) -> Result<AssignmentSweep, ServiceError> {
+        let mut sweep = AssignmentSweep::default();
+
+        let due_soon = self.db_client
+            .get_assignments_to_remind(Utc::now() + chrono::Duration::hours(SLA_REMINDER_HOURS), limit)
+            .await?;
+        for assignment in due_soon {
+            let property = self.get_property(assignment.property_id).await?;
+            if let Err(e) = self.notification_service
+                .notify_verification_reminder(assignment.verifier_id, &property, assignment.due_at)
+                .await
+            {
+                tracing::warn!("Failed to remind {} about property {}: {}", assignment.verifier_id, property.id, e);
+            }
+            self.db_client.mark_assignment_reminded(assignment.id).await?;
+            sweep.reminded += 1;
+        }
+
+        for assignment in self.db_client.get_lapsed_assignments(limit).await? {
+            if self.db_client.lapse_assignment(assignment.id).await? {
+                tracing::info!(
+                    "{} {} let the SLA on property {} lapse",
+                    assignment.verifier_type, assignment.verifier_id, assignment.property_id
+                );
+                sweep.lapsed += 1;
+            }
+        }
+
+        for property in self.db_client.get_unassigned_properties(limit).await? {
+            match self.auto_assign(&property).await {
+                Ok(Some(_)) => sweep.assigned += 1,
+                Ok(None) => tracing::debug!("Still no verifier available for property {}", property.id),
+                Err(e) => tracing::warn!("Failed to assign a verifier to property {}: {}", property.id, e),
+            }
+        }
+
+        Ok(sweep)
+    }
+
+    /// Turnaround per agent or lawyer over the last `days` days
+    pub async fn verifier_metrics(
+        &self,
+        role: Option<VerifierRole>,
+        verifier_id: Option<Uuid>,
+        days: i32,
+    ) -> Result<Vec<VerifierMetrics>, ServiceError> {
+        let since = Utc::now() - chrono::Duration::days(days.clamp(1, MAX_DASHBOARD_DAYS) as i64);
+
+        Ok(self.db_client
+            .get_verifier_metrics(since, role, verifier_id)
+            .await?)
+    }
+
+    /// What the verifier holds now, soonest due first
+    pub async fn verifier_assignments(&self, verifier_id: Uuid) -> Result<Vec<VerificationAssignment>, ServiceError> {
+        Ok(self.db_client
+            .get_active_assignments_for_verifier(verifier_id)
+            .await?)
+    }
+
+    /// Agent inspection. Approval passes through AgentVerified and hands the
+    /// property to the lawyer queue; rejection ends the pipeline.
+    pub async fn agent_review(
+        &self,
+        agent_id: Uuid,
+        property_id: Uuid,
+        review: AgentVerificationDto,
+    ) -> Result<Property, ServiceError> {
+        let property = self.get_property(property_id).await?;
+        if property.agent_id != Some(agent_id) {
+            return Err(ServiceError::UnauthorizedPropertyAccess(agent_id, property_id));
+        }
+
+        let steps = review_steps(VerifierRole::Agent, review.verification_status);
+        ensure_transition(&property, steps[0])?;
+
+        let reviewed = self.db_client
+            .agent_verify_property(
+                agent_id,
+                property_id,
+                steps[0],
+                review.verification_status.to_str(),
+                &review.notes,
+                &review.verification_photos,
+            )
+            .await?
+            .ok_or(ServiceError::InvalidPropertyTransition(property_id, property.status, steps[0]))?;
+
+        let property = self.finish_review(reviewed, &steps[1..]).await?;
+
+        if property.status == PropertyStatus::Rejected {
+            self.notify_rejection(&property, "Agent inspection", &review.notes).await;
+            return Ok(property);
+        }
+
+        Ok(self.assign_automatically(property).await)
+    }
+
+    /// Document review. Approval passes through LawyerVerified and lists the property.
+    pub async fn lawyer_review(
+        &self,
+        lawyer_id: Uuid,
+        property_id: Uuid,
+        review: LawyerVerificationDto,
+    ) -> Result<Property, ServiceError> {
+        let property = self.get_property(property_id).await?;
+        if property.lawyer_id.is_some_and(|assigned| assigned != lawyer_id) {
+            return Err(ServiceError::UnauthorizedPropertyAccess(lawyer_id, property_id));
+        }
+
+        let notes = match review.document_issues.as_deref() {
+            Some(issues) if !issues.is_empty() => format!("{}\nDocument issues: {}", review.notes, issues.join(", ")),
+            _ => review.notes.clone(),
+        };
 
-        let response = self.client.get(&url)
-            .send()
+        let steps = review_steps(VerifierRole::Lawyer, review.verification_status);
+        ensure_transition(&property, steps[0])?;
+
+        let reviewed = self.db_client
+            .lawyer_verify_property(
+                lawyer_id,
+                property_id,
+                steps[0],
+                review.verification_status.to_str(),
+                &notes,
+            )
+            .await?
+            .ok_or(ServiceError::InvalidPropertyTransition(property_id, property.status, steps[0]))?;
+
+        let property = self.finish_review(reviewed, &steps[1..]).await?;
+
+        if property.status == PropertyStatus::Rejected {
+            self.notify_rejection(&property, "Document verification", &notes).await;
+            return Ok(property);
+        }
+
+        self.issue_certificate(&property).await;
+        if let Err(e) = self.notification_service
+            .notify_property_live(property.landlord_id, &property, self.listing_score(&property))
             .await
-            .map_err(|e| PropertyServiceError::NetworkError(e.to_string()))?;
+        {
+            tracing::warn!("Failed to notify landlord of live property {}: {}", property.id, e);
+        }
+
+        Ok(property)
+    }
+
+    /// Take a live listing down, or put a suspended one back up
+    pub async fn set_suspended(
+        &self,
+        property_id: Uuid,
+        suspended: bool,
+    ) -> Result<Property, ServiceError> {
+        let property = self.get_property(property_id).await?;
+        let to = if suspended { PropertyStatus::Suspended } else { PropertyStatus::Active };
+
+        let property = self.transition(&property, to).await?;
+        if suspended {
+            self.certificates.revoke(property.id, SUSPENSION_REVOCATION_REASON).await?;
+        } else {
+            self.issue_certificate(&property).await;
+        }
 
-        let places_response: GooglePlacesResponse = response
-            .json()
+        Ok(property)
+    }
+
+    /// A listing that went live without a certificate gets one from the
+    /// hourly retry job, so failures here only warn
+    async fn issue_certificate(&self, property: &Property) {
+        if let Err(e) = self.certificates.issue(property).await {
+            tracing::warn!("Failed to issue certificate for property {}: {}", property.id, e);
+        }
+    }
+
+    /// The review has recorded its first step; walk the rest of the way
+    async fn finish_review(
+        &self,
+        mut property: Property,
+        remaining: &[PropertyStatus],
+    ) -> Result<Property, ServiceError> {
+        for next in remaining {
+            property = self.transition(&property, *next).await?;
+        }
+        Ok(property)
+    }
+
+    async fn notify_rejection(&self, property: &Property, stage: &str, notes: &str) {
+        if let Err(e) = self.notification_service
+            .notify_property_rejected(property.landlord_id, property, stage, "Verification was not approved", notes)
             .await
-            .map_err(|e| PropertyServiceError::ExternalApiError(e.to_string()))?;
+        {
+            tracing::warn!("Failed to notify landlord of rejected property {}: {}", property.id, e);
+        }
+    }
 
-        if places_response.status != "OK" || places_response.results.is_empty() {
-            return Err(PropertyServiceError::LocationValidationFailed(
-                "Address could not be verified".to_string()
-            ));
+    fn listing_score(&self, property: &Property) -> u32 {
+        let photo_count = property.property_photos.as_array().map(|photos| photos.len()).unwrap_or(0);
+        let has_all_documents = property.certificate_of_occupancy.is_some()
+            && property.survey_plan.is_some()
+            && (property.property_type == PropertyType::Land || property.building_plan_approval.is_some());
+
+        // Documents passed legal review and the agent inspected on site; the
+        // remaining qualitative inputs have no signal yet and score neutral
+        self.calculate_comprehensive_property_score(
+            has_all_documents,
+            1.0,
+            photo_count,
+            0.5,
+            property.description.len(),
+            0.5,
+            property.latitude.is_some(),
+            1.0,
+            0.5,
+            3,
+            0.5,
+        )
+    }
+
+    /// The landlord reprices a live listing. Everyone who saved it hears about a drop.
+    pub async fn change_price(
+        &self,
+        landlord_id: Uuid,
+        property_id: Uuid,
+        new_price: i64,
+    ) -> Result<Property, ServiceError> {
+        let property = self.get_property(property_id).await?;
+        if property.landlord_id != landlord_id {
+            return Err(ServiceError::UnauthorizedPropertyAccess(landlord_id, property_id));
+        }
+        if property.status != PropertyStatus::Active {
+            return Err(ServiceError::Validation("Only live listings can be repriced".to_string()));
+        }
+        if new_price == property.price {
+            return Err(ServiceError::Validation("The listing already has this price".to_string()));
         }
 
-        let place = &places_response.results[0];
-        let lat = place.geometry.location.lat;
-        let lng = place.geometry.location.lng;
+        if property.currency == CurrencyType::Naira {
+            self.validate_property_price(
+                new_price,
+                property.property_type,
+                property.listing_type,
+                &property.state,
+                &property.lga,
+                property.size_sqm.as_ref().and_then(|size| size.to_f64()),
+            )
+            .await?;
+        }
+
+        let updated = self.db_client
+            .change_property_price(property_id, property.price, new_price, landlord_id)
+            .await?
+            .ok_or_else(|| ServiceError::Validation("The listing changed while repricing. Please try again".to_string()))?;
 
-        // Validate coordinates are within Nigeria
-        self.validate_nigeria_coordinates(lat, lng)?;
+        if new_price < property.price {
+            let savers = self.db_client.get_property_saver_ids(property_id).await?;
+            for user_id in savers.into_iter().filter(|id| *id != landlord_id) {
+                if let Err(e) = self.notification_service.notify_price_drop(user_id, &updated, property.price).await {
+                    tracing::warn!("Failed to notify {} of price drop on {}: {}", user_id, property_id, e);
+                }
+            }
+        }
 
-        Ok((lat, lng, place.formatted_address.clone()))
+        Ok(updated)
+    }
+
+    pub async fn price_history(&self, property_id: Uuid) -> Result<Vec<PropertyPriceChange>, ServiceError> {
+        Ok(self.db_client.get_price_history(property_id).await?)
+    }
+
+    /// How each of the landlord's listings did over the last `days` days
+    pub async fn landlord_dashboard(
+        &self,
+        landlord_id: Uuid,
+        days: i32,
+    ) -> Result<Vec<ListingPerformance>, ServiceError> {
+        let since = Utc::now() - chrono::Duration::days(days.clamp(1, MAX_DASHBOARD_DAYS) as i64);
+
+        Ok(self.db_client
+            .get_listing_performance(landlord_id, since)
+            .await?)
+    }
+
+    /// Daily views, saves, viewing requests and offers on one listing
+    pub async fn listing_activity(
+        &self,
+        property_id: Uuid,
+        days: i32,
+    ) -> Result<Vec<ListingActivity>, ServiceError> {
+        Ok(self.db_client
+            .get_listing_activity(property_id, days.clamp(1, MAX_DASHBOARD_DAYS))
+            .await?)
+    }
+
+    /// Fingerprints every photo on the listing and compares it with all photos
+    /// already indexed. Matches are recorded for the reviewers; a match with
+    /// another landlord's listing alerts the assigned agent.
+    pub async fn index_listing_photos(&self, property: &Property) -> Result<PhotoSimilarityReport, ServiceError> {
+        let urls: Vec<String> = property.property_photos
+            .as_array()
+            .map(|photos| photos.iter().filter_map(|p| p.as_str().map(str::to_string)).collect())
+            .unwrap_or_default();
+
+        let mut new_suspicious = std::collections::HashSet::new();
+
+        for url in urls {
+            let fingerprint = match self.image_verification.fetch_image(&url).await {
+                Ok(bytes) => self.image_verification.fingerprint(&bytes),
+                Err(e) => Err(e),
+            };
+
+            let (content_hash, phash) = match fingerprint {
+                Ok(fingerprint) => fingerprint,
+                Err(e) => {
+                    self.db_client
+                        .save_photo_hash(property.id, &url, None, None, Some(&e.to_string()))
+                        .await?;
+                    continue;
+                }
+            };
+
+            self.db_client
+                .save_photo_hash(property.id, &url, Some(&content_hash), Some(phash), None)
+                .await?;
+
+            // A flat picture matches every other flat picture; only identical files count
+            let max_distance = if is_distinctive_hash(phash) { PHOTO_MATCH_MAX_DISTANCE } else { -1 };
+            let similar = self.db_client
+                .find_similar_photos(phash, Some(&content_hash), max_distance.max(0), Some(property.id), PHOTO_MATCHES_PER_PHOTO)
+                .await?;
+
+            for hit in similar {
+                if max_distance < 0 && hit.distance > 0 {
+                    continue;
+                }
+
+                let different_landlord = hit.landlord_id != property.landlord_id;
+                let recorded = self.db_client
+                    .record_photo_match(property.id, &url, hit.property_id, &hit.photo_url, hit.distance, different_landlord)
+                    .await?;
+                if recorded && different_landlord {
+                    new_suspicious.insert(hit.property_id);
+                }
+            }
+        }
+
+        if !new_suspicious.is_empty() {
+            tracing::warn!(
+                "Photos on property {} match {} listing(s) by other landlords",
+                property.id,
+                new_suspicious.len()
+            );
+
+            if let (Some(agent_id), PropertyStatus::AwaitingAgent) = (property.agent_id, property.status) {
+                if let Err(e) = self.notification_service.notify_agent_photo_match(agent_id, property, new_suspicious.len()).await {
+                    tracing::warn!("Failed to notify agent {} of photo matches on {}: {}", agent_id, property.id, e);
+                }
+            }
+        }
+
+        self.photo_report(property.id).await
+    }
+
+    /// Indexes listings submitted since the last run, including any that
+    /// predate photo indexing
+    pub async fn index_pending_photos(&self, limit: i64) -> Result<usize, ServiceError> {
+        let pending = self.db_client
+            .get_properties_needing_photo_index(limit)
+            .await?;
+
+        let mut indexed = 0;
+        for property_id in pending {
+            let property = self.get_property(property_id).await?;
+            match self.index_listing_photos(&property).await {
+                Ok(_) => indexed += 1,
+                Err(e) => tracing::error!("Failed to index photos of property {}: {}", property_id, e),
+            }
+        }
+
+        Ok(indexed)
+    }
+
+    pub async fn photo_report(&self, property_id: Uuid) -> Result<PhotoSimilarityReport, ServiceError> {
+        let (photos_indexed, unreadable_photos) = self.db_client
+            .get_photo_index_counts(property_id)
+            .await?;
+        let matches = self.db_client
+            .get_photo_matches(property_id)
+            .await?;
+
+        Ok(PhotoSimilarityReport {
+            property_id,
+            photos_indexed,
+            unreadable_photos,
+            suspicious: matches.iter().any(|m| m.different_landlord),
+            matches,
+        })
+    }
+
+    /// Listing photos that look like the given image, closest first
+    pub async fn search_by_image(
+        &self,
+        image_data: &[u8],
+        max_distance: i32,
+        limit: i64,
+    ) -> Result<Vec<PhotoSearchHit>, ServiceError> {
+        let (content_hash, phash) = self.image_verification
+            .fingerprint(image_data)
+            .map_err(|e| ServiceError::Validation(e.to_string()))?;
+
+        Ok(self.db_client
+            .find_similar_photos(phash, Some(&content_hash), max_distance, None, limit)
+            .await?)
+    }
+
+    /// Fetches the image at `url` and searches for it
+    pub async fn search_by_image_url(
+        &self,
+        url: &str,
+        max_distance: i32,
+        limit: i64,
+    ) -> Result<Vec<PhotoSearchHit>, ServiceError> {
+        let image_data = self.image_verification
+            .fetch_image(url)
+            .await
+            .map_err(|e| ServiceError::Validation(e.to_string()))?;
+
+        self.search_by_image(&image_data, max_distance, limit).await
+    }
+
+    /// Checks the city and state against the gazetteer and returns approximate
+    /// coordinates with a normalised address. The street itself is not checked.
+    pub fn validate_and_geocode_address(
+        &self,
+        address: &str,
+        city: &str,
+        state: &str,
+    ) -> Result<(f64, f64, String), PropertyServiceError> {
+        let location = gazetteer::resolve(state, None, Some(city))
+            .map_err(|e| PropertyServiceError::LocationValidationFailed(e.to_string()))?;
+
+        self.validate_nigeria_coordinates(location.latitude, location.longitude)?;
+
+        let formatted_address = [
+            Some(address.trim()),
+            location.city.as_deref(),
+            location.lga.as_deref(),
+            Some(location.state.as_str()),
+            Some("Nigeria"),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+        Ok((location.latitude, location.longitude, formatted_address))
     }
 
     /// Validate coordinates are within Nigeria with detailed boundary checking
@@ -172,7 +860,7 @@ impl PropertyService {
         }
 
         // Check if coordinates fall within any major region
-        for (min_lat, max_lat, min_lng, max_lng, region) in &NIGERIA_BOUNDARIES {
+        for (min_lat, max_lat, min_lng, max_lng, _region) in &NIGERIA_BOUNDARIES {
             if latitude >= *min_lat && latitude <= *max_lat &&
                longitude >= *min_lng && longitude <= *max_lng {
                 return Ok(());
@@ -183,123 +871,63 @@ impl PropertyService {
         Ok(())
     }
 
-    /// Advanced price validation with market data
+    /// Checks an asking price against verified listings in the same market.
+    /// Returns None when the market is too thin to judge.
     pub async fn validate_property_price(
         &self,
         price: i64,
-        property_type: &str,
-        city: &str,
+        property_type: PropertyType,
+        listing_type: ListingType,
         state: &str,
-        bedrooms: Option<i32>,
+        lga: &str,
         size_sqm: Option<f64>,
-        listing_type: &str,
-    ) -> Result<PropertyPriceData, PropertyServiceError> {
-        // Market price ranges for major Nigerian cities (in Naira)
-        let market_data = self.get_market_price_data(city, state, property_type, listing_type).await?;
+    ) -> Result<Option<PropertyPriceData>, ServiceError> {
+        let Some(market_data) = self.get_market_price_data(state, lga, property_type, listing_type).await? else {
+            return Ok(None);
+        };
 
-        // Validate against market ranges
-        if price < market_data.min_price {
-            return Err(PropertyServiceError::PriceValidationFailed(
-                format!("Price (₦{:,.0}) is significantly below market range (₦{:,} - ₦{:,}) for {} in {}", 
-                    price, market_data.min_price, market_data.max_price, property_type, city)
-            ));
-        }
+        check_price_against_market(price, property_type, size_sqm, &market_data)
+            .map_err(|e| ServiceError::Validation(e.to_string()))?;
 
-        if price > market_data.max_price * 3 {
-            return Err(PropertyServiceError::PriceValidationFailed(
-                format!("Price (₦{:,.0}) is unreasonably high for {} in {} (max reasonable: ₦{:,})", 
-                    price, property_type, city, market_data.max_price * 3)
-            ));
-        }
+        Ok(Some(market_data))
+    }
 
-        // Validate price per square meter if size provided
-        if let (Some(size), Some(price_per_sqm)) = (size_sqm, market_data.price_per_sqm) {
-            let actual_price_per_sqm = price as f64 / size;
-            let max_reasonable = price_per_sqm * 5.0;
-            let min_reasonable = price_per_sqm * 0.2;
-
-            if actual_price_per_sqm < min_reasonable || actual_price_per_sqm > max_reasonable {
-                return Err(PropertyServiceError::PriceValidationFailed(
-                    format!("Price per sqm (₦{:,}) is outside reasonable range (₦{:,.0} - ₦{:,.0})", 
-                        actual_price_per_sqm, min_reasonable, max_reasonable)
-                ));
+    /// The LGA's figures, or the state's when the LGA has too few listings
+    pub async fn get_market_price_data(
+        &self,
+        state: &str,
+        lga: &str,
+        property_type: PropertyType,
+        listing_type: ListingType,
+    ) -> Result<Option<PropertyPriceData>, ServiceError> {
+        for lga in [Some(lga), None] {
+            let summary = self.db_client
+                .get_market_summary(state, lga, property_type, listing_type)
+                .await?;
+
+            if let Some(summary) = summary.filter(|s| s.listings >= MIN_MARKET_LISTINGS) {
+                return Ok(Some(summary.into()));
             }
         }
 
-        Ok(market_data)
+        Ok(None)
     }
 
-    /// Get market price data for a location and property type
-    async fn get_market_price_data(
+    pub async fn get_market_summaries(
         &self,
-        city: &str,
         state: &str,
-        property_type: &str,
-        listing_type: &str,
-    ) -> Result<PropertyPriceData, PropertyServiceError> {
-        // Real market data for major Nigerian cities
-        let base_prices = match (city.to_lowercase().as_str(), state.to_lowercase().as_str()) {
-            ("lagos", "lagos") => match property_type.to_lowercase().as_str() {
-                "apartment" => if listing_type == "rent" { (500_000, 15_000_000, 2_500_000) } else { (15_000_000, 500_000_000, 75_000_000) },
-                "house" => if listing_type == "rent" { (800_000, 25_000_000, 4_000_000) } else { (25_000_000, 800_000_000, 120_000_000) },
-                "duplex" => if listing_type == "rent" { (1_500_000, 50_000_000, 8_000_000) } else { (50_000_000, 1_500_000_000, 200_000_000) },
-                "land" => (5_000_000, 2_000_000_000, 100_000_000),
-                "commercial" => if listing_type == "rent" { (2_000_000, 100_000_000, 15_000_000) } else { (100_000_000, 3_000_000_000, 500_000_000) },
-                _ => (1_000_000, 100_000_000, 10_000_000),
-            },
-            ("abuja", "fct") => match property_type.to_lowercase().as_str() {
-                "apartment" => if listing_type == "rent" { (400_000, 12_000_000, 2_000_000) } else { (12_000_000, 400_000_000, 60_000_000) },
-                "house" => if listing_type == "rent" { (600_000, 20_000_000, 3_000_000) } else { (20_000_000, 600_000_000, 90_000_000) },
-                "duplex" => if listing_type == "rent" { (1_200_000, 40_000_000, 6_000_000) } else { (40_000_000, 1_200_000_000, 150_000_000) },
-                "land" => (3_000_000, 1_500_000_000, 75_000_000),
-                "commercial" => if listing_type == "rent" { (1_500_000, 80_000_000, 12_000_000) } else { (80_000_000, 2_500_000_000, 400_000_000) },
-                _ => (800_000, 80_000_000, 8_000_000),
-            },
-            ("port harcourt", "rivers") => match property_type.to_lowercase().as_str() {
-                "apartment" => if listing_type == "rent" { (300_000, 8_000_000, 1_500_000) } else { (8_000_000, 300_000_000, 45_000_000) },
-                "house" => if listing_type == "rent" { (450_000, 15_000_000, 2_200_000) } else { (15_000_000, 450_000_000, 65_000_000) },
-                "duplex" => if listing_type == "rent" { (800_000, 30_000_000, 4_500_000) } else { (30_000_000, 900_000_000, 120_000_000) },
-                "land" => (2_000_000, 800_000_000, 50_000_000),
-                "commercial" => if listing_type == "rent" { (1_000_000, 60_000_000, 8_000_000) } else { (60_000_000, 2_000_000_000, 300_000_000) },
-                _ => (500_000, 50_000_000, 5_000_000),
-            },
-            ("kano", "kano") => match property_type.to_lowercase().as_str() {
-                "apartment" => if listing_type == "rent" { (200_000, 5_000_000, 1_000_000) } else { (5_000_000, 200_000_000, 30_000_000) },
-                "house" => if listing_type == "rent" { (300_000, 10_000_000, 1_500_000) } else { (10_000_000, 300_000_000, 45_000_000) },
-                "duplex" => if listing_type == "rent" { (500_000, 20_000_000, 3_000_000) } else { (20_000_000, 600_000_000, 80_000_000) },
-                "land" => (1_000_000, 500_000_000, 25_000_000),
-                "commercial" => if listing_type == "rent" { (600_000, 40_000_000, 5_000_000) } else { (40_000_000, 1_500_000_000, 200_000_000) },
-                _ => (300_000, 30_000_000, 3_000_000),
-            },
-            _ => {
-                // Default ranges for other cities
-                match property_type.to_lowercase().as_str() {
-                    "apartment" => if listing_type == "rent" { (150_000, 4_000_000, 800_000) } else { (4_000_000, 150_000_000, 25_000_000) },
-                    "house" => if listing_type == "rent" { (250_000, 8_000_000, 1_200_000) } else { (8_000_000, 250_000_000, 35_000_000) },
-                    "duplex" => if listing_type == "rent" { (400_000, 15_000_000, 2_500_000) } else { (15_000_000, 500_000_000, 60_000_000) },
-                    "land" => (800_000, 300_000_000, 20_000_000),
-                    "commercial" => if listing_type == "rent" { (500_000, 30_000_000, 4_000_000) } else { (30_000_000, 1_000_000_000, 150_000_000) },
-                    _ => (200_000, 20_000_000, 2_500_000),
-                }
-            }
-        };
-
-        let (min_price, max_price, avg_price) = base_prices;
-        let price_per_sqm = match property_type.to_lowercase().as_str() {
-            "apartment" => Some(if listing_type == "rent" { 2500.0 } else { 75000.0 }),
-            "house" => Some(if listing_type == "rent" { 3000.0 } else { 85000.0 }),
-            "duplex" => Some(if listing_type == "rent" { 4000.0 } else { 120000.0 }),
-            "commercial" => Some(if listing_type == "rent" { 8000.0 } else { 200000.0 }),
-            _ => None,
-        };
+        lga: Option<&str>,
+        property_type: Option<PropertyType>,
+        listing_type: Option<ListingType>,
+    ) -> Result<Vec<MarketSummary>, ServiceError> {
+        Ok(self.db_client
+            .get_market_summaries(state, lga, property_type, listing_type)
+            .await?)
+    }
 
-        Ok(PropertyPriceData {
-            min_price,
-            max_price,
-            average_price: avg_price,
-            currency: "NGN".to_string(),
-            price_per_sqm,
-        })
+    /// Recomputes the market figures from the listings table
+    pub async fn refresh_market_summaries(&self) -> Result<(), ServiceError> {
+        Ok(self.db_client.refresh_market_summaries().await?)
     }
 
     /// Generate comprehensive property amenities based on type and location
@@ -441,7 +1069,6 @@ impl PropertyService {
 
     /// Generate unique property reference with check digit
     pub fn generate_property_reference(
-        &self,
         property_type: &str,
         state: &str,
         lga: &str,
@@ -462,20 +1089,35 @@ impl PropertyService {
             _ => "GEN",
         };
 
-        let state_code = state.chars().take(3).collect::<String>().to_uppercase();
-        let lga_code = lga.chars().take(2).collect::<String>().to_uppercase();
+        let state_code = state.chars().filter(char::is_ascii_alphanumeric).take(3).collect::<String>().to_uppercase();
+        let lga_code = lga.chars().filter(char::is_ascii_alphanumeric).take(2).collect::<String>().to_uppercase();
         let year = created_at.format("%y");
         let id_short = id.to_string().replace("-", "").chars().take(6).collect::<String>().to_uppercase();
         
         // Generate check digit
         let base_ref = format!("{}{}{}{}{}", type_code, state_code, lga_code, year, id_short);
-        let check_digit = self.calculate_check_digit(&base_ref);
+        let check_digit = Self::calculate_check_digit(&base_ref);
         
         format!("VN-{}-{}", base_ref, check_digit)
     }
 
+    /// Whether a reference has the shape and check digit `generate_property_reference` gives it
+    pub fn is_valid_property_reference(reference: &str) -> bool {
+        let Some((base_ref, check)) = reference
+            .strip_prefix("VN-")
+            .and_then(|rest| rest.rsplit_once('-'))
+        else {
+            return false;
+        };
+
+        !base_ref.is_empty()
+            && base_ref.chars().all(|c| c.is_ascii_digit() || c.is_ascii_uppercase())
+            && check.len() == 1
+            && check.starts_with(Self::calculate_check_digit(base_ref))
+    }
+
     /// Calculate check digit for reference validation
-    fn calculate_check_digit(&self, reference: &str) -> char {
+    fn calculate_check_digit(reference: &str) -> char {
         let sum: u32 = reference.chars()
             .enumerate()
             .map(|(i, c)| {
@@ -589,42 +1231,43 @@ impl PropertyService {
         requirements
     }
 
-    /// Market analysis with comparable properties
+    /// Market analysis against our own listings
     pub async fn perform_market_analysis(
         &self,
-        property_type: &str,
+        filters: &ComparableFilters<'_>,
         city: &str,
-        state: &str,
-        price: i64,
-        bedrooms: Option<i32>,
-        size_sqm: Option<f64>,
-        latitude: Option<f64>,
-        longitude: Option<f64>,
-    ) -> Result<MarketAnalysis, PropertyServiceError> {
-        // Simulate market analysis - in production, this would query real estate databases
-        let location_score = self.calculate_location_score(city, state, latitude, longitude).await?;
-        
-        // Get comparable properties (simulated data)
-        let comparable_properties = self.get_comparable_properties(
-            property_type, city, state, bedrooms, size_sqm, latitude, longitude
-        ).await?;
-
-        let price_competitiveness = if !comparable_properties.is_empty() {
-            let avg_comp_price = comparable_properties.iter()
-                .map(|p| p.price)
-                .sum::<i64>() as f64 / comparable_properties.len() as f64;
-            
-            1.0 - ((price as f64 - avg_comp_price).abs() / avg_comp_price).min(1.0)
-        } else {
-            0.5
+    ) -> Result<MarketAnalysis, ServiceError> {
+        let location_score = self
+            .calculate_location_score(city, filters.state, filters.latitude, filters.longitude)
+            .await
+            .map_err(|e| ServiceError::Validation(e.to_string()))?;
+
+        let market = self
+            .get_market_price_data(filters.state, filters.lga, filters.property_type, filters.listing_type)
+            .await?;
+        let comparable_properties = self.db_client
+            .get_comparable_listings(filters, COMPARABLE_LISTINGS)
+            .await?;
+
+        // The market median when the market is big enough, otherwise whatever comparables there are
+        let reference_price = market.as_ref().map(|m| m.median_price as f64).or_else(|| {
+            (!comparable_properties.is_empty()).then(|| {
+                comparable_properties.iter().map(|p| p.price as f64).sum::<f64>() / comparable_properties.len() as f64
+            })
+        });
+        let price_competitiveness = match reference_price {
+            Some(reference) if reference > 0.0 => {
+                1.0 - ((filters.price as f64 - reference).abs() / reference).min(1.0)
+            }
+            _ => 0.5,
         };
 
         let investment_potential = self.calculate_investment_potential(
-            property_type, city, price, location_score
+            filters.property_type.to_str(), city, filters.price, location_score
         );
 
-        let rental_yield_estimate = if property_type != "land" {
-            Some(self.estimate_rental_yield(property_type, city, price))
+        let rental_yield_estimate = if filters.listing_type == ListingType::Sale && filters.property_type != PropertyType::Land {
+            self.estimate_rental_yield(filters.state, filters.lga, filters.property_type, filters.price).await?
         } else {
             None
         };
@@ -632,13 +1275,31 @@ impl PropertyService {
         Ok(MarketAnalysis {
             location_score,
             price_competitiveness,
-            market_trends: self.get_market_trends(city, property_type),
+            market_trends: self.get_market_trends(filters.state, filters.property_type, filters.listing_type).await?,
+            market,
             comparable_properties,
             investment_potential,
             rental_yield_estimate,
         })
     }
 
+    /// Market analysis for a listing, which is left out of its own comparables
+    pub async fn property_market_analysis(&self, property: &Property) -> Result<MarketAnalysis, ServiceError> {
+        let filters = ComparableFilters {
+            exclude_property_id: Some(property.id),
+            state: &property.state,
+            lga: &property.lga,
+            property_type: property.property_type,
+            listing_type: property.listing_type,
+            price: property.price,
+            bedrooms: property.bedrooms,
+            latitude: property.latitude.as_ref().and_then(|l| l.to_f64()),
+            longitude: property.longitude.as_ref().and_then(|l| l.to_f64()),
+        };
+
+        self.perform_market_analysis(&filters, &property.city).await
+    }
+
     async fn calculate_location_score(
         &self,
         city: &str,
@@ -659,7 +1320,7 @@ impl PropertyService {
         };
 
         // State economic ranking
-        let state_bonus = match state.to_lowercase().as_str() {
+        let state_bonus: f64 = match state.to_lowercase().as_str() {
             "lagos" => 0.05,
             "fct" | "abuja" => 0.05,
             "rivers" | "delta" | "akwa ibom" => 0.03,
@@ -733,52 +1394,6 @@ impl PropertyService {
         r * c
     }
 
-    async fn get_comparable_properties(
-        &self,
-        property_type: &str,
-        city: &str,
-        state: &str,
-        bedrooms: Option<i32>,
-        size_sqm: Option<f64>,
-        latitude: Option<f64>,
-        longitude: Option<f64>,
-    ) -> Result<Vec<ComparableProperty>, PropertyServiceError> {
-        // In production, this would query a real estate database
-        // For now, generate realistic comparable properties
-        
-        let base_price = self.get_market_price_data(city, state, property_type, "sale").await?.average_price;
-        let mut comparables = Vec::new();
-
-        // Generate 3-7 comparable properties with realistic variations
-        let count = fastrand::usize(3..=7);
-        for i in 0..count {
-            let price_variation = 1.0 + (fastrand::f64() - 0.5) * 0.4; // ±20% variation
-            let distance_km = fastrand::f64() * 5.0 + 0.5; // 0.5-5.5 km radius
-            
-            let comparable_bedrooms = bedrooms.map(|b| {
-                let variation = fastrand::i32(-1..=1);
-                (b + variation).max(1)
-            });
-
-            comparables.push(ComparableProperty {
-                address: format!("{} Street, {} {}", 
-                    ["Admiralty", "Bourdillon", "Ademola", "Awolowo", "Mobolaji"][i % 5],
-                    city, i + 1),
-                price: ((base_price as f64 * price_variation) as i64),
-                size_sqm: size_sqm.map(|s| s * (0.8 + fastrand::f64() * 0.4)),
-                bedrooms: comparable_bedrooms,
-                distance_km,
-            });
-        }
-
-        // Sort by distance if coordinates available
-        if latitude.is_some() && longitude.is_some() {
-            comparables.sort_by(|a, b| a.distance_km.partial_cmp(&b.distance_km).unwrap());
-        }
-
-        Ok(comparables)
-    }
-
     fn calculate_investment_potential(
         &self,
         property_type: &str,
@@ -826,70 +1441,46 @@ impl PropertyService {
         potential.min(1.0)
     }
 
-    fn estimate_rental_yield(&self, property_type: &str, city: &str, purchase_price: i64) -> f64 {
-        // Annual rental yield estimates for different property types and cities
-        let base_yield = match (city.to_lowercase().as_str(), property_type.to_lowercase().as_str()) {
-            ("lagos", "apartment") => 0.08,
-            ("lagos", "house") => 0.07,
-            ("lagos", "duplex") => 0.06,
-            ("lagos", "commercial") => 0.12,
-            ("abuja", "apartment") => 0.09,
-            ("abuja", "house") => 0.08,
-            ("abuja", "duplex") => 0.07,
-            ("abuja", "commercial") => 0.11,
-            ("port harcourt", "apartment") => 0.10,
-            ("port harcourt", "house") => 0.09,
-            ("port harcourt", "duplex") => 0.08,
-            ("port harcourt", "commercial") => 0.13,
-            (_, "apartment") => 0.11,
-            (_, "house") => 0.10,
-            (_, "duplex") => 0.09,
-            (_, "commercial") => 0.14,
-            _ => 0.08,
-        };
+    /// Gross yield at `purchase_price` from the median rent of the same kind of
+    /// property in the area. Rents on listings are quoted per year.
+    async fn estimate_rental_yield(
+        &self,
+        state: &str,
+        lga: &str,
+        property_type: PropertyType,
+        purchase_price: i64,
+    ) -> Result<Option<f64>, ServiceError> {
+        if purchase_price <= 0 {
+            return Ok(None);
+        }
 
-        // Adjust based on price range (higher-end properties typically have lower yields)
-        let price_millions = purchase_price as f64 / 1_000_000.0;
-        let price_adjustment = if price_millions > 100.0 {
-            0.8 // Luxury properties
-        } else if price_millions > 50.0 {
-            0.9 // Premium properties
-        } else if price_millions < 10.0 {
-            1.2 // Affordable properties (higher yield potential)
-        } else {
-            1.0 // Mid-range properties
-        };
+        let rent = self
+            .get_market_price_data(state, lga, property_type, ListingType::Rent)
+            .await?;
 
-        base_yield * price_adjustment
+        Ok(rent.map(|rent| rent.median_price as f64 / purchase_price as f64))
     }
 
-    fn get_market_trends(&self, city: &str, property_type: &str) -> String {
-        match city.to_lowercase().as_str() {
-            "lagos" => {
-                match property_type.to_lowercase().as_str() {
-                    "apartment" => "Strong demand in Victoria Island, Ikoyi, and Lekki. Price growth of 8-12% annually.".to_string(),
-                    "house" => "High demand for detached houses in GRA Ikeja, Magodo, and Lekki Peninsula.".to_string(),
-                    "duplex" => "Premium duplex market showing 10-15% annual growth in choice locations.".to_string(),
-                    "land" => "Land prices appreciating rapidly, especially along Lekki-Epe corridor.".to_string(),
-                    "commercial" => "Office spaces in high demand, retail spaces experiencing mixed performance.".to_string(),
-                    _ => "Mixed performance across property segments.".to_string(),
-                }
-            },
-            "abuja" => {
-                match property_type.to_lowercase().as_str() {
-                    "apartment" => "Steady growth in Wuse, Garki, and Maitama areas. 6-10% annual appreciation.".to_string(),
-                    "house" => "Strong market for houses in Asokoro, Maitama, and Gwarinpa.".to_string(),
-                    "duplex" => "Growing demand in developing areas like Lugbe and Life Camp.".to_string(),
-                    "land" => "Government allocation lands showing strong appreciation potential.".to_string(),
-                    "commercial" => "CBD areas maintaining strong rental and capital growth.".to_string(),
-                    _ => "Stable market with moderate growth potential.".to_string(),
-                }
-            },
-            "port harcourt" => {
-                "Oil industry recovery driving property market improvement. 5-8% annual growth expected.".to_string()
-            },
-            _ => "Emerging market with good growth potential for well-located properties.".to_string(),
-        }
+    /// Monthly median asking prices over the last year, oldest first
+    pub async fn market_trend(
+        &self,
+        state: &str,
+        property_type: PropertyType,
+        listing_type: ListingType,
+    ) -> Result<Vec<MarketTrendPoint>, ServiceError> {
+        Ok(self.db_client
+            .get_market_trend(state, property_type, listing_type, TREND_MONTHS)
+            .await?)
+    }
+
+    async fn get_market_trends(
+        &self,
+        state: &str,
+        property_type: PropertyType,
+        listing_type: ListingType,
+    ) -> Result<String, ServiceError> {
+        let points = self.market_trend(state, property_type, listing_type).await?;
+        Ok(describe_trend(&points))
     }
 
     /// Extract and validate location keywords for search optimization
@@ -999,8 +1590,9 @@ impl PropertyService {
         address: &str,
         city: &str,
         state: &str,
-        property_type: &str,
-        listing_type: &str,
+        lga: &str,
+        property_type: PropertyType,
+        listing_type: ListingType,
         price: i64,
         size_sqm: Option<f64>,
         bedrooms: Option<i32>,
@@ -1008,7 +1600,7 @@ impl PropertyService {
         let mut report = ValidationReport::new();
         
         // 1. Address and location validation
-        match self.validate_and_geocode_address(address, city, state).await {
+        match self.validate_and_geocode_address(address, city, state) {
             Ok((lat, lng, formatted_address)) => {
                 report.location_valid = true;
                 report.coordinates = Some((lat, lng));
@@ -1021,16 +1613,19 @@ impl PropertyService {
         }
         
         // 2. Price validation with market data
-        match self.validate_property_price(price, property_type, city, state, bedrooms, size_sqm, listing_type).await {
-            Ok(price_data) => {
+        match self.validate_property_price(price, property_type, listing_type, state, lga, size_sqm).await {
+            Ok(None) => {
+                report.add_warning("Too few verified listings in this area to check the price");
+            },
+            Ok(Some(price_data)) => {
                 report.price_valid = true;
                 report.market_price_range = Some((price_data.min_price, price_data.max_price));
                 report.add_success("Price validated against market data");
                 
                 // Price competitiveness analysis
-                let competitiveness = if price <= price_data.average_price {
+                let competitiveness = if price <= price_data.median_price {
                     "Very Competitive"
-                } else if price <= price_data.average_price * 120 / 100 {
+                } else if price <= price_data.median_price * 120 / 100 {
                     "Competitive"
                 } else if price <= price_data.max_price {
                     "Above Average"
@@ -1045,11 +1640,11 @@ impl PropertyService {
         }
         
         // 3. Document requirements check
-        let doc_requirements = self.validate_required_documents(property_type, listing_type, state);
+        let doc_requirements = self.validate_required_documents(property_type.to_str(), listing_type.to_str(), state);
         report.document_requirements = doc_requirements;
         
         // 4. Property specifications validation
-        if let Err(errors) = self.validate_property_specifications(property_type, bedrooms, size_sqm, price) {
+        if let Err(errors) = self.validate_property_specifications(property_type.to_str(), bedrooms, size_sqm, price) {
             for error in errors {
                 report.add_warning(&error);
             }
@@ -1058,15 +1653,24 @@ impl PropertyService {
         }
         
         // 5. Market analysis
-        if let Some((lat, lng)) = report.coordinates {
-            match self.perform_market_analysis(property_type, city, state, price, bedrooms, size_sqm, Some(lat), Some(lng)).await {
-                Ok(analysis) => {
-                    report.market_analysis = Some(analysis);
-                    report.add_success("Market analysis completed");
-                },
-                Err(e) => {
-                    report.add_warning(&format!("Market analysis incomplete: {}", e));
-                }
+        let filters = ComparableFilters {
+            exclude_property_id: None,
+            state,
+            lga,
+            property_type,
+            listing_type,
+            price,
+            bedrooms,
+            latitude: report.coordinates.map(|(lat, _)| lat),
+            longitude: report.coordinates.map(|(_, lng)| lng),
+        };
+        match self.perform_market_analysis(&filters, city).await {
+            Ok(analysis) => {
+                report.market_analysis = Some(analysis);
+                report.add_success("Market analysis completed");
+            },
+            Err(e) => {
+                report.add_warning(&format!("Market analysis incomplete: {}", e));
             }
         }
         
@@ -1126,9 +1730,9 @@ impl PropertyService {
             };
             
             if price_per_sqm < min_price_per_sqm {
-                errors.push(format!("Price per sqm (₦{:,.0}) is below market minimum (₦{:,.0})", price_per_sqm, min_price_per_sqm));
+                errors.push(format!("Price per sqm (₦{:.0}) is below market minimum (₦{:.0})", price_per_sqm, min_price_per_sqm));
             } else if price_per_sqm > max_price_per_sqm {
-                errors.push(format!("Price per sqm (₦{:,.0}) is above market maximum (₦{:,.0})", price_per_sqm, max_price_per_sqm));
+                errors.push(format!("Price per sqm (₦{:.0}) is above market maximum (₦{:.0})", price_per_sqm, max_price_per_sqm));
             }
         }
         
@@ -1202,4 +1806,392 @@ impl ValidationReport {
     pub fn has_warnings(&self) -> bool {
         !self.warnings.is_empty()
     }
-}
\ No newline at end of file
+}
+
+fn sla_hours(role: VerifierRole) -> i64 {
+    match role {
+        VerifierRole::Agent => AGENT_SLA_HOURS,
+        VerifierRole::Lawyer => LAWYER_SLA_HOURS,
+    }
+}
+
+/// Who a waiting listing should go to: among agents based within
+/// `AGENT_MAX_DISTANCE_KM` of it, or lawyers based in its state, the one
+/// holding the fewest listings, nearest first on a tie
+pub fn pick_verifier(
+    role: VerifierRole,
+    property: &Property,
+    candidates: &[VerifierCandidate],
+    exclude: &[Uuid],
+) -> Option<Uuid> {
+    let target = property_point(property);
+    let state = gazetteer::normalise_state(&property.state);
+
+    candidates
+        .iter()
+        .filter(|candidate| !exclude.contains(&candidate.id))
+        .filter_map(|candidate| {
+            let (base_state, base) = verifier_base(candidate)?;
+            let distance = target.map(|target| gazetteer::distance_km(target, base));
+            let eligible = match role {
+                VerifierRole::Agent => distance.is_some_and(|d| d <= AGENT_MAX_DISTANCE_KM),
+                VerifierRole::Lawyer => base_state == state,
+            };
+            eligible.then_some((candidate.active_assignments, distance.unwrap_or(0.0), candidate.id))
+        })
+        .min_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)))
+        .map(|(_, _, id)| id)
+}
+
+/// Where a verifier works from: their nearest landmark if the gazetteer
+/// knows it, otherwise their LGA
+fn verifier_base(candidate: &VerifierCandidate) -> Option<(String, (f64, f64))> {
+    candidate.nearest_landmark
+        .as_deref()
+        .and_then(|landmark| gazetteer::search(landmark, None, 1).into_iter().next())
+        .map(|place| (place.state, (place.latitude, place.longitude)))
+        .or_else(|| {
+            let (state, lga) = gazetteer::find_lga_anywhere(candidate.lga.as_deref()?)?;
+            Some((state.name.clone(), (lga.lat, lga.lng)))
+        })
+}
+
+/// The listing's pin, else its landmark, else its LGA
+fn property_point(property: &Property) -> Option<(f64, f64)> {
+    let pin = property.latitude.as_ref().and_then(|lat| lat.to_f64())
+        .zip(property.longitude.as_ref().and_then(|lng| lng.to_f64()));
+    if pin.is_some() {
+        return pin;
+    }
+
+    property.landmark
+        .as_deref()
+        .and_then(|landmark| gazetteer::search(landmark, Some(&property.state), 1).into_iter().next())
+        .map(|place| (place.latitude, place.longitude))
+        .or_else(|| {
+            gazetteer::resolve(&property.state, Some(&property.lga), Some(&property.city))
+                .ok()
+                .map(|location| (location.latitude, location.longitude))
+        })
+}
+
+/// Statuses a review moves the property through, in order
+pub fn review_steps(role: VerifierRole, decision: ReviewDecision) -> &'static [PropertyStatus] {
+    match (role, decision) {
+        (VerifierRole::Agent, ReviewDecision::Approved) => &[PropertyStatus::AgentVerified, PropertyStatus::AwaitingLawyer],
+        (VerifierRole::Lawyer, ReviewDecision::Approved) => &[PropertyStatus::LawyerVerified, PropertyStatus::Active],
+        (_, ReviewDecision::Rejected) => &[PropertyStatus::Rejected],
+    }
+}
+
+/// Refuses prices far outside the market's range: under half its 10th
+/// percentile, over three times its 90th, or a price per sqm off the median
+/// by more than five times
+pub fn check_price_against_market(
+    price: i64,
+    property_type: PropertyType,
+    size_sqm: Option<f64>,
+    market: &PropertyPriceData,
+) -> Result<(), PropertyServiceError> {
+    if price < market.min_price / 2 {
+        return Err(PropertyServiceError::PriceValidationFailed(
+            format!("Price (₦{}) is significantly below the market range (₦{} - ₦{}) for {} in {}",
+                price, market.min_price, market.max_price, property_type.to_str(), market.area)
+        ));
+    }
+
+    if price > market.max_price.saturating_mul(3) {
+        return Err(PropertyServiceError::PriceValidationFailed(
+            format!("Price (₦{}) is unreasonably high for {} in {} (max reasonable: ₦{})",
+                price, property_type.to_str(), market.area, market.max_price.saturating_mul(3))
+        ));
+    }
+
+    if let (Some(size), Some(price_per_sqm)) = (size_sqm.filter(|s| *s > 0.0), market.price_per_sqm) {
+        let actual_price_per_sqm = price as f64 / size;
+        let max_reasonable = price_per_sqm * 5.0;
+        let min_reasonable = price_per_sqm * 0.2;
+
+        if actual_price_per_sqm < min_reasonable || actual_price_per_sqm > max_reasonable {
+            return Err(PropertyServiceError::PriceValidationFailed(
+                format!("Price per sqm (₦{:.0}) is outside the reasonable range (₦{:.0} - ₦{:.0}) for {}",
+                    actual_price_per_sqm, min_reasonable, max_reasonable, market.area)
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// One line on where asking prices are heading, comparing the first and last
+/// months with enough new listings to trust their median
+pub fn describe_trend(points: &[MarketTrendPoint]) -> String {
+    let supplied: Vec<&MarketTrendPoint> = points.iter()
+        .filter(|p| p.listings >= MIN_TREND_MONTH_LISTINGS && p.median_price > 0)
+        .collect();
+
+    let (first, last) = match (supplied.first(), supplied.last()) {
+        (Some(first), Some(last)) if first.month != last.month => (first, last),
+        _ => return "Not enough listings yet to show a price trend".to_string(),
+    };
+
+    let change = (last.median_price - first.median_price) as f64 / first.median_price as f64 * 100.0;
+    let listings: i64 = points.iter().map(|p| p.listings).sum();
+    let period = format!("from {} to {}", first.month.format("%b %Y"), last.month.format("%b %Y"));
+
+    if change.abs() < 1.0 {
+        format!("Median asking price steady {} across {} listings", period, listings)
+    } else {
+        format!(
+            "Median asking price {} {:.1}% {} across {} listings",
+            if change > 0.0 { "up" } else { "down" },
+            change.abs(),
+            period,
+            listings
+        )
+    }
+}
+
+fn ensure_transition(property: &Property, to: PropertyStatus) -> Result<(), ServiceError> {
+    if property.status.can_transition_to(to) {
+        Ok(())
+    } else {
+        Err(ServiceError::InvalidPropertyTransition(property.id, property.status, to))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::propertymodel::{CurrencyType, ListingType};
+
+    fn property(status: PropertyStatus) -> Property {
+        Property {
+            id: Uuid::new_v4(),
+            landlord_id: Uuid::new_v4(),
+            agent_id: None,
+            lawyer_id: None,
+            title: "Three bedroom flat in Yaba".to_string(),
+            description: "Newly renovated flat close to the university".to_string(),
+            property_type: PropertyType::Apartment,
+            listing_type: ListingType::Rent,
+            address: "12 Herbert Macaulay Way".to_string(),
+            city: "Lagos".to_string(),
+            state: "Lagos".to_string(),
+            lga: "Yaba".to_string(),
+            country: "Nigeria".to_string(),
+            latitude: None,
+            longitude: None,
+            landmark: None,
+            bedrooms: Some(3),
+            bathrooms: Some(2),
+            toilets: Some(3),
+            size_sqm: None,
+            plot_size: None,
+            price: 2_500_000,
+            currency: CurrencyType::Naira,
+            price_negotiable: Some(false),
+            bidding_price: None,
+            inspection_fee: None,
+            amenities: None,
+            features: None,
+            certificate_of_occupancy: None,
+            deed_of_agreement: None,
+            survey_plan: None,
+            building_plan_approval: None,
+            property_photos: serde_json::json!(["https://cdn.example.com/front.jpg"]),
+            agent_verification_photos: None,
+            agent_verification_notes: None,
+            lawyer_verification_notes: None,
+            property_hash: String::new(),
+            coordinates_hash: String::new(),
+            status,
+            agent_verified_at: None,
+            lawyer_verified_at: None,
+            listed_at: None,
+            created_at: Some(Utc::now()),
+            updated_at: Some(Utc::now()),
+        }
+    }
+
+    /// Apply steps the way the service does, failing on the first illegal one
+    fn walk(mut property: Property, steps: &[PropertyStatus]) -> Result<Property, ServiceError> {
+        for next in steps {
+            ensure_transition(&property, *next)?;
+            property.status = *next;
+        }
+        Ok(property)
+    }
+
+    #[test]
+    fn test_approved_listing_walks_every_stage() {
+        let submitted = walk(property(PropertyStatus::Draft), &[PropertyStatus::AwaitingAgent]).unwrap();
+
+        let inspected = walk(submitted, review_steps(VerifierRole::Agent, ReviewDecision::Approved)).unwrap();
+        assert_eq!(inspected.status, PropertyStatus::AwaitingLawyer);
+
+        let listed = walk(inspected, review_steps(VerifierRole::Lawyer, ReviewDecision::Approved)).unwrap();
+        assert_eq!(listed.status, PropertyStatus::Active);
+        assert!(listed.status.is_public());
+    }
+
+    #[test]
+    fn test_either_review_can_reject() {
+        let rejected = walk(
+            property(PropertyStatus::AwaitingAgent),
+            review_steps(VerifierRole::Agent, ReviewDecision::Rejected),
+        )
+        .unwrap();
+        assert_eq!(rejected.status, PropertyStatus::Rejected);
+
+        let rejected = walk(
+            property(PropertyStatus::AwaitingLawyer),
+            review_steps(VerifierRole::Lawyer, ReviewDecision::Rejected),
+        )
+        .unwrap();
+        assert_eq!(rejected.status, PropertyStatus::Rejected);
+    }
+
+    #[test]
+    fn test_reviews_out_of_order_are_refused() {
+        // A lawyer cannot sign off before the agent has inspected
+        let early = walk(
+            property(PropertyStatus::AwaitingAgent),
+            review_steps(VerifierRole::Lawyer, ReviewDecision::Approved),
+        );
+        assert!(matches!(
+            early,
+            Err(ServiceError::InvalidPropertyTransition(_, PropertyStatus::AwaitingAgent, PropertyStatus::LawyerVerified))
+        ));
+
+        // Nor can a live listing be inspected again
+        let repeat = walk(
+            property(PropertyStatus::Active),
+            review_steps(VerifierRole::Agent, ReviewDecision::Approved),
+        );
+        assert!(repeat.is_err());
+
+        // And a rejected draft has to be resubmitted, not reviewed
+        let rejected = walk(
+            property(PropertyStatus::Rejected),
+            review_steps(VerifierRole::Agent, ReviewDecision::Rejected),
+        );
+        assert!(rejected.is_err());
+    }
+
+    #[test]
+    fn test_coordinates_hash_without_coordinates_never_collides() {
+        use crate::db::propertydb::NO_COORDINATES_HASH;
+
+        assert_eq!(coordinates_hash(None, None), NO_COORDINATES_HASH);
+
+        let lat: sqlx::types::BigDecimal = "6.5244".parse().unwrap();
+        let lng: sqlx::types::BigDecimal = "3.3792".parse().unwrap();
+        let nearby: sqlx::types::BigDecimal = "6.52441".parse().unwrap();
+        assert_eq!(coordinates_hash(Some(&lat), Some(&lng)), coordinates_hash(Some(&nearby), Some(&lng)));
+        assert_ne!(coordinates_hash(Some(&lat), Some(&lng)), NO_COORDINATES_HASH);
+    }
+
+    #[test]
+    fn test_price_checked_against_market_range() {
+        let market = PropertyPriceData {
+            min_price: 1_500_000,
+            max_price: 6_000_000,
+            median_price: 2_800_000,
+            currency: "NGN".to_string(),
+            price_per_sqm: Some(25_000.0),
+            days_on_market: Some(34.0),
+            listings: 42,
+            area: "Yaba, Lagos".to_string(),
+        };
+
+        assert!(check_price_against_market(2_500_000, PropertyType::Apartment, None, &market).is_ok());
+        assert!(check_price_against_market(900_000, PropertyType::Apartment, None, &market).is_ok());
+
+        // Far too cheap to be real, or far too dear
+        assert!(check_price_against_market(500_000, PropertyType::Apartment, None, &market).is_err());
+        assert!(check_price_against_market(20_000_000, PropertyType::Apartment, None, &market).is_err());
+
+        // In range overall but a mansion's price for a room
+        assert!(check_price_against_market(2_500_000, PropertyType::Apartment, Some(100.0), &market).is_ok());
+        assert!(check_price_against_market(2_500_000, PropertyType::Apartment, Some(10.0), &market).is_err());
+    }
+
+    #[test]
+    fn test_trend_skips_thin_months() {
+        let point = |month: u32, listings: i64, median_price: i64| MarketTrendPoint {
+            month: chrono::NaiveDate::from_ymd_opt(2026, month, 1).unwrap(),
+            listings,
+            median_price,
+            median_price_per_sqm: None,
+        };
+
+        assert_eq!(describe_trend(&[]), "Not enough listings yet to show a price trend");
+        assert_eq!(
+            describe_trend(&[point(1, 5, 2_000_000), point(2, 1, 9_000_000)]),
+            "Not enough listings yet to show a price trend"
+        );
+
+        let trend = describe_trend(&[
+            point(1, 4, 2_000_000),
+            point(5, 1, 9_000_000),
+            point(9, 6, 2_200_000),
+        ]);
+        assert_eq!(trend, "Median asking price up 10.0% from Jan 2026 to Sep 2026 across 11 listings");
+    }
+
+    #[test]
+    fn test_pick_verifier_balances_workload_nearby() {
+        let candidate = |lga: Option<&str>, landmark: Option<&str>, active_assignments: i64| VerifierCandidate {
+            id: Uuid::new_v4(),
+            name: "Verifier".to_string(),
+            lga: lga.map(str::to_string),
+            nearest_landmark: landmark.map(str::to_string),
+            active_assignments,
+        };
+        // The listing is in Yaba, Lagos Mainland
+        let listing = property(PropertyStatus::AwaitingAgent);
+
+        let surulere = candidate(Some("Surulere"), None, 2);
+        let lekki = candidate(Some("Ikeja"), Some("Lekki"), 0); // The landmark wins over the LGA
+        let kano = candidate(Some("Kano Municipal"), None, 0);
+        let nowhere = candidate(None, Some("Behind the big church"), 0);
+        let agents = vec![surulere.clone(), lekki.clone(), kano.clone(), nowhere];
+
+        // Least loaded within range; Kano is idle but too far
+        assert_eq!(pick_verifier(VerifierRole::Agent, &listing, &agents, &[]), Some(lekki.id));
+        // Someone who let it lapse is not offered it again
+        assert_eq!(pick_verifier(VerifierRole::Agent, &listing, &agents, &[lekki.id]), Some(surulere.id));
+        assert_eq!(pick_verifier(VerifierRole::Agent, &listing, &agents, &[lekki.id, surulere.id]), None);
+
+        // Lawyers only need to be in the state; equal workloads go to the nearer one
+        let ibeju = candidate(Some("Ibeju-Lekki"), None, 0);
+        let lawyers = vec![surulere.clone(), ibeju.clone(), lekki.clone(), kano];
+        assert_eq!(pick_verifier(VerifierRole::Lawyer, &listing, &lawyers, &[]), Some(lekki.id));
+        assert_eq!(pick_verifier(VerifierRole::Lawyer, &listing, &lawyers, &[lekki.id]), Some(ibeju.id));
+    }
+
+    #[test]
+    fn test_property_references_carry_a_valid_check_digit() {
+        let listing = property(PropertyStatus::Active);
+        let created_at = "2026-03-14T10:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let reference = PropertyService::generate_property_reference(
+            listing.property_type.to_str(),
+            "Lagos",
+            "Lagos Mainland",
+            &listing.id,
+            created_at,
+        );
+
+        assert!(reference.starts_with("VN-APTLAGLA26"));
+        assert!(PropertyService::is_valid_property_reference(&reference));
+
+        // A mistyped character fails the check digit
+        let (base_ref, check) = reference.rsplit_once('-').unwrap();
+        let wrong = if check == "0" { "1" } else { "0" };
+        assert!(!PropertyService::is_valid_property_reference(&format!("{}-{}", base_ref, wrong)));
+        assert!(!PropertyService::is_valid_property_reference("VN--0"));
+        assert!(!PropertyService::is_valid_property_reference("APTLAGLA26ABC123-7"));
+    }
+}
@@ -0,0 +1,143 @@
+// service/role_service.rs
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::{
+    db::{db::DBClient, permissiondb::PermissionExt},
+    models::{
+        permissionmodels::{Permission, RolePermissions, UserAccess, UserRoleGrant},
+        usermodel::UserRole,
+    },
+    service::{audit_service::AuditService, error::ServiceError},
+};
+
+/// Roles anyone can take on for themselves
+pub const SELF_SERVICE_ROLES: [UserRole; 3] = [UserRole::Worker, UserRole::Employer, UserRole::Vendor];
+
+/// Users hold any number of roles; routes and handlers check the permissions
+/// those roles carry. Every grant and revocation goes to the audit log.
+#[derive(Debug)]
+pub struct RoleService {
+    db_client: Arc<DBClient>,
+    audit_service: Arc<AuditService>,
+}
+
+impl RoleService {
+    pub fn new(db_client: Arc<DBClient>, audit_service: Arc<AuditService>) -> Self {
+        Self { db_client, audit_service }
+    }
+
+    pub async fn access(&self, user_id: Uuid) -> Result<UserAccess, ServiceError> {
+        Ok(self.db_client.get_user_access(user_id).await?)
+    }
+
+    pub async fn roles(&self, user_id: Uuid) -> Result<Vec<UserRoleGrant>, ServiceError> {
+        Ok(self.db_client.get_user_role_grants(user_id).await?)
+    }
+
+    pub async fn role_permissions(&self) -> Result<Vec<RolePermissions>, ServiceError> {
+        Ok(self.db_client.get_role_permissions().await?)
+    }
+
+    pub async fn has_role(&self, user_id: Uuid, role: UserRole) -> Result<bool, ServiceError> {
+        Ok(self.db_client.user_has_role(user_id, role).await?)
+    }
+
+    /// An admin giving a user a role. Granting a role the user already holds
+    /// changes nothing and is not logged.
+    pub async fn grant(
+        &self,
+        actor_id: Uuid,
+        actor: &UserAccess,
+        target_user_id: Uuid,
+        role: UserRole,
+    ) -> Result<Vec<UserRoleGrant>, ServiceError> {
+        ensure_can_manage(actor, role)?;
+
+        if self.db_client.grant_user_role(target_user_id, role, Some(actor_id)).await? {
+            self.audit_service.log_role_change(actor_id, target_user_id, role, true).await?;
+        }
+
+        self.roles(target_user_id).await
+    }
+
+    /// An admin taking a role away. The base `user` role stays, and admins
+    /// cannot remove their own ability to manage roles.
+    pub async fn revoke(
+        &self,
+        actor_id: Uuid,
+        actor: &UserAccess,
+        target_user_id: Uuid,
+        role: UserRole,
+    ) -> Result<Vec<UserRoleGrant>, ServiceError> {
+        ensure_can_manage(actor, role)?;
+
+        if role == UserRole::User {
+            return Err(ServiceError::Validation("Every account keeps the user role".to_string()));
+        }
+        if actor_id == target_user_id && matches!(role, UserRole::Admin | UserRole::SuperAdmin) {
+            return Err(ServiceError::Validation("You cannot revoke your own admin role".to_string()));
+        }
+
+        if self.db_client.revoke_user_role(target_user_id, role).await? {
+            self.audit_service.log_role_change(actor_id, target_user_id, role, false).await?;
+        }
+
+        self.roles(target_user_id).await
+    }
+
+    /// A user taking on one of the `SELF_SERVICE_ROLES` alongside the roles
+    /// they already hold
+    pub async fn add_own_role(&self, user_id: Uuid, role: UserRole) -> Result<(), ServiceError> {
+        if !SELF_SERVICE_ROLES.contains(&role) {
+            return Err(ServiceError::Validation(format!("The {} role is granted by an admin", role.to_str())));
+        }
+
+        if self.db_client.grant_user_role(user_id, role, None).await? {
+            self.audit_service.log_role_change(user_id, user_id, role, true).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Admin and super admin can only be handed out or taken away by someone allowed to
+pub fn ensure_can_manage(actor: &UserAccess, role: UserRole) -> Result<(), ServiceError> {
+    let required = match role {
+        UserRole::Admin | UserRole::SuperAdmin => Permission::RolesManagePrivileged,
+        _ => Permission::RolesManage,
+    };
+
+    if actor.has_permission(required) {
+        Ok(())
+    } else {
+        Err(ServiceError::MissingPermission(required.to_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn access(permissions: &[Permission]) -> UserAccess {
+        UserAccess {
+            roles: vec![UserRole::Admin],
+            permissions: permissions.iter().map(|p| p.to_str().to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_privileged_roles_need_the_privileged_permission() {
+        let admin = access(&[Permission::RolesManage]);
+        let super_admin = access(&[Permission::RolesManage, Permission::RolesManagePrivileged]);
+
+        assert!(ensure_can_manage(&admin, UserRole::Agent).is_ok());
+        assert!(matches!(
+            ensure_can_manage(&admin, UserRole::Admin),
+            Err(ServiceError::MissingPermission("roles.manage_privileged"))
+        ));
+        assert!(ensure_can_manage(&super_admin, UserRole::SuperAdmin).is_ok());
+        assert!(ensure_can_manage(&access(&[]), UserRole::Worker).is_err());
+    }
+}
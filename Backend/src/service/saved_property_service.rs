@@ -0,0 +1,206 @@
+// src/service/saved_property_service.rs
+use std::sync::Arc;
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        db::DBClient,
+        propertydb::{PropertyExt, PropertySearchFilters},
+        savedpropertydb::SavedPropertyExt,
+    },
+    models::propertymodel::{Property, SavedSearch},
+    service::{error::ServiceError, notification_service::NotificationService, property_service::PropertyService},
+};
+
+pub const MAX_SAVED_SEARCHES: i64 = 20;
+
+/// New listings named in one alert; the rest are counted
+const ALERT_LISTINGS: usize = 20;
+
+/// How often a saved search is re-run
+const ALERT_INTERVAL_MINUTES: i64 = 30;
+
+#[derive(Debug)]
+pub struct SavedPropertyService {
+    db_client: Arc<DBClient>,
+    property_service: Arc<PropertyService>,
+    notification_service: Arc<NotificationService>,
+}
+
+impl SavedPropertyService {
+    pub fn new(
+        db_client: Arc<DBClient>,
+        property_service: Arc<PropertyService>,
+        notification_service: Arc<NotificationService>,
+    ) -> Self {
+        Self {
+            db_client,
+            property_service,
+            notification_service,
+        }
+    }
+
+    /// Saves a public listing. False if it was already saved.
+    pub async fn save_property(&self, user_id: Uuid, property_id: Uuid) -> Result<bool, ServiceError> {
+        let property = self.property_service.get_property(property_id).await?;
+        if !property.status.is_public() {
+            return Err(ServiceError::PropertyNotFound(property_id));
+        }
+
+        Ok(self.db_client.save_property(user_id, property_id).await?)
+    }
+
+    pub async fn unsave_property(&self, user_id: Uuid, property_id: Uuid) -> Result<bool, ServiceError> {
+        Ok(self.db_client.unsave_property(user_id, property_id).await?)
+    }
+
+    pub async fn saved_properties(
+        &self,
+        user_id: Uuid,
+        page: u32,
+        limit: usize,
+    ) -> Result<Vec<Property>, ServiceError> {
+        Ok(self.db_client.get_saved_properties(user_id, page, limit).await?)
+    }
+
+    pub async fn create_search(
+        &self,
+        user_id: Uuid,
+        name: &str,
+        filters: PropertySearchFilters,
+        alerts_enabled: bool,
+    ) -> Result<SavedSearch, ServiceError> {
+        if !has_criteria(&filters) {
+            return Err(ServiceError::Validation("Add at least one filter to save a search".to_string()));
+        }
+
+        if self.db_client.count_saved_searches(user_id).await? >= MAX_SAVED_SEARCHES {
+            return Err(ServiceError::Validation(format!(
+                "You can keep up to {} saved searches. Delete one to add another",
+                MAX_SAVED_SEARCHES
+            )));
+        }
+
+        let filters = serde_json::to_value(&filters)
+            .map_err(|e| ServiceError::Other(e.to_string()))?;
+
+        Ok(self.db_client
+            .create_saved_search(user_id, name, &filters, alerts_enabled)
+            .await?)
+    }
+
+    pub async fn searches(&self, user_id: Uuid) -> Result<Vec<SavedSearch>, ServiceError> {
+        Ok(self.db_client.get_saved_searches(user_id).await?)
+    }
+
+    pub async fn delete_search(&self, user_id: Uuid, search_id: Uuid) -> Result<(), ServiceError> {
+        if !self.db_client.delete_saved_search(user_id, search_id).await? {
+            return Err(ServiceError::SavedSearchNotFound(search_id));
+        }
+        Ok(())
+    }
+
+    pub async fn set_search_alerts(
+        &self,
+        user_id: Uuid,
+        search_id: Uuid,
+        enabled: bool,
+    ) -> Result<SavedSearch, ServiceError> {
+        self.db_client
+            .set_saved_search_alerts(user_id, search_id, enabled)
+            .await?
+            .ok_or(ServiceError::SavedSearchNotFound(search_id))
+    }
+
+    /// Re-runs saved searches against listings that went live since each was
+    /// last checked, and tells their owners about new matches
+    pub async fn send_saved_search_alerts(&self, limit: i64) -> Result<usize, ServiceError> {
+        let searches = self.db_client
+            .get_saved_searches_to_check(Utc::now() - Duration::minutes(ALERT_INTERVAL_MINUTES), limit)
+            .await?;
+
+        let mut alerted = 0;
+        for search in searches {
+            // Taken before the query, so a listing going live meanwhile is caught next run
+            let checked_at = Utc::now();
+
+            let mut filters: PropertySearchFilters = match serde_json::from_value(search.filters.clone()) {
+                Ok(filters) => filters,
+                Err(e) => {
+                    tracing::error!("Saved search {} has unreadable filters: {}", search.id, e);
+                    self.db_client.set_saved_search_alerts(search.user_id, search.id, false).await?;
+                    continue;
+                }
+            };
+            filters.listed_after = Some(search.last_checked_at);
+
+            let matches: Vec<Property> = self.db_client
+                .get_active_properties(filters, 1, ALERT_LISTINGS)
+                .await?
+                .into_iter()
+                .filter(|property| property.landlord_id != search.user_id)
+                .collect();
+
+            if let Some(first) = matches.first() {
+                match self.notification_service
+                    .notify_saved_search_match(search.user_id, &search.name, first, matches.len())
+                    .await
+                {
+                    Ok(()) => alerted += 1,
+                    Err(e) => tracing::warn!("Failed to send saved search alert {}: {}", search.id, e),
+                }
+            }
+
+            self.db_client.mark_saved_search_checked(search.id, checked_at).await?;
+        }
+
+        Ok(alerted)
+    }
+}
+
+/// A saved search with no filters would match every new listing
+pub fn has_criteria(filters: &PropertySearchFilters) -> bool {
+    filters.property_type.is_some()
+        || filters.listing_type.is_some()
+        || filters.min_price.is_some()
+        || filters.max_price.is_some()
+        || filters.city.is_some()
+        || filters.state.is_some()
+        || filters.bedrooms.is_some()
+        || filters.bathrooms.is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::propertymodel::PropertyType;
+
+    #[test]
+    fn test_saved_filters_round_trip_without_cursor() {
+        let filters = PropertySearchFilters {
+            property_type: Some(PropertyType::Duplex),
+            state: Some("Lagos".to_string()),
+            max_price: Some(150_000_000),
+            listed_after: Some(Utc::now()),
+            ..Default::default()
+        };
+        assert!(has_criteria(&filters));
+
+        // The alert cursor lives on the saved search, not in its filters
+        let stored = serde_json::to_value(&filters).unwrap();
+        assert!(stored.get("listed_after").is_none());
+
+        let restored: PropertySearchFilters = serde_json::from_value(stored).unwrap();
+        assert_eq!(restored.property_type, Some(PropertyType::Duplex));
+        assert_eq!(restored.max_price, Some(150_000_000));
+        assert!(restored.listed_after.is_none());
+
+        // Country alone is the whole platform
+        let everything = PropertySearchFilters {
+            country: Some("Nigeria".to_string()),
+            ..Default::default()
+        };
+        assert!(!has_criteria(&everything));
+    }
+}
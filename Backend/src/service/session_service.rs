@@ -0,0 +1,262 @@
+// service/session_service.rs
+use std::sync::Arc;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::{
+    config::Config,
+    db::{db::DBClient, sessiondb::SessionExt},
+    models::sessionmodels::{SessionClient, UserSession},
+    service::error::ServiceError,
+    utils::token,
+};
+
+/// Why a session ended, as recorded in `user_sessions.revoked_reason`
+pub const REVOKED_LOGOUT: &str = "logout";
+pub const REVOKED_BY_USER: &str = "revoked";
+pub const REVOKED_ALL: &str = "revoked_all";
+pub const REVOKED_PASSWORD_CHANGE: &str = "password_change";
+pub const REVOKED_TOKEN_REUSE: &str = "refresh_token_reuse";
+pub const REVOKED_ACCOUNT_DELETED: &str = "account_deleted";
+
+/// An access token and the refresh token that replaces it
+#[derive(Debug, Serialize)]
+pub struct IssuedTokens {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub session_id: Uuid,
+    pub expires_in: i64, // Seconds until the access token expires
+    pub refresh_expires_at: DateTime<Utc>,
+}
+
+/// Sessions pair a short-lived access JWT with a refresh token that rotates on
+/// every use. A refresh token presented twice has leaked, so its session is
+/// revoked outright.
+#[derive(Debug)]
+pub struct SessionService {
+    db_client: Arc<DBClient>,
+    jwt_secret: String,
+    access_token_minutes: i64,
+    refresh_token_days: i64,
+}
+
+impl SessionService {
+    pub fn new(db_client: Arc<DBClient>, config: &Config) -> Self {
+        Self {
+            db_client,
+            jwt_secret: config.jwt_secret.clone(),
+            access_token_minutes: config.jwt_maxage,
+            refresh_token_days: config.refresh_token_maxage_days,
+        }
+    }
+
+    pub fn access_token_minutes(&self) -> i64 {
+        self.access_token_minutes
+    }
+
+    pub fn refresh_token_days(&self) -> i64 {
+        self.refresh_token_days
+    }
+
+    /// Signs the user in on a new device
+    pub async fn start(&self, user_id: Uuid, client: SessionClient) -> Result<IssuedTokens, ServiceError> {
+        let client = SessionClient {
+            device_name: client.device_name
+                .filter(|name| !name.trim().is_empty())
+                .or_else(|| client.user_agent.as_deref().and_then(describe_device))
+                .map(|name| name.chars().take(100).collect()),
+            ..client
+        };
+        let refresh_token = new_refresh_token();
+        let refresh_expires_at = Utc::now() + Duration::days(self.refresh_token_days);
+
+        let session = self.db_client
+            .create_session(user_id, &client, &hash_refresh_token(&refresh_token), refresh_expires_at)
+            .await?;
+
+        self.issue(user_id, session.id, refresh_token, refresh_expires_at)
+    }
+
+    /// Trades a refresh token for a new pair. Returns the user it belongs to.
+    pub async fn refresh(
+        &self,
+        refresh_token: &str,
+        client: SessionClient,
+    ) -> Result<(Uuid, IssuedTokens), ServiceError> {
+        let presented = self.db_client
+            .get_refresh_token(&hash_refresh_token(refresh_token))
+            .await?
+            .ok_or(ServiceError::InvalidRefreshToken)?;
+
+        let session = self.db_client
+            .get_session(presented.session_id)
+            .await?
+            .filter(UserSession::is_active)
+            .ok_or(ServiceError::InvalidRefreshToken)?;
+
+        if presented.used_at.is_some() {
+            self.revoke_for_reuse(&session).await?;
+            return Err(ServiceError::InvalidRefreshToken);
+        }
+        if presented.expires_at <= Utc::now() {
+            return Err(ServiceError::InvalidRefreshToken);
+        }
+
+        let next = new_refresh_token();
+        let refresh_expires_at = Utc::now() + Duration::days(self.refresh_token_days);
+        let rotated = self.db_client
+            .rotate_refresh_token(presented.id, session.id, &client, &hash_refresh_token(&next), refresh_expires_at)
+            .await?;
+
+        // Someone else used the same token a moment ago
+        if !rotated {
+            self.revoke_for_reuse(&session).await?;
+            return Err(ServiceError::InvalidRefreshToken);
+        }
+
+        Ok((session.user_id, self.issue(session.user_id, session.id, next, refresh_expires_at)?))
+    }
+
+    /// Whether an access token's session is still signed in
+    pub async fn is_active(&self, user_id: Uuid, session_id: Uuid) -> Result<bool, ServiceError> {
+        Ok(self.db_client
+            .get_session(session_id)
+            .await?
+            .is_some_and(|session| session.user_id == user_id && session.is_active()))
+    }
+
+    pub async fn active_sessions(&self, user_id: Uuid) -> Result<Vec<UserSession>, ServiceError> {
+        Ok(self.db_client.get_active_sessions(user_id).await?)
+    }
+
+    pub async fn revoke(&self, user_id: Uuid, session_id: Uuid, reason: &str) -> Result<UserSession, ServiceError> {
+        self.db_client
+            .revoke_session(user_id, session_id, reason)
+            .await?
+            .ok_or(ServiceError::SessionNotFound(session_id))
+    }
+
+    /// Signs the user out everywhere, except `keep` if given
+    pub async fn revoke_all(&self, user_id: Uuid, keep: Option<Uuid>, reason: &str) -> Result<u64, ServiceError> {
+        Ok(self.db_client.revoke_user_sessions(user_id, keep, reason).await?)
+    }
+
+    fn issue(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        refresh_token: String,
+        refresh_expires_at: DateTime<Utc>,
+    ) -> Result<IssuedTokens, ServiceError> {
+        let access_token = token::create_token(
+            &user_id.to_string(),
+            &session_id.to_string(),
+            self.jwt_secret.as_bytes(),
+            self.access_token_minutes,
+        )
+        .map_err(|e| ServiceError::Other(e.to_string()))?;
+
+        Ok(IssuedTokens {
+            access_token,
+            refresh_token,
+            session_id,
+            expires_in: self.access_token_minutes * 60,
+            refresh_expires_at,
+        })
+    }
+
+    async fn revoke_for_reuse(&self, session: &UserSession) -> Result<(), ServiceError> {
+        tracing::warn!(
+            "Refresh token reused for session {} of user {}; revoking the session",
+            session.id, session.user_id
+        );
+        self.db_client
+            .revoke_session(session.user_id, session.id, REVOKED_TOKEN_REUSE)
+            .await?;
+        Ok(())
+    }
+}
+
+fn new_refresh_token() -> String {
+    URL_SAFE_NO_PAD.encode(rand::random::<[u8; 32]>())
+}
+
+fn hash_refresh_token(refresh_token: &str) -> String {
+    hex::encode(Sha256::digest(refresh_token.as_bytes()))
+}
+
+/// "Chrome on Android" and the like, for the sessions list
+pub fn describe_device(user_agent: &str) -> Option<String> {
+    let ua = user_agent.to_lowercase();
+
+    // Order matters: Edge and Opera also claim Chrome, Chrome also claims Safari
+    let browser = [
+        ("edg/", "Edge"),
+        ("opr/", "Opera"),
+        ("firefox/", "Firefox"),
+        ("chrome/", "Chrome"),
+        ("safari/", "Safari"),
+        ("okhttp", "Android app"),
+        ("cfnetwork", "iOS app"),
+    ]
+    .iter()
+    .find(|(marker, _)| ua.contains(marker))
+    .map(|(_, name)| *name);
+
+    let platform = [
+        ("android", "Android"),
+        ("iphone", "iPhone"),
+        ("ipad", "iPad"),
+        ("windows", "Windows"),
+        ("mac os x", "macOS"),
+        ("linux", "Linux"),
+    ]
+    .iter()
+    .find(|(marker, _)| ua.contains(marker))
+    .map(|(_, name)| *name);
+
+    match (browser, platform) {
+        (Some(browser), Some(platform)) => Some(format!("{} on {}", browser, platform)),
+        (Some(name), None) | (None, Some(name)) => Some(name.to_string()),
+        (None, None) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refresh_tokens_are_random_and_stored_hashed() {
+        let first = new_refresh_token();
+        let second = new_refresh_token();
+
+        assert_ne!(first, second);
+        assert_eq!(first.len(), 43);
+        assert_eq!(hash_refresh_token(&first), hash_refresh_token(&first));
+        assert_ne!(hash_refresh_token(&first), hash_refresh_token(&second));
+        assert_eq!(hash_refresh_token(&first).len(), 64);
+    }
+
+    #[test]
+    fn test_devices_are_described_from_the_user_agent() {
+        assert_eq!(
+            describe_device("Mozilla/5.0 (Linux; Android 13; SM-A145F) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36").as_deref(),
+            Some("Chrome on Android")
+        );
+        assert_eq!(
+            describe_device("Mozilla/5.0 (iPhone; CPU iPhone OS 17_1 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.1 Mobile/15E148 Safari/604.1").as_deref(),
+            Some("Safari on iPhone")
+        );
+        assert_eq!(
+            describe_device("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36 Edg/120.0.0.0").as_deref(),
+            Some("Edge on Windows")
+        );
+        assert_eq!(describe_device("okhttp/4.9.2").as_deref(), Some("Android app"));
+        assert_eq!(describe_device("curl/8.4.0"), None);
+    }
+}
@@ -0,0 +1,190 @@
+// service/sms.rs
+//! Text message delivery. OTPs and SMS notifications go through one
+//! `SmsSender`, picked by `SMS_PROVIDER`:
+//! - `termii`: the Termii API, with `TERMII_API_KEY` and `TERMII_SENDER_ID`
+//! - `console`: logs each message, for local development
+//! - `file`: appends each message as a JSON line to `SMS_OUTBOX_PATH`
+//!   (default `./data/sms_outbox.jsonl`), for tests that read codes back
+//! - `none`: no SMS at all
+//!
+//! Without `SMS_PROVIDER`, Termii is used when `TERMII_API_KEY` is set.
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use reqwest::Client;
+use thiserror::Error;
+use tokio::io::AsyncWriteExt;
+
+const TERMII_SEND_URL: &str = "https://api.ng.termii.com/api/sms/send";
+const DEFAULT_OUTBOX_PATH: &str = "./data/sms_outbox.jsonl";
+
+#[derive(Debug, Error)]
+pub enum SmsError {
+    #[error("SMS provider failed: {0}")]
+    Provider(String),
+    #[error("SMS could not be written to the outbox: {0}")]
+    Outbox(String),
+    #[error("Invalid SMS configuration: {0}")]
+    Config(String),
+}
+
+#[async_trait]
+pub trait SmsSender: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// `to` is in E.164 form
+    async fn send(&self, to: &str, body: &str) -> Result<(), SmsError>;
+}
+
+/// The sender named by `SMS_PROVIDER`, or None when SMS is switched off
+pub fn from_env() -> Result<Option<Arc<dyn SmsSender>>, SmsError> {
+    let termii_key = std::env::var("TERMII_API_KEY").ok().filter(|key| !key.is_empty());
+    let provider = std::env::var("SMS_PROVIDER").unwrap_or_else(|_| {
+        if termii_key.is_some() { "termii" } else { "none" }.to_string()
+    });
+
+    let sender: Arc<dyn SmsSender> = match provider.as_str() {
+        "termii" => {
+            let api_key = termii_key.ok_or_else(|| {
+                SmsError::Config("SMS_PROVIDER is termii but TERMII_API_KEY is missing".to_string())
+            })?;
+            let sender_id = std::env::var("TERMII_SENDER_ID").unwrap_or_else(|_| "VeriNest".to_string());
+            Arc::new(TermiiSms::new(api_key, sender_id))
+        }
+        "console" => Arc::new(ConsoleSms),
+        "file" => Arc::new(FileSms::new(
+            std::env::var("SMS_OUTBOX_PATH").unwrap_or_else(|_| DEFAULT_OUTBOX_PATH.to_string()),
+        )),
+        "none" => return Ok(None),
+        other => return Err(SmsError::Config(format!("Unknown SMS_PROVIDER '{}'", other))),
+    };
+
+    Ok(Some(sender))
+}
+
+pub struct TermiiSms {
+    client: Client,
+    api_key: String,
+    sender_id: String,
+}
+
+impl TermiiSms {
+    pub fn new(api_key: String, sender_id: String) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(15))
+            .build()
+            .unwrap_or_default();
+
+        Self { client, api_key, sender_id }
+    }
+}
+
+#[async_trait]
+impl SmsSender for TermiiSms {
+    fn name(&self) -> &'static str {
+        "termii"
+    }
+
+    async fn send(&self, to: &str, body: &str) -> Result<(), SmsError> {
+        let payload = serde_json::json!({
+            // Termii wants the number without the plus
+            "to": to.trim_start_matches('+'),
+            "from": self.sender_id,
+            "sms": body,
+            "type": "plain",
+            "api_key": self.api_key,
+            "channel": "generic"
+        });
+
+        let response = self.client
+            .post(TERMII_SEND_URL)
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|e| SmsError::Provider(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(SmsError::Provider(format!("Termii returned {}", response.status())));
+        }
+
+        Ok(())
+    }
+}
+
+/// Logs messages instead of sending them. Never use in production: codes
+/// end up in the logs.
+pub struct ConsoleSms;
+
+#[async_trait]
+impl SmsSender for ConsoleSms {
+    fn name(&self) -> &'static str {
+        "console"
+    }
+
+    async fn send(&self, to: &str, body: &str) -> Result<(), SmsError> {
+        tracing::info!("SMS to {}: {}", to, body);
+        Ok(())
+    }
+}
+
+pub struct FileSms {
+    path: PathBuf,
+}
+
+impl FileSms {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl SmsSender for FileSms {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+
+    async fn send(&self, to: &str, body: &str) -> Result<(), SmsError> {
+        let mut line = serde_json::json!({ "to": to, "body": body, "sent_at": Utc::now() }).to_string();
+        line.push('\n');
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .map_err(|e| SmsError::Outbox(format!("{}: {}", self.path.display(), e)))?;
+
+        file.write_all(line.as_bytes())
+            .await
+            .map_err(|e| SmsError::Outbox(e.to_string()))?;
+        // tokio writes in the background; without this the line may land after the next one
+        file.flush()
+            .await
+            .map_err(|e| SmsError::Outbox(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_file_sender_appends_one_line_per_message() {
+        let path = std::env::temp_dir().join(format!("sms_outbox_{}.jsonl", uuid::Uuid::new_v4().simple()));
+        let sender = FileSms::new(&path);
+
+        sender.send("+2348031234567", "Your code is 123456").await.unwrap();
+        sender.send("+2348031234567", "Your code is 654321").await.unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let lines: Vec<serde_json::Value> = contents.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1]["to"], "+2348031234567");
+        assert_eq!(lines[1]["body"], "Your code is 654321");
+    }
+}
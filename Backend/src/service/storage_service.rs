@@ -0,0 +1,365 @@
+// service/storage_service.rs
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::{
+    config::Config,
+    db::{db::DBClient, mediadb::MediaExt},
+    models::mediamodels::{MediaObject, MediaPurpose, MediaVisibility},
+    service::{
+        error::ServiceError,
+        storage_backends::{validate_key, LocalStorage, S3Storage, StagedUpload, StorageBackend, StorageError},
+    },
+};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// How long a signed link to a private object stays usable
+pub const SIGNED_URL_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// How long a backend URL we redirect to stays usable; the client follows it at once
+const REDIRECT_URL_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// The largest upload any purpose accepts, for capping a body before the purpose is known
+pub const MAX_UPLOAD_MB: usize = 20;
+
+impl From<StorageError> for ServiceError {
+    fn from(err: StorageError) -> Self {
+        ServiceError::Storage(err.to_string())
+    }
+}
+
+/// How the files route answers for an object
+pub enum StoredFile {
+    Redirect(String),
+    Contents { content_type: &'static str, contents: Vec<u8> },
+}
+
+/// Stores uploads content addressed behind a pluggable backend and hands out
+/// URLs for them. Every object has a stable URL under `/api/media/files/`;
+/// private ones are only served with a signature that expires.
+pub struct StorageService {
+    db_client: Arc<DBClient>,
+    backend: Arc<dyn StorageBackend>,
+    files_url: String,
+    signing_secret: Vec<u8>,
+}
+
+impl fmt::Debug for StorageService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StorageService")
+            .field("backend", &self.backend.name())
+            .field("files_url", &self.files_url)
+            .finish()
+    }
+}
+
+impl StorageService {
+    pub fn new(
+        db_client: Arc<DBClient>,
+        backend: Arc<dyn StorageBackend>,
+        app_url: &str,
+        signing_secret: &str,
+    ) -> Self {
+        Self {
+            db_client,
+            backend,
+            files_url: format!("{}/api/media/files", app_url.trim_end_matches('/')),
+            signing_secret: signing_secret.as_bytes().to_vec(),
+        }
+    }
+
+    /// The backend named by `STORAGE_BACKEND`
+    pub fn from_config(db_client: Arc<DBClient>, config: &Config) -> Result<Self, StorageError> {
+        let backend: Arc<dyn StorageBackend> = match config.storage_backend.as_str() {
+            "s3" => {
+                let s3 = config.s3.clone().ok_or_else(|| StorageError::Backend(
+                    "STORAGE_BACKEND is s3 but S3_BUCKET, S3_ACCESS_KEY_ID or S3_SECRET_ACCESS_KEY is missing".to_string(),
+                ))?;
+                Arc::new(S3Storage::new(s3)?)
+            }
+            "local" => Arc::new(LocalStorage::new(&config.storage_local_path)),
+            other => return Err(StorageError::Backend(format!("Unknown STORAGE_BACKEND '{}'", other))),
+        };
+
+        Ok(Self::new(db_client, backend, &config.app_url, &config.storage_signing_secret))
+    }
+
+    /// Stores an upload staged on disk under the key its hash gives it. The
+    /// type is taken from the bytes, not the client.
+    pub async fn store(
+        &self,
+        owner_id: Uuid,
+        purpose: MediaPurpose,
+        filename: Option<&str>,
+        mut upload: StagedUpload,
+    ) -> Result<MediaObject, ServiceError> {
+        if upload.size() == 0 {
+            return Err(ServiceError::Validation("The file is empty".to_string()));
+        }
+        if upload.size() > purpose.max_size_mb() * 1024 * 1024 {
+            return Err(ServiceError::Validation(format!(
+                "{} uploads are limited to {}MB",
+                purpose.to_str(),
+                purpose.max_size_mb()
+            )));
+        }
+
+        let (content_type, extension) = detect_content_type(upload.head())
+            .filter(|(content_type, _)| *content_type != "application/pdf" || purpose.accepts_pdf())
+            .ok_or_else(|| ServiceError::Validation(if purpose.accepts_pdf() {
+                "Only JPEG, PNG, WebP and PDF files are accepted".to_string()
+            } else {
+                "Only JPEG, PNG and WebP images are accepted".to_string()
+            }))?;
+
+        let sha256 = upload.finish().await?;
+        let visibility = purpose.visibility();
+        let key = content_key(visibility, &sha256, extension);
+        let size_bytes = upload.size() as i64;
+
+        if !self.backend.exists(&key).await? {
+            self.backend.put(&key, &upload, &sha256, content_type).await?;
+        }
+
+        let filename = filename
+            .map(|name| name.chars().take(255).collect::<String>())
+            .filter(|name| !name.trim().is_empty());
+
+        Ok(self.db_client
+            .insert_media_object(
+                owner_id,
+                purpose.to_str(),
+                visibility,
+                &key,
+                content_type,
+                size_bytes,
+                &sha256,
+                filename.as_deref(),
+            )
+            .await?)
+    }
+
+    /// The owner's upload, or any upload for an admin
+    pub async fn get_media(&self, user_id: Uuid, media_id: Uuid, is_admin: bool) -> Result<MediaObject, ServiceError> {
+        let object = self.db_client
+            .get_media_object(media_id)
+            .await?
+            .ok_or(ServiceError::MediaNotFound(media_id))?;
+
+        if object.owner_id != user_id && !is_admin {
+            return Err(ServiceError::UnauthorizedMediaAccess(user_id, media_id));
+        }
+
+        Ok(object)
+    }
+
+    /// The stable URL to store against a listing, profile or submission
+    pub fn url(&self, object: &MediaObject) -> String {
+        format!("{}/{}", self.files_url, object.storage_key)
+    }
+
+    /// A URL the caller can open right now: the stable URL for public
+    /// objects, a signed one for private objects
+    pub fn access_url(&self, object: &MediaObject) -> String {
+        match object.visibility {
+            MediaVisibility::Public => self.url(object),
+            MediaVisibility::Private => self.signed_url(&object.storage_key, SIGNED_URL_TTL),
+        }
+    }
+
+    pub fn signed_url(&self, key: &str, expires_in: Duration) -> String {
+        let expires = Utc::now().timestamp() + expires_in.as_secs() as i64;
+        format!(
+            "{}/{}?expires={}&signature={}",
+            self.files_url,
+            key,
+            expires,
+            url_signature(&self.signing_secret, key, expires)
+        )
+    }
+
+    /// Signs a stored URL for display if it points at one of our private
+    /// objects; anything else comes back unchanged
+    pub fn presign_url(&self, url: &str) -> String {
+        match self.key_from_url(url) {
+            Some(key) if key.starts_with("private/") => self.signed_url(key, SIGNED_URL_TTL),
+            _ => url.to_string(),
+        }
+    }
+
+    /// The storage key behind one of our URLs
+    pub fn key_from_url<'a>(&self, url: &'a str) -> Option<&'a str> {
+        let key = url.strip_prefix(&self.files_url)?.strip_prefix('/')?;
+        let key = key.split_once('?').map(|(key, _)| key).unwrap_or(key);
+        validate_key(key).ok().map(|_| key)
+    }
+
+    /// Errors unless `url` is an upload `owner_id` made for one of `purposes`
+    pub async fn ensure_owned(
+        &self,
+        owner_id: Uuid,
+        url: &str,
+        purposes: &[MediaPurpose],
+    ) -> Result<MediaObject, ServiceError> {
+        let not_uploaded = || ServiceError::Validation(format!(
+            "Upload the file through /api/media/upload as {} first",
+            purposes.iter().map(|p| p.to_str()).collect::<Vec<_>>().join(" or ")
+        ));
+
+        let key = self.key_from_url(url).ok_or_else(not_uploaded)?;
+        let object = self.db_client
+            .get_owned_media_by_key(owner_id, key)
+            .await?
+            .ok_or_else(not_uploaded)?;
+
+        if !purposes.iter().any(|purpose| purpose.to_str() == object.purpose) {
+            return Err(not_uploaded());
+        }
+
+        Ok(object)
+    }
+
+    /// `ensure_owned` for every URL in a list
+    pub async fn ensure_all_owned(
+        &self,
+        owner_id: Uuid,
+        urls: &[String],
+        purposes: &[MediaPurpose],
+    ) -> Result<(), ServiceError> {
+        for url in urls {
+            self.ensure_owned(owner_id, url, purposes).await?;
+        }
+        Ok(())
+    }
+
+    /// Whether a request for `key` may be served
+    pub fn authorize(&self, key: &str, expires: Option<i64>, signature: Option<&str>) -> bool {
+        if key.starts_with("public/") {
+            return true;
+        }
+
+        match (expires, signature) {
+            (Some(expires), Some(signature)) => {
+                expires >= Utc::now().timestamp() && verify_url_signature(&self.signing_secret, key, expires, signature)
+            }
+            _ => false,
+        }
+    }
+
+    /// What the files route sends for an authorised key. None if nothing is stored there.
+    pub async fn open(&self, key: &str) -> Result<Option<StoredFile>, ServiceError> {
+        validate_key(key)?;
+        let visibility = if key.starts_with("public/") { MediaVisibility::Public } else { MediaVisibility::Private };
+
+        if let Some(url) = self.backend.direct_url(key, visibility, REDIRECT_URL_TTL) {
+            return Ok(Some(StoredFile::Redirect(url)));
+        }
+
+        Ok(self.backend.get(key).await?.map(|contents| StoredFile::Contents {
+            content_type: content_type_for_key(key),
+            contents,
+        }))
+    }
+
+    /// The bytes behind one of our URLs, read straight from the backend.
+    /// None if the URL is not ours.
+    pub async fn read_url(&self, url: &str) -> Result<Option<Vec<u8>>, ServiceError> {
+        let Some(key) = self.key_from_url(url) else {
+            return Ok(None);
+        };
+
+        let contents = self.backend.get(key).await?;
+        if contents.is_none() {
+            tracing::warn!("Media {} is referenced but not stored", key);
+        }
+        Ok(contents)
+    }
+}
+
+/// {visibility}/{first two hex digits}/{sha256}.{ext}, so the visibility is
+/// in the key and files spread over 256 directories
+fn content_key(visibility: MediaVisibility, sha256: &str, extension: &str) -> String {
+    format!("{}/{}/{}.{}", visibility.to_str(), &sha256[..2], sha256, extension)
+}
+
+/// MIME type and extension from the file's leading bytes
+fn detect_content_type(contents: &[u8]) -> Option<(&'static str, &'static str)> {
+    if contents.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some(("image/jpeg", "jpg"))
+    } else if contents.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some(("image/png", "png"))
+    } else if contents.len() >= 12 && &contents[..4] == b"RIFF" && &contents[8..12] == b"WEBP" {
+        Some(("image/webp", "webp"))
+    } else if contents.starts_with(b"%PDF-") {
+        Some(("application/pdf", "pdf"))
+    } else {
+        None
+    }
+}
+
+fn content_type_for_key(key: &str) -> &'static str {
+    match key.rsplit_once('.').map(|(_, extension)| extension) {
+        Some("jpg") => "image/jpeg",
+        Some("png") => "image/png",
+        Some("webp") => "image/webp",
+        Some("pdf") => "application/pdf",
+        _ => "application/octet-stream",
+    }
+}
+
+fn url_mac(secret: &[u8], key: &str, expires: i64) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC takes keys of any size");
+    mac.update(format!("{}\n{}", key, expires).as_bytes());
+    mac
+}
+
+fn url_signature(secret: &[u8], key: &str, expires: i64) -> String {
+    hex::encode(url_mac(secret, key, expires).finalize().into_bytes())
+}
+
+fn verify_url_signature(secret: &[u8], key: &str, expires: i64, signature: &str) -> bool {
+    hex::decode(signature)
+        .map(|signature| url_mac(secret, key, expires).verify_slice(&signature).is_ok())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::Digest;
+
+    #[test]
+    fn test_content_is_typed_from_its_bytes() {
+        assert_eq!(detect_content_type(&[0xFF, 0xD8, 0xFF, 0xE0, 0, 0]), Some(("image/jpeg", "jpg")));
+        assert_eq!(detect_content_type(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"), Some(("image/png", "png")));
+        assert_eq!(detect_content_type(b"RIFF\x24\0\0\0WEBPVP8 "), Some(("image/webp", "webp")));
+        assert_eq!(detect_content_type(b"%PDF-1.7\n"), Some(("application/pdf", "pdf")));
+        assert_eq!(detect_content_type(b"<svg onload=alert(1)>"), None);
+
+        let sha256 = hex::encode(Sha256::digest(b"selfie"));
+        let key = content_key(MediaVisibility::Private, &sha256, "jpg");
+        assert_eq!(key, format!("private/{}/{}.jpg", &sha256[..2], sha256));
+        assert!(validate_key(&key).is_ok());
+        assert_eq!(content_type_for_key(&key), "image/jpeg");
+    }
+
+    #[test]
+    fn test_url_signatures_bind_key_and_expiry() {
+        let secret = b"storage-secret";
+        let key = "private/ab/abcdef.jpg";
+        let signature = url_signature(secret, key, 1_900_000_000);
+
+        assert!(verify_url_signature(secret, key, 1_900_000_000, &signature));
+        assert!(!verify_url_signature(secret, key, 1_900_000_001, &signature));
+        assert!(!verify_url_signature(secret, "private/ab/abcdeg.jpg", 1_900_000_000, &signature));
+        assert!(!verify_url_signature(b"other-secret", key, 1_900_000_000, &signature));
+        assert!(!verify_url_signature(secret, key, 1_900_000_000, "zz"));
+    }
+}
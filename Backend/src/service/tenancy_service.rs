@@ -0,0 +1,639 @@
+// src/service/tenancy_service.rs
+use std::sync::Arc;
+use chrono::{Duration, Months, NaiveDate, Utc};
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        db::DBClient, naira_walletdb::NairaWalletExt, offerdb::OfferExt, tenancydb::TenancyExt, userdb::UserExt,
+    },
+    dtos::tenancydtos::{AcceptTenancyDto, MoveOutInspectionDto, RentReceiptDto, TenancyOfferDto},
+    models::{
+        propertymodel::{ListingType, Property, PropertyStatus},
+        tenancymodels::{receipt_number, RentPayment, RentPaymentStatus, Tenancy, TenancyStatus},
+        walletmodels::{kobo_to_naira, naira_to_kobo, TransactionType},
+    },
+    service::{error::ServiceError, notification_service::NotificationService, property_service::PropertyService},
+};
+
+pub const DEFAULT_GRACE_PERIOD_DAYS: i32 = 7;
+pub const DEFAULT_LATE_FEE_PERCENT: i32 = 5;
+
+#[derive(Debug)]
+pub struct TenancyService {
+    db_client: Arc<DBClient>,
+    property_service: Arc<PropertyService>,
+    notification_service: Arc<NotificationService>,
+}
+
+impl TenancyService {
+    pub fn new(
+        db_client: Arc<DBClient>,
+        property_service: Arc<PropertyService>,
+        notification_service: Arc<NotificationService>,
+    ) -> Self {
+        Self {
+            db_client,
+            property_service,
+            notification_service,
+        }
+    }
+
+    /// A prospective tenant offers terms on a live rental listing
+    pub async fn make_offer(
+        &self,
+        tenant_id: Uuid,
+        offer: TenancyOfferDto,
+    ) -> Result<Tenancy, ServiceError> {
+        let property = self.property_service.get_property(offer.property_id).await?;
+        if property.status != PropertyStatus::Active || !is_rentable(&property) {
+            return Err(ServiceError::Validation("Offers can only be made on active rental listings".to_string()));
+        }
+        if property.landlord_id == tenant_id {
+            return Err(ServiceError::Validation("You cannot rent your own property".to_string()));
+        }
+        if offer.start_date < Utc::now().date_naive() {
+            return Err(ServiceError::Validation("The tenancy cannot start in the past".to_string()));
+        }
+
+        let end_date = offer.start_date
+            .checked_add_months(Months::new(offer.duration_months))
+            .ok_or(ServiceError::Validation("Invalid tenancy duration".to_string()))?;
+
+        let tenancy = self.db_client
+            .create_tenancy_offer(tenant_id, property.landlord_id, &offer, end_date)
+            .await
+            .map_err(|e| match &e {
+                sqlx::Error::Database(db_error) if db_error.is_unique_violation() => {
+                    ServiceError::Validation("You already have a pending offer on this property".to_string())
+                }
+                _ => ServiceError::Database(e),
+            })?;
+
+        let tenant_name = self.user_name(tenant_id).await?;
+        if let Err(e) = self.notification_service
+            .notify_tenancy_offer(property.landlord_id, &property, &tenancy, &tenant_name)
+            .await
+        {
+            tracing::warn!("Failed to notify landlord of tenancy offer {}: {}", tenancy.id, e);
+        }
+
+        Ok(tenancy)
+    }
+
+    pub async fn get_tenancy(&self, tenancy_id: Uuid) -> Result<Tenancy, ServiceError> {
+        self.db_client
+            .get_tenancy(tenancy_id)
+            .await?
+            .ok_or(ServiceError::TenancyNotFound(tenancy_id))
+    }
+
+    /// A tenancy as seen by its tenant or landlord
+    pub async fn get_tenancy_for(&self, user_id: Uuid, tenancy_id: Uuid) -> Result<Tenancy, ServiceError> {
+        let tenancy = self.get_tenancy(tenancy_id).await?;
+        if !is_party(&tenancy, user_id) {
+            return Err(ServiceError::UnauthorizedTenancyAccess(user_id, tenancy_id));
+        }
+        Ok(tenancy)
+    }
+
+    pub async fn get_rent_schedule(&self, user_id: Uuid, tenancy_id: Uuid) -> Result<Vec<RentPayment>, ServiceError> {
+        let tenancy = self.get_tenancy_for(user_id, tenancy_id).await?;
+        Ok(self.db_client.get_rent_payments(tenancy.id).await?)
+    }
+
+    /// The landlord accepts: the caution deposit is held in the tenant's wallet,
+    /// the rent schedule is written and the property is marked Rented, all in
+    /// one transaction
+    pub async fn accept(
+        &self,
+        landlord_id: Uuid,
+        tenancy_id: Uuid,
+        terms: AcceptTenancyDto,
+    ) -> Result<Tenancy, ServiceError> {
+        let tenancy = self.get_tenancy(tenancy_id).await?;
+        if tenancy.landlord_id != landlord_id {
+            return Err(ServiceError::UnauthorizedTenancyAccess(landlord_id, tenancy_id));
+        }
+        ensure_transition(&tenancy, TenancyStatus::Active)?;
+
+        let property = self.property_service.get_property(tenancy.property_id).await?;
+        if property.status != PropertyStatus::Active {
+            return Err(ServiceError::Validation("The property is no longer available to rent".to_string()));
+        }
+        if self.db_client.get_accepted_offer(property.id).await?.is_some() {
+            return Err(ServiceError::Validation("The property is under offer".to_string()));
+        }
+
+        // Hold, activation and the Rented listing land together or not at all
+        let mut tx = self.db_client.pool.begin().await?;
+        let deposit_hold_id = if tenancy.caution_deposit > 0 {
+            Some(self.hold_deposit(&tenancy, &mut tx).await?)
+        } else {
+            None
+        };
+
+        let accepted = match self.db_client
+            .accept_tenancy_tx(
+                tenancy.id,
+                terms.grace_period_days.unwrap_or(DEFAULT_GRACE_PERIOD_DAYS),
+                terms.late_fee_percent.unwrap_or(DEFAULT_LATE_FEE_PERCENT),
+                terms.note.as_deref(),
+                deposit_hold_id,
+                &tenancy.rent_schedule(),
+                &mut tx,
+            )
+            .await
+        {
+            Ok(Some(accepted)) => accepted,
+            Ok(None) => return Err(ServiceError::InvalidTenancyTransition(tenancy.id, tenancy.status, TenancyStatus::Active)),
+            Err(sqlx::Error::Database(db_error)) if db_error.is_unique_violation() => {
+                return Err(ServiceError::Validation("The property already has a running tenancy".to_string()));
+            }
+            Err(e) => return Err(ServiceError::Database(e)),
+        };
+
+        self.property_service
+            .transition_tx(&property, PropertyStatus::Rented, &mut tx)
+            .await?;
+        tx.commit().await?;
+
+        if let Err(e) = self.notification_service
+            .notify_tenancy_accepted(accepted.tenant_id, &property, &accepted)
+            .await
+        {
+            tracing::warn!("Failed to notify tenant of accepted tenancy {}: {}", accepted.id, e);
+        }
+
+        let competing = self.db_client
+            .decline_competing_offers(property.id, accepted.id, "The property has been let to another tenant")
+            .await?;
+        for offer in competing {
+            if let Err(e) = self.notification_service
+                .notify_tenancy_declined(offer.tenant_id, &property, &offer, "The property has been let to another tenant")
+                .await
+            {
+                tracing::warn!("Failed to notify tenant of declined offer {}: {}", offer.id, e);
+            }
+        }
+
+        // Rent due on the start date is collected straight away
+        self.collect_due_for(&accepted).await;
+
+        Ok(accepted)
+    }
+
+    pub async fn decline(
+        &self,
+        landlord_id: Uuid,
+        tenancy_id: Uuid,
+        reason: &str,
+    ) -> Result<Tenancy, ServiceError> {
+        let tenancy = self.get_tenancy(tenancy_id).await?;
+        if tenancy.landlord_id != landlord_id {
+            return Err(ServiceError::UnauthorizedTenancyAccess(landlord_id, tenancy_id));
+        }
+
+        let tenancy = self.transition(&tenancy, TenancyStatus::Declined, Some(reason)).await?;
+        let property = self.property_service.get_property(tenancy.property_id).await?;
+
+        if let Err(e) = self.notification_service
+            .notify_tenancy_declined(tenancy.tenant_id, &property, &tenancy, reason)
+            .await
+        {
+            tracing::warn!("Failed to notify tenant of declined offer {}: {}", tenancy.id, e);
+        }
+
+        Ok(tenancy)
+    }
+
+    pub async fn withdraw(
+        &self,
+        tenant_id: Uuid,
+        tenancy_id: Uuid,
+    ) -> Result<Tenancy, ServiceError> {
+        let tenancy = self.get_tenancy(tenancy_id).await?;
+        if tenancy.tenant_id != tenant_id {
+            return Err(ServiceError::UnauthorizedTenancyAccess(tenant_id, tenancy_id));
+        }
+
+        let tenancy = self.transition(&tenancy, TenancyStatus::Withdrawn, None).await?;
+        let property = self.property_service.get_property(tenancy.property_id).await?;
+        let tenant_name = self.user_name(tenant_id).await?;
+
+        if let Err(e) = self.notification_service
+            .notify_tenancy_withdrawn(tenancy.landlord_id, &property, &tenancy, &tenant_name)
+            .await
+        {
+            tracing::warn!("Failed to notify landlord of withdrawn offer {}: {}", tenancy.id, e);
+        }
+
+        Ok(tenancy)
+    }
+
+    /// Either side gives notice. Rent that has not fallen due is cancelled and
+    /// the tenancy waits for the move-out inspection.
+    pub async fn give_notice(
+        &self,
+        user_id: Uuid,
+        tenancy_id: Uuid,
+        reason: &str,
+    ) -> Result<Tenancy, ServiceError> {
+        let tenancy = self.get_tenancy_for(user_id, tenancy_id).await?;
+        let tenancy = self.transition(&tenancy, TenancyStatus::MoveOut, Some(reason)).await?;
+
+        self.db_client
+            .cancel_future_rent_payments(tenancy.id, Utc::now().date_naive())
+            .await?;
+
+        let property = self.property_service.get_property(tenancy.property_id).await?;
+        let given_by = self.user_name(user_id).await?;
+        let other_party = if user_id == tenancy.tenant_id { tenancy.landlord_id } else { tenancy.tenant_id };
+
+        if let Err(e) = self.notification_service
+            .notify_tenancy_move_out(other_party, &property, &tenancy, &given_by)
+            .await
+        {
+            tracing::warn!("Failed to notify {} of move-out notice on tenancy {}: {}", other_party, tenancy.id, e);
+        }
+
+        Ok(tenancy)
+    }
+
+    /// The landlord or the property's agent records the move-out inspection.
+    /// The deduction goes to the landlord and the rest of the deposit back to
+    /// the tenant; the property goes back on the market.
+    pub async fn record_inspection(
+        &self,
+        user_id: Uuid,
+        tenancy_id: Uuid,
+        inspection: MoveOutInspectionDto,
+    ) -> Result<Tenancy, ServiceError> {
+        let tenancy = self.get_tenancy(tenancy_id).await?;
+        let property = self.property_service.get_property(tenancy.property_id).await?;
+        if user_id != tenancy.landlord_id && property.agent_id != Some(user_id) {
+            return Err(ServiceError::UnauthorizedTenancyAccess(user_id, tenancy_id));
+        }
+        ensure_transition(&tenancy, TenancyStatus::Ended)?;
+
+        if inspection.deduction > tenancy.caution_deposit {
+            return Err(ServiceError::Validation("The deduction cannot exceed the caution deposit".to_string()));
+        }
+
+        // Ended and settled together, so a failed settlement can be recorded again
+        let mut tx = self.db_client.pool.begin().await?;
+        let tenancy = self.db_client
+            .record_move_out_inspection_tx(tenancy.id, inspection.deduction, &inspection.notes, &mut tx)
+            .await?
+            .ok_or(ServiceError::InvalidTenancyTransition(tenancy.id, tenancy.status, TenancyStatus::Ended))?;
+
+        if let Err(e) = self.settle_deposit(&tenancy, inspection.deduction, &mut tx).await {
+            tracing::error!("Failed to settle caution deposit for tenancy {}: {:?}", tenancy.id, e);
+            return Err(ServiceError::Database(e));
+        }
+        tx.commit().await?;
+
+        if let Err(e) = self.property_service.transition(&property, PropertyStatus::Active).await {
+            tracing::error!("Tenancy {} ended but property {} could not be relisted: {}", tenancy.id, property.id, e);
+        }
+
+        if let Err(e) = self.notification_service
+            .notify_tenancy_ended(tenancy.tenant_id, &property, &tenancy)
+            .await
+        {
+            tracing::warn!("Failed to notify tenant of ended tenancy {}: {}", tenancy.id, e);
+        }
+
+        Ok(tenancy)
+    }
+
+    /// The tenant settles a missed payment by hand after topping up their wallet
+    pub async fn pay_rent(&self, tenant_id: Uuid, payment_id: Uuid) -> Result<RentPayment, ServiceError> {
+        let payment = self.get_rent_payment(payment_id).await?;
+        let tenancy = self.get_tenancy(payment.tenancy_id).await?;
+        if tenancy.tenant_id != tenant_id {
+            return Err(ServiceError::UnauthorizedTenancyAccess(tenant_id, tenancy.id));
+        }
+        if payment.due_date > Utc::now().date_naive() {
+            return Err(ServiceError::Validation("This rent is not due yet".to_string()));
+        }
+
+        self.collect(&payment, &tenancy).await
+    }
+
+    pub async fn receipt(&self, user_id: Uuid, payment_id: Uuid) -> Result<RentReceiptDto, ServiceError> {
+        let payment = self.get_rent_payment(payment_id).await?;
+        let tenancy = self.get_tenancy_for(user_id, payment.tenancy_id).await?;
+        let (Some(receipt_number), Some(paid_at)) = (payment.receipt_number.clone(), payment.paid_at) else {
+            return Err(ServiceError::Validation("This rent has not been paid".to_string()));
+        };
+        let property = self.property_service.get_property(tenancy.property_id).await?;
+
+        Ok(RentReceiptDto {
+            receipt_number,
+            payment_id: payment.id,
+            tenancy_id: tenancy.id,
+            property_title: property.title,
+            property_address: property.address,
+            tenant_name: self.user_name(tenancy.tenant_id).await?,
+            landlord_name: self.user_name(tenancy.landlord_id).await?,
+            period_start: payment.due_date,
+            period_end: period_end(&tenancy, payment.due_date),
+            rent: payment.amount,
+            late_fee: payment.late_fee,
+            total: payment.total_due(),
+            paid_at,
+        })
+    }
+
+    /// Debit every rent payment that has fallen due. Run by the rent collection job.
+    pub async fn collect_due_rent(&self) -> Result<(usize, usize), ServiceError> {
+        let due = self.db_client
+            .get_due_rent_payments(Utc::now().date_naive(), 200)
+            .await?;
+
+        let mut collected = 0;
+        for payment in &due {
+            let tenancy = self.get_tenancy(payment.tenancy_id).await?;
+            match self.collect(payment, &tenancy).await {
+                Ok(_) => collected += 1,
+                Err(e) => tracing::debug!("Rent payment {} not collected: {}", payment.id, e),
+            }
+        }
+        Ok((collected, due.len() - collected))
+    }
+
+    async fn collect_due_for(&self, tenancy: &Tenancy) {
+        let today = Utc::now().date_naive();
+        let payments = match self.db_client.get_rent_payments(tenancy.id).await {
+            Ok(payments) => payments,
+            Err(e) => {
+                tracing::warn!("Failed to load rent schedule for tenancy {}: {:?}", tenancy.id, e);
+                return;
+            }
+        };
+
+        for payment in payments.iter().filter(|payment| payment.due_date <= today) {
+            if let Err(e) = self.collect(payment, tenancy).await {
+                tracing::debug!("Rent payment {} not collected: {}", payment.id, e);
+            }
+        }
+    }
+
+    /// Move one rent payment from the tenant's wallet to the landlord's. The
+    /// claim and both wallet entries commit together, so the payment can never
+    /// be charged twice or half paid; if the wallet cannot cover it nothing
+    /// moves, the attempt is recorded and the tenant told.
+    async fn collect(&self, payment: &RentPayment, tenancy: &Tenancy) -> Result<RentPayment, ServiceError> {
+        let today = Utc::now().date_naive();
+        let late_fee = payment.late_fee.max(tenancy.late_fee_on(payment, today));
+        let total = naira_to_kobo((payment.amount + late_fee) as f64);
+        let already_settled = || ServiceError::Validation("This rent has already been settled".to_string());
+
+        let property = self.property_service.get_property(tenancy.property_id).await?;
+        let description = format!("Rent for {} due {}", property.title, payment.due_date);
+
+        let mut tx = self.db_client.pool.begin().await?;
+        let claimed = self.db_client
+            .claim_rent_payment_tx(payment.id, late_fee, &receipt_number(payment.id, today), &mut tx)
+            .await?
+            .ok_or_else(already_settled)?;
+
+        if let Err(e) = self.db_client.debit_wallet_tx(
+            tenancy.tenant_id,
+            total,
+            TransactionType::ServicePayment,
+            description.clone(),
+            format!("rent_{}", payment.id),
+            None,
+            None,
+            &mut tx,
+        ).await {
+            tx.rollback().await?;
+
+            let status = if late_fee > 0 { RentPaymentStatus::Overdue } else { RentPaymentStatus::Scheduled };
+            let unpaid = self.db_client
+                .record_rent_payment_failure(payment.id, status, late_fee)
+                .await?
+                .ok_or_else(already_settled)?;
+
+            let grace_deadline = payment.due_date + Duration::days(tenancy.grace_period_days as i64);
+            if let Err(notify_error) = self.notification_service
+                .notify_rent_payment_failed(tenancy.tenant_id, &property, &unpaid, grace_deadline)
+                .await
+            {
+                tracing::warn!("Failed to notify tenant of failed rent payment {}: {}", payment.id, notify_error);
+            }
+
+            return Err(match e {
+                sqlx::Error::RowNotFound => ServiceError::InsufficientEscrowFunds {
+                    required: kobo_to_naira(total),
+                    available: self.available_balance(tenancy.tenant_id).await,
+                },
+                _ => ServiceError::Database(e),
+            });
+        }
+
+        if let Err(e) = self.db_client.credit_wallet_tx(
+            tenancy.landlord_id,
+            total,
+            TransactionType::ServicePayment,
+            description,
+            format!("rent_payout_{}", payment.id),
+            None,
+            None,
+            &mut tx,
+        ).await {
+            tracing::error!("Failed to credit landlord for rent payment {}: {:?}", payment.id, e);
+            return Err(ServiceError::Database(e));
+        }
+        tx.commit().await?;
+
+        if let Err(e) = self.notification_service.notify_rent_paid(tenancy.tenant_id, &property, &claimed).await {
+            tracing::warn!("Failed to send rent receipt to tenant for payment {}: {}", claimed.id, e);
+        }
+        if let Err(e) = self.notification_service.notify_rent_received(tenancy.landlord_id, &property, &claimed).await {
+            tracing::warn!("Failed to send rent receipt to landlord for payment {}: {}", claimed.id, e);
+        }
+
+        Ok(claimed)
+    }
+
+    async fn hold_deposit(
+        &self,
+        tenancy: &Tenancy,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<Uuid, ServiceError> {
+        let wallet = self.db_client
+            .get_naira_wallet(tenancy.tenant_id)
+            .await?
+            .ok_or(ServiceError::Validation("The tenant has no Naira wallet for the caution deposit".to_string()))?;
+
+        let hold = self.db_client
+            .create_wallet_hold_tx(
+                wallet.id,
+                None,
+                naira_to_kobo(tenancy.caution_deposit as f64),
+                format!("Caution deposit for tenancy {}", tenancy.id),
+                None,
+                tx,
+            )
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => ServiceError::InsufficientEscrowFunds {
+                    required: tenancy.caution_deposit as f64,
+                    available: kobo_to_naira(wallet.available_balance),
+                },
+                _ => ServiceError::Database(e),
+            })?;
+
+        Ok(hold.id)
+    }
+
+    /// Debits the tenant the deduction, pays it to the landlord and frees the
+    /// rest of the deposit, inside the transaction that ends the tenancy
+    async fn settle_deposit(
+        &self,
+        tenancy: &Tenancy,
+        deduction: i64,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<(), sqlx::Error> {
+        let Some(hold_id) = tenancy.deposit_hold_id else {
+            return Ok(());
+        };
+
+        if deduction == 0 {
+            return self.db_client.release_wallet_hold_tx(hold_id, true, tx).await;
+        }
+
+        // Free the whole deposit, then charge the deduction as a debit so the
+        // tenant's history shows what was kept
+        self.db_client.release_wallet_hold_tx(hold_id, true, tx).await?;
+
+        let deduction = naira_to_kobo(deduction as f64);
+        let description = format!("Caution deposit deduction for tenancy {}", tenancy.id);
+        self.db_client.debit_wallet_tx(
+            tenancy.tenant_id,
+            deduction,
+            TransactionType::ServicePayment,
+            description.clone(),
+            format!("deposit_deduction_{}", tenancy.id),
+            None,
+            None,
+            tx,
+        ).await?;
+
+        self.db_client.credit_wallet_tx(
+            tenancy.landlord_id,
+            deduction,
+            TransactionType::ServicePayment,
+            description,
+            format!("deposit_deduction_payout_{}", tenancy.id),
+            None,
+            None,
+            tx,
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn transition(
+        &self,
+        tenancy: &Tenancy,
+        to: TenancyStatus,
+        note: Option<&str>,
+    ) -> Result<Tenancy, ServiceError> {
+        ensure_transition(tenancy, to)?;
+
+        self.db_client
+            .transition_tenancy_status(tenancy.id, tenancy.status, to, note)
+            .await?
+            .ok_or(ServiceError::InvalidTenancyTransition(tenancy.id, tenancy.status, to))
+    }
+
+    async fn get_rent_payment(&self, payment_id: Uuid) -> Result<RentPayment, ServiceError> {
+        self.db_client
+            .get_rent_payment(payment_id)
+            .await?
+            .ok_or(ServiceError::RentPaymentNotFound(payment_id))
+    }
+
+    async fn user_name(&self, user_id: Uuid) -> Result<String, ServiceError> {
+        Ok(self.db_client
+            .get_user(Some(user_id), None, None, None)
+            .await?
+            .map(|user| user.name)
+            .unwrap_or_default())
+    }
+
+    async fn available_balance(&self, user_id: Uuid) -> f64 {
+        match self.db_client.get_naira_wallet(user_id).await {
+            Ok(Some(wallet)) => kobo_to_naira(wallet.available_balance),
+            _ => 0.0,
+        }
+    }
+}
+
+fn is_party(tenancy: &Tenancy, user_id: Uuid) -> bool {
+    user_id == tenancy.tenant_id || user_id == tenancy.landlord_id
+}
+
+fn ensure_transition(tenancy: &Tenancy, to: TenancyStatus) -> Result<(), ServiceError> {
+    if tenancy.status.can_transition_to(to) {
+        Ok(())
+    } else {
+        Err(ServiceError::InvalidTenancyTransition(tenancy.id, tenancy.status, to))
+    }
+}
+
+/// The last day covered by the rent due on `due_date`
+fn period_end(tenancy: &Tenancy, due_date: NaiveDate) -> NaiveDate {
+    let next = tenancy.rent_schedule()
+        .into_iter()
+        .find(|due| *due > due_date)
+        .unwrap_or(tenancy.end_date);
+    next - Duration::days(1)
+}
+
+/// Listings a tenancy can be offered on
+fn is_rentable(property: &Property) -> bool {
+    matches!(property.listing_type, ListingType::Rent | ListingType::Lease)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::tenancymodels::RentFrequency;
+
+    #[test]
+    fn test_receipt_period_runs_to_the_day_before_next_rent() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let tenancy = Tenancy {
+            id: Uuid::new_v4(),
+            property_id: Uuid::new_v4(),
+            tenant_id: Uuid::new_v4(),
+            landlord_id: Uuid::new_v4(),
+            rent_amount: 150_000,
+            rent_frequency: RentFrequency::Quarterly,
+            caution_deposit: 150_000,
+            start_date: date(2025, 1, 15),
+            end_date: date(2025, 7, 15),
+            grace_period_days: DEFAULT_GRACE_PERIOD_DAYS,
+            late_fee_percent: DEFAULT_LATE_FEE_PERCENT,
+            status: TenancyStatus::Active,
+            message: None,
+            response_note: None,
+            deposit_hold_id: None,
+            deposit_deduction: None,
+            inspection_notes: None,
+            accepted_at: None,
+            move_out_at: None,
+            ended_at: None,
+            created_at: None,
+            updated_at: None,
+        };
+
+        assert_eq!(period_end(&tenancy, date(2025, 1, 15)), date(2025, 4, 14));
+        assert_eq!(period_end(&tenancy, date(2025, 4, 15)), date(2025, 7, 14));
+    }
+}
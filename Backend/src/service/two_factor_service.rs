@@ -0,0 +1,384 @@
+// service/two_factor_service.rs
+use std::future::Future;
+use std::sync::Arc;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::{DateTime, Duration, Utc};
+use rand::Rng;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::{
+    config::Config,
+    db::{db::DBClient, sessiondb::SessionExt, twofactordb::TwoFactorExt, verificationdb::VerificationExt},
+    models::{
+        twofactormodels::{TwoFactorStatus, UserTwoFactor},
+        usermodel::User,
+        verificationmodels::OtpPurpose,
+    },
+    service::{
+        attempt_guard::{AttemptGuard, AttemptKind},
+        error::ServiceError,
+    },
+    utils::{token, totp},
+};
+
+/// How long a second-factor check covers sensitive actions on a session
+pub const STEP_UP_WINDOW_MINUTES: i64 = 10;
+
+/// Wallet transfers at or above this need a recent second-factor check
+pub const LARGE_TRANSFER_NAIRA: f64 = 100_000.0;
+
+pub const TWO_FACTOR_LOGIN_CHALLENGE: &str = "two_factor_login";
+pub const TWO_FACTOR_CHALLENGE_MINUTES: i64 = 5;
+
+const TOTP_ISSUER: &str = "Verinest";
+const RECOVERY_CODE_COUNT: usize = 10;
+
+// No 0/o, 1/l/i, so codes survive being written down
+const RECOVERY_CODE_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+/// What the user scans into their authenticator app
+#[derive(Debug, Serialize)]
+pub struct TwoFactorEnrolment {
+    pub secret: String, // Base32, for typing in by hand
+    pub otpauth_uri: String, // Rendered as a QR code by the client
+}
+
+/// Optional authenticator app two-factor authentication, and the step-up
+/// checks that guard money movement and account changes. A step-up is a
+/// fresh TOTP or an `OtpPurpose::Transaction` code from `OtpService`; it
+/// covers the session it was made on for `STEP_UP_WINDOW_MINUTES`.
+#[derive(Debug)]
+pub struct TwoFactorService {
+    db_client: Arc<DBClient>,
+    secret_key: LessSafeKey,
+    jwt_secret: String,
+    attempt_guard: AttemptGuard,
+}
+
+impl TwoFactorService {
+    pub fn new(db_client: Arc<DBClient>, config: &Config) -> Self {
+        Self {
+            attempt_guard: AttemptGuard::new(&db_client),
+            db_client,
+            secret_key: secret_key(&config.two_factor_encryption_key),
+            jwt_secret: config.jwt_secret.clone(),
+        }
+    }
+
+    pub async fn status(&self, user_id: Uuid) -> Result<TwoFactorStatus, ServiceError> {
+        let enabled_at = self.db_client
+            .get_two_factor(user_id)
+            .await?
+            .and_then(|two_factor| two_factor.enabled_at);
+
+        let recovery_codes_remaining = match enabled_at {
+            Some(_) => self.db_client.count_unused_recovery_codes(user_id).await?,
+            None => 0,
+        };
+
+        Ok(TwoFactorStatus {
+            enabled: enabled_at.is_some(),
+            enabled_at,
+            recovery_codes_remaining,
+        })
+    }
+
+    pub async fn is_enabled(&self, user_id: Uuid) -> Result<bool, ServiceError> {
+        Ok(self.db_client
+            .get_two_factor(user_id)
+            .await?
+            .is_some_and(|two_factor| two_factor.is_enabled()))
+    }
+
+    /// A new secret for the user to add to their app. Nothing changes until
+    /// `enable` confirms a code from it.
+    pub async fn begin_enrolment(&self, user: &User) -> Result<TwoFactorEnrolment, ServiceError> {
+        let secret = totp::generate_secret();
+
+        self.db_client
+            .save_pending_two_factor(user.id, &seal_secret(&self.secret_key, user.id, &secret))
+            .await?
+            .ok_or_else(|| ServiceError::Validation("Two-factor authentication is already enabled".to_string()))?;
+
+        Ok(TwoFactorEnrolment {
+            secret: totp::base32_encode(&secret),
+            otpauth_uri: totp::otpauth_uri(TOTP_ISSUER, &user.email, &secret),
+        })
+    }
+
+    /// Turns two-factor on once the app shows a matching code. Returns the
+    /// recovery codes, which are never shown again.
+    pub async fn enable(&self, user_id: Uuid, code: &str) -> Result<Vec<String>, ServiceError> {
+        let pending = self.db_client
+            .get_two_factor(user_id)
+            .await?
+            .filter(|two_factor| !two_factor.is_enabled())
+            .ok_or_else(|| ServiceError::Validation("Start two-factor enrolment first".to_string()))?;
+
+        let secret = open_secret(&self.secret_key, user_id, &pending.secret_encrypted)?;
+        let step = totp::verify(&secret, code, Utc::now().timestamp())
+            .ok_or(ServiceError::InvalidTwoFactorCode)?;
+
+        let recovery_codes = generate_recovery_codes();
+        let hashes: Vec<String> = recovery_codes.iter().map(|code| hash_recovery_code(code)).collect();
+        self.db_client.enable_two_factor(user_id, step, &hashes).await?;
+
+        tracing::info!("Two-factor authentication enabled for user {}", user_id);
+        Ok(recovery_codes)
+    }
+
+    /// Turns two-factor off; needs a current code or a recovery code
+    pub async fn disable(&self, user_id: Uuid, code: &str) -> Result<(), ServiceError> {
+        self.verify_second_factor(user_id, code).await?;
+        self.db_client.delete_two_factor(user_id).await?;
+
+        tracing::info!("Two-factor authentication disabled for user {}", user_id);
+        Ok(())
+    }
+
+    /// Replaces every recovery code; needs a current authenticator code
+    pub async fn regenerate_recovery_codes(&self, user_id: Uuid, code: &str) -> Result<Vec<String>, ServiceError> {
+        let two_factor = self.enabled(user_id).await?;
+        self.guarded(user_id, self.verify_totp(&two_factor, code)).await?;
+
+        let recovery_codes = generate_recovery_codes();
+        let hashes: Vec<String> = recovery_codes.iter().map(|code| hash_recovery_code(code)).collect();
+        self.db_client.replace_recovery_codes(user_id, &hashes).await?;
+
+        Ok(recovery_codes)
+    }
+
+    /// An authenticator code, or failing that one of the recovery codes
+    pub async fn verify_second_factor(&self, user_id: Uuid, code: &str) -> Result<(), ServiceError> {
+        let two_factor = self.enabled(user_id).await?;
+
+        self.guarded(user_id, async {
+            let code = code.trim();
+            if code.len() == totp::TOTP_DIGITS as usize && code.chars().all(|c| c.is_ascii_digit()) {
+                return self.verify_totp(&two_factor, code).await;
+            }
+
+            if self.db_client.use_recovery_code(user_id, &hash_recovery_code(code)).await? {
+                tracing::warn!("Recovery code used by user {}", user_id);
+                Ok(())
+            } else {
+                Err(ServiceError::InvalidTwoFactorCode)
+            }
+        })
+        .await
+    }
+
+    /// Proof the password was right, traded for a session once the second
+    /// factor checks out
+    pub fn login_challenge(&self, user_id: Uuid) -> Result<String, ServiceError> {
+        token::create_challenge_token(
+            &user_id.to_string(),
+            TWO_FACTOR_LOGIN_CHALLENGE,
+            self.jwt_secret.as_bytes(),
+            TWO_FACTOR_CHALLENGE_MINUTES,
+        )
+        .map_err(|e| ServiceError::Other(e.to_string()))
+    }
+
+    /// The user a login challenge belongs to, once its second factor is checked
+    pub async fn complete_login(&self, challenge_token: &str, code: &str) -> Result<Uuid, ServiceError> {
+        let user_id = token::decode_challenge_token(challenge_token, TWO_FACTOR_LOGIN_CHALLENGE, self.jwt_secret.as_bytes())
+            .ok()
+            .and_then(|sub| Uuid::parse_str(&sub).ok())
+            .ok_or(ServiceError::InvalidTwoFactorCode)?;
+
+        self.verify_second_factor(user_id, code).await?;
+        Ok(user_id)
+    }
+
+    /// Checks a fresh TOTP or emailed code and marks the session stepped up.
+    /// Returns when the step-up lapses.
+    pub async fn step_up(
+        &self,
+        user: &User,
+        session_id: Uuid,
+        totp_code: Option<&str>,
+        email_otp: Option<&str>,
+    ) -> Result<DateTime<Utc>, ServiceError> {
+        match (totp_code, email_otp) {
+            (Some(code), _) => {
+                let two_factor = self.enabled(user.id).await?;
+                self.guarded(user.id, self.verify_totp(&two_factor, code)).await?;
+            }
+            (None, Some(code)) => {
+                self.guarded(user.id, async {
+                    let otp = self.db_client
+                        .get_valid_otp(&user.email, code.trim(), OtpPurpose::Transaction)
+                        .await?
+                        .ok_or(ServiceError::InvalidTwoFactorCode)?;
+                    self.db_client.mark_otp_used(otp.id).await?;
+                    Ok(())
+                })
+                .await?;
+            }
+            (None, None) => return Err(ServiceError::StepUpRequired),
+        }
+
+        self.db_client.mark_session_stepped_up(session_id).await?;
+        Ok(Utc::now() + Duration::minutes(STEP_UP_WINDOW_MINUTES))
+    }
+
+    /// Lets a sensitive action through if the session stepped up recently, or
+    /// if a code came with the request
+    pub async fn require_step_up(
+        &self,
+        user: &User,
+        session_id: Uuid,
+        totp_code: Option<&str>,
+        email_otp: Option<&str>,
+    ) -> Result<(), ServiceError> {
+        if totp_code.is_some() || email_otp.is_some() {
+            self.step_up(user, session_id, totp_code, email_otp).await?;
+            return Ok(());
+        }
+
+        let stepped_up_at = self.db_client
+            .get_session(session_id)
+            .await?
+            .filter(|session| session.user_id == user.id)
+            .and_then(|session| session.stepped_up_at);
+
+        match stepped_up_at {
+            Some(at) if at + Duration::minutes(STEP_UP_WINDOW_MINUTES) > Utc::now() => Ok(()),
+            _ => Err(ServiceError::StepUpRequired),
+        }
+    }
+
+    /// Runs a code check under the user's OTP attempt limits
+    async fn guarded(
+        &self,
+        user_id: Uuid,
+        check: impl Future<Output = Result<(), ServiceError>>,
+    ) -> Result<(), ServiceError> {
+        let account = user_id.to_string();
+        self.attempt_guard.check(AttemptKind::Otp, &account, None).await?;
+
+        match check.await {
+            Ok(()) => {
+                self.attempt_guard.record_success(AttemptKind::Otp, &account).await;
+                Ok(())
+            }
+            Err(ServiceError::InvalidTwoFactorCode) => {
+                self.attempt_guard.record_failure(AttemptKind::Otp, &account, None).await;
+                Err(ServiceError::InvalidTwoFactorCode)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn enabled(&self, user_id: Uuid) -> Result<UserTwoFactor, ServiceError> {
+        self.db_client
+            .get_two_factor(user_id)
+            .await?
+            .filter(UserTwoFactor::is_enabled)
+            .ok_or_else(|| ServiceError::Validation("Two-factor authentication is not enabled".to_string()))
+    }
+
+    async fn verify_totp(&self, two_factor: &UserTwoFactor, code: &str) -> Result<(), ServiceError> {
+        let secret = open_secret(&self.secret_key, two_factor.user_id, &two_factor.secret_encrypted)?;
+        let step = totp::verify(&secret, code, Utc::now().timestamp())
+            .ok_or(ServiceError::InvalidTwoFactorCode)?;
+
+        // A code already used (or an older one) is refused
+        if !self.db_client.record_totp_step(two_factor.user_id, step).await? {
+            return Err(ServiceError::InvalidTwoFactorCode);
+        }
+
+        Ok(())
+    }
+}
+
+fn secret_key(configured: &str) -> LessSafeKey {
+    let key = Sha256::digest(configured.as_bytes());
+    LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &key).expect("SHA-256 output is a valid AES-256 key"))
+}
+
+/// Encrypts a TOTP secret, bound to its user so rows cannot be swapped
+fn seal_secret(key: &LessSafeKey, user_id: Uuid, secret: &[u8]) -> String {
+    let nonce = rand::random::<[u8; NONCE_LEN]>();
+    let mut sealed = secret.to_vec();
+    key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(user_id.as_bytes()), &mut sealed)
+        .expect("TOTP secrets are far below the AES-GCM size limit");
+
+    BASE64.encode([nonce.as_slice(), &sealed].concat())
+}
+
+fn open_secret(key: &LessSafeKey, user_id: Uuid, sealed: &str) -> Result<Vec<u8>, ServiceError> {
+    let undecryptable = || ServiceError::Other(format!("Two-factor secret for user {} cannot be decrypted", user_id));
+
+    let mut sealed = BASE64.decode(sealed).map_err(|_| undecryptable())?;
+    if sealed.len() < NONCE_LEN {
+        return Err(undecryptable());
+    }
+    let mut ciphertext = sealed.split_off(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(&sealed).map_err(|_| undecryptable())?;
+
+    let secret = key
+        .open_in_place(nonce, Aad::from(user_id.as_bytes()), &mut ciphertext)
+        .map_err(|_| undecryptable())?;
+
+    Ok(secret.to_vec())
+}
+
+fn generate_recovery_codes() -> Vec<String> {
+    let mut rng = rand::rng();
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let code: String = (0..10)
+                .map(|_| RECOVERY_CODE_ALPHABET[rng.random_range(0..RECOVERY_CODE_ALPHABET.len())] as char)
+                .collect();
+            format!("{}-{}", &code[..5], &code[5..])
+        })
+        .collect()
+}
+
+/// Case, dashes and spaces are ignored, as codes get copied by hand
+fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+
+    hex::encode(Sha256::digest(normalized.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secrets_are_sealed_to_their_user() {
+        let key = secret_key("test-key");
+        let user_id = Uuid::new_v4();
+        let secret = totp::generate_secret();
+
+        let sealed = seal_secret(&key, user_id, &secret);
+        assert_ne!(sealed, seal_secret(&key, user_id, &secret));
+        assert_eq!(open_secret(&key, user_id, &sealed).unwrap(), secret);
+        assert!(open_secret(&key, Uuid::new_v4(), &sealed).is_err());
+        assert!(open_secret(&secret_key("other-key"), user_id, &sealed).is_err());
+    }
+
+    #[test]
+    fn test_recovery_codes_are_unique_and_forgiving_to_type() {
+        let codes = generate_recovery_codes();
+
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        assert!(codes.iter().all(|code| code.len() == 11 && code.as_bytes()[5] == b'-'));
+        let unique: std::collections::HashSet<_> = codes.iter().collect();
+        assert_eq!(unique.len(), codes.len());
+
+        assert_eq!(hash_recovery_code("abcde-fgh23"), hash_recovery_code(" ABCDE FGH23 "));
+        assert_ne!(hash_recovery_code("abcde-fgh23"), hash_recovery_code("abcde-fgh24"));
+    }
+}
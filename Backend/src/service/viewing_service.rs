@@ -0,0 +1,583 @@
+// src/service/viewing_service.rs
+use std::sync::Arc;
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        db::DBClient, naira_walletdb::NairaWalletExt, propertydb::PropertyExt,
+        userdb::UserExt, viewingdb::ViewingExt,
+    },
+    dtos::viewingdtos::{ConfirmViewingDto, ReportNoShowDto, RequestViewingDto},
+    models::{
+        propertymodel::{Property, PropertyStatus},
+        viewingmodels::{NoShowParty, PropertyViewing, ViewingStatus},
+        walletmodels::{kobo_to_naira, naira_to_kobo, TransactionType},
+    },
+    service::{error::ServiceError, notification_service::NotificationService, trust_service::TrustService},
+};
+
+/// Trust points lost for missing a confirmed viewing
+pub const NO_SHOW_PENALTY: i32 = 5;
+/// How far ahead of a viewing both sides are reminded
+pub const REMINDER_WINDOW_HOURS: i64 = 24;
+/// A confirmed viewing nobody reports on is treated as attended after this long
+pub const AUTO_COMPLETE_AFTER_HOURS: i64 = 48;
+
+/// Which side of a viewing a user is on. The host is the landlord or the
+/// property's assigned agent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ViewingParty {
+    Requester,
+    Host,
+}
+
+/// Where a held inspection fee goes when a viewing closes
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FeeSettlement {
+    /// To the agent, or the landlord without one
+    Pay,
+    /// Back to the requester's available balance
+    Refund,
+}
+
+#[derive(Debug)]
+pub struct ViewingService {
+    db_client: Arc<DBClient>,
+    notification_service: Arc<NotificationService>,
+    trust_service: Arc<TrustService>,
+}
+
+impl ViewingService {
+    pub fn new(
+        db_client: Arc<DBClient>,
+        notification_service: Arc<NotificationService>,
+        trust_service: Arc<TrustService>,
+    ) -> Self {
+        Self {
+            db_client,
+            notification_service,
+            trust_service,
+        }
+    }
+
+    /// Book a viewing of a live listing. If the property charges an inspection
+    /// fee it is held in the requester's wallet until the viewing is settled.
+    pub async fn request_viewing(
+        &self,
+        requester_id: Uuid,
+        request: RequestViewingDto,
+    ) -> Result<PropertyViewing, ServiceError> {
+        let property = self.get_property(request.property_id).await?;
+        if property.status != PropertyStatus::Active {
+            return Err(ServiceError::Validation("Viewings can only be booked for active listings".to_string()));
+        }
+        if party_of(&property, requester_id, requester_id) == Some(ViewingParty::Host) {
+            return Err(ServiceError::Validation("You cannot book a viewing of your own listing".to_string()));
+        }
+
+        let fee = property.inspection_fee.unwrap_or(0);
+        let fee_hold_id = if fee > 0 {
+            Some(self.hold_inspection_fee(requester_id, &property, fee).await?)
+        } else {
+            None
+        };
+
+        let viewing = match self.db_client
+            .create_viewing(
+                property.id,
+                requester_id,
+                &request.proposed_slots,
+                request.message.as_deref(),
+                fee,
+                fee_hold_id,
+            )
+            .await
+        {
+            Ok(viewing) => viewing,
+            Err(e) => {
+                if let Some(hold_id) = fee_hold_id {
+                    if let Err(refund_error) = self.db_client.release_wallet_hold(hold_id, true).await {
+                        tracing::error!("Failed to return inspection fee hold {}: {:?}", hold_id, refund_error);
+                    }
+                }
+                return Err(match &e {
+                    sqlx::Error::Database(db_error) if db_error.is_unique_violation() => {
+                        ServiceError::Validation("You already have an open viewing request for this property".to_string())
+                    }
+                    _ => ServiceError::Database(e),
+                });
+            }
+        };
+
+        let requester_name = self.db_client
+            .get_user(Some(requester_id), None, None, None)
+            .await?
+            .map(|user| user.name)
+            .unwrap_or_else(|| "A prospective tenant".to_string());
+
+        for host_id in hosts(&property) {
+            if let Err(e) = self.notification_service
+                .notify_viewing_requested(host_id, &property, &viewing, &requester_name)
+                .await
+            {
+                tracing::warn!("Failed to notify {} of viewing {}: {}", host_id, viewing.id, e);
+            }
+        }
+
+        Ok(viewing)
+    }
+
+    pub async fn get_viewing(&self, viewing_id: Uuid) -> Result<PropertyViewing, ServiceError> {
+        self.db_client
+            .get_viewing(viewing_id)
+            .await?
+            .ok_or(ServiceError::ViewingNotFound(viewing_id))
+    }
+
+    /// A viewing as seen by one of its parties
+    pub async fn get_viewing_for(&self, user_id: Uuid, viewing_id: Uuid) -> Result<PropertyViewing, ServiceError> {
+        let (viewing, _) = self.load_as(user_id, viewing_id, None).await?;
+        Ok(viewing)
+    }
+
+    /// Landlord or agent accepts one of the proposed slots
+    pub async fn confirm(
+        &self,
+        user_id: Uuid,
+        viewing_id: Uuid,
+        confirmation: ConfirmViewingDto,
+    ) -> Result<PropertyViewing, ServiceError> {
+        let (viewing, property) = self.load_as(user_id, viewing_id, Some(ViewingParty::Host)).await?;
+        ensure_transition(&viewing, ViewingStatus::Confirmed)?;
+
+        if !viewing.proposed_slots().contains(&confirmation.scheduled_at) {
+            return Err(ServiceError::Validation("Pick one of the proposed viewing times".to_string()));
+        }
+        if confirmation.scheduled_at <= Utc::now() {
+            return Err(ServiceError::Validation("That viewing time has already passed".to_string()));
+        }
+
+        let viewing = self.db_client
+            .confirm_viewing(viewing.id, user_id, confirmation.scheduled_at, confirmation.note.as_deref())
+            .await?
+            .ok_or(ServiceError::InvalidViewingTransition(viewing.id, viewing.status, ViewingStatus::Confirmed))?;
+
+        if let Err(e) = self.notification_service
+            .notify_viewing_confirmed(viewing.requester_id, &property, &viewing)
+            .await
+        {
+            tracing::warn!("Failed to notify requester of confirmed viewing {}: {}", viewing.id, e);
+        }
+
+        Ok(viewing)
+    }
+
+    pub async fn decline(
+        &self,
+        user_id: Uuid,
+        viewing_id: Uuid,
+        reason: &str,
+    ) -> Result<PropertyViewing, ServiceError> {
+        let (viewing, property) = self.load_as(user_id, viewing_id, Some(ViewingParty::Host)).await?;
+        let viewing = self
+            .close(&viewing, &property, ViewingStatus::Declined, Some(reason), None, FeeSettlement::Refund)
+            .await?;
+
+        if let Err(e) = self.notification_service
+            .notify_viewing_declined(viewing.requester_id, &property, &viewing, reason)
+            .await
+        {
+            tracing::warn!("Failed to notify requester of declined viewing {}: {}", viewing.id, e);
+        }
+
+        Ok(viewing)
+    }
+
+    /// Either side can call a viewing off before it happens; any fee is returned
+    pub async fn cancel(
+        &self,
+        user_id: Uuid,
+        viewing_id: Uuid,
+        reason: &str,
+    ) -> Result<PropertyViewing, ServiceError> {
+        let (viewing, property) = self.load_as(user_id, viewing_id, None).await?;
+        let cancelled_by = party_of(&property, viewing.requester_id, user_id);
+        let viewing = self
+            .close(&viewing, &property, ViewingStatus::Cancelled, Some(reason), None, FeeSettlement::Refund)
+            .await?;
+
+        let canceller_name = self.db_client
+            .get_user(Some(user_id), None, None, None)
+            .await?
+            .map(|user| user.name)
+            .unwrap_or_default();
+        let recipients = match cancelled_by {
+            Some(ViewingParty::Requester) => viewing.host_id.map(|host_id| vec![host_id]).unwrap_or_else(|| hosts(&property)),
+            _ => vec![viewing.requester_id],
+        };
+
+        for recipient in recipients {
+            if let Err(e) = self.notification_service
+                .notify_viewing_cancelled(recipient, &property, &viewing, &canceller_name, reason)
+                .await
+            {
+                tracing::warn!("Failed to notify {} of cancelled viewing {}: {}", recipient, viewing.id, e);
+            }
+        }
+
+        Ok(viewing)
+    }
+
+    /// The requester confirms the viewing took place, releasing the fee to the agent
+    pub async fn mark_attended(
+        &self,
+        user_id: Uuid,
+        viewing_id: Uuid,
+    ) -> Result<PropertyViewing, ServiceError> {
+        let (viewing, property) = self.load_as(user_id, viewing_id, Some(ViewingParty::Requester)).await?;
+        ensure_started(&viewing)?;
+
+        self.complete(&viewing, &property).await
+    }
+
+    /// One side reports that the other did not turn up. The absent party loses
+    /// trust points; the fee goes to the agent if the requester stayed away and
+    /// back to the requester if the host did.
+    pub async fn report_no_show(
+        &self,
+        user_id: Uuid,
+        viewing_id: Uuid,
+        report: ReportNoShowDto,
+    ) -> Result<PropertyViewing, ServiceError> {
+        let (viewing, property) = self.load_as(user_id, viewing_id, None).await?;
+        let reporter = party_of(&property, viewing.requester_id, user_id);
+        let absent_side = match report.absent_party {
+            NoShowParty::Requester => ViewingParty::Requester,
+            NoShowParty::Host => ViewingParty::Host,
+        };
+        if reporter == Some(absent_side) {
+            return Err(ServiceError::Validation("You can only report the other party as absent".to_string()));
+        }
+        ensure_started(&viewing)?;
+
+        // A requester who stayed away still pays; a host who did gets nothing
+        let (fee, absent_user) = match report.absent_party {
+            NoShowParty::Requester => (FeeSettlement::Pay, viewing.requester_id),
+            NoShowParty::Host => (FeeSettlement::Refund, viewing.host_id.unwrap_or(property.landlord_id)),
+        };
+        let viewing = self
+            .close(&viewing, &property, ViewingStatus::NoShow, report.note.as_deref(), Some(report.absent_party), fee)
+            .await?;
+
+        self.trust_service
+            .deduct_trust_points(absent_user, NO_SHOW_PENALTY, format!("Missed property viewing {}", viewing.id))
+            .await?;
+
+        if let Err(e) = self.notification_service
+            .notify_viewing_no_show(absent_user, &property, &viewing, NO_SHOW_PENALTY)
+            .await
+        {
+            tracing::warn!("Failed to notify {} of missed viewing {}: {}", absent_user, viewing.id, e);
+        }
+
+        Ok(viewing)
+    }
+
+    pub async fn send_reminder(&self, viewing: &PropertyViewing) -> Result<(), ServiceError> {
+        let property = self.get_property(viewing.property_id).await?;
+        let host_id = viewing.host_id.unwrap_or(property.landlord_id);
+
+        for user_id in [viewing.requester_id, host_id] {
+            if let Err(e) = self.notification_service
+                .notify_viewing_reminder(user_id, &property, viewing)
+                .await
+            {
+                tracing::warn!("Failed to remind {} of viewing {}: {}", user_id, viewing.id, e);
+            }
+        }
+
+        self.db_client.mark_viewing_reminded(viewing.id).await?;
+        Ok(())
+    }
+
+    /// Close a confirmed viewing nobody reported on, as if the requester attended
+    pub async fn auto_complete(&self, viewing: &PropertyViewing) -> Result<PropertyViewing, ServiceError> {
+        let property = self.get_property(viewing.property_id).await?;
+        self.complete(viewing, &property).await
+    }
+
+    pub async fn send_due_reminders(&self) -> Result<usize, ServiceError> {
+        let due = self.db_client
+            .get_viewings_due_reminder(Utc::now() + Duration::hours(REMINDER_WINDOW_HOURS))
+            .await?;
+
+        for viewing in &due {
+            if let Err(e) = self.send_reminder(viewing).await {
+                tracing::warn!("Failed to send reminder for viewing {}: {}", viewing.id, e);
+            }
+        }
+        Ok(due.len())
+    }
+
+    pub async fn complete_overdue(&self) -> Result<usize, ServiceError> {
+        let overdue = self.db_client
+            .get_viewings_to_auto_complete(Utc::now() - Duration::hours(AUTO_COMPLETE_AFTER_HOURS))
+            .await?;
+
+        let mut completed = 0;
+        for viewing in &overdue {
+            match self.auto_complete(viewing).await {
+                Ok(_) => completed += 1,
+                Err(e) => tracing::warn!("Failed to auto-complete viewing {}: {}", viewing.id, e),
+            }
+        }
+        Ok(completed)
+    }
+
+    async fn complete(&self, viewing: &PropertyViewing, property: &Property) -> Result<PropertyViewing, ServiceError> {
+        self.close(viewing, property, ViewingStatus::Completed, None, None, FeeSettlement::Pay).await
+    }
+
+    /// Moves the viewing to a closed status and settles its held fee in one
+    /// transaction, so a failed payout leaves the viewing as it was to retry
+    async fn close(
+        &self,
+        viewing: &PropertyViewing,
+        property: &Property,
+        to: ViewingStatus,
+        note: Option<&str>,
+        no_show_party: Option<NoShowParty>,
+        fee: FeeSettlement,
+    ) -> Result<PropertyViewing, ServiceError> {
+        ensure_transition(viewing, to)?;
+
+        let mut tx = self.db_client.pool.begin().await?;
+        let closed = self.db_client
+            .transition_viewing_status_tx(viewing.id, viewing.status, to, note, no_show_party, &mut tx)
+            .await?
+            .ok_or(ServiceError::InvalidViewingTransition(viewing.id, viewing.status, to))?;
+
+        if let Err(e) = self.settle_fee(&closed, property, fee, &mut tx).await {
+            tracing::error!("Failed to settle inspection fee for viewing {}: {:?}", viewing.id, e);
+            return Err(ServiceError::Database(e));
+        }
+        tx.commit().await?;
+
+        if fee == FeeSettlement::Pay && closed.fee_hold_id.is_some() {
+            let recipient = fee_recipient(property);
+            if let Err(e) = self.notification_service
+                .notify_inspection_fee_released(recipient, property, &closed)
+                .await
+            {
+                tracing::warn!("Failed to notify {} of inspection fee for viewing {}: {}", recipient, viewing.id, e);
+            }
+        }
+
+        Ok(closed)
+    }
+
+    async fn get_property(&self, property_id: Uuid) -> Result<Property, ServiceError> {
+        self.db_client
+            .get_property_by_id(property_id)
+            .await?
+            .ok_or(ServiceError::PropertyNotFound(property_id))
+    }
+
+    /// Load a viewing and its property, checking the user is on the expected side
+    async fn load_as(
+        &self,
+        user_id: Uuid,
+        viewing_id: Uuid,
+        required: Option<ViewingParty>,
+    ) -> Result<(PropertyViewing, Property), ServiceError> {
+        let viewing = self.get_viewing(viewing_id).await?;
+        let property = self.get_property(viewing.property_id).await?;
+
+        match (party_of(&property, viewing.requester_id, user_id), required) {
+            (None, _) => Err(ServiceError::UnauthorizedViewingAccess(user_id, viewing_id)),
+            (Some(party), Some(expected)) if party != expected => {
+                Err(ServiceError::UnauthorizedViewingAccess(user_id, viewing_id))
+            }
+            _ => Ok((viewing, property)),
+        }
+    }
+
+    async fn hold_inspection_fee(
+        &self,
+        requester_id: Uuid,
+        property: &Property,
+        fee: i64,
+    ) -> Result<Uuid, ServiceError> {
+        let wallet = self.db_client
+            .get_naira_wallet(requester_id)
+            .await?
+            .ok_or(ServiceError::Validation("Create a Naira wallet to pay the inspection fee".to_string()))?;
+
+        let hold = self.db_client
+            .create_wallet_hold(
+                wallet.id,
+                None,
+                naira_to_kobo(fee as f64),
+                format!("Inspection fee for viewing of property {}", property.id),
+                None,
+            )
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => ServiceError::InsufficientEscrowFunds {
+                    required: fee as f64,
+                    available: kobo_to_naira(wallet.available_balance),
+                },
+                _ => ServiceError::Database(e),
+            })?;
+
+        Ok(hold.id)
+    }
+
+    /// Release the held fee back to the requester, or take it from them and
+    /// credit the agent (or the landlord when no agent is assigned)
+    async fn settle_fee(
+        &self,
+        viewing: &PropertyViewing,
+        property: &Property,
+        fee: FeeSettlement,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<(), sqlx::Error> {
+        let Some(hold_id) = viewing.fee_hold_id else {
+            return Ok(());
+        };
+
+        if fee == FeeSettlement::Refund {
+            return self.db_client.release_wallet_hold_tx(hold_id, true, tx).await;
+        }
+
+        self.db_client.release_wallet_hold_tx(hold_id, false, tx).await?;
+        self.db_client.credit_wallet_tx(
+            fee_recipient(property),
+            naira_to_kobo(viewing.inspection_fee as f64),
+            TransactionType::ServicePayment,
+            format!("Inspection fee for viewing of {}", property.title),
+            format!("viewing_fee_{}", viewing.id),
+            None,
+            None,
+            tx,
+        ).await?;
+
+        Ok(())
+    }
+}
+
+/// Which side of the viewing `user_id` is on, if either
+pub fn party_of(property: &Property, requester_id: Uuid, user_id: Uuid) -> Option<ViewingParty> {
+    if user_id == property.landlord_id || property.agent_id == Some(user_id) {
+        Some(ViewingParty::Host)
+    } else if user_id == requester_id {
+        Some(ViewingParty::Requester)
+    } else {
+        None
+    }
+}
+
+/// The agent shows the property and earns the fee; landlords without an agent show it themselves
+pub fn fee_recipient(property: &Property) -> Uuid {
+    property.agent_id.unwrap_or(property.landlord_id)
+}
+
+fn hosts(property: &Property) -> Vec<Uuid> {
+    std::iter::once(property.landlord_id).chain(property.agent_id).collect()
+}
+
+fn ensure_transition(viewing: &PropertyViewing, to: ViewingStatus) -> Result<(), ServiceError> {
+    if viewing.status.can_transition_to(to) {
+        Ok(())
+    } else {
+        Err(ServiceError::InvalidViewingTransition(viewing.id, viewing.status, to))
+    }
+}
+
+/// Attendance can only be settled once the viewing time has come
+fn ensure_started(viewing: &PropertyViewing) -> Result<(), ServiceError> {
+    match viewing.scheduled_at {
+        Some(scheduled_at) if viewing.status == ViewingStatus::Confirmed && scheduled_at <= Utc::now() => Ok(()),
+        Some(_) if viewing.status == ViewingStatus::Confirmed => {
+            Err(ServiceError::Validation("The viewing has not taken place yet".to_string()))
+        }
+        _ => Err(ServiceError::InvalidViewingTransition(viewing.id, viewing.status, ViewingStatus::Completed)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::propertymodel::{CurrencyType, ListingType, PropertyType};
+
+    fn property(agent_id: Option<Uuid>) -> Property {
+        Property {
+            id: Uuid::new_v4(),
+            landlord_id: Uuid::new_v4(),
+            agent_id,
+            lawyer_id: None,
+            title: "Three bedroom flat".to_string(),
+            description: "A three bedroom flat".to_string(),
+            property_type: PropertyType::Apartment,
+            listing_type: ListingType::Rent,
+            address: "12 Herbert Macaulay Way".to_string(),
+            city: "Lagos".to_string(),
+            state: "Lagos".to_string(),
+            lga: "Yaba".to_string(),
+            country: "Nigeria".to_string(),
+            latitude: None,
+            longitude: None,
+            landmark: None,
+            bedrooms: Some(3),
+            bathrooms: Some(2),
+            toilets: None,
+            size_sqm: None,
+            plot_size: None,
+            price: 2_500_000,
+            currency: CurrencyType::Naira,
+            price_negotiable: Some(false),
+            bidding_price: None,
+            inspection_fee: Some(5_000),
+            amenities: None,
+            features: None,
+            certificate_of_occupancy: None,
+            deed_of_agreement: None,
+            survey_plan: None,
+            building_plan_approval: None,
+            property_photos: serde_json::json!([]),
+            agent_verification_photos: None,
+            agent_verification_notes: None,
+            lawyer_verification_notes: None,
+            property_hash: String::new(),
+            coordinates_hash: String::new(),
+            status: PropertyStatus::Active,
+            agent_verified_at: None,
+            lawyer_verified_at: None,
+            listed_at: None,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn test_landlord_and_agent_host_the_viewing() {
+        let agent_id = Uuid::new_v4();
+        let requester_id = Uuid::new_v4();
+        let property = property(Some(agent_id));
+
+        assert_eq!(party_of(&property, requester_id, property.landlord_id), Some(ViewingParty::Host));
+        assert_eq!(party_of(&property, requester_id, agent_id), Some(ViewingParty::Host));
+        assert_eq!(party_of(&property, requester_id, requester_id), Some(ViewingParty::Requester));
+        assert_eq!(party_of(&property, requester_id, Uuid::new_v4()), None);
+    }
+
+    #[test]
+    fn test_inspection_fee_goes_to_agent_when_assigned() {
+        let agent_id = Uuid::new_v4();
+        assert_eq!(fee_recipient(&property(Some(agent_id))), agent_id);
+
+        let unassigned = property(None);
+        assert_eq!(fee_recipient(&unassigned), unassigned.landlord_id);
+    }
+}
@@ -0,0 +1,169 @@
+// utils/certificate.rs
+//! Ed25519 signing for property verification certificates.
+//!
+//! Certificates are signed with a server key so anyone holding the public key
+//! (published at `GET /api/verify/keys`) can check one without trusting the
+//! API that served it. Each key is known by a short id derived from its public
+//! key; retired keys stay listed so certificates they signed still verify.
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CertificateKeyError {
+    #[error("CERTIFICATE_SIGNING_KEY must be set; without it issued certificates stop verifying")]
+    Missing,
+    #[error("Certificate signing key must be base64: {0}")]
+    Encoding(String),
+    #[error("Certificate signing key must be a 32 byte Ed25519 seed")]
+    InvalidSeed,
+    #[error("Certificate public key must be 32 bytes")]
+    InvalidPublicKey,
+}
+
+/// A public key certificates can be checked against
+#[derive(Debug, Clone, Serialize)]
+pub struct PublicKeyInfo {
+    pub key_id: String,
+    pub algorithm: &'static str,
+    pub public_key: String, // Base64
+    pub current: bool,      // Signs new certificates
+}
+
+#[derive(Debug)]
+pub struct CertificateSigner {
+    key_pair: Ed25519KeyPair,
+    key_id: String,
+    retired: Vec<(String, Vec<u8>)>,
+}
+
+impl CertificateSigner {
+    pub fn from_seed(seed: &[u8]) -> Result<Self, CertificateKeyError> {
+        let key_pair = Ed25519KeyPair::from_seed_unchecked(seed)
+            .map_err(|_| CertificateKeyError::InvalidSeed)?;
+        let key_id = key_id(key_pair.public_key().as_ref());
+
+        Ok(Self { key_pair, key_id, retired: Vec::new() })
+    }
+
+    /// The configured key and retired public keys. A missing key is an error:
+    /// a throwaway one would leave every issued certificate unverifiable after
+    /// a restart or on another instance.
+    pub fn from_config(signing_key: Option<&str>, retired_public_keys: &[String]) -> Result<Self, CertificateKeyError> {
+        let seed = signing_key.ok_or(CertificateKeyError::Missing)?;
+        let mut signer = Self::from_seed(
+            &BASE64.decode(seed.trim()).map_err(|e| CertificateKeyError::Encoding(e.to_string()))?,
+        )?;
+
+        for public_key in retired_public_keys {
+            let public_key = BASE64.decode(public_key)
+                .map_err(|e| CertificateKeyError::Encoding(e.to_string()))?;
+            if public_key.len() != 32 {
+                return Err(CertificateKeyError::InvalidPublicKey);
+            }
+            signer.retired.push((key_id(&public_key), public_key));
+        }
+
+        Ok(signer)
+    }
+
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    /// Base64 signature over the payload text
+    pub fn sign(&self, payload: &str) -> String {
+        BASE64.encode(self.key_pair.sign(payload.as_bytes()).as_ref())
+    }
+
+    /// Whether `signature` is a valid signature over `payload` by the key
+    /// `key_id`. Unknown keys and malformed signatures are simply invalid.
+    pub fn verify(&self, key_id: &str, payload: &str, signature: &str) -> bool {
+        let Some(public_key) = self.public_key(key_id) else {
+            return false;
+        };
+        let Ok(signature) = BASE64.decode(signature) else {
+            return false;
+        };
+
+        UnparsedPublicKey::new(&ED25519, public_key)
+            .verify(payload.as_bytes(), &signature)
+            .is_ok()
+    }
+
+    pub fn public_keys(&self) -> Vec<PublicKeyInfo> {
+        let current = PublicKeyInfo {
+            key_id: self.key_id.clone(),
+            algorithm: "Ed25519",
+            public_key: BASE64.encode(self.key_pair.public_key().as_ref()),
+            current: true,
+        };
+
+        std::iter::once(current)
+            .chain(self.retired.iter().map(|(key_id, public_key)| PublicKeyInfo {
+                key_id: key_id.clone(),
+                algorithm: "Ed25519",
+                public_key: BASE64.encode(public_key),
+                current: false,
+            }))
+            .collect()
+    }
+
+    fn public_key(&self, key_id: &str) -> Option<&[u8]> {
+        if key_id == self.key_id {
+            return Some(self.key_pair.public_key().as_ref());
+        }
+        self.retired
+            .iter()
+            .find(|(retired_id, _)| retired_id == key_id)
+            .map(|(_, public_key)| public_key.as_slice())
+    }
+}
+
+/// First 8 bytes of the public key's SHA-256, in hex
+fn key_id(public_key: &[u8]) -> String {
+    hex::encode(&Sha256::digest(public_key)[..8])
+}
+
+/// Hex SHA-256 of a document as it was reviewed
+pub fn document_hash(contents: &[u8]) -> String {
+    hex::encode(Sha256::digest(contents))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signatures_verify_only_untampered_payloads() {
+        let old = CertificateSigner::from_seed(&[3u8; 32]).unwrap();
+        let old_public_key = old.public_keys()[0].public_key.clone();
+        let old_signature = old.sign(r#"{"reference":"VN-APTLAGYA26ABC123-7"}"#);
+
+        let signer = CertificateSigner::from_config(
+            Some(&BASE64.encode([7u8; 32])),
+            &[old_public_key],
+        )
+        .unwrap();
+        let payload = r#"{"reference":"VN-APTLAGYA26ABC123-7","documents":[]}"#;
+        let signature = signer.sign(payload);
+
+        assert!(signer.verify(signer.key_id(), payload, &signature));
+        assert!(!signer.verify(signer.key_id(), &payload.replace("ABC", "ABD"), &signature));
+        assert!(!signer.verify("0000000000000000", payload, &signature));
+        assert!(!signer.verify(signer.key_id(), payload, "not base64!"));
+
+        // Certificates signed before a key rotation still check out
+        assert!(signer.verify(old.key_id(), r#"{"reference":"VN-APTLAGYA26ABC123-7"}"#, &old_signature));
+
+        // The same seed always gives the same key
+        let again = CertificateSigner::from_seed(&[7u8; 32]).unwrap();
+        assert_eq!(again.key_id(), signer.key_id());
+        assert!(CertificateSigner::from_seed(&[7u8; 16]).is_err());
+
+        // No configured key means no signer, not a throwaway one
+        assert!(matches!(CertificateSigner::from_config(None, &[]), Err(CertificateKeyError::Missing)));
+    }
+}
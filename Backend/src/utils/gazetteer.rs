@@ -0,0 +1,544 @@
+// utils/gazetteer.rs
+//! Gazetteer of Nigerian states, local government areas and major towns.
+//!
+//! The data lives in `data/nigeria_gazetteer.json` and is compiled into the
+//! binary, so location checks and approximate coordinates never need an
+//! external service. Coordinates are centroids (state) or headquarters (LGA,
+//! town) and are only good to a few kilometres.
+//!
+//! Lookups are forgiving about how people type places: case, punctuation,
+//! "State"/"LGA" suffixes and the common alternative spellings listed as
+//! aliases all resolve to the same canonical name.
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use validator::ValidationError;
+
+/// How far a pin may sit from the nearest LGA headquarters of its state
+pub const STATE_RADIUS_KM: f64 = 75.0;
+
+#[derive(Debug, Deserialize)]
+struct Source {
+    states: Vec<State>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct State {
+    pub name: String,
+    pub code: String,
+    pub capital: String,
+    pub lat: f64,
+    pub lng: f64,
+    pub aliases: Vec<String>,
+    #[serde(skip_serializing)]
+    pub lgas: Vec<Lga>,
+    #[serde(skip_serializing)]
+    pub towns: Vec<Town>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "LgaRow")]
+pub struct Lga {
+    pub name: String,
+    pub lat: f64,
+    pub lng: f64,
+    pub aliases: Vec<String>,
+}
+
+/// LGAs are stored as `[name, lat, lng]`, with a trailing alias list where
+/// the official spelling is not the one people use
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LgaRow {
+    Plain(String, f64, f64),
+    Aliased(String, f64, f64, Vec<String>),
+}
+
+impl From<LgaRow> for Lga {
+    fn from(row: LgaRow) -> Self {
+        let (name, lat, lng, aliases) = match row {
+            LgaRow::Plain(name, lat, lng) => (name, lat, lng, Vec::new()),
+            LgaRow::Aliased(name, lat, lng, aliases) => (name, lat, lng, aliases),
+        };
+        Lga { name, lat, lng, aliases }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "(String, String, f64, f64)")]
+pub struct Town {
+    pub name: String,
+    pub lga: String,
+    pub lat: f64,
+    pub lng: f64,
+}
+
+impl From<(String, String, f64, f64)> for Town {
+    fn from((name, lga, lat, lng): (String, String, f64, f64)) -> Self {
+        Town { name, lga, lat, lng }
+    }
+}
+
+struct Gazetteer {
+    states: Vec<State>,
+    state_keys: HashMap<String, usize>,
+    // Per state, keyed like `state_keys`
+    lga_keys: Vec<HashMap<String, usize>>,
+    town_keys: Vec<HashMap<String, usize>>,
+}
+
+static GAZETTEER: LazyLock<Gazetteer> = LazyLock::new(|| {
+    let source: Source = serde_json::from_str(include_str!("../../data/nigeria_gazetteer.json"))
+        .unwrap_or_else(|e| panic!("data/nigeria_gazetteer.json is invalid: {}", e));
+
+    let mut state_keys = HashMap::new();
+    let mut lga_keys = Vec::new();
+    let mut town_keys = Vec::new();
+
+    for (i, state) in source.states.iter().enumerate() {
+        state_keys.insert(key(&state.name), i);
+        state_keys.insert(key(&state.code), i);
+        for alias in &state.aliases {
+            state_keys.insert(key(alias), i);
+        }
+
+        let mut lgas = HashMap::new();
+        for (j, lga) in state.lgas.iter().enumerate() {
+            lgas.insert(key(&lga.name), j);
+            for alias in &lga.aliases {
+                lgas.insert(key(alias), j);
+            }
+        }
+        lga_keys.push(lgas);
+
+        town_keys.push(
+            state.towns.iter().enumerate().map(|(j, town)| (key(&town.name), j)).collect()
+        );
+    }
+
+    Gazetteer { states: source.states, state_keys, lga_keys, town_keys }
+});
+
+/// Lookup key for a place name: lowercase alphanumerics only, without a
+/// trailing "State", "LGA" or "Local Government Area"
+fn key(name: &str) -> String {
+    let lowered = name.to_lowercase();
+    let mut words: Vec<&str> = lowered
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    while words.len() > 1 {
+        match words[words.len() - 1] {
+            "state" | "lga" | "area" | "council" | "government" | "local" => { words.pop(); }
+            _ => break,
+        }
+    }
+
+    words.concat()
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum GazetteerError {
+    #[error("'{0}' is not a Nigerian state")]
+    UnknownState(String),
+
+    #[error("'{lga}' is not a local government area in {state}")]
+    UnknownLga { lga: String, state: String },
+}
+
+/// How closely the coordinates of a resolved location match the place
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Precision {
+    Town,
+    Lga,
+    State,
+}
+
+/// A location with canonical names and approximate coordinates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Location {
+    pub state: String,
+    pub state_code: String,
+    pub lga: Option<String>,
+    pub city: Option<String>,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub precision: Precision,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PlaceKind {
+    State,
+    Lga,
+    Town,
+}
+
+/// An autocomplete match
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Suggestion {
+    pub kind: PlaceKind,
+    pub name: String,
+    pub state: String,
+    pub lga: Option<String>,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+pub fn states() -> &'static [State] {
+    &GAZETTEER.states
+}
+
+pub fn find_state(name: &str) -> Option<&'static State> {
+    GAZETTEER.state_keys
+        .get(&key(name))
+        .map(|&i| &GAZETTEER.states[i])
+}
+
+impl State {
+    fn index(&self) -> usize {
+        GAZETTEER.state_keys[&key(&self.name)]
+    }
+
+    pub fn find_lga(&self, name: &str) -> Option<&Lga> {
+        GAZETTEER.lga_keys[self.index()]
+            .get(&key(name))
+            .map(|&j| &self.lgas[j])
+    }
+
+    pub fn find_town(&self, name: &str) -> Option<&Town> {
+        GAZETTEER.town_keys[self.index()]
+            .get(&key(name))
+            .map(|&j| &self.towns[j])
+    }
+
+    /// Whether a pin plausibly lies in this state: within `STATE_RADIUS_KM`
+    /// of one of its LGA headquarters
+    pub fn contains(&self, lat: f64, lng: f64) -> bool {
+        self.lgas
+            .iter()
+            .any(|lga| distance_km((lat, lng), (lga.lat, lga.lng)) <= STATE_RADIUS_KM)
+    }
+}
+
+/// Canonical name of an LGA when the state is not known. Several names
+/// (Surulere, Obi, Irepodun...) exist in more than one state; they share the
+/// canonical spelling, so the first match is good enough for normalising.
+pub fn find_lga_anywhere(name: &str) -> Option<(&'static State, &'static Lga)> {
+    GAZETTEER.states
+        .iter()
+        .find_map(|state| state.find_lga(name).map(|lga| (state, lga)))
+}
+
+pub fn canonical_state(name: &str) -> Option<String> {
+    find_state(name).map(|state| state.name.clone())
+}
+
+pub fn canonical_lga(name: &str) -> Option<String> {
+    find_lga_anywhere(name).map(|(_, lga)| lga.name.clone())
+}
+
+// The `normalise_*` helpers never fail: a name the gazetteer does not know
+// is kept as typed (trimmed), so search filters and foreign documents still work.
+
+pub fn normalise_state(name: &str) -> String {
+    canonical_state(name).unwrap_or_else(|| name.trim().to_string())
+}
+
+pub fn normalise_lga(name: &str) -> String {
+    canonical_lga(name).unwrap_or_else(|| name.trim().to_string())
+}
+
+/// A city is canonical if it is a known town or LGA of the state
+pub fn normalise_city(state: &str, city: &str) -> String {
+    find_state(state)
+        .and_then(|s| {
+            s.find_town(city)
+                .map(|town| town.name.clone())
+                .or_else(|| s.find_lga(city).map(|lga| lga.name.clone()))
+        })
+        .unwrap_or_else(|| city.trim().to_string())
+}
+
+/// Normalised `(state, city)` for records that only keep those two
+pub fn normalise_place(state: &str, city: &str) -> (String, String) {
+    (normalise_state(state), normalise_city(state, city))
+}
+
+/// Canonicalises a state, LGA and city and attaches the best coordinates the
+/// gazetteer has for them. The LGA must belong to the state. A city the
+/// gazetteer does not know is kept as typed; one it does know fills in a
+/// missing LGA.
+pub fn resolve(
+    state: &str,
+    lga: Option<&str>,
+    city: Option<&str>,
+) -> Result<Location, GazetteerError> {
+    let found_state = find_state(state)
+        .ok_or_else(|| GazetteerError::UnknownState(state.trim().to_string()))?;
+
+    let city = city.map(str::trim).filter(|c| !c.is_empty());
+    let town = city.and_then(|c| found_state.find_town(c));
+    // A city typed as an LGA name ("Ikeja") is as good as a town
+    let city_lga = city.and_then(|c| found_state.find_lga(c));
+
+    let found_lga = match lga.map(str::trim).filter(|l| !l.is_empty()) {
+        Some(name) => {
+            let found = found_state.find_lga(name)
+                // People often give their town as the LGA ("Lekki")
+                .or_else(|| found_state.find_town(name).and_then(|t| found_state.find_lga(&t.lga)))
+                .ok_or_else(|| GazetteerError::UnknownLga {
+                    lga: name.to_string(),
+                    state: found_state.name.clone(),
+                })?;
+            Some(found)
+        }
+        None => town
+            .and_then(|t| found_state.find_lga(&t.lga))
+            .or(city_lga),
+    };
+
+    let mut location = Location {
+        state: found_state.name.clone(),
+        state_code: found_state.code.clone(),
+        lga: found_lga.map(|l| l.name.clone()),
+        city: city.map(|c| c.to_string()),
+        latitude: found_state.lat,
+        longitude: found_state.lng,
+        precision: Precision::State,
+    };
+
+    if let Some(lga) = found_lga {
+        location.latitude = lga.lat;
+        location.longitude = lga.lng;
+        location.precision = Precision::Lga;
+    }
+
+    // Only trust the town's pin if it agrees with the LGA
+    match (town, found_lga) {
+        (Some(town), Some(lga)) if town.lga == lga.name => {
+            location.city = Some(town.name.clone());
+            location.latitude = town.lat;
+            location.longitude = town.lng;
+            location.precision = Precision::Town;
+        }
+        (Some(town), _) => location.city = Some(town.name.clone()),
+        (None, _) => {
+            if let Some(lga) = city_lga {
+                location.city = Some(lga.name.clone());
+            }
+        }
+    }
+
+    Ok(location)
+}
+
+/// Autocomplete over states, LGAs and towns, optionally within one state.
+/// Exact matches rank first, then prefixes, then word prefixes.
+pub fn search(query: &str, state: Option<&str>, limit: usize) -> Vec<Suggestion> {
+    let needle = key(query);
+    if needle.is_empty() {
+        return Vec::new();
+    }
+
+    let words: Vec<String> = query
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    let rank = |name: &str| -> Option<u8> {
+        let candidate = key(name);
+        if candidate == needle {
+            Some(0)
+        } else if candidate.starts_with(&needle) {
+            Some(1)
+        } else {
+            let lowered = name.to_lowercase();
+            let mut parts = lowered.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty());
+            parts.any(|part| words.first().is_some_and(|w| part.starts_with(w.as_str())))
+                .then_some(2)
+        }
+    };
+
+    let scope: Vec<&State> = match state.and_then(find_state) {
+        Some(found) => vec![found],
+        None => GAZETTEER.states.iter().collect(),
+    };
+
+    let mut matches: Vec<(u8, PlaceKind, Suggestion)> = Vec::new();
+    for s in scope {
+        let state_rank = std::iter::once(s.name.as_str())
+            .chain(s.aliases.iter().map(String::as_str))
+            .filter_map(&rank)
+            .min();
+        if let Some(r) = state_rank {
+            matches.push((r, PlaceKind::State, Suggestion {
+                kind: PlaceKind::State,
+                name: s.name.clone(),
+                state: s.name.clone(),
+                lga: None,
+                latitude: s.lat,
+                longitude: s.lng,
+            }));
+        }
+
+        for lga in &s.lgas {
+            let lga_rank = std::iter::once(lga.name.as_str())
+                .chain(lga.aliases.iter().map(String::as_str))
+                .filter_map(&rank)
+                .min();
+            if let Some(r) = lga_rank {
+                matches.push((r, PlaceKind::Lga, Suggestion {
+                    kind: PlaceKind::Lga,
+                    name: lga.name.clone(),
+                    state: s.name.clone(),
+                    lga: Some(lga.name.clone()),
+                    latitude: lga.lat,
+                    longitude: lga.lng,
+                }));
+            }
+        }
+
+        for town in &s.towns {
+            if let Some(r) = rank(&town.name) {
+                matches.push((r, PlaceKind::Town, Suggestion {
+                    kind: PlaceKind::Town,
+                    name: town.name.clone(),
+                    state: s.name.clone(),
+                    lga: Some(town.lga.clone()),
+                    latitude: town.lat,
+                    longitude: town.lng,
+                }));
+            }
+        }
+    }
+
+    let kind_order = |kind: PlaceKind| match kind {
+        PlaceKind::State => 0,
+        PlaceKind::Town => 1,
+        PlaceKind::Lga => 2,
+    };
+    matches.sort_by(|a, b| {
+        a.0.cmp(&b.0)
+            .then(kind_order(a.1).cmp(&kind_order(b.1)))
+            .then(a.2.name.cmp(&b.2.name))
+    });
+    matches.into_iter().take(limit).map(|(_, _, s)| s).collect()
+}
+
+/// Great-circle distance between two (lat, lng) points
+pub fn distance_km(from: (f64, f64), to: (f64, f64)) -> f64 {
+    const EARTH_RADIUS_KM: f64 = 6371.0;
+
+    let (lat1, lng1) = (from.0.to_radians(), from.1.to_radians());
+    let (lat2, lng2) = (to.0.to_radians(), to.1.to_radians());
+    let a = ((lat2 - lat1) / 2.0).sin().powi(2)
+        + lat1.cos() * lat2.cos() * ((lng2 - lng1) / 2.0).sin().powi(2);
+
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
+
+pub fn validate_state(state: &str) -> Result<(), ValidationError> {
+    if find_state(state).is_some() {
+        return Ok(());
+    }
+    let mut error = ValidationError::new("unknown_state");
+    error.message = Some(format!("'{}' is not a Nigerian state", state.trim()).into());
+    Err(error)
+}
+
+pub fn validate_lga(lga: &str) -> Result<(), ValidationError> {
+    if find_lga_anywhere(lga).is_some() {
+        return Ok(());
+    }
+    let mut error = ValidationError::new("unknown_lga");
+    error.message = Some(format!("'{}' is not a Nigerian local government area", lga.trim()).into());
+    Err(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_covers_every_state_and_lga() {
+        assert_eq!(states().len(), 37);
+        assert_eq!(states().iter().map(|s| s.lgas.len()).sum::<usize>(), 774);
+
+        for state in states() {
+            for town in &state.towns {
+                assert!(state.find_lga(&town.lga).is_some(), "{} in {}", town.name, state.name);
+            }
+            assert!(state.contains(state.lat, state.lng), "{}", state.name);
+        }
+    }
+
+    #[test]
+    fn test_lookups_ignore_spelling_noise() {
+        assert_eq!(canonical_state("lagos state").as_deref(), Some("Lagos"));
+        assert_eq!(canonical_state("FCT").as_deref(), Some("Federal Capital Territory"));
+        assert_eq!(canonical_state("akwa-ibom").as_deref(), Some("Akwa Ibom"));
+        assert_eq!(canonical_state("Atlantis"), None);
+
+        assert_eq!(canonical_lga("eti osa lga").as_deref(), Some("Eti-Osa"));
+        assert_eq!(canonical_lga("Obio Akpor").as_deref(), Some("Obio/Akpor"));
+        assert_eq!(canonical_lga("Egbado North").as_deref(), Some("Yewa North"));
+
+        assert_eq!(
+            normalise_place("rivers state", "port harcourt"),
+            ("Rivers".to_string(), "Port Harcourt".to_string())
+        );
+        assert_eq!(normalise_place(" Narnia ", " Cair Paravel "), ("Narnia".to_string(), "Cair Paravel".to_string()));
+    }
+
+    #[test]
+    fn test_resolve_picks_the_most_precise_pin() {
+        let town = resolve("Lagos", None, Some("lekki")).unwrap();
+        assert_eq!(town.lga.as_deref(), Some("Eti-Osa"));
+        assert_eq!(town.city.as_deref(), Some("Lekki"));
+        assert_eq!(town.precision, Precision::Town);
+
+        let lga = resolve("Lagos State", Some("Ikeja"), Some("Somewhere New")).unwrap();
+        assert_eq!(lga.city.as_deref(), Some("Somewhere New"));
+        assert_eq!(lga.precision, Precision::Lga);
+
+        let state = resolve("Abuja", None, None).unwrap();
+        assert_eq!(state.state, "Federal Capital Territory");
+        assert_eq!(state.precision, Precision::State);
+
+        assert_eq!(
+            resolve("Kano", Some("Ikeja"), None).unwrap_err(),
+            GazetteerError::UnknownLga { lga: "Ikeja".into(), state: "Kano".into() }
+        );
+        assert!(matches!(resolve("Wakanda", None, None), Err(GazetteerError::UnknownState(_))));
+    }
+
+    #[test]
+    fn test_search_ranks_exact_matches_first() {
+        let results = search("ikeja", None, 5);
+        assert_eq!(results[0].name, "Ikeja");
+        assert_eq!(results[0].kind, PlaceKind::Lga);
+
+        let scoped = search("ib", Some("Oyo"), 20);
+        assert!(!scoped.is_empty());
+        assert!(scoped.iter().all(|s| s.state == "Oyo"));
+
+        assert!(search("  ", None, 5).is_empty());
+    }
+
+    #[test]
+    fn test_state_contains_its_own_pins_only() {
+        let lagos = find_state("Lagos").unwrap();
+        assert!(lagos.contains(6.45, 3.47));
+        assert!(!lagos.contains(12.0, 8.52));
+
+        let d = distance_km((6.45, 3.40), (9.06, 7.49));
+        assert!((d - 535.0).abs() < 15.0, "{}", d);
+    }
+}
@@ -0,0 +1,177 @@
+// utils/i18n.rs
+//! Translation catalogue for emails and notifications.
+//!
+//! Catalogues live in `locales/<code>.json` as flat `key -> text` maps and are
+//! compiled into the binary. `{name}` placeholders are filled by the caller.
+//! A key missing from a locale falls back along `Locale::fallback`, ending at
+//! English; a key missing everywhere renders as the key itself.
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use crate::models::usermodel::Locale;
+
+type Catalogue = HashMap<String, String>;
+
+static CATALOGUES: LazyLock<HashMap<Locale, Catalogue>> = LazyLock::new(|| {
+    Locale::all()
+        .into_iter()
+        .map(|locale| {
+            let source = catalogue_source(locale);
+            let catalogue: Catalogue = serde_json::from_str(source)
+                .unwrap_or_else(|e| panic!("locales/{}.json is invalid: {}", locale.to_str(), e));
+            (locale, catalogue)
+        })
+        .collect()
+});
+
+fn catalogue_source(locale: Locale) -> &'static str {
+    match locale {
+        Locale::En => include_str!("../../locales/en.json"),
+        Locale::Pcm => include_str!("../../locales/pcm.json"),
+        Locale::Yo => include_str!("../../locales/yo.json"),
+        Locale::Ha => include_str!("../../locales/ha.json"),
+        Locale::Ig => include_str!("../../locales/ig.json"),
+    }
+}
+
+/// Look a key up in `locale`, then along its fallback chain
+pub fn translate(locale: Locale, key: &str) -> Option<&'static str> {
+    let mut current = Some(locale);
+    while let Some(locale) = current {
+        if let Some(text) = CATALOGUES.get(&locale).and_then(|catalogue| catalogue.get(key)) {
+            return Some(text.as_str());
+        }
+        current = locale.fallback();
+    }
+    None
+}
+
+/// Like `translate`, but never fails: an unknown key renders as itself
+pub fn t(locale: Locale, key: &str) -> String {
+    match translate(locale, key) {
+        Some(text) => text.to_string(),
+        None => {
+            tracing::warn!("Missing translation for {}", key);
+            key.to_string()
+        }
+    }
+}
+
+/// Translate and fill `{name}` placeholders
+pub fn format(locale: Locale, key: &str, args: &[(&str, &str)]) -> String {
+    let mut text = t(locale, key);
+    for (name, value) in args {
+        text = text.replace(&format!("{{{}}}", name), value);
+    }
+    text
+}
+
+/// Catalogue slug for an email template file, e.g. `Job-Application.html` -> `job_application`
+pub fn template_slug(template_file: &str) -> String {
+    let file_name = template_file.rsplit(['/', '\\']).next().unwrap_or(template_file);
+    file_name
+        .trim_end_matches(".html")
+        .to_lowercase()
+        .replace('-', "_")
+}
+
+/// Placeholders for the `{{t:...}}` tokens in an email template. These must
+/// come before the template's own placeholders, since translated copy may
+/// itself contain `{{username}}` and friends.
+pub fn email_strings(locale: Locale, template_file: &str) -> Vec<(String, String)> {
+    let slug = template_slug(template_file);
+    let template_prefix = format!("email.{}.", slug);
+
+    let Some(english) = CATALOGUES.get(&Locale::En) else {
+        return Vec::new();
+    };
+
+    english
+        .keys()
+        .filter_map(|key| {
+            let token = if let Some(name) = key.strip_prefix("email.common.") {
+                format!("{{{{t:common.{}}}}}", name)
+            } else if let Some(name) = key.strip_prefix(&template_prefix) {
+                format!("{{{{t:{}.{}}}}}", slug, name)
+            } else {
+                return None;
+            };
+            Some((token, t(locale, key)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+    use std::collections::BTreeSet;
+
+    fn placeholders(text: &str) -> BTreeSet<String> {
+        let pattern = Regex::new(r"\{([a-z_]+)\}").unwrap();
+        pattern.captures_iter(text).map(|c| c[1].to_string()).collect()
+    }
+
+    #[test]
+    fn test_every_locale_has_every_key() {
+        let english = &CATALOGUES[&Locale::En];
+        for locale in Locale::all() {
+            let catalogue = &CATALOGUES[&locale];
+            for key in english.keys() {
+                assert!(catalogue.contains_key(key), "{} is missing {}", locale.to_str(), key);
+            }
+            for key in catalogue.keys() {
+                assert!(english.contains_key(key), "{} has unknown key {}", locale.to_str(), key);
+            }
+        }
+    }
+
+    #[test]
+    fn test_translations_keep_placeholders() {
+        let english = &CATALOGUES[&Locale::En];
+        for locale in Locale::all() {
+            for (key, text) in &CATALOGUES[&locale] {
+                assert_eq!(
+                    placeholders(text),
+                    placeholders(&english[key]),
+                    "{} changes the placeholders of {}",
+                    locale.to_str(),
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_email_template_tokens_exist() {
+        let token = Regex::new(r"\{\{t:([a-z_]+)\.([a-z_]+)\}\}").unwrap();
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/src/mail/templates");
+
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            let html = std::fs::read_to_string(&path).unwrap();
+            let file_name = path.file_name().unwrap().to_string_lossy().to_string();
+            let resolved: HashMap<String, String> = email_strings(Locale::Yo, &file_name).into_iter().collect();
+
+            for capture in token.captures_iter(&html) {
+                let key = format!("email.{}.{}", &capture[1], &capture[2]);
+                assert!(translate(Locale::En, &key).is_some(), "{} uses unknown key {}", file_name, key);
+                assert!(resolved.contains_key(&capture[0]), "{} token {} is not filled", file_name, &capture[0]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fallback_and_formatting() {
+        assert_eq!(translate(Locale::Pcm, "no.such.key"), None);
+        assert_eq!(t(Locale::Ha, "no.such.key"), "no.such.key");
+        assert_eq!(format(Locale::En, "email.common.days", &[("count", "3")]), "3 days");
+        assert_ne!(t(Locale::Yo, "email.common.hello"), t(Locale::En, "email.common.hello"));
+    }
+
+    #[test]
+    fn test_template_slug() {
+        assert_eq!(template_slug("Job-Application.html"), "job_application");
+        assert_eq!(template_slug("src/mail/templates/OTP-email.html"), "otp_email");
+    }
+}
@@ -2,5 +2,11 @@ pub mod decimal;
 pub mod password;
 pub mod token;
 pub mod otp_generator;
-// pub mod image_utils;
-pub mod currency;
\ No newline at end of file
+pub mod currency;
+pub mod i18n;
+pub mod gazetteer;
+pub mod certificate;
+pub mod totp;
+pub mod name_match;
+pub mod zip;
+pub mod phone;
\ No newline at end of file
@@ -0,0 +1,119 @@
+// utils/name_match.rs
+//! Forgiving comparison of the names and dates of birth people give us with
+//! the ones on national registries.
+//!
+//! Registries list surname first or last, abbreviate or drop middle names and
+//! have their own typos, so names are compared token by token in any order
+//! with an edit-distance allowance. A single-token name can never score above
+//! one half, so a shared first name alone is not a match.
+use chrono::{Datelike, NaiveDate};
+
+/// Words that say nothing about which business is meant
+const BUSINESS_NOISE: [&str; 10] = [
+    "ltd", "limited", "plc", "nig", "nigeria", "co", "company", "inc", "enterprises", "enterprise",
+];
+
+/// Lowercase letters and digits only, split into words
+pub fn tokens(name: &str) -> Vec<String> {
+    name.split(|c: char| c.is_whitespace() || c == '-' || c == ',' || c == '.')
+        .map(|token| token.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect::<String>())
+        .filter(|token| !token.is_empty())
+        .collect()
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// 1 for identical words, falling with each edit
+fn token_similarity(a: &str, b: &str) -> f64 {
+    let longest = a.chars().count().max(b.chars().count());
+    if longest == 0 {
+        return 0.0;
+    }
+    1.0 - levenshtein(a, b) as f64 / longest as f64
+}
+
+fn token_set_score(claimed: &[String], official: &[String], min_tokens: usize) -> f64 {
+    if claimed.is_empty() || official.is_empty() {
+        return 0.0;
+    }
+
+    let total: f64 = claimed
+        .iter()
+        .map(|token| official.iter().map(|o| token_similarity(token, o)).fold(0.0, f64::max))
+        .sum();
+
+    (total / claimed.len().max(min_tokens) as f64).min(1.0)
+}
+
+/// How well a name someone gave us matches the registry's, 0..1
+pub fn name_score(claimed: &str, official: &str) -> f64 {
+    token_set_score(&tokens(claimed), &tokens(official), 2)
+}
+
+/// As `name_score`, ignoring words like "Ltd" and "Nigeria". One distinctive
+/// word is enough for a business.
+pub fn business_name_score(claimed: &str, official: &str) -> f64 {
+    let significant = |name: &str| -> Vec<String> {
+        tokens(name).into_iter().filter(|token| !BUSINESS_NOISE.contains(&token.as_str())).collect()
+    };
+
+    token_set_score(&significant(claimed), &significant(official), 1)
+}
+
+/// Same date, or the same date with day and month swapped, which is how
+/// many registry records were keyed in
+pub fn dob_matches(claimed: NaiveDate, official: NaiveDate) -> bool {
+    claimed == official
+        || (claimed.year() == official.year()
+            && claimed.day() == official.month()
+            && claimed.month() == official.day())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_match_in_any_order_with_typos() {
+        assert_eq!(name_score("Chigozie George", "GEORGE CHIGOZIE"), 1.0);
+        assert!(name_score("Adebayo Oluwaseun Ogunleye", "OGUNLEYE ADEBAYO") > 0.6);
+        assert!(name_score("Chukwuemeka Okafor", "Chukwuemka Okafor") > 0.9);
+        assert!(name_score("Ngozi Eze", "Aisha Bello") < 0.4);
+    }
+
+    #[test]
+    fn test_single_name_is_never_a_match() {
+        assert_eq!(name_score("Tunde", "Tunde Bakare"), 0.5);
+        assert_eq!(name_score("", "Tunde Bakare"), 0.0);
+    }
+
+    #[test]
+    fn test_business_names_ignore_suffixes() {
+        assert_eq!(business_name_score("Verinest Ltd", "VERINEST NIGERIA LIMITED"), 1.0);
+        assert!(business_name_score("Verinest Ltd", "Homebase Limited") < 0.5);
+        assert_eq!(business_name_score("Verinest Homes", "VERINEST HOMES LIMITED"), 1.0);
+    }
+
+    #[test]
+    fn test_dob_allows_swapped_day_and_month() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+        assert!(dob_matches(date(1990, 4, 7), date(1990, 4, 7)));
+        assert!(dob_matches(date(1990, 4, 7), date(1990, 7, 4)));
+        assert!(!dob_matches(date(1990, 4, 7), date(1991, 4, 7)));
+    }
+}
@@ -0,0 +1,77 @@
+// utils/phone.rs
+//! Phone numbers are stored and compared in E.164 form (+2348031234567).
+//! Nigerian numbers are accepted the way people write them; numbers from
+//! elsewhere need their country code.
+
+/// Nigerian mobile numbers start 07, 08 or 09 locally
+const NIGERIAN_MOBILE_PREFIXES: [char; 3] = ['7', '8', '9'];
+
+/// The number in E.164 form, or None if it cannot be a mobile number
+pub fn normalize(input: &str) -> Option<String> {
+    let trimmed = input.trim();
+    let has_plus = trimmed.starts_with('+');
+    let digits: String = trimmed
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '(' | ')' | '.' | '+'))
+        .collect();
+
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let national = if let Some(rest) = digits.strip_prefix("234") {
+        rest
+    } else if has_plus {
+        // Another country: trust the country code, check only the length
+        return (8..=15).contains(&digits.len()).then(|| format!("+{}", digits));
+    } else {
+        digits.strip_prefix('0')?
+    };
+
+    let is_mobile = national.len() == 10 && national.starts_with(NIGERIAN_MOBILE_PREFIXES);
+    is_mobile.then(|| format!("+234{}", national))
+}
+
+/// Enough of the number for the user to recognise it, e.g. +234******4567
+pub fn mask(phone: &str) -> String {
+    let chars: Vec<char> = phone.chars().collect();
+    if chars.len() <= 8 {
+        return "*".repeat(chars.len());
+    }
+
+    let (head, rest) = chars.split_at(4);
+    let (middle, tail) = rest.split_at(rest.len() - 4);
+    format!("{}{}{}", head.iter().collect::<String>(), "*".repeat(middle.len()), tail.iter().collect::<String>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nigerian_numbers_in_every_common_form() {
+        let expected = Some("+2348031234567".to_string());
+
+        assert_eq!(normalize("08031234567"), expected);
+        assert_eq!(normalize("0803 123 4567"), expected);
+        assert_eq!(normalize("2348031234567"), expected);
+        assert_eq!(normalize("+234 (803) 123-4567"), expected);
+        assert_eq!(normalize("+2340803123456"), None);
+        assert_eq!(normalize("0603123456"), None);
+        assert_eq!(normalize("8031234567"), None);
+        assert_eq!(normalize("0803123456a"), None);
+    }
+
+    #[test]
+    fn test_foreign_numbers_need_a_country_code() {
+        assert_eq!(normalize("+44 7700 900123"), Some("+447700900123".to_string()));
+        assert_eq!(normalize("447700900123"), None);
+        assert_eq!(normalize("+1234"), None);
+    }
+
+    #[test]
+    fn test_mask_keeps_country_code_and_last_digits() {
+        assert_eq!(mask("+2348031234567"), "+234******4567");
+        assert_eq!(mask("+1234"), "*****");
+    }
+}
@@ -17,14 +17,18 @@ use crate::error::{ErrorMessage, HttpError};
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TokenClaims{
     pub sub: String,
+    pub sid: String, // The session the token was issued to
+    pub jti: String,
     pub iat: usize,
     pub exp: usize,
 }
 
+/// Access token for a session
 pub fn create_token(
     user_id: &str,
+    session_id: &str,
     secret: &[u8],
-    expires_in_seconds: i64,
+    expires_in_minutes: i64,
 ) -> Result<String, jsonwebtoken::errors::Error> {
     if user_id.is_empty() {
         return Err(jsonwebtoken::errors::ErrorKind::InvalidSubject.into());
@@ -32,9 +36,11 @@ pub fn create_token(
 
     let now = Utc::now();
     let iat = now.timestamp() as usize;
-    let exp = (now + Duration::minutes(expires_in_seconds)).timestamp() as usize;
+    let exp = (now + Duration::minutes(expires_in_minutes)).timestamp() as usize;
     let claims = TokenClaims {
         sub: user_id.to_string(),
+        sid: session_id.to_string(),
+        jti: uuid::Uuid::new_v4().to_string(),
         iat,
         exp,
     };
@@ -46,10 +52,11 @@ pub fn create_token(
     )
 }
 
-pub fn decode_token<T: Into<String>>(
+/// ✅ FIX #6: Get full token claims (including expiration)
+pub fn decode_token_claims<T: Into<String>>(
     token: T,
     secret: &[u8]
-) -> Result<String, HttpError> {
+) -> Result<TokenClaims, HttpError> {
     let decode = decode::<TokenClaims>(
         &token.into(), 
         &DecodingKey::from_secret(secret), 
@@ -57,24 +64,53 @@ pub fn decode_token<T: Into<String>>(
     );
 
     match decode {
-        Ok(token) => Ok(token.claims.sub),
+        Ok(token_data) => Ok(token_data.claims),
         Err(_) => Err(HttpError::new(ErrorMessage::InvalidToken.to_string(), StatusCode::UNAUTHORIZED))
     }
 }
 
-/// ✅ FIX #6: Get full token claims (including expiration)
-pub fn decode_token_claims<T: Into<String>>(
+/// A short-lived token proving the first step of a multi-step sign-in (e.g.
+/// the password was right but a second factor is still owed). It lacks the
+/// session claims, so it is never accepted as an access token.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChallengeClaims {
+    pub sub: String,
+    pub purpose: String,
+    pub iat: usize,
+    pub exp: usize,
+}
+
+pub fn create_challenge_token(
+    user_id: &str,
+    purpose: &str,
+    secret: &[u8],
+    expires_in_minutes: i64,
+) -> Result<String, jsonwebtoken::errors::Error> {
+    let now = Utc::now();
+    let claims = ChallengeClaims {
+        sub: user_id.to_string(),
+        purpose: purpose.to_string(),
+        iat: now.timestamp() as usize,
+        exp: (now + Duration::minutes(expires_in_minutes)).timestamp() as usize,
+    };
+
+    encode(&Header::default(), &claims, &EncodingKey::from_secret(secret))
+}
+
+/// The user a challenge token was issued to, if it is for `purpose`
+pub fn decode_challenge_token<T: Into<String>>(
     token: T,
-    secret: &[u8]
-) -> Result<TokenClaims, HttpError> {
-    let decode = decode::<TokenClaims>(
-        &token.into(), 
-        &DecodingKey::from_secret(secret), 
+    purpose: &str,
+    secret: &[u8],
+) -> Result<String, HttpError> {
+    let decoded = decode::<ChallengeClaims>(
+        &token.into(),
+        &DecodingKey::from_secret(secret),
         &Validation::new(Algorithm::HS256),
     );
 
-    match decode {
-        Ok(token_data) => Ok(token_data.claims),
-        Err(_) => Err(HttpError::new(ErrorMessage::InvalidToken.to_string(), StatusCode::UNAUTHORIZED))
+    match decoded {
+        Ok(token_data) if token_data.claims.purpose == purpose => Ok(token_data.claims.sub),
+        _ => Err(HttpError::new(ErrorMessage::InvalidToken.to_string(), StatusCode::UNAUTHORIZED)),
     }
 }
@@ -0,0 +1,186 @@
+// utils/zip.rs
+//! Just enough of the ZIP format to hand people an archive of their data:
+//! deflated entries with UTF-8 names, no encryption and no ZIP64, so every
+//! entry and the archive itself must stay under 4 GiB.
+use std::io::{self, Write};
+
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use flate2::{write::DeflateEncoder, Compression};
+
+const LOCAL_HEADER: u32 = 0x0403_4b50;
+const CENTRAL_HEADER: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x0605_4b50;
+
+/// 2.0, the first version with deflate
+const VERSION: u16 = 20;
+/// Bit 11: names are UTF-8
+const FLAGS: u16 = 0x0800;
+const DEFLATE: u16 = 8;
+
+struct Entry {
+    name: String,
+    crc: u32,
+    compressed_size: u32,
+    size: u32,
+    offset: u32,
+}
+
+pub struct ZipWriter {
+    buffer: Vec<u8>,
+    entries: Vec<Entry>,
+    dos_time: u16,
+    dos_date: u16,
+}
+
+impl ZipWriter {
+    /// Every entry is stamped with `modified`
+    pub fn new(modified: DateTime<Utc>) -> Self {
+        // DOS dates start in 1980
+        let year = modified.year().clamp(1980, 2107) as u16;
+
+        Self {
+            buffer: Vec::new(),
+            entries: Vec::new(),
+            dos_time: ((modified.hour() as u16) << 11) | ((modified.minute() as u16) << 5) | (modified.second() as u16 / 2),
+            dos_date: ((year - 1980) << 9) | ((modified.month() as u16) << 5) | modified.day() as u16,
+        }
+    }
+
+    pub fn add(&mut self, name: &str, contents: &[u8]) -> io::Result<()> {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(contents)?;
+        let compressed = encoder.finish()?;
+
+        let entry = Entry {
+            name: name.to_string(),
+            crc: crc32fast::hash(contents),
+            compressed_size: to_u32(compressed.len())?,
+            size: to_u32(contents.len())?,
+            offset: to_u32(self.buffer.len())?,
+        };
+
+        self.put_u32(LOCAL_HEADER);
+        self.put_u16(VERSION);
+        self.put_entry_fields(&entry);
+        self.put_u16(0); // extra field length
+        self.buffer.extend_from_slice(entry.name.as_bytes());
+        self.buffer.extend_from_slice(&compressed);
+
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// The archive, with its central directory
+    pub fn finish(mut self) -> io::Result<Vec<u8>> {
+        let directory_offset = to_u32(self.buffer.len())?;
+        let entries = std::mem::take(&mut self.entries);
+
+        for entry in &entries {
+            self.put_u32(CENTRAL_HEADER);
+            self.put_u16(VERSION); // made by
+            self.put_u16(VERSION); // needed to extract
+            self.put_entry_fields(entry);
+            self.put_u16(0); // extra field length
+            self.put_u16(0); // comment length
+            self.put_u16(0); // disk number
+            self.put_u16(0); // internal attributes
+            self.put_u32(0); // external attributes
+            self.put_u32(entry.offset);
+            self.buffer.extend_from_slice(entry.name.as_bytes());
+        }
+
+        let directory_size = to_u32(self.buffer.len())? - directory_offset;
+        let count = u16::try_from(entries.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Too many entries for a ZIP archive"))?;
+
+        self.put_u32(END_OF_CENTRAL_DIRECTORY);
+        self.put_u16(0); // this disk
+        self.put_u16(0); // disk with the central directory
+        self.put_u16(count);
+        self.put_u16(count);
+        self.put_u32(directory_size);
+        self.put_u32(directory_offset);
+        self.put_u16(0); // comment length
+
+        Ok(self.buffer)
+    }
+
+    /// Flags through name length, shared by local and central headers
+    fn put_entry_fields(&mut self, entry: &Entry) {
+        self.put_u16(FLAGS);
+        self.put_u16(DEFLATE);
+        self.put_u16(self.dos_time);
+        self.put_u16(self.dos_date);
+        self.put_u32(entry.crc);
+        self.put_u32(entry.compressed_size);
+        self.put_u32(entry.size);
+        self.put_u16(entry.name.len() as u16);
+    }
+
+    fn put_u16(&mut self, value: u16) {
+        self.buffer.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn put_u32(&mut self, value: u32) {
+        self.buffer.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+fn to_u32(value: usize) -> io::Result<u32> {
+    u32::try_from(value).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "ZIP entry exceeds 4 GiB"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use chrono::TimeZone;
+    use flate2::read::DeflateDecoder;
+
+    use super::*;
+
+    fn u16_at(bytes: &[u8], at: usize) -> u16 {
+        u16::from_le_bytes([bytes[at], bytes[at + 1]])
+    }
+
+    fn u32_at(bytes: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+    }
+
+    #[test]
+    fn test_entries_can_be_read_back_through_the_central_directory() {
+        let modified = Utc.with_ymd_and_hms(2024, 5, 17, 13, 45, 30).unwrap();
+        let mut zip = ZipWriter::new(modified);
+        zip.add("profile.json", br#"{"name": "Ada"}"#).unwrap();
+        zip.add("messages.json", "[\"Ẹ káàárọ̀\"]".repeat(50).as_bytes()).unwrap();
+        let archive = zip.finish().unwrap();
+
+        let end = archive.len() - 22;
+        assert_eq!(u32_at(&archive, end), END_OF_CENTRAL_DIRECTORY);
+        assert_eq!(u16_at(&archive, end + 10), 2);
+
+        // The second central directory entry points at the second local header
+        let first_central = u32_at(&archive, end + 16) as usize;
+        let name_len = u16_at(&archive, first_central + 28) as usize;
+        let second_central = first_central + 46 + name_len;
+        assert_eq!(u32_at(&archive, second_central), CENTRAL_HEADER);
+
+        let local = u32_at(&archive, second_central + 42) as usize;
+        assert_eq!(u32_at(&archive, local), LOCAL_HEADER);
+        assert_eq!(u16_at(&archive, local + 12), (44 << 9) | (5 << 5) | 17);
+
+        let compressed_size = u32_at(&archive, local + 18) as usize;
+        let name_len = u16_at(&archive, local + 26) as usize;
+        assert_eq!(&archive[local + 30..local + 30 + name_len], b"messages.json");
+
+        let data_start = local + 30 + name_len;
+        let mut contents = Vec::new();
+        DeflateDecoder::new(&archive[data_start..data_start + compressed_size])
+            .read_to_end(&mut contents)
+            .unwrap();
+
+        assert_eq!(contents.len(), u32_at(&archive, local + 22) as usize);
+        assert_eq!(crc32fast::hash(&contents), u32_at(&archive, local + 14));
+        assert!(compressed_size < contents.len());
+    }
+}
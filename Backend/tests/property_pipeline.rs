@@ -0,0 +1,253 @@
+// tests/property_pipeline.rs
+//! End-to-end run of the property verification pipeline against a running
+//! server: landlord lists, admin assigns an agent, the agent inspects, a
+//! lawyer reviews documents and the listing goes live. Photos and documents
+//! go through the media upload first, as the apps do.
+//!
+//! Needs a server with a migrated database and one signed-in user per role:
+//!
+//! VERINEST_TEST_API_URL          e.g. http://localhost:8000/api
+//! VERINEST_TEST_LANDLORD_TOKEN   JWT of a landlord
+//! VERINEST_TEST_ADMIN_TOKEN      JWT of an admin
+//! VERINEST_TEST_AGENT_TOKEN      JWT of an agent
+//! VERINEST_TEST_AGENT_ID         that agent's user id
+//! VERINEST_TEST_LAWYER_TOKEN     JWT of a lawyer
+//!
+//! Listings are assigned to lawyers automatically, so the lawyer should be the
+//! only one based in Lagos (or have no LGA on their profile, leaving the
+//! listing in the open queue).
+//!
+//! The test is skipped when these are not set.
+use reqwest::{Client, Method, StatusCode};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+struct Env {
+    api_url: String,
+    landlord: String,
+    admin: String,
+    agent: String,
+    agent_id: String,
+    lawyer: String,
+}
+
+impl Env {
+    fn from_env() -> Option<Self> {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+
+        Some(Self {
+            api_url: var("VERINEST_TEST_API_URL")?.trim_end_matches('/').to_string(),
+            landlord: var("VERINEST_TEST_LANDLORD_TOKEN")?,
+            admin: var("VERINEST_TEST_ADMIN_TOKEN")?,
+            agent: var("VERINEST_TEST_AGENT_TOKEN")?,
+            agent_id: var("VERINEST_TEST_AGENT_ID")?,
+            lawyer: var("VERINEST_TEST_LAWYER_TOKEN")?,
+        })
+    }
+}
+
+async fn call(
+    client: &Client,
+    env: &Env,
+    method: Method,
+    path: &str,
+    token: Option<&str>,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let mut request = client.request(method, format!("{}{}", env.api_url, path));
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    if let Some(body) = body {
+        request = request.json(&body);
+    }
+
+    let response = request.send().await.expect("server unreachable");
+    let status = response.status();
+    let body = response.json::<Value>().await.unwrap_or(Value::Null);
+    (status, body)
+}
+
+/// Uploads `contents` through the media endpoint and returns its stable URL
+async fn upload(client: &Client, env: &Env, token: &str, purpose: &str, filename: &str, contents: &[u8]) -> String {
+    let boundary = format!("pipeline-{}", Uuid::new_v4().simple());
+    let mut body = format!(
+        "--{b}\r\nContent-Disposition: form-data; name=\"purpose\"\r\n\r\n{purpose}\r\n\
+         --{b}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{filename}\"\r\n\
+         Content-Type: application/octet-stream\r\n\r\n",
+        b = boundary,
+    )
+    .into_bytes();
+    body.extend_from_slice(contents);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+
+    let response = client
+        .post(format!("{}/media/upload", env.api_url))
+        .bearer_auth(token)
+        .header("content-type", format!("multipart/form-data; boundary={}", boundary))
+        .body(body)
+        .send()
+        .await
+        .expect("server unreachable");
+    let status = response.status();
+    let body = response.json::<Value>().await.unwrap_or(Value::Null);
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    body["data"]["url"].as_str().expect("upload has a url").to_string()
+}
+
+/// Enough of a PNG for the type check; the suffix keeps files apart
+fn png(tag: &str) -> Vec<u8> {
+    let mut contents = b"\x89PNG\r\n\x1a\n".to_vec();
+    contents.extend_from_slice(tag.as_bytes());
+    contents
+}
+
+fn listing(photo_url: &str, document_url: &str) -> Value {
+    // A fresh address per run keeps the duplicate check out of the way
+    let unique = Uuid::new_v4().simple().to_string();
+
+    json!({
+        "title": "Pipeline test three bedroom flat",
+        "description": "Three bedroom flat used by the end-to-end verification pipeline test suite.",
+        "property_type": "Apartment",
+        "listing_type": "Rent",
+        "address": format!("{} Herbert Macaulay Way", &unique[..12]),
+        "city": "Lagos",
+        "state": "Lagos",
+        "lga": "Yaba",
+        "country": "Nigeria",
+        "bedrooms": 3,
+        "bathrooms": 2,
+        "price": 2500000,
+        "currency": "Naira",
+        "certificate_of_occupancy": document_url,
+        "survey_plan": document_url,
+        "property_photos": [photo_url]
+    })
+}
+
+fn listed_ids(body: &Value) -> Vec<String> {
+    body["data"]["properties"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|entry| {
+            entry["property"]["id"].as_str().or(entry["id"].as_str()).map(String::from)
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn property_goes_live_only_after_agent_and_lawyer_approve() {
+    let Some(env) = Env::from_env() else {
+        eprintln!("skipping: VERINEST_TEST_* environment not configured");
+        return;
+    };
+    let client = Client::new();
+
+    // Landlord uploads, then lists; the property starts in the agent queue
+    let photo_url = upload(&client, &env, &env.landlord, "property_photo", "front.png", &png("front")).await;
+    let document_url = upload(&client, &env, &env.landlord, "property_document", "c-of-o.pdf", b"%PDF-1.4\n%pipeline").await;
+    let (status, body) = call(
+        &client, &env, Method::POST, "/properties/create",
+        Some(&env.landlord), Some(listing(&photo_url, &document_url)),
+    ).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"]["property"]["status"], "AwaitingAgent");
+    let property_id = body["data"]["property"]["id"].as_str().unwrap().to_string();
+
+    // Not public while in verification
+    let (status, _) = call(&client, &env, Method::GET, &format!("/properties/{}", property_id), None, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // Landlords cannot verify their own listing
+    let site_photo_url = upload(&client, &env, &env.agent, "property_photo", "site-visit.png", &png("site-visit")).await;
+    let agent_review = json!({
+        "verification_status": "approved",
+        "notes": "Visited the site, matches the photos and address.",
+        "verification_photos": [site_photo_url]
+    });
+    let (status, _) = call(
+        &client, &env, Method::POST, &format!("/properties/agent-verify/{}", property_id),
+        Some(&env.landlord), Some(agent_review.clone()),
+    ).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // The lawyer cannot jump ahead of the agent
+    let lawyer_review = json!({
+        "verification_status": "approved",
+        "notes": "Certificate of occupancy and survey plan are in order."
+    });
+    let (status, _) = call(
+        &client, &env, Method::POST, &format!("/properties/lawyer-verify/{}", property_id),
+        Some(&env.lawyer), Some(lawyer_review.clone()),
+    ).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Admin assigns the agent, who sees it in their queue
+    let (status, body) = call(
+        &client, &env, Method::PUT, &format!("/properties/assign-agent/{}", property_id),
+        Some(&env.admin), Some(json!({ "agent_id": env.agent_id })),
+    ).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    let (status, body) = call(&client, &env, Method::GET, "/properties/for-agent-verification", Some(&env.agent), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(listed_ids(&body).contains(&property_id));
+
+    // Agent approves: through AgentVerified into the lawyer queue
+    let (status, body) = call(
+        &client, &env, Method::POST, &format!("/properties/agent-verify/{}", property_id),
+        Some(&env.agent), Some(agent_review.clone()),
+    ).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"]["property"]["status"], "AwaitingLawyer");
+
+    // A second agent review is refused
+    let (status, _) = call(
+        &client, &env, Method::POST, &format!("/properties/agent-verify/{}", property_id),
+        Some(&env.agent), Some(agent_review),
+    ).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = call(&client, &env, Method::GET, "/properties/for-lawyer-verification", Some(&env.lawyer), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(listed_ids(&body).contains(&property_id));
+
+    // Lawyer approves: through LawyerVerified to Active
+    let (status, body) = call(
+        &client, &env, Method::POST, &format!("/properties/lawyer-verify/{}", property_id),
+        Some(&env.lawyer), Some(lawyer_review),
+    ).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"]["property"]["status"], "Active");
+
+    // Now public, with both reviews on record
+    let (status, body) = call(&client, &env, Method::GET, &format!("/properties/{}", property_id), None, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["property"]["status"], "Active");
+
+    let (status, body) = call(
+        &client, &env, Method::GET, &format!("/properties/{}/verification-history", property_id),
+        Some(&env.landlord), None,
+    ).await;
+    assert_eq!(status, StatusCode::OK);
+    let reviewers: Vec<&str> = body["data"]["verification"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|entry| entry["verifier_type"].as_str())
+        .collect();
+    assert!(reviewers.contains(&"agent") && reviewers.contains(&"lawyer"));
+
+    // Suspending takes it down again
+    let (status, body) = call(
+        &client, &env, Method::PUT, &format!("/properties/admin/{}/suspend", property_id),
+        Some(&env.admin), None,
+    ).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (status, _) = call(&client, &env, Method::GET, &format!("/properties/{}", property_id), None, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
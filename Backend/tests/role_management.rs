@@ -0,0 +1,114 @@
+// tests/role_management.rs
+//! Changing a user's role through the legacy `PUT /users/role` endpoint
+//! against a running server: the old role has to go, so a demoted admin
+//! loses admin permissions, and admins cannot demote themselves.
+//!
+//! Needs a server with a migrated database and two signed-in accounts:
+//!
+//! VERINEST_TEST_API_URL               e.g. http://localhost:8000/api
+//! VERINEST_TEST_SUPER_ADMIN_TOKEN     JWT of a super admin whose session has
+//!                                     stepped up in the last ten minutes
+//! VERINEST_TEST_SUPER_ADMIN_ID        that super admin's user id
+//! VERINEST_TEST_ADMIN_TOKEN           JWT of an admin, demoted and restored
+//! VERINEST_TEST_ADMIN_ID              that admin's user id
+//!
+//! The test is skipped when these are not set.
+use reqwest::{Client, Method, StatusCode};
+use serde_json::{json, Value};
+
+struct Env {
+    api_url: String,
+    super_admin: String,
+    super_admin_id: String,
+    admin: String,
+    admin_id: String,
+}
+
+impl Env {
+    fn from_env() -> Option<Self> {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+
+        Some(Self {
+            api_url: var("VERINEST_TEST_API_URL")?.trim_end_matches('/').to_string(),
+            super_admin: var("VERINEST_TEST_SUPER_ADMIN_TOKEN")?,
+            super_admin_id: var("VERINEST_TEST_SUPER_ADMIN_ID")?,
+            admin: var("VERINEST_TEST_ADMIN_TOKEN")?,
+            admin_id: var("VERINEST_TEST_ADMIN_ID")?,
+        })
+    }
+}
+
+async fn call(
+    client: &Client,
+    env: &Env,
+    method: Method,
+    path: &str,
+    token: &str,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let mut request = client
+        .request(method, format!("{}{}", env.api_url, path))
+        .bearer_auth(token);
+    if let Some(body) = body {
+        request = request.json(&body);
+    }
+
+    let response = request.send().await.expect("server unreachable");
+    let status = response.status();
+    let body = response.json::<Value>().await.unwrap_or(Value::Null);
+    (status, body)
+}
+
+async fn set_role(client: &Client, env: &Env, target: &str, role: &str) -> (StatusCode, Value) {
+    call(
+        client,
+        env,
+        Method::PUT,
+        "/users/role",
+        &env.super_admin,
+        Some(json!({ "target_user_id": target, "role": role })),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn demoted_admin_loses_role_management() {
+    let Some(env) = Env::from_env() else {
+        eprintln!("skipping: VERINEST_TEST_* environment not configured");
+        return;
+    };
+    let client = Client::new();
+
+    // Role management needs Permission::RolesManage
+    let (status, _) = call(&client, &env, Method::GET, "/users/admin/roles", &env.admin, None).await;
+    assert_eq!(status, StatusCode::OK, "the admin should start with role management");
+
+    let (status, body) = set_role(&client, &env, &env.admin_id, "User").await;
+    assert_eq!(status, StatusCode::OK, "demotion failed: {}", body);
+
+    let (status, _) = call(&client, &env, Method::GET, "/users/admin/roles", &env.admin, None).await;
+    let (_, roles) = call(
+        &client,
+        &env,
+        Method::GET,
+        &format!("/users/admin/users/{}/roles", env.admin_id),
+        &env.super_admin,
+        None,
+    )
+    .await;
+
+    // Put the admin back before asserting, so a failure leaves the fixture usable
+    let (restored, body) = set_role(&client, &env, &env.admin_id, "Admin").await;
+    assert_eq!(restored, StatusCode::OK, "restoring the admin failed: {}", body);
+
+    assert_eq!(status, StatusCode::FORBIDDEN, "a demoted admin kept role management");
+    assert!(
+        !roles.to_string().contains("\"Admin\""),
+        "the old admin role is still held: {}",
+        roles
+    );
+
+    // Admins cannot take away their own admin status
+    let (status, _) = set_role(&client, &env, &env.super_admin_id, "User").await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}